@@ -0,0 +1,323 @@
+use sol::instruction::{self as builders, Instruction};
+use sol::lookup_table::{self, AddressLookupTable};
+use sol::state::{Content, DaoState, Pubkey, RoundResult, VoteProposal};
+use solana_client::client_error::ClientError as RpcClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::error::Error as StdError;
+use std::fmt;
+use crate::convert::{from_sdk_pubkey, to_sdk_instruction, to_sdk_lookup_table, to_sdk_pubkey};
+use crate::decode::{decode_dao_account, DecodeError};
+
+#[derive(Debug)]
+pub enum ClientError {
+    RpcError(String),
+    AccountNotFound(Pubkey),
+    // 다른 프로그램이 가진 계정을 DAO 로 읽으려 함
+    WrongOwner { account: Pubkey, owner: Pubkey },
+    DecodeError(String),
+    ContentNotFound { dao: Pubkey, index: u64 },
+    ProposalNotFound { dao: Pubkey, proposal_id: u64 },
+    // v0 메시지를 만들거나 서명하지 못함 (계정이 너무 많거나 서명자가 빠짐)
+    TransactionError(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            ClientError::AccountNotFound(account) => write!(f, "Account not found: {}", account),
+            ClientError::WrongOwner { account, owner } => write!(f, "Account {} is owned by {}, not the Turtle program", account, owner),
+            ClientError::DecodeError(msg) => write!(f, "Decode error: {}", msg),
+            ClientError::ContentNotFound { dao, index } => write!(f, "Content {} not found in DAO {}", index, dao),
+            ClientError::ProposalNotFound { dao, proposal_id } => write!(f, "Proposal {} not found in DAO {}", proposal_id, dao),
+            ClientError::TransactionError(msg) => write!(f, "Transaction error: {}", msg),
+        }
+    }
+}
+
+impl StdError for ClientError {}
+
+impl From<RpcClientError> for ClientError {
+    fn from(e: RpcClientError) -> Self {
+        ClientError::RpcError(e.to_string())
+    }
+}
+
+// 계정 데이터를 DaoState 로 읽는다. 프로그램이 가진 계정이 아니면 거절한다.
+pub fn decode_dao_state(dao: &Pubkey, account: &Account, program_id: &Pubkey) -> Result<DaoState, ClientError> {
+    let owner = from_sdk_pubkey(&account.owner);
+    if owner != *program_id {
+        return Err(ClientError::WrongOwner { account: *dao, owner });
+    }
+    decode_dao_account(&account.data).map_err(|e| match e {
+        DecodeError::NotInitialized => ClientError::AccountNotFound(*dao),
+        e => ClientError::DecodeError(e.to_string()),
+    })
+}
+
+// 한 프로그램 배포에 붙는 비동기 클라이언트. 명령 빌더에 program_id 를 매번 넘기지 않아도 된다.
+pub struct TurtleClient {
+    rpc: RpcClient,
+    program_id: Pubkey,
+}
+
+impl TurtleClient {
+    // confirmed 커밋먼트로 읽고 확인한다
+    pub fn new(rpc_url: impl Into<String>, program_id: Pubkey) -> Self {
+        Self::with_commitment(rpc_url, program_id, CommitmentConfig::confirmed())
+    }
+
+    pub fn with_commitment(rpc_url: impl Into<String>, program_id: Pubkey, commitment: CommitmentConfig) -> Self {
+        Self { rpc: RpcClient::new_with_commitment(rpc_url.into(), commitment), program_id }
+    }
+
+    pub fn from_rpc(rpc: RpcClient, program_id: Pubkey) -> Self {
+        Self { rpc, program_id }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    // process_initialize_dao 가 검증하는 DAO 계정 주소
+    pub fn dao_address(&self, initializer: &Pubkey, dao_name: &str) -> Option<Pubkey> {
+        builders::dao_address(&self.program_id, initializer, dao_name).map(|(address, _)| address)
+    }
+
+    pub async fn fetch_dao_state(&self, dao: &Pubkey) -> Result<DaoState, ClientError> {
+        let account = self.rpc.get_account_with_commitment(&to_sdk_pubkey(dao), self.rpc.commitment())
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound(*dao))?;
+        decode_dao_state(dao, &account, &self.program_id)
+    }
+
+    // index 는 DaoState.contents 안의 위치다. 라운드가 끝나면 프로그램이 목록을 비우므로 지난 라운드 글은 없다.
+    pub async fn fetch_content(&self, dao: &Pubkey, index: u64) -> Result<Content, ClientError> {
+        let state = self.fetch_dao_state(dao).await?;
+        usize::try_from(index).ok()
+            .and_then(|position| state.contents.into_iter().nth(position))
+            .ok_or(ClientError::ContentNotFound { dao: *dao, index })
+    }
+
+    // 지난 라운드 기록. 글은 다음 라운드에 지워져도 승자와 상금은 여기 남는다 (round 는 0..DaoState.round)
+    pub async fn fetch_round_result(&self, dao: &Pubkey, round: u64) -> Result<RoundResult, ClientError> {
+        let (address, _) = builders::round_result_address(&self.program_id, dao, round)
+            .ok_or(ClientError::AccountNotFound(*dao))?;
+        let account = self.rpc.get_account_with_commitment(&to_sdk_pubkey(&address), self.rpc.commitment())
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound(address))?;
+        let owner = from_sdk_pubkey(&account.owner);
+        if owner != self.program_id {
+            return Err(ClientError::WrongOwner { account: address, owner });
+        }
+        RoundResult::from_account_data(&account.data).map_err(|e| ClientError::DecodeError(e.to_string()))
+    }
+
+    pub async fn fetch_proposal(&self, dao: &Pubkey, proposal_id: u64) -> Result<VoteProposal, ClientError> {
+        self.fetch_dao_state(dao).await?
+            .vote_proposals
+            .into_iter()
+            .find(|proposal| proposal.proposal_id == proposal_id)
+            .ok_or(ClientError::ProposalNotFound { dao: *dao, proposal_id })
+    }
+
+    // 최근 blockhash 로 서명해서 보내고 커밋먼트까지 확인되면 서명을 돌려준다.
+    // payer 는 수수료를 내고 첫 번째로 서명한다. 명령이 요구하는 다른 서명자는 signers 로 넘긴다.
+    pub async fn send_and_confirm(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<Signature, ClientError> {
+        let instructions: Vec<_> = instructions.iter().map(to_sdk_instruction).collect();
+        let mut all_signers: Vec<&Keypair> = vec![payer];
+        all_signers.extend(signers.iter().copied().filter(|signer| signer.pubkey() != payer.pubkey()));
+
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &all_signers, blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    // 주소 조회 테이블을 쓰는 v0 트랜잭션. 테이블에 든 계정은 1바이트 인덱스로 들어가서 레거시 크기 한도를 넘는 명령도 보낼 수 있다.
+    pub async fn send_and_confirm_v0(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+        tables: &[AddressLookupTable],
+    ) -> Result<Signature, ClientError> {
+        let instructions: Vec<_> = instructions.iter().map(to_sdk_instruction).collect();
+        let tables: Vec<_> = tables.iter().map(to_sdk_lookup_table).collect();
+        let mut all_signers: Vec<&Keypair> = vec![payer];
+        all_signers.extend(signers.iter().copied().filter(|signer| signer.pubkey() != payer.pubkey()));
+
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let message = v0::Message::try_compile(&payer.pubkey(), &instructions, &tables, blockhash)
+            .map_err(|e| ClientError::TransactionError(e.to_string()))?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &all_signers)
+            .map_err(|e| ClientError::TransactionError(e.to_string()))?;
+        Ok(self.rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    pub async fn fetch_lookup_table(&self, table: &Pubkey) -> Result<AddressLookupTable, ClientError> {
+        let account = self.rpc.get_account_with_commitment(&to_sdk_pubkey(table), self.rpc.commitment())
+            .await?
+            .value
+            .ok_or(ClientError::AccountNotFound(*table))?;
+        AddressLookupTable::from_account_data(*table, &account.data).map_err(|e| ClientError::DecodeError(e.to_string()))
+    }
+
+    // DAO 트랜잭션에 늘 들어가는 계정(DAO, vault, system/token 프로그램) 을 담은 테이블을 만들고 주소를 돌려준다.
+    // 만든 테이블은 다음 slot 부터 쓸 수 있다. 서버 설정의 lookup_table 에 넣으면 트랜잭션 빌더도 쓴다.
+    pub async fn create_dao_lookup_table(&self, authority: &Keypair, dao: &Pubkey) -> Result<Pubkey, ClientError> {
+        let authority_key = from_sdk_pubkey(&authority.pubkey());
+        let recent_slot = self.rpc.get_slot().await?;
+        let (create, table) = lookup_table::create_lookup_table(&authority_key, &authority_key, recent_slot)
+            .ok_or_else(|| ClientError::TransactionError("no lookup table address for this slot".to_string()))?;
+        let extend = lookup_table::extend_lookup_table(&table, &authority_key, &authority_key, &lookup_table::frequent_accounts(&self.program_id, dao));
+        self.send_and_confirm(&[create, extend], authority, &[]).await?;
+        Ok(table)
+    }
+
+    // 자주 쓰는 계정 (예: 보상을 자주 받는 작성자) 을 테이블에 더한다
+    pub async fn extend_lookup_table(&self, authority: &Keypair, table: &Pubkey, addresses: &[Pubkey]) -> Result<Signature, ClientError> {
+        let authority_key = from_sdk_pubkey(&authority.pubkey());
+        let instruction = lookup_table::extend_lookup_table(table, &authority_key, &authority_key, addresses);
+        self.send_and_confirm(&[instruction], authority, &[]).await
+    }
+
+    // round 의 품질 보상을 나눈다. 받는 사람이 많으면 레거시 트랜잭션에 들어가지 않으니 table 을 주면 v0 로 보낸다
+    #[allow(clippy::too_many_arguments)]
+    pub async fn distribute_quality_rewards(
+        &self,
+        admin: &Keypair,
+        dao: &Pubkey,
+        round: u64,
+        creators: Vec<Pubkey>,
+        weights: Vec<u8>,
+        table: Option<&Pubkey>,
+    ) -> Result<Signature, ClientError> {
+        let instruction = builders::distribute_quality_rewards(&self.program_id, &from_sdk_pubkey(&admin.pubkey()), dao, round, creators, weights);
+        match table {
+            Some(table) => {
+                let table = self.fetch_lookup_table(table).await?;
+                self.send_and_confirm_v0(&[instruction], admin, &[], &[table]).await
+            }
+            None => self.send_and_confirm(&[instruction], admin, &[]).await,
+        }
+    }
+
+    pub async fn deposit(&self, depositor: &Keypair, dao: &Pubkey, amount: u64) -> Result<Signature, ClientError> {
+        let instruction = builders::deposit(&self.program_id, &from_sdk_pubkey(&depositor.pubkey()), dao, amount);
+        self.send_and_confirm(&[instruction], depositor, &[]).await
+    }
+
+    pub async fn submit_content(&self, author: &Keypair, dao: &Pubkey, text: String, image_uri: String) -> Result<Signature, ClientError> {
+        let instruction = builders::submit_content(&self.program_id, &from_sdk_pubkey(&author.pubkey()), dao, text, image_uri);
+        self.send_and_confirm(&[instruction], author, &[]).await
+    }
+
+    pub async fn cast_vote(&self, voter: &Keypair, dao: &Pubkey, proposal_id: u64, option_index: u8) -> Result<Signature, ClientError> {
+        let instruction = builders::cast_vote(&self.program_id, &from_sdk_pubkey(&voter.pubkey()), dao, proposal_id, option_index);
+        self.send_and_confirm(&[instruction], voter, &[]).await
+    }
+
+    // content 는 Content::key. 표 기록 계정의 rent 는 voter 가 낸다
+    pub async fn vote_content(&self, voter: &Keypair, dao: &Pubkey, content: &Pubkey) -> Result<Signature, ClientError> {
+        let instruction = builders::vote_content(&self.program_id, &from_sdk_pubkey(&voter.pubkey()), dao, content);
+        self.send_and_confirm(&[instruction], voter, &[]).await
+    }
+
+    // 마감이 지난 라운드를 누구나 처리할 수 있다 (keeper 봇용). 승자가 있으면 caller 가 RoundResult 계정의 rent 를 내고
+    // 상금은 DAO 에서 last_submitter 로 바로 간다.
+    pub async fn process_timeout(&self, caller: &Keypair, dao: &Pubkey) -> Result<Signature, ClientError> {
+        let state = self.fetch_dao_state(dao).await?;
+        let instruction = builders::process_timeout(&self.program_id, &from_sdk_pubkey(&caller.pubkey()), dao, state.round, &state.last_submitter);
+        self.send_and_confirm(&[instruction], caller, &[]).await
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol::state::{RoundState, VoteStatus, VoteType};
+
+    fn state() -> DaoState {
+        DaoState {
+            is_initialized: true,
+            dao_name: "turtles".to_string(),
+            initializer: Pubkey([1; 32]),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 50,
+            timeout_timestamp: 0,
+            total_deposit: 0,
+            depositors: vec![],
+            contents: vec![],
+            vote_proposals: vec![VoteProposal {
+                proposal_id: 4,
+                proposer: Pubkey([1; 32]),
+                title: "longer rounds".to_string(),
+                description: String::new(),
+                vote_type: VoteType::ChangeTimeLimit,
+                options: vec!["Yes".to_string(), "No".to_string()],
+                start_time: 0,
+                end_time: 100,
+                votes: vec![],
+                status: VoteStatus::Active,
+            }],
+            next_proposal_id: 5,
+            rent_reserve: 1_000,
+            bounty_pool: 0,
+            fee_pool: 0,
+            last_submitter: Pubkey::default(),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: false,
+            fee_tiers: vec![],
+            round: 0,
+            max_deposit_per_wallet: 0,
+            max_total_deposit: 0,
+            streak_days: 0,
+            streak_discount: 0,
+            retract_window: 0,
+            retract_refund: 0,
+            round_state: RoundState::Active,
+            min_round_pot: 0,
+        }
+    }
+
+    fn account(owner: Pubkey, state: &DaoState) -> Account {
+        // 실제 계정처럼 최대 크기로 잡혀 뒤가 0 으로 채워져 있다
+        let mut data = borsh::to_vec(state).unwrap();
+        data.resize(data.len() + 64, 0);
+        Account { lamports: 1, data, owner: to_sdk_pubkey(&owner), executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn test_decode_dao_state_checks_owner() {
+        let program_id = Pubkey([7; 32]);
+        let dao = Pubkey([9; 32]);
+
+        let decoded = decode_dao_state(&dao, &account(program_id, &state()), &program_id).unwrap();
+        assert_eq!(decoded, state());
+
+        let foreign = decode_dao_state(&dao, &account(Pubkey([8; 32]), &state()), &program_id);
+        assert!(matches!(foreign, Err(ClientError::WrongOwner { owner, .. }) if owner == Pubkey([8; 32])));
+
+        let empty = DaoState { is_initialized: false, ..state() };
+        assert!(matches!(decode_dao_state(&dao, &account(program_id, &empty), &program_id), Err(ClientError::AccountNotFound(_))));
+    }
+}
@@ -0,0 +1,58 @@
+use sol::instruction::Instruction;
+use sol::lookup_table::AddressLookupTable;
+use sol::state::Pubkey;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::instruction::{AccountMeta as SdkAccountMeta, Instruction as SdkInstruction};
+use solana_sdk::pubkey::Pubkey as SdkPubkey;
+
+// sol 크레이트는 solana-sdk 없이 쓰려고 같은 모양의 타입을 따로 둔다. 바이트가 같아서 그대로 옮기면 된다.
+pub fn to_sdk_pubkey(pubkey: &Pubkey) -> SdkPubkey {
+    SdkPubkey::new_from_array(pubkey.0)
+}
+
+pub fn from_sdk_pubkey(pubkey: &SdkPubkey) -> Pubkey {
+    Pubkey(pubkey.to_bytes())
+}
+
+pub fn to_sdk_instruction(instruction: &Instruction) -> SdkInstruction {
+    SdkInstruction {
+        program_id: to_sdk_pubkey(&instruction.program_id),
+        accounts: instruction.accounts.iter().map(|meta| SdkAccountMeta {
+            pubkey: to_sdk_pubkey(&meta.pubkey),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }).collect(),
+        data: instruction.data.clone(),
+    }
+}
+
+pub fn to_sdk_lookup_table(table: &AddressLookupTable) -> AddressLookupTableAccount {
+    AddressLookupTableAccount {
+        key: to_sdk_pubkey(&table.key),
+        addresses: table.addresses.iter().map(to_sdk_pubkey).collect(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol::instruction::deposit;
+
+    #[test]
+    fn test_instruction_round_trips_into_sdk_types() {
+        let program_id = Pubkey([1; 32]);
+        let instruction = deposit(&program_id, &Pubkey([2; 32]), &Pubkey([3; 32]), 500);
+        let converted = to_sdk_instruction(&instruction);
+
+        assert_eq!(converted.program_id.to_string(), program_id.to_string());
+        assert_eq!(converted.data, instruction.data);
+        let metas: Vec<(Pubkey, bool, bool)> = converted.accounts.iter()
+            .map(|meta| (from_sdk_pubkey(&meta.pubkey), meta.is_signer, meta.is_writable))
+            .collect();
+        let expected: Vec<(Pubkey, bool, bool)> = instruction.accounts.iter()
+            .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+            .collect();
+        assert_eq!(metas, expected);
+    }
+}
@@ -0,0 +1,447 @@
+use serde::Serialize;
+use sol::state::{Content, DaoState, DepositorInfo, FeeTier, Pubkey, VoteInfo, VoteProposal, VoteStatus, VoteType};
+use std::error::Error as StdError;
+use std::fmt;
+
+// 계정 데이터와 트랜잭션 로그를 JSON 으로 읽는 디코더. 익스플로러와 백엔드 인덱서가 같은 해석을 쓰도록 여기에 둔다.
+// 공개키는 base58 문자열, 열거형은 변형 이름 문자열로 내보낸다.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidAccountData(String),
+    NotInitialized,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidAccountData(msg) => write!(f, "Invalid account data: {}", msg),
+            DecodeError::NotInitialized => write!(f, "DAO account is not initialized"),
+        }
+    }
+}
+
+impl StdError for DecodeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DepositorJson {
+    pub depositor: String,
+    pub amount: u64,
+    pub timestamp: u64,
+    pub locked_until: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeeTierJson {
+    pub min_deposit: u64,
+    pub discount: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentJson {
+    pub author: String,
+    pub text: String,
+    pub image_uri: String,
+    pub timestamp: u64,
+    pub vote_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VoteJson {
+    pub voter: String,
+    pub option_index: u8,
+    pub voting_power: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProposalJson {
+    pub proposal_id: u64,
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub vote_type: &'static str,
+    pub options: Vec<String>,
+    // 선택지별 투표력 합 (options 와 같은 순서)
+    pub option_power: Vec<u64>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub votes: Vec<VoteJson>,
+    pub status: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DaoStateJson {
+    pub dao_name: String,
+    pub initializer: String,
+    pub time_limit: u64,
+    pub base_fee: u64,
+    pub ai_moderation: bool,
+    pub deposit_share: u8,
+    pub timeout_timestamp: u64,
+    pub total_deposit: u64,
+    pub depositors: Vec<DepositorJson>,
+    pub contents: Vec<ContentJson>,
+    pub vote_proposals: Vec<ProposalJson>,
+    pub next_proposal_id: u64,
+    // lamports 장부: rent_reserve + bounty_pool + fee_pool == 계정 잔액
+    pub rent_reserve: u64,
+    pub bounty_pool: u64,
+    pub fee_pool: u64,
+    // 이번 라운드 마지막 제출자. 제출이 없으면 None
+    pub last_submitter: Option<String>,
+    pub deposit_resets_timer: bool,
+    // 제출 수수료 할인 구간 (min_deposit 오름차순일 필요는 없다)
+    pub fee_tiers: Vec<FeeTierJson>,
+    // 승자가 나온 라운드 수. 지난 라운드는 0..round 번 RoundResult 계정에 남는다
+    pub round: u64,
+    // 예치 한도 (0 이면 없다)
+    pub max_deposit_per_wallet: u64,
+    pub max_total_deposit: u64,
+    // 연속 제출 보너스: streak_days 일 연속 제출한 작성자는 수수료 streak_discount% 할인 (0 일이면 없다)
+    pub streak_days: u64,
+    pub streak_discount: u8,
+    // 철회 정책: 제출 후 retract_window 초 안에 철회하면 수수료의 retract_refund% 를 돌려받는다 (0 초면 철회 불가)
+    pub retract_window: u64,
+    pub retract_refund: u8,
+    // 라운드가 열리기 전이면 true (timeout_timestamp 는 마감이 아니다)
+    pub dormant: bool,
+    pub min_round_pot: u64,
+}
+
+pub fn vote_type_name(vote_type: &VoteType) -> &'static str {
+    match vote_type {
+        VoteType::ChangeTimeLimit => "ChangeTimeLimit",
+        VoteType::ChangeBaseFee => "ChangeBaseFee",
+        VoteType::ChangeAiModeration => "ChangeAiModeration",
+        VoteType::ContentQualityRating => "ContentQualityRating",
+        VoteType::ChangeDepositResetsTimer => "ChangeDepositResetsTimer",
+        VoteType::ChangeFeeTiers => "ChangeFeeTiers",
+        VoteType::ReplaceAdmin => "ReplaceAdmin",
+        VoteType::ChangeDepositCaps => "ChangeDepositCaps",
+        VoteType::ChangeStreakBonus => "ChangeStreakBonus",
+        VoteType::ChangeRetractPolicy => "ChangeRetractPolicy",
+        VoteType::ChangeRoundMinimum => "ChangeRoundMinimum",
+    }
+}
+
+pub fn vote_status_name(status: &VoteStatus) -> &'static str {
+    match status {
+        VoteStatus::Active => "Active",
+        VoteStatus::Completed => "Completed",
+        VoteStatus::Executed => "Executed",
+    }
+}
+
+impl From<&FeeTier> for FeeTierJson {
+    fn from(tier: &FeeTier) -> Self {
+        FeeTierJson { min_deposit: tier.min_deposit, discount: tier.discount }
+    }
+}
+
+impl From<&DepositorInfo> for DepositorJson {
+    fn from(depositor: &DepositorInfo) -> Self {
+        DepositorJson {
+            depositor: depositor.depositor.to_string(),
+            amount: depositor.amount,
+            timestamp: depositor.timestamp,
+            locked_until: depositor.locked_until,
+        }
+    }
+}
+
+impl From<&Content> for ContentJson {
+    fn from(content: &Content) -> Self {
+        ContentJson {
+            author: content.author.to_string(),
+            text: content.text.clone(),
+            image_uri: content.image_uri.clone(),
+            timestamp: content.timestamp,
+            vote_count: content.vote_count,
+        }
+    }
+}
+
+impl From<&VoteInfo> for VoteJson {
+    fn from(vote: &VoteInfo) -> Self {
+        VoteJson { voter: vote.voter.to_string(), option_index: vote.option_index, voting_power: vote.voting_power }
+    }
+}
+
+impl From<&VoteProposal> for ProposalJson {
+    fn from(proposal: &VoteProposal) -> Self {
+        // 범위를 벗어난 선택지 표는 프로그램 tally_proposal_votes 처럼 버린다
+        let mut option_power = vec![0u64; proposal.options.len()];
+        for vote in &proposal.votes {
+            if let Some(power) = option_power.get_mut(vote.option_index as usize) {
+                *power = power.saturating_add(vote.voting_power);
+            }
+        }
+        ProposalJson {
+            proposal_id: proposal.proposal_id,
+            proposer: proposal.proposer.to_string(),
+            title: proposal.title.clone(),
+            description: proposal.description.clone(),
+            vote_type: vote_type_name(&proposal.vote_type),
+            options: proposal.options.clone(),
+            option_power,
+            start_time: proposal.start_time,
+            end_time: proposal.end_time,
+            votes: proposal.votes.iter().map(VoteJson::from).collect(),
+            status: vote_status_name(&proposal.status),
+        }
+    }
+}
+
+impl From<&DaoState> for DaoStateJson {
+    fn from(state: &DaoState) -> Self {
+        DaoStateJson {
+            dao_name: state.dao_name.clone(),
+            initializer: state.initializer.to_string(),
+            time_limit: state.time_limit,
+            base_fee: state.base_fee,
+            ai_moderation: state.ai_moderation,
+            deposit_share: state.deposit_share,
+            timeout_timestamp: state.timeout_timestamp,
+            total_deposit: state.total_deposit,
+            depositors: state.depositors.iter().map(DepositorJson::from).collect(),
+            contents: state.contents.iter().map(ContentJson::from).collect(),
+            vote_proposals: state.vote_proposals.iter().map(ProposalJson::from).collect(),
+            next_proposal_id: state.next_proposal_id,
+            rent_reserve: state.rent_reserve,
+            bounty_pool: state.bounty_pool,
+            fee_pool: state.fee_pool,
+            last_submitter: (state.last_submitter != Pubkey::default()).then(|| state.last_submitter.to_string()),
+            deposit_resets_timer: state.deposit_resets_timer,
+            fee_tiers: state.fee_tiers.iter().map(FeeTierJson::from).collect(),
+            round: state.round,
+            max_deposit_per_wallet: state.max_deposit_per_wallet,
+            max_total_deposit: state.max_total_deposit,
+            streak_days: state.streak_days,
+            streak_discount: state.streak_discount,
+            retract_window: state.retract_window,
+            retract_refund: state.retract_refund,
+            dormant: state.is_dormant(),
+            min_round_pot: state.min_round_pot,
+        }
+    }
+}
+
+// DAO 계정 데이터 (뒤쪽이 0 으로 채워져 있어도 된다) 를 읽는다. 소유 프로그램 확인은 호출하는 쪽 몫이다.
+pub fn decode_dao_account(data: &[u8]) -> Result<DaoState, DecodeError> {
+    let state = DaoState::from_account_data(data)
+        .map_err(|e| DecodeError::InvalidAccountData(e.to_string()))?;
+    match state.is_initialized {
+        true => Ok(state),
+        false => Err(DecodeError::NotInitialized),
+    }
+}
+
+pub fn decode_dao_account_json(data: &[u8]) -> Result<DaoStateJson, DecodeError> {
+    decode_dao_account(data).map(|state| DaoStateJson::from(&state))
+}
+
+pub fn to_pretty_json<T: Serialize>(value: &T) -> String {
+    // 위 타입들은 문자열/숫자뿐이라 직렬화가 실패하지 않는다
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+// 프로그램이 msg! 로 남기는 문장 (solana_program/src/lib.rs 참고)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProgramEvent {
+    DaoInitialized { dao_name: String },
+    Deposit { amount: u64 },
+    ContentSubmitted,
+    ProposalCreated { proposal_id: u64 },
+    VoteCast { proposal_id: u64 },
+    // 타임아웃 처리. 제출된 콘텐츠가 없었으면 winner 가 없다.
+    TimeoutProcessed { winner: Option<String> },
+    TimeLimitUpdated { seconds: u64 },
+    BaseFeeUpdated { percent: u64 },
+    AiModerationUpdated { enabled: bool },
+    QualityRatingProcessed,
+}
+
+pub fn parse_log_message(message: &str) -> Option<ProgramEvent> {
+    if let Some(dao_name) = message.strip_prefix("DAO initialized: ") {
+        return Some(ProgramEvent::DaoInitialized { dao_name: dao_name.to_string() });
+    }
+    if let Some(rest) = message.strip_prefix("Deposit of ") {
+        let amount = rest.strip_suffix(" lamports processed")?.parse().ok()?;
+        return Some(ProgramEvent::Deposit { amount });
+    }
+    if message == "Content submitted, timeout reset" {
+        return Some(ProgramEvent::ContentSubmitted);
+    }
+    if let Some(proposal_id) = message.strip_prefix("Vote proposal created: ID ") {
+        return Some(ProgramEvent::ProposalCreated { proposal_id: proposal_id.parse().ok()? });
+    }
+    if let Some(proposal_id) = message.strip_prefix("Vote cast for proposal ") {
+        return Some(ProgramEvent::VoteCast { proposal_id: proposal_id.parse().ok()? });
+    }
+    if let Some(winner) = message.strip_prefix("Timeout processed, rewards distributed to winner ") {
+        return Some(ProgramEvent::TimeoutProcessed { winner: Some(winner.to_string()) });
+    }
+    if message == "Timeout processed, no content submissions found" {
+        return Some(ProgramEvent::TimeoutProcessed { winner: None });
+    }
+    if let Some(rest) = message.strip_prefix("Time limit updated to ") {
+        let seconds = rest.strip_suffix(" seconds")?.parse().ok()?;
+        return Some(ProgramEvent::TimeLimitUpdated { seconds });
+    }
+    if let Some(rest) = message.strip_prefix("Base fee updated to ") {
+        let percent = rest.strip_suffix('%')?.parse().ok()?;
+        return Some(ProgramEvent::BaseFeeUpdated { percent });
+    }
+    match message {
+        "AI moderation turned ON" => Some(ProgramEvent::AiModerationUpdated { enabled: true }),
+        "AI moderation turned OFF" => Some(ProgramEvent::AiModerationUpdated { enabled: false }),
+        "Content quality rating processed" => Some(ProgramEvent::QualityRatingProcessed),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoggedEvent {
+    // 트랜잭션에서 이 프로그램을 최상위로 부른 몇 번째 호출인지 (0 부터). 같은 순서의 명령과 짝지으면 계정을 알 수 있다.
+    pub invocation: usize,
+    #[serde(flatten)]
+    pub event: ProgramEvent,
+}
+
+// 트랜잭션 로그에서 이 프로그램이 남긴 이벤트를 꺼낸다.
+// 로그는 "Program <id> invoke [깊이]" ... "Program <id> success" 로 호출 단위가 나뉜다.
+// 최상위(깊이 1) 호출 안의 로그만 본다. CPI 로 불린 다른 프로그램의 로그는 건너뛴다.
+pub fn decode_logs(program_id: &str, logs: &[String]) -> Vec<LoggedEvent> {
+    let mut events = Vec::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut invocation: Option<usize> = None;
+    let mut top_level_count = 0;
+
+    for line in logs {
+        if let Some(message) = line.strip_prefix("Program log: ") {
+            let in_own_top_level = stack.len() == 1 && stack[0] == program_id;
+            if let (true, Some(invocation), Some(event)) = (in_own_top_level, invocation, parse_log_message(message)) {
+                events.push(LoggedEvent { invocation, event });
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            let (Some(program), Some(action)) = (words.next(), words.next()) else {
+                continue;
+            };
+            match action {
+                "invoke" => {
+                    if stack.is_empty() {
+                        invocation = (program == program_id).then_some(top_level_count);
+                        if program == program_id {
+                            top_level_count += 1;
+                        }
+                    }
+                    stack.push(program);
+                }
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol::state::RoundState;
+
+    #[test]
+    fn test_decode_dao_account_json() {
+        let state = DaoState {
+            is_initialized: true,
+            dao_name: "turtles".to_string(),
+            initializer: Pubkey([1; 32]),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 50,
+            timeout_timestamp: 0,
+            total_deposit: 0,
+            depositors: vec![],
+            contents: vec![],
+            vote_proposals: vec![VoteProposal {
+                proposal_id: 4,
+                proposer: Pubkey([1; 32]),
+                title: "longer rounds".to_string(),
+                description: String::new(),
+                vote_type: VoteType::ChangeTimeLimit,
+                options: vec!["7200".to_string(), "3600".to_string()],
+                start_time: 0,
+                end_time: 100,
+                votes: vec![
+                    VoteInfo { voter: Pubkey([2; 32]), option_index: 0, voting_power: 30 },
+                    VoteInfo { voter: Pubkey([3; 32]), option_index: 0, voting_power: 12 },
+                    VoteInfo { voter: Pubkey([4; 32]), option_index: 9, voting_power: 5 },
+                ],
+                status: VoteStatus::Active,
+            }],
+            next_proposal_id: 5,
+            rent_reserve: 1_000,
+            bounty_pool: 0,
+            fee_pool: 0,
+            last_submitter: Pubkey([2; 32]),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: false,
+            fee_tiers: vec![],
+            round: 0,
+            max_deposit_per_wallet: 0,
+            max_total_deposit: 0,
+            streak_days: 0,
+            streak_discount: 0,
+            retract_window: 0,
+            retract_refund: 0,
+            round_state: RoundState::Active,
+            min_round_pot: 0,
+        };
+        let mut data = borsh::to_vec(&state).unwrap();
+        data.resize(data.len() + 64, 0);
+
+        let json: serde_json::Value = serde_json::from_str(&to_pretty_json(&decode_dao_account_json(&data).unwrap())).unwrap();
+        assert_eq!(json["initializer"], Pubkey([1; 32]).to_string());
+        assert_eq!(json["last_submitter"], Pubkey([2; 32]).to_string());
+        assert_eq!(json["vote_proposals"][0]["vote_type"], "ChangeTimeLimit");
+        assert_eq!(json["vote_proposals"][0]["option_power"], serde_json::json!([42, 0]));
+
+        let empty = borsh::to_vec(&DaoState { is_initialized: false, ..state }).unwrap();
+        assert_eq!(decode_dao_account(&empty), Err(DecodeError::NotInitialized));
+        assert!(matches!(decode_dao_account(&[1, 2, 3]), Err(DecodeError::InvalidAccountData(_))));
+    }
+
+    #[test]
+    fn test_decode_logs_skips_other_programs() {
+        let logs: Vec<String> = [
+            "Program Turtle111 invoke [1]",
+            "Program log: Deposit of 500 lamports processed",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program log: Deposit of 1 lamports processed",
+            "Program 11111111111111111111111111111111 success",
+            "Program Turtle111 success",
+            "Program Other111 invoke [1]",
+            "Program log: Vote cast for proposal 1",
+            "Program Other111 success",
+            "Program Turtle111 invoke [1]",
+            "Program log: Base fee updated to 15%",
+            "Program log: Timeout processed, no content submissions found",
+            "Program Turtle111 success",
+        ].iter().map(|line| line.to_string()).collect();
+
+        assert_eq!(decode_logs("Turtle111", &logs), vec![
+            LoggedEvent { invocation: 0, event: ProgramEvent::Deposit { amount: 500 } },
+            LoggedEvent { invocation: 1, event: ProgramEvent::BaseFeeUpdated { percent: 15 } },
+            LoggedEvent { invocation: 1, event: ProgramEvent::TimeoutProcessed { winner: None } },
+        ]);
+        assert_eq!(parse_log_message("Vote proposal created: ID 7"), Some(ProgramEvent::ProposalCreated { proposal_id: 7 }));
+        assert_eq!(parse_log_message("Would distribute 5 lamports to winner abc"), None);
+    }
+}
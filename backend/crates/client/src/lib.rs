@@ -0,0 +1,14 @@
+// 봇과 백엔드가 함께 쓰는 Turtle 프로그램 클라이언트.
+// 계정 읽기/트랜잭션 전송은 solana-client 의 비동기 RPC 를 쓰고, 명령 빌더와 상태 타입은 sol 크레이트 것을 그대로 내보낸다.
+#[cfg(feature = "rpc")]
+pub mod client;
+#[cfg(feature = "rpc")]
+pub mod convert;
+pub mod decode;
+
+#[cfg(feature = "rpc")]
+pub use client::{ClientError, TurtleClient};
+pub use sol::instruction;
+pub use sol::lookup_table;
+pub use sol::state;
+pub use sol::state::{Content, DaoState, Pubkey, VoteProposal};
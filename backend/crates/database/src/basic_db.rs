@@ -1,136 +1,81 @@
-use std::borrow::Cow;
-use libmdbx::{Database, DatabaseOptions, WriteMap, WriteFlags, TableFlags};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
 use std::path::Path;
+use crate::error::{DatabaseError, DbResult};
+use crate::kv::{KeyRange, KvOp, KvStore};
 
-#[derive(Clone)]
-pub struct InnerDatabase {
-    db: Arc<Mutex<Database<WriteMap>>>,
-}
-
-pub trait SafeDatabase {
-
-    fn new<P: AsRef<Path>>(path: P) -> Result<Self, libmdbx::Error> where Self: Sized;
-
-    fn clone(&self) -> Self where Self: Sized;
-
-
+// 핸들러들이 쓰는 문자열 키 기반 인터페이스.
+// KvStore 를 구현한 모든 백엔드에 대해 자동으로 구현되므로, net 크레이트는 이 트레이트에만 의존한다.
+pub trait SafeDatabase: KvStore {
 
     // 트레이트 메서드에 pub 키워드 제거 (트레이트 자체가 pub이므로 메서드도 pub)
-    fn write(&self, key: &str, value: &str, table: &str) -> Result<(), libmdbx::Error>;
+    fn write(&self, key: &str, value: &str, table: &str) -> DbResult<()> {
+        self.put(table, key.as_bytes(), value.as_bytes())
+    }
 
-    fn read(&self, key: &str, table: &str) -> Result<Option<Vec<u8>>, libmdbx::Error>;
+    fn read(&self, key: &str, table: &str) -> DbResult<Option<Vec<u8>>> {
+        self.get(table, key.as_bytes())
+    }
 
-    fn read_all(&self, table: &str) -> Result<HashMap<Vec<u8>, Vec<u8>>, libmdbx::Error>;
+    fn read_all(&self, table: &str) -> DbResult<HashMap<Vec<u8>, Vec<u8>>> {
+        Ok(self.scan(table, &KeyRange::all())?.into_iter().collect())
+    }
 
-    fn batch_write<K, V>(&self, items: &[(K, V)], table: &str) -> Result<(), libmdbx::Error>
+    fn batch_write<K, V>(&self, items: &[(K, V)], table: &str) -> DbResult<()>
     where
         K: AsRef<[u8]>,
-        V: AsRef<[u8]>;
-}
-
-
-impl SafeDatabase for InnerDatabase{
-
-    fn new<P: AsRef<Path>>(path: P) -> Result<Self, libmdbx::Error> {
-        let mut options = DatabaseOptions::default();
-        options.max_tables = Some(100);
-        let db = Database::<WriteMap>::open_with_options(path, options)?;
-
-        Ok(Self {
-            db: Arc::new(Mutex::new(db)),
-        })
+        V: AsRef<[u8]>,
+    {
+        self.ensure_table(table)?;
+        let ops: Vec<KvOp> = items
+            .iter()
+            .map(|(key, value)| KvOp::put(table, key, value))
+            .collect();
+        self.txn(&ops)
     }
 
-    fn clone(&self) -> Self {
-        Self {
-            db: Arc::clone(&self.db),
-        }
+    fn remove(&self, key: &str, table: &str) -> DbResult<bool> {
+        self.delete(table, key.as_bytes())
     }
 
-
-    fn write(&self, key: &str, value: &str, table: &str) -> Result<(), libmdbx::Error> {
-        let db = self.db.lock().expect("Failed to lock database mutex");
-        let transaction = db.begin_rw_txn()?;
-        let table = transaction.create_table(Some(table), TableFlags::default())?;
-
-        transaction.put(&table, key, value, WriteFlags::default())?;
-        transaction.commit()?;
-        Ok(())
+    fn batch_remove<K>(&self, keys: &[K], table: &str) -> DbResult<()>
+    where
+        K: AsRef<[u8]>,
+    {
+        let ops: Vec<KvOp> = keys.iter().map(|key| KvOp::delete(table, key)).collect();
+        self.txn(&ops)
     }
 
-
-    fn read(&self, key: &str, table: &str) -> Result<Option<Vec<u8>>, libmdbx::Error> {
-        let db = self.db.lock().expect("Failed to lock database mutex");
-        let transaction = db.begin_ro_txn()?;
-
-        if let Ok(table) = transaction.open_table(Some(table)) {
-            let result = transaction.get(&table, key.as_bytes())?;
-            return Ok(result);
-        }
-
-        Ok(None)
+    // 빈 테이블이라도 미리 만들어 두기 위한 메서드 (마이그레이션에서 사용)
+    fn create_table(&self, table: &str) -> DbResult<()> {
+        self.ensure_table(table)
     }
 
-    fn read_all(&self, table: &str) -> Result<HashMap<Vec<u8>, Vec<u8>>, libmdbx::Error> {
-        let mut map = HashMap::new();
-        let db = self.db.lock().expect("Failed to lock database mutex");
-        let transaction = db.begin_ro_txn()?;
-
-        if let Ok(table) = transaction.open_table(Some(table)) {
-            let cursor = transaction.cursor(&table)?;
+    fn table_names(&self) -> DbResult<Vec<String>> {
+        self.tables()
+    }
 
-            for item in cursor {
-                let (key, value) = item?;
-                let key_owned = key.to_vec();
-                let value_owned = value.to_vec();
-                map.insert(key_owned, value_owned);
+    // 서버를 멈추지 않고 모든 테이블을 path 의 새 D 데이터베이스로 복사한다. 대상 경로는 비어 있어야 한다.
+    fn backup_to<D: DatabaseBackend, P: AsRef<Path>>(&self, path: P) -> DbResult<()> {
+        let path = path.as_ref();
+        if let Ok(mut entries) = std::fs::read_dir(path) {
+            if entries.next().is_some() {
+                return Err(DatabaseError::AlreadyExists(path.display().to_string()));
             }
         }
-
-        Ok(map)
+        self.export_to::<D>(path)
     }
 
-
-    fn batch_write<K, V>(&self, items: &[(K, V)], table: &str) -> Result<(), libmdbx::Error>
-    where
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
-        let db = self.db.lock().expect("Failed to lock database mutex");
-        let transaction = db.begin_rw_txn()?;
-        let table = transaction.create_table(Some(table), TableFlags::default())?;
-
-        for (key, value) in items {
-            transaction.put(&table, key, value, WriteFlags::default())?;
-        }
-
-        transaction.commit()?;
-        Ok(())
+    // 백업한 D 데이터베이스의 내용으로 현재 데이터베이스를 통째로 교체한다.
+    fn restore_from<D: DatabaseBackend, P: AsRef<Path>>(&self, path: P) -> DbResult<()> {
+        self.import_from::<D>(path.as_ref())
     }
 }
 
+impl<T: KvStore> SafeDatabase for T {}
 
+// 백엔드를 여는 방법. 서버는 설정된 경로로 open 하고, 테스트는 독립된 빈 데이터베이스를 얻는다.
+pub trait DatabaseBackend: KvStore + Sized {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self>;
 
-
-//pub fn read_db<'a, 'b>(path:&'a str, table:&'a str) -> Result<  HashMap::<Cow<'b, [u8]> , Cow<'b, [u8]>>, libmdbx::Error>{
-//    let mut map = HashMap::<Cow<[u8]> , Cow<[u8]>>::new();
-//    let db = open_db(path)?;
-//    let transaction = db.begin_ro_txn()?;
-//    let table = transaction.open_table(Some(table))?;
-//
-//    let cursor = transaction.cursor(&table)?;
-//
-//    for item in cursor {
-//        let (key_ , value_) =  item?;
-//        map.insert(key_, value_);
-//    }
-//
-//    transaction.commit()?;
-//
-//
-//    Ok(map)
-//}  ---> WARNING! : libmdbx using unsafe, so , If we set the lifetime like above,  there will be evoked dangling reference problem.
-
-
+    fn open_temporary() -> DbResult<Self>;
+}
@@ -0,0 +1,59 @@
+use std::process::ExitCode;
+use turtle_database::basic_db::DatabaseBackend;
+use turtle_database::dump::{export_json, import_json};
+use turtle_database::encryption::Encrypted;
+
+// 서버와 같은 규칙으로 고른 저장소 (sled 를 켜면 sled)
+#[cfg(all(feature = "mdbx", not(feature = "sled")))]
+type Store = turtle_database::mdbx_db::InnerDatabase;
+#[cfg(feature = "sled")]
+type Store = turtle_database::sled_db::SledDatabase;
+#[cfg(not(any(feature = "mdbx", feature = "sled")))]
+compile_error!("turtle-db needs a storage backend: enable the mdbx or sled feature");
+
+// 데이터베이스 덤프 도구
+//
+//   turtle-db export <db 디렉토리> <json 파일>
+//   turtle-db import <db 디렉토리> <json 파일>
+//
+// TURTLE_DB_KEY 가 설정되어 있으면 암호화된 테이블을 복호화해서 내보내고, 가져올 때 다시 암호화한다.
+// 서버가 같은 db 를 열고 있는 동안에는 실행하지 않는다.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+
+    let (command, db_path, json_path) = match args.as_slice() {
+        [_, command, db_path, json_path] => (command.as_str(), db_path, json_path),
+        _ => {
+            eprintln!("usage: turtle-db <export|import> <db dir> <json file>");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let database = match Encrypted::<Store>::open(db_path) {
+        Ok(database) => database,
+        Err(e) => {
+            eprintln!("failed to open database at {}: {}", db_path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match command {
+        "export" => export_json(&database, json_path).map(|count| format!("exported {} entries to {}", count, json_path)),
+        "import" => import_json(&database, json_path).map(|count| format!("imported {} entries from {}", count, json_path)),
+        _ => {
+            eprintln!("unknown command: {}", command);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(message) => {
+            println!("{}", message);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{} failed: {}", command, e);
+            ExitCode::FAILURE
+        }
+    }
+}
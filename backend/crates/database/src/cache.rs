@@ -0,0 +1,354 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::DbResult;
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::DatabaseStats;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheConfig {
+    // 0 이면 캐시를 쓰지 않는다
+    pub max_entries: usize,
+    pub max_bytes: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_bytes: 64 << 20,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+type CacheKey = (String, Vec<u8>);
+
+struct CacheEntry {
+    // 없는 키도 캐시한다 (없는 프로필 조회가 반복되는 경우)
+    value: Option<Vec<u8>>,
+    last_used: u64,
+}
+
+fn entry_size(key: &CacheKey, value: &Option<Vec<u8>>) -> usize {
+    key.0.len() + key.1.len() + value.as_ref().map_or(0, Vec::len)
+}
+
+// 최근에 쓰인 순서를 tick 으로 기록하고, 한도를 넘으면 tick 이 가장 작은 항목부터 버린다
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    order: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl LruState {
+    fn get(&mut self, key: &CacheKey) -> Option<Option<Vec<u8>>> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let entry = self.entries.get_mut(key)?;
+        self.order.remove(&entry.last_used);
+        self.order.insert(tick, key.clone());
+        entry.last_used = tick;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: CacheKey, value: Option<Vec<u8>>, config: &CacheConfig) {
+        let size = entry_size(&key, &value);
+        if config.max_entries == 0 || size > config.max_bytes {
+            return;
+        }
+
+        self.remove(&key);
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, CacheEntry { value, last_used: self.tick });
+        self.bytes += size;
+
+        while self.entries.len() > config.max_entries || self.bytes > config.max_bytes {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.bytes -= entry_size(&oldest, &entry.value);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.last_used);
+            self.bytes -= entry_size(key, &entry.value);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.bytes = 0;
+    }
+}
+
+// 아무 KvStore 나 감싸서 get 결과를 프로세스 안의 LRU 캐시에 담아 둔다.
+// 이 핸들을 통한 쓰기는 해당 키를 캐시에서 지운다. 범위 스캔은 캐시하지 않는다.
+#[derive(Clone)]
+pub struct Cached<S> {
+    inner: S,
+    config: Arc<CacheConfig>,
+    // 읽기 miss 와 쓰기가 이 락 안에서 inner 에 접근하므로, 오래된 값이 캐시에 다시 들어가지 않는다
+    state: Arc<Mutex<LruState>>,
+}
+
+impl<S: KvStore> Cached<S> {
+    pub fn new(inner: S, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            config: Arc::new(config),
+            state: Arc::new(Mutex::new(LruState::default())),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        let state = self.state();
+        CacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries.len() as u64,
+            bytes: state.bytes as u64,
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, LruState> {
+        self.state.lock().expect("Failed to lock cache mutex")
+    }
+}
+
+impl<S: DatabaseBackend> DatabaseBackend for Cached<S> {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Ok(Self::new(S::open(path)?, CacheConfig::default()))
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        Ok(Self::new(S::open_temporary()?, CacheConfig::default()))
+    }
+}
+
+impl<S: KvStore> KvStore for Cached<S> {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        let cache_key = (table.to_string(), key.to_vec());
+        let mut state = self.state();
+
+        if let Some(value) = state.get(&cache_key) {
+            state.hits += 1;
+            return Ok(value);
+        }
+
+        state.misses += 1;
+        let value = self.inner.get(table, key)?;
+        state.insert(cache_key, value.clone(), &self.config);
+        Ok(value)
+    }
+
+    // 캐시에 없는 키만 모아서 inner 에서 한 번에 읽는다
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        let mut state = self.state();
+        let mut values = Vec::with_capacity(keys.len());
+        let mut missing = Vec::new();
+
+        for (i, key) in keys.iter().enumerate() {
+            match state.get(&(table.to_string(), key.to_vec())) {
+                Some(value) => {
+                    state.hits += 1;
+                    values.push(value);
+                },
+                None => {
+                    state.misses += 1;
+                    missing.push(i);
+                    values.push(None);
+                },
+            }
+        }
+
+        if !missing.is_empty() {
+            let missing_keys: Vec<&[u8]> = missing.iter().map(|&i| keys[i]).collect();
+            for (i, value) in missing.into_iter().zip(self.inner.get_many(table, &missing_keys)?) {
+                state.insert((table.to_string(), keys[i].to_vec()), value.clone(), &self.config);
+                values[i] = value;
+            }
+        }
+        Ok(values)
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        let mut state = self.state();
+        let result = self.inner.put(table, key, value);
+        state.remove(&(table.to_string(), key.to_vec()));
+        result
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        let mut state = self.state();
+        let result = self.inner.delete(table, key);
+        state.remove(&(table.to_string(), key.to_vec()));
+        result
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.inner.scan(table, range)
+    }
+
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let mut state = self.state();
+        let result = self.inner.txn(ops);
+        for op in ops {
+            match op {
+                KvOp::Put { table, key, .. } | KvOp::Delete { table, key } => {
+                    state.remove(&(table.clone(), key.clone()));
+                }
+            }
+        }
+        result
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.inner.ensure_table(table)
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        self.inner.tables()
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        let mut stats = self.inner.stats()?;
+        stats.cache = Some(self.cache_stats());
+        Ok(stats)
+    }
+
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.inner.export_to::<D>(path)
+    }
+
+    fn import_from<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        let mut state = self.state();
+        let result = self.inner.import_from::<D>(path);
+        state.clear();
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+
+    #[test]
+    fn test_hits_and_invalidation() -> Result<(), Box<dyn std::error::Error>> {
+        let db = Cached::new(MemoryDatabase::new(), CacheConfig::default());
+        db.write("alice", "v1", "user_profiles")?;
+
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"v1".to_vec()));
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"v1".to_vec()));
+        assert_eq!(db.read("nobody", "user_profiles")?, None);
+        assert_eq!(db.read("nobody", "user_profiles")?, None);
+
+        let stats = db.cache_stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+
+        // 쓰기 후에는 새 값을 읽어야 한다
+        db.write("alice", "v2", "user_profiles")?;
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"v2".to_vec()));
+        db.batch_write(&[("nobody", "now-exists")], "user_profiles")?;
+        assert_eq!(db.read("nobody", "user_profiles")?, Some(b"now-exists".to_vec()));
+        assert!(db.remove("alice", "user_profiles")?);
+        assert_eq!(db.read("alice", "user_profiles")?, None);
+
+        assert_eq!(db.stats()?.cache.map(|cache| cache.misses), Some(5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_many_reads_only_misses() -> Result<(), Box<dyn std::error::Error>> {
+        let db = Cached::new(MemoryDatabase::new(), CacheConfig::default());
+        db.batch_write(&[("a", "1"), ("b", "2")], "t")?;
+        db.read("a", "t")?;
+
+        let values = db.get_many("t", &[&b"b"[..], &b"a"[..], &b"missing"[..]])?;
+        assert_eq!(values, vec![Some(b"2".to_vec()), Some(b"1".to_vec()), None]);
+        assert_eq!((db.cache_stats().hits, db.cache_stats().misses), (1, 3));
+
+        // 두 번째에는 없는 키까지 모두 캐시에서 나온다
+        db.get_many("t", &[&b"b"[..], &b"a"[..], &b"missing"[..]])?;
+        assert_eq!((db.cache_stats().hits, db.cache_stats().misses), (4, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() -> Result<(), Box<dyn std::error::Error>> {
+        let config = CacheConfig {
+            max_entries: 2,
+            max_bytes: 1 << 20,
+        };
+        let db = Cached::new(MemoryDatabase::new(), config);
+        db.batch_write(&[("a", "1"), ("b", "2"), ("c", "3")], "t")?;
+
+        db.read("a", "t")?;
+        db.read("b", "t")?;
+        db.read("a", "t")?; // b 가 가장 오래 안 쓰인 항목이 된다
+        db.read("c", "t")?; // b 가 밀려난다
+
+        let before = db.cache_stats();
+        assert_eq!(before.entries, 2);
+        db.read("a", "t")?;
+        db.read("b", "t")?;
+        let after = db.cache_stats();
+        assert_eq!(after.hits - before.hits, 1);
+        assert_eq!(after.misses - before.misses, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_limit() -> Result<(), Box<dyn std::error::Error>> {
+        let config = CacheConfig {
+            max_entries: 100,
+            max_bytes: 20,
+        };
+        let db = Cached::new(MemoryDatabase::new(), config);
+        db.write("big", &"x".repeat(64), "t")?;
+        db.write("k1", "0123456789", "t")?;
+        db.write("k2", "0123456789", "t")?;
+
+        // 한도보다 큰 값은 캐시하지 않는다
+        db.read("big", "t")?;
+        assert_eq!(db.cache_stats().entries, 0);
+
+        db.read("k1", "t")?;
+        db.read("k2", "t")?;
+        let stats = db.cache_stats();
+        assert_eq!(stats.entries, 1);
+        assert!(stats.bytes <= 20);
+
+        Ok(())
+    }
+}
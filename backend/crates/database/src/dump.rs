@@ -0,0 +1,173 @@
+use crate::error::{DatabaseError, DbResult};
+use crate::kv::{KeyRange, KvOp, KvStore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// 환경 간 데이터 이전, 로컬 fixture 용 JSON 덤프 포맷
+//
+// {
+//   "format": "turtle-db-dump",
+//   "version": 1,
+//   "tables": {
+//     "user_profiles": [ { "key": "alice", "value": "{...}" } ],
+//     "history": [ { "key": { "hex": "0000..." }, "value": { "hex": "..." } } ]
+//   }
+// }
+//
+// UTF-8 로 읽히는 키/값은 문자열 그대로, 아니면 {"hex": ...} 로 적는다.
+pub const DUMP_FORMAT: &str = "turtle-db-dump";
+pub const DUMP_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DumpBytes {
+    Utf8(String),
+    Hex { hex: String },
+}
+
+impl DumpBytes {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => DumpBytes::Utf8(text),
+            Err(e) => DumpBytes::Hex {
+                hex: hex::encode(e.into_bytes()),
+            },
+        }
+    }
+
+    pub fn into_bytes(self) -> DbResult<Vec<u8>> {
+        match self {
+            DumpBytes::Utf8(text) => Ok(text.into_bytes()),
+            DumpBytes::Hex { hex } => hex::decode(&hex)
+                .map_err(|e| DatabaseError::Corrupted(format!("invalid hex in dump: {}", e))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpEntry {
+    pub key: DumpBytes,
+    pub value: DumpBytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dump {
+    pub format: String,
+    pub version: u32,
+    pub tables: BTreeMap<String, Vec<DumpEntry>>,
+}
+
+pub fn dump_tables<S: KvStore>(database: &S) -> DbResult<Dump> {
+    let mut tables = BTreeMap::new();
+    for table in database.tables()? {
+        let entries = database
+            .scan(&table, &KeyRange::all())?
+            .into_iter()
+            .map(|(key, value)| DumpEntry {
+                key: DumpBytes::from_bytes(key),
+                value: DumpBytes::from_bytes(value),
+            })
+            .collect();
+        tables.insert(table, entries);
+    }
+
+    Ok(Dump {
+        format: DUMP_FORMAT.to_string(),
+        version: DUMP_VERSION,
+        tables,
+    })
+}
+
+// 덤프의 모든 항목을 한 트랜잭션으로 써 넣는다. 덤프에 없는 기존 데이터는 그대로 둔다.
+pub fn load_tables<S: KvStore>(database: &S, dump: Dump) -> DbResult<usize> {
+    if dump.format != DUMP_FORMAT || dump.version != DUMP_VERSION {
+        return Err(DatabaseError::Corrupted(format!(
+            "unsupported dump {} v{}",
+            dump.format, dump.version
+        )));
+    }
+
+    let mut ops = Vec::new();
+    for (table, entries) in dump.tables {
+        database.ensure_table(&table)?;
+        for entry in entries {
+            ops.push(KvOp::put(&table, entry.key.into_bytes()?, entry.value.into_bytes()?));
+        }
+    }
+
+    database.txn(&ops)?;
+    Ok(ops.len())
+}
+
+// 내보낸 항목 수를 돌려준다
+pub fn export_json<S: KvStore, P: AsRef<Path>>(database: &S, path: P) -> DbResult<usize> {
+    let dump = dump_tables(database)?;
+    let count = dump.tables.values().map(Vec::len).sum();
+
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    serde_json::to_writer_pretty(file, &dump)?;
+    Ok(count)
+}
+
+// 가져온 항목 수를 돌려준다
+pub fn import_json<S: KvStore, P: AsRef<Path>>(database: &S, path: P) -> DbResult<usize> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let dump: Dump = serde_json::from_reader(file)?;
+    load_tables(database, dump)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_json_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("dump.json");
+
+        let source = MemoryDatabase::new();
+        source.write("alice", "{\"nickname\":\"alice\"}", "user_profiles")?;
+        source.put("binary", &[0x00, 0xff], &[0xde, 0xad, 0xbe, 0xef])?;
+        source.create_table("empty")?;
+
+        assert_eq!(export_json(&source, &path)?, 2);
+
+        let target = MemoryDatabase::new();
+        target.write("existing", "kept", "user_profiles")?;
+        assert_eq!(import_json(&target, &path)?, 2);
+
+        assert_eq!(target.read("alice", "user_profiles")?, Some(b"{\"nickname\":\"alice\"}".to_vec()));
+        assert_eq!(target.get("binary", &[0x00, 0xff])?, Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(target.read("existing", "user_profiles")?, Some(b"kept".to_vec()));
+        assert!(target.table_names()?.contains(&"empty".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixture_format() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = r#"{
+            "format": "turtle-db-dump",
+            "version": 1,
+            "tables": {
+                "community": [ { "key": "dao1", "value": "community-1" } ],
+                "raw": [ { "key": { "hex": "00ff" }, "value": "x" } ]
+            }
+        }"#;
+
+        let db = MemoryDatabase::new();
+        assert_eq!(load_tables(&db, serde_json::from_str(fixture)?)?, 2);
+        assert_eq!(db.read("dao1", "community")?, Some(b"community-1".to_vec()));
+        assert_eq!(db.get("raw", &[0x00, 0xff])?, Some(b"x".to_vec()));
+
+        let wrong_version = fixture.replace("\"version\": 1", "\"version\": 9");
+        assert!(load_tables(&db, serde_json::from_str(&wrong_version)?).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::{DatabaseError, DbResult};
+use crate::history::HISTORY_TABLE;
+use crate::keys::KeyBuilder;
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::DatabaseStats;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+// 값을 암호화해서 저장하는 테이블들 (프로필, 세션 토큰, 그리고 프로필 이전 버전이 남는 history)
+pub const ENCRYPTED_TABLES: &[&str] = &["user_profiles", "sessions", HISTORY_TABLE];
+
+// 현재 키: "<key id>:<64자리 hex>"
+pub const KEY_ENV: &str = "TURTLE_DB_KEY";
+// 복호화에만 쓰는 이전 키들: "<key id>:<hex>,<key id>:<hex>"
+pub const OLD_KEYS_ENV: &str = "TURTLE_DB_OLD_KEYS";
+
+// 암호문 포맷: MAGIC(4) | key id(4, big-endian) | nonce(12) | ciphertext + tag
+// MAGIC 이 없는 값은 암호화를 켜기 전에 저장된 평문으로 보고 그대로 돌려준다.
+const MAGIC: &[u8; 4] = b"TENC";
+const KEY_ID_LEN: usize = 4;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + KEY_ID_LEN + NONCE_LEN;
+
+pub const KEY_LEN: usize = 32;
+
+// 현재 키로 암호화하고, 이전 키들로도 복호화할 수 있는 키 묶음
+#[derive(Clone)]
+pub struct Keyring {
+    current: u32,
+    ciphers: HashMap<u32, ChaCha20Poly1305>,
+}
+
+impl Keyring {
+    pub fn new(current_id: u32, current_key: &[u8; KEY_LEN]) -> Self {
+        let mut ciphers = HashMap::new();
+        ciphers.insert(current_id, ChaCha20Poly1305::new(Key::from_slice(current_key)));
+
+        Self {
+            current: current_id,
+            ciphers,
+        }
+    }
+
+    pub fn with_old_key(mut self, id: u32, key: &[u8; KEY_LEN]) -> Self {
+        self.ciphers
+            .entry(id)
+            .or_insert_with(|| ChaCha20Poly1305::new(Key::from_slice(key)));
+        self
+    }
+
+    pub fn current_id(&self) -> u32 {
+        self.current
+    }
+
+    pub fn has_old_keys(&self) -> bool {
+        self.ciphers.len() > 1
+    }
+
+    pub fn parse(current: &str, old: Option<&str>) -> DbResult<Self> {
+        let (id, key) = parse_key(current)?;
+        let mut keyring = Self::new(id, &key);
+
+        for entry in old.unwrap_or("").split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, key) = parse_key(entry)?;
+            keyring = keyring.with_old_key(id, &key);
+        }
+
+        Ok(keyring)
+    }
+
+    // 키가 설정되어 있지 않으면 None (암호화 없이 동작)
+    pub fn from_env() -> DbResult<Option<Self>> {
+        match std::env::var(KEY_ENV) {
+            Ok(current) => Ok(Some(Self::parse(&current, std::env::var(OLD_KEYS_ENV).ok().as_deref())?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> DbResult<Vec<u8>> {
+        let cipher = &self.ciphers[&self.current];
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad })
+            .map_err(|_| DatabaseError::Encryption("encryption failed".to_string()))?;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.current.to_be_bytes());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+
+    fn decrypt(&self, aad: &[u8], bytes: &[u8]) -> DbResult<Vec<u8>> {
+        let Some(key_id) = key_id(bytes) else {
+            return Ok(bytes.to_vec());
+        };
+
+        let cipher = self
+            .ciphers
+            .get(&key_id)
+            .ok_or_else(|| DatabaseError::Encryption(format!("unknown key id {}", key_id)))?;
+        let nonce = Nonce::from_slice(&bytes[MAGIC.len() + KEY_ID_LEN..HEADER_LEN]);
+
+        cipher
+            .decrypt(nonce, Payload { msg: &bytes[HEADER_LEN..], aad })
+            .map_err(|_| DatabaseError::Encryption(format!("cannot decrypt value with key id {}", key_id)))
+    }
+}
+
+fn parse_key(entry: &str) -> DbResult<(u32, [u8; KEY_LEN])> {
+    let invalid = || DatabaseError::Encryption(format!("invalid key entry, expected <id>:<{} hex chars>", KEY_LEN * 2));
+
+    let (id, hex_key) = entry.trim().split_once(':').ok_or_else(invalid)?;
+    let id = id.parse::<u32>().map_err(|_| invalid())?;
+    let key: [u8; KEY_LEN] = hex::decode(hex_key)
+        .map_err(|_| invalid())?
+        .try_into()
+        .map_err(|_| invalid())?;
+
+    Ok((id, key))
+}
+
+// 암호문이면 그 키 id, 평문(헤더 없음)이면 None
+fn key_id(bytes: &[u8]) -> Option<u32> {
+    if bytes.len() < HEADER_LEN || !bytes.starts_with(MAGIC) {
+        return None;
+    }
+    let id = &bytes[MAGIC.len()..MAGIC.len() + KEY_ID_LEN];
+    Some(u32::from_be_bytes(id.try_into().ok()?))
+}
+
+// 암호문을 (테이블, 키) 에 묶어서, 다른 자리로 옮겨 붙인 값은 복호화되지 않게 한다
+fn associated_data(table: &str, key: &[u8]) -> Vec<u8> {
+    KeyBuilder::new().str(table).bytes(key).build()
+}
+
+// 아무 KvStore 나 감싸서, 지정한 테이블의 값을 저장 직전에 암호화하고 읽은 직후에 복호화한다.
+// 키는 평문 그대로 저장되므로 범위 스캔은 그대로 동작한다. keyring 이 없으면 그냥 통과시킨다.
+#[derive(Clone)]
+pub struct Encrypted<S> {
+    inner: S,
+    keyring: Option<Arc<Keyring>>,
+    tables: Arc<Vec<String>>,
+}
+
+impl<S: KvStore> Encrypted<S> {
+    pub fn new(inner: S, keyring: Option<Keyring>, tables: &[&str]) -> Self {
+        Self {
+            inner,
+            keyring: keyring.map(Arc::new),
+            tables: Arc::new(tables.iter().map(|table| table.to_string()).collect()),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn keyring_for(&self, table: &str) -> Option<&Keyring> {
+        match &self.keyring {
+            Some(keyring) if self.tables.iter().any(|t| t == table) => Some(keyring),
+            _ => None,
+        }
+    }
+
+    fn seal(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<Vec<u8>> {
+        match self.keyring_for(table) {
+            Some(keyring) => keyring.encrypt(&associated_data(table, key), value),
+            None => Ok(value.to_vec()),
+        }
+    }
+
+    fn unseal(&self, table: &str, key: &[u8], value: Vec<u8>) -> DbResult<Vec<u8>> {
+        match self.keyring_for(table) {
+            Some(keyring) => keyring.decrypt(&associated_data(table, key), &value),
+            None => Ok(value),
+        }
+    }
+
+    // 현재 키가 아닌 키로 암호화된 값(과 평문)을 현재 키로 다시 암호화한다.
+    // 이전 키를 OLD_KEYS 에서 빼기 전에 한 번 돌려야 한다. 다시 쓴 항목 수를 돌려준다.
+    pub fn rotate(&self) -> DbResult<usize> {
+        let Some(keyring) = &self.keyring else {
+            return Ok(0);
+        };
+
+        let mut ops = Vec::new();
+        for table in self.tables.iter() {
+            for (key, value) in self.inner.scan(table, &KeyRange::all())? {
+                if key_id(&value) == Some(keyring.current_id()) {
+                    continue;
+                }
+                let plaintext = keyring.decrypt(&associated_data(table, &key), &value)?;
+                ops.push(KvOp::put(table, &key, keyring.encrypt(&associated_data(table, &key), &plaintext)?));
+            }
+        }
+
+        self.inner.txn(&ops)?;
+        Ok(ops.len())
+    }
+}
+
+impl<S: DatabaseBackend> DatabaseBackend for Encrypted<S> {
+    // 이전 키가 설정되어 있으면 여는 김에 현재 키로 재암호화한다
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        let keyring = Keyring::from_env()?;
+        let rotate = keyring.as_ref().is_some_and(Keyring::has_old_keys);
+
+        let database = Self::new(S::open(path)?, keyring, ENCRYPTED_TABLES);
+        if rotate {
+            database.rotate()?;
+        }
+        Ok(database)
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        Ok(Self::new(S::open_temporary()?, Keyring::from_env()?, ENCRYPTED_TABLES))
+    }
+}
+
+impl<S: KvStore> KvStore for Encrypted<S> {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        match self.inner.get(table, key)? {
+            Some(value) => Ok(Some(self.unseal(table, key, value)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        keys.iter()
+            .zip(self.inner.get_many(table, keys)?)
+            .map(|(key, value)| value.map(|value| self.unseal(table, key, value)).transpose())
+            .collect()
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        self.inner.put(table, key, &self.seal(table, key, value)?)
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        self.inner.delete(table, key)
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.inner
+            .scan(table, range)?
+            .into_iter()
+            .map(|(key, value)| {
+                let value = self.unseal(table, &key, value)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let ops = ops
+            .iter()
+            .map(|op| match op {
+                KvOp::Put { table, key, value } => Ok(KvOp::put(table, key, self.seal(table, key, value)?)),
+                KvOp::Delete { .. } => Ok(op.clone()),
+            })
+            .collect::<DbResult<Vec<_>>>()?;
+        self.inner.txn(&ops)
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.inner.ensure_table(table)
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        self.inner.tables()
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        self.inner.stats()
+    }
+
+    // 백업에도 암호문이 그대로 들어간다 (복원하려면 같은 키가 필요)
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.inner.export_to::<D>(path)
+    }
+
+    fn import_from<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.inner.import_from::<D>(path)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+
+    const KEY_1: [u8; KEY_LEN] = [1u8; KEY_LEN];
+    const KEY_2: [u8; KEY_LEN] = [2u8; KEY_LEN];
+
+    #[test]
+    fn test_round_trip_and_ciphertext_on_disk() -> Result<(), Box<dyn std::error::Error>> {
+        let raw = MemoryDatabase::new();
+        let db = Encrypted::new(raw.clone(), Some(Keyring::new(1, &KEY_1)), ENCRYPTED_TABLES);
+
+        db.write("alice", "{\"nickname\":\"alice\"}", "user_profiles")?;
+        db.batch_write(&[("token", "secret-session")], "sessions")?;
+        db.write("dao1", "public", "community")?;
+
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"{\"nickname\":\"alice\"}".to_vec()));
+        assert_eq!(db.read_all("sessions")?.get(b"token".as_slice()), Some(&b"secret-session".to_vec()));
+
+        let stored = raw.read("alice", "user_profiles")?.unwrap();
+        assert_eq!(key_id(&stored), Some(1));
+        assert!(!stored.windows(5).any(|w| w == b"alice"));
+        // 지정하지 않은 테이블은 평문
+        assert_eq!(raw.read("dao1", "community")?, Some(b"public".to_vec()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ciphertext_is_bound_to_its_key() -> Result<(), Box<dyn std::error::Error>> {
+        let raw = MemoryDatabase::new();
+        let db = Encrypted::new(raw.clone(), Some(Keyring::new(1, &KEY_1)), ENCRYPTED_TABLES);
+        db.write("alice", "profile-a", "user_profiles")?;
+
+        let stolen = raw.read("alice", "user_profiles")?.unwrap();
+        raw.put("user_profiles", b"mallory", &stolen)?;
+        assert!(db.read("mallory", "user_profiles").is_err());
+
+        let wrong_key = Encrypted::new(raw, Some(Keyring::new(1, &KEY_2)), ENCRYPTED_TABLES);
+        assert!(wrong_key.read("alice", "user_profiles").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_rotation() -> Result<(), Box<dyn std::error::Error>> {
+        let raw = MemoryDatabase::new();
+        // 암호화를 켜기 전에 저장된 평문
+        raw.put("sessions", b"legacy", b"plain")?;
+
+        let old = Encrypted::new(raw.clone(), Some(Keyring::new(1, &KEY_1)), ENCRYPTED_TABLES);
+        old.write("alice", "profile-a", "user_profiles")?;
+        assert_eq!(old.read("legacy", "sessions")?, Some(b"plain".to_vec()));
+
+        let rotating = Encrypted::new(
+            raw.clone(),
+            Some(Keyring::new(2, &KEY_2).with_old_key(1, &KEY_1)),
+            ENCRYPTED_TABLES,
+        );
+        assert_eq!(rotating.read("alice", "user_profiles")?, Some(b"profile-a".to_vec()));
+        assert_eq!(rotating.rotate()?, 2);
+        assert_eq!(rotating.rotate()?, 0);
+
+        // 재암호화 후에는 이전 키 없이도 읽힌다
+        let rotated = Encrypted::new(raw.clone(), Some(Keyring::new(2, &KEY_2)), ENCRYPTED_TABLES);
+        assert_eq!(rotated.read("alice", "user_profiles")?, Some(b"profile-a".to_vec()));
+        assert_eq!(rotated.read("legacy", "sessions")?, Some(b"plain".to_vec()));
+        assert_eq!(key_id(&raw.read("legacy", "sessions")?.unwrap()), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let current = format!("2:{}", hex::encode(KEY_2));
+        let old = format!("1:{}, ", hex::encode(KEY_1));
+
+        let keyring = Keyring::parse(&current, Some(&old)).unwrap();
+        assert_eq!(keyring.current_id(), 2);
+        assert!(keyring.has_old_keys());
+
+        assert!(Keyring::parse("2:abcd", None).is_err());
+        assert!(Keyring::parse(&hex::encode(KEY_1), None).is_err());
+        assert!(Keyring::parse(&format!("x:{}", hex::encode(KEY_1)), None).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+// 저장소 백엔드에 상관없이 database 크레이트가 돌려주는 에러
+#[derive(Debug)]
+pub enum DatabaseError {
+    #[cfg(feature = "mdbx")]
+    Mdbx(libmdbx::Error),
+    #[cfg(feature = "sled")]
+    Sled(sled::Error),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Corrupted(String),
+    AlreadyExists(String),
+    Encryption(String),
+}
+
+pub type DbResult<T> = Result<T, DatabaseError>;
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "mdbx")]
+            DatabaseError::Mdbx(e) => write!(f, "mdbx error: {}", e),
+            #[cfg(feature = "sled")]
+            DatabaseError::Sled(e) => write!(f, "sled error: {}", e),
+            DatabaseError::Io(e) => write!(f, "io error: {}", e),
+            DatabaseError::Json(e) => write!(f, "json error: {}", e),
+            DatabaseError::Corrupted(msg) => write!(f, "corrupted data: {}", msg),
+            DatabaseError::AlreadyExists(msg) => write!(f, "already exists: {}", msg),
+            DatabaseError::Encryption(msg) => write!(f, "encryption error: {}", msg),
+        }
+    }
+}
+
+impl StdError for DatabaseError {}
+
+#[cfg(feature = "mdbx")]
+impl From<libmdbx::Error> for DatabaseError {
+    fn from(e: libmdbx::Error) -> Self {
+        DatabaseError::Mdbx(e)
+    }
+}
+
+#[cfg(feature = "sled")]
+impl From<sled::Error> for DatabaseError {
+    fn from(e: sled::Error) -> Self {
+        DatabaseError::Sled(e)
+    }
+}
+
+impl From<std::io::Error> for DatabaseError {
+    fn from(e: std::io::Error) -> Self {
+        DatabaseError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for DatabaseError {
+    fn from(e: serde_json::Error) -> Self {
+        DatabaseError::Json(e)
+    }
+}
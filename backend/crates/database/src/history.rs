@@ -0,0 +1,258 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::{DatabaseError, DbResult};
+use crate::keys::{KeyBuilder, KeyReader};
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::DatabaseStats;
+use crate::ttl::{decode_entry, encode_entry, now_secs};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+// 모든 버전 기록이 모이는 테이블.
+// 키는 (원본 테이블, 원본 키, 버전), 값은 8바이트 기록 시각 + 그 시점의 값이다.
+pub const HISTORY_TABLE: &str = "history";
+
+// 분쟁 조정을 위해 변경 이력을 남기는 테이블들 (프로필, 콘텐츠)
+pub const HISTORY_TABLES: &[&str] = &["user_profiles", "content"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub version: u64,
+    pub recorded_at: u64,
+    pub value: Vec<u8>,
+}
+
+// 키의 변경 이력을 조회할 수 있는 저장소
+pub trait VersionHistory {
+    // 오래된 버전부터. 마지막 항목이 현재 값이다.
+    fn get_history(&self, table: &str, key: &[u8]) -> DbResult<Vec<HistoryEntry>>;
+}
+
+fn history_prefix(table: &str, key: &[u8]) -> Vec<u8> {
+    KeyBuilder::new().str(table).bytes(key).build()
+}
+
+fn history_key(table: &str, key: &[u8], version: u64) -> Vec<u8> {
+    KeyBuilder::new().str(table).bytes(key).u64(version).build()
+}
+
+fn history_version(history_key: &[u8]) -> DbResult<u64> {
+    let mut reader = KeyReader::new(history_key);
+    reader.str()?;
+    reader.bytes()?;
+    let version = reader.u64()?;
+    reader.finish()?;
+    Ok(version)
+}
+
+// 아무 KvStore 나 감싸서, 지정한 테이블에 대한 put 마다 같은 트랜잭션 안에서 이력 레코드를 추가한다.
+// 이력은 추가만 되고 지워지지 않는다 (원본 키를 삭제해도 남는다).
+#[derive(Clone)]
+pub struct History<S> {
+    inner: S,
+    tables: Arc<Vec<String>>,
+    // 버전 번호를 읽고 쓰는 사이에 다른 쓰기가 끼어들지 않도록 복제본끼리 공유하는 락
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl<S: KvStore> History<S> {
+    pub fn new(inner: S, tables: &[&str]) -> Self {
+        Self {
+            inner,
+            tables: Arc::new(tables.iter().map(|table| table.to_string()).collect()),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn tracks(&self, table: &str) -> bool {
+        self.tables.iter().any(|tracked| tracked == table)
+    }
+
+    fn latest_version(&self, table: &str, key: &[u8]) -> DbResult<u64> {
+        match self.inner.scan(HISTORY_TABLE, &KeyRange::prefix(history_prefix(table, key)))?.last() {
+            Some((history_key, _)) => history_version(history_key),
+            None => Ok(0),
+        }
+    }
+
+    // ops 뒤에 추적 대상 put 들의 이력 레코드를 덧붙인다
+    fn with_history(&self, ops: &[KvOp]) -> DbResult<Vec<KvOp>> {
+        let recorded_at = now_secs();
+        let mut next_versions: HashMap<(&str, &[u8]), u64> = HashMap::new();
+        let mut all_ops = ops.to_vec();
+
+        for op in ops {
+            if let KvOp::Put { table, key, value } = op {
+                if !self.tracks(table) {
+                    continue;
+                }
+
+                let version = match next_versions.get(&(table.as_str(), key.as_slice())) {
+                    Some(version) => *version,
+                    None => self.latest_version(table, key)? + 1,
+                };
+                next_versions.insert((table.as_str(), key.as_slice()), version + 1);
+
+                all_ops.push(KvOp::put(
+                    HISTORY_TABLE,
+                    history_key(table, key, version),
+                    encode_entry(recorded_at, value),
+                ));
+            }
+        }
+
+        Ok(all_ops)
+    }
+}
+
+impl<S: KvStore> VersionHistory for History<S> {
+    fn get_history(&self, table: &str, key: &[u8]) -> DbResult<Vec<HistoryEntry>> {
+        self.inner
+            .scan(HISTORY_TABLE, &KeyRange::prefix(history_prefix(table, key)))?
+            .into_iter()
+            .map(|(history_key, bytes)| {
+                let (recorded_at, value) = decode_entry(&bytes)
+                    .ok_or_else(|| DatabaseError::Corrupted(format!("history entry of {}", table)))?;
+                Ok(HistoryEntry {
+                    version: history_version(&history_key)?,
+                    recorded_at,
+                    value: value.to_vec(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl<S: DatabaseBackend> DatabaseBackend for History<S> {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Ok(Self::new(S::open(path)?, HISTORY_TABLES))
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        Ok(Self::new(S::open_temporary()?, HISTORY_TABLES))
+    }
+}
+
+impl<S: KvStore> KvStore for History<S> {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        self.inner.get(table, key)
+    }
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        self.inner.get_many(table, keys)
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        self.txn(&[KvOp::put(table, key, value)])
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        self.inner.delete(table, key)
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.inner.scan(table, range)
+    }
+
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let _guard = self.write_lock.lock().expect("Failed to lock history mutex");
+        let ops = self.with_history(ops)?;
+        self.inner.txn(&ops)
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.inner.ensure_table(table)
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        self.inner.tables()
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        self.inner.stats()
+    }
+
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.inner.export_to::<D>(path)
+    }
+
+    // 복원은 이력 테이블까지 통째로 교체하는 것이므로 새 이력을 남기지 않는다
+    fn import_from<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        let _guard = self.write_lock.lock().expect("Failed to lock history mutex");
+        self.inner.import_from::<D>(path)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+
+    fn values(history: &[HistoryEntry]) -> Vec<(u64, &[u8])> {
+        history
+            .iter()
+            .map(|entry| (entry.version, entry.value.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn test_each_put_appends_a_version() -> Result<(), Box<dyn std::error::Error>> {
+        let db = History::new(MemoryDatabase::new(), HISTORY_TABLES);
+
+        db.write("alice", "v1", "user_profiles")?;
+        db.write("alice", "v2", "user_profiles")?;
+        db.write("bob", "other", "user_profiles")?;
+        db.write("alice", "v3", "user_profiles")?;
+
+        let history = db.get_history("user_profiles", b"alice")?;
+        assert_eq!(
+            values(&history),
+            vec![(1, &b"v1"[..]), (2, &b"v2"[..]), (3, &b"v3"[..])]
+        );
+        assert!(history.iter().all(|entry| entry.recorded_at > 0));
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"v3".to_vec()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_and_untracked_tables() -> Result<(), Box<dyn std::error::Error>> {
+        let db = History::new(MemoryDatabase::new(), HISTORY_TABLES);
+
+        // 같은 배치 안에서 같은 키를 두 번 써도 버전이 겹치지 않는다
+        db.batch_write(&[("dao1_1", "draft"), ("dao1_1", "final")], "content")?;
+        db.write("dao1", "community", "community")?;
+
+        assert_eq!(
+            values(&db.get_history("content", b"dao1_1")?),
+            vec![(1, &b"draft"[..]), (2, &b"final"[..])]
+        );
+        assert!(db.get_history("community", b"dao1")?.is_empty());
+
+        // 삭제해도 이력은 남는다
+        assert!(db.remove("dao1_1", "content")?);
+        assert_eq!(db.get_history("content", b"dao1_1")?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_with_shared_prefix_do_not_mix() -> Result<(), Box<dyn std::error::Error>> {
+        let db = History::new(MemoryDatabase::new(), HISTORY_TABLES);
+
+        db.write("ab", "short", "user_profiles")?;
+        db.write("abc", "long", "user_profiles")?;
+
+        assert_eq!(values(&db.get_history("user_profiles", b"ab")?), vec![(1, &b"short"[..])]);
+        assert_eq!(values(&db.get_history("user_profiles", b"abc")?), vec![(1, &b"long"[..])]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use crate::error::{DatabaseError, DbResult};
+
+// DAO 하위 레코드(content, depositor, proposal)의 키 포맷
+//
+// 카운터를 0으로 채워서 고정 폭으로 만들어야 바이트 순서 == 숫자 순서가 된다.
+// (예전 포맷 `pda_10` 은 `pda_2` 보다 앞에 정렬되는 문제가 있었음)
+pub const SEQUENCE_WIDTH: usize = 20;
+
+pub fn sequence_key(pda: &str, seq: u64) -> String {
+    format!("{}_{:0width$}", pda, seq, width = SEQUENCE_WIDTH)
+}
+
+pub fn sequence_prefix(pda: &str) -> String {
+    format!("{}_", pda)
+}
+
+
+// 여러 필드로 이루어진 바이너리 키 (예: (dao_pubkey, content_id), (author, timestamp))
+//
+// 인코딩 규칙:
+// - 정수: 고정 폭 big-endian. 부호 있는 정수는 부호 비트를 뒤집어서 음수가 앞에 오게 한다.
+// - 문자열/바이트열: 4바이트 big-endian 길이 + 내용. 같은 필드끼리는 (길이, 내용) 순서로 정렬된다.
+// - pubkey: 32바이트 그대로
+//
+// 앞쪽 필드들이 같으면 뒤 필드 순서로 정렬되므로, 앞 필드들만 인코딩한 값을
+// KeyRange::prefix 에 넘기면 그 그룹만 정렬된 순서로 스캔할 수 있다.
+pub const PUBKEY_LEN: usize = 32;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyBuilder {
+    bytes: Vec<u8>,
+}
+
+impl KeyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn u8(mut self, value: u8) -> Self {
+        self.bytes.push(value);
+        self
+    }
+
+    pub fn u32(mut self, value: u32) -> Self {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn u64(mut self, value: u64) -> Self {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn i64(mut self, value: i64) -> Self {
+        self.bytes.extend_from_slice(&((value as u64) ^ (1 << 63)).to_be_bytes());
+        self
+    }
+
+    pub fn str(self, value: &str) -> Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub fn bytes(mut self, value: &[u8]) -> Self {
+        let len = u32::try_from(value.len()).expect("key field longer than u32::MAX");
+        self.bytes.extend_from_slice(&len.to_be_bytes());
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    pub fn pubkey(mut self, value: &[u8; PUBKEY_LEN]) -> Self {
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+// KeyBuilder 로 만든 키를 같은 순서로 다시 읽는다
+pub struct KeyReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> KeyReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> DbResult<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(DatabaseError::Corrupted(format!(
+                "key truncated: need {} bytes, {} left",
+                len,
+                self.bytes.len()
+            )));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> DbResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    pub fn u8(&mut self) -> DbResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> DbResult<u32> {
+        Ok(u32::from_be_bytes(self.take_array()?))
+    }
+
+    pub fn u64(&mut self) -> DbResult<u64> {
+        Ok(u64::from_be_bytes(self.take_array()?))
+    }
+
+    pub fn i64(&mut self) -> DbResult<i64> {
+        Ok((u64::from_be_bytes(self.take_array()?) ^ (1 << 63)) as i64)
+    }
+
+    pub fn str(&mut self) -> DbResult<&'a str> {
+        std::str::from_utf8(self.bytes()?)
+            .map_err(|e| DatabaseError::Corrupted(format!("key string is not utf-8: {}", e)))
+    }
+
+    pub fn bytes(&mut self) -> DbResult<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub fn pubkey(&mut self) -> DbResult<[u8; PUBKEY_LEN]> {
+        self.take_array()
+    }
+
+    // 모든 필드를 읽은 뒤 남은 바이트가 없는지 확인
+    pub fn finish(self) -> DbResult<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DatabaseError::Corrupted(format!(
+                "{} trailing bytes in key",
+                self.bytes.len()
+            )))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::kv::{KeyRange, KvStore};
+    use crate::memory_db::MemoryDatabase;
+
+    #[test]
+    fn test_sequence_key_orders_numerically() {
+        assert!(sequence_key("dao", 2) < sequence_key("dao", 10));
+        assert!(sequence_key("dao", 10).starts_with(&sequence_prefix("dao")));
+    }
+
+    #[test]
+    fn test_round_trip_all_field_types() -> Result<(), Box<dyn std::error::Error>> {
+        let pubkey = [7u8; PUBKEY_LEN];
+        for (a, b, c, d, s) in [
+            (0u8, 0u32, 0u64, i64::MIN, ""),
+            (u8::MAX, u32::MAX, u64::MAX, i64::MAX, "turtle"),
+            (1, 42, 1 << 40, -1, "한글 키"),
+            (2, 7, 0, 0, "a\0b"),
+        ] {
+            let key = KeyBuilder::new()
+                .u8(a)
+                .u32(b)
+                .u64(c)
+                .i64(d)
+                .str(s)
+                .bytes(&[0xff, 0x00])
+                .pubkey(&pubkey)
+                .build();
+
+            let mut reader = KeyReader::new(&key);
+            assert_eq!(reader.u8()?, a);
+            assert_eq!(reader.u32()?, b);
+            assert_eq!(reader.u64()?, c);
+            assert_eq!(reader.i64()?, d);
+            assert_eq!(reader.str()?, s);
+            assert_eq!(reader.bytes()?, &[0xff, 0x00]);
+            assert_eq!(reader.pubkey()?, pubkey);
+            reader.finish()?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_encoding_preserves_order() {
+        let unsigned = [0u64, 1, 255, 256, 1 << 32, u64::MAX - 1, u64::MAX];
+        for pair in unsigned.windows(2) {
+            assert!(KeyBuilder::new().u64(pair[0]).build() < KeyBuilder::new().u64(pair[1]).build());
+        }
+
+        let signed = [i64::MIN, -256, -1, 0, 1, 256, i64::MAX];
+        for pair in signed.windows(2) {
+            assert!(KeyBuilder::new().i64(pair[0]).build() < KeyBuilder::new().i64(pair[1]).build());
+        }
+    }
+
+    #[test]
+    fn test_composite_order_follows_fields() {
+        let dao_a = [1u8; PUBKEY_LEN];
+        let dao_b = [2u8; PUBKEY_LEN];
+
+        let mut keys = [
+            KeyBuilder::new().pubkey(&dao_b).u64(1).build(),
+            KeyBuilder::new().pubkey(&dao_a).u64(10).build(),
+            KeyBuilder::new().pubkey(&dao_a).u64(2).build(),
+            KeyBuilder::new().pubkey(&dao_b).u64(0).build(),
+        ];
+        keys.sort();
+
+        let decoded: Vec<([u8; PUBKEY_LEN], u64)> = keys
+            .iter()
+            .map(|key| {
+                let mut reader = KeyReader::new(key);
+                (reader.pubkey().unwrap(), reader.u64().unwrap())
+            })
+            .collect();
+        assert_eq!(decoded, vec![(dao_a, 2), (dao_a, 10), (dao_b, 0), (dao_b, 1)]);
+
+        // 길이가 앞에 오므로 한 문자열이 다른 문자열의 prefix 여도 그룹이 섞이지 않는다
+        let author = KeyBuilder::new().str("ab").build();
+        let longer = KeyBuilder::new().str("abc").u64(0).build();
+        assert!(!longer.starts_with(&author));
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_keys() {
+        let key = KeyBuilder::new().str("alice").u64(5).build();
+
+        let mut truncated = KeyReader::new(&key[..key.len() - 1]);
+        assert!(truncated.str().is_ok());
+        assert!(truncated.u64().is_err());
+
+        let mut reader = KeyReader::new(&key);
+        reader.str().unwrap();
+        assert!(reader.finish().is_err());
+
+        let bad_utf8 = KeyBuilder::new().bytes(&[0xff]).build();
+        assert!(KeyReader::new(&bad_utf8).str().is_err());
+
+        let huge_len = KeyBuilder::new().u32(u32::MAX).build();
+        assert!(KeyReader::new(&huge_len).bytes().is_err());
+    }
+
+    #[test]
+    fn test_prefix_scan_returns_sorted_group() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        for (author, timestamp) in [("bob", 30i64), ("alice", 20), ("alice", -5), ("bob", 1), ("alice", 100)] {
+            let key = KeyBuilder::new().str(author).i64(timestamp).build();
+            db.put("posts", &key, author.as_bytes())?;
+        }
+
+        let prefix = KeyBuilder::new().str("alice").build();
+        let timestamps: Vec<i64> = db
+            .scan("posts", &KeyRange::prefix(&prefix))?
+            .iter()
+            .map(|(key, _)| {
+                let mut reader = KeyReader::new(key);
+                reader.str().unwrap();
+                reader.i64().unwrap()
+            })
+            .collect();
+        assert_eq!(timestamps, vec![-5, 20, 100]);
+        assert_eq!(db.read_all("posts")?.len(), 5);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::{DatabaseError, DbResult};
+use crate::stats::{DatabaseStats, TableStats};
+use std::ops::Bound;
+use std::path::Path;
+
+// 한 트랜잭션 안에서 원자적으로 적용되는 쓰기 연산
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KvOp {
+    Put { table: String, key: Vec<u8>, value: Vec<u8> },
+    Delete { table: String, key: Vec<u8> },
+}
+
+impl KvOp {
+    pub fn put(table: &str, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Self {
+        KvOp::Put {
+            table: table.to_string(),
+            key: key.as_ref().to_vec(),
+            value: value.as_ref().to_vec(),
+        }
+    }
+
+    pub fn delete(table: &str, key: impl AsRef<[u8]>) -> Self {
+        KvOp::Delete {
+            table: table.to_string(),
+            key: key.as_ref().to_vec(),
+        }
+    }
+}
+
+// 바이트 순서 기준의 키 범위
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRange {
+    pub start: Bound<Vec<u8>>,
+    pub end: Bound<Vec<u8>>,
+}
+
+impl Default for KeyRange {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl KeyRange {
+    pub fn all() -> Self {
+        Self {
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+        }
+    }
+
+    // [start, end)
+    pub fn between(start: impl AsRef<[u8]>, end: impl AsRef<[u8]>) -> Self {
+        Self {
+            start: Bound::Included(start.as_ref().to_vec()),
+            end: Bound::Excluded(end.as_ref().to_vec()),
+        }
+    }
+
+    pub fn starting_at(start: impl AsRef<[u8]>) -> Self {
+        Self {
+            start: Bound::Included(start.as_ref().to_vec()),
+            end: Bound::Unbounded,
+        }
+    }
+
+    // prefix 로 시작하는 모든 키
+    pub fn prefix(prefix: impl AsRef<[u8]>) -> Self {
+        let prefix = prefix.as_ref().to_vec();
+        let end = match prefix_successor(&prefix) {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        };
+
+        Self {
+            start: Bound::Included(prefix),
+            end,
+        }
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        let after_start = match &self.start {
+            Bound::Included(start) => key >= start.as_slice(),
+            Bound::Excluded(start) => key > start.as_slice(),
+            Bound::Unbounded => true,
+        };
+        after_start && !self.is_past_end(key)
+    }
+
+    // 정렬된 순서로 읽다가 이 키부터는 더 볼 필요가 없는지
+    pub fn is_past_end(&self, key: &[u8]) -> bool {
+        match &self.end {
+            Bound::Included(end) => key > end.as_slice(),
+            Bound::Excluded(end) => key >= end.as_slice(),
+            Bound::Unbounded => false,
+        }
+    }
+}
+
+// prefix 로 시작하는 모든 키보다 큰 가장 작은 키 (전부 0xff 면 없음)
+fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+// 저장소 백엔드가 구현해야 하는 최소한의 바이트 단위 key-value 인터페이스.
+// 테이블이 없으면 읽기는 빈 결과를, 쓰기는 테이블을 만들어서 처리한다.
+pub trait KvStore: Clone + Send + Sync + 'static {
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>>;
+
+    // 같은 테이블의 여러 키를 한 번에 읽는다 (결과는 keys 순서).
+    // 기본 구현은 키마다 get 을 부르고, 백엔드는 읽기 트랜잭션 하나로 덮어써서 구현한다.
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        keys.iter().map(|key| self.get(table, key)).collect()
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()>;
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool>;
+
+    // 키 순서대로 정렬된 결과
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>>;
+
+    // 모두 적용되거나 하나도 적용되지 않는다
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()>;
+
+    fn ensure_table(&self, table: &str) -> DbResult<()>;
+
+    fn tables(&self) -> DbResult<Vec<String>>;
+
+    fn clear_table(&self, table: &str) -> DbResult<()> {
+        let ops: Vec<KvOp> = self
+            .scan(table, &KeyRange::all())?
+            .into_iter()
+            .map(|(key, _)| KvOp::delete(table, key))
+            .collect();
+        self.txn(&ops)
+    }
+
+    // 기본 구현은 테이블마다 항목 수만 센다. 저장소 크기 같은 값은 백엔드가 덮어써서 채운다.
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        let mut tables = Vec::new();
+        for name in self.tables()? {
+            let entries = self.scan(&name, &KeyRange::all())?.len() as u64;
+            tables.push(TableStats { name, entries });
+        }
+
+        Ok(DatabaseStats {
+            backend: "kv",
+            tables,
+            ..Default::default()
+        })
+    }
+
+    // 모든 테이블을 path 의 새 D 데이터베이스로 내보낸다.
+    // 백엔드가 스냅샷을 지원하면 일관된 시점의 사본이 되도록 덮어써서 구현한다.
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        let target = create_empty::<D>(path)?;
+        copy_tables(self, &target)
+    }
+
+    // path 의 D 데이터베이스 내용으로 현재 내용을 교체한다
+    fn import_from<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        let source = D::open(path)?;
+
+        let mut ops = Vec::new();
+        for table in self.tables()? {
+            for (key, _) in self.scan(&table, &KeyRange::all())? {
+                ops.push(KvOp::delete(&table, key));
+            }
+        }
+        for table in source.tables()? {
+            self.ensure_table(&table)?;
+            for (key, value) in source.scan(&table, &KeyRange::all())? {
+                ops.push(KvOp::put(&table, key, value));
+            }
+        }
+
+        self.txn(&ops)
+    }
+}
+
+// 백업 대상으로 쓸 새 데이터베이스 (이미 내용이 있으면 거부)
+pub fn create_empty<D: DatabaseBackend>(path: &Path) -> DbResult<D> {
+    let database = D::open(path)?;
+    if !database.tables()?.is_empty() {
+        return Err(DatabaseError::AlreadyExists(path.display().to_string()));
+    }
+    Ok(database)
+}
+
+pub fn copy_tables<S: KvStore, D: KvStore>(source: &S, target: &D) -> DbResult<()> {
+    let mut ops = Vec::new();
+    for table in source.tables()? {
+        target.ensure_table(&table)?;
+        for (key, value) in source.scan(&table, &KeyRange::all())? {
+            ops.push(KvOp::put(&table, key, value));
+        }
+    }
+    target.txn(&ops)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_range() {
+        let range = KeyRange::prefix(b"dao1_");
+        assert!(range.contains(b"dao1_"));
+        assert!(range.contains(b"dao1_0001"));
+        assert!(!range.contains(b"dao1"));
+        assert!(!range.contains(b"dao1`"));
+        assert!(!range.contains(b"dao2_0001"));
+
+        assert_eq!(KeyRange::prefix([0xff, 0xff]).end, Bound::Unbounded);
+        assert_eq!(KeyRange::prefix([0x01, 0xff]).end, Bound::Excluded(vec![0x02]));
+    }
+
+    #[test]
+    fn test_between_is_half_open() {
+        let range = KeyRange::between(b"b", b"d");
+        assert!(!range.contains(b"a"));
+        assert!(range.contains(b"b"));
+        assert!(range.contains(b"c"));
+        assert!(!range.contains(b"d"));
+        assert!(range.is_past_end(b"d"));
+    }
+}
@@ -1 +1,20 @@
 pub mod basic_db;
+pub mod cache;
+pub mod dump;
+pub mod encryption;
+pub mod error;
+pub mod history;
+pub mod kv;
+pub mod memory_db;
+pub mod keys;
+pub mod migrations;
+pub mod stats;
+pub mod tenant;
+pub mod trace;
+pub mod ttl;
+pub mod typed;
+pub mod watch;
+#[cfg(feature = "mdbx")]
+pub mod mdbx_db;
+#[cfg(feature = "sled")]
+pub mod sled_db;
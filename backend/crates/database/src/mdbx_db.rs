@@ -0,0 +1,484 @@
+use libmdbx::{Database, DatabaseOptions, Geometry, WriteMap, WriteFlags, TableFlags, Transaction, TransactionKind, RW};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use crate::basic_db::DatabaseBackend;
+use crate::error::{DatabaseError, DbResult};
+use crate::kv::{create_empty, KeyRange, KvOp, KvStore};
+use crate::stats::{DatabaseStats, TableStats};
+use crate::ttl::now_secs;
+
+// mdbx map 크기 설정 (바이트 단위).
+// map 은 growth_step 씩 max_size 까지 자동으로 커지고, max_size 에 닿아서 MapFull 이 나면
+// max_size 를 두 배로 늘린 geometry 로 다시 열고 트랜잭션을 재시도한다.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapGeometry {
+    pub min_size: usize,
+    pub max_size: usize,
+    pub growth_step: usize,
+}
+
+impl Default for MapGeometry {
+    fn default() -> Self {
+        Self {
+            min_size: 1 << 20,
+            max_size: 1 << 30,
+            growth_step: 16 << 20,
+        }
+    }
+}
+
+impl MapGeometry {
+    pub fn grown(&self) -> Self {
+        Self {
+            max_size: self.max_size.saturating_mul(2),
+            ..self.clone()
+        }
+    }
+}
+
+// MapFull 로 map 을 키우고 재시도하는 최대 횟수 (기본 설정이면 1GB -> 16GB)
+pub const MAX_MAP_GROWTH_RETRIES: usize = 4;
+
+#[derive(Clone)]
+pub struct InnerDatabase {
+    env: Arc<Mutex<MdbxEnv>>,
+    // open_temporary 로 연 경우, 핸들이 모두 사라질 때 디렉토리도 지워지도록 함께 들고 있는다
+    _temp_dir: Option<Arc<TempDir>>,
+    // 이 프로세스에서 마지막으로 쓰기 트랜잭션을 커밋한 시각 (unix 초, 0이면 아직 없음)
+    last_commit: Arc<AtomicU64>,
+}
+
+// 열려 있는 mdbx 환경.
+// 같은 환경을 한 프로세스에서 두 번 열 수 없어서, map 을 키울 때는 닫았다가 다시 열어야 하므로 Option 으로 들고 있다.
+struct MdbxEnv {
+    db: Option<Database<WriteMap>>,
+    path: PathBuf,
+    geometry: MapGeometry,
+}
+
+impl MdbxEnv {
+    fn open(path: &Path, geometry: MapGeometry) -> DbResult<Self> {
+        Ok(Self {
+            db: Some(open_db(path, &geometry)?),
+            path: path.to_path_buf(),
+            geometry,
+        })
+    }
+
+    fn db(&self) -> &Database<WriteMap> {
+        self.db.as_ref().expect("mdbx environment is closed")
+    }
+
+    fn grow(&mut self) -> DbResult<()> {
+        let grown = self.geometry.grown();
+        self.db = None;
+
+        match open_db(&self.path, &grown) {
+            Ok(db) => {
+                self.db = Some(db);
+                self.geometry = grown;
+                Ok(())
+            }
+            Err(e) => {
+                // 키우지 못했으면 원래 크기로라도 다시 열어 둔다
+                self.db = Some(open_db(&self.path, &self.geometry)?);
+                Err(e.into())
+            }
+        }
+    }
+}
+
+
+impl InnerDatabase {
+    pub fn new<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Self::with_geometry(path, MapGeometry::default())
+    }
+
+    pub fn with_geometry<P: AsRef<Path>>(path: P, geometry: MapGeometry) -> DbResult<Self> {
+        Ok(Self {
+            env: Arc::new(Mutex::new(MdbxEnv::open(path.as_ref(), geometry)?)),
+            _temp_dir: None,
+            last_commit: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    // 현재 적용 중인 map 크기 설정 (MapFull 로 키운 경우 늘어난 값)
+    pub fn geometry(&self) -> MapGeometry {
+        self.env().geometry.clone()
+    }
+
+    fn env(&self) -> MutexGuard<'_, MdbxEnv> {
+        self.env.lock().expect("Failed to lock database mutex")
+    }
+
+    // 쓰기 트랜잭션 하나를 실행하고 커밋한다.
+    // MapFull 이면 map 을 키운 뒤 f 를 처음부터 다시 실행하므로, f 는 여러 번 불려도 같은 결과를 내야 한다.
+    fn write_txn<R>(
+        &self,
+        f: impl Fn(&Transaction<'_, RW, WriteMap>) -> DbResult<R>,
+    ) -> DbResult<R> {
+        let mut env = self.env();
+        let mut retries = 0;
+
+        loop {
+            let result = (|| {
+                let transaction = env.db().begin_rw_txn()?;
+                let value = f(&transaction)?;
+                transaction.commit()?;
+                Ok(value)
+            })();
+
+            match result {
+                Err(DatabaseError::Mdbx(libmdbx::Error::MapFull)) if retries < MAX_MAP_GROWTH_RETRIES => {
+                    retries += 1;
+                    env.grow()?;
+                }
+                Ok(value) => {
+                    self.last_commit.store(now_secs(), Ordering::Relaxed);
+                    return Ok(value);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl DatabaseBackend for InnerDatabase {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Self::new(path)
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut database = Self::new(temp_dir.path())?;
+        database._temp_dir = Some(Arc::new(temp_dir));
+        Ok(database)
+    }
+}
+
+
+impl KvStore for InnerDatabase {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        let env = self.env();
+        let transaction = env.db().begin_ro_txn()?;
+
+        if let Ok(table) = transaction.open_table(Some(table)) {
+            let result = transaction.get(&table, key)?;
+            return Ok(result);
+        }
+
+        Ok(None)
+    }
+
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        let env = self.env();
+        let transaction = env.db().begin_ro_txn()?;
+
+        match transaction.open_table(Some(table)) {
+            Ok(table) => keys.iter().map(|key| transaction.get::<Vec<u8>>(&table, key).map_err(DatabaseError::from)).collect(),
+            Err(_) => Ok(vec![None; keys.len()]),
+        }
+    }
+
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        self.write_txn(|transaction| {
+            let table = transaction.create_table(Some(table), TableFlags::default())?;
+            transaction.put(&table, key, value, WriteFlags::default())?;
+            Ok(())
+        })
+    }
+
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        self.write_txn(|transaction| {
+            let deleted = match transaction.open_table(Some(table)) {
+                Ok(table) => transaction.del(&table, key, None)?,
+                Err(_) => false,
+            };
+            Ok(deleted)
+        })
+    }
+
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        let env = self.env();
+        let transaction = env.db().begin_ro_txn()?;
+        scan_table(&transaction, table, range)
+    }
+
+
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        self.write_txn(|transaction| {
+            for op in ops {
+                match op {
+                    KvOp::Put { table, key, value } => {
+                        let table = transaction.create_table(Some(table), TableFlags::default())?;
+                        transaction.put(&table, key, value, WriteFlags::default())?;
+                    }
+                    KvOp::Delete { table, key } => {
+                        if let Ok(table) = transaction.open_table(Some(table)) {
+                            transaction.del(&table, key, None)?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.write_txn(|transaction| {
+            transaction.create_table(Some(table), TableFlags::default())?;
+            Ok(())
+        })
+    }
+
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        let env = self.env();
+        let transaction = env.db().begin_ro_txn()?;
+        read_table_names(&transaction)
+    }
+
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        let env = self.env();
+        let db = env.db();
+        let info = db.info()?;
+        let page_size = db.stat()?.page_size();
+
+        let transaction = db.begin_ro_txn()?;
+        let mut tables = Vec::new();
+        for name in read_table_names(&transaction)? {
+            let table = transaction.open_table(Some(&name))?;
+            let stat = transaction.table_stat(&table)?;
+            tables.push(TableStats {
+                name,
+                entries: stat.entries() as u64,
+            });
+        }
+
+        let last_commit = self.last_commit.load(Ordering::Relaxed);
+        Ok(DatabaseStats {
+            backend: "mdbx",
+            map_size: Some(info.map_size() as u64),
+            used_bytes: Some((info.last_pgno() as u64 + 1) * page_size as u64),
+            last_txn_id: Some(info.last_txnid() as u64),
+            last_commit_at: (last_commit > 0).then_some(last_commit),
+            tables,
+            cache: None,
+            ops: None,
+        })
+    }
+
+
+    // 하나의 읽기 트랜잭션(스냅샷) 안에서 읽어 두고 쓰므로 백업 도중의 쓰기가 섞이지 않는다
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        let (names, ops) = {
+            let env = self.env();
+            let source = env.db().begin_ro_txn()?;
+            let names = read_table_names(&source)?;
+            let mut ops = Vec::new();
+            for name in &names {
+                for (key, value) in scan_table(&source, name, &KeyRange::all())? {
+                    ops.push(KvOp::put(name, key, value));
+                }
+            }
+            (names, ops)
+        };
+
+        let target = create_empty::<D>(path)?;
+        for name in &names {
+            target.ensure_table(name)?;
+        }
+        target.txn(&ops)
+    }
+}
+
+
+fn open_db(path: &Path, geometry: &MapGeometry) -> Result<Database<WriteMap>, libmdbx::Error> {
+    let options = DatabaseOptions {
+        // 테넌트마다 테이블 묶음이 하나씩 더 생긴다 (tenant::Tenanted)
+        max_tables: Some(1024),
+        geometry: Some(Geometry {
+            size: Some(geometry.min_size..geometry.max_size),
+            growth_step: Some(geometry.growth_step as isize),
+            shrink_threshold: None,
+            page_size: None,
+        }),
+        ..Default::default()
+    };
+    Database::<WriteMap>::open_with_options(path, options)
+}
+
+fn scan_table<K: TransactionKind>(
+    transaction: &Transaction<'_, K, WriteMap>,
+    table: &str,
+    range: &KeyRange,
+) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut items = Vec::new();
+
+    let table = match transaction.open_table(Some(table)) {
+        Ok(table) => table,
+        Err(_) => return Ok(items),
+    };
+    let mut cursor = transaction.cursor(&table)?;
+
+    let iter = match &range.start {
+        std::ops::Bound::Included(start) | std::ops::Bound::Excluded(start) => cursor.iter_from::<Vec<u8>, Vec<u8>>(start),
+        std::ops::Bound::Unbounded => cursor.iter_start::<Vec<u8>, Vec<u8>>(),
+    };
+
+    for item in iter {
+        let (key, value) = item?;
+        if range.is_past_end(&key) {
+            break;
+        }
+        if range.contains(&key) {
+            items.push((key, value));
+        }
+    }
+
+    Ok(items)
+}
+
+// 이름 없는(main) 테이블의 키가 곧 이름 있는 테이블들의 이름이다
+fn read_table_names<K: TransactionKind>(
+    transaction: &Transaction<'_, K, WriteMap>,
+) -> DbResult<Vec<String>> {
+    let main = transaction.open_table(None)?;
+    let cursor = transaction.cursor(&main)?;
+
+    let mut names = Vec::new();
+    for item in cursor {
+        let (key, _) = item?;
+        if let Ok(name) = String::from_utf8(key.to_vec()) {
+            names.push(name);
+        }
+    }
+
+    Ok(names)
+}
+
+//pub fn read_db<'a, 'b>(path:&'a str, table:&'a str) -> Result<  HashMap::<Cow<'b, [u8]> , Cow<'b, [u8]>>, libmdbx::Error>{
+//    let mut map = HashMap::<Cow<[u8]> , Cow<[u8]>>::new();
+//    let db = open_db(path)?;
+//    let transaction = db.begin_ro_txn()?;
+//    let table = transaction.open_table(Some(table))?;
+//
+//    let cursor = transaction.cursor(&table)?;
+//
+//    for item in cursor {
+//        let (key_ , value_) =  item?;
+//        map.insert(key_, value_);
+//    }
+//
+//    transaction.commit()?;
+//
+//
+//    Ok(map)
+//}  ---> WARNING! : libmdbx using unsafe, so , If we set the lifetime like above,  there will be evoked dangling reference problem.
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_scan_range_is_ordered() -> Result<(), Box<dyn std::error::Error>> {
+        let db = InnerDatabase::open_temporary()?;
+        db.batch_write(&[("b", "2"), ("a", "1"), ("c", "3"), ("d", "4")], "t")?;
+
+        let keys: Vec<Vec<u8>> = db
+            .scan("t", &KeyRange::between(b"b", b"d"))?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec![b"b".to_vec(), b"c".to_vec()]);
+        assert!(db.scan("missing", &KeyRange::all())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_txn_applies_all_ops() -> Result<(), Box<dyn std::error::Error>> {
+        let db = InnerDatabase::open_temporary()?;
+        db.write("old", "x", "t")?;
+
+        db.txn(&[KvOp::put("t", b"new", b"y"), KvOp::delete("t", b"old"), KvOp::put("u", b"k", b"v")])?;
+
+        assert_eq!(db.read("new", "t")?, Some(b"y".to_vec()));
+        assert_eq!(db.read("old", "t")?, None);
+        assert_eq!(db.read("k", "u")?, Some(b"v".to_vec()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_full_grows_and_retries() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let geometry = MapGeometry {
+            min_size: 64 << 10,
+            max_size: 256 << 10,
+            growth_step: 64 << 10,
+        };
+        let db = InnerDatabase::with_geometry(temp_dir.path(), geometry.clone())?;
+
+        // 처음 map 크기보다 큰 데이터를 써도 실패하지 않아야 함
+        let value = "x".repeat(1024);
+        let items: Vec<(String, &str)> = (0..512).map(|i| (format!("key-{:04}", i), value.as_str())).collect();
+        db.batch_write(&items, "bulk")?;
+
+        assert!(db.geometry().max_size > geometry.max_size);
+        assert_eq!(db.read("key-0511", "bulk")?, Some(value.into_bytes()));
+        assert_eq!(db.read_all("bulk")?.len(), 512);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_and_restore() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let db = InnerDatabase::open_temporary()?;
+        db.write("alice", "profile-a", "user_profiles")?;
+        db.write("dao1", "community-1", "community")?;
+
+        let backup_path = temp_dir.path().join("backup");
+        db.backup_to::<InnerDatabase, _>(&backup_path)?;
+
+        // 백업 이후의 변경은 복원하면 사라져야 함
+        db.write("bob", "profile-b", "user_profiles")?;
+        db.write("later", "x", "scratch")?;
+
+        db.restore_from::<InnerDatabase, _>(&backup_path)?;
+
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"profile-a".to_vec()));
+        assert_eq!(db.read("dao1", "community")?, Some(b"community-1".to_vec()));
+        assert!(db.read("bob", "user_profiles")?.is_none());
+        assert!(db.read("later", "scratch")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_refuses_non_empty_target() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let db = InnerDatabase::open_temporary()?;
+        db.write("alice", "profile-a", "user_profiles")?;
+
+        let backup_path = temp_dir.path().join("backup");
+        db.backup_to::<InnerDatabase, _>(&backup_path)?;
+
+        assert!(db.backup_to::<InnerDatabase, _>(&backup_path).is_err());
+        Ok(())
+    }
+}
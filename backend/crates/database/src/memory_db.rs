@@ -0,0 +1,173 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::DbResult;
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::{DatabaseStats, TableStats};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+type Tables = BTreeMap<String, BTreeMap<Vec<u8>, Vec<u8>>>;
+
+// 디스크를 전혀 건드리지 않는 KvStore 구현 (테스트용)
+// 복제한 핸들끼리는 같은 데이터를 공유한다.
+#[derive(Clone, Default)]
+pub struct MemoryDatabase {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DatabaseBackend for MemoryDatabase {
+    // 경로는 무시한다. 프로세스가 끝나면 내용도 사라진다.
+    fn open<P: AsRef<Path>>(_path: P) -> DbResult<Self> {
+        Ok(Self::new())
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        Ok(Self::new())
+    }
+}
+
+impl KvStore for MemoryDatabase {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        let tables = self.tables.lock().expect("Failed to lock database mutex");
+        Ok(tables.get(table).and_then(|t| t.get(key).cloned()))
+    }
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        let tables = self.tables.lock().expect("Failed to lock database mutex");
+        let table = tables.get(table);
+        Ok(keys.iter().map(|key| table.and_then(|t| t.get(*key).cloned())).collect())
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        let mut tables = self.tables.lock().expect("Failed to lock database mutex");
+        tables
+            .entry(table.to_string())
+            .or_default()
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        let mut tables = self.tables.lock().expect("Failed to lock database mutex");
+        Ok(tables
+            .get_mut(table)
+            .map(|t| t.remove(key).is_some())
+            .unwrap_or(false))
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        let tables = self.tables.lock().expect("Failed to lock database mutex");
+        Ok(tables
+            .get(table)
+            .map(|t| {
+                t.range::<Vec<u8>, _>((range.start.clone(), range.end.clone()))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    // 락을 잡은 채로 한 번에 적용하므로 다른 핸들에서 중간 상태가 보이지 않는다
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let mut tables = self.tables.lock().expect("Failed to lock database mutex");
+        for op in ops {
+            match op {
+                KvOp::Put { table, key, value } => {
+                    tables
+                        .entry(table.clone())
+                        .or_default()
+                        .insert(key.clone(), value.clone());
+                }
+                KvOp::Delete { table, key } => {
+                    if let Some(t) = tables.get_mut(table) {
+                        t.remove(key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        let mut tables = self.tables.lock().expect("Failed to lock database mutex");
+        tables.entry(table.to_string()).or_default();
+        Ok(())
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        let tables = self.tables.lock().expect("Failed to lock database mutex");
+        Ok(tables.keys().cloned().collect())
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        let tables = self.tables.lock().expect("Failed to lock database mutex");
+        Ok(DatabaseStats {
+            backend: "memory",
+            tables: tables
+                .iter()
+                .map(|(name, table)| TableStats {
+                    name: name.clone(),
+                    entries: table.len() as u64,
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_clones_share_data() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        let other = db.clone();
+
+        db.write("k", "v", "t")?;
+        assert_eq!(other.read("k", "t")?, Some(b"v".to_vec()));
+        assert!(other.remove("k", "t")?);
+        assert_eq!(db.read("k", "t")?, None);
+
+        Ok(())
+    }
+
+    // 메모리 저장소도 어느 백엔드의 파일로든 백업하고 복원할 수 있다
+    fn backup_round_trip<D: DatabaseBackend>() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempdir()?;
+        let db = MemoryDatabase::new();
+        db.write("alice", "profile-a", "user_profiles")?;
+
+        let backup_path = temp_dir.path().join("backup");
+        db.backup_to::<D, _>(&backup_path)?;
+        db.write("bob", "profile-b", "user_profiles")?;
+
+        db.restore_from::<D, _>(&backup_path)?;
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"profile-a".to_vec()));
+        assert_eq!(db.read("bob", "user_profiles")?, None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mdbx")]
+    #[test]
+    fn test_backup_round_trip_through_mdbx() -> Result<(), Box<dyn std::error::Error>> {
+        backup_round_trip::<crate::mdbx_db::InnerDatabase>()
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_backup_round_trip_through_sled() -> Result<(), Box<dyn std::error::Error>> {
+        backup_round_trip::<crate::sled_db::SledDatabase>()
+    }
+}
@@ -0,0 +1,308 @@
+use base64::Engine;
+use serde_json::{json, Value};
+use crate::basic_db::SafeDatabase;
+use crate::error::{DatabaseError, DbResult};
+use crate::keys::sequence_key;
+use crate::ttl::now_secs;
+
+// 스키마 버전은 meta 테이블에 10진수 문자열로 저장한다
+pub const META_TABLE: &str = "meta";
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+pub const CURRENT_SCHEMA_VERSION: u64 = 3;
+
+// 서버가 사용하는 테이블 목록
+pub const KNOWN_TABLES: &[&str] = &[
+    META_TABLE,
+    "user_profiles",
+    "daopda",
+    "community",
+    "content",
+    "depositor",
+    "proposal",
+];
+
+// pda_n 형식의 키를 쓰는 테이블들
+const SEQUENCED_TABLES: &[&str] = &["content", "depositor", "proposal"];
+
+const PROFILE_TABLE: &str = "user_profiles";
+
+pub struct Migration<T> {
+    pub version: u64,
+    pub description: &'static str,
+    pub apply: fn(&T) -> DbResult<()>,
+}
+
+// 순서대로 적용되는 마이그레이션 목록 (version 은 적용 후의 스키마 버전)
+pub fn migrations<T: SafeDatabase>() -> Vec<Migration<T>> {
+    vec![
+        Migration {
+            version: 1,
+            description: "create known tables",
+            apply: create_known_tables::<T>,
+        },
+        Migration {
+            version: 2,
+            description: "zero-pad sequence keys of content/depositor/proposal",
+            apply: pad_sequence_keys::<T>,
+        },
+        Migration {
+            version: 3,
+            description: "convert user_profiles to the Profile schema",
+            apply: upgrade_profiles::<T>,
+        },
+    ]
+}
+
+pub fn schema_version<T: SafeDatabase>(database: &T) -> DbResult<u64> {
+    match database.read(SCHEMA_VERSION_KEY, META_TABLE)? {
+        Some(bytes) => String::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| DatabaseError::Corrupted(format!("{} in {}", SCHEMA_VERSION_KEY, META_TABLE))),
+        None => Ok(0),
+    }
+}
+
+fn set_schema_version<T: SafeDatabase>(database: &T, version: u64) -> DbResult<()> {
+    database.write(SCHEMA_VERSION_KEY, &version.to_string(), META_TABLE)
+}
+
+// 서버 시작 시 호출: 현재 버전 이후의 마이그레이션을 순서대로 적용하고 최종 버전을 반환
+pub fn run_migrations<T: SafeDatabase>(database: &T) -> DbResult<u64> {
+    let mut version = schema_version(database)?;
+
+    for migration in migrations::<T>() {
+        if migration.version <= version {
+            continue;
+        }
+
+        (migration.apply)(database)?;
+        set_schema_version(database, migration.version)?;
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+fn create_known_tables<T: SafeDatabase>(database: &T) -> DbResult<()> {
+    for table in KNOWN_TABLES {
+        database.create_table(table)?;
+    }
+    Ok(())
+}
+
+// v1 키 `{pda}_{n}` 을 v2 키 `{pda}_{n:020}` 으로 바꾼다.
+// 새 키를 먼저 쓰고 나서 옛 키를 지우므로 중간에 실패해도 다시 실행하면 된다.
+fn pad_sequence_keys<T: SafeDatabase>(database: &T) -> DbResult<()> {
+    for table in SEQUENCED_TABLES {
+        let entries = database.read_all(table)?;
+
+        let mut rewritten = Vec::new();
+        let mut stale_keys = Vec::new();
+
+        for (key_bytes, value) in entries {
+            let key = match String::from_utf8(key_bytes) {
+                Ok(key) => key,
+                Err(_) => continue,
+            };
+
+            let (pda, seq) = match key.rsplit_once('_') {
+                Some((pda, seq)) => (pda, seq),
+                None => continue,
+            };
+
+            let seq = match seq.parse::<u64>() {
+                Ok(seq) => seq,
+                Err(_) => continue,
+            };
+
+            let new_key = sequence_key(pda, seq);
+            if new_key != key {
+                rewritten.push((new_key, value));
+                stale_keys.push(key);
+            }
+        }
+
+        database.batch_write(&rewritten, table)?;
+        database.batch_remove(&stale_keys, table)?;
+    }
+
+    Ok(())
+}
+
+// v2 UserProfile JSON 을 v3 Profile JSON 으로 바꾼다. user_address 가 없는 값은 이미 바뀐 것이라 건너뛴다.
+// 옛 아바타 바이트는 저장소를 거치지 않고 data: URI 로 옮긴다 (사용자가 다시 올리면 저장소 URI 로 바뀐다).
+fn upgrade_profiles<T: SafeDatabase>(database: &T) -> DbResult<()> {
+    let now = now_secs();
+    let mut rewritten = Vec::new();
+
+    for (key_bytes, value) in database.read_all(PROFILE_TABLE)? {
+        let key = match String::from_utf8(key_bytes) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+
+        let legacy: Value = match serde_json::from_slice(&value) {
+            Ok(legacy) => legacy,
+            Err(_) => continue,
+        };
+        if legacy.get("user_address").is_none() {
+            continue;
+        }
+
+        let profile = upgrade_profile(&key, &legacy, now);
+        rewritten.push((key, profile.to_string()));
+    }
+
+    database.batch_write(&rewritten, PROFILE_TABLE)
+}
+
+fn upgrade_profile(key: &str, legacy: &Value, now: u64) -> Value {
+    let text = |field: &str| legacy[field].as_str().unwrap_or("").trim().to_string();
+    let handle = |field: &str| {
+        let handle = text(field).trim_start_matches('@').to_string();
+        (!handle.is_empty()).then_some(handle)
+    };
+
+    let wallet = match text("user_address") {
+        address if address.is_empty() => key.to_string(),
+        address => address,
+    };
+
+    let avatar: Option<Vec<u8>> = legacy["user_avatar"]
+        .as_array()
+        .map(|bytes| bytes.iter().filter_map(|byte| byte.as_u64().map(|byte| byte as u8)).collect());
+    let avatar_uri = avatar.filter(|bytes| !bytes.is_empty()).map(|bytes| {
+        let content_type = legacy["avatar_content_type"].as_str().unwrap_or("image/jpeg");
+        format!("data:{};base64,{}", content_type, base64::engine::general_purpose::STANDARD.encode(bytes))
+    });
+
+    json!({
+        "wallet": wallet,
+        "display_name": text("user_name"),
+        "bio": text("user_bio"),
+        "avatar_uri": avatar_uri,
+        "links": {
+            "github": handle("github_account"),
+            "x": handle("x_account"),
+            "telegram": handle("tg_account"),
+            "website": null,
+        },
+        "created_at": now,
+        "updated_at": now,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_db::MemoryDatabase;
+
+    // v1 시절 데이터베이스를 흉내낸 픽스처
+    fn v1_fixture(database: &MemoryDatabase) {
+        set_schema_version(database, 1).unwrap();
+
+        let contents = [
+            ("dao1_1", "{\"n\":1}"),
+            ("dao1_2", "{\"n\":2}"),
+            ("dao1_10", "{\"n\":10}"),
+        ];
+        database.batch_write(&contents, "content").unwrap();
+        database.write("dao1_1", "{\"depositor\":1}", "depositor").unwrap();
+        database.write("dao1_1", "{\"proposal\":1}", "proposal").unwrap();
+        database.write("dao1", "{\"community\":true}", "community").unwrap();
+    }
+
+    #[test]
+    fn test_fresh_database_runs_all_migrations() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+
+        assert_eq!(schema_version(&db)?, 0);
+        assert_eq!(run_migrations(&db)?, CURRENT_SCHEMA_VERSION);
+        assert_eq!(schema_version(&db)?, CURRENT_SCHEMA_VERSION);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_v1_to_v2() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        v1_fixture(&db);
+
+        assert_eq!(run_migrations(&db)?, CURRENT_SCHEMA_VERSION);
+
+        // 옛 키는 지워지고 새 키로 옮겨져야 함
+        assert!(db.read("dao1_10", "content")?.is_none());
+        assert_eq!(db.read(&sequence_key("dao1", 10), "content")?, Some(b"{\"n\":10}".to_vec()));
+        assert_eq!(db.read(&sequence_key("dao1", 1), "depositor")?, Some(b"{\"depositor\":1}".to_vec()));
+        assert_eq!(db.read(&sequence_key("dao1", 1), "proposal")?, Some(b"{\"proposal\":1}".to_vec()));
+
+        // community 테이블은 손대지 않음
+        assert!(db.read("dao1", "community")?.is_some());
+
+        // 정렬 순서가 숫자 순서와 같아야 함
+        let mut keys: Vec<Vec<u8>> = db.read_all("content")?.into_keys().collect();
+        keys.sort();
+        let expected: Vec<Vec<u8>> = [1, 2, 10]
+            .iter()
+            .map(|n| sequence_key("dao1", *n).into_bytes())
+            .collect();
+        assert_eq!(keys, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrations_are_idempotent() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        v1_fixture(&db);
+
+        run_migrations(&db)?;
+        let first = db.read_all("content")?;
+        run_migrations(&db)?;
+        let second = db.read_all("content")?;
+
+        assert_eq!(first, second);
+        assert_eq!(schema_version(&db)?, CURRENT_SCHEMA_VERSION);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_v2_profiles() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        set_schema_version(&db, 2)?;
+
+        let legacy = json!({
+            "user_id": "u1",
+            "user_name": "Alice",
+            "user_address": "",
+            "github_account": "alice",
+            "x_account": "@alice",
+            "tg_account": "",
+            "user_bio": "hi",
+            "user_avatar": [1, 2, 3],
+            "avatar_content_type": "image/png",
+        });
+        db.write("alice", &legacy.to_string(), PROFILE_TABLE)?;
+
+        assert_eq!(run_migrations(&db)?, 3);
+
+        let profile: Value = serde_json::from_slice(&db.read("alice", PROFILE_TABLE)?.unwrap())?;
+        assert_eq!(profile["wallet"], "alice");
+        assert_eq!(profile["display_name"], "Alice");
+        assert_eq!(profile["bio"], "hi");
+        assert_eq!(profile["avatar_uri"], "data:image/png;base64,AQID");
+        assert_eq!(profile["links"], json!({ "github": "alice", "x": "alice", "telegram": null, "website": null }));
+        assert_eq!(profile["created_at"], profile["updated_at"]);
+
+        // 이미 바뀐 프로필은 다시 돌려도 그대로
+        let before = db.read_all(PROFILE_TABLE)?;
+        upgrade_profiles(&db)?;
+        assert_eq!(db.read_all(PROFILE_TABLE)?, before);
+
+        Ok(())
+    }
+}
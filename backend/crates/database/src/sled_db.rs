@@ -0,0 +1,190 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::{DatabaseError, DbResult};
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::{DatabaseStats, TableStats};
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use sled::Transactional;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+// sled 가 내부적으로 만드는 기본 트리. 사용자 테이블 목록에서는 숨긴다.
+const DEFAULT_TREE: &[u8] = b"__sled__default";
+
+// sled 기반 KvStore 구현 (cargo feature "sled").
+// 테이블 하나가 sled 트리 하나에 대응한다.
+#[derive(Clone)]
+pub struct SledDatabase {
+    db: sled::Db,
+    _temp_dir: Option<Arc<TempDir>>,
+}
+
+impl SledDatabase {
+    pub fn new<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Ok(Self {
+            db: sled::open(path)?,
+            _temp_dir: None,
+        })
+    }
+}
+
+impl DatabaseBackend for SledDatabase {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Self::new(path)
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        let temp_dir = tempfile::tempdir()?;
+        let db = sled::open(temp_dir.path())?;
+
+        Ok(Self {
+            db,
+            _temp_dir: Some(Arc::new(temp_dir)),
+        })
+    }
+}
+
+impl KvStore for SledDatabase {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        // 읽기만으로 빈 트리가 생기지 않도록 존재 여부를 먼저 확인
+        if !self.has_table(table) {
+            return Ok(None);
+        }
+        let tree = self.db.open_tree(table)?;
+        Ok(tree.get(key)?.map(|value| value.to_vec()))
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        let tree = self.db.open_tree(table)?;
+        tree.insert(key, value)?;
+        tree.flush()?;
+        Ok(())
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        if !self.has_table(table) {
+            return Ok(false);
+        }
+        let tree = self.db.open_tree(table)?;
+        let deleted = tree.remove(key)?.is_some();
+        tree.flush()?;
+        Ok(deleted)
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        if !self.has_table(table) {
+            return Ok(Vec::new());
+        }
+        let tree = self.db.open_tree(table)?;
+
+        let mut items = Vec::new();
+        for item in tree.range::<Vec<u8>, _>((range.start.clone(), range.end.clone())) {
+            let (key, value) = item?;
+            items.push((key.to_vec(), value.to_vec()));
+        }
+        Ok(items)
+    }
+
+    // 연산에 등장하는 모든 트리를 묶어서 하나의 sled 트랜잭션으로 적용한다
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let mut names: Vec<&str> = ops
+            .iter()
+            .map(|op| match op {
+                KvOp::Put { table, .. } | KvOp::Delete { table, .. } => table.as_str(),
+            })
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+
+        let trees = names
+            .iter()
+            .map(|name| self.db.open_tree(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        trees
+            .as_slice()
+            .transaction(|views| {
+                for op in ops {
+                    match op {
+                        KvOp::Put { table, key, value } => {
+                            let index = names.binary_search(&table.as_str()).expect("table collected above");
+                            views[index].insert(key.as_slice(), value.as_slice())?;
+                        }
+                        KvOp::Delete { table, key } => {
+                            let index = names.binary_search(&table.as_str()).expect("table collected above");
+                            views[index].remove(key.as_slice())?;
+                        }
+                    }
+                }
+                Ok::<(), ConflictableTransactionError<()>>(())
+            })
+            .map_err(|e| match e {
+                TransactionError::Storage(e) => DatabaseError::Sled(e),
+                TransactionError::Abort(()) => DatabaseError::Corrupted("sled transaction aborted".to_string()),
+            })?;
+
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.db.open_tree(table)?;
+        Ok(())
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        Ok(self
+            .db
+            .tree_names()
+            .into_iter()
+            .filter(|name| name.as_ref() != DEFAULT_TREE)
+            .filter_map(|name| String::from_utf8(name.to_vec()).ok())
+            .collect())
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        let mut tables = Vec::new();
+        for name in self.tables()? {
+            let entries = self.db.open_tree(&name)?.len() as u64;
+            tables.push(TableStats { name, entries });
+        }
+
+        Ok(DatabaseStats {
+            backend: "sled",
+            used_bytes: Some(self.db.size_on_disk()?),
+            tables,
+            ..Default::default()
+        })
+    }
+}
+
+impl SledDatabase {
+    fn has_table(&self, table: &str) -> bool {
+        self.db
+            .tree_names()
+            .iter()
+            .any(|name| name.as_ref() == table.as_bytes())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+
+    #[test]
+    fn test_sled_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let db = SledDatabase::open_temporary()?;
+        assert_eq!(db.read("k", "t")?, None);
+
+        db.batch_write(&[("a", "1"), ("b", "2")], "t")?;
+        assert_eq!(db.read("a", "t")?, Some(b"1".to_vec()));
+        assert!(db.remove("a", "t")?);
+        assert_eq!(db.read_all("t")?.len(), 1);
+        assert_eq!(db.table_names()?, vec!["t".to_string()]);
+
+        Ok(())
+    }
+}
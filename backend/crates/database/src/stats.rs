@@ -0,0 +1,109 @@
+// 운영자가 저장소 상태를 모니터링하기 위한 통계.
+// 백엔드가 알려줄 수 없는 값은 None 으로 둔다 (예: 메모리 백엔드의 map 크기).
+
+use crate::cache::CacheStats;
+use crate::trace::OpStats;
+
+// 사용량이 map 크기의 이 비율을 넘으면 곧 MapFull 이 날 수 있다고 본다
+pub const NEAR_FULL_RATIO: f64 = 0.9;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableStats {
+    pub name: String,
+    pub entries: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DatabaseStats {
+    pub backend: &'static str,
+    pub map_size: Option<u64>,
+    pub used_bytes: Option<u64>,
+    pub last_txn_id: Option<u64>,
+    pub last_commit_at: Option<u64>,
+    pub tables: Vec<TableStats>,
+    // 읽기 캐시를 거치는 경우에만 채워진다
+    pub cache: Option<CacheStats>,
+    // Traced 를 거치는 경우에만 채워진다 (프로세스가 뜬 뒤로 저장소까지 내려간 호출 수)
+    pub ops: Option<OpStats>,
+}
+
+impl DatabaseStats {
+    pub fn total_entries(&self) -> u64 {
+        self.tables.iter().map(|table| table.entries).sum()
+    }
+
+    pub fn usage_ratio(&self) -> Option<f64> {
+        match (self.used_bytes, self.map_size) {
+            (Some(used), Some(map_size)) if map_size > 0 => Some(used as f64 / map_size as f64),
+            _ => None,
+        }
+    }
+
+    pub fn near_full(&self) -> bool {
+        self.usage_ratio().is_some_and(|ratio| ratio >= NEAR_FULL_RATIO)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::{DatabaseBackend, SafeDatabase};
+    use crate::memory_db::MemoryDatabase;
+    #[cfg(feature = "mdbx")]
+    use crate::mdbx_db::InnerDatabase;
+
+    fn filled_stats<S: DatabaseBackend>() -> Result<DatabaseStats, Box<dyn std::error::Error>> {
+        let db = S::open_temporary()?;
+        db.batch_write(&[("a", "1"), ("b", "2")], "content")?;
+        db.write("alice", "p", "user_profiles")?;
+        Ok(db.stats()?)
+    }
+
+    #[test]
+    fn test_entries_per_table() -> Result<(), Box<dyn std::error::Error>> {
+        let mut all_stats = vec![filled_stats::<MemoryDatabase>()?];
+        #[cfg(feature = "mdbx")]
+        all_stats.push(filled_stats::<InnerDatabase>()?);
+        #[cfg(feature = "sled")]
+        all_stats.push(filled_stats::<crate::sled_db::SledDatabase>()?);
+        for stats in all_stats {
+            let content = stats.tables.iter().find(|t| t.name == "content").unwrap();
+            assert_eq!(content.entries, 2);
+            assert_eq!(stats.total_entries(), 3);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mdbx")]
+    #[test]
+    fn test_mdbx_reports_map_and_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let db = InnerDatabase::open_temporary()?;
+        assert_eq!(db.stats()?.last_commit_at, None);
+
+        db.write("k", "v", "t")?;
+        let stats = db.stats()?;
+        assert!(stats.map_size.is_some());
+        assert!(stats.usage_ratio().is_some());
+        assert!(stats.last_commit_at.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_near_full() {
+        let mut stats = DatabaseStats {
+            map_size: Some(100),
+            used_bytes: Some(50),
+            ..Default::default()
+        };
+        assert!(!stats.near_full());
+
+        stats.used_bytes = Some(95);
+        assert!(stats.near_full());
+
+        stats.map_size = None;
+        assert!(!stats.near_full());
+    }
+}
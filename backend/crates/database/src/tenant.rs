@@ -0,0 +1,188 @@
+use crate::error::DbResult;
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::watch::{ChangeEvent, ChangeFeed, TableVersions, CHANGE_CHANNEL_CAPACITY};
+use std::borrow::Cow;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+// 테넌트가 나눠 쓰는 테이블. 지갑 세션/역할/프로필/.sol 이름은 클러스터와 상관없이 하나이고,
+// TTL 테이블은 기본 서버의 정리 작업이 한 번에 지운다.
+pub const SHARED_TABLES: &[&str] = &[
+    "sessions", "nonces", "rate_limits", "revoked_tokens", "relay_quotas", "faucet_claims",
+    "roles", "user_profiles", "blocked_media", "sns_names", "sns_pending",
+];
+
+// 아무 KvStore 나 감싸서 테이블 이름 앞에 테넌트 prefix 를 붙인다 (SHARED_TABLES 는 그대로).
+// 한 저장소에 devnet/mainnet 같은 여러 배포의 데이터를 섞이지 않게 담을 때 쓴다.
+#[derive(Clone)]
+pub struct Tenanted<S> {
+    inner: S,
+    prefix: Arc<str>,
+}
+
+impl<S: KvStore> Tenanted<S> {
+    pub fn new(inner: S, prefix: impl Into<String>) -> Self {
+        Self { inner, prefix: Arc::from(prefix.into()) }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn table<'a>(&self, table: &'a str) -> Cow<'a, str> {
+        tenant_table(&self.prefix, table)
+    }
+
+    fn op(&self, op: &KvOp) -> KvOp {
+        match op {
+            KvOp::Put { table, key, value } => KvOp::put(&self.table(table), key, value),
+            KvOp::Delete { table, key } => KvOp::delete(&self.table(table), key),
+        }
+    }
+}
+
+fn tenant_table<'a>(prefix: &str, table: &'a str) -> Cow<'a, str> {
+    if SHARED_TABLES.contains(&table) {
+        Cow::Borrowed(table)
+    } else {
+        Cow::Owned(format!("{}{}", prefix, table))
+    }
+}
+
+// 감싼 저장소의 테이블 이름을 이 테넌트에서 보이는 이름으로. 다른 테넌트나 기본 테이블이면 None.
+fn visible_table(prefix: &str, table: &str) -> Option<String> {
+    match table.strip_prefix(prefix) {
+        Some(table) if !SHARED_TABLES.contains(&table) => Some(table.to_string()),
+        Some(_) => None,
+        None if SHARED_TABLES.contains(&table) => Some(table.to_string()),
+        None => None,
+    }
+}
+
+impl<S: KvStore> KvStore for Tenanted<S> {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        self.inner.get(&self.table(table), key)
+    }
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        self.inner.get_many(&self.table(table), keys)
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        self.inner.put(&self.table(table), key, value)
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        self.inner.delete(&self.table(table), key)
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.inner.scan(&self.table(table), range)
+    }
+
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let ops: Vec<KvOp> = ops.iter().map(|op| self.op(op)).collect();
+        self.inner.txn(&ops)
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.inner.ensure_table(&self.table(table))
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        Ok(self.inner.tables()?
+            .iter()
+            .filter_map(|table| visible_table(&self.prefix, table))
+            .collect())
+    }
+}
+
+// 감싼 저장소의 알림 중 이 테넌트 것만 테이블 이름을 되돌려서 넘긴다.
+// 구독마다 전달 태스크가 하나 돌고, 받는 쪽이 없어지면 다음 알림에서 끝난다.
+impl<S: ChangeFeed> ChangeFeed for Tenanted<S> {
+    fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        let (sender, receiver) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        let mut upstream = self.inner.subscribe();
+        let prefix = self.prefix.clone();
+        tokio::spawn(async move {
+            loop {
+                match upstream.recv().await {
+                    Ok(event) => {
+                        let Some(table) = visible_table(&prefix, &event.table) else {
+                            continue;
+                        };
+                        if sender.send(ChangeEvent { table, ..event }).is_err() {
+                            break;
+                        }
+                    },
+                    // 놓친 알림은 되살릴 수 없다. 다른 테넌트 것까지 세므로 받는 쪽에 Lagged 로 알리지는 않는다.
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        receiver
+    }
+}
+
+impl<S: TableVersions> TableVersions for Tenanted<S> {
+    fn table_version(&self, table: &str) -> u64 {
+        self.inner.table_version(&tenant_table(&self.prefix, table))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+    use crate::watch::{ChangeKind, Watched};
+
+    #[test]
+    fn test_tables_are_namespaced_per_tenant() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        let devnet = Tenanted::new(db.clone(), "devnet.prog.");
+        let mainnet = Tenanted::new(db.clone(), "mainnet.prog.");
+
+        devnet.write("dao", "devnet", "dao_state")?;
+        mainnet.write("dao", "mainnet", "dao_state")?;
+        devnet.txn(&[KvOp::put("events_votes", "1", "v")])?;
+        // 프로필은 함께 쓴다
+        devnet.write("alice", "profile", "user_profiles")?;
+
+        assert_eq!(devnet.read("dao", "dao_state")?, Some(b"devnet".to_vec()));
+        assert_eq!(mainnet.read("dao", "dao_state")?, Some(b"mainnet".to_vec()));
+        assert_eq!(db.read("dao", "dao_state")?, None);
+        assert_eq!(db.read("dao", "devnet.prog.dao_state")?, Some(b"devnet".to_vec()));
+        assert_eq!(mainnet.read("alice", "user_profiles")?, Some(b"profile".to_vec()));
+
+        let mut tables = devnet.tables()?;
+        tables.sort();
+        assert_eq!(tables, ["dao_state", "events_votes", "user_profiles"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_feed_only_sees_own_tables() -> Result<(), Box<dyn std::error::Error>> {
+        let db = Watched::new(MemoryDatabase::new());
+        let devnet = Tenanted::new(db.clone(), "devnet.prog.");
+        let mainnet = Tenanted::new(db.clone(), "mainnet.prog.");
+        let mut receiver = devnet.subscribe();
+
+        mainnet.write("dao", "mainnet", "dao_state")?;
+        db.write("dao", "default", "dao_state")?;
+        devnet.write("dao", "devnet", "dao_state")?;
+
+        let event = receiver.recv().await?;
+        assert_eq!((event.table.as_str(), event.key.as_slice(), event.kind), ("dao_state", &b"dao"[..], ChangeKind::Put));
+        assert_eq!(devnet.table_version("dao_state"), 1);
+        assert_eq!(db.table_version("dao_state"), 1);
+        Ok(())
+    }
+}
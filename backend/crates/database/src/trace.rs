@@ -0,0 +1,182 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::DbResult;
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::DatabaseStats;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpStats {
+    pub gets: u64,
+    pub puts: u64,
+    pub deletes: u64,
+    pub scans: u64,
+    pub txns: u64,
+    pub errors: u64,
+}
+
+#[derive(Default)]
+struct OpCounters {
+    gets: AtomicU64,
+    puts: AtomicU64,
+    deletes: AtomicU64,
+    scans: AtomicU64,
+    txns: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl OpCounters {
+    fn counter(&self, op: &str) -> Option<&AtomicU64> {
+        match op {
+            "get" => Some(&self.gets),
+            "put" => Some(&self.puts),
+            "delete" => Some(&self.deletes),
+            "scan" => Some(&self.scans),
+            "txn" => Some(&self.txns),
+            _ => None,
+        }
+    }
+}
+
+// 아무 KvStore 나 감싸서 호출마다 tracing 의 "db" span 과 소요 시간을 남긴다.
+// 요청 처리 중에 부르면 요청 span 아래에 달리므로, 로그에서 요청 ID 로 DB 호출을 찾을 수 있다.
+// 성공은 debug, 실패는 warn 레벨이다. 연산별 호출 수는 stats().ops 로 볼 수 있다.
+#[derive(Clone)]
+pub struct Traced<S> {
+    inner: S,
+    counters: Arc<OpCounters>,
+}
+
+impl<S: KvStore> Traced<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner, counters: Arc::new(OpCounters::default()) }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn op_stats(&self) -> OpStats {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        OpStats {
+            gets: load(&self.counters.gets),
+            puts: load(&self.counters.puts),
+            deletes: load(&self.counters.deletes),
+            scans: load(&self.counters.scans),
+            txns: load(&self.counters.txns),
+            errors: load(&self.counters.errors),
+        }
+    }
+
+    fn traced<R>(&self, op: &'static str, table: &str, call: impl FnOnce() -> DbResult<R>) -> DbResult<R> {
+        let span = tracing::debug_span!("db", op, table);
+        let _entered = span.enter();
+
+        let started = Instant::now();
+        let result = call();
+        if let Some(counter) = self.counters.counter(op) {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        if result.is_err() {
+            self.counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        let elapsed_us = started.elapsed().as_micros() as u64;
+        match &result {
+            Ok(_) => tracing::debug!(elapsed_us, "db call"),
+            Err(e) => tracing::warn!(elapsed_us, error = %e, "db call failed"),
+        }
+        result
+    }
+}
+
+impl<S: DatabaseBackend> DatabaseBackend for Traced<S> {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Ok(Self::new(S::open(path)?))
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        Ok(Self::new(S::open_temporary()?))
+    }
+}
+
+impl<S: KvStore> KvStore for Traced<S> {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        self.traced("get", table, || self.inner.get(table, key))
+    }
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        self.traced("get_many", table, || self.inner.get_many(table, keys))
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        self.traced("put", table, || self.inner.put(table, key, value))
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        self.traced("delete", table, || self.inner.delete(table, key))
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.traced("scan", table, || self.inner.scan(table, range))
+    }
+
+    // 여러 테이블에 걸칠 수 있어서 첫 연산의 테이블만 남긴다
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        let table = match ops.first() {
+            Some(KvOp::Put { table, .. }) | Some(KvOp::Delete { table, .. }) => table.as_str(),
+            None => "",
+        };
+        self.traced("txn", table, || self.inner.txn(ops))
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.inner.ensure_table(table)
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        self.inner.tables()
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        let mut stats = self.inner.stats()?;
+        stats.ops = Some(self.op_stats());
+        Ok(stats)
+    }
+
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.traced("export", "", || self.inner.export_to::<D>(path))
+    }
+
+    fn import_from<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.traced("import", "", || self.inner.import_from::<D>(path))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+
+    #[test]
+    fn test_calls_pass_through() -> Result<(), Box<dyn std::error::Error>> {
+        let db = Traced::new(MemoryDatabase::new());
+
+        db.write("alice", "v1", "user_profiles")?;
+        db.batch_write(&[("bob", "v2")], "user_profiles")?;
+        assert_eq!(db.read("alice", "user_profiles")?, Some(b"v1".to_vec()));
+        assert_eq!(db.read_all("user_profiles")?.len(), 2);
+        assert!(db.remove("alice", "user_profiles")?);
+        assert_eq!(db.inner().read("alice", "user_profiles")?, None);
+        db.txn(&[])?;
+
+        let ops = db.stats()?.ops.unwrap();
+        assert_eq!(ops, OpStats { gets: 1, puts: 1, deletes: 1, scans: 1, txns: 2, errors: 0 });
+
+        Ok(())
+    }
+}
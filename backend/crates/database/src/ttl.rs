@@ -0,0 +1,162 @@
+use crate::basic_db::SafeDatabase;
+use crate::error::DbResult;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 만료 시간이 있는 데이터(세션, nonce, rate-limit 카운터, SNS 이름 캐시 등)를 담는 테이블들.
+// 서버의 주기적 정리 작업이 이 목록을 순회하며 만료된 항목을 지운다.
+pub const TTL_TABLES: &[&str] = &["sessions", "nonces", "rate_limits", "revoked_tokens", "relay_quotas", "faucet_claims", "sns_names"];
+
+// 값 앞에 8바이트 big-endian 만료 시각(unix 초)을 붙여서 저장한다
+const EXPIRY_LEN: usize = 8;
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+pub fn encode_entry(expires_at: u64, value: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(EXPIRY_LEN + value.len());
+    bytes.extend_from_slice(&expires_at.to_be_bytes());
+    bytes.extend_from_slice(value);
+    bytes
+}
+
+// (만료 시각, 값) 으로 분리. 길이가 모자라면 손상된 항목으로 보고 None
+pub fn decode_entry(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    if bytes.len() < EXPIRY_LEN {
+        return None;
+    }
+    let (expiry, value) = bytes.split_at(EXPIRY_LEN);
+    Some((u64::from_be_bytes(expiry.try_into().ok()?), value))
+}
+
+#[derive(Clone)]
+pub struct TtlTable<T: SafeDatabase> {
+    database: T,
+    table: String,
+}
+
+impl<T: SafeDatabase> TtlTable<T> {
+    pub fn new(database: T, table: impl Into<String>) -> Self {
+        Self {
+            database,
+            table: table.into(),
+        }
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub fn put(&self, key: &str, value: &[u8], ttl: Duration) -> DbResult<u64> {
+        let expires_at = now_secs().saturating_add(ttl.as_secs());
+        self.put_until(key, value, expires_at)?;
+        Ok(expires_at)
+    }
+
+    pub fn put_until(&self, key: &str, value: &[u8], expires_at: u64) -> DbResult<()> {
+        let entry = encode_entry(expires_at, value);
+        self.database.batch_write(&[(key.as_bytes(), entry)], &self.table)
+    }
+
+    pub fn get(&self, key: &str) -> DbResult<Option<Vec<u8>>> {
+        self.get_at(key, now_secs())
+    }
+
+    // 만료된 항목은 읽기 시점에 없는 것으로 취급 (실제 삭제는 purge 에서)
+    pub fn get_at(&self, key: &str, now: u64) -> DbResult<Option<Vec<u8>>> {
+        let bytes = match self.database.read(key, &self.table)? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        match decode_entry(&bytes) {
+            Some((expires_at, value)) if expires_at > now => Ok(Some(value.to_vec())),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn remove(&self, key: &str) -> DbResult<bool> {
+        self.database.remove(key, &self.table)
+    }
+
+    pub fn purge_expired(&self) -> DbResult<usize> {
+        self.purge_expired_at(now_secs())
+    }
+
+    pub fn purge_expired_at(&self, now: u64) -> DbResult<usize> {
+        let entries = self.database.read_all(&self.table)?;
+
+        let expired: Vec<Vec<u8>> = entries
+            .into_iter()
+            .filter(|(_, value)| match decode_entry(value) {
+                Some((expires_at, _)) => expires_at <= now,
+                None => true,
+            })
+            .map(|(key, _)| key)
+            .collect();
+
+        if !expired.is_empty() {
+            self.database.batch_remove(&expired, &self.table)?;
+        }
+
+        Ok(expired.len())
+    }
+}
+
+// TTL_TABLES 전체를 정리하고 지운 항목 수를 반환
+pub fn purge_all_expired<T: SafeDatabase>(database: &T) -> DbResult<usize> {
+    let mut purged = 0;
+    for table in TTL_TABLES {
+        purged += TtlTable::new(database.clone(), *table).purge_expired()?;
+    }
+    Ok(purged)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_db::MemoryDatabase;
+
+    #[test]
+    fn test_entry_round_trip() {
+        let bytes = encode_entry(42, b"hello");
+        assert_eq!(decode_entry(&bytes), Some((42, &b"hello"[..])));
+        assert_eq!(decode_entry(b"short"), None);
+    }
+
+    #[test]
+    fn test_expired_entries_are_hidden_and_purged() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        let sessions = TtlTable::new(db.clone(), "sessions");
+
+        sessions.put_until("alive", b"token-a", 200)?;
+        sessions.put_until("dead", b"token-b", 100)?;
+
+        assert_eq!(sessions.get_at("alive", 150)?, Some(b"token-a".to_vec()));
+        assert_eq!(sessions.get_at("dead", 150)?, None);
+
+        assert_eq!(sessions.purge_expired_at(150)?, 1);
+        assert!(db.read("dead", "sessions")?.is_none());
+        assert!(db.read("alive", "sessions")?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_put_with_duration() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        let nonces = TtlTable::new(db, "nonces");
+
+        let expires_at = nonces.put("n1", b"1", Duration::from_secs(60))?;
+        assert!(expires_at >= now_secs() + 59);
+        assert_eq!(nonces.get("n1")?, Some(b"1".to_vec()));
+        assert!(nonces.remove("n1")?);
+        assert_eq!(nonces.get("n1")?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::DbResult;
+use crate::kv::{KeyRange, KvOp, KvStore};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+// 값 타입이 정해진 테이블. 값은 JSON 으로 직렬화해서 저장한다.
+// 키는 바이트 그대로 쓰므로 정렬이 필요하면 KeyBuilder 로 만든다.
+//
+//     const CONTENT: TypedTable<ContentRecord> = TypedTable::new("content_records");
+//     CONTENT.put(&db, &KeyBuilder::new().u64(id).build(), &record)?;
+pub struct TypedTable<V> {
+    name: &'static str,
+    _value: PhantomData<fn() -> V>,
+}
+
+// V 가 Clone/Copy 가 아니어도 테이블 핸들은 복사할 수 있어야 한다
+impl<V> Clone for TypedTable<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for TypedTable<V> {}
+
+impl<V: Serialize + DeserializeOwned> TypedTable<V> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _value: PhantomData,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get<S: KvStore>(&self, db: &S, key: &[u8]) -> DbResult<Option<V>> {
+        match db.get(self.name, key)? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    // keys 순서대로. 백엔드가 읽기 트랜잭션 하나로 읽는다.
+    pub fn get_many<S: KvStore>(&self, db: &S, keys: &[&[u8]]) -> DbResult<Vec<Option<V>>> {
+        db.get_many(self.name, keys)?
+            .into_iter()
+            .map(|bytes| match bytes {
+                Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    pub fn put<S: KvStore>(&self, db: &S, key: &[u8], value: &V) -> DbResult<()> {
+        db.put(self.name, key, &serde_json::to_vec(value)?)
+    }
+
+    pub fn delete<S: KvStore>(&self, db: &S, key: &[u8]) -> DbResult<bool> {
+        db.delete(self.name, key)
+    }
+
+    pub fn scan<S: KvStore>(&self, db: &S, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, V)>> {
+        db.scan(self.name, range)?
+            .into_iter()
+            .map(|(key, bytes)| Ok((key, serde_json::from_slice(&bytes)?)))
+            .collect()
+    }
+
+    // 다른 테이블 쓰기와 한 트랜잭션으로 묶을 때 쓴다
+    pub fn put_op(&self, key: &[u8], value: &V) -> DbResult<KvOp> {
+        Ok(KvOp::put(self.name, key, serde_json::to_vec(value)?))
+    }
+
+    pub fn delete_op(&self, key: &[u8]) -> KvOp {
+        KvOp::delete(self.name, key)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DatabaseError;
+    use crate::keys::KeyBuilder;
+    use crate::memory_db::MemoryDatabase;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Item {
+        name: String,
+        count: u32,
+    }
+
+    const ITEMS: TypedTable<Item> = TypedTable::new("items");
+
+    fn item(name: &str, count: u32) -> Item {
+        Item { name: name.to_string(), count }
+    }
+
+    #[test]
+    fn test_roundtrip_and_scan() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        let key = |id: u64| KeyBuilder::new().u64(id).build();
+
+        ITEMS.put(&db, &key(2), &item("b", 2))?;
+        db.txn(&[ITEMS.put_op(&key(1), &item("a", 1))?])?;
+
+        assert_eq!(ITEMS.get(&db, &key(1))?, Some(item("a", 1)));
+        assert_eq!(ITEMS.get(&db, &key(3))?, None);
+        assert_eq!(
+            ITEMS.get_many(&db, &[key(2).as_slice(), key(3).as_slice(), key(1).as_slice()])?,
+            vec![Some(item("b", 2)), None, Some(item("a", 1))]
+        );
+
+        let names: Vec<String> = ITEMS
+            .scan(&db, &KeyRange::all())?
+            .into_iter()
+            .map(|(_, item)| item.name)
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+
+        assert!(ITEMS.delete(&db, &key(1))?);
+        assert_eq!(ITEMS.get(&db, &key(1))?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_values_of_another_shape() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        db.put("items", b"bad", b"not json")?;
+
+        assert!(matches!(ITEMS.get(&db, b"bad"), Err(DatabaseError::Json(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use crate::basic_db::DatabaseBackend;
+use crate::error::DbResult;
+use crate::history::{HistoryEntry, VersionHistory};
+use crate::kv::{KeyRange, KvOp, KvStore};
+use crate::stats::DatabaseStats;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+// 구독자가 이만큼 뒤처지면 오래된 이벤트부터 버려진다 (수신 측에서 Lagged 로 보임)
+pub const CHANGE_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Put,
+    Delete,
+}
+
+// 커밋이 끝난 쓰기 하나에 대한 알림
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeEvent {
+    pub table: String,
+    pub key: Vec<u8>,
+    pub kind: ChangeKind,
+}
+
+// 변경 알림을 구독할 수 있는 저장소
+pub trait ChangeFeed {
+    fn subscribe(&self) -> broadcast::Receiver<ChangeEvent>;
+}
+
+// 테이블마다 커밋된 쓰기 수. 값이 같으면 테이블 내용도 같다 (ETag 계산용).
+// 메모리에만 있으므로 프로세스가 다시 뜨면 0 부터 다시 센다.
+pub trait TableVersions {
+    fn table_version(&self, table: &str) -> u64;
+}
+
+// 아무 KvStore 나 감싸서, 이 핸들(과 복제본)을 통한 쓰기를 커밋 후에 브로드캐스트한다.
+// 감싼 저장소에 직접 쓰면 알림이 나가지 않는다 (테이블 버전도 오르지 않는다).
+#[derive(Clone)]
+pub struct Watched<S> {
+    inner: S,
+    sender: broadcast::Sender<ChangeEvent>,
+    versions: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl<S: KvStore> Watched<S> {
+    pub fn new(inner: S) -> Self {
+        let (sender, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self { inner, sender, versions: Arc::default() }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    fn notify(&self, table: &str, key: &[u8], kind: ChangeKind) {
+        *self.versions.lock().unwrap().entry(table.to_string()).or_default() += 1;
+        // 구독자가 없으면 send 가 실패하는데, 그건 정상 상황이다
+        let _ = self.sender.send(ChangeEvent {
+            table: table.to_string(),
+            key: key.to_vec(),
+            kind,
+        });
+    }
+}
+
+impl<S> ChangeFeed for Watched<S> {
+    fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl<S> TableVersions for Watched<S> {
+    fn table_version(&self, table: &str) -> u64 {
+        self.versions.lock().unwrap().get(table).copied().unwrap_or(0)
+    }
+}
+
+impl<S: VersionHistory> VersionHistory for Watched<S> {
+    fn get_history(&self, table: &str, key: &[u8]) -> DbResult<Vec<HistoryEntry>> {
+        self.inner.get_history(table, key)
+    }
+}
+
+impl<S: DatabaseBackend> DatabaseBackend for Watched<S> {
+    fn open<P: AsRef<Path>>(path: P) -> DbResult<Self> {
+        Ok(Self::new(S::open(path)?))
+    }
+
+    fn open_temporary() -> DbResult<Self> {
+        Ok(Self::new(S::open_temporary()?))
+    }
+}
+
+impl<S: KvStore> KvStore for Watched<S> {
+
+    fn get(&self, table: &str, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        self.inner.get(table, key)
+    }
+
+    fn get_many(&self, table: &str, keys: &[&[u8]]) -> DbResult<Vec<Option<Vec<u8>>>> {
+        self.inner.get_many(table, keys)
+    }
+
+    fn put(&self, table: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        self.inner.put(table, key, value)?;
+        self.notify(table, key, ChangeKind::Put);
+        Ok(())
+    }
+
+    fn delete(&self, table: &str, key: &[u8]) -> DbResult<bool> {
+        let deleted = self.inner.delete(table, key)?;
+        if deleted {
+            self.notify(table, key, ChangeKind::Delete);
+        }
+        Ok(deleted)
+    }
+
+    fn scan(&self, table: &str, range: &KeyRange) -> DbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.inner.scan(table, range)
+    }
+
+    fn txn(&self, ops: &[KvOp]) -> DbResult<()> {
+        self.inner.txn(ops)?;
+        for op in ops {
+            match op {
+                KvOp::Put { table, key, .. } => self.notify(table, key, ChangeKind::Put),
+                KvOp::Delete { table, key } => self.notify(table, key, ChangeKind::Delete),
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_table(&self, table: &str) -> DbResult<()> {
+        self.inner.ensure_table(table)
+    }
+
+    fn tables(&self) -> DbResult<Vec<String>> {
+        self.inner.tables()
+    }
+
+    fn stats(&self) -> DbResult<DatabaseStats> {
+        self.inner.stats()
+    }
+
+    // 읽기 전용이므로 감싼 저장소의 (스냅샷) 구현을 그대로 쓴다
+    fn export_to<D: DatabaseBackend>(&self, path: &Path) -> DbResult<()> {
+        self.inner.export_to::<D>(path)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic_db::SafeDatabase;
+    use crate::memory_db::MemoryDatabase;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    fn event(table: &str, key: &str, kind: ChangeKind) -> ChangeEvent {
+        ChangeEvent {
+            table: table.to_string(),
+            key: key.as_bytes().to_vec(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_writes_are_broadcast() -> Result<(), Box<dyn std::error::Error>> {
+        let db = Watched::new(MemoryDatabase::new());
+        let mut receiver = db.clone().subscribe();
+
+        db.write("alice", "profile", "user_profiles")?;
+        db.batch_write(&[("a", "1"), ("b", "2")], "content")?;
+        assert!(db.remove("alice", "user_profiles")?);
+        // 없는 키 삭제는 알림을 보내지 않는다
+        assert!(!db.remove("nobody", "user_profiles")?);
+
+        assert_eq!(receiver.try_recv()?, event("user_profiles", "alice", ChangeKind::Put));
+        assert_eq!(receiver.try_recv()?, event("content", "a", ChangeKind::Put));
+        assert_eq!(receiver.try_recv()?, event("content", "b", ChangeKind::Put));
+        assert_eq!(receiver.try_recv()?, event("user_profiles", "alice", ChangeKind::Delete));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+        // 실제로 바뀐 쓰기만 센다
+        assert_eq!(db.table_version("user_profiles"), 2);
+        assert_eq!(db.table_version("content"), 2);
+        assert_eq!(db.table_version("unknown"), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reads_do_not_notify() -> Result<(), Box<dyn std::error::Error>> {
+        let db = Watched::new(MemoryDatabase::new());
+        db.write("k", "v", "t")?;
+
+        let mut receiver = db.subscribe();
+        assert_eq!(db.read("k", "t")?, Some(b"v".to_vec()));
+        assert_eq!(db.read_all("t")?.len(), 1);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+// 로컬 solana-test-validator 위에서 Turtle 프로그램을 실제로 돌려 보는 통합 테스트 도구.
+// 단위 테스트는 런타임을 흉내 내서 CPI(lamports 이동) 를 확인하지 못하므로, 여기서는 빌드된 turtle_dao.so 를 올린
+// 진짜 validator 에 turtle-client 로 트랜잭션을 보낸다.
+//
+// 필요한 것: PATH 의 solana-test-validator, 그리고 빌드된 프로그램
+//   (cd solana_program && cargo build-sbf)
+//   cargo test -p turtle-e2e -- --ignored
+// 프로그램 경로는 TURTLE_PROGRAM_SO 로 바꿀 수 있다.
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use tempfile::TempDir;
+use turtle_client::convert::{from_sdk_pubkey, to_sdk_pubkey};
+use turtle_client::{Pubkey, TurtleClient};
+
+// validator 가 RPC 를 열 때까지 기다리는 최대 시간
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn program_so_path() -> PathBuf {
+    match std::env::var_os("TURTLE_PROGRAM_SO") {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../../solana_program/target/deploy/turtle_dao.so"),
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").and_then(|listener| listener.local_addr()).map(|addr| addr.port()).expect("no free port")
+}
+
+// 테스트 하나가 쓰는 validator. 원장은 임시 디렉토리에 두고, drop 하면 프로세스를 끝낸다.
+pub struct LocalValidator {
+    process: Child,
+    _ledger: TempDir,
+    rpc_url: String,
+    program_id: Pubkey,
+}
+
+impl LocalValidator {
+    // 프로그램을 새 주소에 올린 채로 validator 를 띄우고 RPC 가 응답할 때까지 기다린다
+    pub async fn start() -> Self {
+        let program_so = program_so_path();
+        assert!(program_so.exists(), "{} not found, build the program with `cargo build-sbf` first", program_so.display());
+
+        let ledger = TempDir::new().expect("failed to create ledger dir");
+        let program_id = from_sdk_pubkey(&Keypair::new().pubkey());
+        let rpc_port = free_port();
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger").arg(ledger.path())
+            .arg("--rpc-port").arg(rpc_port.to_string())
+            .arg("--faucet-port").arg(free_port().to_string())
+            .arg("--bpf-program").arg(program_id.to_string()).arg(&program_so)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start solana-test-validator (is it on PATH?)");
+
+        let validator = Self { process, _ledger: ledger, rpc_url: format!("http://127.0.0.1:{}", rpc_port), program_id };
+        validator.wait_until_ready().await;
+        validator
+    }
+
+    async fn wait_until_ready(&self) {
+        let rpc = self.rpc();
+        let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+        while rpc.get_health().await.is_err() {
+            assert!(tokio::time::Instant::now() < deadline, "solana-test-validator did not start within {:?}", STARTUP_TIMEOUT);
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn rpc(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.rpc_url.clone(), CommitmentConfig::confirmed())
+    }
+
+    pub fn client(&self) -> TurtleClient {
+        TurtleClient::new(self.rpc_url.clone(), self.program_id)
+    }
+
+    // sol 만큼 에어드롭받은 새 지갑
+    pub async fn funded_keypair(&self, sol: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let rpc = self.rpc();
+        let signature = rpc.request_airdrop(&keypair.pubkey(), sol * LAMPORTS_PER_SOL).await.expect("airdrop failed");
+        while !rpc.confirm_transaction(&signature).await.expect("airdrop confirmation failed") {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        keypair
+    }
+
+    pub async fn balance(&self, pubkey: &Pubkey) -> u64 {
+        self.rpc().get_balance(&to_sdk_pubkey(pubkey)).await.expect("getBalance failed")
+    }
+}
+
+impl Drop for LocalValidator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
@@ -0,0 +1,97 @@
+// 한 라운드를 처음부터 끝까지: DAO 생성 → 예치 → 글 제출 → 글 투표 → 타임아웃 처리 → 품질 보상.
+// 실제 validator 가 필요해서 기본으로는 돌지 않는다 (cargo test -p turtle-e2e -- --ignored).
+use solana_sdk::signature::Signer;
+use std::time::Duration;
+use turtle_client::convert::from_sdk_pubkey;
+use turtle_client::instruction;
+use turtle_e2e::LocalValidator;
+
+const TIME_LIMIT_SECS: u64 = 3;
+
+#[tokio::test]
+#[ignore = "needs solana-test-validator and a built turtle_dao.so"]
+async fn test_full_round() {
+    let validator = LocalValidator::start().await;
+    let client = validator.client();
+    let program_id = validator.program_id();
+
+    let admin = validator.funded_keypair(10).await;
+    let depositors = [validator.funded_keypair(10).await, validator.funded_keypair(10).await];
+    let author = validator.funded_keypair(1).await;
+    let admin_key = from_sdk_pubkey(&admin.pubkey());
+
+    let dao = client.dao_address(&admin_key, "e2e").unwrap();
+    let initialize = instruction::initialize_dao(&program_id, &admin_key, &dao, "e2e".to_string(), TIME_LIMIT_SECS, 10, false, 20, false);
+    client.send_and_confirm(&[initialize], &admin, &[]).await.unwrap();
+    let rent = validator.balance(&dao).await;
+
+    // 예치한 lamports 가 실제로 DAO 계정으로 옮겨져야 한다 (시스템 프로그램 CPI)
+    let amounts = [2_000_000_000u64, 500_000_000];
+    for (depositor, amount) in depositors.iter().zip(amounts) {
+        let before = validator.balance(&from_sdk_pubkey(&depositor.pubkey())).await;
+        client.deposit(depositor, &dao, amount).await.unwrap();
+        let after = validator.balance(&from_sdk_pubkey(&depositor.pubkey())).await;
+        assert!(before - after >= amount, "depositor paid {} for a deposit of {}", before - after, amount);
+    }
+    assert_eq!(validator.balance(&dao).await, rent + amounts.iter().sum::<u64>());
+
+    client.submit_content(&author, &dao, "first".to_string(), String::new()).await.unwrap();
+    client.submit_content(&author, &dao, "second".to_string(), String::new()).await.unwrap();
+    let state = client.fetch_dao_state(&dao).await.unwrap();
+    let last_content = state.last_content;
+    assert_eq!(state.total_deposit, amounts.iter().sum::<u64>());
+    assert_eq!(state.depositors.len(), 2);
+    assert_eq!(state.contents.iter().map(|content| content.text.as_str()).collect::<Vec<_>>(), ["first", "second"]);
+    assert_eq!(state.last_submitter, from_sdk_pubkey(&author.pubkey()));
+    // 할인 구간이 없으니 제출마다 base_fee 10 lamports 가 fee_pool 로 들어간다
+    assert_eq!(state.fee_pool, 20);
+    assert_eq!(validator.balance(&dao).await, rent + amounts.iter().sum::<u64>() + 20);
+
+    // 예치자가 첫 글에 투표한다. 표 기록 계정이 있어서 두 번째 표는 거절된다
+    let first = state.contents[0].key();
+    client.vote_content(&depositors[0], &dao, &first).await.unwrap();
+    assert!(client.vote_content(&depositors[0], &dao, &first).await.is_err());
+    assert_eq!(client.fetch_dao_state(&dao).await.unwrap().contents[0].vote_count, 1);
+
+    // 마감 전에는 거절된다
+    assert!(client.process_timeout(&admin, &dao).await.is_err());
+
+    tokio::time::sleep(Duration::from_secs(TIME_LIMIT_SECS + 2)).await;
+    let author_before = validator.balance(&from_sdk_pubkey(&author.pubkey())).await;
+    client.process_timeout(&admin, &dao).await.unwrap();
+
+    // 마지막 제출자가 이긴 라운드라 글과 예치 기록이 비워진다. 상금이 다 나가 팟이 비었으니
+    // 다음 예치가 들어올 때까지 Dormant 로 쉬고 마감도 없다.
+    let state = client.fetch_dao_state(&dao).await.unwrap();
+    assert!(state.is_dormant());
+    assert_eq!(state.timeout_timestamp, 0);
+    assert!(state.contents.is_empty());
+    assert_eq!(state.total_deposit, 0);
+    assert_eq!(state.last_submitter, Default::default());
+
+    // 글은 지워졌어도 라운드 기록 계정에 승자가 남는다 (rent 는 호출한 admin 이 냈다)
+    assert_eq!(state.round, 1);
+    let round_result = client.fetch_round_result(&dao, 0).await.unwrap();
+    assert_eq!((round_result.winner, round_result.content), (from_sdk_pubkey(&author.pubkey()), last_content));
+    // 제출 수수료는 라운드가 끝날 때 팟에 합쳐진다
+    assert_eq!(round_result.pot, amounts.iter().sum::<u64>() + 20);
+    assert_eq!(state.fee_pool, 0);
+
+    // 상금은 DAO 에서 승자에게 바로 간다. 품질 보상 몫은 bounty_pool 에 남는다
+    assert!(round_result.reward > 0 && round_result.reward < round_result.pot);
+    assert_eq!(validator.balance(&from_sdk_pubkey(&author.pubkey())).await - author_before, round_result.reward);
+    assert_eq!(state.bounty_pool, round_result.pot - round_result.reward);
+    assert_eq!(round_result.quality_pool, state.bounty_pool);
+    // 표를 받은 첫 글만 품질 보상 후보다
+    assert_eq!(round_result.candidates.iter().map(|candidate| (candidate.content, candidate.votes)).collect::<Vec<_>>(), [(first, 1)]);
+    assert_eq!(validator.balance(&dao).await, rent + round_result.pot - round_result.reward);
+
+    // 관리자가 그 몫을 표를 받은 작성자에게 준다. 라운드마다 한 번뿐이다
+    let author_before = validator.balance(&from_sdk_pubkey(&author.pubkey())).await;
+    let creators = vec![from_sdk_pubkey(&author.pubkey())];
+    client.distribute_quality_rewards(&admin, &dao, 0, creators.clone(), vec![100], None).await.unwrap();
+    assert_eq!(validator.balance(&from_sdk_pubkey(&author.pubkey())).await - author_before, round_result.quality_pool);
+    assert_eq!(client.fetch_round_result(&dao, 0).await.unwrap().quality_pool, 0);
+    assert_eq!(client.fetch_dao_state(&dao).await.unwrap().bounty_pool, 0);
+    assert!(client.distribute_quality_rewards(&admin, &dao, 0, creators, vec![100], None).await.is_err());
+}
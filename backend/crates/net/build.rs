@@ -0,0 +1,12 @@
+fn main() {
+    // grpc 기능을 켰을 때만 proto 에서 서버 코드를 만든다. protoc 는 크레이트에 들어 있는 것을 쓴다.
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/indexer.proto");
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+        tonic_build::configure()
+            .build_client(false)
+            .compile_protos(&["proto/indexer.proto"], &["proto"])
+            .unwrap();
+    }
+}
@@ -0,0 +1,974 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sol::lookup_table::AddressLookupTable;
+use sol::state::{Pubkey, MAX_QUALITY_CANDIDATES};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::KeyRange;
+use turtle_database::stats::DatabaseStats;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::chain::{ChainContent, DaoSnapshot};
+use turtle_service::parser::moderation::{ModerationVerdict, Verdict};
+use crate::indexer::{event_key, load_checkpoint, EVENT_TABLES};
+use crate::moderation::{override_verdict, MODERATION_VERDICTS};
+use crate::rpc::{shared_rpc, RpcClient};
+use crate::scheduler::{Scheduler, SchedulerError};
+use crate::server::Store;
+use crate::session::AuthedWallet;
+use crate::sync::{round_content_prefix, RoundContent, CHAIN_CONTENTS, DAO_SNAPSHOTS, ROUND_CONTENTS};
+use crate::tenant::{current_tenant, Tenant};
+use crate::fees::PriorityFeeSource;
+use crate::tx::{draft_process_timeout, draft_quality_rewards, lookup_table_for, tx_config, BlockhashSource, UnsignedTransactionResponse};
+use crate::error::ApiError;
+
+// 백업 파일이 쌓이는 디렉토리 (백업마다 하위 디렉토리를 새로 만든다)
+pub const BACKUP_DIR: &str = "./backups";
+// 품질 보상을 받을 수 있는 최대 작성자 수. 한 명마다 계정 32 바이트와 데이터 33 바이트라 트랜잭션 크기(1232) 안에 들어가게 둔다.
+pub const MAX_REWARD_RECIPIENTS: usize = 10;
+const DEFAULT_TOP_N: usize = 3;
+// 품질 보상을 받으려면 콘텐츠 하나가 이만큼은 득표해야 한다 (요청의 min_votes 가 없을 때)
+pub const DEFAULT_MIN_REWARD_VOTES: u64 = 1;
+
+#[derive(Serialize)]
+pub struct BackupResponse {
+    pub path: String,
+    pub created_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct TableStatsResponse {
+    pub name: String,
+    pub entries: u64,
+}
+
+#[derive(Serialize)]
+pub struct DbStatsResponse {
+    pub backend: String,
+    pub map_size: Option<u64>,
+    pub used_bytes: Option<u64>,
+    pub usage_ratio: Option<f64>,
+    pub near_full: bool,
+    pub last_txn_id: Option<u64>,
+    pub last_commit_at: Option<u64>,
+    pub total_entries: u64,
+    pub tables: Vec<TableStatsResponse>,
+    pub cache_hits: Option<u64>,
+    pub cache_misses: Option<u64>,
+}
+
+impl From<DatabaseStats> for DbStatsResponse {
+    fn from(stats: DatabaseStats) -> Self {
+        Self {
+            backend: stats.backend.to_string(),
+            map_size: stats.map_size,
+            used_bytes: stats.used_bytes,
+            usage_ratio: stats.usage_ratio(),
+            near_full: stats.near_full(),
+            last_txn_id: stats.last_txn_id,
+            last_commit_at: stats.last_commit_at,
+            total_entries: stats.total_entries(),
+            cache_hits: stats.cache.map(|cache| cache.hits),
+            cache_misses: stats.cache.map(|cache| cache.misses),
+            tables: stats
+                .tables
+                .into_iter()
+                .map(|table| TableStatsResponse {
+                    name: table.name,
+                    entries: table.entries,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AdminError {
+    DatabaseError(String),
+    ValidationError(String),
+    NotFound(String),
+    RpcError(String),
+    ConfigError(String),
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            AdminError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            AdminError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            AdminError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            AdminError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
+        }
+    }
+}
+
+impl StdError for AdminError {}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            AdminError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            AdminError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            AdminError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            AdminError::RpcError(msg) => (StatusCode::BAD_GATEWAY, msg),
+            AdminError::ConfigError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+// BACKUP_DIR 아래 backup-<시각> 에 새 백업을 만든다
+pub fn create_backup<T: SafeDatabase>(database: &T) -> Result<BackupResponse, AdminError> {
+    let created_at = now_secs();
+    let path = PathBuf::from(BACKUP_DIR).join(format!("backup-{}", created_at));
+
+    database.backup_to::<Store, _>(&path)
+        .map_err(|e| AdminError::DatabaseError(e.to_string()))?;
+
+    Ok(BackupResponse {
+        path: path.to_string_lossy().to_string(),
+        created_at,
+    })
+}
+
+pub async fn backup_database<T: SafeDatabase>(
+    State(database): State<T>,
+) -> Result<Json<BackupResponse>, AdminError> {
+    Ok(Json(create_backup(&database)?))
+}
+
+// backup_schedule (cron 식) 마다 백업을 만든다. 같은 초에 두 번 돌지 않도록 지터는 두지 않는다.
+pub fn register_backup_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>, schedule: &str) -> Result<(), SchedulerError> {
+    scheduler.cron("backup", schedule, Duration::ZERO, |database| async move {
+        let backup = create_backup(&database).map_err(|e| e.to_string())?;
+        tracing::info!(path = %backup.path, "Scheduled backup created");
+        Ok(())
+    })?;
+    Ok(())
+}
+
+pub async fn get_db_stats<T: SafeDatabase>(
+    State(database): State<T>,
+) -> Result<Json<DbStatsResponse>, AdminError> {
+    let stats = database.stats()
+        .map_err(|e| AdminError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(DbStatsResponse::from(stats)))
+}
+
+fn db_err(e: impl fmt::Display) -> AdminError {
+    AdminError::DatabaseError(e.to_string())
+}
+
+pub const DEFAULT_FLAGGED_LIMIT: usize = 50;
+pub const MAX_FLAGGED_LIMIT: usize = 200;
+
+// 대시보드가 체인에서 직접 읽는 값. 서버는 RPC 를 쓰고, 테스트는 값을 정해 둔다.
+pub trait ChainReader: Send + Sync + 'static {
+    fn current_slot(&self) -> impl Future<Output = Result<u64, String>> + Send;
+    fn balance(&self, pubkey: &str) -> impl Future<Output = Result<u64, String>> + Send;
+}
+
+impl ChainReader for RpcClient {
+    async fn current_slot(&self) -> Result<u64, String> {
+        let result = self.call("getSlot", json!([{ "commitment": "confirmed" }])).await.map_err(|e| e.to_string())?;
+        result.as_u64().ok_or_else(|| format!("Unexpected getSlot response: {}", result))
+    }
+
+    // {"context": ..., "value": <lamports>}
+    async fn balance(&self, pubkey: &str) -> Result<u64, String> {
+        let result = self.call("getBalance", json!([pubkey, { "commitment": "confirmed" }])).await.map_err(|e| e.to_string())?;
+        result["value"].as_u64().ok_or_else(|| format!("Unexpected getBalance response: {}", result))
+    }
+}
+
+pub fn chain_reader() -> RpcClient {
+    current_tenant().map_or_else(shared_rpc, Tenant::rpc)
+}
+
+#[derive(Deserialize)]
+pub struct FlaggedQuery {
+    dao: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlaggedContent {
+    #[serde(flatten)]
+    pub verdict: ModerationVerdict,
+    pub content: Option<ChainContent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlaggedList {
+    pub flagged: Vec<FlaggedContent>,
+}
+
+// Rejected 판정을 받은 콘텐츠 (운영자가 Rejected 로 바꾼 것 포함)
+pub async fn list_flagged_content<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<FlaggedQuery>,
+) -> Result<Json<FlaggedList>, AdminError> {
+    let limit = query.limit.unwrap_or(DEFAULT_FLAGGED_LIMIT).clamp(1, MAX_FLAGGED_LIMIT);
+    let range = match &query.dao {
+        Some(dao) => KeyRange::prefix(KeyBuilder::new().str(dao).build()),
+        None => KeyRange::all(),
+    };
+
+    let mut flagged = Vec::new();
+    for (key, verdict) in MODERATION_VERDICTS.scan(&database, &range).map_err(db_err)? {
+        if verdict.verdict != Verdict::Rejected {
+            continue;
+        }
+        let content = CHAIN_CONTENTS.get(&database, &key).map_err(db_err)?;
+        flagged.push(FlaggedContent { verdict, content });
+        if flagged.len() >= limit {
+            break;
+        }
+    }
+    Ok(Json(FlaggedList { flagged }))
+}
+
+#[derive(Deserialize)]
+pub struct OverrideRequest {
+    dao: String,
+    index: u64,
+    verdict: Verdict,
+    reason: Option<String>,
+}
+
+// 판정을 운영자 결정으로 바꾼다. 아직 판정이 없는 콘텐츠도 바로 결정할 수 있다.
+pub async fn override_moderation<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<OverrideRequest>,
+) -> Result<Json<ModerationVerdict>, AdminError> {
+    let key = KeyBuilder::new().str(&request.dao).u64(request.index).build();
+    if CHAIN_CONTENTS.get(&database, &key).map_err(db_err)?.is_none() {
+        return Err(AdminError::NotFound(format!("No content {} in DAO {}", request.index, request.dao)));
+    }
+
+    let verdict = override_verdict(&database, &request.dao, request.index, request.verdict, &wallet, request.reason, now_secs())
+        .map_err(db_err)?;
+    Ok(Json(verdict))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsumerLag {
+    pub name: String,
+    pub last_event_id: u64,
+    pub behind: u64,                    // 아직 처리하지 않은 인덱싱된 이벤트 수
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexerStatus {
+    pub last_signature: Option<String>,
+    pub last_slot: u64,
+    pub last_event_id: u64,
+    pub last_event_at: Option<u64>,
+    pub chain_slot: Option<u64>,
+    pub slot_lag: Option<u64>,
+    pub seconds_since_last_event: Option<u64>,
+    pub consumers: Vec<ConsumerLag>,
+    pub rpc_error: Option<String>,
+}
+
+fn consumer<T: SafeDatabase, V: Serialize + serde::de::DeserializeOwned>(
+    database: &T,
+    name: &str,
+    table: TypedTable<V>,
+    key: &[u8],
+    last_event_id: impl Fn(&V) -> u64,
+    indexed: u64,
+) -> Result<ConsumerLag, AdminError> {
+    let processed = table.get(database, key).map_err(db_err)?.map(|state| last_event_id(&state)).unwrap_or(0);
+    Ok(ConsumerLag { name: name.to_string(), last_event_id: processed, behind: indexed.saturating_sub(processed) })
+}
+
+// 인덱싱된 이벤트를 읽어 가는 작업들의 진행 상황 (indexed 는 인덱서가 마지막으로 붙인 이벤트 ID)
+pub fn consumer_lags<T: SafeDatabase>(database: &T, indexed: u64) -> Result<Vec<ConsumerLag>, AdminError> {
+    Ok(vec![
+        consumer(database, "notifications", crate::notifications::NOTIFIER_STATE, crate::notifications::CHECKPOINT_KEY, |state| state.last_event_id, indexed)?,
+        consumer(database, "webhooks", crate::webhooks::WEBHOOK_STATE, crate::webhooks::CHECKPOINT_KEY, |state| state.last_event_id, indexed)?,
+        consumer(database, "bridge", crate::bridge::BRIDGE_STATE, crate::bridge::CHECKPOINT_KEY, |state| state.last_event_id, indexed)?,
+        consumer(database, "analytics", crate::analytics::ANALYTICS_STATE, crate::analytics::CHECKPOINT_KEY, |state| state.last_event_id, indexed)?,
+    ])
+}
+
+// 인덱서가 체인보다 얼마나 뒤처졌는지, 이벤트를 읽는 작업들이 인덱서보다 얼마나 뒤처졌는지.
+// RPC 가 실패해도 DB 에서 알 수 있는 값은 돌려준다.
+pub async fn indexer_status<T: SafeDatabase, C: ChainReader>(database: &T, chain: &C, now: u64) -> Result<IndexerStatus, AdminError> {
+    let checkpoint = load_checkpoint(database).map_err(db_err)?;
+    let last_event_id = checkpoint.next_event_id.saturating_sub(1);
+
+    let mut last_event_at = None;
+    for table in EVENT_TABLES {
+        if let Some(record) = table.get(database, &event_key(last_event_id)).map_err(db_err)? {
+            last_event_at = record.block_time;
+            break;
+        }
+    }
+
+    let consumers = consumer_lags(database, last_event_id)?;
+
+    let (chain_slot, rpc_error) = match chain.current_slot().await {
+        Ok(slot) => (Some(slot), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    Ok(IndexerStatus {
+        last_signature: checkpoint.last_signature,
+        last_slot: checkpoint.last_slot,
+        last_event_id,
+        last_event_at,
+        chain_slot,
+        slot_lag: chain_slot.map(|slot| slot.saturating_sub(checkpoint.last_slot)),
+        seconds_since_last_event: last_event_at.map(|at| now.saturating_sub(at)),
+        consumers,
+        rpc_error,
+    })
+}
+
+pub async fn get_indexer_status<T: SafeDatabase>(
+    State(database): State<T>,
+) -> Result<Json<IndexerStatus>, AdminError> {
+    Ok(Json(indexer_status(&database, &chain_reader(), now_secs()).await?))
+}
+
+#[derive(Deserialize)]
+pub struct RewardDraftRequest {
+    // 없으면 타이머가 끝났고 상금이 남은 모든 DAO
+    dao: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RewardDrafts {
+    pub drafts: Vec<UnsignedTransactionResponse>,
+}
+
+// 타이머가 끝났는데 아직 보상이 분배되지 않은 DAO
+pub fn expired_daos<T: SafeDatabase>(database: &T, dao: Option<&str>, now: u64) -> Result<Vec<String>, AdminError> {
+    let expired = |timeout_timestamp: u64, total_deposit: u64| timeout_timestamp != 0 && timeout_timestamp <= now && total_deposit > 0;
+
+    match dao {
+        Some(dao) => {
+            let snapshot = DAO_SNAPSHOTS.get(database, dao.as_bytes())
+                .map_err(db_err)?
+                .ok_or_else(|| AdminError::NotFound(format!("Unknown DAO: {}", dao)))?;
+            if !expired(snapshot.timeout_timestamp, snapshot.total_deposit) {
+                return Err(AdminError::ValidationError(format!("DAO {} has no expired round to distribute", dao)));
+            }
+            Ok(vec![snapshot.pubkey])
+        },
+        None => Ok(DAO_SNAPSHOTS.scan(database, &KeyRange::all())
+            .map_err(db_err)?
+            .into_iter()
+            .filter(|(_, snapshot)| expired(snapshot.timeout_timestamp, snapshot.total_deposit))
+            .map(|(_, snapshot)| snapshot.pubkey)
+            .collect()),
+    }
+}
+
+pub async fn draft_rewards<T: SafeDatabase, B: BlockhashSource + PriorityFeeSource>(
+    database: &T,
+    source: &B,
+    program_id: &Pubkey,
+    wallet: &str,
+    dao: Option<&str>,
+    now: u64,
+) -> Result<RewardDrafts, AdminError> {
+    let signer = wallet.parse::<Pubkey>()
+        .map_err(|_| AdminError::ValidationError(format!("Invalid wallet: {}", wallet)))?;
+
+    let mut drafts = Vec::new();
+    for dao in expired_daos(database, dao, now)? {
+        let (round, last_submitter) = DAO_SNAPSHOTS.get(database, dao.as_bytes()).map_err(db_err)?
+            .map_or((0, Pubkey::default()), |snapshot| (snapshot.round, snapshot.last_submitter.parse().unwrap_or_default()));
+        let Ok(dao) = dao.parse::<Pubkey>() else {
+            continue;
+        };
+        let draft = draft_process_timeout(source, program_id, &signer, &dao, round, &last_submitter).await
+            .map_err(|e| AdminError::RpcError(e.to_string()))?;
+        drafts.push(draft);
+    }
+    Ok(RewardDrafts { drafts })
+}
+
+// 보상 분배(ProcessTimeout) 트랜잭션 초안. 관리자 지갑이 서명해서 보낸다.
+pub async fn create_reward_drafts<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<RewardDraftRequest>,
+) -> Result<Json<RewardDrafts>, AdminError> {
+    let config = tx_config().map_err(|e| AdminError::ConfigError(e.to_string()))?;
+    let drafts = draft_rewards(&database, &config.rpc, &config.program_id, &wallet, request.dao.as_deref(), now_secs()).await?;
+    Ok(Json(drafts))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardRule {
+    // 받은 투표 수에 비례
+    #[default]
+    Proportional,
+    // 상위 N 명이 똑같이
+    TopN,
+}
+
+#[derive(Deserialize)]
+pub struct QualityRewardRequest {
+    dao: String,
+    #[serde(default)]
+    rule: RewardRule,
+    // 받는 작성자 수 (1..=MAX_REWARD_RECIPIENTS). 없으면 top_n 은 3 명, proportional 은 최대.
+    top_n: Option<usize>,
+    // 콘텐츠별 최소 득표. 없으면 DEFAULT_MIN_REWARD_VOTES.
+    min_votes: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct RewardAllocation {
+    pub wallet: String,
+    pub votes: u64,
+    pub weight: u8,                     // 퍼센트. 합이 100 이다.
+}
+
+#[derive(Debug, Serialize)]
+pub struct QualityRewardDraft {
+    pub dao: String,
+    pub round: u64,
+    pub rule: RewardRule,
+    pub allocations: Vec<RewardAllocation>,
+    pub draft: UnsignedTransactionResponse,
+}
+
+// 품질 보상 자격: 체인의 후보 (RoundResult.candidates) 안에 들고, 숨기지 않았고, 득표가 min_votes 이상이어야 한다.
+// AI 모더레이션을 켠 DAO 는 승인 판정까지 받아야 하고, 끈 DAO 도 거절 판정을 받은 콘텐츠는 뺀다.
+// 후보 밖의 작성자는 프로그램이 IneligibleCreator 로 막고, 모더레이션은 여기서만 거른다.
+fn is_reward_eligible(snapshot: &DaoSnapshot, content: &RoundContent, min_votes: u64) -> bool {
+    if content.hidden || content.content.vote_count < min_votes {
+        return false;
+    }
+    match snapshot.ai_moderation {
+        true => content.verdict == Some(Verdict::Approved),
+        false => content.verdict != Some(Verdict::Rejected),
+    }
+}
+
+// 끝난 라운드의 작성자별 득표 (자격 있는 콘텐츠만). 많이 받은 순서, 같으면 지갑 순서.
+pub fn round_standings<T: SafeDatabase>(database: &T, snapshot: &DaoSnapshot, round: u64, min_votes: u64) -> Result<Vec<(String, u64)>, AdminError> {
+    let mut contents: Vec<RoundContent> = ROUND_CONTENTS.scan(database, &KeyRange::prefix(round_content_prefix(&snapshot.pubkey, round)))
+        .map_err(db_err)?
+        .into_iter()
+        .map(|(_, content)| content)
+        .filter(|content| content.content.vote_count > 0)
+        .collect();
+    // 프로그램의 quality_candidates 와 같은 순서 (득표 많은 순, 같으면 먼저 올라온 순) 로 후보만 남긴다
+    contents.sort_by_key(|content| (std::cmp::Reverse(content.content.vote_count), content.content.index));
+    contents.truncate(MAX_QUALITY_CANDIDATES);
+
+    let mut votes: BTreeMap<String, u64> = BTreeMap::new();
+    for content in contents {
+        if !is_reward_eligible(snapshot, &content, min_votes) {
+            continue;
+        }
+        *votes.entry(content.content.author).or_default() += content.content.vote_count;
+    }
+    let mut standings: Vec<(String, u64)> = votes.into_iter().collect();
+    standings.sort_by_key(|standing| std::cmp::Reverse(standing.1));
+    Ok(standings)
+}
+
+// 득표가 있는 상위 top_n 명에게 나눈다. 내림한 퍼센트의 나머지는 소수점 아래가 큰 순서로 1 씩 더해서 합을 100 으로 맞춘다.
+pub fn distribution_weights(standings: &[(String, u64)], rule: RewardRule, top_n: usize) -> Vec<RewardAllocation> {
+    let winners: Vec<&(String, u64)> = standings.iter().filter(|(_, votes)| *votes > 0).take(top_n).collect();
+    // 득표 합이 u64 를 넘어도 되도록 u128 로 계산한다
+    let shares: Vec<u128> = winners.iter()
+        .map(|(_, votes)| match rule {
+            RewardRule::Proportional => u128::from(*votes),
+            RewardRule::TopN => 1,
+        })
+        .collect();
+    let total: u128 = shares.iter().sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut weights: Vec<u128> = shares.iter().map(|share| share * 100 / total).collect();
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(shares[i] * 100 % total));
+    let remainder = 100 - weights.iter().sum::<u128>();
+    for &i in order.iter().take(remainder as usize) {
+        weights[i] += 1;
+    }
+
+    winners.into_iter()
+        .zip(weights)
+        .filter(|(_, weight)| *weight > 0)
+        .map(|((wallet, votes), weight)| RewardAllocation { wallet: wallet.clone(), votes: *votes, weight: weight as u8 })
+        .collect()
+}
+
+pub async fn draft_quality_reward<T: SafeDatabase, B: BlockhashSource + PriorityFeeSource>(
+    database: &T,
+    source: &B,
+    program_id: &Pubkey,
+    wallet: &str,
+    request: &QualityRewardRequest,
+    lookup_table: Option<&AddressLookupTable>,
+) -> Result<QualityRewardDraft, AdminError> {
+    let signer = wallet.parse::<Pubkey>()
+        .map_err(|_| AdminError::ValidationError(format!("Invalid wallet: {}", wallet)))?;
+    let dao = request.dao.parse::<Pubkey>()
+        .map_err(|_| AdminError::ValidationError(format!("Invalid DAO: {}", request.dao)))?;
+    let top_n = match (request.top_n, request.rule) {
+        (Some(top_n), _) if (1..=MAX_REWARD_RECIPIENTS).contains(&top_n) => top_n,
+        (Some(_), _) => return Err(AdminError::ValidationError(format!("top_n must be 1..={}", MAX_REWARD_RECIPIENTS))),
+        (None, RewardRule::TopN) => DEFAULT_TOP_N,
+        (None, RewardRule::Proportional) => MAX_REWARD_RECIPIENTS,
+    };
+    let snapshot = DAO_SNAPSHOTS.get(database, request.dao.as_bytes())
+        .map_err(db_err)?
+        .ok_or_else(|| AdminError::NotFound(format!("Unknown DAO: {}", request.dao)))?;
+    // ProcessTimeout 이 닫은 마지막 라운드. 돌고 있는 라운드는 RoundResult 가 아직 없다.
+    let round = snapshot.round.checked_sub(1)
+        .ok_or_else(|| AdminError::ValidationError(format!("DAO {} has no closed round", request.dao)))?;
+    let min_votes = request.min_votes.unwrap_or(DEFAULT_MIN_REWARD_VOTES).max(1);
+
+    // 지갑 주소가 아닌 작성자는 받을 수 없으니 순위에서 뺀다
+    let standings: Vec<(String, u64)> = round_standings(database, &snapshot, round, min_votes)?
+        .into_iter()
+        .filter(|(author, _)| author.parse::<Pubkey>().is_ok())
+        .collect();
+    let allocations = distribution_weights(&standings, request.rule, top_n);
+    if allocations.is_empty() {
+        return Err(AdminError::ValidationError(format!("DAO {} has no eligible content in round {}", request.dao, round)));
+    }
+
+    let creators = allocations.iter().filter_map(|allocation| allocation.wallet.parse::<Pubkey>().ok()).collect();
+    let weights = allocations.iter().map(|allocation| allocation.weight).collect();
+    let draft = draft_quality_rewards(source, program_id, &signer, &dao, round, creators, weights, lookup_table).await
+        .map_err(|e| AdminError::RpcError(e.to_string()))?;
+    Ok(QualityRewardDraft { dao: request.dao.clone(), round, rule: request.rule, allocations, draft })
+}
+
+// 끝난 라운드의 득표로 품질 보상 분배(DistributeQualityRewards) 초안을 만든다. 관리자 지갑이 서명해서 보낸다.
+pub async fn create_quality_reward_draft<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<QualityRewardRequest>,
+) -> Result<Json<QualityRewardDraft>, AdminError> {
+    let config = tx_config().map_err(|e| AdminError::ConfigError(e.to_string()))?;
+    // 테이블이 설정돼 있으면 늘 v0 로 만든다 (받는 사람이 많으면 레거시 크기를 넘는다)
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), config.lookup_table.is_some()).await
+        .map_err(|e| AdminError::RpcError(e.to_string()))?;
+    Ok(Json(draft_quality_reward(&database, &config.rpc, &config.program_id, &wallet, &request, lookup_table.as_ref()).await?))
+}
+
+#[derive(Deserialize)]
+pub struct TreasuryQuery {
+    dao: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreasuryBalance {
+    pub dao: String,
+    pub dao_name: String,
+    pub lamports: Option<u64>,          // DAO 계정의 실제 잔액 (RPC 가 실패하면 None)
+    pub recorded_deposit: u64,          // 마지막 동기화 때의 상금 풀
+    pub synced_at: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreasuryReport {
+    pub treasuries: Vec<TreasuryBalance>,
+}
+
+pub async fn treasury_report<T: SafeDatabase, C: ChainReader>(database: &T, chain: &C, dao: Option<&str>) -> Result<TreasuryReport, AdminError> {
+    let snapshots = match dao {
+        Some(dao) => vec![DAO_SNAPSHOTS.get(database, dao.as_bytes())
+            .map_err(db_err)?
+            .ok_or_else(|| AdminError::NotFound(format!("Unknown DAO: {}", dao)))?],
+        None => DAO_SNAPSHOTS.scan(database, &KeyRange::all())
+            .map_err(db_err)?
+            .into_iter()
+            .map(|(_, snapshot)| snapshot)
+            .collect(),
+    };
+
+    let mut treasuries = Vec::new();
+    for snapshot in snapshots {
+        let (lamports, error) = match chain.balance(&snapshot.pubkey).await {
+            Ok(lamports) => (Some(lamports), None),
+            Err(e) => (None, Some(e)),
+        };
+        treasuries.push(TreasuryBalance {
+            dao: snapshot.pubkey,
+            dao_name: snapshot.dao_name,
+            lamports,
+            recorded_deposit: snapshot.total_deposit,
+            synced_at: snapshot.synced_at,
+            error,
+        });
+    }
+    Ok(TreasuryReport { treasuries })
+}
+
+pub async fn get_treasury<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<TreasuryQuery>,
+) -> Result<Json<TreasuryReport>, AdminError> {
+    Ok(Json(treasury_report(&database, &chain_reader(), query.dao.as_deref()).await?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::{IndexerCheckpoint, INDEXER_STATE, SUBMISSION_EVENTS};
+    use crate::notifications::NotifierCheckpoint;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::event::{EventRecord, TurtleEvent};
+    use proptest::prelude::*;
+
+    #[tokio::test]
+    async fn test_get_db_stats() {
+        let database = MemoryDatabase::new();
+        database.write("alice", "profile", "user_profiles").unwrap();
+        database.batch_write(&[("a", "1"), ("b", "2")], "content").unwrap();
+
+        let Json(response) = get_db_stats(State(database)).await.unwrap();
+
+        assert_eq!(response.backend, "memory");
+        assert_eq!(response.total_entries, 3);
+        assert_eq!(response.tables.len(), 2);
+        assert!(!response.near_full);
+        assert_eq!(response.map_size, None);
+    }
+
+    struct FakeChain {
+        slot: Result<u64, String>,
+    }
+
+    impl ChainReader for FakeChain {
+        async fn current_slot(&self) -> Result<u64, String> {
+            self.slot.clone()
+        }
+
+        async fn balance(&self, pubkey: &str) -> Result<u64, String> {
+            match pubkey {
+                "dao" => Ok(7_000_000),
+                _ => Err("account not found".to_string()),
+            }
+        }
+    }
+
+    struct FixedBlockhash;
+
+    impl BlockhashSource for FixedBlockhash {
+        async fn latest_blockhash(&self) -> Result<[u8; 32], crate::tx::TxError> {
+            Ok([9; 32])
+        }
+    }
+
+    impl PriorityFeeSource for FixedBlockhash {
+        async fn recent_prioritization_fees(&self, _accounts: &[Pubkey]) -> Result<Vec<u64>, crate::tx::TxError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn snapshot(pubkey: &str, timeout_timestamp: u64, total_deposit: u64) -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: pubkey.to_string(),
+            dao_name: format!("{} name", pubkey),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: true,
+            deposit_share: 10,
+            timeout_timestamp,
+            total_deposit,
+            depositor_count: 1,
+            content_count: 1,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 5,
+        }
+    }
+
+    fn content_key(dao: &str, index: u64) -> Vec<u8> {
+        KeyBuilder::new().str(dao).u64(index).build()
+    }
+
+    #[tokio::test]
+    async fn test_flagged_content_and_override() {
+        let database = MemoryDatabase::new();
+        for index in 0..2 {
+            let content = ChainContent {
+                dao: "dao".to_string(),
+                index,
+                author: "bob".to_string(),
+                text: format!("post {}", index),
+                image_uri: String::new(),
+                timestamp: 0,
+                vote_count: 0,
+                round: 0,
+            };
+            CHAIN_CONTENTS.put(&database, &content_key("dao", index), &content).unwrap();
+        }
+        let rejected = ModerationVerdict {
+            dao: "dao".to_string(),
+            index: 0,
+            verdict: Verdict::Rejected,
+            score: 0.9,
+            categories: vec!["spam".to_string()],
+            provider: "fake".to_string(),
+            moderated_at: 1,
+            overridden: None,
+        };
+        MODERATION_VERDICTS.put(&database, &content_key("dao", 0), &rejected).unwrap();
+
+        let flagged = |dao: Option<&str>| {
+            let query = FlaggedQuery { dao: dao.map(str::to_string), limit: None };
+            list_flagged_content(State(database.clone()), Query(query))
+        };
+        let list = flagged(Some("dao")).await.unwrap().0.flagged;
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].content.as_ref().map(|content| content.text.as_str()), Some("post 0"));
+
+        // 거부된 콘텐츠를 승인하고, 판정 전인 콘텐츠를 거부한다
+        let request = OverrideRequest { dao: "dao".to_string(), index: 0, verdict: Verdict::Approved, reason: Some("false positive".to_string()) };
+        let approved = override_moderation(State(database.clone()), AuthedWallet("mod".to_string()), Json(request)).await.unwrap().0;
+        assert_eq!(approved.verdict, Verdict::Approved);
+        assert_eq!(approved.score, 0.9);
+        assert_eq!(approved.overridden.as_ref().and_then(|overridden| overridden.original), Some(Verdict::Rejected));
+
+        let request = OverrideRequest { dao: "dao".to_string(), index: 1, verdict: Verdict::Rejected, reason: None };
+        let manual = override_moderation(State(database.clone()), AuthedWallet("mod".to_string()), Json(request)).await.unwrap().0;
+        assert_eq!((manual.provider.as_str(), manual.overridden.unwrap().original), ("manual", None));
+
+        let list = flagged(None).await.unwrap().0.flagged;
+        assert_eq!(list.iter().map(|flagged| flagged.verdict.index).collect::<Vec<_>>(), [1]);
+
+        let request = OverrideRequest { dao: "dao".to_string(), index: 9, verdict: Verdict::Rejected, reason: None };
+        let missing = override_moderation(State(database.clone()), AuthedWallet("mod".to_string()), Json(request)).await;
+        assert!(matches!(missing, Err(AdminError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_indexer_lag_and_treasury() {
+        let database = MemoryDatabase::new();
+        let record = EventRecord {
+            id: 3,
+            signature: "sig".to_string(),
+            slot: 90,
+            block_time: Some(1_000),
+            dao: "dao".to_string(),
+            actor: "bob".to_string(),
+            event: TurtleEvent::Submission,
+        };
+        SUBMISSION_EVENTS.put(&database, &event_key(3), &record).unwrap();
+        INDEXER_STATE.put(&database, b"checkpoint", &IndexerCheckpoint { last_signature: Some("sig".to_string()), last_slot: 90, next_event_id: 4 }).unwrap();
+        crate::notifications::NOTIFIER_STATE.put(&database, crate::notifications::CHECKPOINT_KEY, &NotifierCheckpoint { last_event_id: 1, next_notification_id: 0 }).unwrap();
+
+        let status = indexer_status(&database, &FakeChain { slot: Ok(100) }, 1_060).await.unwrap();
+        assert_eq!((status.last_event_id, status.slot_lag, status.seconds_since_last_event), (3, Some(10), Some(60)));
+        assert_eq!(status.consumers.iter().map(|consumer| consumer.behind).collect::<Vec<_>>(), [2, 3, 3, 3]);
+
+        // RPC 가 죽어 있어도 DB 쪽 상태는 보여 준다
+        let status = indexer_status(&database, &FakeChain { slot: Err("timeout".to_string()) }, 1_060).await.unwrap();
+        assert_eq!((status.chain_slot, status.rpc_error.as_deref()), (None, Some("timeout")));
+
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot("dao", 0, 5_000_000)).unwrap();
+        DAO_SNAPSHOTS.put(&database, b"gone", &snapshot("gone", 0, 0)).unwrap();
+        let chain = FakeChain { slot: Ok(100) };
+        let report = treasury_report(&database, &chain, None).await.unwrap();
+        assert_eq!(report.treasuries.len(), 2);
+        assert_eq!((report.treasuries[0].lamports, report.treasuries[0].recorded_deposit), (Some(7_000_000), 5_000_000));
+        assert!(report.treasuries[1].error.is_some());
+        assert!(matches!(treasury_report(&database, &chain, Some("nope")).await, Err(AdminError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reward_drafts_only_for_expired_rounds() {
+        let database = MemoryDatabase::new();
+        let expired = Pubkey([1; 32]).to_string();
+        let running = Pubkey([2; 32]).to_string();
+        DAO_SNAPSHOTS.put(&database, expired.as_bytes(), &snapshot(&expired, 100, 5_000)).unwrap();
+        DAO_SNAPSHOTS.put(&database, running.as_bytes(), &snapshot(&running, 10_000, 5_000)).unwrap();
+
+        let admin = Pubkey([7; 32]).to_string();
+        let program_id = Pubkey([3; 32]);
+        let drafts = draft_rewards(&database, &FixedBlockhash, &program_id, &admin, None, 500).await.unwrap();
+        assert_eq!(drafts.drafts.iter().map(|draft| draft.dao.as_str()).collect::<Vec<_>>(), [expired.as_str()]);
+
+        let not_yet = draft_rewards(&database, &FixedBlockhash, &program_id, &admin, Some(&running), 500).await;
+        assert!(matches!(not_yet, Err(AdminError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_distribution_weights_sum_to_100() {
+        let standings = vec![("a".to_string(), 5), ("b".to_string(), 3), ("c".to_string(), 1), ("d".to_string(), 0)];
+        let weights = |rule, top_n| distribution_weights(&standings, rule, top_n)
+            .into_iter()
+            .map(|allocation| (allocation.wallet, allocation.weight))
+            .collect::<Vec<_>>();
+
+        assert_eq!(weights(RewardRule::Proportional, 10), [("a".to_string(), 56), ("b".to_string(), 33), ("c".to_string(), 11)]);
+        assert_eq!(weights(RewardRule::TopN, 2), [("a".to_string(), 50), ("b".to_string(), 50)]);
+        assert_eq!(weights(RewardRule::TopN, 3), [("a".to_string(), 34), ("b".to_string(), 33), ("c".to_string(), 33)]);
+        assert!(distribution_weights(&standings[3..], RewardRule::TopN, 3).is_empty());
+    }
+
+    proptest! {
+        // 득표가 있는 작성자가 한 명이라도 있으면 가중치는 정확히 100 이 되고, 득표 순서를 거스르지 않는다
+        #[test]
+        fn prop_distribution_weights_fully_distribute(
+            votes in prop::collection::vec(any::<u64>(), 1..30),
+            proportional in any::<bool>(),
+            top_n in 1..=MAX_REWARD_RECIPIENTS,
+        ) {
+            let mut standings: Vec<(String, u64)> = votes.into_iter().enumerate().map(|(i, votes)| (format!("w{}", i), votes)).collect();
+            standings.sort_by_key(|standing| std::cmp::Reverse(standing.1));
+            let rule = match proportional {
+                true => RewardRule::Proportional,
+                false => RewardRule::TopN,
+            };
+
+            let allocations = distribution_weights(&standings, rule, top_n);
+            let voted = standings.iter().filter(|(_, votes)| *votes > 0).count();
+            prop_assert!(allocations.len() <= top_n.min(voted));
+            match voted {
+                0 => prop_assert!(allocations.is_empty()),
+                _ => prop_assert_eq!(allocations.iter().map(|allocation| u32::from(allocation.weight)).sum::<u32>(), 100),
+            }
+            prop_assert!(allocations.iter().all(|allocation| allocation.votes > 0 && allocation.weight > 0));
+            prop_assert!(allocations.windows(2).all(|pair| pair[0].votes >= pair[1].votes));
+        }
+    }
+
+    fn archive(database: &MemoryDatabase, dao: &str, round: u64, index: u64, author: &str, vote_count: u64, verdict: Option<Verdict>) {
+        let content = RoundContent {
+            content: ChainContent {
+                dao: dao.to_string(),
+                index,
+                author: author.to_string(),
+                text: String::new(),
+                image_uri: String::new(),
+                timestamp: 0,
+                vote_count,
+                round,
+            },
+            hidden: false,
+            verdict,
+        };
+        ROUND_CONTENTS.put(database, &KeyBuilder::new().str(dao).u64(round).u64(index).build(), &content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_quality_reward_draft_from_round_votes() {
+        let database = MemoryDatabase::new();
+        let dao = Pubkey([1; 32]).to_string();
+        let (alice, bob) = (Pubkey([4; 32]).to_string(), Pubkey([5; 32]).to_string());
+        // 라운드 0 이 닫혔고 타임아웃이 콘텐츠를 비운 뒤
+        DAO_SNAPSHOTS.put(&database, dao.as_bytes(), &DaoSnapshot { round: 1, ..snapshot(&dao, 100, 5_000) }).unwrap();
+        for (index, author, vote_count) in [(0, alice.as_str(), 2), (1, bob.as_str(), 6), (2, alice.as_str(), 1), (3, "not-a-wallet", 9)] {
+            archive(&database, &dao, 0, index, author, vote_count, Some(Verdict::Approved));
+        }
+
+        let admin = Pubkey([7; 32]).to_string();
+        let request = QualityRewardRequest { dao: dao.clone(), rule: RewardRule::Proportional, top_n: None, min_votes: None };
+        let draft = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await.unwrap();
+        assert_eq!(draft.round, 0);
+        assert_eq!(
+            draft.allocations,
+            [
+                RewardAllocation { wallet: bob.clone(), votes: 6, weight: 67 },
+                RewardAllocation { wallet: alice.clone(), votes: 3, weight: 33 },
+            ]
+        );
+        assert_eq!((draft.draft.dao.as_str(), draft.draft.version.as_str()), (dao.as_str(), "legacy"));
+
+        // 주소 조회 테이블이 있으면 v0 로 만든다
+        let table = AddressLookupTable { key: Pubkey([8; 32]), addresses: vec![dao.parse().unwrap(), bob.parse().unwrap()] };
+        let versioned = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, Some(&table)).await.unwrap();
+        assert_eq!(versioned.draft.version, "v0");
+
+        // 닫힌 라운드가 없으면 만들지 않는다
+        DAO_SNAPSHOTS.put(&database, dao.as_bytes(), &snapshot(&dao, 100, 5_000)).unwrap();
+        let unclosed = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await;
+        assert!(matches!(unclosed, Err(AdminError::ValidationError(_))));
+        let request = QualityRewardRequest { dao, rule: RewardRule::TopN, top_n: Some(MAX_REWARD_RECIPIENTS + 1), min_votes: None };
+        let too_many = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await;
+        assert!(matches!(too_many, Err(AdminError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_quality_reward_eligibility() {
+        let database = MemoryDatabase::new();
+        let dao = Pubkey([1; 32]).to_string();
+        let wallet = |seed: u8| Pubkey([seed; 32]).to_string();
+        DAO_SNAPSHOTS.put(&database, dao.as_bytes(), &DaoSnapshot { round: 2, ..snapshot(&dao, 100, 5_000) }).unwrap();
+        // (index, 작성자, 득표, 판정)
+        let contents = [
+            (0, wallet(10), 4, Some(Verdict::Approved)),
+            (1, wallet(12), 5, Some(Verdict::Rejected)),   // 거절됨
+            (2, wallet(13), 7, None),                      // 아직 판정 없음
+            (3, wallet(14), 1, Some(Verdict::Approved)),   // 득표 부족
+            (4, wallet(15), 8, Some(Verdict::Approved)),   // 숨김
+        ];
+        for (index, author, vote_count, verdict) in contents {
+            archive(&database, &dao, 1, index, &author, vote_count, verdict);
+        }
+        let hidden_key = KeyBuilder::new().str(&dao).u64(1).u64(4).build();
+        let hidden = RoundContent { hidden: true, ..ROUND_CONTENTS.get(&database, &hidden_key).unwrap().unwrap() };
+        ROUND_CONTENTS.put(&database, &hidden_key, &hidden).unwrap();
+        archive(&database, &dao, 0, 0, &wallet(11), 9, Some(Verdict::Approved)); // 그 전 라운드
+
+        let admin = wallet(7);
+        let request = QualityRewardRequest { dao: dao.clone(), rule: RewardRule::Proportional, top_n: None, min_votes: Some(2) };
+        let draft = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await.unwrap();
+        assert_eq!(draft.round, 1);
+        assert_eq!(draft.allocations, [RewardAllocation { wallet: wallet(10), votes: 4, weight: 100 }]);
+
+        // AI 모더레이션을 끈 DAO 는 판정 없는 콘텐츠도 받지만 거절된 콘텐츠는 여전히 뺀다
+        let unmoderated = DaoSnapshot { round: 2, ai_moderation: false, ..snapshot(&dao, 100, 5_000) };
+        DAO_SNAPSHOTS.put(&database, dao.as_bytes(), &unmoderated).unwrap();
+        let draft = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await.unwrap();
+        assert_eq!(
+            draft.allocations,
+            [
+                RewardAllocation { wallet: wallet(13), votes: 7, weight: 64 },
+                RewardAllocation { wallet: wallet(10), votes: 4, weight: 36 },
+            ]
+        );
+
+        // 체인의 후보 (득표 상위 MAX_QUALITY_CANDIDATES 개) 밖의 콘텐츠는 받을 수 없다
+        for index in 5..5 + MAX_QUALITY_CANDIDATES as u64 {
+            archive(&database, &dao, 1, index, &wallet(20), 6, None);
+        }
+        let draft = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await.unwrap();
+        assert_eq!(draft.allocations.iter().map(|allocation| allocation.wallet.clone()).collect::<Vec<_>>(), [wallet(20), wallet(13)]);
+        assert_eq!(draft.allocations[0].votes, 6 * 8);
+
+        // 자격 있는 콘텐츠가 없으면 초안을 만들지 않는다
+        let request = QualityRewardRequest { dao, rule: RewardRule::Proportional, top_n: None, min_votes: Some(100) };
+        let none = draft_quality_reward(&database, &FixedBlockhash, &Pubkey([3; 32]), &admin, &request, None).await;
+        assert!(matches!(none, Err(AdminError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,351 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::analytics::DailyAnalytics;
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use crate::indexer::events_after;
+use crate::scheduler::Scheduler;
+use crate::error::ApiError;
+
+// (dao, period_start) -> DailyAnalytics
+pub const ANALYTICS_DAILY: TypedTable<DailyAnalytics> = TypedTable::new("analytics_daily");
+// (dao, period_start, role, wallet) -> (). 하루에 같은 지갑을 두 번 세지 않도록 남겨 둔다.
+pub const ANALYTICS_WALLETS: TypedTable<()> = TypedTable::new("analytics_wallets");
+pub const ANALYTICS_STATE: TypedTable<AnalyticsCheckpoint> = TypedTable::new("analytics_state");
+pub const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+pub const ANALYTICS_TABLES: &[&str] = &[ANALYTICS_DAILY.name()];
+
+pub const ANALYTICS_INTERVAL: Duration = Duration::from_secs(60);
+// 한 트랜잭션에 반영하는 이벤트 수
+const ANALYTICS_BATCH: usize = 500;
+pub const DEFAULT_ANALYTICS_DAYS: u64 = 30;
+pub const MAX_ANALYTICS_DAYS: u64 = 366;
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// 지갑을 세는 구분 (ANALYTICS_WALLETS 키의 role)
+const ACTIVE: u8 = 0;
+const POSTER: u8 = 1;
+const DEPOSITOR: u8 = 2;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalyticsCheckpoint {
+    pub last_event_id: u64,             // 이 ID 까지의 인덱싱된 이벤트는 집계에 들어갔다
+    // dao -> 지난 분배 이후 쌓인 상금 풀 (block_time 이 없는 이벤트도 반영한다)
+    pub pots: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsGranularity {
+    #[default]
+    Day,
+}
+
+#[derive(Deserialize)]
+pub struct AnalyticsQuery {
+    granularity: Option<AnalyticsGranularity>,
+    // block_time 기준 [from, to). 기본은 최근 30일.
+    from: Option<u64>,
+    to: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalyticsResponse {
+    pub dao: String,
+    pub granularity: AnalyticsGranularity,
+    // 이 이벤트 ID 까지 집계됐다
+    pub last_event_id: u64,
+    // 구간의 모든 날. 이벤트가 없던 날은 0 이고 tvl 만 앞날에서 이어 온다.
+    pub buckets: Vec<DailyAnalytics>,
+}
+
+#[derive(Debug)]
+pub enum AnalyticsError {
+    DatabaseError(String),
+    ValidationError(String),
+}
+
+impl fmt::Display for AnalyticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalyticsError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            AnalyticsError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+        }
+    }
+}
+
+impl StdError for AnalyticsError {}
+
+impl IntoResponse for AnalyticsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AnalyticsError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AnalyticsError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> AnalyticsError {
+    AnalyticsError::DatabaseError(e.to_string())
+}
+
+fn daily_key(dao: &str, period_start: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(period_start).build()
+}
+
+fn wallet_key(dao: &str, period_start: u64, role: u8, wallet: &str) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(period_start).u8(role).str(wallet).build()
+}
+
+fn day_start(time: u64) -> u64 {
+    time / SECONDS_PER_DAY * SECONDS_PER_DAY
+}
+
+// 한 배치 동안 고친 하루 집계와 새로 센 지갑
+struct Rollup<'a, T> {
+    database: &'a T,
+    buckets: BTreeMap<Vec<u8>, DailyAnalytics>,
+    counted: HashSet<Vec<u8>>,
+}
+
+impl<T: SafeDatabase> Rollup<'_, T> {
+    fn bucket(&mut self, dao: &str, period_start: u64) -> Result<&mut DailyAnalytics, AnalyticsError> {
+        let key = daily_key(dao, period_start);
+        if !self.buckets.contains_key(&key) {
+            let bucket = ANALYTICS_DAILY.get(self.database, &key).map_err(db_err)?.unwrap_or_else(|| DailyAnalytics {
+                dao: dao.to_string(),
+                period_start,
+                ..Default::default()
+            });
+            self.buckets.insert(key.clone(), bucket);
+        }
+        Ok(self.buckets.get_mut(&key).unwrap())
+    }
+
+    // 그날 처음 보는 지갑이면 true
+    fn first_seen(&mut self, dao: &str, period_start: u64, role: u8, wallet: &str) -> Result<bool, AnalyticsError> {
+        let key = wallet_key(dao, period_start, role, wallet);
+        if self.counted.contains(&key) || ANALYTICS_WALLETS.get(self.database, &key).map_err(db_err)?.is_some() {
+            return Ok(false);
+        }
+        self.counted.insert(key);
+        Ok(true)
+    }
+
+    fn add(&mut self, record: &EventRecord, pot: u64, claimed: u64) -> Result<(), AnalyticsError> {
+        // 날짜를 모르는 이벤트는 상금 풀에만 반영된다
+        let Some(time) = record.block_time else {
+            return Ok(());
+        };
+        let (dao, day) = (record.dao.as_str(), day_start(time));
+        let active = self.first_seen(dao, day, ACTIVE, &record.actor)?;
+        let role = match record.event {
+            TurtleEvent::Submission => Some(POSTER),
+            TurtleEvent::Deposit { .. } => Some(DEPOSITOR),
+            _ => None,
+        };
+        let first_in_role = match role {
+            Some(role) => self.first_seen(dao, day, role, &record.actor)?,
+            None => false,
+        };
+
+        let bucket = self.bucket(dao, day)?;
+        bucket.active_wallets += u64::from(active);
+        match &record.event {
+            TurtleEvent::Deposit { amount } => {
+                bucket.deposits += 1;
+                bucket.deposit_volume += amount;
+                bucket.unique_depositors += u64::from(first_in_role);
+            },
+            TurtleEvent::Submission => {
+                bucket.submissions += 1;
+                bucket.unique_posters += u64::from(first_in_role);
+            },
+            TurtleEvent::Vote { .. } => bucket.votes += 1,
+            TurtleEvent::Claim { winner: Some(_) } => {
+                bucket.claims += 1;
+                bucket.claim_amount += claimed;
+            },
+            TurtleEvent::Claim { winner: None } => {},
+        }
+        bucket.tvl = pot;
+        Ok(())
+    }
+}
+
+// 체크포인트 다음의 이벤트를 한 배치만큼 하루 집계에 더하고, 반영한 이벤트 수를 돌려준다.
+// 집계와 체크포인트는 한 트랜잭션으로 쓰므로 중간에 멈춰도 두 번 세지 않는다.
+pub fn roll_up_events<T: SafeDatabase>(database: &T) -> Result<usize, AnalyticsError> {
+    let mut checkpoint = ANALYTICS_STATE.get(database, CHECKPOINT_KEY).map_err(db_err)?.unwrap_or_default();
+    let events = events_after(database, checkpoint.last_event_id, ANALYTICS_BATCH).map_err(db_err)?;
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    let mut rollup = Rollup { database, buckets: BTreeMap::new(), counted: HashSet::new() };
+    for record in &events {
+        // 우승자가 있는 타임아웃이 풀 전체를 나눠 준다. 제출이 없던 라운드는 다음 라운드로 넘어간다.
+        let pot = checkpoint.pots.entry(record.dao.clone()).or_default();
+        let claimed = match &record.event {
+            TurtleEvent::Deposit { amount } => {
+                *pot += amount;
+                0
+            },
+            TurtleEvent::Claim { winner: Some(_) } => std::mem::take(pot),
+            _ => 0,
+        };
+        let pot = *pot;
+        rollup.add(record, pot, claimed)?;
+        checkpoint.last_event_id = record.id;
+    }
+
+    let mut ops = Vec::new();
+    for (key, bucket) in &rollup.buckets {
+        ops.push(ANALYTICS_DAILY.put_op(key, bucket).map_err(db_err)?);
+    }
+    for key in &rollup.counted {
+        ops.push(ANALYTICS_WALLETS.put_op(key, &()).map_err(db_err)?);
+    }
+    ops.push(ANALYTICS_STATE.put_op(CHECKPOINT_KEY, &checkpoint).map_err(db_err)?);
+    database.txn(&ops).map_err(db_err)?;
+    Ok(events.len())
+}
+
+pub fn register_analytics_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>) {
+    scheduler.every("analytics", ANALYTICS_INTERVAL, ANALYTICS_INTERVAL / 6, |database| async move {
+        // 밀린 이벤트가 많으면 배치를 이어서 따라잡는다
+        while roll_up_events(&database).map_err(|e| e.to_string())? == ANALYTICS_BATCH {}
+        Ok(())
+    });
+}
+
+// [from, to) 의 하루 집계. 빈 날도 채워서 돌려준다.
+pub fn daily_series<T: SafeDatabase>(database: &T, dao: &str, from: u64, to: u64) -> Result<Vec<DailyAnalytics>, AnalyticsError> {
+    let from = day_start(from);
+    // 구간 앞의 마지막 날에서 tvl 을 이어 온다
+    let before = ANALYTICS_DAILY.scan(database, &KeyRange::between(daily_key(dao, 0), daily_key(dao, from))).map_err(db_err)?;
+    let mut tvl = before.last().map_or(0, |(_, bucket)| bucket.tvl);
+    let mut stored: BTreeMap<u64, DailyAnalytics> = ANALYTICS_DAILY
+        .scan(database, &KeyRange::between(daily_key(dao, from), daily_key(dao, to)))
+        .map_err(db_err)?
+        .into_iter()
+        .map(|(_, bucket)| (bucket.period_start, bucket))
+        .collect();
+
+    let mut buckets = Vec::new();
+    for period_start in (from..to).step_by(SECONDS_PER_DAY as usize) {
+        let bucket = stored.remove(&period_start).unwrap_or_else(|| DailyAnalytics {
+            dao: dao.to_string(),
+            period_start,
+            tvl,
+            ..Default::default()
+        });
+        tvl = bucket.tvl;
+        buckets.push(bucket);
+    }
+    Ok(buckets)
+}
+
+// DAO 의 하루 단위 활동 지갑/예치/상금 풀 추이. 집계 작업이 1분마다 새 이벤트를 반영한다.
+pub async fn get_dao_analytics<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(pubkey): Path<String>,
+    Query(query): Query<AnalyticsQuery>,
+) -> Result<Json<AnalyticsResponse>, AnalyticsError> {
+    let to = query.to.unwrap_or_else(|| day_start(now_secs()) + SECONDS_PER_DAY);
+    let from = query.from.unwrap_or(to.saturating_sub(DEFAULT_ANALYTICS_DAYS * SECONDS_PER_DAY));
+    if from >= to {
+        return Err(AnalyticsError::ValidationError("from must be before to".to_string()));
+    }
+    if to - from > MAX_ANALYTICS_DAYS * SECONDS_PER_DAY {
+        return Err(AnalyticsError::ValidationError(format!("range must be at most {} days", MAX_ANALYTICS_DAYS)));
+    }
+
+    let checkpoint = ANALYTICS_STATE.get(&database, CHECKPOINT_KEY).map_err(db_err)?.unwrap_or_default();
+    Ok(Json(AnalyticsResponse {
+        granularity: query.granularity.unwrap_or_default(),
+        last_event_id: checkpoint.last_event_id,
+        buckets: daily_series(&database, &pubkey, from, to)?,
+        dao: pubkey,
+    }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+    use crate::indexer::{event_key, CLAIM_EVENTS, DEPOSIT_EVENTS, SUBMISSION_EVENTS, VOTE_EVENTS};
+
+    const DAY1: u64 = 20_000 * SECONDS_PER_DAY;
+
+    fn put(database: &MemoryDatabase, id: u64, actor: &str, block_time: Option<u64>, event: TurtleEvent) {
+        let table = match event {
+            TurtleEvent::Deposit { .. } => DEPOSIT_EVENTS,
+            TurtleEvent::Submission => SUBMISSION_EVENTS,
+            TurtleEvent::Vote { .. } => VOTE_EVENTS,
+            TurtleEvent::Claim { .. } => CLAIM_EVENTS,
+        };
+        let record = EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time,
+            dao: "dao".to_string(),
+            actor: actor.to_string(),
+            event,
+        };
+        table.put(database, &event_key(id), &record).unwrap();
+    }
+
+    #[test]
+    fn test_rolls_events_into_daily_buckets() {
+        let database = MemoryDatabase::new();
+        put(&database, 1, "alice", Some(DAY1 + 10), TurtleEvent::Deposit { amount: 100 });
+        put(&database, 2, "alice", Some(DAY1 + 20), TurtleEvent::Deposit { amount: 50 });
+        put(&database, 3, "bob", Some(DAY1 + 30), TurtleEvent::Submission);
+        put(&database, 4, "bob", None, TurtleEvent::Deposit { amount: 25 });
+        assert_eq!(roll_up_events(&database).unwrap(), 4);
+
+        // 다음 배치에서 같은 날 같은 지갑은 다시 세지 않는다
+        put(&database, 5, "alice", Some(DAY1 + 40), TurtleEvent::Submission);
+        put(&database, 6, "carol", Some(DAY1 + 50), TurtleEvent::Vote { proposal_id: 0 });
+        put(&database, 7, "carol", Some(DAY1 + 2 * SECONDS_PER_DAY), TurtleEvent::Claim { winner: Some("bob".to_string()) });
+        assert_eq!(roll_up_events(&database).unwrap(), 3);
+        assert_eq!(roll_up_events(&database).unwrap(), 0);
+
+        let day1 = ANALYTICS_DAILY.get(&database, &daily_key("dao", DAY1)).unwrap().unwrap();
+        assert_eq!(
+            (day1.active_wallets, day1.unique_depositors, day1.unique_posters, day1.deposits, day1.deposit_volume, day1.submissions, day1.votes, day1.tvl),
+            (3, 1, 2, 2, 150, 2, 1, 175),
+        );
+
+        let series = daily_series(&database, "dao", DAY1, DAY1 + 3 * SECONDS_PER_DAY).unwrap();
+        let summary: Vec<(u64, u64, u64, u64)> = series.iter().map(|bucket| (bucket.period_start - DAY1, bucket.claims, bucket.claim_amount, bucket.tvl)).collect();
+        assert_eq!(summary, [(0, 0, 0, 175), (SECONDS_PER_DAY, 0, 0, 175), (2 * SECONDS_PER_DAY, 1, 175, 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_ranges() {
+        let database = MemoryDatabase::new();
+        let query = |from, to| Query(AnalyticsQuery { granularity: None, from: Some(from), to: Some(to) });
+        let analytics = |from, to| get_dao_analytics(State(database.clone()), Path("dao".to_string()), query(from, to));
+
+        assert!(matches!(analytics(DAY1, DAY1).await, Err(AnalyticsError::ValidationError(_))));
+        assert!(matches!(analytics(0, DAY1).await, Err(AnalyticsError::ValidationError(_))));
+        let Json(response) = analytics(DAY1, DAY1 + 7 * SECONDS_PER_DAY).await.unwrap();
+        assert_eq!((response.buckets.len(), response.last_event_id), (7, 0));
+    }
+}
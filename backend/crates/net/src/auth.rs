@@ -0,0 +1,383 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+use axum::Json;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sol::state::Pubkey;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::TtlTable;
+use crate::session::{issue_session, TokenPair};
+use crate::error::ApiError;
+
+// 로그인 챌린지(nonce)는 TTL 테이블에 두고 주기적으로 정리된다 (ttl::TTL_TABLES)
+pub const NONCE_TABLE: &str = "nonces";
+// 챌린지를 받은 뒤 서명해서 돌려줘야 하는 시간
+pub const NONCE_TTL: Duration = Duration::from_secs(5 * 60);
+
+const NONCE_BYTES: usize = 16;
+// 로그인 챌린지도 purpose 가 sign_in 인 nonce 다
+pub const SIGN_IN_PURPOSE: &str = "sign_in";
+const MAX_PURPOSE_LEN: usize = 32;
+
+#[derive(Deserialize)]
+pub struct ChallengeRequest {
+    wallet: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeResponse {
+    wallet: String,
+    nonce: String,
+    // 지갑이 signMessage 로 서명해야 하는 원문
+    message: String,
+    expires_at: u64,
+}
+
+#[derive(Deserialize)]
+pub struct NonceRequest {
+    wallet: String,
+    // nonce 를 쓸 요청 종류. 다른 purpose 의 요청에는 쓸 수 없다.
+    purpose: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NonceResponse {
+    wallet: String,
+    purpose: String,
+    nonce: String,
+    // 서명할 메시지의 앞부분. 요청 본문(payload)을 뒤에 그대로 붙여서 서명한다.
+    message_prefix: String,
+    expires_at: u64,
+}
+
+// 세션 대신 지갑 서명으로 인증하는 요청이 함께 보내는 값
+#[derive(Clone, Deserialize)]
+pub struct SignedRequest {
+    pub wallet: String,
+    pub nonce: String,
+    // 메시지에 대한 ed25519 서명 (base58)
+    pub signature: String,
+}
+
+pub type VerifyRequest = SignedRequest;
+
+#[derive(Debug)]
+pub enum AuthError {
+    DatabaseError(String),
+    ValidationError(String),
+    Unauthorized(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            AuthError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            AuthError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+        }
+    }
+}
+
+impl StdError for AuthError {}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            AuthError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            AuthError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            AuthError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+fn random_nonce() -> String {
+    let mut bytes = [0u8; NONCE_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    bs58::encode(bytes).into_string()
+}
+
+fn parse_wallet(wallet: &str) -> Result<Pubkey, AuthError> {
+    wallet.parse::<Pubkey>()
+        .map_err(|_| AuthError::ValidationError(format!("Invalid wallet: {}", wallet)))
+}
+
+// 지갑이 서명하는 메시지. 챌린지와 검증 양쪽에서 같은 값을 만들어야 한다.
+pub fn sign_in_message(wallet: &str, nonce: &str) -> String {
+    format!("Turtle wants you to sign in with your Solana account:\n{}\n\nNonce: {}", wallet, nonce)
+}
+
+// 로그인 외의 서명 요청이 서명하는 메시지. payload 는 요청 본문을 정해진 형태로 적은 것이다.
+pub fn signed_request_message(purpose: &str, wallet: &str, nonce: &str, payload: &str) -> String {
+    format!("Turtle signed request\nPurpose: {}\nWallet: {}\nNonce: {}\n\n{}", purpose, wallet, nonce, payload)
+}
+
+fn validate_purpose(purpose: &str) -> Result<(), AuthError> {
+    let valid = !purpose.is_empty()
+        && purpose.len() <= MAX_PURPOSE_LEN
+        && purpose.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !valid {
+        return Err(AuthError::ValidationError(format!("Invalid purpose: {}", purpose)));
+    }
+    Ok(())
+}
+
+// nonce 에 묶어 두는 값. 발급받은 지갑과 purpose 가 모두 맞아야 쓸 수 있다.
+fn nonce_binding(purpose: &str, wallet: &str) -> String {
+    format!("{}:{}", purpose, wallet)
+}
+
+pub fn issue_nonce<T: SafeDatabase>(database: &T, wallet: &str, purpose: &str) -> Result<(String, u64), AuthError> {
+    parse_wallet(wallet)?;
+    validate_purpose(purpose)?;
+
+    let nonce = random_nonce();
+    let expires_at = TtlTable::new(database.clone(), NONCE_TABLE)
+        .put(&nonce, nonce_binding(purpose, wallet).as_bytes(), NONCE_TTL)
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+    Ok((nonce, expires_at))
+}
+
+// 서명이 맞으면 nonce 를 지우고 지갑을 돌려준다. 만료됐거나 이미 쓴 nonce 는 거부한다.
+// 서명이 틀리면 nonce 는 남겨 둔다 (nonce 만 훔쳐 본 쪽이 정상 요청을 막지 못하게).
+pub fn consume_nonce<T: SafeDatabase>(
+    database: &T,
+    request: &SignedRequest,
+    purpose: &str,
+    message: &str,
+) -> Result<Pubkey, AuthError> {
+    let wallet = check_nonce(database, &request.wallet, &request.nonce, purpose)?;
+    verify_signature(&wallet, message.as_bytes(), &request.signature)?;
+    spend_nonce(database, &request.nonce)?;
+    Ok(wallet)
+}
+
+fn unknown_nonce() -> AuthError {
+    AuthError::Unauthorized("Unknown or expired nonce".to_string())
+}
+
+// nonce 가 이 지갑과 purpose 로 발급됐고 아직 살아 있는지만 본다 (지우지 않는다).
+// 서명 대신 다른 증명(memo 트랜잭션 등) 을 확인하는 요청은 이것과 spend_nonce 를 따로 부른다.
+pub fn check_nonce<T: SafeDatabase>(database: &T, wallet: &str, nonce: &str, purpose: &str) -> Result<Pubkey, AuthError> {
+    let parsed = parse_wallet(wallet)?;
+    let bound_to = TtlTable::new(database.clone(), NONCE_TABLE).get(nonce)
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+    if bound_to.as_deref() != Some(nonce_binding(purpose, wallet).as_bytes()) {
+        return Err(unknown_nonce());
+    }
+    Ok(parsed)
+}
+
+// 같은 요청이 동시에 들어오면 먼저 지운 쪽만 통과한다
+pub fn spend_nonce<T: SafeDatabase>(database: &T, nonce: &str) -> Result<(), AuthError> {
+    let removed = TtlTable::new(database.clone(), NONCE_TABLE).remove(nonce)
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+    if !removed {
+        return Err(unknown_nonce());
+    }
+    Ok(())
+}
+
+fn verify_signature(wallet: &Pubkey, message: &[u8], signature: &str) -> Result<(), AuthError> {
+    let invalid = || AuthError::Unauthorized("Invalid signature".to_string());
+
+    let bytes = bs58::decode(signature).into_vec().map_err(|_| invalid())?;
+    let signature = Signature::from_slice(&bytes).map_err(|_| invalid())?;
+    let key = VerifyingKey::from_bytes(&wallet.to_bytes()).map_err(|_| invalid())?;
+
+    key.verify(message, &signature).map_err(|_| invalid())
+}
+
+pub async fn create_challenge<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<ChallengeRequest>,
+) -> Result<Json<ChallengeResponse>, AuthError> {
+    let (nonce, expires_at) = issue_nonce(&database, &request.wallet, SIGN_IN_PURPOSE)?;
+
+    Ok(Json(ChallengeResponse {
+        message: sign_in_message(&request.wallet, &nonce),
+        wallet: request.wallet,
+        nonce,
+        expires_at,
+    }))
+}
+
+pub async fn verify_challenge<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<VerifyRequest>,
+) -> Result<Json<TokenPair>, AuthError> {
+    let message = sign_in_message(&request.wallet, &request.nonce);
+    consume_nonce(&database, &request, SIGN_IN_PURPOSE, &message)?;
+
+    Ok(Json(issue_session(&request.wallet)?))
+}
+
+// 세션 없이 서명으로 인증하는 요청에 쓸 nonce. 요청은 signed_request_message 를 서명해서 보낸다.
+pub async fn create_nonce<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<NonceRequest>,
+) -> Result<Json<NonceResponse>, AuthError> {
+    let (nonce, expires_at) = issue_nonce(&database, &request.wallet, &request.purpose)?;
+
+    Ok(Json(NonceResponse {
+        message_prefix: signed_request_message(&request.purpose, &request.wallet, &nonce, ""),
+        wallet: request.wallet,
+        purpose: request.purpose,
+        nonce,
+        expires_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use crate::roles::{restricted, Role};
+    use crate::session::{verify_token, AuthedWallet, TokenKind};
+    use ed25519_dalek::{Signer, SigningKey};
+    use tower::ServiceExt;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn wallet_of(key: &SigningKey) -> String {
+        bs58::encode(key.verifying_key().to_bytes()).into_string()
+    }
+
+    async fn sign_in(database: &MemoryDatabase, key: &SigningKey) -> Result<Json<TokenPair>, AuthError> {
+        let wallet = wallet_of(key);
+        let Json(challenge) = create_challenge(State(database.clone()), Json(ChallengeRequest { wallet: wallet.clone() })).await?;
+        let signature = key.sign(challenge.message.as_bytes());
+
+        verify_challenge(State(database.clone()), Json(VerifyRequest {
+            wallet,
+            nonce: challenge.nonce,
+            signature: bs58::encode(signature.to_bytes()).into_string(),
+        })).await
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_issues_session() {
+        let database = MemoryDatabase::new();
+        let key = signing_key();
+
+        let Json(session) = sign_in(&database, &key).await.unwrap();
+        assert_eq!(session.wallet, wallet_of(&key));
+        assert_eq!(verify_token(&database, &session.token, TokenKind::Access).unwrap().sub, wallet_of(&key));
+        assert!(verify_token(&database, "unknown", TokenKind::Access).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_signature_and_reused_nonce() {
+        let database = MemoryDatabase::new();
+        let key = signing_key();
+        let wallet = wallet_of(&key);
+
+        let Json(challenge) = create_challenge(State(database.clone()), Json(ChallengeRequest { wallet: wallet.clone() })).await.unwrap();
+        let request = |signature: &[u8]| VerifyRequest {
+            wallet: wallet.clone(),
+            nonce: challenge.nonce.clone(),
+            signature: bs58::encode(signature).into_string(),
+        };
+
+        // 다른 키로 서명하면 거부
+        let forged = SigningKey::from_bytes(&[8; 32]).sign(challenge.message.as_bytes());
+        assert!(matches!(
+            verify_challenge(State(database.clone()), Json(request(&forged.to_bytes()))).await,
+            Err(AuthError::Unauthorized(_))
+        ));
+
+        let signature = key.sign(challenge.message.as_bytes());
+        assert!(verify_challenge(State(database.clone()), Json(request(&signature.to_bytes()))).await.is_ok());
+        assert!(matches!(
+            verify_challenge(State(database.clone()), Json(request(&signature.to_bytes()))).await,
+            Err(AuthError::Unauthorized(_))
+        ));
+
+        assert!(matches!(
+            create_challenge(State(database), Json(ChallengeRequest { wallet: "not-a-wallet".to_string() })).await,
+            Err(AuthError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_nonce_is_single_use() {
+        let database = MemoryDatabase::new();
+        let key = signing_key();
+        let wallet = wallet_of(&key);
+
+        let Json(issued) = create_nonce(State(database.clone()), Json(NonceRequest {
+            wallet: wallet.clone(),
+            purpose: "profile_update".to_string(),
+        })).await.unwrap();
+        let message = signed_request_message("profile_update", &wallet, &issued.nonce, "bio=hi");
+        assert_eq!(message, format!("{}bio=hi", issued.message_prefix));
+        let request = SignedRequest {
+            wallet: wallet.clone(),
+            nonce: issued.nonce.clone(),
+            signature: bs58::encode(key.sign(message.as_bytes()).to_bytes()).into_string(),
+        };
+
+        // 다른 purpose 로는 쓸 수 없다
+        assert!(matches!(consume_nonce(&database, &request, "vote", &message), Err(AuthError::Unauthorized(_))));
+        assert_eq!(consume_nonce(&database, &request, "profile_update", &message).unwrap().to_string(), wallet);
+        // 같은 요청을 다시 보내면 거부
+        assert!(matches!(consume_nonce(&database, &request, "profile_update", &message), Err(AuthError::Unauthorized(_))));
+
+        // 만료된 nonce
+        let (stale, _) = issue_nonce(&database, &wallet, "profile_update").unwrap();
+        TtlTable::new(database.clone(), NONCE_TABLE)
+            .put_until(&stale, nonce_binding("profile_update", &wallet).as_bytes(), 1)
+            .unwrap();
+        let message = signed_request_message("profile_update", &wallet, &stale, "bio=hi");
+        let request = SignedRequest {
+            wallet: wallet.clone(),
+            nonce: stale,
+            signature: bs58::encode(key.sign(message.as_bytes()).to_bytes()).into_string(),
+        };
+        assert!(matches!(consume_nonce(&database, &request, "profile_update", &message), Err(AuthError::Unauthorized(_))));
+
+        assert!(matches!(issue_nonce(&database, &wallet, "Bad Purpose"), Err(AuthError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_protected_route_requires_session() {
+        let database = MemoryDatabase::new();
+        let Json(session) = sign_in(&database, &signing_key()).await.unwrap();
+
+        let component = (
+            "/secret".to_string(),
+            Router::new().route("/secret", get(|Extension(AuthedWallet(wallet)): Extension<AuthedWallet>| async move { wallet })),
+        );
+        let (_, router) = restricted(component, &database, Role::User);
+        let app = router.with_state(database);
+
+        let send = |authorization: Option<String>| {
+            let mut request = Request::builder().uri("/secret");
+            if let Some(authorization) = authorization {
+                request = request.header(axum::http::header::AUTHORIZATION, authorization);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        assert_eq!(send(None).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(send(Some("Bearer nope".to_string())).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let response = send(Some(format!("Bearer {}", session.token))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, session.wallet.as_bytes());
+    }
+}
@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_database::watch::ChangeFeed;
+use turtle_service::parser::event::TurtleEvent;
+use crate::indexer::{events_after, load_checkpoint, EVENT_TABLES};
+use crate::sync::DAO_SNAPSHOTS;
+
+// 쉼표로 구분한 Discord 웹훅 URL 들
+pub const DISCORD_WEBHOOKS_ENV: &str = "TURTLE_DISCORD_WEBHOOK_URLS";
+pub const TELEGRAM_BOT_TOKEN_ENV: &str = "TURTLE_TELEGRAM_BOT_TOKEN";
+// 쉼표로 구분한 Telegram chat id 들
+pub const TELEGRAM_CHAT_IDS_ENV: &str = "TURTLE_TELEGRAM_CHAT_IDS";
+// 있으면 이 DAO 들의 이벤트만 보낸다
+pub const BRIDGE_DAOS_ENV: &str = "TURTLE_BRIDGE_DAOS";
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
+pub const BRIDGE_STATE: TypedTable<BridgeCheckpoint> = TypedTable::new("bridge_state");
+// dao -> 지금 라운드가 시작된 시각. Claim 이 나오면 지운다.
+pub const BRIDGE_ROUNDS: TypedTable<u64> = TypedTable::new("bridge_rounds");
+// (dao, timeout_timestamp) -> 카운트다운 알림을 보낸 시각
+pub const BRIDGE_WARNED: TypedTable<u64> = TypedTable::new("bridge_warned");
+pub const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+
+// 카운트다운이 이만큼 남으면 알린다
+pub const COUNTDOWN_WARNING_SECS: u64 = 600;
+// 쓰기 알림이 없어도 카운트다운을 확인하는 주기
+const BRIDGE_TICK: Duration = Duration::from_secs(15);
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+const EVENT_BATCH: usize = 500;
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeCheckpoint {
+    pub last_event_id: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_ids: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BridgeConfig {
+    pub discord_webhooks: Vec<String>,
+    pub telegram: Option<TelegramConfig>,
+    pub daos: Option<BTreeSet<String>>,
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl BridgeConfig {
+    // 보낼 곳이 하나도 없으면 브리지를 띄우지 않는다
+    pub fn from_env() -> Option<Self> {
+        let env_list = |name: &str| std::env::var(name).map(|value| parse_list(&value)).unwrap_or_default();
+
+        let discord_webhooks = env_list(DISCORD_WEBHOOKS_ENV);
+        let chat_ids = env_list(TELEGRAM_CHAT_IDS_ENV);
+        let telegram = std::env::var(TELEGRAM_BOT_TOKEN_ENV)
+            .ok()
+            .filter(|token| !token.is_empty() && !chat_ids.is_empty())
+            .map(|bot_token| TelegramConfig { bot_token, chat_ids });
+        let daos = Some(env_list(BRIDGE_DAOS_ENV))
+            .filter(|daos| !daos.is_empty())
+            .map(|daos| daos.into_iter().collect());
+
+        if discord_webhooks.is_empty() && telegram.is_none() {
+            return None;
+        }
+        Some(Self { discord_webhooks, telegram, daos })
+    }
+
+    fn wants(&self, dao: &str) -> bool {
+        self.daos.as_ref().is_none_or(|daos| daos.contains(dao))
+    }
+
+    // (URL, JSON 본문) 목록. Discord 는 content, Telegram 은 sendMessage 형식이다.
+    fn requests(&self, text: &str) -> Vec<(String, Value)> {
+        let mut requests: Vec<(String, Value)> = self.discord_webhooks.iter()
+            .map(|url| (url.clone(), json!({ "content": text })))
+            .collect();
+        if let Some(telegram) = &self.telegram {
+            let url = format!("{}/bot{}/sendMessage", TELEGRAM_API, telegram.bot_token);
+            requests.extend(telegram.chat_ids.iter().map(|chat_id| {
+                (url.clone(), json!({ "chat_id": chat_id, "text": text, "disable_web_page_preview": true }))
+            }));
+        }
+        requests
+    }
+}
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum BridgeError {
+    DatabaseError(String),
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for BridgeError {}
+
+fn db_err(e: impl fmt::Display) -> BridgeError {
+    BridgeError::DatabaseError(e.to_string())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BridgeEvent {
+    NewRound { dao: String, opened_by: String },
+    CountdownEnding { dao: String, seconds_left: u64, pot: u64 },
+    WinnerClaimed { dao: String, winner: Option<String> },
+}
+
+impl BridgeEvent {
+    fn dao(&self) -> &str {
+        match self {
+            BridgeEvent::NewRound { dao, .. } => dao,
+            BridgeEvent::CountdownEnding { dao, .. } => dao,
+            BridgeEvent::WinnerClaimed { dao, .. } => dao,
+        }
+    }
+}
+
+fn short(address: &str) -> String {
+    match (address.get(..4), address.get(address.len().saturating_sub(4)..)) {
+        (Some(head), Some(tail)) if address.len() > 12 => format!("{}…{}", head, tail),
+        _ => address.to_string(),
+    }
+}
+
+fn format_sol(lamports: u64) -> String {
+    let sol = format!("{}.{:09}", lamports / LAMPORTS_PER_SOL, lamports % LAMPORTS_PER_SOL);
+    sol.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// 채팅방에 올릴 한 줄짜리 메시지. DAO 이름은 스냅샷에 있으면 쓴다.
+pub fn format_message(event: &BridgeEvent, dao_name: Option<&str>) -> String {
+    let dao = dao_name.map(str::to_string).unwrap_or_else(|| short(event.dao()));
+    match event {
+        BridgeEvent::NewRound { opened_by, .. } => {
+            format!("🐢 New round in {}: {} posted the first submission. The countdown is running!", dao, short(opened_by))
+        },
+        BridgeEvent::CountdownEnding { seconds_left, pot, .. } => {
+            format!("⏳ {}: {} minutes left on the countdown. Pot: {} SOL", dao, seconds_left.div_ceil(60), format_sol(*pot))
+        },
+        BridgeEvent::WinnerClaimed { winner: Some(winner), .. } => {
+            format!("🏆 {}: {} won the round and claimed the pot!", dao, short(winner))
+        },
+        BridgeEvent::WinnerClaimed { winner: None, .. } => {
+            format!("🏁 {}: the round ended without a winner.", dao)
+        },
+    }
+}
+
+fn warned_key(dao: &str, timeout_timestamp: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(timeout_timestamp).build()
+}
+
+// 처음 실행할 때는 인덱서가 지금까지 읽은 이벤트를 건너뛰고, 타이머가 돌고 있는 DAO 는 라운드 중으로 본다
+fn initialize<T: SafeDatabase>(database: &T, now: u64) -> Result<(), BridgeError> {
+    let indexed = load_checkpoint(database).map_err(db_err)?;
+    let mut ops = Vec::new();
+    for (key, snapshot) in DAO_SNAPSHOTS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        if snapshot.timeout_timestamp > now {
+            ops.push(BRIDGE_ROUNDS.put_op(&key, &now).map_err(db_err)?);
+        }
+    }
+    let checkpoint = BridgeCheckpoint { last_event_id: indexed.next_event_id.saturating_sub(1) };
+    ops.push(BRIDGE_STATE.put_op(CHECKPOINT_KEY, &checkpoint).map_err(db_err)?);
+    database.txn(&ops).map_err(db_err)
+}
+
+// 체크포인트 이후의 이벤트와 카운트다운을 브리지 이벤트로 바꾸고 상태를 한 트랜잭션으로 남긴다.
+// 채팅 메시지는 보내기 전에 처리한 것으로 기록하므로 실패해도 다시 보내지 않는다 (최대 한 번).
+pub fn collect_bridge_events<T: SafeDatabase>(database: &T, now: u64) -> Result<Vec<BridgeEvent>, BridgeError> {
+    let Some(mut checkpoint) = BRIDGE_STATE.get(database, CHECKPOINT_KEY).map_err(db_err)? else {
+        initialize(database, now)?;
+        return Ok(Vec::new());
+    };
+
+    let mut events = Vec::new();
+    let mut ops: Vec<KvOp> = Vec::new();
+    // 이번 회차에서 바뀐 라운드 상태 (아직 DB 에 쓰기 전이라 따로 들고 있는다)
+    let mut rounds: Vec<(String, bool)> = Vec::new();
+    let round_open = |rounds: &[(String, bool)], dao: &str| -> Result<bool, BridgeError> {
+        match rounds.iter().rev().find(|(open_dao, _)| open_dao == dao) {
+            Some((_, open)) => Ok(*open),
+            None => Ok(BRIDGE_ROUNDS.get(database, dao.as_bytes()).map_err(db_err)?.is_some()),
+        }
+    };
+
+    loop {
+        let records = events_after(database, checkpoint.last_event_id, EVENT_BATCH).map_err(db_err)?;
+        if records.is_empty() {
+            break;
+        }
+        for record in records {
+            checkpoint.last_event_id = record.id;
+            match &record.event {
+                TurtleEvent::Submission if !round_open(&rounds, &record.dao)? => {
+                    ops.push(BRIDGE_ROUNDS.put_op(record.dao.as_bytes(), &record.block_time.unwrap_or(now)).map_err(db_err)?);
+                    rounds.push((record.dao.clone(), true));
+                    events.push(BridgeEvent::NewRound { dao: record.dao.clone(), opened_by: record.actor.clone() });
+                },
+                TurtleEvent::Claim { winner } => {
+                    ops.push(BRIDGE_ROUNDS.delete_op(record.dao.as_bytes()));
+                    rounds.push((record.dao.clone(), false));
+                    events.push(BridgeEvent::WinnerClaimed { dao: record.dao.clone(), winner: winner.clone() });
+                },
+                _ => {},
+            }
+        }
+    }
+
+    for (_, snapshot) in DAO_SNAPSHOTS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let seconds_left = snapshot.timeout_timestamp.saturating_sub(now);
+        if seconds_left == 0 || seconds_left > COUNTDOWN_WARNING_SECS || !round_open(&rounds, &snapshot.pubkey)? {
+            continue;
+        }
+        let key = warned_key(&snapshot.pubkey, snapshot.timeout_timestamp);
+        if BRIDGE_WARNED.get(database, &key).map_err(db_err)?.is_some() {
+            continue;
+        }
+        ops.push(BRIDGE_WARNED.put_op(&key, &now).map_err(db_err)?);
+        events.push(BridgeEvent::CountdownEnding { dao: snapshot.pubkey.clone(), seconds_left, pot: snapshot.total_deposit });
+    }
+
+    ops.push(BRIDGE_STATE.put_op(CHECKPOINT_KEY, &checkpoint).map_err(db_err)?);
+    database.txn(&ops).map_err(db_err)?;
+    Ok(events)
+}
+
+// 메시지를 실제로 올리는 곳. 서버는 reqwest 를 쓰고, 테스트는 요청만 모은다.
+pub trait BridgePoster: Send + Sync + 'static {
+    fn post(&self, url: &str, body: Value) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+impl BridgePoster for reqwest::Client {
+    async fn post(&self, url: &str, body: Value) -> Result<(), String> {
+        let response = reqwest::Client::post(self, url)
+            .timeout(POST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        match response.status().is_success() {
+            true => Ok(()),
+            false => Err(format!("HTTP {}", response.status())),
+        }
+    }
+}
+
+// 설정된 DAO 의 이벤트만 모든 채널에 올린다. 실패한 채널은 로그만 남긴다.
+pub async fn broadcast<T: SafeDatabase, P: BridgePoster>(database: &T, config: &BridgeConfig, poster: &P, events: &[BridgeEvent]) -> usize {
+    let mut posted = 0;
+    for event in events.iter().filter(|event| config.wants(event.dao())) {
+        let dao_name = DAO_SNAPSHOTS.get(database, event.dao().as_bytes())
+            .ok()
+            .flatten()
+            .map(|snapshot| snapshot.dao_name)
+            .filter(|name| !name.is_empty());
+        let text = format_message(event, dao_name.as_deref());
+
+        for (url, body) in config.requests(&text) {
+            match poster.post(&url, body).await {
+                Ok(()) => posted += 1,
+                // 봇 토큰이 URL 에 들어 있으므로 URL 은 로그에 남기지 않는다
+                Err(e) => tracing::error!(dao = %event.dao(), error = %e, "Bridge message failed"),
+            }
+        }
+    }
+    posted
+}
+
+fn is_bridge_source(table: &str) -> bool {
+    table == DAO_SNAPSHOTS.name() || EVENT_TABLES.iter().any(|events| events.name() == table)
+}
+
+// 인덱서가 이벤트를 쓰거나 스냅샷이 바뀌면 바로, 그렇지 않아도 BRIDGE_TICK 마다 카운트다운을 확인한다
+pub fn spawn_bridge<T: SafeDatabase + ChangeFeed>(database: T, config: BridgeConfig) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut changes = database.subscribe();
+        let mut tick = tokio::time::interval(BRIDGE_TICK);
+        loop {
+            let pending = tokio::select! {
+                _ = tick.tick() => true,
+                change = changes.recv() => match change {
+                    Ok(change) => is_bridge_source(&change.table),
+                    Err(RecvError::Lagged(_)) => true,
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if !pending {
+                continue;
+            }
+
+            match collect_bridge_events(&database, now_secs()) {
+                Ok(events) => {
+                    broadcast(&database, &config, &client, &events).await;
+                },
+                Err(e) => tracing::error!(error = %e, "Bridge event collection failed"),
+            }
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::indexer::{event_key, IndexerCheckpoint, CLAIM_EVENTS, INDEXER_STATE, SUBMISSION_EVENTS};
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::DaoSnapshot;
+    use turtle_service::parser::event::EventRecord;
+
+    const NOW: u64 = 50_000;
+
+    #[derive(Default)]
+    struct FakePoster {
+        posts: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl BridgePoster for FakePoster {
+        async fn post(&self, url: &str, body: Value) -> Result<(), String> {
+            self.posts.lock().unwrap().push((url.to_string(), body));
+            Ok(())
+        }
+    }
+
+    fn snapshot(timeout_timestamp: u64) -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: "dao".to_string(),
+            dao_name: "Turtle Club".to_string(),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: false,
+            deposit_share: 10,
+            timeout_timestamp,
+            total_deposit: 2_500_000_000,
+            depositor_count: 1,
+            content_count: 0,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    fn put(database: &MemoryDatabase, id: u64, actor: &str, event: TurtleEvent) {
+        let table = match event {
+            TurtleEvent::Claim { .. } => CLAIM_EVENTS,
+            _ => SUBMISSION_EVENTS,
+        };
+        let record = EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: Some(NOW),
+            dao: "dao".to_string(),
+            actor: actor.to_string(),
+            event,
+        };
+        table.put(database, &event_key(id), &record).unwrap();
+    }
+
+    #[test]
+    fn test_messages() {
+        let new_round = BridgeEvent::NewRound { dao: "dao".to_string(), opened_by: "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU".to_string() };
+        assert_eq!(
+            format_message(&new_round, Some("Turtle Club")),
+            "🐢 New round in Turtle Club: 7xKX…gAsU posted the first submission. The countdown is running!"
+        );
+
+        let ending = BridgeEvent::CountdownEnding { dao: "dao".to_string(), seconds_left: 541, pot: 2_500_000_000 };
+        assert_eq!(format_message(&ending, None), "⏳ dao: 10 minutes left on the countdown. Pot: 2.5 SOL");
+        assert_eq!(format_sol(3 * LAMPORTS_PER_SOL), "3");
+
+        let config = BridgeConfig {
+            discord_webhooks: vec!["https://discord.test/hook".to_string()],
+            telegram: Some(TelegramConfig { bot_token: "token".to_string(), chat_ids: parse_list(" -100, -200 ,") }),
+            daos: None,
+        };
+        let requests = config.requests("hello");
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].1, json!({ "content": "hello" }));
+        assert_eq!(requests[2].0, "https://api.telegram.org/bottoken/sendMessage");
+        assert_eq!(requests[2].1["chat_id"], "-200");
+    }
+
+    #[tokio::test]
+    async fn test_round_lifecycle_is_bridged() {
+        let database = MemoryDatabase::new();
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot(0)).unwrap();
+        // 브리지를 켜기 전 이벤트는 보내지 않는다
+        put(&database, 1, "bob", TurtleEvent::Submission);
+        INDEXER_STATE.put(&database, b"checkpoint", &IndexerCheckpoint { next_event_id: 2, ..Default::default() }).unwrap();
+        assert!(collect_bridge_events(&database, NOW).unwrap().is_empty());
+
+        put(&database, 2, "bob", TurtleEvent::Submission);
+        put(&database, 3, "carol", TurtleEvent::Submission);
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot(NOW + 300)).unwrap();
+        let events = collect_bridge_events(&database, NOW).unwrap();
+        assert_eq!(events, [
+            BridgeEvent::NewRound { dao: "dao".to_string(), opened_by: "bob".to_string() },
+            BridgeEvent::CountdownEnding { dao: "dao".to_string(), seconds_left: 300, pot: 2_500_000_000 },
+        ]);
+        // 같은 타이머로는 한 번만 알린다
+        assert!(collect_bridge_events(&database, NOW + 60).unwrap().is_empty());
+
+        put(&database, 4, "carol", TurtleEvent::Claim { winner: Some("carol".to_string()) });
+        put(&database, 5, "dave", TurtleEvent::Submission);
+        let events = collect_bridge_events(&database, NOW + 400).unwrap();
+        assert_eq!(events, [
+            BridgeEvent::WinnerClaimed { dao: "dao".to_string(), winner: Some("carol".to_string()) },
+            BridgeEvent::NewRound { dao: "dao".to_string(), opened_by: "dave".to_string() },
+        ]);
+
+        // 다른 DAO 만 받도록 설정하면 아무것도 올리지 않는다
+        let poster = FakePoster::default();
+        let mut config = BridgeConfig { discord_webhooks: vec!["https://discord.test/hook".to_string()], telegram: None, daos: None };
+        assert_eq!(broadcast(&database, &config, &poster, &events).await, 2);
+        assert_eq!(poster.posts.lock().unwrap()[0].1["content"], "🏆 Turtle Club: carol won the round and claimed the pot!");
+
+        config.daos = Some(BTreeSet::from(["other".to_string()]));
+        assert_eq!(broadcast(&database, &config, &poster, &events).await, 0);
+    }
+}
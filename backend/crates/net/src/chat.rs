@@ -0,0 +1,315 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::{KeyBuilder, KeyReader};
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_database::watch::{ChangeEvent, ChangeFeed, ChangeKind};
+use turtle_service::parser::chat::ChatMessage;
+use crate::dao::DAO_REGISTRY;
+use crate::pagination::{in_order, paginate, ListQuery, ListQueryError, SortOrder};
+use crate::session::AuthedWallet;
+use crate::ws::ConnectionGuard;
+use crate::error::ApiError;
+
+// (dao, sent_at, seq) -> ChatMessage. DAO prefix 로 스캔하면 보낸 순서대로 나온다.
+pub const DAO_CHAT: TypedTable<ChatMessage> = TypedTable::new("dao_chat");
+
+pub const MAX_CHAT_MESSAGE_CHARS: usize = 500;
+pub const DEFAULT_CHAT_LIMIT: usize = 50;
+pub const MAX_CHAT_LIMIT: usize = 200;
+
+// 같은 초에 온 메시지의 seq 를 겹치지 않게 매기려고 쓰기를 한 줄로 세운다
+static CHAT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug)]
+pub enum ChatError {
+    DatabaseError(String),
+    ValidationError(String),
+    NotFound(String),
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            ChatError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            ChatError::NotFound(pubkey) => write!(f, "DAO not found: {}", pubkey),
+        }
+    }
+}
+
+impl StdError for ChatError {}
+
+impl IntoResponse for ChatError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ChatError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ChatError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            ChatError::NotFound(_) => StatusCode::NOT_FOUND,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+impl From<ListQueryError> for ChatError {
+    fn from(e: ListQueryError) -> Self {
+        match e {
+            ListQueryError::InvalidQuery(msg) => ChatError::ValidationError(msg),
+            ListQueryError::DatabaseError(msg) => ChatError::DatabaseError(msg),
+        }
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> ChatError {
+    ChatError::DatabaseError(e.to_string())
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SendChatRequest {
+    text: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChatMessages {
+    messages: Vec<ChatMessage>,
+    next_cursor: Option<String>,
+}
+
+// /ws/dao/{pubkey}/chat 로 나가는 메시지
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatStreamMessage {
+    Message(ChatMessage),
+    // 알림을 놓쳐서 빠진 메시지가 있을 수 있다. 클라이언트는 GET 으로 다시 읽는다.
+    Lagged,
+}
+
+pub fn chat_key(dao: &str, sent_at: u64, seq: u32) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(sent_at).u32(seq).build()
+}
+
+fn chat_range(dao: &str) -> KeyRange {
+    KeyRange::prefix(KeyBuilder::new().str(dao).build())
+}
+
+fn ensure_dao<T: SafeDatabase>(database: &T, dao: &str) -> Result<(), ChatError> {
+    match DAO_REGISTRY.get(database, dao.as_bytes()).map_err(db_err)? {
+        Some(_) => Ok(()),
+        None => Err(ChatError::NotFound(dao.to_string())),
+    }
+}
+
+// 앞뒤 공백을 떼고 빈 메시지와 너무 긴 메시지는 받지 않는다
+fn clean_message(text: &str) -> Result<String, ChatError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ChatError::ValidationError("Message cannot be empty".to_string()));
+    }
+    if text.chars().count() > MAX_CHAT_MESSAGE_CHARS {
+        return Err(ChatError::ValidationError(format!("Message must be at most {} characters", MAX_CHAT_MESSAGE_CHARS)));
+    }
+    Ok(text.to_string())
+}
+
+pub fn append_message<T: SafeDatabase>(database: &T, dao: &str, sender: &str, text: &str, now: u64) -> Result<ChatMessage, ChatError> {
+    let text = clean_message(text)?;
+    ensure_dao(database, dao)?;
+
+    let _guard = CHAT_LOCK.lock().unwrap();
+    // 이 초에 이미 들어간 메시지 뒤에 붙인다
+    let same_second = KeyRange::between(chat_key(dao, now, 0), chat_key(dao, now, u32::MAX));
+    let seq = DAO_CHAT.scan(database, &same_second).map_err(db_err)?
+        .last()
+        .map_or(0, |(_, message)| message.seq + 1);
+
+    let message = ChatMessage {
+        dao: dao.to_string(),
+        sender: sender.to_string(),
+        text,
+        sent_at: now,
+        seq,
+    };
+    DAO_CHAT.put(database, &chat_key(dao, now, seq), &message).map_err(db_err)?;
+    Ok(message)
+}
+
+#[utoipa::path(
+    post, path = "/api/dao/{pubkey}/chat", tag = "chat",
+    params(("pubkey" = String, Path, description = "DAO pubkey")),
+    request_body = SendChatRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 201, body = ChatMessage),
+        (status = 400, description = "Empty or too long message", body = String),
+        (status = 401, description = "Not signed in", body = String),
+        (status = 404, description = "Unknown DAO", body = String),
+    )
+)]
+pub async fn send_chat_message<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Path(pubkey): Path<String>,
+    Json(request): Json<SendChatRequest>,
+) -> Result<(StatusCode, Json<ChatMessage>), ChatError> {
+    let message = append_message(&database, &pubkey, &wallet, &request.text, now_secs())?;
+    Ok((StatusCode::CREATED, Json(message)))
+}
+
+// 최신 메시지부터 (order=asc 면 오래된 것부터)
+#[utoipa::path(
+    get, path = "/api/dao/{pubkey}/chat", tag = "chat",
+    params(("pubkey" = String, Path, description = "DAO pubkey"), ListQuery),
+    responses(
+        (status = 200, description = "Newest first", body = ChatMessages),
+        (status = 400, description = "Unknown filter/sort or invalid cursor", body = String),
+        (status = 404, description = "Unknown DAO", body = String),
+    )
+)]
+pub async fn list_chat_messages<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(pubkey): Path<String>,
+    query: ListQuery,
+) -> Result<Json<ChatMessages>, ChatError> {
+    query.check_filters(&[])?;
+    query.sort(&["sent_at"])?;
+    let limit = query.limit(DEFAULT_CHAT_LIMIT, MAX_CHAT_LIMIT);
+    let order = query.order(SortOrder::Desc);
+    ensure_dao(&database, &pubkey)?;
+
+    let rows = DAO_CHAT.scan(&database, &query.range(chat_range(&pubkey), order)?).map_err(db_err)?;
+    let (messages, next_cursor) = paginate(in_order(rows, order).into_iter().map(Ok::<_, ChatError>), limit)?;
+    Ok(Json(ChatMessages { messages, next_cursor }))
+}
+
+fn chat_owner(key: &[u8]) -> Option<&str> {
+    KeyReader::new(key).str().ok()
+}
+
+fn on_change<T: SafeDatabase>(database: &T, dao: &str, change: &ChangeEvent) -> Option<ChatStreamMessage> {
+    if change.kind != ChangeKind::Put || change.table != DAO_CHAT.name() || chat_owner(&change.key) != Some(dao) {
+        return None;
+    }
+    DAO_CHAT.get(database, &change.key).ok()?.map(ChatStreamMessage::Message)
+}
+
+// 새 메시지를 실시간으로 받는 방. 보내기는 인증이 필요해서 POST 로만 받는다.
+pub async fn chat_stream<T: SafeDatabase + ChangeFeed>(
+    ws: WebSocketUpgrade,
+    State(database): State<T>,
+    Path(pubkey): Path<String>,
+) -> Response {
+    match ensure_dao(&database, &pubkey) {
+        Ok(()) => ws.on_upgrade(move |socket| stream_chat(socket, database, pubkey)),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn stream_chat<T: SafeDatabase + ChangeFeed>(mut socket: WebSocket, database: T, dao: String) {
+    let _connection = ConnectionGuard::open();
+    let mut changes = database.subscribe();
+
+    loop {
+        let message = tokio::select! {
+            change = changes.recv() => match change {
+                Ok(change) => on_change(&database, &dao, &change),
+                Err(RecvError::Lagged(_)) => Some(ChatStreamMessage::Lagged),
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => None,
+            },
+        };
+
+        let Some(message) = message else {
+            continue;
+        };
+        let Ok(text) = serde_json::to_string(&message) else {
+            continue;
+        };
+        if socket.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_database::watch::Watched;
+    use turtle_service::parser::community::DaoInfo;
+    use turtle_service::parser::upload::StorageKind;
+
+    fn register(database: &impl SafeDatabase, pubkey: &str) {
+        let info = DaoInfo {
+            pubkey: pubkey.to_string(),
+            name: "turtles".to_string(),
+            description: String::new(),
+            image_uri: None,
+            storage: StorageKind::Ipfs,
+            registered_at: 0,
+        };
+        DAO_REGISTRY.put(database, pubkey.as_bytes(), &info).unwrap();
+    }
+
+    fn list(database: &MemoryDatabase, dao: &str, query: &str) -> impl std::future::Future<Output = Result<Json<ChatMessages>, ChatError>> {
+        let query = ListQuery::from_uri(&format!("/api/dao/{}/chat?{}", dao, query).parse().unwrap()).unwrap();
+        list_chat_messages(State(database.clone()), Path(dao.to_string()), query)
+    }
+
+    #[tokio::test]
+    async fn test_send_and_page_messages() {
+        let database = MemoryDatabase::new();
+        register(&database, "dao1");
+
+        // 같은 초에 온 메시지는 seq 로 순서가 갈린다
+        let first = append_message(&database, "dao1", "alice", "  hi  ", 100).unwrap();
+        let second = append_message(&database, "dao1", "bob", "hello", 100).unwrap();
+        append_message(&database, "dao1", "alice", "later", 101).unwrap();
+        assert_eq!((first.text.as_str(), first.seq, second.seq), ("hi", 0, 1));
+
+        assert!(matches!(append_message(&database, "dao1", "alice", " ", 102), Err(ChatError::ValidationError(_))));
+        let long = "a".repeat(MAX_CHAT_MESSAGE_CHARS + 1);
+        assert!(matches!(append_message(&database, "dao1", "alice", &long, 102), Err(ChatError::ValidationError(_))));
+        assert!(matches!(append_message(&database, "dao2", "alice", "hi", 102), Err(ChatError::NotFound(_))));
+
+        let Json(page) = list(&database, "dao1", "limit=2").await.unwrap();
+        let texts: Vec<&str> = page.messages.iter().map(|message| message.text.as_str()).collect();
+        assert_eq!(texts, ["later", "hello"]);
+        let Json(rest) = list(&database, "dao1", &format!("limit=2&cursor={}", page.next_cursor.unwrap())).await.unwrap();
+        assert_eq!((rest.messages, rest.next_cursor), (vec![first], None));
+
+        assert!(matches!(list(&database, "dao1", "filter[sender]=alice").await, Err(ChatError::ValidationError(_))));
+        assert!(matches!(list(&database, "dao2", "").await, Err(ChatError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_room_only_sees_its_dao() {
+        let database = Watched::new(MemoryDatabase::new());
+        register(&database, "dao1");
+        register(&database, "dao2");
+        let mut changes = database.subscribe();
+
+        append_message(&database, "dao2", "bob", "elsewhere", 100).unwrap();
+        let sent = append_message(&database, "dao1", "alice", "hi", 100).unwrap();
+
+        let mut messages = Vec::new();
+        for _ in 0..2 {
+            messages.extend(on_change(&database, "dao1", &changes.recv().await.unwrap()));
+        }
+        assert_eq!(messages, vec![ChatStreamMessage::Message(sent)]);
+    }
+}
@@ -1,57 +1,81 @@
-use axum::extract::{Multipart, Query, State};
+use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use std::error::Error as StdError;
 use std::fmt;
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use turtle_database::basic_db::{SafeDatabase};
+use turtle_database::keys::{sequence_key, sequence_prefix};
 use turtle_service::parser::community::{Community, Content, Depositor, Proposal, Daopda};
 use std::collections::HashMap;
+use turtle_database::ttl::now_secs;
+use crate::sns::{sol_name, sol_names};
+use crate::error::ApiError;
 
 // 다양한 쿼리 파라미터를 위한 구조체들
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PdaQuery {
     pda: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ContentCreateQuery {
     pda: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DepositorCreateQuery {
     pda: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ProposalCreateQuery {
     pda: String,
 }
 
 // 응답 구조체들
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PdasResponse {
     pdas: Vec<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CommunitiesResponse {
     communities: Vec<Community>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ContentsResponse {
     contents: Vec<Content>,
 }
 
-#[derive(Serialize)]
+// admin_name, sol_name 은 지갑의 대표 .sol 이름 (아직 조회 전이거나 없으면 null)
+#[derive(Serialize, ToSchema)]
+pub struct CommunityResponse {
+    #[serde(flatten)]
+    community: Community,
+    admin_name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DepositorItem {
+    #[serde(flatten)]
+    depositor: Depositor,
+    sol_name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct DepositorsResponse {
-    depositors: Vec<Depositor>,
+    depositors: Vec<DepositorItem>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ProposalsResponse {
     proposals: Vec<Proposal>,
 }
@@ -87,11 +111,19 @@ impl IntoResponse for DaoError {
             DaoError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
         };
 
-        (status, error_message).into_response()
+        ApiError::new(status, error_message).into_response()
     }
 }
 
 // DAOPDA 테이블 관련 함수들
+#[utoipa::path(
+    post, path = "/api/dao/pda", tag = "dao",
+    request_body = Daopda,
+    responses(
+        (status = 200, description = "Saved"),
+        (status = 400, description = "Missing PDA or unknown community", body = String),
+    )
+)]
 pub async fn save_pda<T: SafeDatabase>(
     State(database): State<T>,
     Json(daopda): Json<Daopda>,
@@ -108,6 +140,12 @@ pub async fn save_pda<T: SafeDatabase>(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    get, path = "/api/dao/pdas", tag = "dao",
+    responses(
+        (status = 200, body = PdasResponse),
+    )
+)]
 pub async fn get_all_pdas<T: SafeDatabase>(
     State(database): State<T>,
 ) -> Result<Json<PdasResponse>, DaoError> {
@@ -127,6 +165,15 @@ pub async fn get_all_pdas<T: SafeDatabase>(
 }
 
 // COMMUNITY 테이블 관련 함수들
+#[utoipa::path(
+    post, path = "/api/dao/community", tag = "dao",
+    params(PdaQuery),
+    request_body = Community,
+    responses(
+        (status = 200, description = "Saved"),
+        (status = 400, description = "Missing PDA or unknown community", body = String),
+    )
+)]
 pub async fn save_community<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<PdaQuery>,
@@ -148,6 +195,12 @@ pub async fn save_community<T: SafeDatabase>(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    get, path = "/api/dao/communities", tag = "dao",
+    responses(
+        (status = 200, body = CommunitiesResponse),
+    )
+)]
 pub async fn get_all_communities<T: SafeDatabase>(
     State(database): State<T>,
 ) -> Result<Json<CommunitiesResponse>, DaoError> {
@@ -169,10 +222,18 @@ pub async fn get_all_communities<T: SafeDatabase>(
     Ok(Json(CommunitiesResponse { communities }))
 }
 
+#[utoipa::path(
+    get, path = "/api/dao/community", tag = "dao",
+    params(PdaQuery),
+    responses(
+        (status = 200, body = CommunityResponse),
+        (status = 400, description = "Unknown community", body = String),
+    )
+)]
 pub async fn get_community_by_pda<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<PdaQuery>,
-) -> Result<Json<Community>, DaoError> {
+) -> Result<Json<CommunityResponse>, DaoError> {
     // PDA 유효성 검사
     if query.pda.is_empty() {
         return Err(DaoError::ValidationError("PDA cannot be empty".to_string()));
@@ -188,14 +249,25 @@ pub async fn get_community_by_pda<T: SafeDatabase>(
 
         let community: Community = serde_json::from_str(&community_str)
             .map_err(|e| DaoError::SerializationError(format!("Invalid JSON: {}", e)))?;
+        let admin_name = sol_name(&database, &community.admin, now_secs())
+            .map_err(|e| DaoError::DatabaseError(e.to_string()))?;
 
-        Ok(Json(community))
+        Ok(Json(CommunityResponse { community, admin_name }))
     } else {
         Err(DaoError::ValidationError(format!("Community with PDA {} not found", query.pda)))
     }
 }
 
 // CONTENT 테이블 관련 함수들
+#[utoipa::path(
+    post, path = "/api/dao/content", tag = "dao",
+    params(ContentCreateQuery),
+    request_body = Content,
+    responses(
+        (status = 200, description = "Saved"),
+        (status = 400, description = "Missing PDA or unknown community", body = String),
+    )
+)]
 pub async fn save_content<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<ContentCreateQuery>,
@@ -221,13 +293,10 @@ pub async fn save_content<T: SafeDatabase>(
         community.content_count += 1;
 
         // last_activity_timestamp 업데이트
-        community.last_activity_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        community.last_activity_timestamp = now_secs();
 
-        // 콘텐츠 키 생성 (pda_n 형식)
-        let content_key = format!("{}_{}", query.pda, community.content_count);
+        // 콘텐츠 키 생성 (pda_n 형식, n 은 0으로 채운 고정 폭)
+        let content_key = sequence_key(&query.pda, community.content_count);
 
         // 콘텐츠 JSON 직렬화
         let content_json = serde_json::to_string(&content)
@@ -251,6 +320,13 @@ pub async fn save_content<T: SafeDatabase>(
     }
 }
 
+#[utoipa::path(
+    get, path = "/api/dao/contents", tag = "dao",
+    params(PdaQuery),
+    responses(
+        (status = 200, body = ContentsResponse),
+    )
+)]
 pub async fn get_contents_by_pda<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<PdaQuery>,
@@ -265,7 +341,7 @@ pub async fn get_contents_by_pda<T: SafeDatabase>(
         .map_err(|e| DaoError::DatabaseError(e.to_string()))?;
 
     // PDA에 해당하는 콘텐츠만 필터링
-    let prefix = format!("{}_", query.pda);
+    let prefix = sequence_prefix(&query.pda);
     let mut contents = Vec::new();
 
     for (key_bytes, value_bytes) in content_entries {
@@ -292,6 +368,15 @@ pub async fn get_contents_by_pda<T: SafeDatabase>(
 }
 
 // DEPOSIT 테이블 관련 함수들
+#[utoipa::path(
+    post, path = "/api/dao/depositor", tag = "dao",
+    params(DepositorCreateQuery),
+    request_body = Depositor,
+    responses(
+        (status = 200, description = "Saved"),
+        (status = 400, description = "Missing PDA or unknown community", body = String),
+    )
+)]
 pub async fn save_depositor<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<DepositorCreateQuery>,
@@ -317,13 +402,10 @@ pub async fn save_depositor<T: SafeDatabase>(
         community.depositor_count += 1;
 
         // last_activity_timestamp 업데이트
-        community.last_activity_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        community.last_activity_timestamp = now_secs();
 
         // depositor 키 생성 (pda_n 형식)
-        let depositor_key = format!("{}_{}", query.pda, community.depositor_count);
+        let depositor_key = sequence_key(&query.pda, community.depositor_count);
 
         // depositor JSON 직렬화
         let depositor_json = serde_json::to_string(&depositor)
@@ -347,6 +429,13 @@ pub async fn save_depositor<T: SafeDatabase>(
     }
 }
 
+#[utoipa::path(
+    get, path = "/api/dao/depositors", tag = "dao",
+    params(PdaQuery),
+    responses(
+        (status = 200, body = DepositorsResponse),
+    )
+)]
 pub async fn get_depositors_by_pda<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<PdaQuery>,
@@ -361,7 +450,7 @@ pub async fn get_depositors_by_pda<T: SafeDatabase>(
         .map_err(|e| DaoError::DatabaseError(e.to_string()))?;
 
     // PDA에 해당하는 depositor만 필터링
-    let prefix = format!("{}_", query.pda);
+    let prefix = sequence_prefix(&query.pda);
     let mut depositors = Vec::new();
 
     for (key_bytes, value_bytes) in depositor_entries {
@@ -384,10 +473,28 @@ pub async fn get_depositors_by_pda<T: SafeDatabase>(
         }
     }
 
+    let names = sol_names(&database, depositors.iter().map(|depositor| depositor.pubkey.as_str()), now_secs())
+        .map_err(|e| DaoError::DatabaseError(e.to_string()))?;
+    let depositors = depositors.into_iter()
+        .map(|depositor| {
+            let sol_name = names.get(&depositor.pubkey).cloned().flatten();
+            DepositorItem { depositor, sol_name }
+        })
+        .collect();
+
     Ok(Json(DepositorsResponse { depositors }))
 }
 
 // PROPOSAL 테이블 관련 함수들
+#[utoipa::path(
+    post, path = "/api/dao/proposal", tag = "dao",
+    params(ProposalCreateQuery),
+    request_body = Proposal,
+    responses(
+        (status = 200, description = "Saved"),
+        (status = 400, description = "Missing PDA or unknown community", body = String),
+    )
+)]
 pub async fn save_proposal<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<ProposalCreateQuery>,
@@ -413,13 +520,10 @@ pub async fn save_proposal<T: SafeDatabase>(
         community.active_proposal_count += 1;
 
         // last_activity_timestamp 업데이트
-        community.last_activity_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        community.last_activity_timestamp = now_secs();
 
         // proposal 키 생성 (pda_n 형식)
-        let proposal_key = format!("{}_{}", query.pda, community.active_proposal_count);
+        let proposal_key = sequence_key(&query.pda, community.active_proposal_count);
 
         // proposal JSON 직렬화
         let proposal_json = serde_json::to_string(&proposal)
@@ -443,6 +547,13 @@ pub async fn save_proposal<T: SafeDatabase>(
     }
 }
 
+#[utoipa::path(
+    get, path = "/api/dao/proposals", tag = "dao",
+    params(PdaQuery),
+    responses(
+        (status = 200, body = ProposalsResponse),
+    )
+)]
 pub async fn get_proposals_by_pda<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<PdaQuery>,
@@ -457,7 +568,7 @@ pub async fn get_proposals_by_pda<T: SafeDatabase>(
         .map_err(|e| DaoError::DatabaseError(e.to_string()))?;
 
     // PDA에 해당하는 proposal만 필터링
-    let prefix = format!("{}_", query.pda);
+    let prefix = sequence_prefix(&query.pda);
     let mut proposals = Vec::new();
 
     for (key_bytes, value_bytes) in proposal_entries {
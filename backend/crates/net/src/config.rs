@@ -0,0 +1,579 @@
+use figment::providers::{Env, Format, Serialized, Toml};
+use axum::http::Method;
+use figment::Figment;
+use serde::{Deserialize, Deserializer, Serialize};
+use sol::state::Pubkey;
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use std::time::Duration;
+use crate::indexer::{IndexerConfig, DEFAULT_INDEX_INTERVAL};
+use crate::ratelimit::RateLimit;
+use crate::scheduler::CronSchedule;
+use crate::sync::{SyncConfig, DEFAULT_RPC_URL, DEFAULT_SYNC_INTERVAL};
+use crate::telemetry::LogFormat;
+use crate::health::DEFAULT_READY_MAX_SLOT_LAG;
+use crate::upload::DEFAULT_IPFS_API_URL;
+use crate::gateway::DEFAULT_ARWEAVE_GATEWAY_URL;
+use crate::sns::DEFAULT_SNS_API_URL;
+
+// 설정 파일 경로. 없으면 작업 디렉토리의 turtle.toml 을 읽고, 그것도 없으면 기본값과 환경 변수만 쓴다.
+pub const CONFIG_PATH_ENV: &str = "TURTLE_CONFIG";
+pub const DEFAULT_CONFIG_PATH: &str = "turtle.toml";
+// 환경 변수는 TURTLE_<필드 이름> (예: TURTLE_BIND_ADDR, TURTLE_RPC_URL).
+// 중첩된 필드는 __ 로 구분한다 (예: TURTLE_RATE_LIMITS__UPLOAD__BURST).
+pub const ENV_PREFIX: &str = "TURTLE_";
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum ConfigError {
+    LoadError(String),
+    ValidationError(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::LoadError(msg) => write!(f, "Config load error: {}", msg),
+            ConfigError::ValidationError(msg) => write!(f, "Invalid config: {}", msg),
+        }
+    }
+}
+
+impl StdError for ConfigError {}
+
+// 라우트 하나의 요청 한도. per_secs 동안 burst 번까지 받는다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitSetting {
+    pub burst: u32,
+    pub per_secs: u64,
+}
+
+impl LimitSetting {
+    const fn per_minute(burst: u32) -> Self {
+        Self { burst, per_secs: 60 }
+    }
+
+    pub fn limit(&self, route: &'static str) -> RateLimit {
+        RateLimit::new(route, self.burst, Duration::from_secs(self.per_secs))
+    }
+}
+
+// 쓰기 라우트별 요청 한도 (IP 와 로그인한 지갑 각각에 적용)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    pub profile_write: LimitSetting,
+    pub content_post: LimitSetting,
+    pub auth_challenge: LimitSetting,
+    pub upload: LimitSetting,
+    pub tx_relay: LimitSetting,
+    pub chat_post: LimitSetting,
+    pub faucet: LimitSetting,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            profile_write: LimitSetting::per_minute(10),
+            content_post: LimitSetting::per_minute(20),
+            auth_challenge: LimitSetting::per_minute(10),
+            upload: LimitSetting::per_minute(10),
+            tx_relay: LimitSetting::per_minute(10),
+            chat_post: LimitSetting::per_minute(30),
+            faucet: LimitSetting::per_minute(5),
+        }
+    }
+}
+
+impl RateLimits {
+    fn entries(&self) -> [(&'static str, &LimitSetting); 7] {
+        [
+            ("profile_write", &self.profile_write),
+            ("content_post", &self.content_post),
+            ("auth_challenge", &self.auth_challenge),
+            ("upload", &self.upload),
+            ("tx_relay", &self.tx_relay),
+            ("chat_post", &self.chat_post),
+            ("faucet", &self.faucet),
+        ]
+    }
+}
+
+// CORS 기본값 묶음. production 은 origin 목록을 꼭 받고 자격 증명(Authorization, 쿠키)을 허용한다.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorsPreset {
+    // origin 목록이 비어 있으면 아무 origin 이나 받는다. 자격 증명은 기본으로 막는다.
+    #[default]
+    Development,
+    Production,
+}
+
+// 값을 주지 않은 항목은 preset 을 따른다. 환경 변수는 TURTLE_CORS__ORIGINS="https://a, https://b" 처럼 쓴다.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    pub preset: CorsPreset,
+    // 허용할 origin 목록 (scheme://host[:port], 경로 없이)
+    #[serde(deserialize_with = "string_or_list")]
+    pub origins: Vec<String>,
+    // 비어 있으면 DEFAULT_CORS_METHODS
+    #[serde(deserialize_with = "string_or_list")]
+    pub methods: Vec<String>,
+    pub allow_credentials: Option<bool>,
+}
+
+pub const DEFAULT_CORS_METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+impl CorsConfig {
+    pub fn allow_credentials(&self) -> bool {
+        self.allow_credentials.unwrap_or(self.preset == CorsPreset::Production)
+    }
+
+    pub fn methods(&self) -> Result<Vec<Method>, ConfigError> {
+        match self.methods.is_empty() {
+            true => Ok(DEFAULT_CORS_METHODS.iter().map(|method| Method::from_bytes(method.as_bytes()).unwrap()).collect()),
+            false => self.methods.iter()
+                .map(|method| Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|_| ConfigError::ValidationError(format!("cors.methods has an invalid method: {}", method))))
+                .collect(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for origin in &self.origins {
+            match reqwest::Url::parse(origin) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() && url.path() == "/" && !origin.ends_with('/') && url.query().is_none() => {}
+                _ => return Err(ConfigError::ValidationError(format!("cors.origins must be scheme://host[:port] without a path: {}", origin))),
+            }
+        }
+        // 모든 origin 에 자격 증명을 허용하면 어느 사이트든 로그인한 사용자로 API 를 부를 수 있다
+        if self.origins.is_empty() && (self.preset == CorsPreset::Production || self.allow_credentials()) {
+            return Err(ConfigError::ValidationError("cors.origins must be set for the production preset or when allow_credentials is on".to_string()));
+        }
+        self.methods()?;
+        Ok(())
+    }
+}
+
+// 서버 전체 설정. 기본값 < 설정 파일 < 환경 변수 순서로 덮어쓴다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub bind_addr: String,
+    // 밖에서 보이는 서버 주소 (예: "https://api.turtle.xyz"). 공유 링크 미리보기의 이미지/링크를 절대 주소로 만든다.
+    pub public_url: Option<String>,
+    // 있으면 bind_addr 가 HTTPS 를 받는다 (리버스 프록시 없이 띄울 때)
+    pub tls: Option<TlsConfig>,
+    // 인덱서 gRPC 서버 주소 (grpc 기능으로 빌드했을 때만). 인증이 없으니 내부망 주소만 쓴다.
+    pub grpc_bind_addr: Option<String>,
+    pub database_path: String,          // mdbx/sled 파일이 들어갈 디렉토리
+    pub cors: CorsConfig,
+    pub rpc_url: String,
+    // rpc_url 이 실패하면 넘어갈 엔드포인트들 ("a, b" 또는 목록)
+    #[serde(deserialize_with = "string_or_list")]
+    pub rpc_fallback_urls: Vec<String>,
+    pub sync_interval_secs: u64,
+    pub program_id: Option<String>,     // 없으면 인덱서와 트랜잭션 빌더를 끈다
+    // DAO/vault/시스템 프로그램 등을 담은 주소 조회 테이블. 있으면 트랜잭션 빌더가 ?versioned=true 와 보상 분배 초안을 v0 로 만든다.
+    pub lookup_table: Option<String>,
+    pub index_interval_secs: u64,
+    // /readyz 가 인덱서를 준비됐다고 보는 체인 tip 과의 최대 slot 차이
+    pub ready_max_slot_lag: u64,
+    pub ipfs_api_url: String,
+    pub ipfs_api_auth: Option<String>,   // pinning 서비스에 보낼 Authorization 헤더 값 (예: "Bearer ...")
+    // 원본 바이트를 POST 하면 서명/번들링해서 {"id": "<tx id>"} 를 돌려주는 업로더(bundler). 없으면 arweave 저장소는 꺼진다.
+    pub arweave_upload_url: Option<String>,
+    pub arweave_upload_auth: Option<String>,
+    // /api/media/{id} 가 Arweave 객체를 받아 오는 게이트웨이 (IPFS 는 ipfs_api_url 에서 받는다)
+    pub arweave_gateway_url: String,
+    pub media_cache_dir: String,        // 받아 온 객체를 담아 두는 디렉토리
+    pub media_cache_max_bytes: u64,     // 넘으면 오래 안 쓴 것부터 지운다
+    pub media_max_object_bytes: u64,    // 이보다 큰 객체는 받지 않는다
+    // 작성자/예치자/관리자 지갑의 .sol 이름을 찾는 SNS 프록시
+    pub sns_api_url: String,
+    pub rate_limits: RateLimits,
+    // POST /api/faucet 이 한 번에 보내는 devnet lamports. 0 이면 끈다 (mainnet 배포에서는 항상 꺼진다).
+    pub faucet_lamports: u64,
+    pub faucet_cooldown_secs: u64,      // 같은 지갑/IP 가 다시 받을 수 있을 때까지
+    // DB 백업을 만들 cron 식 (분 시 일 월 요일, UTC). 없으면 POST /api/admin/backup 으로만 만든다.
+    pub backup_schedule: Option<String>,
+    // 개발용. 켜면 웹훅 URL 로 http://localhost 같은 루프백 주소를 받는다 (운영에서는 끈다).
+    pub webhook_allow_localhost: bool,
+    pub log_format: LogFormat,          // "pretty" 또는 "json"
+    pub log_level: String,              // EnvFilter 문법 (예: "info", "turtle_net=debug,info")
+    // 같은 서버에서 함께 돌리는 다른 배포들. /api/{cluster}/... 와 /ws/{cluster}/... 로 나간다.
+    pub tenants: Vec<TenantConfig>,
+}
+
+// PEM 인증서 체인과 개인 키. SIGHUP 을 보내면 같은 경로에서 다시 읽는다 (갱신된 인증서를 재시작 없이 쓰도록).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+    // 평문 HTTP 를 받아 같은 호스트의 https 로 돌려보낼 주소 (예: "0.0.0.0:80"). 없으면 띄우지 않는다.
+    #[serde(default)]
+    pub redirect_addr: Option<String>,
+}
+
+impl TlsConfig {
+    fn validate(&self, bind_addr: &str) -> Result<(), ConfigError> {
+        if self.cert_path.trim().is_empty() || self.key_path.trim().is_empty() {
+            return Err(ConfigError::ValidationError("tls.cert_path and tls.key_path must not be empty".to_string()));
+        }
+        if let Some(addr) = &self.redirect_addr {
+            addr.parse::<SocketAddr>()
+                .map_err(|_| ConfigError::ValidationError(format!("tls.redirect_addr must be host:port: {}", addr)))?;
+            if addr == bind_addr {
+                return Err(ConfigError::ValidationError("tls.redirect_addr must differ from bind_addr".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub const TENANT_CLUSTERS: &[&str] = &["mainnet", "devnet", "testnet", "localnet"];
+
+// (cluster, program_id) 로 구분되는 배포 하나. 데이터는 같은 DB 의 "{cluster}.{program_id}." 테이블에 따로 쌓인다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantConfig {
+    pub cluster: String,                // TENANT_CLUSTERS 중 하나, 경로에 그대로 쓴다
+    pub program_id: String,
+    pub rpc_url: String,
+    #[serde(default)]
+    pub lookup_table: Option<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub rpc_fallback_urls: Vec<String>,
+    // 없으면 최상위 값을 쓴다
+    #[serde(default)]
+    pub sync_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub index_interval_secs: Option<u64>,
+}
+
+impl TenantConfig {
+    pub fn rpc_endpoints(&self) -> Vec<String> {
+        std::iter::once(self.rpc_url.clone()).chain(self.rpc_fallback_urls.iter().cloned()).collect()
+    }
+
+    pub fn sync(&self, defaults: &AppConfig) -> SyncConfig {
+        SyncConfig {
+            interval: Duration::from_secs(self.sync_interval_secs.unwrap_or(defaults.sync_interval_secs)),
+        }
+    }
+
+    pub fn indexer(&self, defaults: &AppConfig) -> IndexerConfig {
+        IndexerConfig {
+            program_id: self.program_id.clone(),
+            interval: Duration::from_secs(self.index_interval_secs.unwrap_or(defaults.index_interval_secs)),
+        }
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if !TENANT_CLUSTERS.contains(&self.cluster.as_str()) {
+            return Err(ConfigError::ValidationError(format!("tenants.cluster must be one of {}: {}", TENANT_CLUSTERS.join(", "), self.cluster)));
+        }
+        self.program_id.parse::<Pubkey>()
+            .map_err(|_| ConfigError::ValidationError(format!("tenants.program_id is not a valid pubkey: {}", self.program_id)))?;
+        if let Some(lookup_table) = &self.lookup_table {
+            lookup_table.parse::<Pubkey>()
+                .map_err(|_| ConfigError::ValidationError(format!("tenants.lookup_table is not a valid pubkey: {}", lookup_table)))?;
+        }
+        for url in self.rpc_endpoints() {
+            validate_http_url("tenants.rpc_url", &url)?;
+        }
+        if self.sync_interval_secs == Some(0) || self.index_interval_secs == Some(0) {
+            return Err(ConfigError::ValidationError("tenants intervals must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: "0.0.0.0:8080".to_string(),
+            public_url: None,
+            tls: None,
+            grpc_bind_addr: None,
+            database_path: ".".to_string(),
+            cors: CorsConfig::default(),
+            rpc_url: DEFAULT_RPC_URL.to_string(),
+            rpc_fallback_urls: Vec::new(),
+            sync_interval_secs: DEFAULT_SYNC_INTERVAL.as_secs(),
+            program_id: None,
+            lookup_table: None,
+            index_interval_secs: DEFAULT_INDEX_INTERVAL.as_secs(),
+            ready_max_slot_lag: DEFAULT_READY_MAX_SLOT_LAG,
+            ipfs_api_url: DEFAULT_IPFS_API_URL.to_string(),
+            ipfs_api_auth: None,
+            arweave_upload_url: None,
+            arweave_upload_auth: None,
+            arweave_gateway_url: DEFAULT_ARWEAVE_GATEWAY_URL.to_string(),
+            media_cache_dir: "media-cache".to_string(),
+            media_cache_max_bytes: 1 << 30,
+            media_max_object_bytes: 10 << 20,
+            sns_api_url: DEFAULT_SNS_API_URL.to_string(),
+            rate_limits: RateLimits::default(),
+            faucet_lamports: 500_000_000,
+            faucet_cooldown_secs: 86_400,
+            backup_schedule: None,
+            webhook_allow_localhost: false,
+            log_format: LogFormat::default(),
+            log_level: "info".to_string(),
+            tenants: Vec::new(),
+        }
+    }
+}
+
+// "a, b" 와 ["a", "b"] 를 모두 받는다
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        String(String),
+        List(Vec<String>),
+    }
+
+    let items = match StringOrList::deserialize(deserializer)? {
+        StringOrList::String(value) => value.split(',').map(str::to_string).collect(),
+        StringOrList::List(items) => items,
+    };
+    Ok(items.into_iter().map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect())
+}
+
+fn validate_http_url(field: &str, url: &str) -> Result<(), ConfigError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.host_str().is_some() => Ok(()),
+        _ => Err(ConfigError::ValidationError(format!("{} must be an http(s) URL: {}", field, url))),
+    }
+}
+
+impl AppConfig {
+    pub fn figment(path: &str) -> Figment {
+        Figment::from(Serialized::defaults(AppConfig::default()))
+            .merge(Toml::file(path))
+            .merge(Env::prefixed(ENV_PREFIX).split("__"))
+    }
+
+    // TURTLE_CONFIG (없으면 turtle.toml) 과 TURTLE_* 환경 변수에서 읽고 검증한다
+    pub fn load() -> Result<Self, ConfigError> {
+        let path = std::env::var(CONFIG_PATH_ENV).unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+        Self::extract(Self::figment(&path))
+    }
+
+    pub fn extract(figment: Figment) -> Result<Self, ConfigError> {
+        let config: Self = figment.extract().map_err(|e| ConfigError::LoadError(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.bind_addr.parse::<SocketAddr>()
+            .map_err(|_| ConfigError::ValidationError(format!("bind_addr must be host:port: {}", self.bind_addr)))?;
+        if let Some(addr) = &self.grpc_bind_addr {
+            addr.parse::<SocketAddr>()
+                .map_err(|_| ConfigError::ValidationError(format!("grpc_bind_addr must be host:port: {}", addr)))?;
+        }
+        if let Some(tls) = &self.tls {
+            tls.validate(&self.bind_addr)?;
+        }
+        if let Some(url) = &self.public_url {
+            validate_http_url("public_url", url)?;
+        }
+        if self.database_path.trim().is_empty() {
+            return Err(ConfigError::ValidationError("database_path must not be empty".to_string()));
+        }
+        self.cors.validate()?;
+        validate_http_url("rpc_url", &self.rpc_url)?;
+        for url in &self.rpc_fallback_urls {
+            validate_http_url("rpc_fallback_urls", url)?;
+        }
+        validate_http_url("ipfs_api_url", &self.ipfs_api_url)?;
+        if let Some(url) = &self.arweave_upload_url {
+            validate_http_url("arweave_upload_url", url)?;
+        }
+        validate_http_url("arweave_gateway_url", &self.arweave_gateway_url)?;
+        validate_http_url("sns_api_url", &self.sns_api_url)?;
+        if self.media_max_object_bytes == 0 || self.media_max_object_bytes > self.media_cache_max_bytes {
+            return Err(ConfigError::ValidationError("media_max_object_bytes must be positive and at most media_cache_max_bytes".to_string()));
+        }
+        if let Some(program_id) = &self.program_id {
+            program_id.parse::<Pubkey>()
+                .map_err(|_| ConfigError::ValidationError(format!("program_id is not a valid pubkey: {}", program_id)))?;
+        }
+        if let Some(lookup_table) = &self.lookup_table {
+            lookup_table.parse::<Pubkey>()
+                .map_err(|_| ConfigError::ValidationError(format!("lookup_table is not a valid pubkey: {}", lookup_table)))?;
+        }
+        if self.sync_interval_secs == 0 || self.index_interval_secs == 0 {
+            return Err(ConfigError::ValidationError("sync_interval_secs and index_interval_secs must be positive".to_string()));
+        }
+        if let Some(schedule) = &self.backup_schedule {
+            CronSchedule::parse(schedule)
+                .map_err(|e| ConfigError::ValidationError(format!("backup_schedule: {}", e)))?;
+        }
+        tracing_subscriber::EnvFilter::try_new(&self.log_level)
+            .map_err(|e| ConfigError::ValidationError(format!("log_level is not a valid filter: {}", e)))?;
+        for (route, limit) in self.rate_limits.entries() {
+            if limit.burst == 0 || limit.per_secs == 0 {
+                return Err(ConfigError::ValidationError(format!("rate_limits.{} must have a positive burst and per_secs", route)));
+            }
+        }
+        if self.faucet_cooldown_secs == 0 {
+            return Err(ConfigError::ValidationError("faucet_cooldown_secs must be positive".to_string()));
+        }
+        // cluster 가 경로 앞부분이므로 배포마다 달라야 한다
+        for (i, tenant) in self.tenants.iter().enumerate() {
+            tenant.validate()?;
+            if self.tenants[..i].iter().any(|other| other.cluster == tenant.cluster) {
+                return Err(ConfigError::ValidationError(format!("tenants.cluster must be unique: {}", tenant.cluster)));
+            }
+        }
+        Ok(())
+    }
+
+    // rpc_url 이 먼저, 그다음 rpc_fallback_urls 순서
+    pub fn rpc_endpoints(&self) -> Vec<String> {
+        std::iter::once(self.rpc_url.clone()).chain(self.rpc_fallback_urls.iter().cloned()).collect()
+    }
+
+    pub fn sync(&self) -> SyncConfig {
+        SyncConfig {
+            interval: Duration::from_secs(self.sync_interval_secs),
+        }
+    }
+
+    pub fn indexer(&self) -> Option<IndexerConfig> {
+        Some(IndexerConfig {
+            program_id: self.program_id.clone()?,
+            interval: Duration::from_secs(self.index_interval_secs),
+        })
+    }
+}
+
+static APP_CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+// build_server 가 시작할 때 한 번 넣는다. 상태로 설정을 받지 않는 핸들러(업로드, 트랜잭션 빌더 등) 가 읽는다.
+pub fn install(config: AppConfig) -> &'static AppConfig {
+    APP_CONFIG.get_or_init(|| config)
+}
+
+// 설치된 설정. 테스트처럼 설치 전에 부르면 기본값을 쓴다.
+pub fn app_config() -> &'static AppConfig {
+    APP_CONFIG.get_or_init(AppConfig::default)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_and_env_layers() {
+        let file = r#"
+            bind_addr = "127.0.0.1:9000"
+            log_format = "json"
+
+            [cors]
+            preset = "production"
+            origins = ["https://turtle.app", "http://localhost:3000"]
+
+            [rate_limits.upload]
+            burst = 3
+            per_secs = 10
+        "#;
+        // 환경 변수 자리. 쉼표로 구분한 문자열도 목록으로 읽는다.
+        let figment = Figment::from(Serialized::defaults(AppConfig::default()))
+            .merge(Toml::string(file))
+            .merge(Serialized::default("cors.origins", "https://a.example, https://b.example"))
+            .merge(Serialized::default("rpc_url", "http://127.0.0.1:8899"))
+            .merge(Serialized::default("rpc_fallback_urls", "https://rpc-b.example, https://rpc-c.example"));
+
+        let config = AppConfig::extract(figment).unwrap();
+        assert_eq!(config.bind_addr, "127.0.0.1:9000");
+        assert_eq!(config.cors.origins, ["https://a.example", "https://b.example"]);
+        assert_eq!(config.cors.preset, CorsPreset::Production);
+        assert!(config.cors.allow_credentials());
+        assert_eq!(config.rpc_endpoints(), ["http://127.0.0.1:8899", "https://rpc-b.example", "https://rpc-c.example"]);
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.rate_limits.upload, LimitSetting { burst: 3, per_secs: 10 });
+        // 파일에 없는 값은 기본값
+        assert_eq!(config.rate_limits.content_post, LimitSetting::per_minute(20));
+        assert_eq!(config.sync().interval, Duration::from_secs(30));
+        assert!(config.indexer().is_none());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(AppConfig::default().validate().is_ok());
+
+        let invalid = [
+            AppConfig { bind_addr: "localhost".to_string(), ..Default::default() },
+            AppConfig { grpc_bind_addr: Some("localhost".to_string()), ..Default::default() },
+            AppConfig { database_path: " ".to_string(), ..Default::default() },
+            AppConfig { public_url: Some("turtle.example".to_string()), ..Default::default() },
+            AppConfig { tls: Some(TlsConfig { cert_path: "cert.pem".to_string(), key_path: String::new(), redirect_addr: None }), ..Default::default() },
+            AppConfig { tls: Some(TlsConfig { cert_path: "cert.pem".to_string(), key_path: "key.pem".to_string(), redirect_addr: Some("0.0.0.0:8080".to_string()) }), ..Default::default() },
+            AppConfig { cors: CorsConfig { origins: vec!["turtle.app".to_string()], ..Default::default() }, ..Default::default() },
+            AppConfig { rpc_url: "ftp://rpc".to_string(), ..Default::default() },
+            AppConfig { rpc_fallback_urls: vec!["rpc.example".to_string()], ..Default::default() },
+            AppConfig { program_id: Some("not-a-key".to_string()), ..Default::default() },
+            AppConfig { sync_interval_secs: 0, ..Default::default() },
+            AppConfig { log_level: "turtle_net=loud".to_string(), ..Default::default() },
+            AppConfig { backup_schedule: Some("30 3 * *".to_string()), ..Default::default() },
+            AppConfig { faucet_cooldown_secs: 0, ..Default::default() },
+        ];
+        for config in invalid {
+            assert!(matches!(config.validate(), Err(ConfigError::ValidationError(_))), "{:?}", config);
+        }
+
+        let mut config = AppConfig::default();
+        config.rate_limits.tx_relay.burst = 0;
+        assert!(config.validate().is_err());
+
+        // production 이나 자격 증명 허용에는 origin 목록이 있어야 한다
+        for cors in [
+            CorsConfig { preset: CorsPreset::Production, ..Default::default() },
+            CorsConfig { allow_credentials: Some(true), ..Default::default() },
+            CorsConfig { origins: vec!["https://turtle.app/app".to_string()], ..Default::default() },
+            CorsConfig { origins: vec!["https://turtle.app/".to_string()], ..Default::default() },
+            CorsConfig { methods: vec!["GET POST".to_string()], ..Default::default() },
+        ] {
+            assert!(cors.validate().is_err(), "{:?}", cors);
+        }
+        let cors = CorsConfig { preset: CorsPreset::Production, origins: vec!["https://turtle.app".to_string()], methods: vec!["get".to_string()], ..Default::default() };
+        assert!(cors.validate().is_ok());
+        assert_eq!(cors.methods().unwrap(), [Method::GET]);
+
+        let mut config = AppConfig::default();
+        config.cors.allow_credentials = Some(true);
+        assert!(config.validate().is_err());
+
+        let tenant = TenantConfig {
+            cluster: "devnet".to_string(),
+            program_id: "11111111111111111111111111111111".to_string(),
+            rpc_url: "https://api.devnet.solana.com".to_string(),
+            lookup_table: None,
+            rpc_fallback_urls: Vec::new(),
+            sync_interval_secs: None,
+            index_interval_secs: Some(5),
+        };
+        assert!(AppConfig { tenants: vec![tenant.clone()], ..Default::default() }.validate().is_ok());
+        assert_eq!(tenant.indexer(&AppConfig::default()).interval, Duration::from_secs(5));
+        for tenants in [
+            vec![TenantConfig { cluster: "api".to_string(), ..tenant.clone() }],
+            vec![TenantConfig { program_id: "not-a-key".to_string(), ..tenant.clone() }],
+            vec![TenantConfig { lookup_table: Some("not-a-key".to_string()), ..tenant.clone() }],
+            vec![TenantConfig { rpc_fallback_urls: vec!["rpc.example".to_string()], ..tenant.clone() }],
+            vec![tenant.clone(), tenant.clone()],
+        ] {
+            assert!(AppConfig { tenants, ..Default::default() }.validate().is_err());
+        }
+
+        let figment = Figment::from(Serialized::defaults(AppConfig::default())).merge(Serialized::default("sync_interval_secs", "soon"));
+        assert!(matches!(AppConfig::extract(figment), Err(ConfigError::LoadError(_))));
+    }
+}
@@ -0,0 +1,386 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Mutex;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::{KeyBuilder, KeyReader};
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::content::ContentRecord;
+use crate::pagination::{in_order, paginate, ListQuery, ListQueryError, SortOrder};
+use crate::profile::{is_verified, PROFILES};
+use crate::ranking::{rank_entry, rank_range, CONTENT_TOP, CONTENT_TRENDING};
+use crate::sns::{sol_names, SNS_NAMES_TABLE};
+use crate::error::ApiError;
+
+// id -> ContentRecord
+pub const CONTENT_RECORDS: TypedTable<ContentRecord> = TypedTable::new("content_records");
+// (dao, id) -> (), (author, id) -> () 목록 조회용 인덱스
+pub const CONTENT_BY_DAO: TypedTable<()> = TypedTable::new("content_by_dao");
+pub const CONTENT_BY_AUTHOR: TypedTable<()> = TypedTable::new("content_by_author");
+// list_contents 가 읽는 테이블 (ETag 계산용). 작성자 배지와 .sol 이름 때문에 프로필과 SNS 캐시도 읽는다.
+pub const CONTENT_LIST_TABLES: &[&str] = &[
+    CONTENT_RECORDS.name(), CONTENT_BY_DAO.name(), CONTENT_BY_AUTHOR.name(), CONTENT_TRENDING.name(), CONTENT_TOP.name(),
+    PROFILES.name(), SNS_NAMES_TABLE,
+];
+// 마지막으로 발급한 ID
+pub const CONTENT_LAST_ID: TypedTable<u64> = TypedTable::new("content_meta");
+const LAST_ID_KEY: &[u8] = b"last_id";
+
+pub const DEFAULT_LIST_LIMIT: usize = 50;
+pub const MAX_LIST_LIMIT: usize = 100;
+pub const CONTENT_LIST_FILTERS: &[&str] = &["dao", "author"];
+// id: ID 순서 (기본 오름차순), new: 최신부터, trending/top: 랭킹 작업이 쓴 점수 높은 순
+pub const CONTENT_LIST_SORTS: &[&str] = &["id", "new", "trending", "top"];
+
+// 새 ID 를 정하고 레코드를 쓰는 사이에 다른 제출이 끼어들지 않도록 막는다
+static CONTENT_ID_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewContentRequest {
+    dao: String,
+    author: String,
+    content_hash: String,
+    content_uri: String,
+    timestamp: u64,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ContentIdQuery {
+    id: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ContentCreatedResponse {
+    id: u64,
+}
+
+// 목록의 한 항목. author_verified 는 작성자 지갑의 소유가 증명됐는지 (같은 이름을 쓰는 다른 지갑과 구분용).
+#[derive(Serialize, ToSchema)]
+pub struct ContentListItem {
+    #[serde(flatten)]
+    record: ContentRecord,
+    author_verified: bool,
+    // 작성자 지갑의 대표 .sol 이름 (아직 조회 전이거나 없으면 null)
+    author_name: Option<String>,
+    // sort=trending 이면 시간 감쇠 점수, sort=top 이면 받은 보상 (lamports). 다른 정렬에서는 빠진다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ContentListResponse {
+    contents: Vec<ContentListItem>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ContentError {
+    DatabaseError(String),
+    ValidationError(String),
+    NotFound(u64),
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            ContentError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            ContentError::NotFound(id) => write!(f, "Content not found: {}", id),
+        }
+    }
+}
+
+impl StdError for ContentError {}
+
+impl IntoResponse for ContentError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ContentError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ContentError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            ContentError::NotFound(_) => StatusCode::NOT_FOUND,
+        };
+
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn record_key(id: u64) -> Vec<u8> {
+    KeyBuilder::new().u64(id).build()
+}
+
+fn index_key(owner: &str, id: u64) -> Vec<u8> {
+    KeyBuilder::new().str(owner).u64(id).build()
+}
+
+fn index_id(key: &[u8]) -> Result<u64, ContentError> {
+    let mut reader = KeyReader::new(key);
+    reader.str().map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+    let id = reader.u64().map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+    Ok(id)
+}
+
+fn next_content_id<T: SafeDatabase>(database: &T) -> Result<u64, ContentError> {
+    let last = CONTENT_LAST_ID.get(database, LAST_ID_KEY)
+        .map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+    Ok(last.unwrap_or(0) + 1)
+}
+
+#[utoipa::path(
+    post, path = "/api/content", tag = "content",
+    request_body = NewContentRequest,
+    responses(
+        (status = 201, body = ContentCreatedResponse),
+        (status = 400, description = "Empty field", body = String),
+        (status = 429, description = "Rate limited", body = String),
+    )
+)]
+pub async fn create_content<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<NewContentRequest>,
+) -> Result<(StatusCode, Json<ContentCreatedResponse>), ContentError> {
+    for (field, value) in [
+        ("dao", &request.dao),
+        ("author", &request.author),
+        ("content_hash", &request.content_hash),
+        ("content_uri", &request.content_uri),
+    ] {
+        if value.is_empty() {
+            return Err(ContentError::ValidationError(format!("{} cannot be empty", field)));
+        }
+    }
+
+    let _guard = CONTENT_ID_LOCK.lock().expect("Failed to lock content id mutex");
+    let id = next_content_id(&database)?;
+    let record = ContentRecord {
+        id,
+        dao: request.dao,
+        author: request.author,
+        content_hash: request.content_hash,
+        content_uri: request.content_uri,
+        timestamp: request.timestamp,
+    };
+
+    // 레코드, 인덱스, 마지막 ID 를 한 트랜잭션으로 쓴다
+    let ops = [
+        CONTENT_RECORDS.put_op(&record_key(id), &record),
+        CONTENT_BY_DAO.put_op(&index_key(&record.dao, id), &()),
+        CONTENT_BY_AUTHOR.put_op(&index_key(&record.author, id), &()),
+        CONTENT_LAST_ID.put_op(LAST_ID_KEY, &id),
+    ]
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+
+    database.txn(&ops)
+        .map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(ContentCreatedResponse { id })))
+}
+
+#[utoipa::path(
+    get, path = "/api/content", tag = "content",
+    params(ContentIdQuery),
+    responses(
+        (status = 200, body = ContentRecord),
+        (status = 404, description = "Unknown id", body = String),
+    )
+)]
+pub async fn get_content_by_id<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<ContentIdQuery>,
+) -> Result<Json<ContentRecord>, ContentError> {
+    CONTENT_RECORDS.get(&database, &record_key(query.id))
+        .map_err(|e| ContentError::DatabaseError(e.to_string()))?
+        .map(Json)
+        .ok_or(ContentError::NotFound(query.id))
+}
+
+// filter[dao], filter[author] 로 거른 목록을 ID 순서대로 돌려준다 (order=desc 면 최신부터).
+// dao 가 있으면 DAO 인덱스를, author 만 있으면 작성자 인덱스를 훑고, 둘 다 있으면 작성자로 한 번 더 거른다.
+// sort=trending/top 이면 랭킹 테이블 (dao 가 없으면 전체 피드) 을 점수 높은 순으로 훑는다.
+#[utoipa::path(
+    get, path = "/api/contents", tag = "content",
+    params(ListQuery),
+    responses(
+        (status = 200, body = ContentListResponse),
+        (status = 400, description = "Unknown filter/sort (id, new, trending, top) or invalid cursor", body = String),
+    )
+)]
+pub async fn list_contents<T: SafeDatabase>(
+    State(database): State<T>,
+    query: ListQuery,
+) -> Result<Json<ContentListResponse>, ContentError> {
+    query.check_filters(CONTENT_LIST_FILTERS)?;
+    let sort = query.sort(CONTENT_LIST_SORTS)?;
+    let limit = query.limit(DEFAULT_LIST_LIMIT, MAX_LIST_LIMIT);
+    let order = query.order(if sort == "id" { SortOrder::Asc } else { SortOrder::Desc });
+    let author = query.filter("author");
+    let ranked = sort == "trending" || sort == "top";
+
+    let owner_prefix = |owner: &str| KeyRange::prefix(KeyBuilder::new().str(owner).build());
+    let (table, base) = match (sort, query.filter("dao"), author) {
+        ("trending", dao, _) => (CONTENT_TRENDING.name(), rank_range(dao)),
+        ("top", dao, _) => (CONTENT_TOP.name(), rank_range(dao)),
+        (_, Some(dao), _) => (CONTENT_BY_DAO.name(), owner_prefix(dao)),
+        (_, None, Some(author)) => (CONTENT_BY_AUTHOR.name(), owner_prefix(author)),
+        (_, None, None) => (CONTENT_RECORDS.name(), KeyRange::all()),
+    };
+    let rows = database.scan(table, &query.range(base, order)?)
+        .map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+
+    // 인덱스/랭킹 행이면 가리키는 레코드를 읽는다. paginate 가 필요한 만큼만 꺼내 간다.
+    let records = in_order(rows, order).into_iter().filter_map(|(key, value)| {
+        let record = match (table == CONTENT_RECORDS.name(), ranked) {
+            (true, _) => serde_json::from_slice::<ContentRecord>(&value)
+                .map(|record| (record, None))
+                .map_err(|e| ContentError::DatabaseError(e.to_string())),
+            (false, true) => rank_entry(&key)
+                .map_err(|e| ContentError::DatabaseError(e.to_string()))
+                .and_then(|(score, id)| load_record(&database, id).map(|record| (record, Some(score)))),
+            (false, false) => index_id(&key).and_then(|id| load_record(&database, id)).map(|record| (record, None)),
+        };
+        match record {
+            Ok((record, _)) if author.is_some_and(|author| record.author != author) => None,
+            Ok(row) => Some(Ok((key, row))),
+            Err(e) => Some(Err(e)),
+        }
+    });
+    let (records, next_cursor) = paginate(records, limit)?;
+
+    let names = sol_names(&database, records.iter().map(|(record, _)| record.author.as_str()), now_secs())
+        .map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+    let mut verified = HashMap::new();
+    let mut contents = Vec::with_capacity(records.len());
+    for (record, score) in records {
+        let author_verified = match verified.get(&record.author) {
+            Some(&author_verified) => author_verified,
+            None => {
+                let author_verified = is_verified(&database, &record.author)
+                    .map_err(|e| ContentError::DatabaseError(e.to_string()))?;
+                verified.insert(record.author.clone(), author_verified);
+                author_verified
+            },
+        };
+        let author_name = names.get(&record.author).cloned().flatten();
+        contents.push(ContentListItem { record, author_verified, author_name, score });
+    }
+
+    Ok(Json(ContentListResponse { contents, next_cursor }))
+}
+
+fn load_record<T: SafeDatabase>(database: &T, id: u64) -> Result<ContentRecord, ContentError> {
+    CONTENT_RECORDS.get(database, &record_key(id))
+        .map_err(|e| ContentError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| ContentError::DatabaseError(format!("content index points at missing record {}", id)))
+}
+
+impl From<ListQueryError> for ContentError {
+    fn from(e: ListQueryError) -> Self {
+        match e {
+            ListQueryError::InvalidQuery(msg) => ContentError::ValidationError(msg),
+            ListQueryError::DatabaseError(msg) => ContentError::DatabaseError(msg),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    fn submission(dao: &str, author: &str, hash: &str) -> NewContentRequest {
+        NewContentRequest {
+            dao: dao.to_string(),
+            author: author.to_string(),
+            content_hash: hash.to_string(),
+            content_uri: format!("ipfs://{}", hash),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    async fn submit(database: &MemoryDatabase, dao: &str, author: &str, hash: &str) -> u64 {
+        let (status, Json(created)) = create_content(State(database.clone()), Json(submission(dao, author, hash)))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        created.id
+    }
+
+    async fn list_page(database: &MemoryDatabase, query: &str) -> (Vec<String>, Option<String>) {
+        let query = ListQuery::from_uri(&format!("/api/contents?{}", query).parse().unwrap()).unwrap();
+        let Json(response) = list_contents(State(database.clone()), query).await.unwrap();
+        (response.contents.into_iter().map(|item| item.record.content_hash).collect(), response.next_cursor)
+    }
+
+    async fn list(database: &MemoryDatabase, query: &str) -> Vec<String> {
+        list_page(database, query).await.0
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_by_id() {
+        let database = MemoryDatabase::new();
+        assert_eq!(submit(&database, "dao1", "alice", "h1").await, 1);
+        assert_eq!(submit(&database, "dao1", "bob", "h2").await, 2);
+
+        let Json(record) = get_content_by_id(State(database.clone()), Query(ContentIdQuery { id: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(record.author, "bob");
+        assert_eq!(record.content_uri, "ipfs://h2");
+
+        let missing = get_content_by_id(State(database), Query(ContentIdQuery { id: 3 })).await;
+        assert!(matches!(missing, Err(ContentError::NotFound(3))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_empty_fields() {
+        let database = MemoryDatabase::new();
+        let result = create_content(State(database.clone()), Json(submission("dao1", "", "h1"))).await;
+
+        assert!(matches!(result, Err(ContentError::ValidationError(_))));
+        assert!(list(&database, "").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_and_pages() {
+        let database = MemoryDatabase::new();
+        submit(&database, "dao1", "alice", "h1").await;
+        submit(&database, "dao2", "alice", "h2").await;
+        submit(&database, "dao1", "bob", "h3").await;
+        submit(&database, "dao1", "alice", "h4").await;
+        // 앞부분이 같은 DAO 이름끼리 섞이지 않아야 한다
+        submit(&database, "dao10", "alice", "h5").await;
+
+        assert_eq!(list(&database, "filter[dao]=dao1").await, vec!["h1", "h3", "h4"]);
+        assert_eq!(list(&database, "filter[author]=alice").await, vec!["h1", "h2", "h4", "h5"]);
+        assert_eq!(list(&database, "filter[dao]=dao1&filter[author]=alice").await, vec!["h1", "h4"]);
+        assert_eq!(list(&database, "filter[dao]=dao1&order=desc").await, vec!["h4", "h3", "h1"]);
+
+        // 커서를 따라가면 빠짐없이 겹치지 않게 다 나온다
+        let (first, cursor) = list_page(&database, "limit=2").await;
+        assert_eq!(first, vec!["h1", "h2"]);
+        let (second, cursor) = list_page(&database, &format!("limit=2&cursor={}", cursor.unwrap())).await;
+        assert_eq!(second, vec!["h3", "h4"]);
+        let (last, cursor) = list_page(&database, &format!("limit=2&cursor={}", cursor.unwrap())).await;
+        assert_eq!((last, cursor), (vec!["h5".to_string()], None));
+
+        // 작성자로 거를 때도 커서는 DAO 인덱스 키를 따라간다
+        let (first, cursor) = list_page(&database, "filter[dao]=dao1&filter[author]=alice&limit=1").await;
+        assert_eq!(first, vec!["h1"]);
+        let (second, _) = list_page(&database, &format!("filter[dao]=dao1&filter[author]=alice&limit=1&cursor={}", cursor.unwrap())).await;
+        assert_eq!(second, vec!["h4"]);
+
+        let query = ListQuery::from_uri(&"/api/contents?filter[title]=x".parse().unwrap()).unwrap();
+        assert!(matches!(list_contents(State(database.clone()), query).await, Err(ContentError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,105 @@
+use axum::http::{header, HeaderName, HeaderValue};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use crate::config::{CorsConfig, CorsPreset};
+use crate::telemetry::REQUEST_ID_HEADER;
+
+// production 에서는 브라우저가 preflight 결과를 이만큼 들고 있는다
+pub const PRODUCTION_PREFLIGHT_MAX_AGE: Duration = Duration::from_secs(600);
+
+// 브라우저가 보낼 수 있는 요청 헤더. 자격 증명을 허용하면 "*" 를 쓸 수 없어서 목록으로 둔다.
+fn allowed_headers() -> [HeaderName; 3] {
+    [header::AUTHORIZATION, header::CONTENT_TYPE, HeaderName::from_static(REQUEST_ID_HEADER)]
+}
+
+// 프론트엔드가 읽을 수 있게 열어 두는 응답 헤더
+fn exposed_headers() -> [HeaderName; 2] {
+    [HeaderName::from_static(REQUEST_ID_HEADER), header::RETRY_AFTER]
+}
+
+// AppConfig::validate 를 통과한 설정을 받는다.
+// (origin 없이 자격 증명을 허용하는 조합은 거기서 막으므로 여기서는 다시 보지 않는다)
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let allow_origin = match config.origins.is_empty() {
+        true => AllowOrigin::from(Any),
+        false => AllowOrigin::list(config.origins.iter().filter_map(|origin| HeaderValue::from_str(origin).ok())),
+    };
+    let layer = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(config.methods().unwrap_or_default())
+        .allow_headers(allowed_headers())
+        .expose_headers(exposed_headers())
+        .allow_credentials(config.allow_credentials());
+
+    match config.preset {
+        CorsPreset::Development => layer,
+        CorsPreset::Production => layer.max_age(PRODUCTION_PREFLIGHT_MAX_AGE),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::response::Response;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn preflight(cors: &CorsConfig, origin: &str, method: &str) -> Response {
+        let app = Router::new()
+            .route("/api/profile", get(|| async { "ok" }))
+            .layer(cors_layer(cors));
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/profile")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, method)
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization,content-type")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    fn allowed_origin(response: &Response) -> Option<&str> {
+        response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).map(|value| value.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_production_preflight() {
+        let cors = CorsConfig {
+            preset: CorsPreset::Production,
+            origins: vec!["https://turtle.app".to_string()],
+            ..Default::default()
+        };
+
+        let response = preflight(&cors, "https://turtle.app", "PATCH").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(allowed_origin(&response), Some("https://turtle.app"));
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+        let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS].to_str().unwrap();
+        assert!(methods.contains("PATCH") && methods.contains("DELETE"));
+        assert!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS].to_str().unwrap().contains("authorization"));
+
+        // 목록에 없는 origin 에는 CORS 헤더를 주지 않으므로 브라우저가 막는다
+        let response = preflight(&cors, "https://evil.example", "PATCH").await;
+        assert_eq!(allowed_origin(&response), None);
+    }
+
+    #[tokio::test]
+    async fn test_development_preflight_and_method_list() {
+        let response = preflight(&CorsConfig::default(), "http://localhost:3000", "POST").await;
+        assert_eq!(allowed_origin(&response), Some("*"));
+        assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_CREDENTIALS));
+        assert!(!response.headers().contains_key(header::ACCESS_CONTROL_MAX_AGE));
+
+        // methods 를 좁히면 나머지는 preflight 에 나오지 않는다
+        let cors = CorsConfig { methods: vec!["GET".to_string()], ..Default::default() };
+        let response = preflight(&cors, "http://localhost:3000", "DELETE").await;
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS], "GET");
+    }
+}
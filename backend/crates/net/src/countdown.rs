@@ -0,0 +1,292 @@
+use ed25519_dalek::{Signer, SigningKey};
+use sol::instruction;
+use sol::state::Pubkey;
+use sol::transaction::{Message, Transaction};
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::chain::ClaimableRound;
+use crate::fees::{compute_budget_instructions, priority_fee, PriorityFeeSource};
+use crate::relay::{parse_keypair, TransactionSubmitter};
+use crate::scheduler::Scheduler;
+use crate::sync::DAO_SNAPSHOTS;
+use crate::tx::{tx_config, BlockhashSource};
+
+// dao -> 마감이 지나 타임아웃 처리를 기다리는 라운드. 알림 작업과 웹훅이 이 테이블을 보고 알린다.
+pub const CLAIMABLE_ROUNDS: TypedTable<ClaimableRound> = TypedTable::new("claimable_rounds");
+
+pub const COUNTDOWN_INTERVAL: Duration = Duration::from_secs(5);
+// 마감이 지난 DAO 에 ProcessTimeout 을 대신 보내 줄 keeper 키 (relayer 와 같은 형식). 없으면 표시와 알림만 한다.
+pub const KEEPER_KEYPAIR_ENV: &str = "TURTLE_KEEPER_KEYPAIR";
+// 보낸 뒤에도 라운드가 그대로면 이만큼 뒤에 다시 보낸다 (체인 시계가 늦거나 트랜잭션이 떨어졌을 수 있다)
+pub const CRANK_RETRY_SECS: u64 = 60;
+
+#[derive(Debug)]
+pub enum CountdownError {
+    DatabaseError(String),
+}
+
+impl fmt::Display for CountdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountdownError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for CountdownError {}
+
+fn db_err(e: impl fmt::Display) -> CountdownError {
+    CountdownError::DatabaseError(e.to_string())
+}
+
+pub struct KeeperConfig {
+    pub keypair: SigningKey,
+}
+
+impl KeeperConfig {
+    pub fn from_env() -> Option<Self> {
+        let keypair = std::env::var(KEEPER_KEYPAIR_ENV).ok()?;
+        let Some(keypair) = parse_keypair(&keypair) else {
+            tracing::warn!("{} is invalid, keeper crank disabled", KEEPER_KEYPAIR_ENV);
+            return None;
+        };
+        Some(Self { keypair })
+    }
+
+    pub fn keeper(&self) -> Pubkey {
+        Pubkey(self.keypair.verifying_key().to_bytes())
+    }
+}
+
+// 스냅샷의 마감이 지난 DAO 를 claimable 로 표시하고, 마감이 밀려난 (타임아웃이 처리됐거나 새 활동이 있었던) DAO 의 표시는 지운다.
+// 새로 표시한 라운드를 돌려준다.
+pub fn mark_claimable_rounds<T: SafeDatabase>(database: &T, now: u64) -> Result<Vec<ClaimableRound>, CountdownError> {
+    let mut ops = Vec::new();
+    let mut marked = Vec::new();
+
+    for (key, snapshot) in DAO_SNAPSHOTS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let deadline = snapshot.timeout_timestamp;
+        // 0 은 예치를 기다리는 (라운드가 열리지 않은) DAO 라 마감이 없다
+        let expired = deadline != 0 && deadline <= now;
+        match (CLAIMABLE_ROUNDS.get(database, &key).map_err(db_err)?, expired) {
+            (Some(round), true) if round.deadline == deadline => {},
+            (Some(_), false) => ops.push(CLAIMABLE_ROUNDS.delete_op(&key)),
+            (_, true) => {
+                let round = ClaimableRound {
+                    dao: snapshot.pubkey.clone(),
+                    deadline,
+                    pot_size: snapshot.total_deposit,
+                    content_count: snapshot.content_count,
+                    detected_at: now,
+                    crank_attempted_at: None,
+                    crank_signature: None,
+                    crank_error: None,
+                };
+                ops.push(CLAIMABLE_ROUNDS.put_op(&key, &round).map_err(db_err)?);
+                marked.push(round);
+            },
+            (None, false) => {},
+        }
+    }
+
+    if !ops.is_empty() {
+        database.txn(&ops).map_err(db_err)?;
+    }
+    Ok(marked)
+}
+
+// keeper 가 fee payer 이자 호출자로 서명한 ProcessTimeout 을 보내고 트랜잭션 서명을 돌려준다.
+// round 는 스냅샷의 DaoState.round 이고, 승자가 있으면 그 RoundResult 계정의 rent 도 keeper 가 낸다.
+// 상금은 프로그램이 DAO 에서 last_submitter 로 바로 보낸다 (제출이 없었으면 기본 주소).
+pub async fn crank_claim<C: BlockhashSource + TransactionSubmitter + PriorityFeeSource>(
+    chain: &C,
+    keeper: &KeeperConfig,
+    program_id: &Pubkey,
+    dao: &Pubkey,
+    round: u64,
+    last_submitter: &Pubkey,
+) -> Result<String, String> {
+    let caller = keeper.keeper();
+    let fee = priority_fee(chain, &[*dao], None).await.map_err(|e| e.to_string())?;
+    let recent_blockhash = chain.latest_blockhash().await.map_err(|e| e.to_string())?;
+    let mut instructions = compute_budget_instructions(fee);
+    instructions.push(instruction::process_timeout(program_id, &caller, dao, round, last_submitter));
+    let message = Message::new(&instructions, &caller, recent_blockhash);
+    let mut transaction = Transaction::unsigned(message);
+    transaction.signatures[0] = keeper.keypair.sign(&transaction.message.serialize()).to_bytes();
+    chain.send_transaction(&transaction.serialize()).await.map_err(|e| e.to_string())
+}
+
+// claimable 라운드마다 ProcessTimeout 을 보내고 결과를 라운드에 남긴다. 보낸 트랜잭션 수를 돌려준다.
+// 반영되면 다음 체인 동기화에서 마감이 밀려나 표시가 지워지고, 그대로면 CRANK_RETRY_SECS 뒤에 다시 보낸다.
+pub async fn crank_claimable_rounds<T: SafeDatabase, C: BlockhashSource + TransactionSubmitter + PriorityFeeSource>(
+    database: &T,
+    chain: &C,
+    keeper: &KeeperConfig,
+    program_id: &Pubkey,
+    now: u64,
+) -> Result<usize, CountdownError> {
+    let mut sent = 0;
+    for (key, mut round) in CLAIMABLE_ROUNDS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        if round.crank_attempted_at.is_some_and(|at| at + CRANK_RETRY_SECS > now) {
+            continue;
+        }
+        let (round_number, last_submitter) = DAO_SNAPSHOTS.get(database, round.dao.as_bytes()).map_err(db_err)?
+            .map_or((0, Pubkey::default()), |snapshot| (snapshot.round, snapshot.last_submitter.parse().unwrap_or_default()));
+        let result = match round.dao.parse::<Pubkey>() {
+            Ok(dao) => crank_claim(chain, keeper, program_id, &dao, round_number, &last_submitter).await,
+            Err(e) => Err(e.to_string()),
+        };
+
+        round.crank_attempted_at = Some(now);
+        match result {
+            Ok(signature) => {
+                tracing::info!(dao = %round.dao, %signature, "Sent ProcessTimeout for claimable round");
+                round.crank_signature = Some(signature);
+                round.crank_error = None;
+                sent += 1;
+            },
+            Err(e) => {
+                tracing::warn!(dao = %round.dao, error = %e, "Keeper crank failed");
+                round.crank_error = Some(e);
+            },
+        }
+        CLAIMABLE_ROUNDS.put(database, &key, &round).map_err(db_err)?;
+    }
+    Ok(sent)
+}
+
+// 마감이 지난 라운드를 표시하고, keeper 가 있으면 ProcessTimeout 까지 보낸다.
+// keeper 는 program_id 가 설정돼 있어야 쓸 수 있다.
+pub fn register_countdown_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>, keeper: Option<KeeperConfig>) {
+    let keeper = match (keeper, tx_config()) {
+        (Some(keeper), Ok(config)) => Some(Arc::new((keeper, config))),
+        (Some(_), Err(e)) => {
+            tracing::warn!(error = %e, "Keeper crank disabled");
+            None
+        },
+        (None, _) => None,
+    };
+
+    scheduler.every("countdown", COUNTDOWN_INTERVAL, Duration::ZERO, move |database| {
+        let keeper = keeper.clone();
+        async move {
+            for round in mark_claimable_rounds(&database, now_secs()).map_err(|e| e.to_string())? {
+                tracing::info!(dao = %round.dao, deadline = round.deadline, pot_size = round.pot_size, "Round is claimable");
+            }
+            if let Some(keeper) = keeper.as_deref() {
+                let (keeper, config) = keeper;
+                crank_claimable_rounds(&database, &config.rpc, keeper, &config.program_id, now_secs())
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::DaoSnapshot;
+    use crate::relay::RelayError;
+    use crate::tx::TxError;
+
+    const PROGRAM_ID: Pubkey = Pubkey([3; 32]);
+
+    struct FakeChain(Mutex<Vec<Transaction>>);
+
+    impl BlockhashSource for FakeChain {
+        async fn latest_blockhash(&self) -> Result<[u8; 32], TxError> {
+            Ok([9; 32])
+        }
+    }
+
+    impl PriorityFeeSource for FakeChain {
+        async fn recent_prioritization_fees(&self, _accounts: &[Pubkey]) -> Result<Vec<u64>, TxError> {
+            Ok(vec![300])
+        }
+    }
+
+    impl TransactionSubmitter for FakeChain {
+        async fn send_transaction(&self, transaction: &[u8]) -> Result<String, RelayError> {
+            self.0.lock().unwrap().push(Transaction::deserialize(transaction).unwrap());
+            Ok("sig".to_string())
+        }
+    }
+
+    fn snapshot(dao: &Pubkey, timeout_timestamp: u64) -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: dao.to_string(),
+            dao_name: "turtles".to_string(),
+            initializer: Pubkey([1; 32]).to_string(),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: false,
+            deposit_share: 10,
+            timeout_timestamp,
+            total_deposit: 7_000,
+            depositor_count: 1,
+            content_count: 2,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_marks_and_cranks_expired_rounds() {
+        let database = MemoryDatabase::new();
+        let expired = Pubkey([8; 32]);
+        let running = Pubkey([9; 32]);
+        let dormant = Pubkey([10; 32]);
+        let winner = Pubkey([11; 32]);
+        let expired_snapshot = DaoSnapshot { round: 3, last_submitter: winner.to_string(), ..snapshot(&expired, 1_000) };
+        DAO_SNAPSHOTS.put(&database, expired.to_string().as_bytes(), &expired_snapshot).unwrap();
+        DAO_SNAPSHOTS.put(&database, running.to_string().as_bytes(), &snapshot(&running, 5_000)).unwrap();
+        DAO_SNAPSHOTS.put(&database, dormant.to_string().as_bytes(), &snapshot(&dormant, 0)).unwrap();
+
+        let marked = mark_claimable_rounds(&database, 1_500).unwrap();
+        assert_eq!(marked.len(), 1);
+        assert_eq!((marked[0].dao.as_str(), marked[0].deadline, marked[0].pot_size), (expired.to_string().as_str(), 1_000, 7_000));
+        // 이미 표시한 라운드는 다시 돌려주지 않는다
+        assert!(mark_claimable_rounds(&database, 1_600).unwrap().is_empty());
+
+        let chain = FakeChain(Mutex::new(Vec::new()));
+        let keeper = KeeperConfig { keypair: SigningKey::from_bytes(&[5; 32]) };
+        assert_eq!(crank_claimable_rounds(&database, &chain, &keeper, &PROGRAM_ID, 1_600).await.unwrap(), 1);
+        let sent = chain.0.lock().unwrap()[0].clone();
+        assert_eq!(*sent.message.fee_payer(), keeper.keeper());
+        assert!(sent.is_signed_by(0));
+        assert_eq!(sent.message.instructions.last().unwrap().data, vec![5]);
+        // 스냅샷의 round 번호로 RoundResult 계정을 넘긴다
+        let (round_result, _) = instruction::round_result_address(&PROGRAM_ID, &expired, 3).unwrap();
+        assert!(sent.message.account_keys.contains(&round_result));
+        // 상금을 받을 마지막 제출자도 같이 넘긴다
+        assert!(sent.message.account_keys.contains(&winner));
+        assert_eq!(instruction::ComputeBudgetInstruction::unpack(&sent.message.instructions[1].data).unwrap(), instruction::ComputeBudgetInstruction::SetComputeUnitPrice(300));
+
+        let round = CLAIMABLE_ROUNDS.get(&database, expired.to_string().as_bytes()).unwrap().unwrap();
+        assert_eq!((round.crank_attempted_at, round.crank_signature.as_deref()), (Some(1_600), Some("sig")));
+
+        // 반영되기 전에는 재시도 간격이 지나야 다시 보낸다
+        assert_eq!(crank_claimable_rounds(&database, &chain, &keeper, &PROGRAM_ID, 1_630).await.unwrap(), 0);
+        assert_eq!(crank_claimable_rounds(&database, &chain, &keeper, &PROGRAM_ID, 1_600 + CRANK_RETRY_SECS).await.unwrap(), 1);
+
+        // 타임아웃이 처리돼 마감이 밀려나면 표시를 지운다
+        DAO_SNAPSHOTS.put(&database, expired.to_string().as_bytes(), &snapshot(&expired, 5_300)).unwrap();
+        assert!(mark_claimable_rounds(&database, 1_700).unwrap().is_empty());
+        assert!(CLAIMABLE_ROUNDS.scan(&database, &KeyRange::all()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,332 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::error::Error as StdError;
+use std::fmt;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::community::{Community, DaoInfo};
+use turtle_service::parser::upload::StorageKind;
+use crate::pagination::{in_order, paginate, ListQuery, ListQueryError, SortOrder};
+use crate::error::ApiError;
+
+// pubkey -> DaoInfo
+pub const DAO_REGISTRY: TypedTable<DaoInfo> = TypedTable::new("dao_registry");
+// 인덱서가 체인에서 읽어 와 저장해 둔 DAO 상태 (/api/dao/community 와 같은 테이블)
+pub const COMMUNITY_CACHE: TypedTable<Community> = TypedTable::new("community");
+
+pub const DEFAULT_DAO_LIMIT: usize = 50;
+pub const MAX_DAO_LIMIT: usize = 100;
+pub const DAO_LIST_FILTERS: &[&str] = &["storage"];
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegisterDaoRequest {
+    pubkey: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    image_uri: Option<String>,
+    // 업로드 기본 저장소 ("ipfs" 또는 "arweave")
+    #[serde(default)]
+    storage: StorageKind,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DaoQuery {
+    pubkey: String,
+}
+
+// 캐시된 체인 상태로 계산한 요약. 아직 캐시가 없으면 summary 는 null 이다.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DaoSummary {
+    time_limit: u64,
+    base_fee: u64,
+    pot_size: u64,
+    last_activity_timestamp: u64,
+    // 마지막 활동 후 time_limit 이 지나기까지 남은 초
+    countdown: u64,
+    expired: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DaoResponse {
+    #[serde(flatten)]
+    info: DaoInfo,
+    summary: Option<DaoSummary>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DaosResponse {
+    daos: Vec<DaoResponse>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum DaoRegistryError {
+    DatabaseError(String),
+    ValidationError(String),
+    AlreadyRegistered(String),
+    NotFound(String),
+}
+
+impl fmt::Display for DaoRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DaoRegistryError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            DaoRegistryError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            DaoRegistryError::AlreadyRegistered(pubkey) => write!(f, "DAO already registered: {}", pubkey),
+            DaoRegistryError::NotFound(pubkey) => write!(f, "DAO not found: {}", pubkey),
+        }
+    }
+}
+
+impl StdError for DaoRegistryError {}
+
+impl IntoResponse for DaoRegistryError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            DaoRegistryError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DaoRegistryError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            DaoRegistryError::AlreadyRegistered(_) => StatusCode::CONFLICT,
+            DaoRegistryError::NotFound(_) => StatusCode::NOT_FOUND,
+        };
+
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// 32바이트 공개키의 base58 표기는 32~44자다
+fn is_valid_pubkey(pubkey: &str) -> bool {
+    (32..=44).contains(&pubkey.len()) && pubkey.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+fn summarize(community: &Community, now: u64) -> DaoSummary {
+    let deadline = community.last_activity_timestamp.saturating_add(community.time_limit);
+    DaoSummary {
+        time_limit: community.time_limit,
+        base_fee: community.base_fee,
+        pot_size: community.total_deposit,
+        last_activity_timestamp: community.last_activity_timestamp,
+        countdown: deadline.saturating_sub(now),
+        expired: now >= deadline,
+    }
+}
+
+fn with_summary<T: SafeDatabase>(database: &T, info: DaoInfo, now: u64) -> Result<DaoResponse, DaoRegistryError> {
+    let summary = COMMUNITY_CACHE.get(database, info.pubkey.as_bytes())
+        .map_err(|e| DaoRegistryError::DatabaseError(e.to_string()))?
+        .map(|community| summarize(&community, now));
+    Ok(DaoResponse { info, summary })
+}
+
+#[utoipa::path(
+    post, path = "/api/dao", tag = "dao",
+    request_body = RegisterDaoRequest,
+    responses(
+        (status = 201, body = DaoInfo),
+        (status = 400, description = "Invalid pubkey or name", body = String),
+        (status = 409, description = "Already registered", body = String),
+    )
+)]
+pub async fn register_dao<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<RegisterDaoRequest>,
+) -> Result<(StatusCode, Json<DaoInfo>), DaoRegistryError> {
+    if !is_valid_pubkey(&request.pubkey) {
+        return Err(DaoRegistryError::ValidationError(format!("Invalid DAO pubkey: {}", request.pubkey)));
+    }
+    if request.name.trim().is_empty() {
+        return Err(DaoRegistryError::ValidationError("DAO name cannot be empty".to_string()));
+    }
+
+    let existing = DAO_REGISTRY.get(&database, request.pubkey.as_bytes())
+        .map_err(|e| DaoRegistryError::DatabaseError(e.to_string()))?;
+    if existing.is_some() {
+        return Err(DaoRegistryError::AlreadyRegistered(request.pubkey));
+    }
+
+    let info = DaoInfo {
+        pubkey: request.pubkey,
+        name: request.name,
+        description: request.description,
+        image_uri: request.image_uri,
+        storage: request.storage,
+        registered_at: now_secs(),
+    };
+    DAO_REGISTRY.put(&database, info.pubkey.as_bytes(), &info)
+        .map_err(|e| DaoRegistryError::DatabaseError(e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
+// 등록된 DAO 를 pubkey 순서로. filter[storage]=ipfs|arweave 로 기본 저장소를 거를 수 있다.
+#[utoipa::path(
+    get, path = "/api/daos", tag = "dao",
+    params(ListQuery),
+    responses(
+        (status = 200, body = DaosResponse),
+        (status = 400, description = "Unknown filter/sort or invalid cursor", body = String),
+    )
+)]
+pub async fn list_daos<T: SafeDatabase>(
+    State(database): State<T>,
+    query: ListQuery,
+) -> Result<Json<DaosResponse>, DaoRegistryError> {
+    query.check_filters(DAO_LIST_FILTERS)?;
+    query.sort(&["pubkey"])?;
+    let limit = query.limit(DEFAULT_DAO_LIMIT, MAX_DAO_LIMIT);
+    let order = query.order(SortOrder::Asc);
+    let storage = query.filter("storage")
+        .map(|storage| serde_json::from_value::<StorageKind>(serde_json::Value::from(storage))
+            .map_err(|_| DaoRegistryError::ValidationError(format!("filter[storage] must be ipfs or arweave: {}", storage))))
+        .transpose()?;
+
+    let now = now_secs();
+    let rows = DAO_REGISTRY.scan(&database, &query.range(KeyRange::all(), order)?)
+        .map_err(|e| DaoRegistryError::DatabaseError(e.to_string()))?;
+    let daos = in_order(rows, order)
+        .into_iter()
+        .filter(|(_, info)| storage.is_none_or(|storage| info.storage == storage))
+        .map(|(key, info)| with_summary(&database, info, now).map(|dao| (key, dao)));
+    let (daos, next_cursor) = paginate(daos, limit)?;
+
+    Ok(Json(DaosResponse { daos, next_cursor }))
+}
+
+impl From<ListQueryError> for DaoRegistryError {
+    fn from(e: ListQueryError) -> Self {
+        match e {
+            ListQueryError::InvalidQuery(msg) => DaoRegistryError::ValidationError(msg),
+            ListQueryError::DatabaseError(msg) => DaoRegistryError::DatabaseError(msg),
+        }
+    }
+}
+
+#[utoipa::path(
+    get, path = "/api/dao", tag = "dao",
+    params(DaoQuery),
+    responses(
+        (status = 200, body = DaoResponse),
+        (status = 404, description = "Unknown DAO", body = String),
+    )
+)]
+pub async fn get_dao_summary<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<DaoQuery>,
+) -> Result<Json<DaoResponse>, DaoRegistryError> {
+    let info = DAO_REGISTRY.get(&database, query.pubkey.as_bytes())
+        .map_err(|e| DaoRegistryError::DatabaseError(e.to_string()))?
+        .ok_or(DaoRegistryError::NotFound(query.pubkey))?;
+
+    Ok(Json(with_summary(&database, info, now_secs())?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    const DAO_A: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+    const DAO_B: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+
+    fn registration(pubkey: &str, name: &str) -> RegisterDaoRequest {
+        RegisterDaoRequest {
+            pubkey: pubkey.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            image_uri: None,
+            storage: StorageKind::Ipfs,
+        }
+    }
+
+    async fn register(database: &MemoryDatabase, pubkey: &str, name: &str) {
+        let (status, Json(info)) = register_dao(State(database.clone()), Json(registration(pubkey, name)))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(info.pubkey, pubkey);
+    }
+
+    fn community(last_activity_timestamp: u64, time_limit: u64) -> Community {
+        Community {
+            admin: DAO_A.to_string(),
+            time_limit,
+            base_fee: 1_000,
+            ai_moderation: false,
+            deposit_share: 10,
+            last_activity_timestamp,
+            total_deposit: 5_000,
+            active_proposal_count: 0,
+            content_count: 0,
+            depositor_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_list() {
+        let database = MemoryDatabase::new();
+        register(&database, DAO_A, "turtles").await;
+        register(&database, DAO_B, "rabbits").await;
+
+        let duplicate = register_dao(State(database.clone()), Json(registration(DAO_A, "again"))).await;
+        assert!(matches!(duplicate, Err(DaoRegistryError::AlreadyRegistered(_))));
+
+        let invalid = register_dao(State(database.clone()), Json(registration("not-a-pubkey", "x"))).await;
+        assert!(matches!(invalid, Err(DaoRegistryError::ValidationError(_))));
+
+        let list = |query: &str| list_daos(State(database.clone()), ListQuery::from_uri(&format!("/api/daos?{}", query).parse().unwrap()).unwrap());
+        let Json(response) = list("").await.unwrap();
+        let mut names: Vec<&str> = response.daos.iter().map(|dao| dao.info.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["rabbits", "turtles"]);
+        assert!(response.daos.iter().all(|dao| dao.summary.is_none()));
+        assert_eq!(response.next_cursor, None);
+
+        // 한 개씩 넘겨 보면 두 번째 페이지에서 끝난다
+        let Json(first) = list("limit=1").await.unwrap();
+        let cursor = first.next_cursor.unwrap();
+        let Json(second) = list(&format!("limit=1&cursor={}", cursor)).await.unwrap();
+        assert_eq!(second.next_cursor, None);
+        assert_ne!(first.daos[0].info.pubkey, second.daos[0].info.pubkey);
+
+        assert!(list("filter[storage]=arweave").await.unwrap().0.daos.is_empty());
+        assert!(matches!(list("filter[storage]=s3").await, Err(DaoRegistryError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_summary_uses_cached_community() {
+        let database = MemoryDatabase::new();
+        register(&database, DAO_A, "turtles").await;
+        COMMUNITY_CACHE.put(&database, DAO_A.as_bytes(), &community(now_secs(), 3_600)).unwrap();
+
+        let Json(response) = get_dao_summary(State(database.clone()), Query(DaoQuery { pubkey: DAO_A.to_string() }))
+            .await
+            .unwrap();
+        let summary = response.summary.unwrap();
+        assert_eq!((summary.base_fee, summary.pot_size), (1_000, 5_000));
+        assert!(summary.countdown > 3_500 && !summary.expired);
+
+        let missing = get_dao_summary(State(database), Query(DaoQuery { pubkey: DAO_B.to_string() })).await;
+        assert!(matches!(missing, Err(DaoRegistryError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_countdown_stops_at_zero() {
+        let summary = summarize(&community(1_000, 60), 2_000);
+        assert_eq!(summary.countdown, 0);
+        assert!(summary.expired);
+
+        let summary = summarize(&community(1_000, 60), 1_030);
+        assert_eq!(summary.countdown, 30);
+        assert!(!summary.expired);
+    }
+}
@@ -0,0 +1,421 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error as StdError;
+use std::fmt;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::kv::KeyRange;
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use crate::indexer::{CLAIM_EVENTS, DEPOSIT_EVENTS};
+use crate::scheduler::month_day;
+use crate::sync::DAO_SNAPSHOTS;
+use crate::error::ApiError;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug)]
+pub enum EarningsError {
+    DatabaseError(String),
+    ValidationError(String),
+}
+
+impl fmt::Display for EarningsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EarningsError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            EarningsError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+        }
+    }
+}
+
+impl StdError for EarningsError {}
+
+impl IntoResponse for EarningsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            EarningsError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            EarningsError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> EarningsError {
+    EarningsError::DatabaseError(e.to_string())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EarningsPeriod {
+    Day,
+    Week,
+    #[default]
+    Month,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EarningsFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize, Default)]
+pub struct EarningsQuery {
+    // block_time 기준 [from, to) 구간. 주면 block_time 이 없는 이벤트는 빠진다.
+    from: Option<u64>,
+    to: Option<u64>,
+    #[serde(default)]
+    period: EarningsPeriod,
+    #[serde(default)]
+    format: EarningsFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EarningKind {
+    // 우승 라운드가 끝날 때 예치액 비율만큼 돌려받은 몫
+    RoundReward,
+    // 우승 콘텐츠 작성자가 받는 몫 (수수료 중 deposit_share%)
+    QualityReward,
+}
+
+impl EarningKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EarningKind::RoundReward => "round_reward",
+            EarningKind::QualityReward => "quality_reward",
+        }
+    }
+}
+
+// Claim 이벤트 하나에서 지갑이 받은 금액
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EarningEntry {
+    pub event_id: u64,
+    pub signature: String,
+    pub dao: String,
+    pub kind: EarningKind,
+    pub amount: u64,                    // lamports
+    pub block_time: Option<u64>,
+}
+
+// tips 와 referrals 는 프로그램이 아직 해당 이벤트를 남기지 않아서 항상 0 이다
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct EarningTotals {
+    pub round_rewards: u64,
+    pub quality_rewards: u64,
+    pub tips: u64,
+    pub referrals: u64,
+    pub total: u64,
+}
+
+impl EarningTotals {
+    fn add(&mut self, entry: &EarningEntry) {
+        match entry.kind {
+            EarningKind::RoundReward => self.round_rewards += entry.amount,
+            EarningKind::QualityReward => self.quality_rewards += entry.amount,
+        }
+        self.total += entry.amount;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeriodEarnings {
+    pub period_start: u64,
+    #[serde(flatten)]
+    pub totals: EarningTotals,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EarningsResponse {
+    pub wallet: String,
+    pub totals: EarningTotals,
+    // 오래된 구간부터. block_time 이 없는 이벤트는 totals 에만 들어간다.
+    pub periods: Vec<PeriodEarnings>,
+    pub entries: Vec<EarningEntry>,
+}
+
+// 우승자가 있던 라운드 하나의 분배
+pub struct ClaimedRound {
+    pub claim: EventRecord,
+    pub winner: String,
+    pub deposits: BTreeMap<String, u64>,    // 지난 분배 이후 지갑별 예치액
+    pub total_deposit: u64,
+    pub quality_share: u64,                 // 우승 콘텐츠 작성자 몫
+    pub remaining: u64,                     // 예치 비율로 나누는 몫
+}
+
+fn percent_of(amount: u64, percent: u64) -> u64 {
+    (u128::from(amount) * u128::from(percent.min(100)) / 100) as u64
+}
+
+// 라운드 분배: (우승 작성자 몫, 예치 비율로 나누는 몫). 두 몫의 합은 항상 total_deposit 이다.
+pub fn round_split(total_deposit: u64, base_fee: u64, deposit_share: u8) -> (u64, u64) {
+    let base_fee_amount = percent_of(total_deposit, base_fee);
+    let quality_share = percent_of(base_fee_amount, u64::from(deposit_share));
+    (quality_share, total_deposit - quality_share)
+}
+
+// 예치 비율 몫에서 deposit 만큼 받는 금액. 내림이라 예치자 몫을 다 더해도 remaining 을 넘지 않는다.
+pub fn deposit_payout(remaining: u64, deposit: u64, total_deposit: u64) -> u64 {
+    match total_deposit {
+        0 => 0,
+        total => (u128::from(remaining) * u128::from(deposit.min(total)) / u128::from(total)) as u64,
+    }
+}
+
+// 이벤트 ID 순서로 라운드를 다시 돌려 보며 우승자가 있던 라운드의 분배를 계산한다.
+// 프로그램의 process_timeout 과 같은 식이고, base_fee/deposit_share 는 지금 스냅샷 값을 쓴다
+// (거버넌스로 바뀌었다면 이전 라운드 금액은 어긋날 수 있다).
+pub fn claimed_rounds<T: SafeDatabase>(database: &T) -> Result<Vec<ClaimedRound>, EarningsError> {
+    let mut events: Vec<EventRecord> = Vec::new();
+    for table in [DEPOSIT_EVENTS, CLAIM_EVENTS] {
+        events.extend(table.scan(database, &KeyRange::all()).map_err(db_err)?.into_iter().map(|(_, record)| record));
+    }
+    events.sort_by_key(|record| record.id);
+
+    // dao -> 지난 분배 이후 지갑별 예치액
+    let mut rounds: HashMap<String, BTreeMap<String, u64>> = HashMap::new();
+    let mut claimed = Vec::new();
+    for record in events {
+        match &record.event {
+            TurtleEvent::Deposit { amount } => {
+                let deposit = rounds.entry(record.dao.clone()).or_default().entry(record.actor.clone()).or_default();
+                *deposit = deposit.saturating_add(*amount);
+            },
+            // 제출이 없던 라운드는 분배 없이 예치가 다음 라운드로 넘어간다
+            TurtleEvent::Claim { winner: Some(winner) } => {
+                let deposits = rounds.remove(&record.dao).unwrap_or_default();
+                let total_deposit = deposits.values().fold(0u64, |total, deposit| total.saturating_add(*deposit));
+                let Some(snapshot) = DAO_SNAPSHOTS.get(database, record.dao.as_bytes()).map_err(db_err)? else {
+                    continue;
+                };
+                let (quality_share, remaining) = round_split(total_deposit, snapshot.base_fee, snapshot.deposit_share);
+                claimed.push(ClaimedRound {
+                    winner: winner.clone(),
+                    deposits,
+                    total_deposit,
+                    quality_share,
+                    remaining,
+                    claim: record,
+                });
+            },
+            TurtleEvent::Claim { winner: None } | TurtleEvent::Submission | TurtleEvent::Vote { .. } => {},
+        }
+    }
+    Ok(claimed)
+}
+
+// wallet 이 라운드 분배로 받은 몫
+pub fn wallet_earnings<T: SafeDatabase>(database: &T, wallet: &str) -> Result<Vec<EarningEntry>, EarningsError> {
+    let mut entries = Vec::new();
+    for round in claimed_rounds(database)? {
+        let mut earn = |kind, amount: u64| if amount > 0 {
+            entries.push(EarningEntry {
+                event_id: round.claim.id,
+                signature: round.claim.signature.clone(),
+                dao: round.claim.dao.clone(),
+                kind,
+                amount,
+                block_time: round.claim.block_time,
+            });
+        };
+        if round.winner == wallet {
+            earn(EarningKind::QualityReward, round.quality_share);
+        }
+        if let Some(deposit) = round.deposits.get(wallet).filter(|_| round.total_deposit > 0) {
+            earn(EarningKind::RoundReward, deposit_payout(round.remaining, *deposit, round.total_deposit));
+        }
+    }
+    Ok(entries)
+}
+
+fn period_start(time: u64, period: EarningsPeriod) -> u64 {
+    let days = time / SECONDS_PER_DAY;
+    let start = match period {
+        EarningsPeriod::Day => days,
+        // 1970-01-01 은 목요일이다. 주는 월요일부터 센다.
+        EarningsPeriod::Week => days - (days + 3) % 7,
+        EarningsPeriod::Month => days - (month_day(days).1 - 1),
+    };
+    start * SECONDS_PER_DAY
+}
+
+fn summarize(wallet: &str, entries: Vec<EarningEntry>, period: EarningsPeriod) -> EarningsResponse {
+    let mut totals = EarningTotals::default();
+    let mut periods: BTreeMap<u64, EarningTotals> = BTreeMap::new();
+    for entry in &entries {
+        totals.add(entry);
+        if let Some(time) = entry.block_time {
+            periods.entry(period_start(time, period)).or_default().add(entry);
+        }
+    }
+    EarningsResponse {
+        wallet: wallet.to_string(),
+        totals,
+        periods: periods.into_iter().map(|(period_start, totals)| PeriodEarnings { period_start, totals }).collect(),
+        entries,
+    }
+}
+
+// 세금/정산 기록용. 금액은 lamports 그대로 쓴다.
+fn to_csv(entries: &[EarningEntry]) -> String {
+    let mut csv = String::from("block_time,dao,kind,amount_lamports,event_id,signature\n");
+    for entry in entries {
+        let block_time = entry.block_time.map(|time| time.to_string()).unwrap_or_default();
+        csv.push_str(&format!("{},{},{},{},{},{}\n", block_time, entry.dao, entry.kind.as_str(), entry.amount, entry.event_id, entry.signature));
+    }
+    csv
+}
+
+// 지갑이 라운드 분배로 받은 금액. format=csv 면 항목을 CSV 파일로 내려준다.
+pub async fn get_wallet_earnings<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(wallet): Path<String>,
+    Query(query): Query<EarningsQuery>,
+) -> Result<Response, EarningsError> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return Err(EarningsError::ValidationError("from must be before to".to_string()));
+        }
+    }
+
+    let ranged = query.from.is_some() || query.to.is_some();
+    let entries: Vec<EarningEntry> = wallet_earnings(&database, &wallet)?
+        .into_iter()
+        .filter(|entry| !ranged || entry.block_time.is_some_and(|time| {
+            query.from.is_none_or(|from| time >= from) && query.to.is_none_or(|to| time < to)
+        }))
+        .collect();
+
+    Ok(match query.format {
+        EarningsFormat::Json => Json(summarize(&wallet, entries, query.period)).into_response(),
+        EarningsFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"earnings-{}.csv\"", wallet)),
+            ],
+            to_csv(&entries),
+        ).into_response(),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::DaoSnapshot;
+    use crate::indexer::event_key;
+    use proptest::prelude::*;
+
+    fn put(database: &MemoryDatabase, id: u64, actor: &str, block_time: u64, event: TurtleEvent) {
+        let table = match event {
+            TurtleEvent::Deposit { .. } => DEPOSIT_EVENTS,
+            _ => CLAIM_EVENTS,
+        };
+        let record = EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: Some(block_time),
+            dao: "dao".to_string(),
+            actor: actor.to_string(),
+            event,
+        };
+        table.put(database, &event_key(id), &record).unwrap();
+    }
+
+    fn snapshot() -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: "dao".to_string(),
+            dao_name: "turtles".to_string(),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 50,
+            timeout_timestamp: 0,
+            total_deposit: 0,
+            depositor_count: 0,
+            content_count: 0,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rounds_split_into_rewards() {
+        let database = MemoryDatabase::new();
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot()).unwrap();
+        // 1월: 제출이 없어서 분배 없이 예치가 넘어간다
+        put(&database, 1, "alice", 0, TurtleEvent::Deposit { amount: 3_000 });
+        put(&database, 2, "keeper", 100, TurtleEvent::Claim { winner: None });
+        put(&database, 3, "bob", 200, TurtleEvent::Deposit { amount: 1_000 });
+        put(&database, 4, "keeper", 300, TurtleEvent::Claim { winner: Some("alice".to_string()) });
+        // 2월: bob 만 예치했다
+        put(&database, 5, "bob", 40 * SECONDS_PER_DAY, TurtleEvent::Deposit { amount: 2_000 });
+        put(&database, 6, "keeper", 40 * SECONDS_PER_DAY, TurtleEvent::Claim { winner: Some("alice".to_string()) });
+
+        // 4000 의 10% 수수료 중 50% (200) 가 우승자에게, 남은 3800 이 예치 비율로 (3/4 = 2850)
+        let entries = wallet_earnings(&database, "alice").unwrap();
+        let amounts: Vec<(EarningKind, u64)> = entries.iter().map(|entry| (entry.kind, entry.amount)).collect();
+        assert_eq!(amounts, [(EarningKind::QualityReward, 200), (EarningKind::RoundReward, 2_850), (EarningKind::QualityReward, 100)]);
+
+        let response = summarize("alice", entries, EarningsPeriod::Month);
+        assert_eq!((response.totals.quality_rewards, response.totals.round_rewards, response.totals.total), (300, 2_850, 3_150));
+        let periods: Vec<(u64, u64)> = response.periods.iter().map(|period| (period.period_start, period.totals.total)).collect();
+        assert_eq!(periods, [(0, 3_050), (31 * SECONDS_PER_DAY, 100)]);
+
+        let bob = summarize("bob", wallet_earnings(&database, "bob").unwrap(), EarningsPeriod::Day);
+        assert_eq!(bob.totals.round_rewards, 950 + 1_900);
+
+        let csv = to_csv(&wallet_earnings(&database, "alice").unwrap());
+        assert_eq!(csv.lines().nth(1), Some("300,dao,quality_reward,200,4,sig4"));
+        assert_eq!(csv.lines().count(), 4);
+
+        let query = EarningsQuery { from: Some(SECONDS_PER_DAY), ..Default::default() };
+        let response = get_wallet_earnings(State(database.clone()), Path("alice".to_string()), Query(query)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["totals"]["total"], 100);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_split_adds_up(total_deposit in any::<u64>(), base_fee in 0u64..=200, deposit_share in any::<u8>()) {
+            let (quality_share, remaining) = round_split(total_deposit, base_fee, deposit_share);
+            prop_assert_eq!(u128::from(quality_share) + u128::from(remaining), u128::from(total_deposit));
+            prop_assert!(quality_share <= remaining || deposit_share > 50);
+        }
+
+        // 예치 순서와 금액이 어떻든 작성자 몫과 예치자 몫을 더해도 예치 총액을 넘지 않는다.
+        // 예치 합은 u64 안에 들어오게 잡는다 (lamport 총량보다 훨씬 크다).
+        #[test]
+        fn prop_payouts_never_exceed_pot(
+            deposits in prop::collection::vec((0usize..5, 0..u64::MAX / 64), 1..40),
+            base_fee in 0u64..=100,
+            deposit_share in 0u8..=100,
+        ) {
+            let mut wallets: BTreeMap<usize, u64> = BTreeMap::new();
+            for (wallet, amount) in deposits {
+                *wallets.entry(wallet).or_default() += amount;
+            }
+            let total_deposit: u64 = wallets.values().sum();
+            let (quality_share, remaining) = round_split(total_deposit, base_fee, deposit_share);
+            let paid: u128 = wallets.values().map(|deposit| u128::from(deposit_payout(remaining, *deposit, total_deposit))).sum();
+            prop_assert!(paid <= u128::from(remaining));
+            prop_assert!(paid + u128::from(quality_share) <= u128::from(total_deposit));
+            // 내림으로 남는 건 예치자 한 명당 1 lamport 미만이다
+            prop_assert!(u128::from(remaining) - paid < wallets.len() as u128);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use axum::body::to_bytes;
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::error::Error as StdError;
+use std::fmt;
+use utoipa::ToSchema;
+
+// 본문 한도를 따로 주지 않은 라우트의 한도. JSON 요청만 받으니 넉넉하다 (axum 기본값은 2MB).
+pub const DEFAULT_BODY_LIMIT: usize = 64 * 1024;
+// 평문 에러 응답을 JSON 으로 바꿀 때 읽는 최대 크기. 넘으면 본문 없이 상태 코드만 담는다.
+const MAX_ERROR_BODY_BYTES: usize = 16 * 1024;
+
+// 모든 에러 응답의 모양: {"code": "not_found", "message": "...", "details": {...}}
+// 모듈 에러(XxxError) 는 상태 코드를 정하고 이걸로 응답을 만든다. code 는 상태 코드에서 정해진다.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<Value>,
+    #[serde(skip)]
+    retry_after: Option<u64>,
+}
+
+// 클라이언트가 분기할 때 쓰는 코드. 같은 상태 코드는 항상 같은 코드다.
+pub fn error_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => "validation_error",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::CONFLICT => "conflict",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+        StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS => "blocked",
+        StatusCode::INTERNAL_SERVER_ERROR => "internal_error",
+        StatusCode::BAD_GATEWAY => "upstream_error",
+        StatusCode::SERVICE_UNAVAILABLE => "unavailable",
+        StatusCode::GATEWAY_TIMEOUT => "upstream_timeout",
+        status if status.is_client_error() => "bad_request",
+        _ => "internal_error",
+    }
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, code: error_code(status), message: message.into(), details: None, retry_after: None }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    // Retry-After 헤더와 details.retry_after 를 함께 붙인다
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self.with_details(json!({ "retry_after": seconds }))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl StdError for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        let retry_after = self.retry_after;
+        let mut response = (status, Json(self)).into_response();
+        if let Some(seconds) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+fn is_json(response: &Response) -> bool {
+    response.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+// 핸들러 밖에서 만들어진 평문 에러(본문 한도 초과, JSON 추출 실패, 없는 경로/메서드 등) 도 ApiError 모양으로 바꾼다.
+// 이미 JSON 인 응답과 성공 응답은 그대로 둔다.
+pub async fn normalize_errors(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error()) || is_json(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let message = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) if !bytes.is_empty() => String::from_utf8_lossy(&bytes).trim().to_string(),
+        _ => status.canonical_reason().unwrap_or("Error").to_string(),
+    };
+    let normalized = ApiError::new(status, message).into_response();
+
+    // Retry-After, WWW-Authenticate, Allow 같은 헤더는 살리고 본문 관련 헤더만 바꾼다
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let (normalized_parts, body) = normalized.into_parts();
+    parts.headers.extend(normalized_parts.headers);
+    Response::from_parts(parts, body)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::middleware;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+    use crate::router::body_limited;
+
+    async fn json_body(response: Response) -> Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), 1 << 20).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_api_error_shape_and_retry_after() {
+        let response = ApiError::new(StatusCode::TOO_MANY_REQUESTS, "slow down").with_retry_after(7).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "7");
+        assert_eq!(json_body(response).await, json!({"code": "rate_limited", "message": "slow down", "details": {"retry_after": 7}}));
+    }
+
+    #[tokio::test]
+    async fn test_rejections_become_json_errors() {
+        let (_, router) = body_limited(("/echo".to_string(), Router::new().route("/echo", post(|Json(value): Json<Value>| async move { Json(value) }))), 16);
+        let app = router.layer(middleware::from_fn(normalize_errors));
+        let request = |body: &'static str| {
+            Request::post("/echo").header(header::CONTENT_TYPE, "application/json").body(Body::from(body)).unwrap()
+        };
+
+        let response = app.clone().oneshot(request(r#"{"a":1}"#)).await.unwrap();
+        assert_eq!(json_body(response).await, json!({"a": 1}));
+
+        let response = app.clone().oneshot(request(r#"{"a":"this body is too long"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(json_body(response).await["code"], "payload_too_large");
+
+        let response = app.oneshot(request("{")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(json_body(response).await["code"], "validation_error");
+    }
+}
@@ -0,0 +1,130 @@
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode, Uri};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::OnceLock;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::watch::TableVersions;
+
+// 캐시는 저장해도 되지만 쓸 때마다 If-None-Match 로 다시 확인해야 한다
+pub const ETAG_CACHE_CONTROL: &str = "public, no-cache";
+
+// 재시작하면 테이블 버전이 0 부터 다시 세지므로, 이전 프로세스가 준 ETag 와 겹치지 않게 섞는다
+fn boot_id() -> u64 {
+    static BOOT_ID: OnceLock<u64> = OnceLock::new();
+    *BOOT_ID.get_or_init(rand::random)
+}
+
+// 응답이 읽는 테이블들의 버전과 요청 URI(쿼리 포함) 로 만든다.
+// 압축된 응답과 원본이 같은 태그를 쓰므로 weak ETag 이다.
+pub fn etag_for<T: TableVersions>(database: &T, tables: &[&str], uri: &Uri) -> String {
+    let mut hasher = DefaultHasher::new();
+    boot_id().hash(&mut hasher);
+    uri.to_string().hash(&mut hasher);
+    for table in tables {
+        table.hash(&mut hasher);
+        database.table_version(table).hash(&mut hasher);
+    }
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+// If-None-Match 는 weak 비교라서 W/ 를 떼고 본다
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match.trim() == "*" || if_none_match.split(',').any(|tag| opaque(tag) == opaque(etag))
+}
+
+async fn conditional_get<T: TableVersions>(
+    State((database, tables)): State<(T, &'static [&'static str])>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    // 핸들러가 읽기 전에 버전을 본다. 그 사이에 쓰기가 끼면 태그가 옛것이라 다음 요청이 다시 받아 가게 된다.
+    let etag = etag_for(&database, tables, request.uri());
+    let not_modified = request.headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| matches_etag(value, &etag));
+    let Ok(etag) = HeaderValue::from_str(&etag) else {
+        return next.run(request).await;
+    };
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    let mut response = next.run(request).await;
+    if response.status() == StatusCode::OK {
+        response.headers_mut().insert(header::ETAG, etag);
+        response.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static(ETAG_CACHE_CONTROL));
+    }
+    response
+}
+
+// tables 는 핸들러가 읽는 테이블 전부여야 한다. 하나라도 빠지면 바뀐 응답에 304 를 줄 수 있다.
+pub fn etag_cached<T: SafeDatabase + TableVersions>(
+    component: (String, Router<T>),
+    database: &T,
+    tables: &'static [&'static str],
+) -> (String, Router<T>) {
+    let (path, router) = component;
+    let router = router.route_layer(middleware::from_fn_with_state((database.clone(), tables), conditional_get::<T>));
+    (path, router)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_database::watch::Watched;
+
+    async fn count(State(database): State<Watched<MemoryDatabase>>) -> String {
+        database.read_all("items").unwrap().len().to_string()
+    }
+
+    async fn send(app: &Router, uri: &str, if_none_match: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(tag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, tag);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_etag_follows_table_version() {
+        let database = Watched::new(MemoryDatabase::new());
+        let (_, router) = etag_cached(("/items".to_string(), Router::new().route("/items", get(count))), &database, &["items"]);
+        let app = router.with_state(database.clone());
+
+        let response = send(&app, "/items", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], ETAG_CACHE_CONTROL);
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        assert!(etag.starts_with("W/\""));
+
+        let response = send(&app, "/items", Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        // 목록이나 W/ 없는 형태로 보내도 같은 태그로 본다
+        let listed = format!("\"other\", {}", etag.trim_start_matches("W/"));
+        assert_eq!(send(&app, "/items", Some(&listed)).await.status(), StatusCode::NOT_MODIFIED);
+
+        // 쿼리가 다르면 다른 응답이다
+        assert_eq!(send(&app, "/items?limit=1", Some(&etag)).await.status(), StatusCode::OK);
+
+        // 쓰기가 있으면 태그가 바뀐다
+        database.write("a", "1", "items").unwrap();
+        let response = send(&app, "/items", Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag.as_str());
+    }
+}
@@ -0,0 +1,296 @@
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use tokio::sync::broadcast::{self, error::RecvError};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::watch::{ChangeEvent, ChangeFeed};
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use turtle_database::kv::KeyRange;
+use crate::indexer::{events_after, load_checkpoint, EVENT_TABLES};
+use crate::pagination::{in_order, paginate, ListQuery, ListQueryError, SortOrder};
+
+pub const DEFAULT_REPLAY: u64 = 20;
+pub const MAX_REPLAY: u64 = 200;
+// 한 번에 테이블에서 읽어 오는 이벤트 수
+const EVENT_BATCH: usize = 100;
+
+pub const DEFAULT_EVENT_LIMIT: usize = 50;
+pub const MAX_EVENT_LIMIT: usize = 200;
+pub const EVENT_LIST_FILTERS: &[&str] = &["kind", "dao", "actor"];
+// EVENT_TABLES 와 같은 순서의 filter[kind] 값 (SSE 의 event 이름과 같다)
+const EVENT_KINDS: [&str; 4] = ["deposit", "submission", "claim", "vote"];
+
+#[derive(Deserialize)]
+pub struct EventStreamQuery {
+    replay: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct EventList {
+    events: Vec<EventRecord>,
+    next_cursor: Option<String>,
+}
+
+// SSE 의 event 필드. 클라이언트는 addEventListener("deposit", ...) 처럼 종류별로 받을 수 있다.
+fn event_name(event: &TurtleEvent) -> &'static str {
+    match event {
+        TurtleEvent::Deposit { .. } => "deposit",
+        TurtleEvent::Submission => "submission",
+        TurtleEvent::Claim { .. } => "claim",
+        TurtleEvent::Vote { .. } => "vote",
+    }
+}
+
+fn to_sse(record: &EventRecord) -> Event {
+    let event = Event::default()
+        .id(record.id.to_string())
+        .event(event_name(&record.event));
+    event.json_data(record).unwrap_or_else(|_| Event::default().comment("unserializable event"))
+}
+
+fn is_event_write(change: &ChangeEvent) -> bool {
+    EVENT_TABLES.iter().any(|table| table.name() == change.table)
+}
+
+// 이 ID 이후의 이벤트부터 보낸다.
+// Last-Event-ID 가 있으면 거기서 이어 가고, 없으면 가장 최근 replay 개를 먼저 보낸다.
+fn resume_after<T: SafeDatabase>(database: &T, headers: &HeaderMap, replay: u64) -> Result<u64, String> {
+    if let Some(last_event_id) = headers.get("last-event-id") {
+        return last_event_id
+            .to_str()
+            .ok()
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| "Invalid Last-Event-ID".to_string());
+    }
+
+    let latest = load_checkpoint(database)
+        .map_err(|e| e.to_string())?
+        .next_event_id
+        .saturating_sub(1);
+    Ok(latest.saturating_sub(replay))
+}
+
+struct StreamState<T> {
+    database: T,
+    changes: broadcast::Receiver<ChangeEvent>,
+    last_id: u64,
+    pending: VecDeque<EventRecord>,
+}
+
+impl<T: SafeDatabase> StreamState<T> {
+    fn refill(&mut self) {
+        match events_after(&self.database, self.last_id, EVENT_BATCH) {
+            Ok(records) => self.pending.extend(records),
+            Err(e) => tracing::error!(error = %e, "Event stream read failed"),
+        }
+    }
+
+    async fn next_record(&mut self) -> Option<EventRecord> {
+        loop {
+            if let Some(record) = self.pending.pop_front() {
+                self.last_id = record.id;
+                return Some(record);
+            }
+
+            match self.changes.recv().await {
+                Ok(change) if is_event_write(&change) => self.refill(),
+                Ok(_) => {}
+                // 알림을 놓쳤으면 테이블에서 다시 읽어서 따라잡는다
+                Err(RecvError::Lagged(_)) => self.refill(),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+// after 다음 이벤트부터 ID 순서로. 따라잡으면 새로 인덱싱되는 이벤트를 기다린다 (SSE 와 gRPC 구독이 함께 쓴다).
+pub fn record_stream<T: SafeDatabase + ChangeFeed>(database: T, after: u64) -> impl Stream<Item = EventRecord> {
+    // 구독을 먼저 하고 나서 지난 이벤트를 읽어야 그 사이에 들어온 이벤트를 놓치지 않는다
+    let mut state = StreamState {
+        changes: database.subscribe(),
+        database,
+        last_id: after,
+        pending: VecDeque::new(),
+    };
+    state.refill();
+
+    stream::unfold(state, |mut state| async move {
+        let record = state.next_record().await?;
+        Some((record, state))
+    })
+}
+
+fn event_stream<T: SafeDatabase + ChangeFeed>(database: T, after: u64) -> impl Stream<Item = Result<Event, Infallible>> {
+    record_stream(database, after).map(|record| Ok(to_sse(&record)))
+}
+
+pub async fn stream_events<T: SafeDatabase + ChangeFeed>(
+    State(database): State<T>,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let replay = query.replay.unwrap_or(DEFAULT_REPLAY).min(MAX_REPLAY);
+    match resume_after(&database, &headers, replay) {
+        Ok(after) => Sse::new(event_stream(database, after))
+            .keep_alive(KeepAlive::default())
+            .into_response(),
+        Err(msg) => (StatusCode::BAD_REQUEST, msg).into_response(),
+    }
+}
+
+// 인덱싱된 이벤트를 최신부터 (order=asc 면 오래된 것부터).
+// filter[kind]=deposit|submission|claim|vote, filter[dao], filter[actor] 로 거를 수 있다.
+// 이벤트 ID 는 모든 이벤트 테이블에서 공유하므로 커서(event_key) 하나로 여러 테이블을 함께 넘긴다.
+pub async fn list_events<T: SafeDatabase>(
+    State(database): State<T>,
+    query: ListQuery,
+) -> Result<Json<EventList>, ListQueryError> {
+    query.check_filters(EVENT_LIST_FILTERS)?;
+    query.sort(&["id"])?;
+    let limit = query.limit(DEFAULT_EVENT_LIMIT, MAX_EVENT_LIMIT);
+    let order = query.order(SortOrder::Desc);
+    let tables = match query.filter("kind") {
+        None => EVENT_TABLES.to_vec(),
+        Some(kind) => match EVENT_KINDS.iter().position(|name| *name == kind) {
+            Some(index) => vec![EVENT_TABLES[index]],
+            None => return Err(ListQueryError::InvalidQuery(format!("filter[kind] must be one of {}: {}", EVENT_KINDS.join(", "), kind))),
+        },
+    };
+
+    let range = query.range(KeyRange::all(), order)?;
+    let mut rows = Vec::new();
+    for table in tables {
+        rows.extend(table.scan(&database, &range).map_err(|e| ListQueryError::DatabaseError(e.to_string()))?);
+    }
+    rows.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let (dao, actor) = (query.filter("dao"), query.filter("actor"));
+    let events = in_order(rows, order)
+        .into_iter()
+        .filter(|(_, record)| dao.is_none_or(|dao| record.dao == dao) && actor.is_none_or(|actor| record.actor == actor))
+        .map(Ok);
+    let (events, next_cursor) = paginate(events, limit)?;
+
+    Ok(Json(EventList { events, next_cursor }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use turtle_database::kv::KvStore;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_database::watch::Watched;
+    use crate::indexer::{event_key, IndexerCheckpoint, DEPOSIT_EVENTS, INDEXER_STATE, VOTE_EVENTS};
+
+    fn record(id: u64, event: TurtleEvent) -> EventRecord {
+        EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: None,
+            dao: "dao1".to_string(),
+            actor: "alice".to_string(),
+            event,
+        }
+    }
+
+    fn append(database: &Watched<MemoryDatabase>, record: EventRecord) {
+        let table = match record.event {
+            TurtleEvent::Vote { .. } => VOTE_EVENTS,
+            _ => DEPOSIT_EVENTS,
+        };
+        let checkpoint = IndexerCheckpoint {
+            last_signature: Some(record.signature.clone()),
+            last_slot: record.slot,
+            next_event_id: record.id + 1,
+        };
+        database.txn(&[
+            table.put_op(&event_key(record.id), &record).unwrap(),
+            INDEXER_STATE.put_op(b"checkpoint", &checkpoint).unwrap(),
+        ]).unwrap();
+    }
+
+    fn ids(records: &[EventRecord]) -> Vec<u64> {
+        records.iter().map(|record| record.id).collect()
+    }
+
+    #[test]
+    fn test_resume_point() {
+        let database = Watched::new(MemoryDatabase::new());
+        for id in 1..=5 {
+            append(&database, record(id, TurtleEvent::Deposit { amount: id }));
+        }
+
+        assert_eq!(resume_after(&database, &HeaderMap::new(), 2), Ok(3));
+        assert_eq!(resume_after(&database, &HeaderMap::new(), 50), Ok(0));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("last-event-id", "4".parse().unwrap());
+        assert_eq!(resume_after(&database, &headers, 2), Ok(4));
+        headers.insert("last-event-id", "abc".parse().unwrap());
+        assert!(resume_after(&database, &headers, 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replays_then_streams_new_events() {
+        let database = Watched::new(MemoryDatabase::new());
+        append(&database, record(1, TurtleEvent::Deposit { amount: 10 }));
+        append(&database, record(2, TurtleEvent::Vote { proposal_id: 0 }));
+
+        let mut state = StreamState {
+            changes: database.subscribe(),
+            database: database.clone(),
+            last_id: 1,
+            pending: VecDeque::new(),
+        };
+        state.refill();
+        assert_eq!(ids(state.pending.make_contiguous()), vec![2]);
+        assert_eq!(state.next_record().await.map(|record| record.id), Some(2));
+
+        append(&database, record(3, TurtleEvent::Deposit { amount: 30 }));
+        let next = state.next_record().await.unwrap();
+        assert_eq!((next.id, next.event), (3, TurtleEvent::Deposit { amount: 30 }));
+    }
+
+    #[tokio::test]
+    async fn test_list_events_across_tables() {
+        let database = Watched::new(MemoryDatabase::new());
+        for id in 1..=4 {
+            let event = match id % 2 {
+                0 => TurtleEvent::Vote { proposal_id: id },
+                _ => TurtleEvent::Deposit { amount: id },
+            };
+            append(&database, record(id, event));
+        }
+        let list = |query: &str| list_events(State(database.clone()), ListQuery::from_uri(&format!("/api/events?{}", query).parse().unwrap()).unwrap());
+
+        let Json(page) = list("limit=3").await.unwrap();
+        assert_eq!(ids(&page.events), vec![4, 3, 2]);
+        let Json(page) = list(&format!("limit=3&cursor={}", page.next_cursor.unwrap())).await.unwrap();
+        assert_eq!((ids(&page.events), page.next_cursor), (vec![1], None));
+
+        assert_eq!(ids(&list("filter[kind]=vote&order=asc").await.unwrap().0.events), vec![2, 4]);
+        assert!(list("filter[dao]=dao2").await.unwrap().0.events.is_empty());
+        assert!(list("filter[kind]=mint").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stream_yields_sse_events() {
+        let database = Watched::new(MemoryDatabase::new());
+        append(&database, record(1, TurtleEvent::Submission));
+
+        let mut events = Box::pin(event_stream(database.clone(), 0));
+        assert!(events.next().await.unwrap().is_ok());
+
+        append(&database, record(2, TurtleEvent::Claim { winner: None }));
+        assert!(events.next().await.unwrap().is_ok());
+    }
+}
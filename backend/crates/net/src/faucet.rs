@@ -0,0 +1,257 @@
+use axum::extract::{ConnectInfo, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use sol::state::Pubkey;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::{now_secs, TtlTable};
+use crate::admin::chain_reader;
+use crate::config::app_config;
+use crate::rpc::RpcClient;
+use crate::tenant::current_tenant;
+use crate::error::ApiError;
+
+// 지갑/IP 별 마지막 airdrop (ttl::TTL_TABLES). 키는 "{cluster}:wallet:{wallet}" / "{cluster}:ip:{ip}" 이고
+// 쿨다운이 끝나면 만료된다. 테넌트끼리 함께 쓰는 테이블이라 키에 cluster 를 넣는다.
+pub const FAUCET_TABLE: &str = "faucet_claims";
+// mainnet-beta 의 genesis hash. RPC 가 이 체인이면 설정과 상관없이 faucet 을 끈다.
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+// 한도 확인-기록을 직렬화
+static FAUCET_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug)]
+pub enum FaucetError {
+    DatabaseError(String),
+    ValidationError(String),
+    Disabled(String),
+    CoolingDown { retry_after: u64 },
+    RpcError(String),
+}
+
+impl fmt::Display for FaucetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaucetError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            FaucetError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            FaucetError::Disabled(msg) => write!(f, "Faucet disabled: {}", msg),
+            FaucetError::CoolingDown { retry_after } => write!(f, "Faucet already used, retry after {}s", retry_after),
+            FaucetError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+        }
+    }
+}
+
+impl StdError for FaucetError {}
+
+impl IntoResponse for FaucetError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            FaucetError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            FaucetError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            FaucetError::Disabled(_) => StatusCode::NOT_FOUND,
+            FaucetError::CoolingDown { retry_after } => {
+                return ApiError::new(StatusCode::TOO_MANY_REQUESTS, self.to_string()).with_retry_after(retry_after).into_response();
+            },
+            FaucetError::RpcError(_) => StatusCode::BAD_GATEWAY,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> FaucetError {
+    FaucetError::DatabaseError(e.to_string())
+}
+
+// airdrop 을 보내는 체인. 서버는 RpcClient 를 쓴다.
+pub trait FaucetChain: Send + Sync + 'static {
+    fn genesis_hash(&self) -> impl Future<Output = Result<String, String>> + Send;
+    fn request_airdrop(&self, wallet: &Pubkey, lamports: u64) -> impl Future<Output = Result<String, String>> + Send;
+}
+
+impl FaucetChain for RpcClient {
+    async fn genesis_hash(&self) -> Result<String, String> {
+        let result = self.call("getGenesisHash", json!([])).await.map_err(|e| e.to_string())?;
+        result.as_str().map(str::to_string).ok_or_else(|| format!("Unexpected getGenesisHash response: {}", result))
+    }
+
+    async fn request_airdrop(&self, wallet: &Pubkey, lamports: u64) -> Result<String, String> {
+        let result = self.call("requestAirdrop", json!([wallet.to_string(), lamports, { "commitment": "confirmed" }]))
+            .await
+            .map_err(|e| e.to_string())?;
+        result.as_str().map(str::to_string).ok_or_else(|| format!("Unexpected requestAirdrop response: {}", result))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FaucetSettings {
+    pub cluster: String,                // 한도 키의 앞부분 (기본 배포는 "default")
+    pub lamports: u64,
+    pub cooldown_secs: u64,
+}
+
+// 설정으로 꺼져 있거나 mainnet 테넌트면 None
+pub fn faucet_settings() -> Option<FaucetSettings> {
+    let config = app_config();
+    if config.faucet_lamports == 0 {
+        return None;
+    }
+    let cluster = current_tenant().map_or("default", |tenant| tenant.cluster());
+    if cluster == "mainnet" {
+        return None;
+    }
+    Some(FaucetSettings { cluster: cluster.to_string(), lamports: config.faucet_lamports, cooldown_secs: config.faucet_cooldown_secs })
+}
+
+#[derive(Deserialize)]
+pub struct FaucetRequest {
+    wallet: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FaucetResponse {
+    pub wallet: String,
+    pub lamports: u64,
+    pub signature: String,
+    // 이 지갑과 IP 가 다시 받을 수 있는 시각
+    pub next_claim_at: u64,
+}
+
+fn claim_keys(settings: &FaucetSettings, wallet: &Pubkey, ip: IpAddr) -> [String; 2] {
+    [
+        format!("{}:wallet:{}", settings.cluster, wallet),
+        format!("{}:ip:{}", settings.cluster, ip),
+    ]
+}
+
+// 지갑과 IP 모두 쿨다운이 지났는지 보고 지금 받은 것으로 기록한다
+fn reserve<T: SafeDatabase>(table: &TtlTable<T>, keys: &[String], until: u64, now: u64) -> Result<(), FaucetError> {
+    let _guard = FAUCET_LOCK.lock().unwrap();
+    let mut retry_after = 0;
+    for key in keys {
+        if let Some(bytes) = table.get_at(key, now).map_err(db_err)? {
+            let claimed_until = bytes.try_into().map(u64::from_be_bytes).unwrap_or(until);
+            retry_after = retry_after.max(claimed_until.saturating_sub(now));
+        }
+    }
+    if retry_after > 0 {
+        return Err(FaucetError::CoolingDown { retry_after });
+    }
+    for key in keys {
+        table.put_until(key, &until.to_be_bytes(), until).map_err(db_err)?;
+    }
+    Ok(())
+}
+
+pub async fn airdrop<T: SafeDatabase, C: FaucetChain>(
+    database: &T,
+    chain: &C,
+    settings: &FaucetSettings,
+    wallet: &str,
+    ip: IpAddr,
+    now: u64,
+) -> Result<FaucetResponse, FaucetError> {
+    let pubkey: Pubkey = wallet.parse().map_err(|_| FaucetError::ValidationError(format!("Invalid wallet: {}", wallet)))?;
+    // 설정이 devnet 이라고 해도 RPC 가 실제로 mainnet 이면 보내지 않는다
+    let genesis_hash = chain.genesis_hash().await.map_err(FaucetError::RpcError)?;
+    if genesis_hash == MAINNET_GENESIS_HASH {
+        return Err(FaucetError::Disabled("the RPC endpoint is mainnet".to_string()));
+    }
+
+    let table = TtlTable::new(database.clone(), FAUCET_TABLE);
+    let keys = claim_keys(settings, &pubkey, ip);
+    let next_claim_at = now.saturating_add(settings.cooldown_secs);
+    reserve(&table, &keys, next_claim_at, now)?;
+
+    match chain.request_airdrop(&pubkey, settings.lamports).await {
+        Ok(signature) => Ok(FaucetResponse { wallet: pubkey.to_string(), lamports: settings.lamports, signature, next_claim_at }),
+        // 보내지 못했으면 한도를 돌려준다
+        Err(e) => {
+            let _guard = FAUCET_LOCK.lock().unwrap();
+            for key in &keys {
+                table.remove(key).map_err(db_err)?;
+            }
+            Err(FaucetError::RpcError(e))
+        },
+    }
+}
+
+// devnet/testnet 에서 지갑에 SOL 을 조금 보내 준다. 지갑과 IP 마다 faucet_cooldown_secs 에 한 번.
+pub async fn request_faucet<T: SafeDatabase>(
+    State(database): State<T>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(request): Json<FaucetRequest>,
+) -> Result<Json<FaucetResponse>, FaucetError> {
+    let settings = faucet_settings().ok_or_else(|| FaucetError::Disabled("not available on this deployment".to_string()))?;
+    Ok(Json(airdrop(&database, &chain_reader(), &settings, &request.wallet, addr.ip(), now_secs()).await?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    struct FakeChain {
+        genesis: &'static str,
+        fail: bool,
+        airdrops: Mutex<Vec<(Pubkey, u64)>>,
+    }
+
+    impl FaucetChain for FakeChain {
+        async fn genesis_hash(&self) -> Result<String, String> {
+            Ok(self.genesis.to_string())
+        }
+
+        async fn request_airdrop(&self, wallet: &Pubkey, lamports: u64) -> Result<String, String> {
+            if self.fail {
+                return Err("airdrop limit".to_string());
+            }
+            self.airdrops.lock().unwrap().push((*wallet, lamports));
+            Ok("sig".to_string())
+        }
+    }
+
+    fn chain(genesis: &'static str, fail: bool) -> FakeChain {
+        FakeChain { genesis, fail, airdrops: Mutex::new(Vec::new()) }
+    }
+
+    #[tokio::test]
+    async fn test_limits_each_wallet_and_ip() {
+        let database = MemoryDatabase::new();
+        let settings = FaucetSettings { cluster: "devnet".to_string(), lamports: 500, cooldown_secs: 100 };
+        let devnet = chain("devnet-genesis", false);
+        let (alice, bob) = (Pubkey([1; 32]).to_string(), Pubkey([2; 32]).to_string());
+        let (ip1, ip2): (IpAddr, IpAddr) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+
+        let response = airdrop(&database, &devnet, &settings, &alice, ip1, 1_000).await.unwrap();
+        assert_eq!((response.lamports, response.next_claim_at), (500, 1_100));
+        // 같은 지갑은 다른 IP 로도, 같은 IP 는 다른 지갑으로도 못 받는다
+        assert!(matches!(airdrop(&database, &devnet, &settings, &alice, ip2, 1_010).await, Err(FaucetError::CoolingDown { retry_after: 90 })));
+        assert!(matches!(airdrop(&database, &devnet, &settings, &bob, ip1, 1_010).await, Err(FaucetError::CoolingDown { .. })));
+        // 쿨다운이 지나면 다시 받는다
+        airdrop(&database, &devnet, &settings, &alice, ip1, 1_100).await.unwrap();
+        assert_eq!(devnet.airdrops.lock().unwrap().len(), 2);
+
+        // 실패한 airdrop 은 한도를 쓰지 않는다
+        assert!(matches!(airdrop(&database, &chain("devnet-genesis", true), &settings, &bob, ip2, 1_100).await, Err(FaucetError::RpcError(_))));
+        airdrop(&database, &devnet, &settings, &bob, ip2, 1_100).await.unwrap();
+        assert!(matches!(airdrop(&database, &devnet, &settings, "not-a-key", ip2, 2_000).await, Err(FaucetError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refuses_mainnet_rpc() {
+        let database = MemoryDatabase::new();
+        let settings = FaucetSettings { cluster: "default".to_string(), lamports: 500, cooldown_secs: 100 };
+        let mainnet = chain(MAINNET_GENESIS_HASH, false);
+        let result = airdrop(&database, &mainnet, &settings, &Pubkey([1; 32]).to_string(), "10.0.0.1".parse().unwrap(), 0).await;
+        assert!(matches!(result, Err(FaucetError::Disabled(_))));
+        assert!(mainnet.airdrops.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use serde_json::{json, Value};
+use std::future::Future;
+use sol::instruction::{self, Instruction};
+use sol::state::Pubkey;
+use crate::rpc::RpcClient;
+use crate::tx::TxError;
+
+// 트랜잭션마다 잡아 두는 compute unit. Turtle instruction 하나는 이 안에서 끝난다 (런타임 기본값과 같다).
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+// 최근 slot 들의 우선 수수료 중 이 백분위를 쓴다
+pub const PRIORITY_FEE_PERCENTILE: usize = 75;
+// micro-lamports / CU 상한. 200k CU 면 0.0002 SOL 이다. priority_fee 로 직접 넘기는 값도 이걸 넘을 수 없다.
+pub const MAX_PRIORITY_FEE: u64 = 1_000_000;
+
+// 최근 slot 들에서 accounts 에 쓰기 잠금을 건 트랜잭션이 낸 우선 수수료 (micro-lamports / CU)
+pub trait PriorityFeeSource: Send + Sync + 'static {
+    fn recent_prioritization_fees(&self, accounts: &[Pubkey]) -> impl Future<Output = Result<Vec<u64>, TxError>> + Send;
+}
+
+impl PriorityFeeSource for RpcClient {
+    async fn recent_prioritization_fees(&self, accounts: &[Pubkey]) -> Result<Vec<u64>, TxError> {
+        let accounts: Vec<String> = accounts.iter().map(Pubkey::to_string).collect();
+        let result = self
+            .call("getRecentPrioritizationFees", json!([accounts]))
+            .await
+            .map_err(|e| TxError::RpcError(e.to_string()))?;
+        parse_prioritization_fees(&result)
+    }
+}
+
+// [{"slot": n, "prioritizationFee": n}, ...]
+fn parse_prioritization_fees(result: &Value) -> Result<Vec<u64>, TxError> {
+    result
+        .as_array()
+        .ok_or_else(|| TxError::RpcError(format!("Unexpected getRecentPrioritizationFees response: {}", result)))?
+        .iter()
+        .map(|entry| entry["prioritizationFee"].as_u64()
+            .ok_or_else(|| TxError::RpcError(format!("Unexpected prioritization fee entry: {}", entry))))
+        .collect()
+}
+
+// 수수료를 안 낸 slot (0) 도 포함해서 센다. 한산할 때는 0 에 가깝고 붐빌수록 올라간다.
+pub fn percentile_fee(fees: &[u64]) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    let mut fees = fees.to_vec();
+    fees.sort_unstable();
+    let index = (fees.len() * PRIORITY_FEE_PERCENTILE).div_ceil(100).saturating_sub(1);
+    fees[index].min(MAX_PRIORITY_FEE)
+}
+
+// requested 가 있으면 그대로 쓰고, 없으면 DAO 계정 기준으로 추정한다.
+// 추정이 실패해도 트랜잭션은 만들 수 있어야 하므로 0 으로 둔다.
+pub async fn priority_fee<S: PriorityFeeSource>(source: &S, accounts: &[Pubkey], requested: Option<u64>) -> Result<u64, TxError> {
+    match requested {
+        Some(fee) if fee > MAX_PRIORITY_FEE => Err(TxError::ValidationError(format!("priority_fee must be at most {} micro-lamports", MAX_PRIORITY_FEE))),
+        Some(fee) => Ok(fee),
+        None => match source.recent_prioritization_fees(accounts).await {
+            Ok(fees) => Ok(percentile_fee(&fees)),
+            Err(e) => {
+                tracing::warn!(error = %e, "Priority fee estimation failed, building without a priority fee");
+                Ok(0)
+            },
+        },
+    }
+}
+
+// 프로그램 instruction 앞에 붙이는 SetComputeUnitLimit, SetComputeUnitPrice
+pub fn compute_budget_instructions(priority_fee: u64) -> Vec<Instruction> {
+    vec![
+        instruction::set_compute_unit_limit(DEFAULT_COMPUTE_UNIT_LIMIT),
+        instruction::set_compute_unit_price(priority_fee),
+    ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fees(Result<Vec<u64>, String>);
+
+    impl PriorityFeeSource for Fees {
+        async fn recent_prioritization_fees(&self, _accounts: &[Pubkey]) -> Result<Vec<u64>, TxError> {
+            self.0.clone().map_err(TxError::RpcError)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_and_override() {
+        let result = json!([{"slot": 1, "prioritizationFee": 0}, {"slot": 2, "prioritizationFee": 500}]);
+        assert_eq!(parse_prioritization_fees(&result).unwrap(), [0, 500]);
+        assert!(parse_prioritization_fees(&json!({"value": []})).is_err());
+
+        assert_eq!(percentile_fee(&[]), 0);
+        assert_eq!(percentile_fee(&[0, 0, 100, 200]), 100);
+        assert_eq!(percentile_fee(&[40, 10, 30, 20, 0, 0, 0, 0]), 20);
+        assert_eq!(percentile_fee(&[u64::MAX]), MAX_PRIORITY_FEE);
+
+        let busy = Fees(Ok(vec![0, 1_000, 2_000, 3_000]));
+        assert_eq!(priority_fee(&busy, &[], None).await.unwrap(), 2_000);
+        assert_eq!(priority_fee(&busy, &[], Some(7)).await.unwrap(), 7);
+        assert!(matches!(priority_fee(&busy, &[], Some(MAX_PRIORITY_FEE + 1)).await, Err(TxError::ValidationError(_))));
+        // RPC 가 실패하면 우선 수수료 없이 만든다
+        assert_eq!(priority_fee(&Fees(Err("down".to_string())), &[], None).await.unwrap(), 0);
+    }
+}
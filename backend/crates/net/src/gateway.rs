@@ -0,0 +1,578 @@
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::error::DbResult;
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::chain::ChainContent;
+use turtle_service::parser::upload::BlockedMedia;
+use crate::config::{app_config, AppConfig};
+use crate::media::sniff_image;
+use crate::session::AuthedWallet;
+use crate::upload::UPLOADS;
+use crate::error::ApiError;
+
+// id -> BlockedMedia. 여기 있는 객체는 게이트웨이가 내주지 않고 캐시에서도 지운다.
+pub const BLOCKED_MEDIA: TypedTable<BlockedMedia> = TypedTable::new("blocked_media");
+
+pub const DEFAULT_ARWEAVE_GATEWAY_URL: &str = "https://arweave.net";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+// 주소가 내용의 해시라서 같은 ID 는 항상 같은 바이트다
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[derive(Debug)]
+pub enum MediaError {
+    ValidationError(String),
+    NotFound(String),
+    Blocked(String),
+    UpstreamError(String),
+    DatabaseError(String),
+    ConfigError(String),
+}
+
+impl fmt::Display for MediaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            MediaError::NotFound(id) => write!(f, "Media not found: {}", id),
+            MediaError::Blocked(id) => write!(f, "Media blocked: {}", id),
+            MediaError::UpstreamError(msg) => write!(f, "Upstream error: {}", msg),
+            MediaError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            MediaError::ConfigError(msg) => write!(f, "Config error: {}", msg),
+        }
+    }
+}
+
+impl StdError for MediaError {}
+
+impl IntoResponse for MediaError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            MediaError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            MediaError::NotFound(_) => StatusCode::NOT_FOUND,
+            MediaError::Blocked(_) => StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+            MediaError::UpstreamError(_) => StatusCode::BAD_GATEWAY,
+            MediaError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            MediaError::ConfigError(_) => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> MediaError {
+    MediaError::DatabaseError(e.to_string())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MediaLocation {
+    Ipfs(String),
+    Arweave(String),
+}
+
+// ID 모양으로 저장소를 고른다. 검사를 통과한 ID 는 캐시 파일 이름으로 그대로 쓴다.
+//   CIDv0: Qm 으로 시작하는 46자 base58, CIDv1: b 로 시작하는 base32 (소문자)
+//   Arweave: 43자 base64url
+pub fn parse_media_id(id: &str) -> Result<MediaLocation, MediaError> {
+    let cid_v0 = id.len() == 46 && id.starts_with("Qm") && bs58::decode(id).into_vec().is_ok();
+    let cid_v1 = (50..=100).contains(&id.len())
+        && id.starts_with('b')
+        && id.bytes().all(|b| b.is_ascii_lowercase() || (b'2'..=b'7').contains(&b));
+    let arweave = id.len() == 43 && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+
+    if cid_v0 || cid_v1 {
+        Ok(MediaLocation::Ipfs(id.to_string()))
+    } else if arweave {
+        Ok(MediaLocation::Arweave(id.to_string()))
+    } else {
+        Err(MediaError::ValidationError(format!("Not an IPFS CID or Arweave transaction id: {}", id)))
+    }
+}
+
+// ipfs://<cid>, ar://<id> 에서 ID 만 꺼낸다
+pub fn media_id(uri: &str) -> Option<&str> {
+    uri.strip_prefix("ipfs://")
+        .or_else(|| uri.strip_prefix("ar://"))
+        .filter(|id| parse_media_id(id).is_ok())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Media {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+// 저장소가 주는 content type 은 쓰지 않는다. 아는 이미지와 JSON 만 그 형식으로 내주고 나머지는 octet-stream.
+fn content_type_of(bytes: &[u8]) -> &'static str {
+    if let Ok(format) = sniff_image(bytes) {
+        format.to_mime_type()
+    } else if serde_json::from_slice::<serde_json::Value>(bytes).is_ok() {
+        "application/json"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+// 서버는 GatewaySource 를 쓰고, 테스트는 메모리에 담는다
+pub trait MediaSource: Send + Sync + 'static {
+    fn fetch(&self, location: &MediaLocation, max_bytes: u64) -> impl Future<Output = Result<Vec<u8>, MediaError>> + Send;
+}
+
+// IPFS 는 업로드에 쓰는 노드(ipfs_api_url)의 cat 으로, Arweave 는 게이트웨이에서 받는다
+pub struct GatewaySource {
+    http: reqwest::Client,
+    ipfs_api_url: String,
+    ipfs_api_auth: Option<String>,
+    arweave_gateway_url: String,
+}
+
+impl GatewaySource {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            ipfs_api_url: config.ipfs_api_url.trim_end_matches('/').to_string(),
+            ipfs_api_auth: config.ipfs_api_auth.clone(),
+            arweave_gateway_url: config.arweave_gateway_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl MediaSource for GatewaySource {
+    async fn fetch(&self, location: &MediaLocation, max_bytes: u64) -> Result<Vec<u8>, MediaError> {
+        let upstream = |e: reqwest::Error| MediaError::UpstreamError(e.to_string());
+        let (request, id) = match location {
+            MediaLocation::Ipfs(cid) => {
+                let mut request = self.http
+                    .post(format!("{}/api/v0/cat", self.ipfs_api_url))
+                    .query(&[("arg", cid)]);
+                if let Some(auth) = &self.ipfs_api_auth {
+                    request = request.header(reqwest::header::AUTHORIZATION, auth);
+                }
+                (request, cid)
+            },
+            MediaLocation::Arweave(id) => (self.http.get(format!("{}/{}", self.arweave_gateway_url, id)), id),
+        };
+
+        let response = request.timeout(FETCH_TIMEOUT).send().await.map_err(upstream)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(MediaError::NotFound(id.clone()));
+        }
+        let mut response = response.error_for_status().map_err(upstream)?;
+
+        // 크기를 미리 알려주지 않는 응답도 있어서 받으면서 센다
+        let too_large = || MediaError::UpstreamError(format!("{} is larger than {} bytes", id, max_bytes));
+        if response.content_length().is_some_and(|length| length > max_bytes) {
+            return Err(too_large());
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(upstream)? {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() as u64 > max_bytes {
+                return Err(too_large());
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+#[derive(Default)]
+struct CacheIndex {
+    // id -> (파일 크기, 마지막으로 쓴 순번)
+    entries: HashMap<String, (u64, u64)>,
+    total_bytes: u64,
+    clock: u64,
+}
+
+impl CacheIndex {
+    fn touch(&mut self, id: &str) -> bool {
+        self.clock += 1;
+        match self.entries.get_mut(id) {
+            Some((_, used)) => {
+                *used = self.clock;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, id: String, size: u64) {
+        self.remove(&id);
+        self.clock += 1;
+        self.total_bytes += size;
+        self.entries.insert(id, (size, self.clock));
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        match self.entries.remove(id) {
+            Some((size, _)) => {
+                self.total_bytes -= size;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn least_recently_used(&self) -> Option<String> {
+        self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(id, _)| id.clone())
+    }
+}
+
+// 디스크 캐시. 파일 하나에 "<content type>\n<바이트>" 를 담고, 합이 max_bytes 를 넘으면 오래 안 쓴 것부터 지운다.
+// 다시 띄우면 파일 수정 시각 순서로 색인을 만든다 (읽을 때마다 수정 시각을 갱신한다).
+pub struct MediaCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<CacheIndex>,
+}
+
+impl MediaCache {
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !metadata.is_file() {
+                continue;
+            }
+            // 쓰다가 멈춘 파일
+            if name.ends_with(".tmp") {
+                let _ = fs::remove_file(entry.path());
+                continue;
+            }
+            files.push((metadata.modified().unwrap_or(UNIX_EPOCH), name, metadata.len()));
+        }
+        files.sort();
+
+        let cache = Self { dir, max_bytes, index: Mutex::new(CacheIndex::default()) };
+        {
+            let mut index = cache.index.lock().unwrap();
+            for (_, id, size) in files {
+                index.insert(id, size);
+            }
+            cache.evict(&mut index);
+        }
+        Ok(cache)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Media> {
+        if !self.index.lock().unwrap().touch(id) {
+            return None;
+        }
+        let path = self.dir.join(id);
+        let decoded = fs::read(&path).ok().and_then(|bytes| {
+            let split = bytes.iter().position(|&b| b == b'\n')?;
+            let content_type = String::from_utf8(bytes[..split].to_vec()).ok()?;
+            Some(Media { content_type, bytes: bytes[split + 1..].to_vec() })
+        });
+        match decoded {
+            Some(media) => {
+                if let Ok(file) = fs::File::options().write(true).open(&path) {
+                    let _ = file.set_modified(SystemTime::now());
+                }
+                Some(media)
+            },
+            // 밖에서 지워졌거나 깨진 파일
+            None => {
+                self.remove(id);
+                None
+            },
+        }
+    }
+
+    pub fn put(&self, id: &str, media: &Media) -> io::Result<()> {
+        let mut contents = Vec::with_capacity(media.content_type.len() + 1 + media.bytes.len());
+        contents.extend_from_slice(media.content_type.as_bytes());
+        contents.push(b'\n');
+        contents.extend_from_slice(&media.bytes);
+        let size = contents.len() as u64;
+        if size > self.max_bytes {
+            return Ok(());
+        }
+
+        let path = self.dir.join(id);
+        let temp = self.dir.join(format!("{}.tmp", id));
+        fs::write(&temp, &contents)?;
+        fs::rename(&temp, &path)?;
+
+        let mut index = self.index.lock().unwrap();
+        index.insert(id.to_string(), size);
+        self.evict(&mut index);
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &str) {
+        if self.index.lock().unwrap().remove(id) {
+            let _ = fs::remove_file(self.dir.join(id));
+        }
+    }
+
+    fn evict(&self, index: &mut CacheIndex) {
+        while index.total_bytes > self.max_bytes {
+            let Some(id) = index.least_recently_used() else { break };
+            index.remove(&id);
+            let _ = fs::remove_file(self.dir.join(&id));
+        }
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.index.lock().unwrap().total_bytes
+    }
+}
+
+static MEDIA_CACHE: OnceLock<Result<MediaCache, String>> = OnceLock::new();
+static GATEWAY_SOURCE: OnceLock<GatewaySource> = OnceLock::new();
+
+pub fn media_cache() -> Result<&'static MediaCache, MediaError> {
+    MEDIA_CACHE
+        .get_or_init(|| {
+            let config = app_config();
+            MediaCache::open(&config.media_cache_dir, config.media_cache_max_bytes).map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| MediaError::ConfigError(format!("media cache unavailable: {}", e)))
+}
+
+pub fn gateway_source() -> &'static GatewaySource {
+    GATEWAY_SOURCE.get_or_init(|| GatewaySource::from_config(app_config()))
+}
+
+// 막힌 객체인지 먼저 보고, 캐시에 없으면 저장소에서 받아 캐시에 넣는다.
+// 캐시에 쓰지 못해도 받은 바이트는 내준다.
+pub async fn serve_media<T: SafeDatabase, S: MediaSource>(
+    database: &T,
+    cache: &MediaCache,
+    source: &S,
+    id: &str,
+    max_bytes: u64,
+) -> Result<Media, MediaError> {
+    let location = parse_media_id(id)?;
+    if BLOCKED_MEDIA.get(database, id.as_bytes()).map_err(db_err)?.is_some() {
+        cache.remove(id);
+        return Err(MediaError::Blocked(id.to_string()));
+    }
+    if let Some(media) = cache.get(id) {
+        return Ok(media);
+    }
+
+    let bytes = source.fetch(&location, max_bytes).await?;
+    let media = Media { content_type: content_type_of(&bytes).to_string(), bytes };
+    match cache.put(id, &media) {
+        Ok(()) => tracing::debug!(id, cache_bytes = cache.total_bytes(), "Cached media"),
+        Err(e) => tracing::warn!(id, error = %e, "Failed to cache media"),
+    }
+    Ok(media)
+}
+
+// GET /api/media/{id}. 공개 게이트웨이 대신 이 서버가 IPFS/Arweave 객체를 받아서 캐시해 둔다.
+pub async fn get_media<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(id): Path<String>,
+) -> Result<Response, MediaError> {
+    let media = serve_media(&database, media_cache()?, gateway_source(), &id, app_config().media_max_object_bytes).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, media.content_type),
+            (header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL.to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CONTENT_SECURITY_POLICY, "default-src 'none'; sandbox".to_string()),
+        ],
+        media.bytes,
+    ).into_response())
+}
+
+// 콘텐츠 이미지와, 업로드 기록이 있으면 그 축소본들의 ID
+fn content_media_ids<T: SafeDatabase>(database: &T, content: &ChainContent) -> DbResult<Vec<String>> {
+    let Some(id) = media_id(&content.image_uri) else {
+        return Ok(Vec::new());
+    };
+    let mut ids = vec![id.to_string()];
+    for (_, upload) in UPLOADS.scan(database, &KeyRange::all())? {
+        if upload.image_uri == content.image_uri {
+            ids.extend(upload.image_variants.iter().filter_map(|variant| media_id(&variant.uri)).map(str::to_string));
+        }
+    }
+    ids.sort();
+    ids.dedup();
+    Ok(ids)
+}
+
+// 모더레이터가 콘텐츠를 숨길 때 그 이미지도 막는다. 직접 막아 둔 항목은 건드리지 않는다.
+pub fn block_content_media_ops<T: SafeDatabase>(database: &T, content: &ChainContent, by: &str, now: u64) -> DbResult<Vec<KvOp>> {
+    let mut ops = Vec::new();
+    for id in content_media_ids(database, content)? {
+        if BLOCKED_MEDIA.get(database, id.as_bytes())?.is_some_and(|blocked| blocked.hidden_content.is_none()) {
+            continue;
+        }
+        let blocked = BlockedMedia {
+            id: id.clone(),
+            reason: None,
+            hidden_content: Some((content.dao.clone(), content.index)),
+            blocked_by: by.to_string(),
+            blocked_at: now,
+        };
+        ops.push(BLOCKED_MEDIA.put_op(id.as_bytes(), &blocked)?);
+    }
+    Ok(ops)
+}
+
+// 숨김을 풀면 그 콘텐츠 때문에 막힌 항목만 푼다
+pub fn unblock_content_media_ops<T: SafeDatabase>(database: &T, content: &ChainContent) -> DbResult<Vec<KvOp>> {
+    let source = Some((content.dao.clone(), content.index));
+    let mut ops = Vec::new();
+    for id in content_media_ids(database, content)? {
+        if BLOCKED_MEDIA.get(database, id.as_bytes())?.is_some_and(|blocked| blocked.hidden_content == source) {
+            ops.push(BLOCKED_MEDIA.delete_op(id.as_bytes()));
+        }
+    }
+    Ok(ops)
+}
+
+#[derive(Deserialize)]
+pub struct BlockMediaRequest {
+    id: String,
+    reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UnblockMediaRequest {
+    id: String,
+}
+
+// 콘텐츠와 상관없이 객체 하나를 막는다 (모더레이터)
+pub async fn block_media<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<BlockMediaRequest>,
+) -> Result<Json<BlockedMedia>, MediaError> {
+    parse_media_id(&request.id)?;
+    let blocked = BlockedMedia {
+        id: request.id.clone(),
+        reason: request.reason.map(|reason| reason.trim().to_string()).filter(|reason| !reason.is_empty()),
+        hidden_content: None,
+        blocked_by: wallet,
+        blocked_at: now_secs(),
+    };
+    BLOCKED_MEDIA.put(&database, request.id.as_bytes(), &blocked).map_err(db_err)?;
+    if let Ok(cache) = media_cache() {
+        cache.remove(&request.id);
+    }
+    Ok(Json(blocked))
+}
+
+pub async fn unblock_media<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<UnblockMediaRequest>,
+) -> Result<StatusCode, MediaError> {
+    if !BLOCKED_MEDIA.delete(&database, request.id.as_bytes()).map_err(db_err)? {
+        return Err(MediaError::NotFound(request.id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use turtle_database::kv::KvStore;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    const CID: &str = "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+    const ARWEAVE_ID: &str = "bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U";
+
+    // 요청마다 같은 바이트를 주고 몇 번 불렸는지 센다
+    struct FixedSource(Vec<u8>, AtomicUsize);
+
+    impl MediaSource for FixedSource {
+        async fn fetch(&self, _location: &MediaLocation, max_bytes: u64) -> Result<Vec<u8>, MediaError> {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            if self.0.len() as u64 > max_bytes {
+                return Err(MediaError::UpstreamError("too large".to_string()));
+            }
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_parse_media_ids() {
+        assert_eq!(parse_media_id(CID).unwrap(), MediaLocation::Ipfs(CID.to_string()));
+        assert!(matches!(parse_media_id("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG").unwrap(), MediaLocation::Ipfs(_)));
+        assert_eq!(parse_media_id(ARWEAVE_ID).unwrap(), MediaLocation::Arweave(ARWEAVE_ID.to_string()));
+        assert!(parse_media_id("../../etc/passwd").is_err());
+        assert!(parse_media_id("").is_err());
+
+        assert_eq!(media_id(&format!("ipfs://{}", CID)), Some(CID));
+        assert_eq!(media_id(&format!("ar://{}", ARWEAVE_ID)), Some(ARWEAVE_ID));
+        assert_eq!(media_id("https://example.com/a.png"), None);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let media = |byte: u8| Media { content_type: "image/png".to_string(), bytes: vec![byte; 90] };
+        // 파일 하나가 100 바이트 ("image/png\n" + 90)
+        let cache = MediaCache::open(dir.path(), 250).unwrap();
+
+        cache.put("a", &media(1)).unwrap();
+        cache.put("b", &media(2)).unwrap();
+        assert_eq!(cache.get("a"), Some(media(1)));
+        cache.put("c", &media(3)).unwrap();
+
+        // b 가 가장 오래 안 쓰였다
+        assert_eq!(cache.get("b"), None);
+        assert!(!dir.path().join("b").exists());
+        assert_eq!(cache.total_bytes(), 200);
+
+        // 다시 열어도 남은 파일을 그대로 쓴다
+        drop(cache);
+        let cache = MediaCache::open(dir.path(), 150).unwrap();
+        assert_eq!(cache.total_bytes(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_serves_cached_media_and_blocks_hidden_content() {
+        let database = MemoryDatabase::new();
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MediaCache::open(dir.path(), 1 << 20).unwrap();
+        let source = FixedSource(br#"{"text":"hello"}"#.to_vec(), AtomicUsize::new(0));
+
+        let media = serve_media(&database, &cache, &source, CID, 1024).await.unwrap();
+        assert_eq!(media.content_type, "application/json");
+        serve_media(&database, &cache, &source, CID, 1024).await.unwrap();
+        assert_eq!(source.1.load(Ordering::SeqCst), 1);
+        assert!(matches!(serve_media(&database, &cache, &source, ARWEAVE_ID, 4).await, Err(MediaError::UpstreamError(_))));
+
+        let content = ChainContent {
+            dao: "dao".to_string(),
+            index: 3,
+            author: "alice".to_string(),
+            text: "hello".to_string(),
+            image_uri: format!("ipfs://{}", CID),
+            timestamp: 0,
+            vote_count: 0,
+            round: 0,
+        };
+        database.txn(&block_content_media_ops(&database, &content, "moderator", 10).unwrap()).unwrap();
+        assert!(matches!(serve_media(&database, &cache, &source, CID, 1024).await, Err(MediaError::Blocked(_))));
+        assert!(cache.get(CID).is_none());
+
+        database.txn(&unblock_content_media_ops(&database, &content).unwrap()).unwrap();
+        assert!(serve_media(&database, &cache, &source, CID, 1024).await.is_ok());
+    }
+}
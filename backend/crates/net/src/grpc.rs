@@ -0,0 +1,242 @@
+use futures_util::stream::{Stream, StreamExt};
+use std::collections::HashSet;
+use std::fmt;
+use std::future::ready;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use tonic::{Request, Response, Status};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::KeyRange;
+use turtle_database::watch::ChangeFeed;
+use turtle_service::parser::chain::{ChainContent, DaoSnapshot};
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use crate::events::record_stream;
+use crate::sync::{CHAIN_CONTENTS, DAO_SNAPSHOTS};
+
+// build.rs 가 proto/indexer.proto 에서 만든 메시지와 서버 코드
+mod proto {
+    tonic::include_proto!("turtle.indexer.v1");
+}
+
+use proto::indexer_server::{Indexer, IndexerServer};
+
+pub const DEFAULT_CONTENT_LIMIT: u32 = 100;
+pub const MAX_CONTENT_LIMIT: u32 = 1_000;
+
+impl From<EventRecord> for proto::Event {
+    fn from(record: EventRecord) -> Self {
+        let kind = match record.event {
+            TurtleEvent::Deposit { amount } => proto::event::Kind::Deposit(proto::Deposit { amount }),
+            TurtleEvent::Submission => proto::event::Kind::Submission(proto::Submission {}),
+            TurtleEvent::Claim { winner } => proto::event::Kind::Claim(proto::Claim { winner }),
+            TurtleEvent::Vote { proposal_id } => proto::event::Kind::Vote(proto::Vote { proposal_id }),
+        };
+        Self {
+            id: record.id,
+            signature: record.signature,
+            slot: record.slot,
+            block_time: record.block_time,
+            dao: record.dao,
+            actor: record.actor,
+            kind: Some(kind),
+        }
+    }
+}
+
+impl From<DaoSnapshot> for proto::Dao {
+    fn from(snapshot: DaoSnapshot) -> Self {
+        Self {
+            pubkey: snapshot.pubkey,
+            dao_name: snapshot.dao_name,
+            initializer: snapshot.initializer,
+            time_limit: snapshot.time_limit,
+            base_fee: snapshot.base_fee,
+            ai_moderation: snapshot.ai_moderation,
+            deposit_share: u32::from(snapshot.deposit_share),
+            timeout_timestamp: snapshot.timeout_timestamp,
+            total_deposit: snapshot.total_deposit,
+            depositor_count: snapshot.depositor_count,
+            content_count: snapshot.content_count,
+            proposal_count: snapshot.proposal_count,
+            next_proposal_id: snapshot.next_proposal_id,
+            synced_at: snapshot.synced_at,
+        }
+    }
+}
+
+impl From<ChainContent> for proto::Content {
+    fn from(content: ChainContent) -> Self {
+        Self {
+            dao: content.dao,
+            index: content.index,
+            author: content.author,
+            text: content.text,
+            image_uri: content.image_uri,
+            timestamp: content.timestamp,
+            vote_count: content.vote_count,
+        }
+    }
+}
+
+fn db_status(e: impl fmt::Display) -> Status {
+    Status::internal(format!("Database error: {}", e))
+}
+
+fn content_key(dao: &str, index: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(index).build()
+}
+
+// HTTP API 와 같은 테이블을 읽는다. 쓰기는 하지 않는다.
+pub struct IndexerService<T> {
+    database: T,
+}
+
+impl<T: SafeDatabase + ChangeFeed> IndexerService<T> {
+    pub fn new(database: T) -> Self {
+        Self { database }
+    }
+}
+
+// 에러 타입은 생성된 트레이트가 정한 tonic::Status 라 줄일 수 없다
+#[allow(clippy::result_large_err)]
+#[tonic::async_trait]
+impl<T: SafeDatabase + ChangeFeed> Indexer for IndexerService<T> {
+    type SubscribeEventsStream = Pin<Box<dyn Stream<Item = Result<proto::Event, Status>> + Send>>;
+
+    async fn subscribe_events(
+        &self,
+        request: Request<proto::SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        let request = request.into_inner();
+        let daos: HashSet<String> = request.daos.into_iter().collect();
+        let events = record_stream(self.database.clone(), request.after_id)
+            .filter(move |record| ready(daos.is_empty() || daos.contains(&record.dao)))
+            .map(|record| Ok(proto::Event::from(record)));
+        Ok(Response::new(Box::pin(events)))
+    }
+
+    async fn get_dao(&self, request: Request<proto::GetDaoRequest>) -> Result<Response<proto::Dao>, Status> {
+        let pubkey = request.into_inner().pubkey;
+        DAO_SNAPSHOTS.get(&self.database, pubkey.as_bytes()).map_err(db_status)?
+            .map(|snapshot| Response::new(snapshot.into()))
+            .ok_or_else(|| Status::not_found(format!("No synced DAO {}", pubkey)))
+    }
+
+    async fn list_daos(&self, _request: Request<proto::ListDaosRequest>) -> Result<Response<proto::ListDaosResponse>, Status> {
+        let daos = DAO_SNAPSHOTS.scan(&self.database, &KeyRange::all()).map_err(db_status)?;
+        Ok(Response::new(proto::ListDaosResponse {
+            daos: daos.into_iter().map(|(_, snapshot)| snapshot.into()).collect(),
+        }))
+    }
+
+    async fn list_contents(&self, request: Request<proto::ListContentsRequest>) -> Result<Response<proto::ListContentsResponse>, Status> {
+        let request = request.into_inner();
+        if request.dao.is_empty() {
+            return Err(Status::invalid_argument("dao is required"));
+        }
+        let limit = match request.limit {
+            0 => DEFAULT_CONTENT_LIMIT,
+            limit => limit.min(MAX_CONTENT_LIMIT),
+        } as usize;
+
+        let range = KeyRange::between(content_key(&request.dao, request.start_index), content_key(&request.dao, u64::MAX));
+        let mut contents = CHAIN_CONTENTS.scan(&self.database, &range).map_err(db_status)?;
+        // 하나 더 읽어서 다음 페이지가 있는지 본다
+        let next_index = contents.get(limit).map(|(_, content)| content.index);
+        contents.truncate(limit);
+        Ok(Response::new(proto::ListContentsResponse {
+            contents: contents.into_iter().map(|(_, content)| content.into()).collect(),
+            next_index,
+        }))
+    }
+}
+
+// grpc_bind_addr 에 띄운다. 서버가 멈추면 로그만 남기고 HTTP 서버는 그대로 둔다.
+pub fn spawn_grpc_server<T: SafeDatabase + ChangeFeed>(database: T, addr: SocketAddr) {
+    tokio::spawn(async move {
+        tracing::info!(grpc_bind_addr = %addr, "gRPC server listening");
+        let result = tonic::transport::Server::builder()
+            .add_service(IndexerServer::new(IndexerService::new(database)))
+            .serve(addr)
+            .await;
+        if let Err(e) = result {
+            tracing::error!(error = %e, "gRPC server stopped");
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::kv::KvStore;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_database::watch::Watched;
+    use crate::indexer::{event_key, CLAIM_EVENTS, DEPOSIT_EVENTS};
+
+    fn record(id: u64, dao: &str, event: TurtleEvent) -> EventRecord {
+        EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: None,
+            dao: dao.to_string(),
+            actor: "alice".to_string(),
+            event,
+        }
+    }
+
+    fn content(index: u64) -> ChainContent {
+        ChainContent {
+            dao: "dao1".to_string(),
+            index,
+            author: "bob".to_string(),
+            text: format!("post {}", index),
+            image_uri: String::new(),
+            timestamp: index,
+            vote_count: 0,
+            round: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_replays_then_streams_filtered_events() {
+        let database = Watched::new(MemoryDatabase::new());
+        DEPOSIT_EVENTS.put(&database, &event_key(1), &record(1, "dao1", TurtleEvent::Deposit { amount: 5 })).unwrap();
+        DEPOSIT_EVENTS.put(&database, &event_key(2), &record(2, "dao2", TurtleEvent::Deposit { amount: 7 })).unwrap();
+        let service = IndexerService::new(database.clone());
+
+        let request = proto::SubscribeEventsRequest { after_id: 0, daos: vec!["dao1".to_string()] };
+        let mut events = service.subscribe_events(Request::new(request)).await.unwrap().into_inner();
+        let first = events.next().await.unwrap().unwrap();
+        assert_eq!((first.id, first.kind), (1, Some(proto::event::Kind::Deposit(proto::Deposit { amount: 5 }))));
+
+        // 구독 뒤에 인덱싱된 이벤트도 온다 (dao2 것은 걸러진다)
+        database.txn(&[
+            CLAIM_EVENTS.put_op(&event_key(3), &record(3, "dao2", TurtleEvent::Claim { winner: None })).unwrap(),
+            CLAIM_EVENTS.put_op(&event_key(4), &record(4, "dao1", TurtleEvent::Claim { winner: Some("bob".to_string()) })).unwrap(),
+        ]).unwrap();
+        let next = events.next().await.unwrap().unwrap();
+        assert_eq!((next.id, next.kind), (4, Some(proto::event::Kind::Claim(proto::Claim { winner: Some("bob".to_string()) }))));
+    }
+
+    #[tokio::test]
+    async fn test_lists_contents_by_page() {
+        let database = Watched::new(MemoryDatabase::new());
+        for index in 0..5 {
+            CHAIN_CONTENTS.put(&database, &content_key("dao1", index), &content(index)).unwrap();
+        }
+        let service = IndexerService::new(database);
+
+        let request = proto::ListContentsRequest { dao: "dao1".to_string(), start_index: 1, limit: 3 };
+        let page = service.list_contents(Request::new(request)).await.unwrap().into_inner();
+        assert_eq!(page.contents.iter().map(|content| content.index).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(page.next_index, Some(4));
+
+        let request = proto::ListContentsRequest { dao: String::new(), start_index: 0, limit: 0 };
+        assert_eq!(service.list_contents(Request::new(request)).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+        let missing = service.get_dao(Request::new(proto::GetDaoRequest { pubkey: "nope".to_string() })).await.unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+    }
+}
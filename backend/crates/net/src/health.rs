@@ -0,0 +1,165 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use crate::admin::{chain_reader, ChainReader};
+use crate::config::app_config;
+use crate::indexer::{load_checkpoint, INDEXER_METRICS};
+
+// 약 1분 (slot 하나가 400ms 정도)
+pub const DEFAULT_READY_MAX_SLOT_LAG: u64 = 150;
+// 프로브가 RPC 응답을 기다리는 최대 시간. 로드밸런서의 프로브 타임아웃보다 짧아야 한다.
+pub const READY_RPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Check {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl Check {
+    fn passed(detail: Option<String>) -> Self {
+        Self { ok: true, detail }
+    }
+
+    fn failed(detail: impl Into<String>) -> Self {
+        Self { ok: false, detail: Some(detail.into()) }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub database: Check,
+    pub rpc: Check,
+    // program_id 가 없어서 인덱서가 꺼져 있으면 보지 않는다
+    pub indexer: Option<Check>,
+}
+
+// 저장소를 읽을 수 있고, RPC 가 답하고, 인덱서가 체인 tip 에서 max_slot_lag 안쪽이면 준비된 것이다.
+// synced_slot 은 인덱서가 다 봤다고 기록한 slot (인덱서가 꺼져 있으면 None).
+pub async fn readiness<T: SafeDatabase, C: ChainReader>(
+    database: &T,
+    chain: &C,
+    synced_slot: Option<u64>,
+    max_slot_lag: u64,
+) -> Readiness {
+    let database_check = match database.tables() {
+        Ok(_) => Check::passed(None),
+        Err(e) => Check::failed(e.to_string()),
+    };
+
+    let tip = match tokio::time::timeout(READY_RPC_TIMEOUT, chain.current_slot()).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("RPC did not answer within {}s", READY_RPC_TIMEOUT.as_secs())),
+    };
+    let rpc = match &tip {
+        Ok(slot) => Check::passed(Some(format!("slot {}", slot))),
+        Err(e) => Check::failed(e.clone()),
+    };
+
+    // 프로그램 트랜잭션이 한동안 없으면 체크포인트의 slot 은 멈춰 있으므로, 마지막 패스가 본 tip 과 더 큰 쪽을 쓴다
+    let indexer = synced_slot.map(|synced_slot| {
+        let checkpoint = match load_checkpoint(database) {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => return Check::failed(e.to_string()),
+        };
+        let Ok(tip) = &tip else {
+            return Check::failed("chain tip is unknown");
+        };
+        let lag = tip.saturating_sub(checkpoint.last_slot.max(synced_slot));
+        match lag <= max_slot_lag {
+            true => Check::passed(Some(format!("{} slots behind tip", lag))),
+            false => Check::failed(format!("{} slots behind tip (max {})", lag, max_slot_lag)),
+        }
+    });
+
+    Readiness {
+        ready: database_check.ok && rpc.ok && indexer.as_ref().is_none_or(|check| check.ok),
+        database: database_check,
+        rpc,
+        indexer,
+    }
+}
+
+// 프로세스가 떠 있으면 200. 의존성은 보지 않는다 (liveness).
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+// 준비되지 않았으면 503 과 함께 어떤 검사가 실패했는지 돌려준다 (readiness)
+pub async fn readyz<T: SafeDatabase>(
+    State(database): State<T>,
+) -> Response {
+    let config = app_config();
+    let synced_slot = config.program_id.as_ref().map(|_| INDEXER_METRICS.synced_slot.load(Ordering::Relaxed));
+    let report = readiness(&database, &chain_reader(), synced_slot, config.ready_max_slot_lag).await;
+
+    let status = match report.ready {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(report)).into_response()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+    use crate::indexer::{IndexerCheckpoint, CHECKPOINT_KEY, INDEXER_STATE};
+
+    struct FakeChain {
+        slot: Result<u64, String>,
+    }
+
+    impl ChainReader for FakeChain {
+        async fn current_slot(&self) -> Result<u64, String> {
+            self.slot.clone()
+        }
+
+        async fn balance(&self, _pubkey: &str) -> Result<u64, String> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readiness() {
+        let database = MemoryDatabase::new();
+        let checkpoint = IndexerCheckpoint { last_slot: 900, ..Default::default() };
+        INDEXER_STATE.put(&database, CHECKPOINT_KEY, &checkpoint).unwrap();
+        let chain = FakeChain { slot: Ok(1_000) };
+
+        // 체크포인트는 100 slot 뒤지만 마지막 패스가 980 까지 봤다
+        let report = readiness(&database, &chain, Some(980), 50).await;
+        assert!(report.ready);
+        assert_eq!(report.indexer, Some(Check::passed(Some("20 slots behind tip".to_string()))));
+
+        let report = readiness(&database, &chain, Some(0), 50).await;
+        assert!(!report.ready);
+        assert!(report.database.ok && report.rpc.ok);
+        assert_eq!(report.indexer, Some(Check::failed("100 slots behind tip (max 50)")));
+
+        // 인덱서가 꺼져 있으면 저장소와 RPC 만 본다
+        assert!(readiness(&database, &chain, None, 50).await.ready);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_down_is_not_ready() {
+        let database = MemoryDatabase::new();
+        let chain = FakeChain { slot: Err("connection refused".to_string()) };
+
+        let report = readiness(&database, &chain, None, 50).await;
+        assert!(!report.ready);
+        assert!(report.database.ok);
+        assert_eq!(report.rpc, Check::failed("connection refused"));
+
+        let report = readiness(&database, &chain, Some(1_000), 50).await;
+        assert_eq!(report.indexer, Some(Check::failed("chain tip is unknown")));
+    }
+}
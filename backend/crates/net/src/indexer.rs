@@ -0,0 +1,558 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_client::decode::{self, ProgramEvent};
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use crate::rpc::RpcClient;
+
+pub const DEFAULT_INDEX_INTERVAL: Duration = Duration::from_secs(10);
+// getSignaturesForAddress 한 번에 받을 수 있는 최대 개수
+pub const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+// 이벤트 종류별 테이블. 키는 전역 이벤트 ID (u64 big-endian) 이다.
+pub const DEPOSIT_EVENTS: TypedTable<EventRecord> = TypedTable::new("events_deposits");
+pub const SUBMISSION_EVENTS: TypedTable<EventRecord> = TypedTable::new("events_submissions");
+pub const CLAIM_EVENTS: TypedTable<EventRecord> = TypedTable::new("events_claims");
+pub const VOTE_EVENTS: TypedTable<EventRecord> = TypedTable::new("events_votes");
+pub const EVENT_TABLES: [TypedTable<EventRecord>; 4] = [DEPOSIT_EVENTS, SUBMISSION_EVENTS, CLAIM_EVENTS, VOTE_EVENTS];
+
+// 재시작해도 이어서 인덱싱할 수 있도록 마지막으로 처리한 트랜잭션을 남겨 둔다
+pub const INDEXER_STATE: TypedTable<IndexerCheckpoint> = TypedTable::new("indexer_state");
+pub const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexerCheckpoint {
+    pub last_signature: Option<String>,
+    pub last_slot: u64,
+    pub next_event_id: u64,
+}
+
+impl Default for IndexerCheckpoint {
+    fn default() -> Self {
+        Self {
+            last_signature: None,
+            last_slot: 0,
+            next_event_id: 1,
+        }
+    }
+}
+
+// /metrics 로 내보내는 인덱서 카운터 (프로세스가 뜬 뒤로)
+#[derive(Default)]
+pub struct IndexerMetrics {
+    pub runs: AtomicU64,
+    pub failures: AtomicU64,
+    pub events_indexed: AtomicU64,
+    pub last_run_at: AtomicU64,
+    // 마지막으로 성공한 패스가 시작될 때의 체인 slot. 이 slot 까지의 트랜잭션은 모두 인덱싱됐다.
+    pub synced_slot: AtomicU64,
+}
+
+impl IndexerMetrics {
+    pub const fn new() -> Self {
+        Self {
+            runs: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            events_indexed: AtomicU64::new(0),
+            last_run_at: AtomicU64::new(0),
+            synced_slot: AtomicU64::new(0),
+        }
+    }
+}
+
+// 기본 배포의 인덱서. [[tenants]] 의 인덱서는 Tenant 마다 따로 센다.
+pub static INDEXER_METRICS: IndexerMetrics = IndexerMetrics::new();
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexerConfig {
+    pub program_id: String,
+    pub interval: Duration,
+}
+
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum IndexerError {
+    RpcError(String),
+    DecodeError(String),
+    DatabaseError(String),
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            IndexerError::DecodeError(msg) => write!(f, "Decode error: {}", msg),
+            IndexerError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for IndexerError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+    pub failed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionRef {
+    pub program_id: String,
+    pub accounts: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionLogs {
+    pub slot: u64,
+    pub block_time: Option<u64>,
+    pub instructions: Vec<InstructionRef>,
+    pub logs: Vec<String>,
+}
+
+// 트랜잭션 서명과 로그를 가져오는 곳. 서버는 RpcClient 를 쓴다.
+pub trait LogSource: Send + Sync + 'static {
+    // 최신 서명부터. before 보다 오래되고 until 보다 새로운 것만 돌려준다.
+    fn get_signatures(
+        &self,
+        program_id: &str,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<SignatureInfo>, IndexerError>> + Send;
+
+    fn get_transaction(&self, signature: &str) -> impl Future<Output = Result<Option<TransactionLogs>, IndexerError>> + Send;
+
+    // 체인의 현재 slot. 인덱싱을 시작하기 전에 읽어 두면, 끝났을 때 그 slot 까지는 다 본 것이다.
+    fn current_slot(&self) -> impl Future<Output = Result<u64, IndexerError>> + Send;
+}
+
+impl LogSource for RpcClient {
+    async fn get_signatures(
+        &self,
+        program_id: &str,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SignatureInfo>, IndexerError> {
+        let mut options = json!({ "limit": limit, "commitment": "confirmed" });
+        if let Some(before) = before {
+            options["before"] = json!(before);
+        }
+        if let Some(until) = until {
+            options["until"] = json!(until);
+        }
+
+        let result = self.call("getSignaturesForAddress", json!([program_id, options]))
+            .await
+            .map_err(|e| IndexerError::RpcError(e.0))?;
+        parse_signatures(&result)
+    }
+
+    async fn get_transaction(&self, signature: &str) -> Result<Option<TransactionLogs>, IndexerError> {
+        let options = json!({ "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 });
+        let result = self.call("getTransaction", json!([signature, options]))
+            .await
+            .map_err(|e| IndexerError::RpcError(e.0))?;
+        parse_transaction(&result)
+    }
+
+    async fn current_slot(&self) -> Result<u64, IndexerError> {
+        let result = self.call("getSlot", json!([{ "commitment": "confirmed" }]))
+            .await
+            .map_err(|e| IndexerError::RpcError(e.0))?;
+        result.as_u64().ok_or_else(|| IndexerError::DecodeError(format!("Unexpected getSlot response: {}", result)))
+    }
+}
+
+fn parse_signatures(result: &Value) -> Result<Vec<SignatureInfo>, IndexerError> {
+    let entries = result
+        .as_array()
+        .ok_or_else(|| IndexerError::DecodeError("signature list is not an array".to_string()))?;
+
+    entries
+        .iter()
+        .map(|entry| {
+            Ok(SignatureInfo {
+                signature: entry["signature"]
+                    .as_str()
+                    .ok_or_else(|| IndexerError::DecodeError("signature missing".to_string()))?
+                    .to_string(),
+                slot: entry["slot"].as_u64().unwrap_or(0),
+                failed: !entry["err"].is_null(),
+            })
+        })
+        .collect()
+}
+
+fn parse_transaction(result: &Value) -> Result<Option<TransactionLogs>, IndexerError> {
+    if result.is_null() {
+        return Ok(None);
+    }
+
+    let decode_err = |what: &str| IndexerError::DecodeError(format!("transaction {} missing", what));
+    let message = &result["transaction"]["message"];
+    let account_keys: Vec<&str> = message["accountKeys"]
+        .as_array()
+        .ok_or_else(|| decode_err("accountKeys"))?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    let key_at = |index: &Value| {
+        index
+            .as_u64()
+            .and_then(|index| account_keys.get(index as usize))
+            .map(|key| key.to_string())
+            .ok_or_else(|| decode_err("account index"))
+    };
+
+    let instructions = message["instructions"]
+        .as_array()
+        .ok_or_else(|| decode_err("instructions"))?
+        .iter()
+        .map(|instruction| {
+            Ok(InstructionRef {
+                program_id: key_at(&instruction["programIdIndex"])?,
+                accounts: instruction["accounts"]
+                    .as_array()
+                    .ok_or_else(|| decode_err("instruction accounts"))?
+                    .iter()
+                    .map(key_at)
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect::<Result<_, IndexerError>>()?;
+
+    let logs = result["meta"]["logMessages"]
+        .as_array()
+        .map(|logs| logs.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default();
+
+    Ok(Some(TransactionLogs {
+        slot: result["slot"].as_u64().unwrap_or(0),
+        block_time: result["blockTime"].as_u64(),
+        instructions,
+        logs,
+    }))
+}
+
+// 인덱서가 저장하는 이벤트만 남긴다. 해석은 익스플로러와 같은 turtle_client::decode 를 쓴다.
+fn turtle_event(event: ProgramEvent) -> Option<TurtleEvent> {
+    match event {
+        ProgramEvent::Deposit { amount } => Some(TurtleEvent::Deposit { amount }),
+        ProgramEvent::ContentSubmitted => Some(TurtleEvent::Submission),
+        ProgramEvent::TimeoutProcessed { winner } => Some(TurtleEvent::Claim { winner }),
+        ProgramEvent::VoteCast { proposal_id } => Some(TurtleEvent::Vote { proposal_id }),
+        _ => None,
+    }
+}
+
+// (dao, actor, event) 목록.
+// n 번째 최상위 호출을 이 프로그램을 부른 n 번째 명령과 짝지어 DAO(계정 1)와 서명자(계정 0)를 얻는다.
+fn extract_events(program_id: &str, tx: &TransactionLogs) -> Vec<(String, String, TurtleEvent)> {
+    let own_instructions: Vec<&InstructionRef> = tx.instructions
+        .iter()
+        .filter(|instruction| instruction.program_id == program_id)
+        .collect();
+
+    decode::decode_logs(program_id, &tx.logs)
+        .into_iter()
+        .filter_map(|logged| {
+            let instruction = own_instructions.get(logged.invocation)?;
+            let (actor, dao) = (instruction.accounts.first()?, instruction.accounts.get(1)?);
+            Some((dao.clone(), actor.clone(), turtle_event(logged.event)?))
+        })
+        .collect()
+}
+
+fn event_table(event: &TurtleEvent) -> TypedTable<EventRecord> {
+    match event {
+        TurtleEvent::Deposit { .. } => DEPOSIT_EVENTS,
+        TurtleEvent::Submission => SUBMISSION_EVENTS,
+        TurtleEvent::Claim { .. } => CLAIM_EVENTS,
+        TurtleEvent::Vote { .. } => VOTE_EVENTS,
+    }
+}
+
+pub fn event_key(id: u64) -> Vec<u8> {
+    KeyBuilder::new().u64(id).build()
+}
+
+pub fn load_checkpoint<T: SafeDatabase>(database: &T) -> Result<IndexerCheckpoint, IndexerError> {
+    Ok(INDEXER_STATE.get(database, CHECKPOINT_KEY)
+        .map_err(|e| IndexerError::DatabaseError(e.to_string()))?
+        .unwrap_or_default())
+}
+
+// after 보다 큰 ID 의 이벤트를 종류에 상관없이 ID 순서로 최대 limit 개
+pub fn events_after<T: SafeDatabase>(database: &T, after: u64, limit: usize) -> Result<Vec<EventRecord>, IndexerError> {
+    let range = KeyRange::starting_at(event_key(after.saturating_add(1)));
+    let mut events = Vec::new();
+    for table in EVENT_TABLES {
+        let records = table.scan(database, &range)
+            .map_err(|e| IndexerError::DatabaseError(e.to_string()))?;
+        events.extend(records.into_iter().take(limit).map(|(_, record)| record));
+    }
+    events.sort_by_key(|record| record.id);
+    events.truncate(limit);
+    Ok(events)
+}
+
+// 체크포인트 이후의 서명을 모두 모아 오래된 것부터 돌려준다
+async fn new_signatures<S: LogSource>(
+    source: &S,
+    program_id: &str,
+    until: Option<&str>,
+) -> Result<Vec<SignatureInfo>, IndexerError> {
+    let mut signatures = Vec::new();
+    let mut before: Option<String> = None;
+    loop {
+        let page = source.get_signatures(program_id, before.as_deref(), until, SIGNATURE_PAGE_LIMIT).await?;
+        let done = page.len() < SIGNATURE_PAGE_LIMIT;
+        before = page.last().map(|info| info.signature.clone());
+        signatures.extend(page);
+        if done || before.is_none() {
+            break;
+        }
+    }
+    signatures.reverse();
+    Ok(signatures)
+}
+
+// 새 트랜잭션들을 인덱싱하고 추가된 이벤트 수를 돌려준다.
+// 트랜잭션마다 이벤트와 체크포인트를 한 트랜잭션으로 쓰므로, 중간에 멈춰도 같은 이벤트가 두 번 들어가지 않는다.
+pub async fn index_once<T: SafeDatabase, S: LogSource>(
+    database: &T,
+    source: &S,
+    program_id: &str,
+) -> Result<usize, IndexerError> {
+    let mut checkpoint = load_checkpoint(database)?;
+    let signatures = new_signatures(source, program_id, checkpoint.last_signature.as_deref()).await?;
+
+    let mut indexed = 0;
+    for info in signatures {
+        let mut ops = Vec::new();
+
+        let tx = match info.failed {
+            true => None,
+            false => source.get_transaction(&info.signature).await?,
+        };
+        if let Some(tx) = tx {
+            for (dao, actor, event) in extract_events(program_id, &tx) {
+                let record = EventRecord {
+                    id: checkpoint.next_event_id,
+                    signature: info.signature.clone(),
+                    slot: tx.slot,
+                    block_time: tx.block_time,
+                    dao,
+                    actor,
+                    event,
+                };
+                ops.push(event_table(&record.event)
+                    .put_op(&event_key(record.id), &record)
+                    .map_err(|e| IndexerError::DatabaseError(e.to_string()))?);
+                checkpoint.next_event_id += 1;
+                indexed += 1;
+            }
+        }
+
+        checkpoint.last_signature = Some(info.signature);
+        checkpoint.last_slot = info.slot;
+        ops.push(INDEXER_STATE.put_op(CHECKPOINT_KEY, &checkpoint)
+            .map_err(|e| IndexerError::DatabaseError(e.to_string()))?);
+        database.txn(&ops)
+            .map_err(|e| IndexerError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(indexed)
+}
+
+pub fn spawn_indexer<T: SafeDatabase, S: LogSource>(database: T, source: S, config: IndexerConfig, metrics: &'static IndexerMetrics) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        loop {
+            interval.tick().await;
+            let tip = source.current_slot().await;
+            let result = index_once(&database, &source, &config.program_id).await;
+            metrics.runs.fetch_add(1, Ordering::Relaxed);
+            metrics.last_run_at.store(now_secs(), Ordering::Relaxed);
+            match result {
+                Ok(indexed) => {
+                    metrics.events_indexed.fetch_add(indexed as u64, Ordering::Relaxed);
+                    if let Ok(tip) = tip {
+                        metrics.synced_slot.fetch_max(tip, Ordering::Relaxed);
+                    }
+                }
+                Err(e) => {
+                    metrics.failures.fetch_add(1, Ordering::Relaxed);
+                    tracing::error!(error = %e, "Log indexing failed");
+                }
+            }
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    const PROGRAM: &str = "Turt1eProgram11111111111111111111111111111";
+    const OTHER: &str = "11111111111111111111111111111111";
+
+    // 서명은 오래된 순서로 들어 있고, get_signatures 는 RPC 처럼 최신 것부터 돌려준다
+    #[derive(Default)]
+    struct FakeChain {
+        signatures: Mutex<Vec<SignatureInfo>>,
+        transactions: Mutex<HashMap<String, TransactionLogs>>,
+    }
+
+    impl FakeChain {
+        fn push(&self, signature: &str, failed: bool, logs: Vec<String>) {
+            let mut signatures = self.signatures.lock().unwrap();
+            let slot = signatures.len() as u64 + 1;
+            signatures.push(SignatureInfo { signature: signature.to_string(), slot, failed });
+            self.transactions.lock().unwrap().insert(signature.to_string(), TransactionLogs {
+                slot,
+                block_time: Some(1_700_000_000 + slot),
+                instructions: vec![InstructionRef {
+                    program_id: PROGRAM.to_string(),
+                    accounts: vec![format!("user-{}", signature), "dao1".to_string()],
+                }],
+                logs,
+            });
+        }
+    }
+
+    impl LogSource for FakeChain {
+        async fn get_signatures(
+            &self,
+            _program_id: &str,
+            before: Option<&str>,
+            until: Option<&str>,
+            limit: usize,
+        ) -> Result<Vec<SignatureInfo>, IndexerError> {
+            let signatures = self.signatures.lock().unwrap();
+            Ok(signatures
+                .iter()
+                .rev()
+                .skip_while(|info| before.is_some_and(|before| info.signature != before))
+                .skip(usize::from(before.is_some()))
+                .take_while(|info| until != Some(info.signature.as_str()))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn get_transaction(&self, signature: &str) -> Result<Option<TransactionLogs>, IndexerError> {
+            Ok(self.transactions.lock().unwrap().get(signature).cloned())
+        }
+
+        async fn current_slot(&self) -> Result<u64, IndexerError> {
+            Ok(self.signatures.lock().unwrap().len() as u64)
+        }
+    }
+
+    fn logs(message: &str) -> Vec<String> {
+        vec![
+            format!("Program {} invoke [1]", PROGRAM),
+            format!("Program log: {}", message),
+            format!("Program {} success", PROGRAM),
+        ]
+    }
+
+    #[test]
+    fn test_parse_log_messages() {
+        let parse_log_message = |message: &str| decode::parse_log_message(message).and_then(turtle_event);
+        assert_eq!(parse_log_message("Deposit of 500 lamports processed"), Some(TurtleEvent::Deposit { amount: 500 }));
+        assert_eq!(parse_log_message("Content submitted, timeout reset"), Some(TurtleEvent::Submission));
+        assert_eq!(
+            parse_log_message("Timeout processed, rewards distributed to winner abc"),
+            Some(TurtleEvent::Claim { winner: Some("abc".to_string()) })
+        );
+        assert_eq!(parse_log_message("Vote cast for proposal 3"), Some(TurtleEvent::Vote { proposal_id: 3 }));
+        assert_eq!(parse_log_message("DAO initialized: turtles"), None);
+    }
+
+    #[test]
+    fn test_ignores_logs_of_other_programs() {
+        let mut tx_logs = logs("Deposit of 5 lamports processed");
+        tx_logs.insert(1, format!("Program {} invoke [2]", OTHER));
+        tx_logs.insert(2, "Program log: Deposit of 999 lamports processed".to_string());
+        tx_logs.insert(3, format!("Program {} success", OTHER));
+        let tx = TransactionLogs {
+            slot: 1,
+            block_time: None,
+            instructions: vec![InstructionRef {
+                program_id: PROGRAM.to_string(),
+                accounts: vec!["alice".to_string(), "dao1".to_string()],
+            }],
+            logs: tx_logs,
+        };
+
+        assert_eq!(
+            extract_events(PROGRAM, &tx),
+            vec![("dao1".to_string(), "alice".to_string(), TurtleEvent::Deposit { amount: 5 })]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_resumes_from_checkpoint() {
+        let database = MemoryDatabase::new();
+        let chain = FakeChain::default();
+
+        chain.push("s1", false, logs("Deposit of 100 lamports processed"));
+        chain.push("s2", true, vec![]);
+        chain.push("s3", false, logs("Content submitted, timeout reset"));
+        assert_eq!(index_once(&database, &chain, PROGRAM).await.unwrap(), 2);
+
+        // 이미 처리한 트랜잭션은 다시 인덱싱하지 않는다
+        assert_eq!(index_once(&database, &chain, PROGRAM).await.unwrap(), 0);
+
+        chain.push("s4", false, logs("Vote cast for proposal 7"));
+        assert_eq!(index_once(&database, &chain, PROGRAM).await.unwrap(), 1);
+
+        let events = events_after(&database, 0, 10).unwrap();
+        assert_eq!(events.iter().map(|record| record.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(events[0].actor, "user-s1");
+        assert_eq!(events[1].event, TurtleEvent::Submission);
+        assert_eq!(events[2].event, TurtleEvent::Vote { proposal_id: 7 });
+
+        let checkpoint = load_checkpoint(&database).unwrap();
+        assert_eq!((checkpoint.last_signature.as_deref(), checkpoint.next_event_id), (Some("s4"), 4));
+
+        let later: Vec<u64> = events_after(&database, 1, 1).unwrap().iter().map(|record| record.id).collect();
+        assert_eq!(later, vec![2]);
+    }
+
+    #[test]
+    fn test_parse_transaction() {
+        let result = json!({
+            "slot": 42,
+            "blockTime": 1_700_000_000,
+            "meta": {"err": null, "logMessages": ["Program log: hi"]},
+            "transaction": {"message": {
+                "accountKeys": ["alice", "dao1", PROGRAM],
+                "instructions": [{"programIdIndex": 2, "accounts": [0, 1], "data": ""}]
+            }}
+        });
+
+        let tx = parse_transaction(&result).unwrap().unwrap();
+        assert_eq!((tx.slot, tx.block_time), (42, Some(1_700_000_000)));
+        assert_eq!(tx.instructions[0].accounts, vec!["alice", "dao1"]);
+        assert_eq!(parse_transaction(&Value::Null).unwrap(), None);
+    }
+}
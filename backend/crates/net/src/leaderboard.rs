@@ -0,0 +1,273 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use turtle_service::parser::leaderboard::{Leaderboard, LeaderboardEntry, LeaderboardWindow};
+use crate::indexer::EVENT_TABLES;
+use crate::scheduler::Scheduler;
+use crate::error::ApiError;
+
+// (dao, window) -> Leaderboard. 요청 경로에서는 이벤트를 훑지 않고 이 테이블만 읽는다.
+pub const LEADERBOARDS: TypedTable<Leaderboard> = TypedTable::new("leaderboards");
+pub const LEADERBOARD_TABLES: &[&str] = &[LEADERBOARDS.name()];
+pub const LEADERBOARD_INTERVAL: Duration = Duration::from_secs(60);
+
+pub const DEFAULT_LEADERBOARD_LIMIT: usize = 20;
+pub const MAX_LEADERBOARD_LIMIT: usize = 100;
+
+const WINDOWS: [LeaderboardWindow; 3] = [LeaderboardWindow::Day, LeaderboardWindow::Week, LeaderboardWindow::AllTime];
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardSort {
+    #[default]
+    Submissions,
+    Votes,
+    Tips,
+    Rewards,
+}
+
+#[derive(Deserialize)]
+pub struct LeaderboardQuery {
+    window: Option<LeaderboardWindow>,
+    sort: Option<LeaderboardSort>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderboardResponse {
+    dao: String,
+    window: LeaderboardWindow,
+    // 아직 한 번도 집계하지 않았으면 null
+    computed_at: Option<u64>,
+    entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    DatabaseError(String),
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaderboardError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for LeaderboardError {}
+
+impl IntoResponse for LeaderboardError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            LeaderboardError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn window_id(window: LeaderboardWindow) -> u8 {
+    match window {
+        LeaderboardWindow::Day => 0,
+        LeaderboardWindow::Week => 1,
+        LeaderboardWindow::AllTime => 2,
+    }
+}
+
+fn leaderboard_key(dao: &str, window: LeaderboardWindow) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u8(window_id(window)).build()
+}
+
+// 구간에 들어가는 이벤트인지. block_time 이 없는 이벤트는 전체 기간에만 센다.
+fn in_window(record: &EventRecord, window: LeaderboardWindow, now: u64) -> bool {
+    let days = match window {
+        LeaderboardWindow::Day => 1,
+        LeaderboardWindow::Week => 7,
+        LeaderboardWindow::AllTime => return true,
+    };
+    record.block_time.is_some_and(|time| time >= now.saturating_sub(days * SECONDS_PER_DAY))
+}
+
+// dao -> wallet -> 집계
+pub fn aggregate(events: &[EventRecord], window: LeaderboardWindow, now: u64) -> BTreeMap<String, BTreeMap<String, LeaderboardEntry>> {
+    let mut boards: BTreeMap<String, BTreeMap<String, LeaderboardEntry>> = BTreeMap::new();
+    for record in events.iter().filter(|record| in_window(record, window, now)) {
+        let board = boards.entry(record.dao.clone()).or_default();
+        match &record.event {
+            TurtleEvent::Deposit { amount } => entry(board, &record.actor).tips += amount,
+            TurtleEvent::Submission => entry(board, &record.actor).submissions += 1,
+            TurtleEvent::Vote { .. } => entry(board, &record.actor).votes += 1,
+            // 보상은 타임아웃을 처리한 쪽이 아니라 우승자에게 돌아간다
+            TurtleEvent::Claim { winner: Some(winner) } => entry(board, winner).rewards += 1,
+            TurtleEvent::Claim { winner: None } => {}
+        }
+    }
+    boards
+}
+
+fn entry<'a>(board: &'a mut BTreeMap<String, LeaderboardEntry>, wallet: &str) -> &'a mut LeaderboardEntry {
+    board.entry(wallet.to_string()).or_insert_with(|| LeaderboardEntry {
+        wallet: wallet.to_string(),
+        ..Default::default()
+    })
+}
+
+// 모든 DAO 의 리더보드를 다시 계산해서 한 트랜잭션으로 쓰고, 쓴 리더보드 수를 돌려준다.
+// 이벤트가 있었던 DAO 는 구간 안에 이벤트가 없어도 빈 리더보드를 쓴다 (지난 집계가 남지 않도록).
+pub fn compute_leaderboards<T: SafeDatabase>(database: &T, now: u64) -> Result<usize, LeaderboardError> {
+    let mut events = Vec::new();
+    for table in EVENT_TABLES {
+        let records = table.scan(database, &KeyRange::all())
+            .map_err(|e| LeaderboardError::DatabaseError(e.to_string()))?;
+        events.extend(records.into_iter().map(|(_, record)| record));
+    }
+    let daos: BTreeSet<String> = events.iter().map(|record| record.dao.clone()).collect();
+
+    let mut ops = Vec::new();
+    for window in WINDOWS {
+        let mut boards = aggregate(&events, window, now);
+        for dao in &daos {
+            let leaderboard = Leaderboard {
+                dao: dao.clone(),
+                window,
+                computed_at: now,
+                entries: boards.remove(dao).unwrap_or_default().into_values().collect(),
+            };
+            ops.push(LEADERBOARDS.put_op(&leaderboard_key(dao, window), &leaderboard)
+                .map_err(|e| LeaderboardError::DatabaseError(e.to_string()))?);
+        }
+    }
+
+    database.txn(&ops)
+        .map_err(|e| LeaderboardError::DatabaseError(e.to_string()))?;
+    Ok(ops.len())
+}
+
+// 집계는 무거우니 인스턴스끼리 겹치지 않게 조금씩 흩는다
+pub fn register_leaderboard_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>) {
+    scheduler.every("leaderboard", LEADERBOARD_INTERVAL, LEADERBOARD_INTERVAL / 6, |database| async move {
+        compute_leaderboards(&database, now_secs()).map(|_| ()).map_err(|e| e.to_string())
+    });
+}
+
+fn metric(entry: &LeaderboardEntry, sort: LeaderboardSort) -> u64 {
+    match sort {
+        LeaderboardSort::Submissions => entry.submissions,
+        LeaderboardSort::Votes => entry.votes,
+        LeaderboardSort::Tips => entry.tips,
+        LeaderboardSort::Rewards => entry.rewards,
+    }
+}
+
+// 고른 지표 내림차순. 같으면 나머지 지표 합, 그다음 지갑 주소 순으로 정렬해 순서가 매번 같게 한다.
+fn rank(mut entries: Vec<LeaderboardEntry>, sort: LeaderboardSort, limit: usize) -> Vec<LeaderboardEntry> {
+    entries.retain(|entry| metric(entry, sort) > 0);
+    entries.sort_by(|a, b| {
+        metric(b, sort).cmp(&metric(a, sort))
+            .then_with(|| (b.submissions + b.votes + b.rewards).cmp(&(a.submissions + a.votes + a.rewards)))
+            .then_with(|| a.wallet.cmp(&b.wallet))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+pub async fn get_leaderboard<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(pubkey): Path<String>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<LeaderboardResponse>, LeaderboardError> {
+    let window = query.window.unwrap_or(LeaderboardWindow::AllTime);
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT).min(MAX_LEADERBOARD_LIMIT);
+
+    let leaderboard = LEADERBOARDS.get(&database, &leaderboard_key(&pubkey, window))
+        .map_err(|e| LeaderboardError::DatabaseError(e.to_string()))?;
+    let (computed_at, entries) = match leaderboard {
+        Some(leaderboard) => (Some(leaderboard.computed_at), rank(leaderboard.entries, query.sort.unwrap_or_default(), limit)),
+        None => (None, Vec::new()),
+    };
+
+    Ok(Json(LeaderboardResponse { dao: pubkey, window, computed_at, entries }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::{event_key, CLAIM_EVENTS, DEPOSIT_EVENTS, SUBMISSION_EVENTS, VOTE_EVENTS};
+    use turtle_database::memory_db::MemoryDatabase;
+
+    const NOW: u64 = 100 * SECONDS_PER_DAY;
+
+    fn put(database: &MemoryDatabase, id: u64, dao: &str, actor: &str, age_days: u64, event: TurtleEvent) {
+        let table = match event {
+            TurtleEvent::Deposit { .. } => DEPOSIT_EVENTS,
+            TurtleEvent::Submission => SUBMISSION_EVENTS,
+            TurtleEvent::Claim { .. } => CLAIM_EVENTS,
+            TurtleEvent::Vote { .. } => VOTE_EVENTS,
+        };
+        let record = EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: Some(NOW - age_days * SECONDS_PER_DAY),
+            dao: dao.to_string(),
+            actor: actor.to_string(),
+            event,
+        };
+        table.put(database, &event_key(id), &record).unwrap();
+    }
+
+    async fn board(database: &MemoryDatabase, dao: &str, query: &str) -> LeaderboardResponse {
+        let Query(query) = Query::try_from_uri(&format!("/?{}", query).parse().unwrap()).unwrap();
+        get_leaderboard(State(database.clone()), Path(dao.to_string()), Query(query)).await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_windows_and_sorting() {
+        let database = MemoryDatabase::new();
+        put(&database, 1, "dao", "alice", 0, TurtleEvent::Submission);
+        put(&database, 2, "dao", "alice", 3, TurtleEvent::Submission);
+        put(&database, 3, "dao", "bob", 0, TurtleEvent::Submission);
+        put(&database, 4, "dao", "bob", 30, TurtleEvent::Deposit { amount: 500 });
+        put(&database, 5, "dao", "carol", 0, TurtleEvent::Vote { proposal_id: 0 });
+        put(&database, 6, "dao", "carol", 0, TurtleEvent::Claim { winner: Some("alice".to_string()) });
+        put(&database, 7, "other", "dave", 0, TurtleEvent::Submission);
+
+        // 조회 전에는 아직 집계가 없다
+        assert_eq!(board(&database, "dao", "").await.computed_at, None);
+        assert_eq!(compute_leaderboards(&database, NOW).unwrap(), 6);
+
+        let all = board(&database, "dao", "window=all").await;
+        assert_eq!(all.computed_at, Some(NOW));
+        let wallets: Vec<_> = all.entries.iter().map(|entry| entry.wallet.as_str()).collect();
+        assert_eq!(wallets, ["alice", "bob"]);
+        assert_eq!(all.entries[0].rewards, 1);
+
+        let day = board(&database, "dao", "window=24h").await;
+        assert_eq!(day.entries.iter().map(|entry| entry.submissions).collect::<Vec<_>>(), [1, 1]);
+
+        let tips = board(&database, "dao", "window=7d&sort=tips").await;
+        assert!(tips.entries.is_empty());
+        let tips = board(&database, "dao", "sort=tips").await;
+        assert_eq!(tips.entries[0].wallet, "bob");
+        assert_eq!(tips.entries[0].tips, 500);
+
+        let votes = board(&database, "dao", "sort=votes&limit=1").await;
+        assert_eq!(votes.entries.len(), 1);
+        assert_eq!(votes.entries[0].wallet, "carol");
+    }
+}
@@ -1,5 +1,53 @@
 mod router;
+mod error;
+mod config;
+mod cors;
+mod tls;
+mod etag;
+mod pagination;
+mod scheduler;
+mod telemetry;
+mod metrics;
+mod health;
+mod openapi;
 mod profile;
+mod verification;
+mod sns;
+mod admin;
+mod auth;
+mod session;
+pub mod roles;
+mod ratelimit;
+mod content;
+mod upload;
+mod media;
+mod gateway;
+mod preview;
+mod dao;
+mod events;
+mod leaderboard;
+mod earnings;
+mod ranking;
+mod analytics;
+mod countdown;
+mod chat;
+mod search;
+mod notifications;
+mod webhooks;
+mod bridge;
+mod moderation;
+mod reports;
+pub mod indexer;
+mod rpc;
+mod fees;
+mod sync;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod tenant;
+mod tx;
+mod relay;
+mod faucet;
+mod ws;
 pub mod server;
 
 pub mod community;
\ No newline at end of file
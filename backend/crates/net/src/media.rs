@@ -0,0 +1,230 @@
+use image::imageops::FilterType;
+use image::io::{Limits, Reader};
+use image::{DynamicImage, ImageFormat, ImageOutputFormat};
+use std::io::Cursor;
+use crate::upload::{Image, UploadError};
+
+// 디코딩할 수 있는 가로/세로 최대 픽셀. 작은 파일로 거대한 비트맵을 만드는 이미지(decompression bomb)를 막는다.
+pub const MAX_IMAGE_DIMENSION: u32 = 8_192;
+// 디코더가 한 번에 잡을 수 있는 메모리
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+const JPEG_QUALITY: u8 = 85;
+
+// (이름, 긴 변 최대 픽셀). 원본이 이보다 작으면 그 축소본은 만들지 않는다.
+pub const IMAGE_VARIANTS: &[(&str, u32)] = &[("thumbnail", 320), ("medium", 1280)];
+
+// 원본 하나와 축소본들. 모두 다시 인코딩한 것이라 EXIF 같은 메타데이터는 남지 않는다.
+pub struct ProcessedImage {
+    pub original: Image,
+    pub variants: Vec<Variant>,
+}
+
+pub struct Variant {
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub image: Image,
+}
+
+// 클라이언트가 보낸 content type 은 믿지 않고 앞부분 바이트로 형식을 정한다
+pub fn sniff_image(bytes: &[u8]) -> Result<ImageFormat, UploadError> {
+    match image::guess_format(bytes) {
+        Ok(format @ (ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP)) => Ok(format),
+        _ => Err(UploadError::ValidationError("image must be a PNG, JPEG, GIF or WebP file".to_string())),
+    }
+}
+
+// JPEG 은 JPEG 으로, 나머지는 PNG 로 다시 인코딩한다.
+// GIF 는 첫 프레임만 남는다 (애니메이션은 지원하지 않는다).
+fn encode(image: &DynamicImage, jpeg: bool, name: &str) -> Result<Image, UploadError> {
+    let mut bytes = Cursor::new(Vec::new());
+    let (result, content_type, extension) = if jpeg {
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        (rgb.write_to(&mut bytes, ImageOutputFormat::Jpeg(JPEG_QUALITY)), "image/jpeg", "jpg")
+    } else {
+        (image.write_to(&mut bytes, ImageOutputFormat::Png), "image/png", "png")
+    };
+    result.map_err(|e| UploadError::ValidationError(format!("Failed to encode image: {}", e)))?;
+
+    Ok(Image {
+        file_name: format!("{}.{}", name, extension),
+        content_type: content_type.to_string(),
+        bytes: bytes.into_inner(),
+    })
+}
+
+// 형식 확인 -> 크기 제한을 걸고 디코딩 -> EXIF 방향 적용 -> 다시 인코딩 + 축소본.
+// CPU 를 쓰는 작업이라 핸들러에서는 spawn_blocking 으로 부른다.
+pub fn process_image(bytes: &[u8]) -> Result<ProcessedImage, UploadError> {
+    let format = sniff_image(bytes)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+    let mut reader = Reader::new(Cursor::new(bytes));
+    reader.set_format(format);
+    reader.limits(limits);
+    let mut decoded = reader.decode()
+        .map_err(|e| UploadError::ValidationError(format!("Invalid image: {}", e)))?;
+
+    // 메타데이터를 버리기 전에 회전 정보는 픽셀에 반영해 둔다
+    if format == ImageFormat::Jpeg {
+        decoded = apply_orientation(decoded, jpeg_orientation(bytes).unwrap_or(1));
+    }
+
+    let jpeg = format == ImageFormat::Jpeg;
+    let longest = decoded.width().max(decoded.height());
+    let mut variants = Vec::new();
+    for &(name, max) in IMAGE_VARIANTS {
+        if longest <= max {
+            continue;
+        }
+        let resized = decoded.resize(max, max, FilterType::CatmullRom);
+        variants.push(Variant {
+            name,
+            width: resized.width(),
+            height: resized.height(),
+            image: encode(&resized, jpeg, name)?,
+        });
+    }
+
+    Ok(ProcessedImage {
+        original: encode(&decoded, jpeg, "image")?,
+        variants,
+    })
+}
+
+// EXIF Orientation (1~8) 에 맞게 돌리거나 뒤집는다
+fn apply_orientation(image: DynamicImage, orientation: u16) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+// JPEG 의 APP1 (Exif) 세그먼트에서 IFD0 의 Orientation(0x0112) 태그를 찾는다
+fn jpeg_orientation(bytes: &[u8]) -> Option<u16> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut i = 2;
+    while i + 4 <= bytes.len() && bytes[i] == 0xFF {
+        let marker = bytes[i + 1];
+        // SOS 뒤부터는 이미지 데이터다
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+        let length = usize::from(u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]));
+        let segment = bytes.get(i + 4..i + 2 + length)?;
+        if marker == 0xE1 {
+            if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                return tiff_orientation(tiff);
+            }
+        }
+        i += 2 + length;
+    }
+    None
+}
+
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |at: usize| -> Option<u16> {
+        let b: [u8; 2] = tiff.get(at..at + 2)?.try_into().ok()?;
+        Some(if big_endian { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) })
+    };
+    let u32_at = |at: usize| -> Option<u32> {
+        let b: [u8; 4] = tiff.get(at..at + 4)?.try_into().ok()?;
+        Some(if big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    };
+
+    let ifd = usize::try_from(u32_at(4)?).ok()?;
+    let count = usize::from(u16_at(ifd)?);
+    (0..count)
+        .map(|n| ifd + 2 + n * 12)
+        .find(|&entry| u16_at(entry) == Some(0x0112))
+        .and_then(|entry| u16_at(entry + 8))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbImage};
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut bytes, ImageOutputFormat::Jpeg(90))
+            .unwrap();
+        bytes.into_inner()
+    }
+
+    // SOI 바로 뒤에 Orientation 만 담은 빅엔디언 Exif 세그먼트를 끼워 넣는다
+    fn with_exif_orientation(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+        let mut tiff = b"MM\0\x2a\0\0\0\x08".to_vec();
+        tiff.extend_from_slice(&1u16.to_be_bytes());
+        tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1]);
+        tiff.extend_from_slice(&orientation.to_be_bytes());
+        tiff.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+        let mut segment = b"Exif\0\0".to_vec();
+        segment.extend_from_slice(&tiff);
+        let mut bytes = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        bytes.extend_from_slice(&((segment.len() + 2) as u16).to_be_bytes());
+        bytes.extend_from_slice(&segment);
+        bytes.extend_from_slice(&jpeg[2..]);
+        bytes
+    }
+
+    #[test]
+    fn test_rejects_non_images_by_content() {
+        assert!(sniff_image(b"<html><script>alert(1)</script></html>").is_err());
+        assert!(sniff_image(b"%PDF-1.7").is_err());
+        assert_eq!(sniff_image(&jpeg(4, 4)).unwrap(), ImageFormat::Jpeg);
+
+        // 앞부분만 PNG 인 파일은 디코딩에서 걸린다
+        let mut truncated = b"\x89PNG\r\n\x1a\n".to_vec();
+        truncated.extend_from_slice(&[0; 16]);
+        assert!(matches!(process_image(&truncated), Err(UploadError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_strips_exif_and_applies_orientation() {
+        let bytes = with_exif_orientation(&jpeg(40, 20), 6);
+        assert_eq!(jpeg_orientation(&bytes), Some(6));
+
+        let processed = process_image(&bytes).unwrap();
+        assert_eq!(processed.original.content_type, "image/jpeg");
+        assert!(processed.variants.is_empty());
+        assert!(!processed.original.bytes.windows(4).any(|window| window == b"Exif"));
+        assert_eq!(jpeg_orientation(&processed.original.bytes), None);
+
+        // 90도 돌아서 세로로 길어진다
+        let decoded = image::load_from_memory(&processed.original.bytes).unwrap();
+        assert_eq!(decoded.dimensions(), (20, 40));
+    }
+
+    #[test]
+    fn test_generates_variants_for_large_images() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::new_rgba8(1600, 800).write_to(&mut png, ImageOutputFormat::Png).unwrap();
+
+        let processed = process_image(&png.into_inner()).unwrap();
+        assert_eq!(processed.original.content_type, "image/png");
+        let sizes: Vec<(&str, u32, u32)> = processed.variants.iter().map(|variant| (variant.name, variant.width, variant.height)).collect();
+        assert_eq!(sizes, [("thumbnail", 320, 160), ("medium", 1280, 640)]);
+        assert_eq!(processed.variants[0].image.file_name, "thumbnail.png");
+    }
+}
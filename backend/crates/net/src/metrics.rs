@@ -0,0 +1,331 @@
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use turtle_database::basic_db::SafeDatabase;
+use crate::admin::consumer_lags;
+use crate::indexer::{load_checkpoint, IndexerMetrics, INDEXER_METRICS};
+use crate::rpc::RPC_METRICS;
+use crate::scheduler::JOB_METRICS;
+use crate::tenant::tenants;
+use crate::ws::WS_CONNECTIONS;
+use crate::error::ApiError;
+
+// Prometheus 기본 버킷 (초)
+pub const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+// 라우트에 걸리지 않은 요청 (404). 실제 경로를 라벨에 넣으면 라벨 종류가 끝없이 늘어난다.
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+#[derive(Debug)]
+pub enum MetricsError {
+    DatabaseError(String),
+}
+
+impl fmt::Display for MetricsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricsError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for MetricsError {}
+
+impl IntoResponse for MetricsError {
+    fn into_response(self) -> Response {
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+#[derive(Default)]
+struct RouteStats {
+    statuses: BTreeMap<u16, u64>,
+    // 구간별로 세어 두고 내보낼 때 누적한다. 마지막 버킷보다 오래 걸린 요청은 count 에만 들어간다.
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum_secs: f64,
+    count: u64,
+}
+
+// (method, 라우트 패턴) 별 요청 수와 걸린 시간
+pub struct RequestMetrics {
+    routes: Mutex<BTreeMap<(String, String), RouteStats>>,
+}
+
+impl Default for RequestMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestMetrics {
+    pub const fn new() -> Self {
+        Self { routes: Mutex::new(BTreeMap::new()) }
+    }
+
+    pub fn record(&self, method: &str, route: &str, status: u16, secs: f64) {
+        let mut routes = self.routes.lock().unwrap();
+        let stats = routes.entry((method.to_string(), route.to_string())).or_default();
+        *stats.statuses.entry(status).or_default() += 1;
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|le| secs <= *le) {
+            stats.buckets[bucket] += 1;
+        }
+        stats.sum_secs += secs;
+        stats.count += 1;
+    }
+
+    fn render(&self, out: &mut String) {
+        let routes = self.routes.lock().unwrap();
+
+        describe(out, "turtle_http_requests_total", "counter", "HTTP requests by route and status");
+        for ((method, route), stats) in routes.iter() {
+            for (status, count) in &stats.statuses {
+                let status = status.to_string();
+                sample(out, "turtle_http_requests_total", &[("method", method), ("route", route), ("status", &status)], *count as f64);
+            }
+        }
+
+        describe(out, "turtle_http_request_duration_seconds", "histogram", "HTTP request latency by route");
+        for ((method, route), stats) in routes.iter() {
+            let mut cumulative = 0;
+            for (le, count) in LATENCY_BUCKETS.iter().zip(stats.buckets) {
+                cumulative += count;
+                let le = le.to_string();
+                sample(out, "turtle_http_request_duration_seconds_bucket", &[("method", method), ("route", route), ("le", &le)], cumulative as f64);
+            }
+            let labels = [("method", method.as_str()), ("route", route.as_str())];
+            sample(out, "turtle_http_request_duration_seconds_bucket", &[labels[0], labels[1], ("le", "+Inf")], stats.count as f64);
+            sample(out, "turtle_http_request_duration_seconds_sum", &labels, stats.sum_secs);
+            sample(out, "turtle_http_request_duration_seconds_count", &labels, stats.count as f64);
+        }
+    }
+}
+
+pub static REQUEST_METRICS: RequestMetrics = RequestMetrics::new();
+
+// Router::layer 로 걸면 라우팅이 끝난 뒤에 불려서 MatchedPath (예: /ws/dao/{pubkey}) 로 라우트를 묶을 수 있다
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request.extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    REQUEST_METRICS.record(&method, &route, response.status().as_u16(), started.elapsed().as_secs_f64());
+    response
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn describe(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels.iter().map(|(key, value)| format!("{}=\"{}\"", key, escape(value))).collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+fn db_err(e: impl fmt::Display) -> MetricsError {
+    MetricsError::DatabaseError(e.to_string())
+}
+
+// Prometheus text 형식으로 요청/저장소/캐시/인덱서/주기 작업/RPC/WebSocket 지표를 모은다
+pub fn render_metrics<T: SafeDatabase>(database: &T, requests: &RequestMetrics) -> Result<String, MetricsError> {
+    let mut out = String::new();
+    requests.render(&mut out);
+
+    let stats = database.stats().map_err(db_err)?;
+    describe(&mut out, "turtle_db_entries", "gauge", "Entries across all tables");
+    sample(&mut out, "turtle_db_entries", &[], stats.total_entries() as f64);
+    if let Some(used_bytes) = stats.used_bytes {
+        describe(&mut out, "turtle_db_used_bytes", "gauge", "Bytes used by the storage backend");
+        sample(&mut out, "turtle_db_used_bytes", &[], used_bytes as f64);
+    }
+    if let Some(ops) = stats.ops {
+        describe(&mut out, "turtle_db_operations_total", "counter", "Storage operations that reached the backend");
+        for (op, count) in [("get", ops.gets), ("put", ops.puts), ("delete", ops.deletes), ("scan", ops.scans), ("txn", ops.txns)] {
+            sample(&mut out, "turtle_db_operations_total", &[("op", op)], count as f64);
+        }
+        describe(&mut out, "turtle_db_errors_total", "counter", "Storage operations that failed");
+        sample(&mut out, "turtle_db_errors_total", &[], ops.errors as f64);
+    }
+    if let Some(cache) = stats.cache {
+        describe(&mut out, "turtle_cache_hits_total", "counter", "Read cache hits");
+        sample(&mut out, "turtle_cache_hits_total", &[], cache.hits as f64);
+        describe(&mut out, "turtle_cache_misses_total", "counter", "Read cache misses");
+        sample(&mut out, "turtle_cache_misses_total", &[], cache.misses as f64);
+        describe(&mut out, "turtle_cache_hit_ratio", "gauge", "Read cache hits / lookups since startup");
+        let lookups = cache.hits + cache.misses;
+        sample(&mut out, "turtle_cache_hit_ratio", &[], match lookups {
+            0 => 0.0,
+            _ => cache.hits as f64 / lookups as f64,
+        });
+        describe(&mut out, "turtle_cache_entries", "gauge", "Entries held in the read cache");
+        sample(&mut out, "turtle_cache_entries", &[], cache.entries as f64);
+    }
+
+    let checkpoint = load_checkpoint(database).map_err(db_err)?;
+    let last_event_id = checkpoint.next_event_id.saturating_sub(1);
+    // 기본 배포는 라벨 없이, [[tenants]] 의 인덱서는 cluster 라벨을 달아서
+    let indexer_sample = |out: &mut String, name: &str, counter: fn(&IndexerMetrics) -> &AtomicU64| {
+        sample(out, name, &[], counter(&INDEXER_METRICS).load(Ordering::Relaxed) as f64);
+        for tenant in tenants() {
+            sample(out, name, &[("cluster", tenant.cluster())], counter(&tenant.indexer_metrics).load(Ordering::Relaxed) as f64);
+        }
+    };
+    describe(&mut out, "turtle_indexer_runs_total", "counter", "Indexer passes");
+    indexer_sample(&mut out, "turtle_indexer_runs_total", |metrics| &metrics.runs);
+    describe(&mut out, "turtle_indexer_failures_total", "counter", "Indexer passes that failed");
+    indexer_sample(&mut out, "turtle_indexer_failures_total", |metrics| &metrics.failures);
+    describe(&mut out, "turtle_indexer_events_indexed_total", "counter", "Program events indexed since startup");
+    indexer_sample(&mut out, "turtle_indexer_events_indexed_total", |metrics| &metrics.events_indexed);
+    describe(&mut out, "turtle_indexer_last_run_timestamp_seconds", "gauge", "Unix time of the last indexer pass");
+    indexer_sample(&mut out, "turtle_indexer_last_run_timestamp_seconds", |metrics| &metrics.last_run_at);
+    describe(&mut out, "turtle_indexer_last_slot", "gauge", "Slot of the last indexed transaction");
+    sample(&mut out, "turtle_indexer_last_slot", &[], checkpoint.last_slot as f64);
+    describe(&mut out, "turtle_indexer_synced_slot", "gauge", "Chain slot the indexer has fully caught up to");
+    indexer_sample(&mut out, "turtle_indexer_synced_slot", |metrics| &metrics.synced_slot);
+    describe(&mut out, "turtle_indexer_last_event_id", "gauge", "ID of the last indexed event");
+    sample(&mut out, "turtle_indexer_last_event_id", &[], last_event_id as f64);
+
+    let consumers = consumer_lags(database, last_event_id).map_err(db_err)?;
+    describe(&mut out, "turtle_event_consumer_lag", "gauge", "Indexed events a consumer has not processed yet");
+    for consumer in consumers {
+        sample(&mut out, "turtle_event_consumer_lag", &[("consumer", &consumer.name)], consumer.behind as f64);
+    }
+
+    let jobs = JOB_METRICS.lock().unwrap().clone();
+    describe(&mut out, "turtle_job_runs_total", "counter", "Scheduled job runs since startup");
+    for (job, stats) in &jobs {
+        sample(&mut out, "turtle_job_runs_total", &[("job", job)], stats.runs as f64);
+    }
+    describe(&mut out, "turtle_job_failures_total", "counter", "Scheduled job runs that failed");
+    for (job, stats) in &jobs {
+        sample(&mut out, "turtle_job_failures_total", &[("job", job)], stats.failures as f64);
+    }
+    describe(&mut out, "turtle_job_last_run_timestamp_seconds", "gauge", "Unix time of the last run of a scheduled job");
+    for (job, stats) in &jobs {
+        sample(&mut out, "turtle_job_last_run_timestamp_seconds", &[("job", job)], stats.last_run_at as f64);
+    }
+    describe(&mut out, "turtle_job_last_duration_seconds", "gauge", "How long the last run of a scheduled job took");
+    for (job, stats) in &jobs {
+        sample(&mut out, "turtle_job_last_duration_seconds", &[("job", job)], stats.last_duration_secs);
+    }
+
+    let endpoints = RPC_METRICS.lock().unwrap().clone();
+    describe(&mut out, "turtle_rpc_requests_total", "counter", "RPC requests sent to an endpoint");
+    for (endpoint, stats) in &endpoints {
+        sample(&mut out, "turtle_rpc_requests_total", &[("endpoint", endpoint)], stats.requests as f64);
+    }
+    describe(&mut out, "turtle_rpc_failures_total", "counter", "RPC requests that failed over from an endpoint");
+    for (endpoint, stats) in &endpoints {
+        sample(&mut out, "turtle_rpc_failures_total", &[("endpoint", endpoint)], stats.failures as f64);
+    }
+    describe(&mut out, "turtle_rpc_endpoint_health", "gauge", "Moving average of RPC endpoint successes (1 = healthy)");
+    for (endpoint, stats) in &endpoints {
+        sample(&mut out, "turtle_rpc_endpoint_health", &[("endpoint", endpoint)], stats.health);
+    }
+    describe(&mut out, "turtle_rpc_last_latency_seconds", "gauge", "Latency of the last RPC request to an endpoint");
+    for (endpoint, stats) in &endpoints {
+        sample(&mut out, "turtle_rpc_last_latency_seconds", &[("endpoint", endpoint)], stats.last_latency_secs);
+    }
+
+    describe(&mut out, "turtle_websocket_connections", "gauge", "Open /ws/dao connections");
+    sample(&mut out, "turtle_websocket_connections", &[], WS_CONNECTIONS.load(Ordering::Relaxed) as f64);
+
+    Ok(out)
+}
+
+// 스크레이퍼는 세션이 없으므로 열어 둔다 (사용자 데이터는 들어 있지 않다)
+pub async fn get_metrics<T: SafeDatabase>(
+    State(database): State<T>,
+) -> Result<Response, MetricsError> {
+    let body = render_metrics(&database, &REQUEST_METRICS)?;
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], body).into_response())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+    use turtle_database::cache::{CacheConfig, Cached};
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_database::trace::Traced;
+    use crate::notifications::{NotifierCheckpoint, CHECKPOINT_KEY, NOTIFIER_STATE};
+
+    #[test]
+    fn test_request_histogram() {
+        let metrics = RequestMetrics::new();
+        metrics.record("GET", "/api/dao", 200, 0.003);
+        metrics.record("GET", "/api/dao", 200, 0.2);
+        metrics.record("GET", "/api/dao", 404, 30.0);
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        for line in [
+            "turtle_http_requests_total{method=\"GET\",route=\"/api/dao\",status=\"200\"} 2",
+            "turtle_http_requests_total{method=\"GET\",route=\"/api/dao\",status=\"404\"} 1",
+            "turtle_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/dao\",le=\"0.005\"} 1",
+            "turtle_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/dao\",le=\"0.25\"} 2",
+            "turtle_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/dao\",le=\"10\"} 2",
+            "turtle_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/dao\",le=\"+Inf\"} 3",
+            "turtle_http_request_duration_seconds_count{method=\"GET\",route=\"/api/dao\"} 3",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing {}\n{}", line, out);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let database = Cached::new(Traced::new(MemoryDatabase::new()), CacheConfig::default());
+        NOTIFIER_STATE.put(&database, CHECKPOINT_KEY, &NotifierCheckpoint { last_event_id: 0, next_notification_id: 1 }).unwrap();
+
+        let app = Router::new()
+            .route("/api/dao/{pubkey}", get(|| async { "ok" }))
+            .route("/metrics", get(get_metrics::<Cached<Traced<MemoryDatabase>>>))
+            .layer(middleware::from_fn(track_requests))
+            .with_state(database);
+
+        for uri in ["/api/dao/dao1", "/api/dao/dao2", "/nowhere"] {
+            app.clone().oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap()).await.unwrap();
+        }
+        let response = app.oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let out = String::from_utf8(bytes.to_vec()).unwrap();
+
+        for line in [
+            "turtle_http_requests_total{method=\"GET\",route=\"/api/dao/{pubkey}\",status=\"200\"} 2",
+            "turtle_http_requests_total{method=\"GET\",route=\"unmatched\",status=\"404\"} 1",
+            "turtle_db_operations_total{op=\"put\"} 1",
+            "turtle_event_consumer_lag{consumer=\"notifications\"} 0",
+            "turtle_indexer_last_event_id 0",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing {}\n{}", line, out);
+        }
+        assert!(out.contains("turtle_cache_hit_ratio "));
+        assert!(out.contains("turtle_websocket_connections "));
+    }
+}
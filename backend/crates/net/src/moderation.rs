@@ -0,0 +1,406 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_database::watch::ChangeFeed;
+use turtle_service::parser::chain::ChainContent;
+use turtle_service::parser::moderation::{ModerationOverride, ModerationVerdict, Verdict};
+use crate::sync::{CHAIN_CONTENTS, DAO_SNAPSHOTS};
+use crate::error::ApiError;
+
+// 콘텐츠를 보내 판정을 받을 HTTP 엔드포인트. 없으면 모더레이션 워커를 띄우지 않는다.
+pub const MODERATION_URL_ENV: &str = "TURTLE_MODERATION_URL";
+// 있으면 Authorization: Bearer 로 붙인다
+pub const MODERATION_API_KEY_ENV: &str = "TURTLE_MODERATION_API_KEY";
+pub const MODERATION_THRESHOLD_ENV: &str = "TURTLE_MODERATION_THRESHOLD";
+pub const DEFAULT_MODERATION_THRESHOLD: f64 = 0.8;
+
+// (dao, index) -> ModerationVerdict. 키는 chain_contents 와 같다.
+pub const MODERATION_VERDICTS: TypedTable<ModerationVerdict> = TypedTable::new("moderation_verdicts");
+
+// 쓰기 알림이 없어도 실패한 판정을 다시 시도하는 주기
+const MODERATION_TICK: Duration = Duration::from_secs(60);
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(20);
+// 한 번에 제공자에게 보내는 콘텐츠 수
+const MODERATION_BATCH: usize = 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModerationConfig {
+    pub url: String,
+    pub api_key: Option<String>,
+    pub threshold: f64,
+}
+
+impl ModerationConfig {
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var(MODERATION_URL_ENV).ok().filter(|url| !url.is_empty())?;
+        let api_key = std::env::var(MODERATION_API_KEY_ENV).ok().filter(|key| !key.is_empty());
+        let threshold = std::env::var(MODERATION_THRESHOLD_ENV)
+            .ok()
+            .and_then(|threshold| threshold.parse().ok())
+            .filter(|threshold: &f64| (0.0..=1.0).contains(threshold))
+            .unwrap_or(DEFAULT_MODERATION_THRESHOLD);
+        Some(Self { url, api_key, threshold })
+    }
+}
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum ModerationError {
+    DatabaseError(String),
+    ProviderError(String),
+}
+
+impl fmt::Display for ModerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModerationError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            ModerationError::ProviderError(msg) => write!(f, "Moderation provider error: {}", msg),
+        }
+    }
+}
+
+impl StdError for ModerationError {}
+
+impl IntoResponse for ModerationError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            ModerationError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ModerationError::ProviderError(msg) => (StatusCode::BAD_GATEWAY, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> ModerationError {
+    ModerationError::DatabaseError(e.to_string())
+}
+
+// 제공자가 돌려준 점수 (0.0 안전 ~ 1.0 위반)
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ModerationScore {
+    pub score: f64,
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+// 콘텐츠 하나를 판정하는 곳. 서버는 HTTP 제공자를 쓰고, 테스트는 점수를 정해 둔다.
+pub trait ModerationProvider: Send + Sync + 'static {
+    fn name(&self) -> &str;
+    fn moderate(&self, content: &ChainContent) -> impl Future<Output = Result<ModerationScore, String>> + Send;
+}
+
+// POST {url} 에 {"text", "image_uri"} 를 보내고 {"score", "categories"} 를 받는다
+pub struct HttpModerationProvider {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    // 판정에 남기는 제공자 이름. URL 에 토큰이 있을 수 있어 host 만 쓴다.
+    host: String,
+}
+
+impl HttpModerationProvider {
+    pub fn new(config: &ModerationConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: config.url.clone(),
+            api_key: config.api_key.clone(),
+            host: reqwest::Url::parse(&config.url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl ModerationProvider for HttpModerationProvider {
+    fn name(&self) -> &str {
+        &self.host
+    }
+
+    async fn moderate(&self, content: &ChainContent) -> Result<ModerationScore, String> {
+        let mut request = self.client.post(&self.url)
+            .timeout(PROVIDER_TIMEOUT)
+            .json(&serde_json::json!({ "text": content.text, "image_uri": content.image_uri }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+        response.json().await.map_err(|e| e.to_string())
+    }
+}
+
+fn content_prefix(dao: &str) -> Vec<u8> {
+    KeyBuilder::new().str(dao).build()
+}
+
+// ai_moderation 이 켜진 DAO 의 콘텐츠 중 아직 판정이 없는 것을 제공자에게 보내고 판정을 남긴다.
+// 제공자가 실패하면 이번 회차는 멈추고, 남은 콘텐츠는 다음 회차에 다시 시도한다.
+pub async fn moderate_pending<T: SafeDatabase, P: ModerationProvider>(
+    database: &T,
+    provider: &P,
+    threshold: f64,
+    now: u64,
+) -> Result<usize, ModerationError> {
+    let daos: Vec<String> = DAO_SNAPSHOTS.scan(database, &KeyRange::all())
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|(_, snapshot)| snapshot.ai_moderation)
+        .map(|(_, snapshot)| snapshot.pubkey)
+        .collect();
+
+    let mut moderated = 0;
+    for dao in daos {
+        for (key, content) in CHAIN_CONTENTS.scan(database, &KeyRange::prefix(content_prefix(&dao))).map_err(db_err)? {
+            if moderated >= MODERATION_BATCH {
+                return Ok(moderated);
+            }
+            if MODERATION_VERDICTS.get(database, &key).map_err(db_err)?.is_some() {
+                continue;
+            }
+
+            let result = provider.moderate(&content).await.map_err(ModerationError::ProviderError)?;
+            if !(0.0..=1.0).contains(&result.score) {
+                return Err(ModerationError::ProviderError(format!("score out of range: {}", result.score)));
+            }
+
+            let verdict = ModerationVerdict {
+                dao: content.dao.clone(),
+                index: content.index,
+                verdict: if result.score >= threshold { Verdict::Rejected } else { Verdict::Approved },
+                score: result.score,
+                categories: result.categories,
+                provider: provider.name().to_string(),
+                moderated_at: now,
+                overridden: None,
+            };
+            MODERATION_VERDICTS.put(database, &key, &verdict).map_err(db_err)?;
+            moderated += 1;
+        }
+    }
+
+    Ok(moderated)
+}
+
+fn is_moderation_source(table: &str) -> bool {
+    table == CHAIN_CONTENTS.name() || table == DAO_SNAPSHOTS.name()
+}
+
+// 체인 동기화가 콘텐츠를 쓰면 바로, 그렇지 않아도 MODERATION_TICK 마다 밀린 판정을 처리한다
+pub fn spawn_moderation_worker<T: SafeDatabase + ChangeFeed, P: ModerationProvider>(database: T, provider: P, threshold: f64) {
+    tokio::spawn(async move {
+        let mut changes = database.subscribe();
+        let mut tick = tokio::time::interval(MODERATION_TICK);
+        loop {
+            let pending = tokio::select! {
+                _ = tick.tick() => true,
+                change = changes.recv() => match change {
+                    Ok(change) => is_moderation_source(&change.table),
+                    Err(RecvError::Lagged(_)) => true,
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if !pending {
+                continue;
+            }
+
+            // 배치가 꽉 찼으면 남은 것도 바로 이어서 처리한다
+            loop {
+                match moderate_pending(&database, &provider, threshold, now_secs()).await {
+                    Ok(count) if count >= MODERATION_BATCH => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Moderation failed");
+                        break;
+                    },
+                }
+            }
+        }
+    });
+}
+
+// 판정을 운영자 결정으로 바꾼다. 아직 판정이 없는 콘텐츠면 manual 판정을 새로 만든다.
+// 콘텐츠가 있는지는 부르는 쪽에서 확인한다.
+pub fn override_verdict<T: SafeDatabase>(
+    database: &T,
+    dao: &str,
+    index: u64,
+    decision: Verdict,
+    by: &str,
+    reason: Option<String>,
+    now: u64,
+) -> Result<ModerationVerdict, ModerationError> {
+    let key = KeyBuilder::new().str(dao).u64(index).build();
+    let existing = MODERATION_VERDICTS.get(database, &key).map_err(db_err)?;
+    // 여러 번 바꿔도 original 은 제공자의 첫 판정을 가리킨다
+    let original = existing.as_ref().and_then(|verdict| match &verdict.overridden {
+        Some(overridden) => overridden.original,
+        None => Some(verdict.verdict),
+    });
+    let mut verdict = existing.unwrap_or(ModerationVerdict {
+        dao: dao.to_string(),
+        index,
+        verdict: decision,
+        score: 0.0,
+        categories: Vec::new(),
+        provider: "manual".to_string(),
+        moderated_at: now,
+        overridden: None,
+    });
+    verdict.verdict = decision;
+    verdict.overridden = Some(ModerationOverride {
+        by: by.to_string(),
+        reason: reason.filter(|reason| !reason.trim().is_empty()),
+        original,
+        at: now,
+    });
+
+    MODERATION_VERDICTS.put(database, &key, &verdict).map_err(db_err)?;
+    Ok(verdict)
+}
+
+#[derive(Deserialize)]
+pub struct ModerationQuery {
+    dao: String,
+    index: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModerationList {
+    verdicts: Vec<ModerationVerdict>,
+}
+
+// DAO 의 판정 목록 (index 를 주면 그 콘텐츠 것만)
+pub async fn get_moderation<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<ModerationQuery>,
+) -> Result<Json<ModerationList>, ModerationError> {
+    let verdicts = match query.index {
+        Some(index) => {
+            let key = KeyBuilder::new().str(&query.dao).u64(index).build();
+            MODERATION_VERDICTS.get(&database, &key).map_err(db_err)?.into_iter().collect()
+        },
+        None => MODERATION_VERDICTS.scan(&database, &KeyRange::prefix(content_prefix(&query.dao)))
+            .map_err(db_err)?
+            .into_iter()
+            .map(|(_, verdict)| verdict)
+            .collect(),
+    };
+    Ok(Json(ModerationList { verdicts }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::DaoSnapshot;
+
+    // 텍스트에 "spam" 이 있으면 높은 점수를 준다. down 이면 실패한다.
+    #[derive(Default)]
+    struct FakeProvider {
+        down: AtomicBool,
+    }
+
+    impl ModerationProvider for FakeProvider {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        async fn moderate(&self, content: &ChainContent) -> Result<ModerationScore, String> {
+            if self.down.load(Ordering::SeqCst) {
+                return Err("provider unavailable".to_string());
+            }
+            Ok(match content.text.contains("spam") {
+                true => ModerationScore { score: 0.95, categories: vec!["spam".to_string()] },
+                false => ModerationScore { score: 0.1, categories: Vec::new() },
+            })
+        }
+    }
+
+    fn snapshot(pubkey: &str, ai_moderation: bool) -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: pubkey.to_string(),
+            dao_name: pubkey.to_string(),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation,
+            deposit_share: 10,
+            timeout_timestamp: 0,
+            total_deposit: 0,
+            depositor_count: 0,
+            content_count: 0,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    fn put_content(database: &MemoryDatabase, dao: &str, index: u64, text: &str) {
+        let content = ChainContent {
+            dao: dao.to_string(),
+            index,
+            author: "bob".to_string(),
+            text: text.to_string(),
+            image_uri: String::new(),
+            timestamp: 0,
+            vote_count: 0,
+            round: 0,
+        };
+        CHAIN_CONTENTS.put(database, &KeyBuilder::new().str(dao).u64(index).build(), &content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_only_moderated_daos_get_verdicts() {
+        let database = MemoryDatabase::new();
+        DAO_SNAPSHOTS.put(&database, b"moderated", &snapshot("moderated", true)).unwrap();
+        DAO_SNAPSHOTS.put(&database, b"open", &snapshot("open", false)).unwrap();
+        put_content(&database, "moderated", 0, "hello turtles");
+        put_content(&database, "moderated", 1, "buy spam now");
+        put_content(&database, "open", 0, "buy spam now");
+
+        let provider = FakeProvider::default();
+        assert_eq!(moderate_pending(&database, &provider, DEFAULT_MODERATION_THRESHOLD, 100).await.unwrap(), 2);
+        // 이미 판정한 콘텐츠는 다시 보내지 않는다
+        assert_eq!(moderate_pending(&database, &provider, DEFAULT_MODERATION_THRESHOLD, 200).await.unwrap(), 0);
+
+        let query = ModerationQuery { dao: "moderated".to_string(), index: None };
+        let verdicts = get_moderation(State(database.clone()), Query(query)).await.unwrap().0.verdicts;
+        assert_eq!(verdicts.iter().map(|verdict| verdict.verdict).collect::<Vec<_>>(), [Verdict::Approved, Verdict::Rejected]);
+        assert_eq!(verdicts[1].categories, ["spam"]);
+        assert_eq!((verdicts[1].provider.as_str(), verdicts[1].moderated_at), ("fake", 100));
+
+        let query = ModerationQuery { dao: "open".to_string(), index: Some(0) };
+        assert!(get_moderation(State(database.clone()), Query(query)).await.unwrap().0.verdicts.is_empty());
+
+        // 제공자가 실패하면 판정을 남기지 않고 다음 회차에 다시 시도한다
+        put_content(&database, "moderated", 2, "more spam");
+        provider.down.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            moderate_pending(&database, &provider, DEFAULT_MODERATION_THRESHOLD, 300).await,
+            Err(ModerationError::ProviderError(_))
+        ));
+        provider.down.store(false, Ordering::SeqCst);
+        assert_eq!(moderate_pending(&database, &provider, DEFAULT_MODERATION_THRESHOLD, 400).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,570 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Mutex;
+use tokio::sync::broadcast::error::RecvError;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_database::watch::ChangeFeed;
+use turtle_service::parser::event::{EventRecord, TurtleEvent};
+use turtle_service::parser::notification::{Notification, NotificationKind, NotificationRound};
+use crate::countdown::CLAIMABLE_ROUNDS;
+use crate::indexer::{events_after, EVENT_TABLES, VOTE_EVENTS};
+use crate::pagination::{in_order, paginate, ListQuery, ListQueryError, SortOrder};
+use crate::session::AuthedWallet;
+use crate::sync::CHAIN_PROPOSALS;
+use crate::error::ApiError;
+
+// (wallet, id) -> Notification. 지갑 prefix 로 스캔하면 그 지갑의 인박스가 ID 순서로 나온다.
+pub const NOTIFICATIONS: TypedTable<Notification> = TypedTable::new("notifications");
+// wallet -> 안 읽은 알림 수
+pub const UNREAD_COUNTS: TypedTable<u64> = TypedTable::new("notification_unread");
+// dao -> 지난 Claim 이후의 제출 상태
+pub const NOTIFICATION_ROUNDS: TypedTable<NotificationRound> = TypedTable::new("notification_rounds");
+// chain_proposals 의 키 -> 실행 알림을 보낸 시각
+pub const NOTIFIED_PROPOSALS: TypedTable<u64> = TypedTable::new("notified_proposals");
+// (dao, 마감) -> 마감 알림을 보낸 시각
+pub const NOTIFIED_ROUNDS: TypedTable<u64> = TypedTable::new("notified_rounds");
+pub const NOTIFIER_STATE: TypedTable<NotifierCheckpoint> = TypedTable::new("notifier_state");
+pub const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+
+pub const DEFAULT_NOTIFICATION_LIMIT: usize = 20;
+pub const MAX_NOTIFICATION_LIMIT: usize = 100;
+pub const NOTIFICATION_LIST_FILTERS: &[&str] = &["unread"];
+pub const MAX_MARK_READ_IDS: usize = 500;
+// 한 트랜잭션으로 알림으로 바꾸는 이벤트 수
+const EVENT_BATCH: usize = 500;
+const EXECUTED_STATUS: &str = "Executed";
+
+// 알림 작업과 읽음 처리가 둘 다 안 읽은 수를 고치므로 한 줄로 세운다
+static NOTIFY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifierCheckpoint {
+    pub last_event_id: u64,             // 이 ID 까지의 이벤트는 알림으로 바꿨다
+    pub next_notification_id: u64,
+}
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum NotificationError {
+    DatabaseError(String),
+    ValidationError(String),
+}
+
+impl fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            NotificationError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+        }
+    }
+}
+
+impl StdError for NotificationError {}
+
+impl IntoResponse for NotificationError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            NotificationError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            NotificationError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> NotificationError {
+    NotificationError::DatabaseError(e.to_string())
+}
+
+pub fn notification_key(wallet: &str, id: u64) -> Vec<u8> {
+    KeyBuilder::new().str(wallet).u64(id).build()
+}
+
+fn unread_count<T: SafeDatabase>(database: &T, wallet: &str) -> Result<u64, NotificationError> {
+    Ok(UNREAD_COUNTS.get(database, wallet.as_bytes()).map_err(db_err)?.unwrap_or(0))
+}
+
+// 한 번에 만든 알림과 라운드 상태를 모았다가 체크포인트와 함께 한 트랜잭션으로 쓴다.
+// 중간에 멈춰도 같은 이벤트로 알림이 두 번 생기지 않는다.
+struct Batch {
+    checkpoint: NotifierCheckpoint,
+    ops: Vec<KvOp>,
+    rounds: BTreeMap<String, NotificationRound>,
+    unread: BTreeMap<String, u64>,
+    created: usize,
+}
+
+impl Batch {
+    fn new(checkpoint: NotifierCheckpoint) -> Self {
+        Self {
+            checkpoint,
+            ops: Vec::new(),
+            rounds: BTreeMap::new(),
+            unread: BTreeMap::new(),
+            created: 0,
+        }
+    }
+
+    fn round<T: SafeDatabase>(&mut self, database: &T, dao: &str) -> Result<&mut NotificationRound, NotificationError> {
+        if !self.rounds.contains_key(dao) {
+            let round = NOTIFICATION_ROUNDS.get(database, dao.as_bytes()).map_err(db_err)?.unwrap_or_default();
+            self.rounds.insert(dao.to_string(), round);
+        }
+        Ok(self.rounds.get_mut(dao).unwrap())
+    }
+
+    fn push(&mut self, wallet: &str, dao: &str, event_id: Option<u64>, kind: NotificationKind, created_at: u64) -> Result<(), NotificationError> {
+        let notification = Notification {
+            id: self.checkpoint.next_notification_id,
+            wallet: wallet.to_string(),
+            dao: dao.to_string(),
+            event_id,
+            created_at,
+            read: false,
+            kind,
+        };
+        self.checkpoint.next_notification_id += 1;
+        self.ops.push(NOTIFICATIONS.put_op(&notification_key(wallet, notification.id), &notification).map_err(db_err)?);
+        *self.unread.entry(wallet.to_string()).or_default() += 1;
+        self.created += 1;
+        Ok(())
+    }
+
+    fn commit<T: SafeDatabase>(&mut self, database: &T) -> Result<usize, NotificationError> {
+        for (dao, round) in std::mem::take(&mut self.rounds) {
+            self.ops.push(NOTIFICATION_ROUNDS.put_op(dao.as_bytes(), &round).map_err(db_err)?);
+        }
+        for (wallet, added) in std::mem::take(&mut self.unread) {
+            let count = unread_count(database, &wallet)? + added;
+            self.ops.push(UNREAD_COUNTS.put_op(wallet.as_bytes(), &count).map_err(db_err)?);
+        }
+        self.ops.push(NOTIFIER_STATE.put_op(CHECKPOINT_KEY, &self.checkpoint).map_err(db_err)?);
+
+        database.txn(&std::mem::take(&mut self.ops)).map_err(db_err)?;
+        Ok(std::mem::take(&mut self.created))
+    }
+}
+
+// 제출: 직전 마지막 제출자에게 Outbid. Claim: 그 라운드 제출자들과 우승자에게 RewardDistributed.
+fn apply_event<T: SafeDatabase>(database: &T, batch: &mut Batch, record: &EventRecord, now: u64) -> Result<(), NotificationError> {
+    let created_at = record.block_time.unwrap_or(now);
+
+    match &record.event {
+        TurtleEvent::Submission => {
+            let round = batch.round(database, &record.dao)?;
+            let outbid = round.last_submitter
+                .replace(record.actor.clone())
+                .filter(|previous| *previous != record.actor);
+            round.participants.insert(record.actor.clone());
+
+            if let Some(previous) = outbid {
+                let kind = NotificationKind::Outbid { by: record.actor.clone() };
+                batch.push(&previous, &record.dao, Some(record.id), kind, created_at)?;
+            }
+        },
+        TurtleEvent::Claim { winner } => {
+            let round = std::mem::take(batch.round(database, &record.dao)?);
+            let mut recipients = round.participants;
+            recipients.extend(winner.clone());
+
+            for wallet in recipients {
+                let kind = NotificationKind::RewardDistributed { winner: winner.clone() };
+                batch.push(&wallet, &record.dao, Some(record.id), kind, created_at)?;
+            }
+        },
+        TurtleEvent::Deposit { .. } | TurtleEvent::Vote { .. } => {},
+    }
+
+    batch.checkpoint.last_event_id = record.id;
+    Ok(())
+}
+
+// 체인 동기화가 Executed 로 바꾼 제안마다 한 번, 그 제안에 투표한 지갑들에게 알린다
+fn apply_executed_proposals<T: SafeDatabase>(database: &T, batch: &mut Batch, now: u64) -> Result<(), NotificationError> {
+    let mut voters: Option<BTreeMap<(String, u64), BTreeSet<String>>> = None;
+
+    for (key, proposal) in CHAIN_PROPOSALS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        if proposal.status != EXECUTED_STATUS || NOTIFIED_PROPOSALS.get(database, &key).map_err(db_err)?.is_some() {
+            continue;
+        }
+
+        // 투표 이벤트는 알릴 제안이 있을 때 한 번만 훑는다
+        if voters.is_none() {
+            let mut by_proposal: BTreeMap<(String, u64), BTreeSet<String>> = BTreeMap::new();
+            for (_, record) in VOTE_EVENTS.scan(database, &KeyRange::all()).map_err(db_err)? {
+                if let TurtleEvent::Vote { proposal_id } = record.event {
+                    by_proposal.entry((record.dao, proposal_id)).or_default().insert(record.actor);
+                }
+            }
+            voters = Some(by_proposal);
+        }
+
+        let proposal_voters = voters.as_ref()
+            .and_then(|voters| voters.get(&(proposal.dao.clone(), proposal.proposal_id)))
+            .cloned()
+            .unwrap_or_default();
+        for voter in proposal_voters {
+            let kind = NotificationKind::ProposalExecuted { proposal_id: proposal.proposal_id, title: proposal.title.clone() };
+            batch.push(&voter, &proposal.dao, None, kind, now)?;
+        }
+        batch.ops.push(NOTIFIED_PROPOSALS.put_op(&key, &now).map_err(db_err)?);
+    }
+
+    Ok(())
+}
+
+// 카운트다운 감시 작업이 claimable 로 표시한 라운드마다 한 번, 그 라운드에 제출한 지갑들에게 알린다
+fn apply_claimable_rounds<T: SafeDatabase>(database: &T, batch: &mut Batch, now: u64) -> Result<(), NotificationError> {
+    for (_, round) in CLAIMABLE_ROUNDS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let key = KeyBuilder::new().str(&round.dao).u64(round.deadline).build();
+        if NOTIFIED_ROUNDS.get(database, &key).map_err(db_err)?.is_some() {
+            continue;
+        }
+
+        let participants = batch.round(database, &round.dao)?.participants.clone();
+        for wallet in participants {
+            let kind = NotificationKind::RoundClaimable { deadline: round.deadline };
+            batch.push(&wallet, &round.dao, None, kind, now)?;
+        }
+        batch.ops.push(NOTIFIED_ROUNDS.put_op(&key, &now).map_err(db_err)?);
+    }
+
+    Ok(())
+}
+
+// 체크포인트 이후의 이벤트와 새로 실행된 제안, 마감이 지난 라운드를 알림으로 바꾸고 만든 알림 수를 돌려준다
+pub fn process_notifications<T: SafeDatabase>(database: &T, now: u64) -> Result<usize, NotificationError> {
+    let _guard = NOTIFY_LOCK.lock().unwrap();
+
+    let checkpoint = NOTIFIER_STATE.get(database, CHECKPOINT_KEY).map_err(db_err)?.unwrap_or_default();
+    let mut batch = Batch::new(checkpoint);
+    let mut created = 0;
+
+    loop {
+        let events = events_after(database, batch.checkpoint.last_event_id, EVENT_BATCH).map_err(db_err)?;
+        if events.is_empty() {
+            break;
+        }
+        for record in &events {
+            apply_event(database, &mut batch, record, now)?;
+        }
+        created += batch.commit(database)?;
+    }
+
+    apply_executed_proposals(database, &mut batch, now)?;
+    apply_claimable_rounds(database, &mut batch, now)?;
+    if !batch.ops.is_empty() {
+        created += batch.commit(database)?;
+    }
+    Ok(created)
+}
+
+fn is_notification_source(table: &str) -> bool {
+    table == CHAIN_PROPOSALS.name() || table == CLAIMABLE_ROUNDS.name() || EVENT_TABLES.iter().any(|events| events.name() == table)
+}
+
+// 이벤트 테이블, 제안 스냅샷, claimable 라운드가 바뀔 때마다 밀린 알림을 만든다
+pub fn spawn_notifier<T: SafeDatabase + ChangeFeed>(database: T) {
+    tokio::spawn(async move {
+        let mut changes = database.subscribe();
+        // 시작할 때 밀린 이벤트부터 처리
+        let mut pending = true;
+        loop {
+            if pending {
+                if let Err(e) = process_notifications(&database, now_secs()) {
+                    tracing::error!(error = %e, "Notification processing failed");
+                }
+            }
+            pending = match changes.recv().await {
+                Ok(change) => is_notification_source(&change.table),
+                // 알림을 놓쳤어도 체크포인트부터 다시 읽으면 된다
+                Err(RecvError::Lagged(_)) => true,
+                Err(RecvError::Closed) => break,
+            };
+        }
+    });
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationList {
+    notifications: Vec<Notification>,
+    unread: u64,
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnreadCount {
+    unread: u64,
+}
+
+// 최신 알림부터 (order=asc 면 오래된 것부터). filter[unread]=true 면 안 읽은 것만.
+#[utoipa::path(
+    get, path = "/api/notifications", tag = "notifications",
+    params(ListQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Newest first", body = NotificationList),
+        (status = 400, description = "Unknown filter/sort or invalid cursor", body = String),
+        (status = 401, description = "Not signed in", body = String),
+    )
+)]
+pub async fn list_notifications<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    query: ListQuery,
+) -> Result<Json<NotificationList>, NotificationError> {
+    query.check_filters(NOTIFICATION_LIST_FILTERS)?;
+    query.sort(&["id"])?;
+    let limit = query.limit(DEFAULT_NOTIFICATION_LIMIT, MAX_NOTIFICATION_LIMIT);
+    let order = query.order(SortOrder::Desc);
+    let unread_only = match query.filter("unread") {
+        None | Some("false") => false,
+        Some("true") => true,
+        Some(other) => return Err(NotificationError::ValidationError(format!("filter[unread] must be true or false: {}", other))),
+    };
+
+    // 커서는 이 지갑의 키 범위 안에 있어야 한다
+    let base = KeyRange::between(notification_key(&wallet, 0), notification_key(&wallet, u64::MAX));
+    let rows = NOTIFICATIONS.scan(&database, &query.range(base, order)?).map_err(db_err)?;
+    let notifications = in_order(rows, order)
+        .into_iter()
+        .filter(|(_, notification)| !unread_only || !notification.read)
+        .map(Ok::<_, NotificationError>);
+    let (notifications, next_cursor) = paginate(notifications, limit)?;
+
+    let unread = unread_count(&database, &wallet)?;
+    Ok(Json(NotificationList { notifications, unread, next_cursor }))
+}
+
+impl From<ListQueryError> for NotificationError {
+    fn from(e: ListQueryError) -> Self {
+        match e {
+            ListQueryError::InvalidQuery(msg) => NotificationError::ValidationError(msg),
+            ListQueryError::DatabaseError(msg) => NotificationError::DatabaseError(msg),
+        }
+    }
+}
+
+#[utoipa::path(
+    get, path = "/api/notifications/unread", tag = "notifications",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = UnreadCount),
+        (status = 401, description = "Not signed in", body = String),
+    )
+)]
+pub async fn get_unread_count<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+) -> Result<Json<UnreadCount>, NotificationError> {
+    Ok(Json(UnreadCount { unread: unread_count(&database, &wallet)? }))
+}
+
+// ids 가 있으면 그 알림만, 없으면 모두 읽음으로 바꾸고 남은 안 읽은 수를 돌려준다.
+// 다른 지갑의 ID 는 키에 지갑이 들어가므로 찾지 못하고 건너뛴다.
+pub fn mark_notifications_read<T: SafeDatabase>(database: &T, wallet: &str, ids: Option<&[u64]>) -> Result<u64, NotificationError> {
+    let _guard = NOTIFY_LOCK.lock().unwrap();
+
+    let unread: Vec<Notification> = match ids {
+        Some(ids) => {
+            let mut found = Vec::new();
+            for id in ids.iter().collect::<BTreeSet<_>>() {
+                found.extend(NOTIFICATIONS.get(database, &notification_key(wallet, *id)).map_err(db_err)?);
+            }
+            found
+        },
+        None => NOTIFICATIONS.scan(database, &KeyRange::prefix(KeyBuilder::new().str(wallet).build()))
+            .map_err(db_err)?
+            .into_iter()
+            .map(|(_, notification)| notification)
+            .collect(),
+    };
+
+    let mut ops = Vec::new();
+    for mut notification in unread.into_iter().filter(|notification| !notification.read) {
+        notification.read = true;
+        ops.push(NOTIFICATIONS.put_op(&notification_key(wallet, notification.id), &notification).map_err(db_err)?);
+    }
+    let remaining = match ids {
+        Some(_) => unread_count(database, wallet)?.saturating_sub(ops.len() as u64),
+        None => 0,
+    };
+    if ops.is_empty() {
+        return Ok(remaining);
+    }
+
+    ops.push(UNREAD_COUNTS.put_op(wallet.as_bytes(), &remaining).map_err(db_err)?);
+    database.txn(&ops).map_err(db_err)?;
+    Ok(remaining)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct MarkReadRequest {
+    ids: Option<Vec<u64>>,
+}
+
+#[utoipa::path(
+    post, path = "/api/notifications/read", tag = "notifications",
+    request_body = MarkReadRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Unread count after marking", body = UnreadCount),
+        (status = 400, description = "Too many ids", body = String),
+        (status = 401, description = "Not signed in", body = String),
+    )
+)]
+pub async fn mark_read<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<MarkReadRequest>,
+) -> Result<Json<UnreadCount>, NotificationError> {
+    if request.ids.as_ref().is_some_and(|ids| ids.len() > MAX_MARK_READ_IDS) {
+        return Err(NotificationError::ValidationError(format!("at most {} ids per request", MAX_MARK_READ_IDS)));
+    }
+
+    let unread = mark_notifications_read(&database, &wallet, request.ids.as_deref())?;
+    Ok(Json(UnreadCount { unread }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::{event_key, CLAIM_EVENTS, DEPOSIT_EVENTS, SUBMISSION_EVENTS};
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::{ChainProposal, ClaimableRound};
+
+    const NOW: u64 = 1_000;
+
+    fn put(database: &MemoryDatabase, id: u64, dao: &str, actor: &str, event: TurtleEvent) {
+        let table = match event {
+            TurtleEvent::Deposit { .. } => DEPOSIT_EVENTS,
+            TurtleEvent::Submission => SUBMISSION_EVENTS,
+            TurtleEvent::Claim { .. } => CLAIM_EVENTS,
+            TurtleEvent::Vote { .. } => VOTE_EVENTS,
+        };
+        let record = EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: Some(id),
+            dao: dao.to_string(),
+            actor: actor.to_string(),
+            event,
+        };
+        table.put(database, &event_key(id), &record).unwrap();
+    }
+
+    fn proposal(status: &str) -> ChainProposal {
+        ChainProposal {
+            dao: "dao".to_string(),
+            proposal_id: 0,
+            proposer: "carol".to_string(),
+            title: "Faster rounds".to_string(),
+            description: String::new(),
+            vote_type: "ChangeTimeLimit".to_string(),
+            options: vec!["yes".to_string(), "no".to_string()],
+            start_time: 0,
+            end_time: 10,
+            vote_count: 1,
+            status: status.to_string(),
+            option_power: vec![5, 0],
+        }
+    }
+
+    fn kinds(database: &MemoryDatabase, wallet: &str) -> Vec<NotificationKind> {
+        NOTIFICATIONS.scan(database, &KeyRange::prefix(KeyBuilder::new().str(wallet).build()))
+            .unwrap()
+            .into_iter()
+            .map(|(_, notification)| notification.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_events_become_inbox_entries() {
+        let database = MemoryDatabase::new();
+        put(&database, 1, "dao", "alice", TurtleEvent::Submission);
+        put(&database, 2, "dao", "alice", TurtleEvent::Submission);
+        put(&database, 3, "dao", "bob", TurtleEvent::Submission);
+        put(&database, 4, "other", "dave", TurtleEvent::Submission);
+        put(&database, 5, "dao", "carol", TurtleEvent::Vote { proposal_id: 0 });
+        put(&database, 6, "dao", "carol", TurtleEvent::Claim { winner: Some("bob".to_string()) });
+        CHAIN_PROPOSALS.put(&database, b"p0", &proposal("Active")).unwrap();
+
+        // 자기 자신을 다시 앞지른 건 알리지 않는다
+        assert_eq!(process_notifications(&database, NOW).unwrap(), 3);
+        let bob_won = NotificationKind::RewardDistributed { winner: Some("bob".to_string()) };
+        assert_eq!(kinds(&database, "alice"), [NotificationKind::Outbid { by: "bob".to_string() }, bob_won.clone()]);
+        assert_eq!(kinds(&database, "bob"), [bob_won]);
+        assert!(kinds(&database, "dave").is_empty());
+
+        // 새 라운드에서는 지난 라운드 제출자를 앞지른 것으로 치지 않는다
+        put(&database, 7, "dao", "erin", TurtleEvent::Submission);
+        CHAIN_PROPOSALS.put(&database, b"p0", &proposal("Executed")).unwrap();
+        assert_eq!(process_notifications(&database, NOW).unwrap(), 1);
+        assert_eq!(kinds(&database, "carol"), [NotificationKind::ProposalExecuted { proposal_id: 0, title: "Faster rounds".to_string() }]);
+
+        // 마감이 지난 라운드는 그 라운드 제출자에게 한 번 알린다
+        let round = ClaimableRound {
+            dao: "dao".to_string(),
+            deadline: 50,
+            pot_size: 0,
+            content_count: 1,
+            detected_at: NOW,
+            crank_attempted_at: None,
+            crank_signature: None,
+            crank_error: None,
+        };
+        CLAIMABLE_ROUNDS.put(&database, b"dao", &round).unwrap();
+        assert_eq!(process_notifications(&database, NOW).unwrap(), 1);
+        assert_eq!(kinds(&database, "erin"), [NotificationKind::RoundClaimable { deadline: 50 }]);
+
+        // 다시 돌려도 같은 알림이 또 생기지 않는다
+        assert_eq!(process_notifications(&database, NOW).unwrap(), 0);
+        assert_eq!(unread_count(&database, "alice").unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_mark_read() {
+        let database = MemoryDatabase::new();
+        for id in 1..=3 {
+            put(&database, id, "dao", if id % 2 == 1 { "alice" } else { "bob" }, TurtleEvent::Submission);
+        }
+        process_notifications(&database, NOW).unwrap();
+
+        let list = |query: &str| {
+            let query = ListQuery::from_uri(&format!("/?{}", query).parse().unwrap()).unwrap();
+            list_notifications(State(database.clone()), AuthedWallet("alice".to_string()), query)
+        };
+
+        // alice 는 2번, bob 은 3번 이벤트에서 밀렸다
+        let page = list("limit=1").await.unwrap().0;
+        assert_eq!(page.notifications.len(), 1);
+        assert_eq!(page.unread, 1);
+        assert_eq!(page.next_cursor, None);
+        let id = page.notifications[0].id;
+
+        // 다른 지갑의 알림 ID 로는 읽음 처리가 되지 않는다
+        assert_eq!(mark_notifications_read(&database, "bob", Some(&[id])).unwrap(), 1);
+        assert_eq!(mark_notifications_read(&database, "alice", Some(&[id, id])).unwrap(), 0);
+
+        let page = list("filter[unread]=true").await.unwrap().0;
+        assert!(page.notifications.is_empty());
+        assert_eq!(page.unread, 0);
+        assert!(list("").await.unwrap().0.notifications[0].read);
+
+        assert_eq!(mark_notifications_read(&database, "bob", None).unwrap(), 0);
+        assert_eq!(unread_count(&database, "bob").unwrap(), 0);
+    }
+}
@@ -0,0 +1,102 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use crate::chat::*;
+use crate::community::*;
+use crate::content::*;
+use crate::preview::*;
+use crate::dao::*;
+use crate::error::ApiError;
+use crate::notifications::*;
+use crate::profile::*;
+use crate::sns::*;
+use crate::tx::*;
+use crate::verification::*;
+use turtle_service::parser::chat::ChatMessage;
+use turtle_service::parser::community::{Community, Content, DaoInfo, Daopda, Depositor, Proposal};
+use turtle_service::parser::content::ContentRecord;
+use turtle_service::parser::notification::{Notification, NotificationKind};
+use turtle_service::parser::profile::{Profile, SocialLinks, VerificationMethod, WalletVerification};
+use turtle_service::parser::upload::StorageKind;
+
+pub const OPENAPI_JSON_PATH: &str = "/api/openapi.json";
+pub const SWAGGER_UI_PATH: &str = "/api/docs";
+
+// 프론트엔드가 타입 있는 클라이언트를 만들 수 있도록 공개 API 를 문서로 낸다.
+// 새 핸들러에 #[utoipa::path] 를 달았으면 여기 paths 에도 넣어야 스펙에 나온다.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Turtle API"),
+    paths(
+        profile_write, put_profile, patch_profile, get_profile_by_address, get_profiles_batch, verify_profile, reverse_lookup,
+        create_content, get_content_by_id, list_contents, get_content_preview,
+        register_dao, get_dao_summary, list_daos,
+        save_pda, get_all_pdas, save_community, get_all_communities, get_community_by_pda,
+        save_content, get_contents_by_pda, save_depositor, get_depositors_by_pda,
+        save_proposal, get_proposals_by_pda,
+        build_initialize_dao_tx, build_deposit_tx, build_submit_content_tx,
+        build_create_vote_tx, build_vote_tx, build_process_timeout_tx,
+        list_notifications, get_unread_count, mark_read,
+        send_chat_message, list_chat_messages,
+    ),
+    components(schemas(
+        ApiError,
+        Profile, ProfileResponse, ProfileBatchRequest, ProfileBatchResponse, SocialLinks, ProfilePatch, ProfileUpload, StorageKind, SolNameResponse,
+        VerificationMethod, WalletVerification, VerifyProfileRequest,
+        ContentRecord, NewContentRequest, ContentCreatedResponse, ContentListItem, ContentListResponse, ContentPreview,
+        DaoInfo, RegisterDaoRequest, DaoSummary, DaoResponse, DaosResponse,
+        Daopda, Community, CommunityResponse, Content, Depositor, DepositorItem, Proposal,
+        PdasResponse, CommunitiesResponse, ContentsResponse, DepositorsResponse, ProposalsResponse,
+        UnsignedTransactionResponse, InitializeDaoTx, DepositTx, SubmitContentTx,
+        CreateVoteTx, CastVoteTx, ProcessTimeoutTx,
+        Notification, NotificationKind, NotificationList, UnreadCount, MarkReadRequest,
+        ChatMessage, SendChatRequest, ChatMessages,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "profile", description = "Wallet profiles"),
+        (name = "content", description = "Off-chain content records"),
+        (name = "dao", description = "DAO registry and community tables"),
+        (name = "tx", description = "Unsigned transaction builders"),
+        (name = "notifications", description = "Per-wallet notifications"),
+        (name = "chat", description = "Per-DAO chat"),
+    )
+)]
+pub struct ApiDoc;
+
+// /api/auth/verify 가 돌려준 세션 토큰을 Authorization: Bearer 로 보낸다
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_public_api() {
+        let spec = ApiDoc::openapi();
+
+        for path in ["/api/profile", "/api/content", "/api/contents", "/api/dao", "/api/daos", "/api/dao/community", "/api/tx/vote", "/api/notifications/read", "/api/dao/{pubkey}/chat"] {
+            assert!(spec.paths.paths.contains_key(path), "{} is missing", path);
+        }
+        let profile = &spec.paths.paths["/api/profile"];
+        assert!(profile.get.is_some() && profile.post.is_some() && profile.put.is_some() && profile.patch.is_some());
+
+        let components = spec.components.as_ref().unwrap();
+        assert!(components.schemas.contains_key("Profile"));
+        assert!(components.schemas.contains_key("UnsignedTransactionResponse"));
+        assert!(components.security_schemes.contains_key("bearer_auth"));
+
+        let json = spec.to_json().unwrap();
+        assert!(json.contains("\"bearer_auth\""));
+    }
+}
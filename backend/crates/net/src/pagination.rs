@@ -0,0 +1,239 @@
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use axum::http::{StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::ops::Bound;
+use turtle_database::kv::KeyRange;
+use utoipa::{IntoParams, ToSchema};
+use crate::error::ApiError;
+
+#[derive(Debug, PartialEq)]
+pub enum ListQueryError {
+    InvalidQuery(String),
+    DatabaseError(String),
+}
+
+impl fmt::Display for ListQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListQueryError::InvalidQuery(msg) => write!(f, "Invalid list query: {}", msg),
+            ListQueryError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for ListQueryError {}
+
+impl IntoResponse for ListQueryError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ListQueryError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            ListQueryError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> ListQueryError {
+    ListQueryError::InvalidQuery(msg.into())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+// 목록 라우트가 함께 쓰는 쿼리: ?limit=&cursor=&sort=&order=&filter[name]=value
+// 커서는 마지막으로 준 항목의 저장소 키라서, 그 사이에 쓰기가 있어도 항목이 밀리거나 겹치지 않는다.
+// 핸들러는 허용하는 sort/filter 이름을 넘겨서 모르는 값을 400 으로 막는다.
+#[derive(Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    limit: Option<usize>,
+    // 이전 응답의 next_cursor
+    cursor: Option<String>,
+    sort: Option<String>,
+    order: Option<SortOrder>,
+    // filter[dao]=...&filter[author]=... (라우트마다 받는 이름이 다르다)
+    #[param(style = DeepObject, explode, value_type = Option<BTreeMap<String, String>>)]
+    filter: BTreeMap<String, String>,
+}
+
+impl ListQuery {
+    pub fn from_uri(uri: &Uri) -> Result<Self, ListQueryError> {
+        let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(uri)
+            .map_err(|e| invalid(e.body_text()))?;
+
+        let mut query = Self::default();
+        for (name, value) in pairs {
+            match name.as_str() {
+                "limit" => query.limit = Some(value.parse().map_err(|_| invalid(format!("limit must be a number: {}", value)))?),
+                "cursor" => query.cursor = Some(value),
+                "sort" => query.sort = Some(value),
+                "order" => query.order = Some(match value.as_str() {
+                    "asc" => SortOrder::Asc,
+                    "desc" => SortOrder::Desc,
+                    _ => return Err(invalid(format!("order must be asc or desc: {}", value))),
+                }),
+                // 캐시 무효화용 파라미터 같은 모르는 이름은 그냥 둔다
+                _ => if let Some(field) = name.strip_prefix("filter[").and_then(|rest| rest.strip_suffix(']')) {
+                    query.filter.insert(field.to_string(), value);
+                },
+            }
+        }
+        Ok(query)
+    }
+
+    pub fn limit(&self, default: usize, max: usize) -> usize {
+        self.limit.unwrap_or(default).clamp(1, max)
+    }
+
+    pub fn order(&self, default: SortOrder) -> SortOrder {
+        self.order.unwrap_or(default)
+    }
+
+    // allowed 의 첫 번째가 기본값. 커서가 저장소 키라서 키 순서와 같은 정렬만 받을 수 있다.
+    pub fn sort<'a>(&self, allowed: &[&'a str]) -> Result<&'a str, ListQueryError> {
+        match &self.sort {
+            None => Ok(allowed[0]),
+            Some(sort) => allowed.iter()
+                .find(|field| *field == sort)
+                .copied()
+                .ok_or_else(|| invalid(format!("sort must be one of {}: {}", allowed.join(", "), sort))),
+        }
+    }
+
+    // allowed 에 없는 filter 가 있으면 거절한다
+    pub fn check_filters(&self, allowed: &[&str]) -> Result<(), ListQueryError> {
+        match self.filter.keys().find(|name| !allowed.contains(&name.as_str())) {
+            Some(name) => Err(invalid(format!("unknown filter {} (expected one of {})", name, allowed.join(", ")))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn filter(&self, name: &str) -> Option<&str> {
+        self.filter.get(name).map(String::as_str)
+    }
+
+    // base 를 커서 다음 (order 방향) 으로 좁힌다. 다른 목록(예: 다른 지갑) 의 커서는 받지 않는다.
+    pub fn range(&self, base: KeyRange, order: SortOrder) -> Result<KeyRange, ListQueryError> {
+        let Some(cursor) = &self.cursor else {
+            return Ok(base);
+        };
+        let key = URL_SAFE_NO_PAD.decode(cursor)
+            .ok()
+            .filter(|key| base.contains(key))
+            .ok_or_else(|| invalid("cursor does not belong to this list"))?;
+        Ok(match order {
+            SortOrder::Asc => KeyRange { start: Bound::Excluded(key), end: base.end },
+            SortOrder::Desc => KeyRange { start: base.start, end: Bound::Excluded(key) },
+        })
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ListQuery {
+    type Rejection = ListQueryError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Self::from_uri(&parts.uri)
+    }
+}
+
+pub fn encode_cursor(key: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(key)
+}
+
+// 키 순서로 읽은 행을 order 방향으로 돌린다
+pub fn in_order<T>(mut rows: Vec<T>, order: SortOrder) -> Vec<T> {
+    if order == SortOrder::Desc {
+        rows.reverse();
+    }
+    rows
+}
+
+// order 방향으로 정렬된 (키, 항목) 에서 limit 개를 꺼내고, 더 남아 있으면 마지막 키를 다음 커서로 준다.
+// rows 는 지연 평가라서 limit + 1 번째까지만 읽는다.
+pub fn paginate<V, E>(
+    rows: impl IntoIterator<Item = Result<(Vec<u8>, V), E>>,
+    limit: usize,
+) -> Result<(Vec<V>, Option<String>), E> {
+    let mut items = Vec::new();
+    let mut last_key = None;
+    for row in rows {
+        let (key, item) = row?;
+        if items.len() == limit {
+            return Ok((items, last_key.as_deref().map(encode_cursor)));
+        }
+        items.push(item);
+        last_key = Some(key);
+    }
+    Ok((items, None))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(query: &str) -> Result<ListQuery, ListQueryError> {
+        ListQuery::from_uri(&format!("/list?{}", query).parse().unwrap())
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let query = parse("limit=500&sort=id&order=desc&filter[dao]=dao1&filter%5Bauthor%5D=alice&_=123").unwrap();
+        assert_eq!(query.limit(20, 100), 100);
+        assert_eq!(query.order(SortOrder::Asc), SortOrder::Desc);
+        assert_eq!(query.sort(&["id"]), Ok("id"));
+        assert_eq!(query.filter("dao"), Some("dao1"));
+        assert_eq!(query.filter("author"), Some("alice"));
+        assert!(query.check_filters(&["dao", "author"]).is_ok());
+        assert!(query.check_filters(&["dao"]).is_err());
+        assert!(query.sort(&["pubkey"]).is_err());
+
+        let query = parse("").unwrap();
+        assert_eq!((query.limit(20, 100), query.order(SortOrder::Asc)), (20, SortOrder::Asc));
+        assert_eq!(query.sort(&["id", "created"]), Ok("id"));
+
+        for bad in ["limit=ten", "order=up", "limit=-1"] {
+            assert!(matches!(parse(bad), Err(ListQueryError::InvalidQuery(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_keyset_pages() {
+        let rows: Vec<(Vec<u8>, u8)> = (1..=5u8).map(|i| (vec![b'k', i], i)).collect();
+        let base = KeyRange::prefix(b"k");
+        let page = |query: &ListQuery, order| {
+            let range = query.range(base.clone(), order).unwrap();
+            let rows = rows.iter().filter(|(key, _)| range.contains(key)).cloned().map(Ok::<_, ListQueryError>).collect();
+            paginate(in_order(rows, order), 2).unwrap()
+        };
+
+        let (items, cursor) = page(&parse("").unwrap(), SortOrder::Asc);
+        assert_eq!(items, [1, 2]);
+        let (items, cursor) = page(&parse(&format!("cursor={}", cursor.unwrap())).unwrap(), SortOrder::Asc);
+        assert_eq!(items, [3, 4]);
+        let (items, cursor) = page(&parse(&format!("cursor={}", cursor.unwrap())).unwrap(), SortOrder::Asc);
+        assert_eq!((items, cursor), (vec![5], None));
+
+        let (items, cursor) = page(&parse("").unwrap(), SortOrder::Desc);
+        assert_eq!(items, [5, 4]);
+        let (items, _) = page(&parse(&format!("cursor={}", cursor.unwrap())).unwrap(), SortOrder::Desc);
+        assert_eq!(items, [3, 2]);
+
+        // 범위 밖의 키나 깨진 커서는 받지 않는다
+        for cursor in [encode_cursor(b"other"), "!!".to_string()] {
+            let query = parse(&format!("cursor={}", cursor)).unwrap();
+            assert!(query.range(base.clone(), SortOrder::Asc).is_err());
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::fmt;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::ttl::now_secs;
+use utoipa::{IntoParams, ToSchema};
+use crate::config::app_config;
+use crate::content::CONTENT_RECORDS;
+use crate::dao::DAO_REGISTRY;
+use crate::gateway::{gateway_source, media_cache, media_id, serve_media, MediaCache, MediaError, MediaSource, BLOCKED_MEDIA};
+use crate::profile::PROFILES;
+use crate::sns::sol_name;
+use crate::sync::DAO_SNAPSHOTS;
+use crate::upload::UPLOADS;
+use crate::error::ApiError;
+
+pub const SITE_NAME: &str = "Turtle";
+const TITLE_CHARS: usize = 70;
+const DESCRIPTION_CHARS: usize = 200;
+// 크롤러가 자주 다시 긁어 가므로 짧게 캐시하게 둔다 (검열/이름 변경이 늦게 반영돼도 몇 분)
+const PREVIEW_CACHE_CONTROL: &str = "public, max-age=300";
+// 미리보기에 쓸 축소본. 없으면 원본 이미지.
+const PREVIEW_VARIANT: &str = "medium";
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PreviewQuery {
+    // "html" 이면 og:/twitter: 메타 태그가 든 페이지를 돌려준다 (기본은 JSON)
+    format: Option<String>,
+}
+
+// 공유 링크 미리보기(Open Graph). 이미지는 항상 /api/media/{id} 주소로 내주고 ipfs:// 같은 원래 주소는 싣지 않는다.
+#[derive(Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ContentPreview {
+    pub id: u64,
+    pub title: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub image_width: Option<u32>,
+    pub image_height: Option<u32>,
+    pub author: String,
+    // 프로필 이름, 없으면 .sol 이름
+    pub author_name: Option<String>,
+    pub dao: String,
+    pub dao_name: Option<String>,
+    pub url: String,
+    pub site_name: String,
+}
+
+#[derive(Debug)]
+pub enum PreviewError {
+    DatabaseError(String),
+    ValidationError(String),
+    NotFound(u64),
+}
+
+impl fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreviewError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            PreviewError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            PreviewError::NotFound(id) => write!(f, "Content not found: {}", id),
+        }
+    }
+}
+
+impl StdError for PreviewError {}
+
+impl IntoResponse for PreviewError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PreviewError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            PreviewError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            PreviewError::NotFound(_) => StatusCode::NOT_FOUND,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> PreviewError {
+    PreviewError::DatabaseError(e.to_string())
+}
+
+// 글자 수로 자르고 잘렸으면 말줄임표를 붙인다. 줄바꿈과 연속 공백은 하나로 합친다.
+pub fn snippet(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+// public_url 이 있으면 절대 주소. 크롤러는 상대 주소 이미지를 무시하므로 배포에서는 설정해야 한다.
+fn public_link(path: &str) -> String {
+    match &app_config().public_url {
+        Some(base) => format!("{}{}", base.trim_end_matches('/'), path),
+        None => path.to_string(),
+    }
+}
+
+// 막히지 않은 ipfs:// / ar:// 주소만 게이트웨이 경로로 바꾼다
+fn gateway_link<T: SafeDatabase>(database: &T, uri: &str) -> Result<Option<String>, PreviewError> {
+    let Some(id) = media_id(uri) else {
+        return Ok(None);
+    };
+    if BLOCKED_MEDIA.get(database, id.as_bytes()).map_err(db_err)?.is_some() {
+        return Ok(None);
+    }
+    Ok(Some(public_link(&format!("/api/media/{}", id))))
+}
+
+// 고정해 둔 콘텐츠 문서의 본문. 받지 못하거나 막혔으면 본문 없이 미리보기를 만든다.
+async fn pinned_text<T: SafeDatabase, S: MediaSource>(
+    database: &T,
+    cache: &MediaCache,
+    source: &S,
+    content_uri: &str,
+    max_bytes: u64,
+) -> Option<String> {
+    let id = media_id(content_uri)?;
+    let media = match serve_media(database, cache, source, id, max_bytes).await {
+        Ok(media) => media,
+        Err(MediaError::Blocked(_)) => return None,
+        Err(e) => {
+            tracing::warn!(id, error = %e, "Failed to load content document for preview");
+            return None;
+        },
+    };
+    let document: serde_json::Value = serde_json::from_slice(&media.bytes).ok()?;
+    document.get("text")?.as_str().map(str::to_string)
+}
+
+pub async fn build_preview<T: SafeDatabase, S: MediaSource>(
+    database: &T,
+    cache: &MediaCache,
+    source: &S,
+    id: u64,
+    max_bytes: u64,
+) -> Result<ContentPreview, PreviewError> {
+    let record = CONTENT_RECORDS.get(database, &KeyBuilder::new().u64(id).build())
+        .map_err(db_err)?
+        .ok_or(PreviewError::NotFound(id))?;
+
+    let dao_name = match DAO_REGISTRY.get(database, record.dao.as_bytes()).map_err(db_err)? {
+        Some(info) => Some(info.name),
+        None => DAO_SNAPSHOTS.get(database, record.dao.as_bytes()).map_err(db_err)?.map(|snapshot| snapshot.dao_name),
+    };
+    let author_name = match PROFILES.get(database, record.author.as_bytes()).map_err(db_err)? {
+        Some(profile) if !profile.display_name.is_empty() => Some(profile.display_name),
+        _ => sol_name(database, &record.author, now_secs()).map_err(db_err)?,
+    };
+
+    // 업로드 기록이 있으면 중간 크기 축소본을 쓴다
+    let (mut image, mut image_width, mut image_height) = (None, None, None);
+    if let Some(upload) = UPLOADS.get(database, record.content_hash.as_bytes()).map_err(db_err)? {
+        match upload.image_variants.iter().find(|variant| variant.name == PREVIEW_VARIANT) {
+            Some(variant) => {
+                image = gateway_link(database, &variant.uri)?;
+                if image.is_some() {
+                    (image_width, image_height) = (Some(variant.width), Some(variant.height));
+                }
+            },
+            None => image = gateway_link(database, &upload.image_uri)?,
+        }
+    }
+
+    let text = pinned_text(database, cache, source, &record.content_uri, max_bytes).await;
+    let title = match (text.as_deref().map(|text| snippet(text, TITLE_CHARS)), &dao_name) {
+        (Some(title), _) if !title.is_empty() => title,
+        (_, Some(dao_name)) => format!("{} #{}", dao_name, id),
+        (_, None) => format!("{} #{}", SITE_NAME, id),
+    };
+    let description = text
+        .map(|text| snippet(&text, DESCRIPTION_CHARS))
+        .filter(|description| !description.is_empty());
+
+    Ok(ContentPreview {
+        id,
+        title,
+        description,
+        image,
+        image_width,
+        image_height,
+        author: record.author,
+        author_name,
+        dao: record.dao,
+        dao_name,
+        url: public_link(&format!("/api/content/{}/preview?format=html", id)),
+        site_name: SITE_NAME.to_string(),
+    })
+}
+
+// 크롤러(Discord, Twitter 등) 가 읽는 메타 태그만 든 페이지
+pub fn render_html(preview: &ContentPreview) -> String {
+    let mut tags = vec![
+        ("og:type", "article".to_string()),
+        ("og:site_name", preview.site_name.clone()),
+        ("og:title", preview.title.clone()),
+        ("og:url", preview.url.clone()),
+        ("twitter:title", preview.title.clone()),
+    ];
+    if let Some(description) = &preview.description {
+        tags.push(("og:description", description.clone()));
+        tags.push(("twitter:description", description.clone()));
+    }
+    let by = preview.author_name.as_deref().unwrap_or(&preview.author);
+    tags.push(("article:author", by.to_string()));
+    if let Some(dao_name) = &preview.dao_name {
+        tags.push(("article:section", dao_name.clone()));
+    }
+    match &preview.image {
+        Some(image) => {
+            tags.push(("og:image", image.clone()));
+            tags.push(("twitter:image", image.clone()));
+            tags.push(("twitter:card", "summary_large_image".to_string()));
+            if let (Some(width), Some(height)) = (preview.image_width, preview.image_height) {
+                tags.push(("og:image:width", width.to_string()));
+                tags.push(("og:image:height", height.to_string()));
+            }
+        },
+        None => tags.push(("twitter:card", "summary".to_string())),
+    }
+
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(&preview.title)));
+    for (property, content) in tags {
+        let attribute = if property.starts_with("twitter:") { "name" } else { "property" };
+        html.push_str(&format!("<meta {}=\"{}\" content=\"{}\">\n", attribute, property, escape_html(&content)));
+    }
+    html.push_str("</head>\n<body></body>\n</html>\n");
+    html
+}
+
+// 공유 링크 미리보기. ?format=html 이면 메타 태그 페이지, 아니면 JSON.
+#[utoipa::path(
+    get, path = "/api/content/{id}/preview", tag = "content",
+    params(("id" = u64, Path, description = "Content id"), PreviewQuery),
+    responses(
+        (status = 200, body = ContentPreview),
+        (status = 400, description = "Unknown format", body = String),
+        (status = 404, description = "Unknown id", body = String),
+    )
+)]
+pub async fn get_content_preview<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(id): Path<u64>,
+    Query(query): Query<PreviewQuery>,
+) -> Result<Response, PreviewError> {
+    let html = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("html") => true,
+        Some(other) => return Err(PreviewError::ValidationError(format!("format must be json or html: {}", other))),
+    };
+    let cache = media_cache().map_err(|e| PreviewError::DatabaseError(e.to_string()))?;
+    let preview = build_preview(&database, cache, gateway_source(), id, app_config().media_max_object_bytes).await?;
+
+    if html {
+        Ok((
+            [
+                (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+                (header::CACHE_CONTROL, PREVIEW_CACHE_CONTROL),
+            ],
+            render_html(&preview),
+        ).into_response())
+    } else {
+        Ok(([(header::CACHE_CONTROL, PREVIEW_CACHE_CONTROL)], Json(preview)).into_response())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::content::ContentRecord;
+    use turtle_service::parser::upload::{BlockedMedia, ImageVariant, StorageKind, UploadRecord};
+    use crate::gateway::MediaLocation;
+
+    const DOCUMENT_CID: &str = "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+    const MEDIUM_CID: &str = "bafkreiaxnnnb7qz2focittuqq3ya25q7rcv3bqynnczfzako47346wosmu";
+
+    struct DocumentSource(Vec<u8>);
+
+    impl MediaSource for DocumentSource {
+        async fn fetch(&self, _location: &MediaLocation, _max_bytes: u64) -> Result<Vec<u8>, MediaError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn seed(database: &MemoryDatabase) {
+        let record = ContentRecord {
+            id: 1,
+            dao: "dao".to_string(),
+            author: "alice".to_string(),
+            content_hash: "hash".to_string(),
+            content_uri: format!("ipfs://{}", DOCUMENT_CID),
+            timestamp: 0,
+        };
+        CONTENT_RECORDS.put(database, &KeyBuilder::new().u64(1).build(), &record).unwrap();
+        let upload = UploadRecord {
+            content_hash: "hash".to_string(),
+            content_uri: record.content_uri.clone(),
+            image_uri: "ipfs://original".to_string(),
+            image_variants: vec![ImageVariant {
+                name: "medium".to_string(),
+                uri: format!("ipfs://{}", MEDIUM_CID),
+                content_type: "image/webp".to_string(),
+                width: 800,
+                height: 600,
+            }],
+            author: "alice".to_string(),
+            dao: "dao".to_string(),
+            storage: StorageKind::Ipfs,
+            uploaded_at: 0,
+        };
+        UPLOADS.put(database, b"hash", &upload).unwrap();
+    }
+
+    #[test]
+    fn test_snippet_collapses_whitespace_and_truncates() {
+        assert_eq!(snippet("hello\n\n  world", 20), "hello world");
+        assert_eq!(snippet("가나다라마바사", 4), "가나다…");
+    }
+
+    #[tokio::test]
+    async fn test_preview_uses_gateway_links_and_escapes_html() {
+        let database = MemoryDatabase::new();
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MediaCache::open(dir.path(), 1 << 20).unwrap();
+        let source = DocumentSource(br#"{"text":"<b>gm</b> turtles"}"#.to_vec());
+        seed(&database);
+
+        let preview = build_preview(&database, &cache, &source, 1, 1024).await.unwrap();
+        assert_eq!(preview.title, "<b>gm</b> turtles");
+        assert_eq!(preview.image.as_deref(), Some(format!("/api/media/{}", MEDIUM_CID).as_str()));
+        assert_eq!((preview.image_width, preview.image_height), (Some(800), Some(600)));
+        assert_eq!(preview.dao_name, None);
+
+        let html = render_html(&preview);
+        assert!(html.contains("content=\"&lt;b&gt;gm&lt;/b&gt; turtles\""));
+        assert!(!html.contains("ipfs://"));
+
+        // 막힌 이미지와 본문은 빼고 DAO 이름으로 제목을 만든다
+        for cid in [MEDIUM_CID, DOCUMENT_CID] {
+            let blocked = BlockedMedia {
+                id: cid.to_string(),
+                reason: Some("spam".to_string()),
+                hidden_content: None,
+                blocked_by: "moderator".to_string(),
+                blocked_at: 0,
+            };
+            BLOCKED_MEDIA.put(&database, cid.as_bytes(), &blocked).unwrap();
+        }
+        let preview = build_preview(&database, &cache, &source, 1, 1024).await.unwrap();
+        assert_eq!((preview.image, preview.description), (None, None));
+        assert_eq!(preview.title, "Turtle #1");
+
+        assert!(matches!(build_preview(&database, &cache, &source, 2, 1024).await, Err(PreviewError::NotFound(2))));
+    }
+}
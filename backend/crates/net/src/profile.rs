@@ -3,25 +3,73 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use std::error::Error as StdError;
 use std::fmt;
+use std::sync::Mutex;
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use turtle_database::basic_db::{SafeDatabase};
-use turtle_service::parser::profile::UserProfile;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::profile::{Profile, SocialLinks, WalletVerification};
+use turtle_service::parser::upload::StorageKind;
+use crate::session::AuthedWallet;
+use crate::sns::{sol_name, sol_names};
+use crate::upload::{arweave_client, ipfs_client, parse_storage, Image, StorageProvider, UploadError};
+use crate::error::ApiError;
+
+// wallet -> Profile (스키마 v3 에서 UserProfile 을 이 형식으로 옮겼다)
+pub const PROFILES: TypedTable<Profile> = TypedTable::new("user_profiles");
+
+pub const MAX_DISPLAY_NAME_CHARS: usize = 50;
+pub const MAX_BIO_CHARS: usize = 500;
+pub const MAX_AVATAR_BYTES: usize = 2 * 1024 * 1024;
+// POST /api/profiles/batch 한 번에 받는 주소 수
+pub const MAX_BATCH_ADDRESSES: usize = 100;
+const MAX_URI_BYTES: usize = 512;
+
+const AVATAR_CONTENT_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+// 사용자가 직접 넣는 avatar_uri 는 저장소 URI 나 https 만 받는다
+const AVATAR_URI_SCHEMES: &[&str] = &["ipfs://", "ar://", "https://"];
+const WEBSITE_SCHEMES: &[&str] = &["https://", "http://"];
+
+// 같은 지갑의 PUT/PATCH 가 겹쳐도 읽고-고치고-쓰기가 서로를 덮어쓰지 않게 한다
+static PROFILE_LOCK: Mutex<()> = Mutex::new(());
 
 // Query parameters struct for the get_profile_by_address endpoint
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AddressQuery {
     address: String,
 }
 
-// Response struct for the get_profile_by_address endpoint
+// GET /api/profile 응답. sol_name 은 지갑의 대표 .sol 이름 (아직 조회 전이거나 없으면 null).
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct ProfileResponse {
+    #[serde(flatten)]
+    pub profile: Profile,
+    pub sol_name: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ProfileBatchRequest {
+    addresses: Vec<String>,
+}
 
+// 요청한 순서대로 (중복 주소는 한 번만). 저장된 적 없는 지갑은 빈 프로필이다.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProfileBatchResponse {
+    profiles: Vec<ProfileResponse>,
+}
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum ProfileError {
     MultipartError(String),
+    ValidationError(String),
     DatabaseError(String),
-    SerializationError(String),
+    StorageError(String),
+    ConfigError(String),
+    Forbidden(String),
 }
 
 // ProfileError에 Display 트레이트 구현 (Error 트레이트 구현에 필요)
@@ -29,8 +77,11 @@ impl fmt::Display for ProfileError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ProfileError::MultipartError(msg) => write!(f, "Multipart error: {}", msg),
+            ProfileError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
             ProfileError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
-            ProfileError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            ProfileError::StorageError(msg) => write!(f, "Storage error: {}", msg),
+            ProfileError::ConfigError(msg) => write!(f, "Config error: {}", msg),
+            ProfileError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
         }
     }
 }
@@ -43,129 +94,405 @@ impl IntoResponse for ProfileError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             ProfileError::MultipartError(msg) => (StatusCode::BAD_REQUEST, msg),
+            ProfileError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
             ProfileError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            ProfileError::SerializationError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ProfileError::StorageError(msg) => (StatusCode::BAD_GATEWAY, msg),
+            ProfileError::ConfigError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            ProfileError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
         };
 
         // 에러 메시지와 상태 코드 반환
-        (status, error_message).into_response()
+        ApiError::new(status, error_message).into_response()
     }
 }
 
-pub async fn profile_write<T: SafeDatabase>(
-    State(database): State<T>,
-    mut multipart: Multipart
-) -> Result<StatusCode, ProfileError>
-{
-    // 사용자 프로필 데이터 초기화
-    let mut user_profile = UserProfile {
-        user_id: String::new(),
-        user_name: String::new(),
-        user_address: String::new(),
-        github_account: String::new(),
-        x_account: String::new(),
-        tg_account: String::new(),
-        user_bio: String::new(),
-        user_avatar: None,
-        avatar_content_type: None,
+impl From<UploadError> for ProfileError {
+    fn from(error: UploadError) -> Self {
+        match error {
+            UploadError::MultipartError(msg) => ProfileError::MultipartError(msg),
+            UploadError::ValidationError(msg) => ProfileError::ValidationError(msg),
+            UploadError::DatabaseError(msg) => ProfileError::DatabaseError(msg),
+            UploadError::ConfigError(msg) => ProfileError::ConfigError(msg),
+            UploadError::StorageError(msg) | UploadError::NotFound(msg) => ProfileError::StorageError(msg),
+        }
+    }
+}
+
+// PUT 과 PATCH 가 같이 쓰는 본문. PATCH 는 들어온 필드만 바꾸고 PUT 은 빠진 필드를 비운다.
+// 선택 필드(avatar_uri, links.*)는 빈 문자열을 주면 지워진다.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilePatch {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_uri: Option<String>,
+    pub links: Option<SocialLinks>,
+}
+
+fn clean_text(field: &str, value: &str, max_chars: usize, multiline: bool) -> Result<String, ProfileError> {
+    let value = value.trim();
+    if value.chars().count() > max_chars {
+        return Err(ProfileError::ValidationError(format!("{} must be at most {} characters", field, max_chars)));
+    }
+    if value.chars().any(|c| c.is_control() && !(multiline && c == '\n')) {
+        return Err(ProfileError::ValidationError(format!("{} must not contain control characters", field)));
+    }
+    Ok(value.to_string())
+}
+
+// 앞의 @ 는 떼고 저장한다
+fn clean_handle(field: &str, value: &str, len: (usize, usize), allowed: fn(char) -> bool) -> Result<Option<String>, ProfileError> {
+    let handle = value.trim().trim_start_matches('@');
+    if handle.is_empty() {
+        return Ok(None);
+    }
+
+    let count = handle.chars().count();
+    if count < len.0 || count > len.1 || !handle.chars().all(allowed) {
+        return Err(ProfileError::ValidationError(format!("Invalid {} handle: {}", field, value)));
+    }
+    Ok(Some(handle.to_string()))
+}
+
+fn clean_uri(field: &str, value: &str, schemes: &[&str]) -> Result<Option<String>, ProfileError> {
+    let uri = value.trim();
+    if uri.is_empty() {
+        return Ok(None);
+    }
+
+    let known_scheme = schemes.iter().any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme));
+    if !known_scheme || uri.len() > MAX_URI_BYTES || uri.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(ProfileError::ValidationError(format!("Invalid {}: {}", field, value)));
+    }
+    Ok(Some(uri.to_string()))
+}
+
+// 검증과 정규화를 하면서 patch 를 적용한다. 하나라도 틀리면 profile 은 버려진다.
+pub fn apply_patch(profile: &mut Profile, patch: ProfilePatch) -> Result<(), ProfileError> {
+    if let Some(display_name) = patch.display_name {
+        profile.display_name = clean_text("display_name", &display_name, MAX_DISPLAY_NAME_CHARS, false)?;
+    }
+    if let Some(bio) = patch.bio {
+        profile.bio = clean_text("bio", &bio, MAX_BIO_CHARS, true)?;
+    }
+    if let Some(avatar_uri) = patch.avatar_uri {
+        profile.avatar_uri = clean_uri("avatar_uri", &avatar_uri, AVATAR_URI_SCHEMES)?;
+    }
+
+    if let Some(links) = patch.links {
+        if let Some(github) = links.github {
+            profile.links.github = clean_handle("github", &github, (1, 39), |c| c.is_ascii_alphanumeric() || c == '-')?;
+        }
+        if let Some(x) = links.x {
+            profile.links.x = clean_handle("x", &x, (1, 15), |c| c.is_ascii_alphanumeric() || c == '_')?;
+        }
+        if let Some(telegram) = links.telegram {
+            profile.links.telegram = clean_handle("telegram", &telegram, (5, 32), |c| c.is_ascii_alphanumeric() || c == '_')?;
+        }
+        if let Some(website) = links.website {
+            profile.links.website = clean_uri("website", &website, WEBSITE_SCHEMES)?;
+        }
+    }
+
+    Ok(())
+}
+
+// replace 면 PUT (빈 프로필에서 시작), 아니면 PATCH (저장된 프로필에서 시작). created_at 은 처음 저장한 시각을 유지한다.
+pub fn update_profile<T: SafeDatabase>(
+    database: &T,
+    wallet: &str,
+    patch: ProfilePatch,
+    replace: bool,
+    now: u64,
+) -> Result<Profile, ProfileError> {
+    let _guard = PROFILE_LOCK.lock().unwrap();
+
+    let existing = PROFILES.get(database, wallet.as_bytes())
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+    let created_at = existing.as_ref().map_or(now, |profile| profile.created_at);
+    // 지갑 소유 증명은 PUT 으로도 지워지지 않는다
+    let verification = existing.as_ref().and_then(|profile| profile.verification.clone());
+
+    let mut profile = match existing {
+        Some(profile) if !replace => profile,
+        _ => Profile::empty(wallet),
     };
+    apply_patch(&mut profile, patch)?;
+    profile.created_at = created_at;
+    profile.updated_at = now;
+    profile.verified = verification.is_some();
+    profile.verification = verification;
+
+    PROFILES.put(database, wallet.as_bytes(), &profile)
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+    Ok(profile)
+}
+
+// 지갑 소유가 증명됐을 때 배지를 붙인다. 프로필이 없으면 빈 프로필을 만든다.
+pub fn mark_verified<T: SafeDatabase>(database: &T, wallet: &str, verification: WalletVerification) -> Result<Profile, ProfileError> {
+    let _guard = PROFILE_LOCK.lock().unwrap();
+
+    let mut profile = PROFILES.get(database, wallet.as_bytes())
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?
+        .unwrap_or_else(|| Profile { created_at: verification.verified_at, ..Profile::empty(wallet) });
+    profile.updated_at = verification.verified_at;
+    profile.verified = true;
+    profile.verification = Some(verification);
+
+    PROFILES.put(database, wallet.as_bytes(), &profile)
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+    Ok(profile)
+}
+
+pub fn is_verified<T: SafeDatabase>(database: &T, wallet: &str) -> Result<bool, ProfileError> {
+    let profile = PROFILES.get(database, wallet.as_bytes())
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+    Ok(profile.is_some_and(|profile| profile.verified))
+}
+
+fn validate_avatar(avatar: &Image) -> Result<(), ProfileError> {
+    if !AVATAR_CONTENT_TYPES.contains(&avatar.content_type.as_str()) {
+        return Err(ProfileError::ValidationError(format!("avatar must be one of {}", AVATAR_CONTENT_TYPES.join(", "))));
+    }
+    if avatar.bytes.len() > MAX_AVATAR_BYTES {
+        return Err(ProfileError::ValidationError(format!("avatar must be at most {} bytes", MAX_AVATAR_BYTES)));
+    }
+    Ok(())
+}
+
+// multipart 로 받은 프로필 수정 요청
+pub struct ProfileForm {
+    pub patch: ProfilePatch,
+    pub avatar: Option<Image>,
+    pub storage: Option<StorageKind>,
+}
+
+// POST /api/profile 의 multipart 필드 (OpenAPI 문서용. 실제 파싱은 read_profile_form)
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct ProfileUpload {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub github: Option<String>,
+    pub x: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+    pub storage: Option<StorageKind>,
+    #[schema(value_type = Option<String>, format = Binary)]
+    pub avatar: Option<Vec<u8>>,
+}
+
+// 필드 이름은 새 이름과 옛 UserProfile 이름(user_name, user_bio, github_account, ...)을 모두 받는다.
+// 보낸 필드만 바뀐다 (PATCH 와 같음).
+async fn read_profile_form(wallet: &str, multipart: &mut Multipart) -> Result<ProfileForm, ProfileError> {
+    let mut patch = ProfilePatch::default();
+    let mut links = SocialLinks::default();
+    let mut avatar = None;
+    let mut storage = None;
 
     // multipart 필드 처리
     while let Some(field) = multipart.next_field().await.map_err(|e| ProfileError::MultipartError(e.to_string()))? {
         let name = field.name().unwrap_or("").to_string();
 
-        match name.as_str() {
-            "user_id" => {
-                user_profile.user_id = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-            },
-            "user_name" => {
-                user_profile.user_name = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-            },
-            "user_address" => {
-                user_profile.user_address = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-            },
-            "github_account" => {
-                user_profile.github_account = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-            },
-            "x_account" => {
-                user_profile.x_account = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-            },
-            "tg_account" => {
-                user_profile.tg_account = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-            },
-            "user_bio" => {
-                user_profile.user_bio = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
+        if name == "avatar" || name == "user_avatar" {
+            // 이미지 데이터 처리
+            let file_name = field.file_name().unwrap_or("avatar").to_string();
+            let content_type = field.content_type().unwrap_or("").to_string();
+            let bytes = field.bytes().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
+
+            if !bytes.is_empty() {
+                avatar = Some(Image { file_name, content_type, bytes: bytes.to_vec() });
+            }
+            continue;
+        }
+
+        let target = match name.as_str() {
+            "display_name" | "user_name" => &mut patch.display_name,
+            "bio" | "user_bio" => &mut patch.bio,
+            "github" | "github_account" => &mut links.github,
+            "x" | "x_account" => &mut links.x,
+            "telegram" | "tg_account" => &mut links.telegram,
+            "website" => &mut links.website,
+            "storage" => {
+                let value = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
+                storage = Some(parse_storage(&value)?);
+                continue;
             },
-            "user_avatar" => {
-                // 이미지 데이터 처리
-                let content_type = field.content_type().map(|ct| ct.to_string());
-                let data = field.bytes().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
-
-                if !data.is_empty() {
-                    user_profile.user_avatar = Some(data.to_vec());
-                    user_profile.avatar_content_type = content_type;
+            "user_address" | "wallet" => {
+                // 주소는 세션의 지갑을 쓴다. 본문에 다른 주소를 넣어서 남의 프로필을 덮어쓸 수 없다.
+                let address = field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?;
+                if !address.is_empty() && address != wallet {
+                    return Err(ProfileError::Forbidden("user_address does not match the signed-in wallet".to_string()));
                 }
+                continue;
             },
             _ => continue,
-        }
+        };
+        *target = Some(field.text().await.map_err(|e| ProfileError::MultipartError(e.to_string()))?);
     }
 
-    if user_profile.user_address.is_empty() {
-        return Err(ProfileError::MultipartError("User ID is required".to_string()));
+    if links != SocialLinks::default() {
+        patch.links = Some(links);
     }
+    Ok(ProfileForm { patch, avatar, storage })
+}
 
-    let profile_json = serde_json::to_string(&user_profile)
-        .map_err(|e| ProfileError::SerializationError(e.to_string()))?;
+// 아바타가 있으면 저장소에 먼저 올리고 그 URI 를 avatar_uri 로 쓴다
+pub async fn save_profile_form<T: SafeDatabase, P: StorageProvider>(
+    database: &T,
+    storage: &P,
+    wallet: &str,
+    form: ProfileForm,
+    now: u64,
+) -> Result<Profile, ProfileError> {
+    let mut patch = form.patch;
+
+    if let Some(avatar) = form.avatar {
+        validate_avatar(&avatar)?;
+        let uri = storage.store(&avatar.file_name, &avatar.content_type, avatar.bytes).await?;
+        patch.avatar_uri = Some(uri);
+    }
 
-    database.write(&user_profile.user_address, &profile_json, "user_profiles")
-        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+    update_profile(database, wallet, patch, false, now)
+}
+
+// POST /api/profile (multipart). 아바타 파일을 받을 수 있는 유일한 경로다.
+#[utoipa::path(
+    post, path = "/api/profile", tag = "profile",
+    request_body(content = ProfileUpload, content_type = "multipart/form-data"),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Profile),
+        (status = 400, description = "Invalid field or avatar", body = String),
+        (status = 401, description = "Not signed in", body = String),
+        (status = 403, description = "user_address is not the signed-in wallet", body = String),
+        (status = 502, description = "Avatar upload failed", body = String),
+    )
+)]
+pub async fn profile_write<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    mut multipart: Multipart
+) -> Result<Json<Profile>, ProfileError>
+{
+    let form = read_profile_form(&wallet, &mut multipart).await?;
 
-    Ok(StatusCode::OK)
+    let profile = match form.storage.unwrap_or_default() {
+        StorageKind::Ipfs => save_profile_form(&database, ipfs_client(), &wallet, form, now_secs()).await?,
+        StorageKind::Arweave => save_profile_form(&database, arweave_client()?, &wallet, form, now_secs()).await?,
+    };
+    Ok(Json(profile))
+}
+
+// PUT /api/profile (JSON): 프로필 전체를 바꾼다
+#[utoipa::path(
+    put, path = "/api/profile", tag = "profile",
+    request_body = ProfilePatch,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Profile),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 401, description = "Not signed in", body = String),
+    )
+)]
+pub async fn put_profile<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(patch): Json<ProfilePatch>,
+) -> Result<Json<Profile>, ProfileError> {
+    update_profile(&database, &wallet, patch, true, now_secs()).map(Json)
+}
+
+// PATCH /api/profile (JSON): 보낸 필드만 바꾼다
+#[utoipa::path(
+    patch, path = "/api/profile", tag = "profile",
+    request_body = ProfilePatch,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, body = Profile),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 401, description = "Not signed in", body = String),
+    )
+)]
+pub async fn patch_profile<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(patch): Json<ProfilePatch>,
+) -> Result<Json<Profile>, ProfileError> {
+    update_profile(&database, &wallet, patch, false, now_secs()).map(Json)
 }
 
 
+#[utoipa::path(
+    get, path = "/api/profile", tag = "profile",
+    params(AddressQuery),
+    responses(
+        (status = 200, description = "Stored profile, or an empty one for unknown wallets", body = ProfileResponse),
+        (status = 400, description = "Missing address", body = String),
+    )
+)]
 pub async fn get_profile_by_address<T: SafeDatabase>(
     State(database): State<T>,
     Query(query): Query<AddressQuery>,
-) -> Result<Json<UserProfile>, ProfileError> {
+) -> Result<Json<ProfileResponse>, ProfileError> {
     // Validate address
     if query.address.is_empty() {
         return Err(ProfileError::MultipartError("Address is required".to_string()));
     }
 
     // Try to read the profile from the database
-    let profile_data = database.read(&query.address, "user_profiles")
+    let profile = PROFILES.get(&database, query.address.as_bytes())
         .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
 
-    // Check if the profile exists
-    if let Some(data) = profile_data {
-        // Parse the profile from JSON
-        let profile_str = String::from_utf8(data)
-            .map_err(|e| ProfileError::SerializationError(format!("Invalid UTF-8: {}", e)))?;
-
-        let profile: UserProfile = serde_json::from_str(&profile_str)
-            .map_err(|e| ProfileError::SerializationError(format!("Invalid JSON: {}", e)))?;
-
-        // Return the existing profile
-        Ok(Json(
-            profile))
-    } else {
-        // Create a default profile with only the address field
-        let default_profile = UserProfile {
-            user_id: String::new(),
-            user_name: String::new(),
-            user_address: query.address.clone(),
-            github_account: String::new(),
-            x_account: String::new(),
-            tg_account: String::new(),
-            user_bio: String::new(),
-            user_avatar: None,
-            avatar_content_type: None,
-        };
+    let sol_name = sol_name(&database, &query.address, now_secs())
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+
+    // 저장된 적 없는 지갑이면 주소만 채운 빈 프로필을 돌려준다
+    let profile = profile.unwrap_or_else(|| Profile::empty(&query.address));
+    Ok(Json(ProfileResponse { profile, sol_name }))
+}
 
-        // Return the default profile
-        Ok(Json(default_profile))
+// 작성자 목록처럼 여러 지갑을 한 번에 채울 때 쓴다. 프로필은 읽기 트랜잭션 하나로 읽는다.
+pub fn load_profiles<T: SafeDatabase>(database: &T, addresses: &[String], now: u64) -> Result<Vec<ProfileResponse>, ProfileError> {
+    let mut unique: Vec<&str> = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        if address.is_empty() {
+            return Err(ProfileError::ValidationError("addresses must not contain an empty address".to_string()));
+        }
+        if !unique.contains(&address.as_str()) {
+            unique.push(address);
+        }
     }
+    if unique.len() > MAX_BATCH_ADDRESSES {
+        return Err(ProfileError::ValidationError(format!("at most {} addresses per request", MAX_BATCH_ADDRESSES)));
+    }
+
+    let keys: Vec<&[u8]> = unique.iter().map(|address| address.as_bytes()).collect();
+    let profiles = PROFILES.get_many(database, &keys)
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+    let names = sol_names(database, unique.iter().copied(), now)
+        .map_err(|e| ProfileError::DatabaseError(e.to_string()))?;
+
+    Ok(unique.into_iter().zip(profiles).map(|(address, profile)| ProfileResponse {
+        profile: profile.unwrap_or_else(|| Profile::empty(address)),
+        sol_name: names.get(address).cloned().flatten(),
+    }).collect())
+}
+
+#[utoipa::path(
+    post, path = "/api/profiles/batch", tag = "profile",
+    request_body = ProfileBatchRequest,
+    responses(
+        (status = 200, body = ProfileBatchResponse),
+        (status = 400, description = "Empty address or too many addresses", body = String),
+    )
+)]
+pub async fn get_profiles_batch<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<ProfileBatchRequest>,
+) -> Result<Json<ProfileBatchResponse>, ProfileError> {
+    let profiles = load_profiles(&database, &request.addresses, now_secs())?;
+    Ok(Json(ProfileBatchResponse { profiles }))
 }
 
 
@@ -177,12 +504,26 @@ mod tests {
     use axum::body::Body;
     use axum::extract::FromRequest;
     use axum::http::Request;
-    use tempfile::tempdir;
-    use turtle_database::basic_db::InnerDatabase;
-    use turtle_service::parser::profile::UserProfile;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::profile::VerificationMethod;
 
     use axum::extract::Query;
 
+    // 올린 순서대로 ipfs://avatar0, avatar1 ... 을 돌려준다
+    struct MemoryStorage(Mutex<Vec<Vec<u8>>>);
+
+    impl StorageProvider for MemoryStorage {
+        fn kind(&self) -> StorageKind {
+            StorageKind::Ipfs
+        }
+
+        async fn store(&self, _file_name: &str, _content_type: &str, bytes: Vec<u8>) -> Result<String, UploadError> {
+            let mut stored = self.0.lock().unwrap();
+            stored.push(bytes);
+            Ok(format!("ipfs://avatar{}", stored.len() - 1))
+        }
+    }
+
 
     // 테스트용 멀티파트 바디 생성 함수
     fn create_multipart_body(fields: Vec<(&str, &str)>, file_field: Option<(&str, &str, &[u8])>) -> (String, Vec<u8>) {
@@ -215,16 +556,28 @@ mod tests {
         (format!("multipart/form-data; boundary={}", boundary), body)
     }
 
+    async fn multipart(fields: Vec<(&str, &str)>, file_field: Option<(&str, &str, &[u8])>) -> Result<Multipart, Box<dyn std::error::Error>> {
+        let (content_type, body_bytes) = create_multipart_body(fields, file_field);
+
+        // Multipart 추출기 생성
+        let request = Request::builder()
+            .header("content-type", content_type)
+            .body(Body::from(body_bytes))?;
+
+        Ok(Multipart::from_request(request, &()).await?)
+    }
+
+    fn patch(json: &str) -> ProfilePatch {
+        serde_json::from_str(json).unwrap()
+    }
+
     #[tokio::test]
     async fn test_profile_write_success() -> Result<(), Box<dyn std::error::Error>> {
-        // 임시 디렉토리 생성
-        let temp_dir = tempdir()?;
-        let db_path = temp_dir.path().join("test_db");
-
-        // 데이터베이스 초기화
-        let db = InnerDatabase::new(&db_path)?;
+        // 메모리 데이터베이스 초기화
+        let db = MemoryDatabase::new();
+        let storage = MemoryStorage(Mutex::new(Vec::new()));
 
-        // 테스트용 멀티파트 데이터 생성
+        // 옛 필드 이름으로 보낸 폼도 새 스키마로 저장되어야 함
         let fields = vec![
             ("user_id", "test_user"),
             ("user_name", "Test User"),
@@ -237,59 +590,35 @@ mod tests {
 
         // 테스트용 아바타 이미지 데이터
         let avatar_data = [1, 2, 3, 4, 5]; // 간단한 바이너리 데이터
-        let file_field = Some(("user_avatar", "avatar.jpg", &avatar_data[..]));
-
-        let (content_type, body_bytes) = create_multipart_body(fields, file_field);
+        let mut multipart = multipart(fields, Some(("user_avatar", "avatar.jpg", &avatar_data[..]))).await?;
 
-        // Multipart 추출기 생성
-        let request = Request::builder()
-            .header("content-type", content_type)
-            .body(Body::from(body_bytes))?;
-
-        let multipart = Multipart::from_request(request, &()).await?;
-
-        // profile_write 함수 호출
-        let result = profile_write(State(db.clone()), multipart).await?;
-
-        // 결과 확인 - 성공해야 함
-        assert_eq!(result, StatusCode::OK);
+        let form = read_profile_form("0xabcdef123456789", &mut multipart).await?;
+        let result = save_profile_form(&db, &storage, "0xabcdef123456789", form, 100).await?;
 
         // 데이터베이스에서 저장된 프로필 읽기
-        let profile_data = db.read("0xabcdef123456789", "user_profiles")?;
-        assert!(profile_data.is_some(), "Profile data not found in database");
-
-        // 저장된 데이터 검증
-        if let Some(data) = profile_data {
-            let profile_str = String::from_utf8(data)?;
-            let profile: UserProfile = serde_json::from_str(&profile_str)?;
-
-            assert_eq!(profile.user_id, "test_user");
-            assert_eq!(profile.user_name, "Test User");
-            assert_eq!(profile.user_address, "0xabcdef123456789");
-            assert_eq!(profile.github_account, "testuser");
-            assert_eq!(profile.x_account, "@testuser");
-            assert_eq!(profile.tg_account, "@test_user");
-            assert_eq!(profile.user_bio, "This is a test bio");
-
-            // 아바타 검증
-            assert!(profile.user_avatar.is_some());
-            if let Some(avatar) = &profile.user_avatar {
-                assert_eq!(avatar, &avatar_data);
-            }
-            assert_eq!(profile.avatar_content_type, Some("image/jpeg".to_string()));
-        }
+        let profile = PROFILES.get(&db, b"0xabcdef123456789")?.expect("Profile data not found in database");
+        assert_eq!(profile, result);
+
+        assert_eq!(profile.wallet, "0xabcdef123456789");
+        assert_eq!(profile.display_name, "Test User");
+        assert_eq!(profile.bio, "This is a test bio");
+        assert_eq!(profile.links.github.as_deref(), Some("testuser"));
+        assert_eq!(profile.links.x.as_deref(), Some("testuser"));
+        assert_eq!(profile.links.telegram.as_deref(), Some("test_user"));
+        assert_eq!(profile.links.website, None);
+        assert_eq!((profile.created_at, profile.updated_at), (100, 100));
+
+        // 아바타는 저장소에 올라가고 URI 만 남는다
+        assert_eq!(profile.avatar_uri.as_deref(), Some("ipfs://avatar0"));
+        assert_eq!(*storage.0.lock().unwrap(), vec![avatar_data.to_vec()]);
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_profile_write_missing_address() -> Result<(), Box<dyn std::error::Error>> {
-        // 임시 디렉토리 생성
-        let temp_dir = tempdir()?;
-        let db_path = temp_dir.path().join("test_db");
-
-        // 데이터베이스 초기화
-        let db = InnerDatabase::new(&db_path)?;
+        // 메모리 데이터베이스 초기화
+        let db = MemoryDatabase::new();
 
         // user_address가 없는 멀티파트 데이터 생성
         let fields = vec![
@@ -298,37 +627,45 @@ mod tests {
             // user_address 필드 생략
             ("github_account", "testuser"),
         ];
+        let multipart = multipart(fields, None).await?;
 
-        let (content_type, body_bytes) = create_multipart_body(fields, None);
+        // 주소가 없으면 세션의 지갑 주소로 저장되어야 함
+        let result = profile_write(State(db.clone()), AuthedWallet("wallet_from_session".to_string()), multipart).await?;
+        assert_eq!(result.0.wallet, "wallet_from_session");
 
-        // Multipart 추출기 생성
-        let request = Request::builder()
-            .header("content-type", content_type)
-            .body(Body::from(body_bytes))?;
+        let profile = PROFILES.get(&db, b"wallet_from_session")?.unwrap();
+        assert_eq!(profile.wallet, "wallet_from_session");
+        assert_eq!(profile.display_name, "Test User");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_profile_write_other_address_forbidden() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
 
-        let multipart = Multipart::from_request(request, &()).await?;
+        // 다른 지갑의 주소로 쓰려는 요청
+        let fields = vec![
+            ("user_name", "Mallory"),
+            ("user_address", "victim_wallet"),
+        ];
+        let multipart = multipart(fields, None).await?;
 
         // profile_write 함수 호출 - 여기서는 에러를 기대하므로 ? 연산자를 사용하지 않음
-        let result = profile_write(State(db.clone()), multipart).await;
+        let result = profile_write(State(db.clone()), AuthedWallet("mallory_wallet".to_string()), multipart).await;
 
-        // 결과 확인 - 에러가 발생해야 함
-        match result {
-            Err(ProfileError::MultipartError(msg)) => {
-                assert_eq!(msg, "User ID is required");
-                Ok(())
-            },
-            _ => Err("Expected MultipartError with 'User ID is required' message".into()),
-        }
+        // 결과 확인 - 에러가 발생하고 아무것도 저장되지 않아야 함
+        assert!(matches!(result, Err(ProfileError::Forbidden(_))));
+        assert!(db.read("victim_wallet", "user_profiles")?.is_none());
+        assert!(db.read("mallory_wallet", "user_profiles")?.is_none());
+
+        Ok(())
     }
 
     #[tokio::test]
     async fn test_profile_write_empty_fields() -> Result<(), Box<dyn std::error::Error>> {
-        // 임시 디렉토리 생성
-        let temp_dir = tempdir()?;
-        let db_path = temp_dir.path().join("test_db");
-
-        // 데이터베이스 초기화
-        let db = InnerDatabase::new(&db_path)?;
+        // 메모리 데이터베이스 초기화
+        let db = MemoryDatabase::new();
 
         // 일부 필드가 빈 멀티파트 데이터 생성
         let fields = vec![
@@ -340,70 +677,107 @@ mod tests {
             ("tg_account", ""),
             ("user_bio", ""),
         ];
+        let multipart = multipart(fields, None).await?;
 
-        let (content_type, body_bytes) = create_multipart_body(fields, None);
+        // profile_write 함수 호출 - 성공해야 함
+        let result = profile_write(State(db.clone()), AuthedWallet("0xabcdef123456789".to_string()), multipart).await?;
+        assert_eq!(result.0.updated_at, result.0.created_at);
 
-        // Multipart 추출기 생성
-        let request = Request::builder()
-            .header("content-type", content_type)
-            .body(Body::from(body_bytes))?;
-
-        let multipart = Multipart::from_request(request, &()).await?;
+        // 저장된 데이터 검증
+        let profile = PROFILES.get(&db, b"0xabcdef123456789")?.expect("Profile data not found in database");
+        assert_eq!(profile.wallet, "0xabcdef123456789");
+        assert_eq!(profile.display_name, "");
+        assert_eq!(profile.bio, "");
+        assert_eq!(profile.links, SocialLinks::default());
+        assert!(profile.avatar_uri.is_none());
 
-        // profile_write 함수 호출
-        let result = profile_write(State(db.clone()), multipart).await?;
+        Ok(())
+    }
 
-        // 결과 확인 - 성공해야 함 (user_address가 있으므로)
-        assert_eq!(result, StatusCode::OK);
+    #[test]
+    fn test_put_replaces_and_patch_merges() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+
+        let put = patch(r#"{"display_name":" Turtle ","bio":"slow\nsteady","avatar_uri":"ipfs://cid","links":{"x":"@turtle","website":"https://turtle.dev"}}"#);
+        let profile = update_profile(&db, "wallet", put, true, 100)?;
+        assert_eq!(profile.display_name, "Turtle");
+        assert_eq!(profile.bio, "slow\nsteady");
+        assert_eq!(profile.links.x.as_deref(), Some("turtle"));
+
+        // PATCH: 보낸 필드만 바뀌고, 빈 문자열은 선택 필드를 지운다
+        let profile = update_profile(&db, "wallet", patch(r#"{"bio":"fast","links":{"website":""}}"#), false, 200)?;
+        assert_eq!(profile.display_name, "Turtle");
+        assert_eq!(profile.bio, "fast");
+        assert_eq!(profile.avatar_uri.as_deref(), Some("ipfs://cid"));
+        assert_eq!(profile.links.x.as_deref(), Some("turtle"));
+        assert_eq!(profile.links.website, None);
+        assert_eq!((profile.created_at, profile.updated_at), (100, 200));
+
+        // PUT: 빠진 필드는 비워지지만 created_at 은 유지
+        let profile = update_profile(&db, "wallet", patch(r#"{"display_name":"Shell"}"#), true, 300)?;
+        assert_eq!(profile, Profile {
+            display_name: "Shell".to_string(),
+            created_at: 100,
+            updated_at: 300,
+            ..Profile::empty("wallet")
+        });
+        assert_eq!(PROFILES.get(&db, b"wallet")?, Some(profile));
 
-        // 데이터베이스에서 저장된 프로필 읽기
-        let profile_data = db.read("0xabcdef123456789", "user_profiles")?;
-        assert!(profile_data.is_some(), "Profile data not found in database");
+        Ok(())
+    }
 
-        // 저장된 데이터 검증
-        if let Some(data) = profile_data {
-            let profile_str = String::from_utf8(data)?;
-            let profile: UserProfile = serde_json::from_str(&profile_str)?;
-
-            assert_eq!(profile.user_id, "");
-            assert_eq!(profile.user_name, "");
-            assert_eq!(profile.user_address, "0xabcdef123456789");
-            assert_eq!(profile.github_account, "");
-            assert_eq!(profile.x_account, "");
-            assert_eq!(profile.tg_account, "");
-            assert_eq!(profile.user_bio, "");
-            assert!(profile.user_avatar.is_none());
+    #[tokio::test]
+    async fn test_profile_validation() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+
+        let long_name = format!(r#"{{"display_name":"{}"}}"#, "a".repeat(MAX_DISPLAY_NAME_CHARS + 1));
+        let invalid = [
+            long_name.as_str(),
+            r#"{"display_name":"bad\u0007name"}"#,
+            r#"{"avatar_uri":"javascript:alert(1)"}"#,
+            r#"{"links":{"github":"not a handle"}}"#,
+            r#"{"links":{"telegram":"@abc"}}"#,
+            r#"{"links":{"website":"ftp://turtle.dev"}}"#,
+        ];
+        for json in invalid {
+            let result = update_profile(&db, "wallet", patch(json), false, 100);
+            assert!(matches!(result, Err(ProfileError::ValidationError(_))), "{}", json);
         }
+        assert!(serde_json::from_str::<ProfilePatch>(r#"{"wallet":"victim"}"#).is_err());
+        assert!(PROFILES.get(&db, b"wallet")?.is_none());
+
+        // 이미지가 아닌 아바타는 저장소에 올리지 않는다
+        let storage = MemoryStorage(Mutex::new(Vec::new()));
+        let form = ProfileForm {
+            patch: ProfilePatch::default(),
+            avatar: Some(Image { file_name: "a.html".to_string(), content_type: "text/html".to_string(), bytes: vec![1] }),
+            storage: None,
+        };
+        let result = save_profile_form(&db, &storage, "wallet", form, 100).await;
+        assert!(matches!(result, Err(ProfileError::ValidationError(_))));
+        assert!(storage.0.lock().unwrap().is_empty());
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_get_profile_by_address_existing() -> Result<(), Box<dyn std::error::Error>> {
-        // Create temporary directory
-        let temp_dir = tempdir()?;
-        let db_path = temp_dir.path().join("test_db");
-
-        // Initialize database
-        let db = InnerDatabase::new(&db_path)?;
+        // Initialize in-memory database
+        let db = MemoryDatabase::new();
 
         // Create a test profile
         let test_address = "0xabcdef123456789";
-        let test_profile = UserProfile {
-            user_id: "test_user".to_string(),
-            user_name: "Test User".to_string(),
-            user_address: test_address.to_string(),
-            github_account: "testuser".to_string(),
-            x_account: "@testuser".to_string(),
-            tg_account: "@test_user".to_string(),
-            user_bio: "This is a test bio".to_string(),
-            user_avatar: None,
-            avatar_content_type: None,
+        let test_profile = Profile {
+            display_name: "Test User".to_string(),
+            bio: "This is a test bio".to_string(),
+            links: SocialLinks { github: Some("testuser".to_string()), ..SocialLinks::default() },
+            created_at: 1,
+            updated_at: 2,
+            ..Profile::empty(test_address)
         };
 
         // Save the profile to the database
-        let profile_json = serde_json::to_string(&test_profile)?;
-        db.write(test_address, &profile_json, "user_profiles")?;
+        PROFILES.put(&db, test_address.as_bytes(), &test_profile)?;
 
         // Create query parameters
         let query = AddressQuery {
@@ -414,27 +788,15 @@ mod tests {
         let result = get_profile_by_address(State(db), Query(query)).await?;
 
         // Check the result
-        let response = result.0;
-        assert!(response.exists);
-        assert_eq!(response.profile.user_id, "test_user");
-        assert_eq!(response.profile.user_name, "Test User");
-        assert_eq!(response.profile.user_address, test_address);
-        assert_eq!(response.profile.github_account, "testuser");
-        assert_eq!(response.profile.x_account, "@testuser");
-        assert_eq!(response.profile.tg_account, "@test_user");
-        assert_eq!(response.profile.user_bio, "This is a test bio");
+        assert_eq!(result.0, ProfileResponse { profile: test_profile, sol_name: None });
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_get_profile_by_address_nonexistent() -> Result<(), Box<dyn std::error::Error>> {
-        // Create temporary directory
-        let temp_dir = tempdir()?;
-        let db_path = temp_dir.path().join("test_db");
-
-        // Initialize database
-        let db = InnerDatabase::new(&db_path)?;
+        // Initialize in-memory database
+        let db = MemoryDatabase::new();
 
         // Create query parameters for a non-existent address
         let test_address = "0xnonexistent123";
@@ -445,29 +807,37 @@ mod tests {
         // Call get_profile_by_address function
         let result = get_profile_by_address(State(db), Query(query)).await?;
 
-        // Check the result
-        let response = result.0;
-        assert!(!response.exists);
-        assert_eq!(response.profile.user_address, test_address);
-        assert!(response.profile.user_id.is_empty());
-        assert!(response.profile.user_name.is_empty());
-        assert!(response.profile.github_account.is_empty());
-        assert!(response.profile.x_account.is_empty());
-        assert!(response.profile.tg_account.is_empty());
-        assert!(response.profile.user_bio.is_empty());
-        assert!(response.profile.user_avatar.is_none());
+        // Check the result - a default profile carrying only the address
+        assert_eq!(result.0.profile, Profile::empty(test_address));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_profiles_in_request_order() -> Result<(), Box<dyn std::error::Error>> {
+        let db = MemoryDatabase::new();
+        update_profile(&db, "bob", patch(r#"{"display_name":"Bob"}"#), true, 100)?;
+        mark_verified(&db, "bob", WalletVerification { method: VerificationMethod::SignedMessage, proof: "sig".to_string(), verified_at: 110 })?;
+
+        let addresses = ["alice", "bob", "alice"].map(str::to_string);
+        let profiles = load_profiles(&db, &addresses, 200)?;
+        let wallets: Vec<&str> = profiles.iter().map(|item| item.profile.wallet.as_str()).collect();
+        assert_eq!(wallets, vec!["alice", "bob"]);
+        assert_eq!(profiles[0].profile, Profile::empty("alice"));
+        assert_eq!(profiles[1].profile.display_name, "Bob");
+        assert!(profiles[1].profile.verified);
+
+        let too_many: Vec<String> = (0..=MAX_BATCH_ADDRESSES).map(|i| format!("wallet{}", i)).collect();
+        assert!(matches!(load_profiles(&db, &too_many, 200), Err(ProfileError::ValidationError(_))));
+        assert!(matches!(load_profiles(&db, &["".to_string()], 200), Err(ProfileError::ValidationError(_))));
 
         Ok(())
     }
 
     #[tokio::test]
     async fn test_get_profile_by_address_empty_address() -> Result<(), Box<dyn std::error::Error>> {
-        // Create temporary directory
-        let temp_dir = tempdir()?;
-        let db_path = temp_dir.path().join("test_db");
-
-        // Initialize database
-        let db = InnerDatabase::new(&db_path)?;
+        // Initialize in-memory database
+        let db = MemoryDatabase::new();
 
         // Create query parameters with an empty address
         let query = AddressQuery {
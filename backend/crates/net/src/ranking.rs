@@ -0,0 +1,314 @@
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::error::DbResult;
+use turtle_database::keys::{KeyBuilder, KeyReader};
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::content::{ContentRecord, ContentStats};
+use crate::content::CONTENT_RECORDS;
+use crate::earnings::{claimed_rounds, ClaimedRound};
+use crate::scheduler::Scheduler;
+use crate::sync::CHAIN_CONTENTS;
+use crate::upload::content_hash;
+
+// id -> ContentStats
+pub const CONTENT_STATS: TypedTable<ContentStats> = TypedTable::new("content_stats");
+// (scope, score, id) -> (). scope 는 DAO 공개키이고, 전체 피드는 ALL_DAOS 아래에 한 번 더 쓴다.
+// 저장소 키 순서가 곧 점수 순서라서 목록 API 가 커서로 그대로 넘길 수 있다.
+pub const CONTENT_TRENDING: TypedTable<()> = TypedTable::new("content_trending");
+pub const CONTENT_TOP: TypedTable<()> = TypedTable::new("content_top");
+pub const RANKING_INTERVAL: Duration = Duration::from_secs(60);
+
+// 길이가 붙는 키라서 빈 문자열은 어떤 DAO 와도 겹치지 않는다
+const ALL_DAOS: &str = "";
+// trending = votes / (경과 시간 + 2)^GRAVITY. 클수록 오래된 글이 빨리 내려간다.
+const TRENDING_GRAVITY: f64 = 1.5;
+// 정수 키로 쓰려고 곱하는 값 (점수 1 은 백만분의 1 표)
+const TRENDING_SCALE: f64 = 1_000_000.0;
+const SECONDS_PER_HOUR: f64 = 3_600.0;
+
+#[derive(Debug)]
+pub enum RankingError {
+    DatabaseError(String),
+}
+
+impl fmt::Display for RankingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RankingError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for RankingError {}
+
+fn db_err(e: impl fmt::Display) -> RankingError {
+    RankingError::DatabaseError(e.to_string())
+}
+
+pub fn rank_key(scope: &str, score: u64, id: u64) -> Vec<u8> {
+    KeyBuilder::new().str(scope).u64(score).u64(id).build()
+}
+
+// 랭킹 행의 (점수, 콘텐츠 ID)
+pub fn rank_entry(key: &[u8]) -> DbResult<(u64, u64)> {
+    let mut reader = KeyReader::new(key);
+    reader.str()?;
+    Ok((reader.u64()?, reader.u64()?))
+}
+
+// dao 가 없으면 전체 피드
+pub fn rank_range(dao: Option<&str>) -> KeyRange {
+    KeyRange::prefix(KeyBuilder::new().str(dao.unwrap_or(ALL_DAOS)).build())
+}
+
+// 프로그램은 콘텐츠별 팁을 남기지 않아서 (예치는 DAO 상금 풀로 간다) 투표 수에만 시간 감쇠를 건다
+pub fn trending_score(votes: u64, timestamp: u64, now: u64) -> u64 {
+    if votes == 0 {
+        return 0;
+    }
+    let age_hours = now.saturating_sub(timestamp) as f64 / SECONDS_PER_HOUR;
+    (votes as f64 * TRENDING_SCALE / (age_hours + 2.0).powf(TRENDING_GRAVITY)) as u64
+}
+
+// (dao, content_hash) -> 지금 체인에 있는 투표 수. 레코드의 content_hash 는 텍스트와 이미지 참조로 만든다.
+fn chain_votes<T: SafeDatabase>(database: &T) -> Result<HashMap<(String, String), u64>, RankingError> {
+    let mut votes = HashMap::new();
+    for (_, content) in CHAIN_CONTENTS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let hash = content_hash(&content.text, &content.image_uri);
+        let count = votes.entry((content.dao, hash)).or_insert(0);
+        *count = (*count).max(content.vote_count);
+    }
+    Ok(votes)
+}
+
+// Claim 이벤트에는 우승자 지갑만 있으므로, 그 라운드 (지난 Claim 이후 ~ 이번 Claim) 에 우승자가 올린 글 중
+// 투표를 가장 많이 받은 것을 우승 콘텐츠로 본다. block_time 이 없는 Claim 은 라운드 경계를 알 수 없어 건너뛴다.
+fn round_rewards(records: &[ContentRecord], votes: &HashMap<u64, u64>, rounds: &[ClaimedRound]) -> HashMap<u64, u64> {
+    let mut rewards = HashMap::new();
+    let mut round_start: HashMap<&str, u64> = HashMap::new();
+    for round in rounds {
+        let Some(claimed_at) = round.claim.block_time else {
+            continue;
+        };
+        let start = round_start.insert(&round.claim.dao, claimed_at).unwrap_or(0);
+        let winner = records.iter()
+            .filter(|record| record.dao == round.claim.dao && record.author == round.winner)
+            .filter(|record| record.timestamp > start && record.timestamp <= claimed_at)
+            .max_by_key(|record| (votes.get(&record.id).copied().unwrap_or(0), record.id));
+        if let Some(record) = winner {
+            *rewards.entry(record.id).or_insert(0) += round.quality_share;
+        }
+    }
+    rewards
+}
+
+// desired 와 다른 행만 지우고 쓴다
+fn sync_index<T: SafeDatabase>(
+    database: &T,
+    table: TypedTable<()>,
+    desired: &BTreeSet<Vec<u8>>,
+    ops: &mut Vec<KvOp>,
+) -> Result<(), RankingError> {
+    let mut existing = BTreeSet::new();
+    for (key, _) in table.scan(database, &KeyRange::all()).map_err(db_err)? {
+        if !desired.contains(&key) {
+            ops.push(table.delete_op(&key));
+        }
+        existing.insert(key);
+    }
+    for key in desired.difference(&existing) {
+        ops.push(table.put_op(key, &()).map_err(db_err)?);
+    }
+    Ok(())
+}
+
+// 모든 콘텐츠의 집계와 trending/top 랭킹을 다시 계산해서 한 트랜잭션으로 쓰고, 랭킹에 올린 콘텐츠 수를 돌려준다
+pub fn compute_rankings<T: SafeDatabase>(database: &T, now: u64) -> Result<usize, RankingError> {
+    let records: Vec<ContentRecord> = CONTENT_RECORDS.scan(database, &KeyRange::all())
+        .map_err(db_err)?
+        .into_iter()
+        .map(|(_, record)| record)
+        .collect();
+    let previous: HashMap<u64, ContentStats> = CONTENT_STATS.scan(database, &KeyRange::all())
+        .map_err(db_err)?
+        .into_iter()
+        .filter_map(|(key, stats)| KeyReader::new(&key).u64().ok().map(|id| (id, stats)))
+        .collect();
+
+    // 라운드가 끝나 체인에서 사라진 글은 마지막으로 본 투표 수를 그대로 쓴다
+    let on_chain = chain_votes(database)?;
+    let votes: HashMap<u64, u64> = records.iter().map(|record| {
+        let seen = previous.get(&record.id).map(|stats| stats.votes).unwrap_or(0);
+        let current = on_chain.get(&(record.dao.clone(), record.content_hash.clone())).copied().unwrap_or(0);
+        (record.id, seen.max(current))
+    }).collect();
+    let rounds = claimed_rounds(database).map_err(db_err)?;
+    let rewards = round_rewards(&records, &votes, &rounds);
+
+    let mut ops = Vec::new();
+    let mut trending = BTreeSet::new();
+    let mut top = BTreeSet::new();
+    for record in &records {
+        let votes = votes[&record.id];
+        let stats = ContentStats {
+            votes,
+            reward: rewards.get(&record.id).copied().unwrap_or(0),
+            trending: trending_score(votes, record.timestamp, now),
+            ranked_at: now,
+        };
+        for scope in [ALL_DAOS, record.dao.as_str()] {
+            trending.insert(rank_key(scope, stats.trending, record.id));
+            top.insert(rank_key(scope, stats.reward, record.id));
+        }
+        ops.push(CONTENT_STATS.put_op(&KeyBuilder::new().u64(record.id).build(), &stats).map_err(db_err)?);
+    }
+    sync_index(database, CONTENT_TRENDING, &trending, &mut ops)?;
+    sync_index(database, CONTENT_TOP, &top, &mut ops)?;
+
+    database.txn(&ops).map_err(db_err)?;
+    Ok(records.len())
+}
+
+// trending 점수는 시간이 지나며 내려가므로 주기적으로 다시 계산한다
+pub fn register_ranking_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>) {
+    scheduler.every("ranking", RANKING_INTERVAL, RANKING_INTERVAL / 6, |database| async move {
+        compute_rankings(&database, now_secs()).map(|_| ()).map_err(|e| e.to_string())
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::Json;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::{ChainContent, DaoSnapshot};
+    use turtle_service::parser::event::{EventRecord, TurtleEvent};
+    use crate::content::list_contents;
+    use crate::indexer::{event_key, CLAIM_EVENTS, DEPOSIT_EVENTS};
+    use crate::pagination::ListQuery;
+    use crate::sync::DAO_SNAPSHOTS;
+
+    const HOUR: u64 = 3_600;
+    const NOW: u64 = 1_000 * HOUR;
+
+    fn record(id: u64, dao: &str, author: &str, text: &str, age_hours: u64) -> ContentRecord {
+        ContentRecord {
+            id,
+            dao: dao.to_string(),
+            author: author.to_string(),
+            content_hash: content_hash(text, ""),
+            content_uri: format!("ipfs://{}", text),
+            timestamp: NOW - age_hours * HOUR,
+        }
+    }
+
+    fn on_chain(database: &MemoryDatabase, index: u64, record: &ContentRecord, text: &str, vote_count: u64) {
+        let content = ChainContent {
+            dao: record.dao.clone(),
+            index,
+            author: record.author.clone(),
+            text: text.to_string(),
+            image_uri: String::new(),
+            timestamp: record.timestamp,
+            vote_count,
+            round: 0,
+        };
+        CHAIN_CONTENTS.put(database, &KeyBuilder::new().str(&record.dao).u64(index).build(), &content).unwrap();
+    }
+
+    fn event(database: &MemoryDatabase, id: u64, actor: &str, event: TurtleEvent) {
+        let table = match event {
+            TurtleEvent::Deposit { .. } => DEPOSIT_EVENTS,
+            _ => CLAIM_EVENTS,
+        };
+        let record = EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: Some(NOW),
+            dao: "dao1".to_string(),
+            actor: actor.to_string(),
+            event,
+        };
+        table.put(database, &event_key(id), &record).unwrap();
+    }
+
+    fn snapshot() -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: "dao1".to_string(),
+            dao_name: "turtles".to_string(),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 50,
+            timeout_timestamp: 0,
+            total_deposit: 0,
+            depositor_count: 0,
+            content_count: 0,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    async fn feed(database: &MemoryDatabase, query: &str) -> Vec<u64> {
+        let query = ListQuery::from_uri(&format!("/api/contents?{}", query).parse().unwrap()).unwrap();
+        let Json(response) = list_contents(State(database.clone()), query).await.unwrap();
+        let response = serde_json::to_value(&response).unwrap();
+        response["contents"].as_array().unwrap().iter().map(|item| item["id"].as_u64().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_trending_score_decays_with_age() {
+        assert_eq!(trending_score(0, NOW, NOW), 0);
+        assert!(trending_score(10, NOW, NOW) > trending_score(10, NOW - 24 * HOUR, NOW));
+        // 하루 지난 글이 표를 훨씬 많이 받았으면 새 글보다 위에 있다
+        assert!(trending_score(100, NOW - 24 * HOUR, NOW) > trending_score(2, NOW, NOW));
+    }
+
+    #[tokio::test]
+    async fn test_rankings_feed_list_sorts() {
+        let database = MemoryDatabase::new();
+        DAO_SNAPSHOTS.put(&database, b"dao1", &snapshot()).unwrap();
+        let records = [
+            record(1, "dao1", "alice", "old but loved", 48),
+            record(2, "dao1", "bob", "fresh", 1),
+            record(3, "dao1", "alice", "ignored", 2),
+            record(4, "dao2", "carol", "elsewhere", 5),
+        ];
+        for record in &records {
+            CONTENT_RECORDS.put(&database, &KeyBuilder::new().u64(record.id).build(), record).unwrap();
+        }
+        on_chain(&database, 0, &records[0], "old but loved", 9);
+        on_chain(&database, 1, &records[1], "fresh", 3);
+        on_chain(&database, 2, &records[2], "ignored", 0);
+        on_chain(&database, 0, &records[3], "elsewhere", 1);
+
+        assert_eq!(compute_rankings(&database, NOW).unwrap(), 4);
+        // 이틀 된 9표보다 한 시간 된 3표가 위에 있다
+        assert_eq!(feed(&database, "sort=trending").await, [2, 4, 1, 3]);
+        assert_eq!(feed(&database, "sort=trending&filter[dao]=dao1&filter[author]=alice").await, [1, 3]);
+        assert_eq!(feed(&database, "sort=new&limit=2").await, [4, 3]);
+
+        // 라운드가 끝나면 체인에서 글이 지워지지만 투표 수는 남고, 우승자의 가장 많이 받은 글이 보상을 가져간다
+        event(&database, 1, "bob", TurtleEvent::Deposit { amount: 4_000 });
+        event(&database, 2, "keeper", TurtleEvent::Claim { winner: Some("alice".to_string()) });
+        CHAIN_CONTENTS.delete(&database, &KeyBuilder::new().str("dao1").u64(0).build()).unwrap();
+        compute_rankings(&database, NOW + HOUR).unwrap();
+
+        let stats = CONTENT_STATS.get(&database, &KeyBuilder::new().u64(1).build()).unwrap().unwrap();
+        assert_eq!((stats.votes, stats.reward), (9, 200));
+        assert_eq!(feed(&database, "sort=top&limit=1").await, [1]);
+        assert_eq!(feed(&database, "sort=top&filter[dao]=dao2").await, [4]);
+    }
+}
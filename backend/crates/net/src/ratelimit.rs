@@ -0,0 +1,231 @@
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::TtlTable;
+use crate::session::{bearer_token, verify_token, TokenKind};
+use crate::error::ApiError;
+
+// 토큰 버킷 상태는 TTL 테이블에 둬서 재시작해도 한도가 유지된다 (ttl::TTL_TABLES)
+pub const RATE_LIMIT_TABLE: &str = "rate_limits";
+
+// 버킷 읽기-수정-쓰기를 직렬화 (동시에 들어온 요청이 같은 토큰을 두 번 쓰지 않도록)
+static BUCKET_LOCK: Mutex<()> = Mutex::new(());
+
+// 라우트별 한도. burst 개까지 한 번에 쓸 수 있고, per 동안 burst 개가 다시 찬다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub route: &'static str,
+    pub burst: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub const fn new(route: &'static str, burst: u32, per: Duration) -> Self {
+        Self { route, burst, per }
+    }
+
+    fn tokens_per_ms(&self) -> f64 {
+        self.burst as f64 / self.per.as_millis().max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bucket {
+    pub tokens: f64,
+    pub updated_at_ms: u64,
+}
+
+#[derive(Debug)]
+pub enum RateLimitError {
+    DatabaseError(String),
+    TooManyRequests { retry_after: u64 },
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            RateLimitError::TooManyRequests { retry_after } => write!(f, "Rate limit exceeded, retry after {}s", retry_after),
+        }
+    }
+}
+
+impl StdError for RateLimitError {}
+
+impl IntoResponse for RateLimitError {
+    fn into_response(self) -> Response {
+        match self {
+            RateLimitError::DatabaseError(msg) => ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, msg).into_response(),
+            RateLimitError::TooManyRequests { retry_after } => {
+                ApiError::new(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").with_retry_after(retry_after).into_response()
+            },
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+// 버킷에서 토큰 하나를 꺼낸다. 모자라면 다시 한 개가 찰 때까지의 초를 돌려준다.
+pub fn take_token(bucket: Option<Bucket>, limit: &RateLimit, now_ms: u64) -> (Bucket, Result<(), u64>) {
+    let burst = limit.burst as f64;
+    let tokens = match bucket {
+        Some(bucket) => {
+            let elapsed = now_ms.saturating_sub(bucket.updated_at_ms) as f64;
+            (bucket.tokens + elapsed * limit.tokens_per_ms()).min(burst)
+        }
+        None => burst,
+    };
+
+    if tokens >= 1.0 {
+        (Bucket { tokens: tokens - 1.0, updated_at_ms: now_ms }, Ok(()))
+    } else {
+        let wait_ms = (1.0 - tokens) / limit.tokens_per_ms();
+        let retry_after = (wait_ms / 1000.0).ceil().max(1.0) as u64;
+        (Bucket { tokens, updated_at_ms: now_ms }, Err(retry_after))
+    }
+}
+
+pub fn check_rate_limit<T: SafeDatabase>(database: &T, limit: &RateLimit, subject: &str, now_ms: u64) -> Result<(), RateLimitError> {
+    let table = TtlTable::new(database.clone(), RATE_LIMIT_TABLE);
+    let key = format!("{}:{}", limit.route, subject);
+    let _guard = BUCKET_LOCK.lock().unwrap();
+
+    let bucket = table.get_at(&key, now_ms / 1000)
+        .map_err(|e| RateLimitError::DatabaseError(e.to_string()))?
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+
+    let (bucket, result) = take_token(bucket, limit, now_ms);
+
+    // per 가 지나면 버킷은 어차피 가득 차므로 그때까지만 저장한다
+    let bytes = serde_json::to_vec(&bucket)
+        .map_err(|e| RateLimitError::DatabaseError(e.to_string()))?;
+    let expires_at = (now_ms / 1000).saturating_add(limit.per.as_secs()).saturating_add(1);
+    table.put_until(&key, &bytes, expires_at)
+        .map_err(|e| RateLimitError::DatabaseError(e.to_string()))?;
+
+    result.map_err(|retry_after| RateLimitError::TooManyRequests { retry_after })
+}
+
+// 요청을 보낸 쪽. 서버는 into_make_service_with_connect_info 로 띄워야 IP 가 잡힌다.
+fn subjects<T: SafeDatabase>(database: &T, request: &Request) -> Vec<String> {
+    let ip = request.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut subjects = vec![format!("ip:{}", ip)];
+
+    // 로그인한 요청은 지갑 단위로도 센다 (IP 를 바꿔 가며 보내는 것을 막기 위해)
+    let wallet = bearer_token(request.headers())
+        .ok()
+        .and_then(|token| verify_token(database, token, TokenKind::Access).ok());
+    if let Some(claims) = wallet {
+        subjects.push(format!("wallet:{}", claims.sub));
+    }
+
+    subjects
+}
+
+pub async fn enforce_rate_limit<T: SafeDatabase>(
+    State((database, limit)): State<(T, RateLimit)>,
+    request: Request,
+    next: Next,
+) -> Result<Response, RateLimitError> {
+    let now_ms = now_millis();
+    for subject in subjects(&database, &request) {
+        check_rate_limit(&database, &limit, &subject, now_ms)?;
+    }
+    Ok(next.run(request).await)
+}
+
+// 컴포넌트 라우터의 모든 라우트에 limit 을 건다
+pub fn rate_limited<T: SafeDatabase>(component: (String, Router<T>), database: &T, limit: RateLimit) -> (String, Router<T>) {
+    let (path, router) = component;
+    let router = router.route_layer(middleware::from_fn_with_state((database.clone(), limit), enforce_rate_limit::<T>));
+    (path, router)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header;
+    use axum::body::Body;
+    use axum::routing::post;
+    use tower::ServiceExt;
+    use turtle_database::memory_db::MemoryDatabase;
+    use crate::session::issue_session;
+
+    const LIMIT: RateLimit = RateLimit::new("test", 2, Duration::from_secs(10));
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let (bucket, result) = take_token(None, &LIMIT, 0);
+        assert_eq!(result, Ok(()));
+        let (bucket, result) = take_token(Some(bucket), &LIMIT, 0);
+        assert_eq!(result, Ok(()));
+
+        // 비었으면 토큰 하나가 차는 5초를 기다려야 한다
+        let (bucket, result) = take_token(Some(bucket), &LIMIT, 1_000);
+        assert_eq!(result, Err(4));
+        let (_, result) = take_token(Some(bucket), &LIMIT, 5_000);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_buckets_persist_per_subject() {
+        let database = MemoryDatabase::new();
+
+        assert!(check_rate_limit(&database, &LIMIT, "ip:1.1.1.1", 0).is_ok());
+        assert!(check_rate_limit(&database, &LIMIT, "ip:1.1.1.1", 0).is_ok());
+        assert!(matches!(
+            check_rate_limit(&database, &LIMIT, "ip:1.1.1.1", 0),
+            Err(RateLimitError::TooManyRequests { retry_after: 5 })
+        ));
+        assert!(check_rate_limit(&database, &LIMIT, "ip:2.2.2.2", 0).is_ok());
+        assert!(check_rate_limit(&database, &LIMIT, "ip:1.1.1.1", 5_000).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_layer_returns_429_with_retry_after() {
+        let database = MemoryDatabase::new();
+        let component = ("/write".to_string(), Router::new().route("/write", post(|| async { "ok" })));
+        let (_, router) = rate_limited(component, &database, LIMIT);
+        let app = router.with_state(database);
+
+        let send = |ip: [u8; 4], token: Option<&str>| {
+            let mut request = axum::http::Request::builder().method("POST").uri("/write");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            let mut request = request.body(Body::empty()).unwrap();
+            request.extensions_mut().insert(ConnectInfo(SocketAddr::from((ip, 1234))));
+            app.clone().oneshot(request)
+        };
+
+        assert_eq!(send([1, 1, 1, 1], None).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send([1, 1, 1, 1], None).await.unwrap().status(), StatusCode::OK);
+        let limited = send([1, 1, 1, 1], None).await.unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(limited.headers().contains_key(header::RETRY_AFTER));
+
+        // 같은 지갑은 IP 를 바꿔도 같은 버킷을 쓴다
+        let token = issue_session("alice").unwrap().token;
+        assert_eq!(send([3, 3, 3, 3], Some(&token)).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send([4, 4, 4, 4], Some(&token)).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send([5, 5, 5, 5], Some(&token)).await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,451 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use sol::instruction::{ComputeBudgetInstruction, TurtleInstruction, COMPUTE_BUDGET_PROGRAM_ID};
+use sol::state::Pubkey;
+use sol::transaction::Transaction;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::{now_secs, TtlTable};
+use crate::fees::{DEFAULT_COMPUTE_UNIT_LIMIT, MAX_PRIORITY_FEE};
+use crate::rpc::RpcClient;
+use crate::tx::{tx_config, TxError};
+use crate::error::ApiError;
+
+// 수수료를 대신 내 줄 relayer 키. 비어 있으면 relay 는 꺼진다.
+// solana-keygen 파일 형식(64바이트 JSON 배열) 또는 base58 로 인코딩한 64바이트 keypair.
+pub const RELAYER_KEYPAIR_ENV: &str = "TURTLE_RELAYER_KEYPAIR";
+pub const RELAY_DAILY_CAP_ENV: &str = "TURTLE_RELAY_DAILY_CAP";
+const DEFAULT_DAILY_CAP: u32 = 20;
+
+// 지갑별 하루 사용량 (ttl::TTL_TABLES). 키는 "{wallet}:{day}" 이고 그날이 끝나면 만료된다.
+pub const RELAY_QUOTA_TABLE: &str = "relay_quotas";
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// 사용량 읽기-수정-쓰기를 직렬화
+static QUOTA_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug)]
+pub enum RelayError {
+    DatabaseError(String),
+    ValidationError(String),
+    Forbidden(String),
+    QuotaExceeded { retry_after: u64 },
+    RpcError(String),
+    ConfigError(String),
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            RelayError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            RelayError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            RelayError::QuotaExceeded { retry_after } => write!(f, "Daily relay cap reached, retry after {}s", retry_after),
+            RelayError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            RelayError::ConfigError(msg) => write!(f, "Config error: {}", msg),
+        }
+    }
+}
+
+impl StdError for RelayError {}
+
+impl IntoResponse for RelayError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            RelayError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            RelayError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            RelayError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            RelayError::QuotaExceeded { retry_after } => {
+                return ApiError::new(StatusCode::TOO_MANY_REQUESTS, "Daily relay cap reached").with_retry_after(retry_after).into_response();
+            }
+            RelayError::RpcError(msg) => (StatusCode::BAD_GATEWAY, msg),
+            RelayError::ConfigError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+impl From<TxError> for RelayError {
+    fn from(error: TxError) -> Self {
+        match error {
+            TxError::ValidationError(msg) => RelayError::ValidationError(msg),
+            TxError::RpcError(msg) => RelayError::RpcError(msg),
+            TxError::ConfigError(msg) => RelayError::ConfigError(msg),
+        }
+    }
+}
+
+pub struct RelayConfig {
+    pub keypair: SigningKey,
+    pub daily_cap: u32,
+}
+
+impl RelayConfig {
+    pub fn fee_payer(&self) -> Pubkey {
+        Pubkey(self.keypair.verifying_key().to_bytes())
+    }
+}
+
+static RELAY_CONFIG: OnceLock<Option<RelayConfig>> = OnceLock::new();
+
+pub fn parse_keypair(value: &str) -> Option<SigningKey> {
+    let value = value.trim();
+    let bytes = if value.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(value).ok()?
+    } else {
+        bs58::decode(value).into_vec().ok()?
+    };
+    // 뒤 32바이트(공개키)가 앞 32바이트(비밀키)와 맞는지도 확인한다
+    SigningKey::from_keypair_bytes(&bytes.try_into().ok()?).ok()
+}
+
+pub fn relay_config() -> Result<&'static RelayConfig, RelayError> {
+    RELAY_CONFIG
+        .get_or_init(|| {
+            let keypair = std::env::var(RELAYER_KEYPAIR_ENV).ok()?;
+            let Some(keypair) = parse_keypair(&keypair) else {
+                tracing::warn!("{} is invalid, relayer disabled", RELAYER_KEYPAIR_ENV);
+                return None;
+            };
+            let daily_cap = std::env::var(RELAY_DAILY_CAP_ENV)
+                .ok()
+                .and_then(|cap| cap.parse().ok())
+                .unwrap_or(DEFAULT_DAILY_CAP);
+            Some(RelayConfig { keypair, daily_cap })
+        })
+        .as_ref()
+        .ok_or_else(|| RelayError::ConfigError(format!("{} is not set, relayer disabled", RELAYER_KEYPAIR_ENV)))
+}
+
+// 서명이 끝난 트랜잭션을 체인에 보낸다
+pub trait TransactionSubmitter: Send + Sync + 'static {
+    fn send_transaction(&self, transaction: &[u8]) -> impl Future<Output = Result<String, RelayError>> + Send;
+}
+
+impl TransactionSubmitter for RpcClient {
+    async fn send_transaction(&self, transaction: &[u8]) -> Result<String, RelayError> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(transaction);
+        let result = self
+            .call("sendTransaction", json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]))
+            .await
+            .map_err(|e| RelayError::RpcError(e.to_string()))?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RelayError::RpcError(format!("Unexpected sendTransaction response: {}", result)))
+    }
+}
+
+// 수수료를 대신 내 주는 instruction. DAO 생성이나 예치처럼 사용자의 SOL 이 움직이는 건 직접 내게 한다.
+// 제출 수수료 (링크된 글이면 claim 계정 rent 도) 는 작성자 지갑에서 나가므로 relayer 가 대신 내는 건 트랜잭션 수수료뿐이다.
+fn is_sponsored(instruction: &TurtleInstruction) -> bool {
+    matches!(
+        instruction,
+        TurtleInstruction::SubmitContent { .. }
+            | TurtleInstruction::SubmitLinkedContent { .. }
+            | TurtleInstruction::CreateVote { .. }
+            | TurtleInstruction::CastVote { .. }
+            | TurtleInstruction::ProcessTimeout { .. }
+    )
+}
+
+// relayer 가 fee payer 이고 사용자 지갑이 유일한 다른 서명자인지, Turtle 프로그램의 허용된 instruction 만 있는지 확인한다.
+// 통과하면 사용자 지갑을 돌려준다.
+pub fn validate_transaction(transaction: &Transaction, program_id: &Pubkey, relayer: &Pubkey) -> Result<Pubkey, RelayError> {
+    let message = &transaction.message;
+    if message.fee_payer() != relayer {
+        return Err(RelayError::ValidationError(format!("Fee payer must be the relayer {}", relayer)));
+    }
+    let [_, wallet] = message.signers() else {
+        return Err(RelayError::ValidationError("Transaction must have exactly one signer besides the relayer".to_string()));
+    };
+
+    let mut turtle_instructions = 0;
+    for instruction in &message.instructions {
+        let program = message.account_keys[instruction.program_id_index as usize];
+        // 우선 수수료도 relayer 가 내므로 빌더가 붙이는 한도 안에서만 받는다
+        if program == COMPUTE_BUDGET_PROGRAM_ID {
+            match ComputeBudgetInstruction::unpack(&instruction.data) {
+                Ok(ComputeBudgetInstruction::SetComputeUnitLimit(units)) if units <= DEFAULT_COMPUTE_UNIT_LIMIT => {},
+                Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) if price <= MAX_PRIORITY_FEE => {},
+                _ => return Err(RelayError::Forbidden("Compute budget instruction exceeds the relay limits".to_string())),
+            }
+            continue;
+        }
+        if program != *program_id {
+            return Err(RelayError::Forbidden("Only Turtle program instructions can be relayed".to_string()));
+        }
+        // relayer 계정을 instruction 에 넘기면 수수료 외의 용도로 쓰일 수 있다
+        if instruction.accounts.contains(&0) {
+            return Err(RelayError::Forbidden("Relayer account cannot be passed to instructions".to_string()));
+        }
+        let decoded = TurtleInstruction::unpack(&instruction.data)
+            .map_err(|_| RelayError::ValidationError("Unknown Turtle instruction".to_string()))?;
+        if !is_sponsored(&decoded) {
+            return Err(RelayError::Forbidden("Instruction is not eligible for relaying".to_string()));
+        }
+        turtle_instructions += 1;
+    }
+    if turtle_instructions == 0 {
+        return Err(RelayError::ValidationError("Transaction has no Turtle instructions".to_string()));
+    }
+
+    // 사용자 서명이 틀리면 체인에서 어차피 실패하니 수수료/한도를 쓰기 전에 걸러 낸다
+    let verifying_key = VerifyingKey::from_bytes(&wallet.to_bytes())
+        .map_err(|_| RelayError::ValidationError("Invalid wallet".to_string()))?;
+    let signature = Signature::from_slice(&transaction.signatures[1])
+        .map_err(|_| RelayError::ValidationError("Invalid signature".to_string()))?;
+    if !transaction.is_signed_by(1) || verifying_key.verify(&message.serialize(), &signature).is_err() {
+        return Err(RelayError::ValidationError("Transaction is not signed by the wallet".to_string()));
+    }
+
+    Ok(*wallet)
+}
+
+fn quota_key(wallet: &Pubkey, now: u64) -> String {
+    format!("{}:{}", wallet, now / SECONDS_PER_DAY)
+}
+
+fn read_count<T: SafeDatabase>(table: &TtlTable<T>, key: &str, now: u64) -> Result<u32, RelayError> {
+    let count = table.get_at(key, now)
+        .map_err(|e| RelayError::DatabaseError(e.to_string()))?
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_be_bytes)
+        .unwrap_or(0);
+    Ok(count)
+}
+
+fn write_count<T: SafeDatabase>(table: &TtlTable<T>, key: &str, count: u32, now: u64) -> Result<(), RelayError> {
+    let end_of_day = (now / SECONDS_PER_DAY + 1) * SECONDS_PER_DAY;
+    table.put_until(key, &count.to_be_bytes(), end_of_day)
+        .map_err(|e| RelayError::DatabaseError(e.to_string()))
+}
+
+// 오늘(UTC) 사용량을 하나 올리고 남은 횟수를 돌려준다
+pub fn consume_quota<T: SafeDatabase>(database: &T, wallet: &Pubkey, daily_cap: u32, now: u64) -> Result<u32, RelayError> {
+    let table = TtlTable::new(database.clone(), RELAY_QUOTA_TABLE);
+    let key = quota_key(wallet, now);
+    let _guard = QUOTA_LOCK.lock().unwrap();
+
+    let used = read_count(&table, &key, now)?;
+    if used >= daily_cap {
+        let retry_after = SECONDS_PER_DAY - now % SECONDS_PER_DAY;
+        return Err(RelayError::QuotaExceeded { retry_after });
+    }
+    write_count(&table, &key, used + 1, now)?;
+    Ok(daily_cap - used - 1)
+}
+
+// 전송에 실패한 요청은 한도에서 빼 준다
+fn refund_quota<T: SafeDatabase>(database: &T, wallet: &Pubkey, now: u64) -> Result<(), RelayError> {
+    let table = TtlTable::new(database.clone(), RELAY_QUOTA_TABLE);
+    let key = quota_key(wallet, now);
+    let _guard = QUOTA_LOCK.lock().unwrap();
+
+    let used = read_count(&table, &key, now)?;
+    write_count(&table, &key, used.saturating_sub(1), now)
+}
+
+#[derive(Deserialize)]
+pub struct RelayRequest {
+    // 사용자 지갑이 서명한 트랜잭션 (base64). fee payer 서명 자리는 비워 둔다.
+    transaction: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelayResponse {
+    pub signature: String,
+    pub wallet: String,
+    pub remaining_today: u32,
+}
+
+pub async fn relay<T: SafeDatabase, S: TransactionSubmitter>(
+    database: &T,
+    submitter: &S,
+    config: &RelayConfig,
+    program_id: &Pubkey,
+    encoded: &str,
+    now: u64,
+) -> Result<RelayResponse, RelayError> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|_| RelayError::ValidationError("transaction must be base64".to_string()))?;
+    let mut transaction = Transaction::deserialize(&bytes)
+        .map_err(|e| RelayError::ValidationError(e.to_string()))?;
+
+    let wallet = validate_transaction(&transaction, program_id, &config.fee_payer())?;
+    let remaining_today = consume_quota(database, &wallet, config.daily_cap, now)?;
+
+    transaction.signatures[0] = config.keypair.sign(&transaction.message.serialize()).to_bytes();
+    match submitter.send_transaction(&transaction.serialize()).await {
+        Ok(signature) => Ok(RelayResponse { signature, wallet: wallet.to_string(), remaining_today }),
+        Err(e) => {
+            refund_quota(database, &wallet, now)?;
+            Err(e)
+        }
+    }
+}
+
+pub async fn relay_transaction<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<RelayRequest>,
+) -> Result<Json<RelayResponse>, RelayError> {
+    let config = relay_config()?;
+    let tx_config = tx_config()?;
+    let response = relay(&database, &tx_config.rpc, config, &tx_config.program_id, &request.transaction, now_secs()).await?;
+    Ok(Json(response))
+}
+
+// 클라이언트가 fee payer 로 넣을 relayer 주소와 하루 한도
+pub async fn get_relayer() -> Result<Json<serde_json::Value>, RelayError> {
+    let config = relay_config()?;
+    Ok(Json(json!({
+        "fee_payer": config.fee_payer().to_string(),
+        "daily_cap": config.daily_cap,
+    })))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol::instruction::{self, Instruction};
+    use sol::transaction::Message;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    const PROGRAM_ID: Pubkey = Pubkey([3; 32]);
+    const DAO: Pubkey = Pubkey([2; 32]);
+
+    struct Recorder(Mutex<Vec<Vec<u8>>>);
+
+    impl TransactionSubmitter for Recorder {
+        async fn send_transaction(&self, transaction: &[u8]) -> Result<String, RelayError> {
+            self.0.lock().unwrap().push(transaction.to_vec());
+            Ok("sig".to_string())
+        }
+    }
+
+    fn config(daily_cap: u32) -> RelayConfig {
+        RelayConfig { keypair: SigningKey::from_bytes(&[5; 32]), daily_cap }
+    }
+
+    fn user() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn signed(instruction: Instruction, relayer: &Pubkey, user: &SigningKey) -> Transaction {
+        signed_all(&[instruction], relayer, user)
+    }
+
+    fn signed_all(instructions: &[Instruction], relayer: &Pubkey, user: &SigningKey) -> Transaction {
+        let message = Message::new(instructions, relayer, [9; 32]);
+        let mut transaction = Transaction::unsigned(message);
+        transaction.signatures[1] = user.sign(&transaction.message.serialize()).to_bytes();
+        transaction
+    }
+
+    fn encode(transaction: &Transaction) -> String {
+        base64::engine::general_purpose::STANDARD.encode(transaction.serialize())
+    }
+
+    #[test]
+    fn test_parse_keypair_formats() {
+        let key = SigningKey::from_bytes(&[5; 32]);
+        let bytes = key.to_keypair_bytes();
+        let json = serde_json::to_string(&bytes.to_vec()).unwrap();
+
+        assert_eq!(parse_keypair(&json).unwrap().to_bytes(), key.to_bytes());
+        assert_eq!(parse_keypair(&bs58::encode(bytes).into_string()).unwrap().to_bytes(), key.to_bytes());
+
+        // 공개키 부분이 맞지 않으면 거부
+        let mut mismatched = bytes;
+        mismatched[40] ^= 1;
+        assert!(parse_keypair(&bs58::encode(mismatched).into_string()).is_none());
+    }
+
+    #[test]
+    fn test_validate_rejects_unsafe_transactions() {
+        let relayer = config(1).fee_payer();
+        let user = user();
+        let wallet = Pubkey(user.verifying_key().to_bytes());
+
+        let ok = signed(instruction::cast_vote(&PROGRAM_ID, &wallet, &DAO, 0, 1), &relayer, &user);
+        assert_eq!(validate_transaction(&ok, &PROGRAM_ID, &relayer).unwrap(), wallet);
+
+        // 다른 프로그램
+        let other = signed(instruction::cast_vote(&Pubkey([4; 32]), &wallet, &DAO, 0, 1), &relayer, &user);
+        assert!(matches!(validate_transaction(&other, &PROGRAM_ID, &relayer), Err(RelayError::Forbidden(_))));
+
+        // 허용되지 않은 instruction (사용자 SOL 이 움직인다)
+        let deposit = signed(instruction::deposit(&PROGRAM_ID, &wallet, &DAO, 10), &relayer, &user);
+        assert!(matches!(validate_transaction(&deposit, &PROGRAM_ID, &relayer), Err(RelayError::Forbidden(_))));
+
+        // relayer 계정을 instruction 에 넘기는 경우
+        let drain = signed(instruction::cast_vote(&PROGRAM_ID, &wallet, &relayer, 0, 1), &relayer, &user);
+        assert!(matches!(validate_transaction(&drain, &PROGRAM_ID, &relayer), Err(RelayError::Forbidden(_))));
+
+        // fee payer 가 relayer 가 아님
+        let self_paid = signed(instruction::cast_vote(&PROGRAM_ID, &wallet, &DAO, 0, 1), &Pubkey([8; 32]), &user);
+        assert!(matches!(validate_transaction(&self_paid, &PROGRAM_ID, &relayer), Err(RelayError::ValidationError(_))));
+
+        // 빌더가 붙이는 compute budget instruction 은 한도 안이면 받는다
+        let vote = instruction::cast_vote(&PROGRAM_ID, &wallet, &DAO, 0, 1);
+        let mut prioritized = crate::fees::compute_budget_instructions(MAX_PRIORITY_FEE);
+        prioritized.push(vote.clone());
+        assert_eq!(validate_transaction(&signed_all(&prioritized, &relayer, &user), &PROGRAM_ID, &relayer).unwrap(), wallet);
+
+        // relayer 가 낼 우선 수수료가 한도를 넘음
+        let pricey = [instruction::set_compute_unit_price(MAX_PRIORITY_FEE + 1), vote.clone()];
+        assert!(matches!(validate_transaction(&signed_all(&pricey, &relayer, &user), &PROGRAM_ID, &relayer), Err(RelayError::Forbidden(_))));
+        let heavy = [instruction::set_compute_unit_limit(1_400_000), vote];
+        assert!(matches!(validate_transaction(&signed_all(&heavy, &relayer, &user), &PROGRAM_ID, &relayer), Err(RelayError::Forbidden(_))));
+
+        // compute budget 만 있고 Turtle instruction 이 없음
+        let mut price = instruction::set_compute_unit_price(0);
+        price.accounts.push(instruction::AccountMeta::new_readonly(wallet, true));
+        let budget_only = [price];
+        assert!(matches!(validate_transaction(&signed_all(&budget_only, &relayer, &user), &PROGRAM_ID, &relayer), Err(RelayError::ValidationError(_))));
+
+        // 사용자 서명이 틀림
+        let mut forged = ok.clone();
+        forged.signatures[1] = SigningKey::from_bytes(&[6; 32]).sign(&ok.message.serialize()).to_bytes();
+        assert!(matches!(validate_transaction(&forged, &PROGRAM_ID, &relayer), Err(RelayError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_relay_cosigns_and_enforces_daily_cap() {
+        let database = MemoryDatabase::new();
+        let config = config(1);
+        let recorder = Recorder(Mutex::new(Vec::new()));
+        let user = user();
+        let wallet = Pubkey(user.verifying_key().to_bytes());
+        let transaction = signed(instruction::process_timeout(&PROGRAM_ID, &wallet, &DAO, 0, &Pubkey::default()), &config.fee_payer(), &user);
+        let now = 10 * SECONDS_PER_DAY + 100;
+
+        let response = relay(&database, &recorder, &config, &PROGRAM_ID, &encode(&transaction), now).await.unwrap();
+        assert_eq!(response.wallet, wallet.to_string());
+        assert_eq!(response.remaining_today, 0);
+
+        // 보낸 트랜잭션에는 relayer 서명이 채워져 있다
+        let sent = Transaction::deserialize(&recorder.0.lock().unwrap()[0]).unwrap();
+        let relayer_signature = Signature::from_bytes(&sent.signatures[0]);
+        assert!(config.keypair.verifying_key().verify(&sent.message.serialize(), &relayer_signature).is_ok());
+        assert_eq!(sent.signatures[1], transaction.signatures[1]);
+
+        let capped = relay(&database, &recorder, &config, &PROGRAM_ID, &encode(&transaction), now).await;
+        assert!(matches!(capped, Err(RelayError::QuotaExceeded { retry_after }) if retry_after == SECONDS_PER_DAY - 100));
+
+        // 다음 날에는 다시 쓸 수 있다
+        assert!(relay(&database, &recorder, &config, &PROGRAM_ID, &encode(&transaction), now + SECONDS_PER_DAY).await.is_ok());
+    }
+}
@@ -0,0 +1,454 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::Mutex;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::chain::ChainContent;
+use turtle_service::parser::moderation::{
+    CaseStatus, ContentReport, ModerationAction, ModerationAudit, ModerationVerdict, ReportCase, ReportReason, Verdict,
+};
+use crate::gateway::{block_content_media_ops, unblock_content_media_ops};
+use crate::moderation::{override_verdict, MODERATION_VERDICTS};
+use crate::pagination::{in_order, paginate, ListQuery, ListQueryError, SortOrder};
+use crate::session::AuthedWallet;
+use crate::sync::CHAIN_CONTENTS;
+use crate::error::ApiError;
+
+// (dao, index, reporter) -> ContentReport
+pub const CONTENT_REPORTS: TypedTable<ContentReport> = TypedTable::new("content_reports");
+// (dao, index) -> ReportCase. 키는 chain_contents 와 같다.
+pub const REPORT_CASES: TypedTable<ReportCase> = TypedTable::new("report_cases");
+// (dao, index) -> 숨긴 시각. 여기 있는 콘텐츠는 검색과 실시간 피드에 나가지 않는다.
+pub const HIDDEN_CONTENTS: TypedTable<u64> = TypedTable::new("hidden_contents");
+// (at, seq) -> ModerationAudit. 시간 순서로 쌓인다.
+pub const MODERATION_AUDIT: TypedTable<ModerationAudit> = TypedTable::new("moderation_audit");
+
+pub const MAX_REPORT_DETAILS_CHARS: usize = 500;
+pub const DEFAULT_REPORT_LIMIT: usize = 20;
+pub const MAX_REPORT_LIMIT: usize = 100;
+pub const REPORT_QUEUE_FILTERS: &[&str] = &["status", "dao"];
+pub const AUDIT_LIST_FILTERS: &[&str] = &["dao"];
+// 큐 항목 하나에 붙여 보내는 신고 수
+const REPORTS_PER_CASE: usize = 20;
+
+// 신고와 조치가 같은 case 를 고치므로 한 줄로 세운다
+static REPORT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug)]
+pub enum ReportError {
+    DatabaseError(String),
+    ValidationError(String),
+    NotFound(String),
+    AlreadyReported,
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            ReportError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            ReportError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            ReportError::AlreadyReported => write!(f, "Content already reported by this wallet"),
+        }
+    }
+}
+
+impl StdError for ReportError {}
+
+impl IntoResponse for ReportError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ReportError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ReportError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            ReportError::NotFound(_) => StatusCode::NOT_FOUND,
+            ReportError::AlreadyReported => StatusCode::CONFLICT,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+impl From<ListQueryError> for ReportError {
+    fn from(e: ListQueryError) -> Self {
+        match e {
+            ListQueryError::InvalidQuery(msg) => ReportError::ValidationError(msg),
+            ListQueryError::DatabaseError(msg) => ReportError::DatabaseError(msg),
+        }
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> ReportError {
+    ReportError::DatabaseError(e.to_string())
+}
+
+#[derive(Deserialize)]
+pub struct ReportRequest {
+    dao: String,
+    index: u64,
+    reason: ReportReason,
+    details: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReportActionRequest {
+    dao: String,
+    index: u64,
+    action: ModerationAction,
+    note: Option<String>,
+}
+
+// 모더레이터 큐의 한 줄. 판단에 필요한 콘텐츠, 기존 판정, 신고들을 함께 보낸다.
+#[derive(Debug, Serialize)]
+pub struct ReportQueueItem {
+    #[serde(flatten)]
+    pub case: ReportCase,
+    pub content: Option<ChainContent>,
+    pub verdict: Option<ModerationVerdict>,
+    pub hidden: bool,
+    pub reports: Vec<ContentReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportQueue {
+    pub cases: Vec<ReportQueueItem>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditList {
+    pub entries: Vec<ModerationAudit>,
+    pub next_cursor: Option<String>,
+}
+
+fn content_key(dao: &str, index: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(index).build()
+}
+
+fn report_key(dao: &str, index: u64, reporter: &str) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(index).str(reporter).build()
+}
+
+fn audit_key(at: u64, seq: u32) -> Vec<u8> {
+    KeyBuilder::new().u64(at).u32(seq).build()
+}
+
+// 검색 색인과 실시간 피드가 숨긴 콘텐츠를 거를 때 쓴다 (키는 chain_contents 의 키)
+pub fn is_hidden<T: SafeDatabase>(database: &T, key: &[u8]) -> Result<bool, ReportError> {
+    Ok(HIDDEN_CONTENTS.get(database, key).map_err(db_err)?.is_some())
+}
+
+fn ensure_content<T: SafeDatabase>(database: &T, dao: &str, index: u64) -> Result<ChainContent, ReportError> {
+    CHAIN_CONTENTS.get(database, &content_key(dao, index)).map_err(db_err)?
+        .ok_or_else(|| ReportError::NotFound(format!("No content {} in DAO {}", index, dao)))
+}
+
+fn clean_note(note: Option<String>) -> Result<Option<String>, ReportError> {
+    let note = note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty());
+    if note.as_ref().is_some_and(|note| note.chars().count() > MAX_REPORT_DETAILS_CHARS) {
+        return Err(ReportError::ValidationError(format!("details must be at most {} characters", MAX_REPORT_DETAILS_CHARS)));
+    }
+    Ok(note)
+}
+
+// 이 초에 이미 남긴 기록 뒤에 붙인다. REPORT_LOCK 을 잡고 불러야 한다.
+fn audit_op<T: SafeDatabase>(database: &T, entry: &ModerationAudit) -> Result<KvOp, ReportError> {
+    let same_second = KeyRange::between(audit_key(entry.at, 0), audit_key(entry.at, u32::MAX));
+    let seq = MODERATION_AUDIT.scan(database, &same_second).map_err(db_err)?.len() as u32;
+    MODERATION_AUDIT.put_op(&audit_key(entry.at, seq), entry).map_err(db_err)
+}
+
+// 신고를 남기고 콘텐츠의 case 를 연다. 닫힌 (dismissed) case 는 새 신고가 오면 다시 연다.
+pub fn file_report<T: SafeDatabase>(
+    database: &T,
+    reporter: &str,
+    request: ReportRequest,
+    now: u64,
+) -> Result<ReportCase, ReportError> {
+    let details = clean_note(request.details)?;
+    ensure_content(database, &request.dao, request.index)?;
+
+    let _guard = REPORT_LOCK.lock().unwrap();
+    let key = report_key(&request.dao, request.index, reporter);
+    if CONTENT_REPORTS.get(database, &key).map_err(db_err)?.is_some() {
+        return Err(ReportError::AlreadyReported);
+    }
+
+    let case_key = content_key(&request.dao, request.index);
+    let mut case = REPORT_CASES.get(database, &case_key).map_err(db_err)?.unwrap_or(ReportCase {
+        dao: request.dao.clone(),
+        index: request.index,
+        status: CaseStatus::Open,
+        report_count: 0,
+        first_reported_at: now,
+        last_reported_at: now,
+        updated_at: now,
+    });
+    if case.status == CaseStatus::Dismissed {
+        case.status = CaseStatus::Open;
+    }
+    case.report_count += 1;
+    case.last_reported_at = now;
+    case.updated_at = now;
+
+    let report = ContentReport {
+        dao: request.dao.clone(),
+        index: request.index,
+        reporter: reporter.to_string(),
+        reason: request.reason,
+        details: details.clone(),
+        created_at: now,
+    };
+    let audit = ModerationAudit {
+        dao: request.dao,
+        index: request.index,
+        action: ModerationAction::Report,
+        by: reporter.to_string(),
+        note: details,
+        at: now,
+    };
+    database.txn(&[
+        CONTENT_REPORTS.put_op(&key, &report).map_err(db_err)?,
+        REPORT_CASES.put_op(&case_key, &case).map_err(db_err)?,
+        audit_op(database, &audit)?,
+    ]).map_err(db_err)?;
+    Ok(case)
+}
+
+// 모더레이터 조치. 숨기기/되돌리기/닫기는 hidden_contents 와 콘텐츠 이미지의 미디어 차단을 고치고,
+// escalate 는 Rejected 모더레이션 판정을 남겨 flagged 목록과 /api/moderation 에 나오게 한다.
+pub fn apply_action<T: SafeDatabase>(
+    database: &T,
+    moderator: &str,
+    request: ReportActionRequest,
+    now: u64,
+) -> Result<ReportCase, ReportError> {
+    let note = clean_note(request.note)?;
+    let content = ensure_content(database, &request.dao, request.index)?;
+
+    let _guard = REPORT_LOCK.lock().unwrap();
+    let key = content_key(&request.dao, request.index);
+    // 신고 없이도 모더레이터가 바로 숨길 수 있다
+    let mut case = REPORT_CASES.get(database, &key).map_err(db_err)?.unwrap_or(ReportCase {
+        dao: request.dao.clone(),
+        index: request.index,
+        status: CaseStatus::Open,
+        report_count: 0,
+        first_reported_at: now,
+        last_reported_at: now,
+        updated_at: now,
+    });
+
+    let mut ops = Vec::new();
+    case.status = match request.action {
+        ModerationAction::Report => return Err(ReportError::ValidationError("Use POST /api/content/report to report content".to_string())),
+        ModerationAction::Hide => {
+            ops.push(HIDDEN_CONTENTS.put_op(&key, &now).map_err(db_err)?);
+            ops.extend(block_content_media_ops(database, &content, moderator, now).map_err(db_err)?);
+            CaseStatus::Hidden
+        },
+        ModerationAction::Unhide => {
+            ops.push(HIDDEN_CONTENTS.delete_op(&key));
+            ops.extend(unblock_content_media_ops(database, &content).map_err(db_err)?);
+            CaseStatus::Open
+        },
+        ModerationAction::Escalate => {
+            override_verdict(database, &request.dao, request.index, Verdict::Rejected, moderator, note.clone(), now).map_err(db_err)?;
+            CaseStatus::Escalated
+        },
+        ModerationAction::Dismiss => {
+            ops.push(HIDDEN_CONTENTS.delete_op(&key));
+            ops.extend(unblock_content_media_ops(database, &content).map_err(db_err)?);
+            CaseStatus::Dismissed
+        },
+    };
+    case.updated_at = now;
+
+    let audit = ModerationAudit {
+        dao: request.dao,
+        index: request.index,
+        action: request.action,
+        by: moderator.to_string(),
+        note,
+        at: now,
+    };
+    ops.push(REPORT_CASES.put_op(&key, &case).map_err(db_err)?);
+    ops.push(audit_op(database, &audit)?);
+    database.txn(&ops).map_err(db_err)?;
+    Ok(case)
+}
+
+// 색인된 콘텐츠를 신고한다. 같은 지갑이 같은 콘텐츠를 다시 신고하면 409.
+pub async fn report_content<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<ReportRequest>,
+) -> Result<(StatusCode, Json<ReportCase>), ReportError> {
+    let case = file_report(&database, &wallet, request, now_secs())?;
+    Ok((StatusCode::CREATED, Json(case)))
+}
+
+fn parse_status(status: &str) -> Result<CaseStatus, ReportError> {
+    serde_json::from_value(serde_json::Value::from(status))
+        .map_err(|_| ReportError::ValidationError(format!("filter[status] must be open, hidden, escalated or dismissed: {}", status)))
+}
+
+fn queue_item<T: SafeDatabase>(database: &T, key: &[u8], case: ReportCase) -> Result<ReportQueueItem, ReportError> {
+    let reports = CONTENT_REPORTS.scan(database, &KeyRange::prefix(key)).map_err(db_err)?;
+    Ok(ReportQueueItem {
+        content: CHAIN_CONTENTS.get(database, key).map_err(db_err)?,
+        verdict: MODERATION_VERDICTS.get(database, key).map_err(db_err)?,
+        hidden: is_hidden(database, key)?,
+        reports: reports.into_iter().take(REPORTS_PER_CASE).map(|(_, report)| report).collect(),
+        case,
+    })
+}
+
+// 모더레이터 큐. 기본은 아직 처리하지 않은 (open) case 를 콘텐츠 순서로.
+// filter[status]=hidden|escalated|dismissed, filter[dao]=... 로 좁힐 수 있다.
+pub async fn list_reports<T: SafeDatabase>(
+    State(database): State<T>,
+    query: ListQuery,
+) -> Result<Json<ReportQueue>, ReportError> {
+    query.check_filters(REPORT_QUEUE_FILTERS)?;
+    query.sort(&["content"])?;
+    let limit = query.limit(DEFAULT_REPORT_LIMIT, MAX_REPORT_LIMIT);
+    let order = query.order(SortOrder::Asc);
+    let status = query.filter("status").map(parse_status).transpose()?.unwrap_or(CaseStatus::Open);
+    let base = match query.filter("dao") {
+        Some(dao) => KeyRange::prefix(KeyBuilder::new().str(dao).build()),
+        None => KeyRange::all(),
+    };
+
+    let rows = REPORT_CASES.scan(&database, &query.range(base, order)?).map_err(db_err)?;
+    let cases = in_order(rows, order)
+        .into_iter()
+        .filter(|(_, case)| case.status == status)
+        .map(|(key, case)| queue_item(&database, &key, case).map(|item| (key, item)));
+    let (cases, next_cursor) = paginate(cases, limit)?;
+    Ok(Json(ReportQueue { cases, next_cursor }))
+}
+
+// 모더레이터 조치를 case 에 적용하고 바뀐 case 를 돌려준다
+pub async fn act_on_report<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<ReportActionRequest>,
+) -> Result<Json<ReportCase>, ReportError> {
+    Ok(Json(apply_action(&database, &wallet, request, now_secs())?))
+}
+
+// 신고/조치 기록. 최신 것부터 (filter[dao]=... 로 DAO 하나만)
+pub async fn list_moderation_audit<T: SafeDatabase>(
+    State(database): State<T>,
+    query: ListQuery,
+) -> Result<Json<AuditList>, ReportError> {
+    query.check_filters(AUDIT_LIST_FILTERS)?;
+    query.sort(&["at"])?;
+    let limit = query.limit(DEFAULT_REPORT_LIMIT, MAX_REPORT_LIMIT);
+    let order = query.order(SortOrder::Desc);
+    let dao = query.filter("dao");
+
+    let rows = MODERATION_AUDIT.scan(&database, &query.range(KeyRange::all(), order)?).map_err(db_err)?;
+    let entries = in_order(rows, order)
+        .into_iter()
+        .filter(|(_, entry)| dao.is_none_or(|dao| entry.dao == dao))
+        .map(Ok::<_, ReportError>);
+    let (entries, next_cursor) = paginate(entries, limit)?;
+    Ok(Json(AuditList { entries, next_cursor }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    fn put_content(database: &MemoryDatabase, dao: &str, index: u64) {
+        let content = ChainContent {
+            dao: dao.to_string(),
+            index,
+            author: "bob".to_string(),
+            text: "buy spam".to_string(),
+            image_uri: String::new(),
+            timestamp: 0,
+            vote_count: 0,
+            round: 0,
+        };
+        CHAIN_CONTENTS.put(database, &content_key(dao, index), &content).unwrap();
+    }
+
+    fn report(index: u64, reason: ReportReason) -> ReportRequest {
+        ReportRequest { dao: "dao".to_string(), index, reason, details: Some(" looks like spam ".to_string()) }
+    }
+
+    fn action(index: u64, action: ModerationAction) -> ReportActionRequest {
+        ReportActionRequest { dao: "dao".to_string(), index, action, note: None }
+    }
+
+    fn queue(database: &MemoryDatabase, query: &str) -> impl std::future::Future<Output = Result<Json<ReportQueue>, ReportError>> {
+        list_reports(State(database.clone()), ListQuery::from_uri(&format!("/q?{}", query).parse().unwrap()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_reports_open_cases_once_per_wallet() {
+        let database = MemoryDatabase::new();
+        put_content(&database, "dao", 0);
+        put_content(&database, "dao", 1);
+
+        file_report(&database, "alice", report(0, ReportReason::Spam), 100).unwrap();
+        let case = file_report(&database, "carol", report(0, ReportReason::Abuse), 110).unwrap();
+        assert_eq!((case.status, case.report_count, case.first_reported_at, case.last_reported_at), (CaseStatus::Open, 2, 100, 110));
+        assert!(matches!(file_report(&database, "alice", report(0, ReportReason::Other), 120), Err(ReportError::AlreadyReported)));
+        assert!(matches!(file_report(&database, "alice", report(9, ReportReason::Spam), 120), Err(ReportError::NotFound(_))));
+
+        let Json(open) = queue(&database, "").await.unwrap();
+        assert_eq!(open.cases.len(), 1);
+        let item = &open.cases[0];
+        assert_eq!(item.content.as_ref().map(|content| content.text.as_str()), Some("buy spam"));
+        assert_eq!(item.reports.iter().map(|report| report.reporter.as_str()).collect::<Vec<_>>(), ["alice", "carol"]);
+        assert_eq!(item.reports[0].details.as_deref(), Some("looks like spam"));
+        assert!(matches!(queue(&database, "filter[status]=closed").await, Err(ReportError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_actions_hide_escalate_and_audit() {
+        let database = MemoryDatabase::new();
+        put_content(&database, "dao", 0);
+        put_content(&database, "dao", 1);
+        file_report(&database, "alice", report(0, ReportReason::Spam), 100).unwrap();
+
+        let hidden = apply_action(&database, "mod", action(0, ModerationAction::Hide), 200).unwrap();
+        assert_eq!(hidden.status, CaseStatus::Hidden);
+        assert!(is_hidden(&database, &content_key("dao", 0)).unwrap());
+        assert!(queue(&database, "").await.unwrap().0.cases.is_empty());
+        assert_eq!(queue(&database, "filter[status]=hidden").await.unwrap().0.cases.len(), 1);
+
+        // 신고가 없던 콘텐츠도 바로 escalate 할 수 있다
+        let escalated = apply_action(&database, "mod", action(1, ModerationAction::Escalate), 200).unwrap();
+        assert_eq!((escalated.status, escalated.report_count), (CaseStatus::Escalated, 0));
+        let verdict = MODERATION_VERDICTS.get(&database, &content_key("dao", 1)).unwrap().unwrap();
+        assert_eq!((verdict.verdict, verdict.overridden.unwrap().by.as_str()), (Verdict::Rejected, "mod"));
+
+        // 닫으면 다시 보이고, 새 신고가 오면 다시 열린다
+        apply_action(&database, "mod", action(0, ModerationAction::Dismiss), 300).unwrap();
+        assert!(!is_hidden(&database, &content_key("dao", 0)).unwrap());
+        let reopened = file_report(&database, "carol", report(0, ReportReason::Abuse), 400).unwrap();
+        assert_eq!(reopened.status, CaseStatus::Open);
+        assert!(matches!(apply_action(&database, "mod", action(0, ModerationAction::Report), 500), Err(ReportError::ValidationError(_))));
+
+        let audit = |query: &str| list_moderation_audit(State(database.clone()), ListQuery::from_uri(&format!("/a?{}", query).parse().unwrap()).unwrap());
+        let Json(entries) = audit("").await.unwrap();
+        let actions: Vec<ModerationAction> = entries.entries.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, [ModerationAction::Report, ModerationAction::Dismiss, ModerationAction::Escalate, ModerationAction::Hide, ModerationAction::Report]);
+        // 같은 초의 기록은 seq 로 갈린다
+        assert_eq!(entries.entries[2].index, 1);
+        assert!(audit("filter[dao]=other").await.unwrap().0.entries.is_empty());
+    }
+}
@@ -0,0 +1,338 @@
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::error::Error as StdError;
+use std::fmt;
+use std::marker::PhantomData;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use sol::state::Pubkey;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use crate::auth::AuthError;
+use crate::session::AuthedWallet;
+use crate::error::ApiError;
+
+// 서버를 처음 띄울 때 admin 으로 넣어 둘 지갑들 (쉼표로 구분). 그 뒤로는 admin API 로 부여한다.
+pub const ADMIN_WALLETS_ENV: &str = "TURTLE_ADMIN_WALLETS";
+
+// wallet -> RoleGrant. 항목이 없는 지갑은 user 이다.
+pub const ROLES: TypedTable<RoleGrant> = TypedTable::new("roles");
+
+// 선언 순서가 곧 권한 순서 (admin 은 moderator 가 할 수 있는 일도 할 수 있다)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    User,
+    Moderator,
+    Admin,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::User => write!(f, "user"),
+            Role::Moderator => write!(f, "moderator"),
+            Role::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleGrant {
+    pub wallet: String,
+    pub role: Role,
+    pub granted_by: String,
+    pub granted_at: u64,
+}
+
+#[derive(Deserialize)]
+pub struct GrantRoleRequest {
+    wallet: String,
+    role: Role,
+}
+
+#[derive(Deserialize)]
+pub struct RevokeRoleRequest {
+    wallet: String,
+}
+
+#[derive(Serialize)]
+pub struct RolesResponse {
+    roles: Vec<RoleGrant>,
+}
+
+#[derive(Debug)]
+pub enum RoleError {
+    DatabaseError(String),
+    ValidationError(String),
+    Unauthorized(String),
+    Forbidden(String),
+}
+
+impl fmt::Display for RoleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoleError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            RoleError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            RoleError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            RoleError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+        }
+    }
+}
+
+impl StdError for RoleError {}
+
+impl IntoResponse for RoleError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            RoleError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            RoleError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            RoleError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            RoleError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+impl From<AuthError> for RoleError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::DatabaseError(msg) => RoleError::DatabaseError(msg),
+            AuthError::ValidationError(msg) => RoleError::ValidationError(msg),
+            AuthError::Unauthorized(msg) => RoleError::Unauthorized(msg),
+        }
+    }
+}
+
+pub fn role_of<T: SafeDatabase>(database: &T, wallet: &str) -> Result<Role, RoleError> {
+    let grant = ROLES.get(database, wallet.as_bytes())
+        .map_err(|e| RoleError::DatabaseError(e.to_string()))?;
+    Ok(grant.map(|grant| grant.role).unwrap_or(Role::User))
+}
+
+// 로그인한 지갑이 required 이상의 역할을 가졌는지 확인
+async fn authorize<T: SafeDatabase>(parts: &mut Parts, database: &T, required: Role) -> Result<(String, Role), RoleError> {
+    let AuthedWallet(wallet) = AuthedWallet::from_request_parts(parts, database).await?;
+    let role = role_of(database, &wallet)?;
+    if role < required {
+        return Err(RoleError::Forbidden(format!("{} role required", required)));
+    }
+    Ok((wallet, role))
+}
+
+pub fn seed_admins<T: SafeDatabase>(database: &T, wallets: &[String]) -> Result<usize, RoleError> {
+    let mut seeded = 0;
+    for wallet in wallets {
+        if role_of(database, wallet)? == Role::Admin {
+            continue;
+        }
+        let grant = RoleGrant {
+            wallet: wallet.clone(),
+            role: Role::Admin,
+            granted_by: ADMIN_WALLETS_ENV.to_string(),
+            granted_at: now_secs(),
+        };
+        ROLES.put(database, wallet.as_bytes(), &grant)
+            .map_err(|e| RoleError::DatabaseError(e.to_string()))?;
+        seeded += 1;
+    }
+    Ok(seeded)
+}
+
+pub fn admin_wallets_from_env() -> Vec<String> {
+    std::env::var(ADMIN_WALLETS_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|wallet| !wallet.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// 핸들러 인자로 쓰는 역할 요구사항.
+//     async fn handler(Authorized { wallet, .. }: Authorized<AdminOnly>) { ... }
+pub trait RequiredRole {
+    const ROLE: Role;
+}
+
+pub struct AdminOnly;
+pub struct ModeratorOnly;
+
+impl RequiredRole for AdminOnly {
+    const ROLE: Role = Role::Admin;
+}
+
+impl RequiredRole for ModeratorOnly {
+    const ROLE: Role = Role::Moderator;
+}
+
+pub struct Authorized<R> {
+    pub wallet: String,
+    pub role: Role,
+    _required: PhantomData<R>,
+}
+
+impl<T: SafeDatabase, R: RequiredRole> FromRequestParts<T> for Authorized<R> {
+    type Rejection = RoleError;
+
+    async fn from_request_parts(parts: &mut Parts, database: &T) -> Result<Self, Self::Rejection> {
+        let (wallet, role) = authorize(parts, database, R::ROLE).await?;
+        Ok(Authorized { wallet, role, _required: PhantomData })
+    }
+}
+
+pub async fn require_role<T: SafeDatabase>(
+    State((database, required)): State<(T, Role)>,
+    request: Request,
+    next: Next,
+) -> Result<Response, RoleError> {
+    let (mut parts, body) = request.into_parts();
+    let (wallet, _) = authorize(&mut parts, &database, required).await?;
+
+    let mut request = Request::from_parts(parts, body);
+    request.extensions_mut().insert(AuthedWallet(wallet));
+    Ok(next.run(request).await)
+}
+
+// 컴포넌트 라우터의 모든 라우트를 required 이상의 역할로 제한한다
+pub fn restricted<T: SafeDatabase>(component: (String, Router<T>), database: &T, required: Role) -> (String, Router<T>) {
+    let (path, router) = component;
+    let router = router.route_layer(middleware::from_fn_with_state((database.clone(), required), require_role::<T>));
+    (path, router)
+}
+
+pub async fn grant_role<T: SafeDatabase>(
+    State(database): State<T>,
+    admin: Authorized<AdminOnly>,
+    Json(request): Json<GrantRoleRequest>,
+) -> Result<Json<RoleGrant>, RoleError> {
+    if request.wallet.parse::<Pubkey>().is_err() {
+        return Err(RoleError::ValidationError(format!("Invalid wallet: {}", request.wallet)));
+    }
+
+    let grant = RoleGrant {
+        wallet: request.wallet,
+        role: request.role,
+        granted_by: admin.wallet,
+        granted_at: now_secs(),
+    };
+    ROLES.put(&database, grant.wallet.as_bytes(), &grant)
+        .map_err(|e| RoleError::DatabaseError(e.to_string()))?;
+
+    Ok(Json(grant))
+}
+
+pub async fn revoke_role<T: SafeDatabase>(
+    State(database): State<T>,
+    admin: Authorized<AdminOnly>,
+    Json(request): Json<RevokeRoleRequest>,
+) -> Result<StatusCode, RoleError> {
+    // 마지막 admin 이 실수로 자기 권한을 지워서 아무도 관리할 수 없게 되는 것을 막는다
+    if request.wallet == admin.wallet {
+        return Err(RoleError::ValidationError("Cannot revoke your own role".to_string()));
+    }
+
+    ROLES.delete(&database, request.wallet.as_bytes())
+        .map_err(|e| RoleError::DatabaseError(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_roles<T: SafeDatabase>(
+    State(database): State<T>,
+    _admin: Authorized<AdminOnly>,
+) -> Result<Json<RolesResponse>, RoleError> {
+    let roles = ROLES.scan(&database, &KeyRange::all())
+        .map_err(|e| RoleError::DatabaseError(e.to_string()))?
+        .into_iter()
+        .map(|(_, grant)| grant)
+        .collect();
+
+    Ok(Json(RolesResponse { roles }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::header;
+    use axum::routing::get;
+    use tower::ServiceExt;
+    use turtle_database::memory_db::MemoryDatabase;
+    use crate::session::issue_session;
+
+    const ADMIN: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+    const MODERATOR: &str = "8opHzTAnfzRpPEx21XtnrVTX28YQuCpAjcn1PczScKh";
+    const USER: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+    fn database() -> MemoryDatabase {
+        let database = MemoryDatabase::new();
+        seed_admins(&database, &[ADMIN.to_string()]).unwrap();
+        ROLES.put(&database, MODERATOR.as_bytes(), &RoleGrant {
+            wallet: MODERATOR.to_string(),
+            role: Role::Moderator,
+            granted_by: ADMIN.to_string(),
+            granted_at: 0,
+        }).unwrap();
+        database
+    }
+
+    async fn status_for(app: &Router, wallet: Option<&str>) -> StatusCode {
+        let mut request = axum::http::Request::builder().uri("/moderate");
+        if let Some(wallet) = wallet {
+            let token = issue_session(wallet).unwrap().token;
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_restricted_route_checks_role() {
+        let database = database();
+        let component = ("/moderate".to_string(), Router::new().route("/moderate", get(|| async { "ok" })));
+        let (_, router) = restricted(component, &database, Role::Moderator);
+        let app = router.with_state(database);
+
+        assert_eq!(status_for(&app, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status_for(&app, Some(USER)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status_for(&app, Some(MODERATOR)).await, StatusCode::OK);
+        assert_eq!(status_for(&app, Some(ADMIN)).await, StatusCode::OK);
+    }
+
+    fn admin() -> Authorized<AdminOnly> {
+        Authorized { wallet: ADMIN.to_string(), role: Role::Admin, _required: PhantomData }
+    }
+
+    #[tokio::test]
+    async fn test_grant_and_revoke() {
+        let database = database();
+        assert_eq!(role_of(&database, USER).unwrap(), Role::User);
+
+        let Json(grant) = grant_role(State(database.clone()), admin(), Json(GrantRoleRequest { wallet: USER.to_string(), role: Role::Moderator })).await.unwrap();
+        assert_eq!(grant.granted_by, ADMIN);
+        assert_eq!(role_of(&database, USER).unwrap(), Role::Moderator);
+
+        let Json(RolesResponse { roles }) = list_roles(State(database.clone()), admin()).await.unwrap();
+        assert_eq!(roles.len(), 3);
+
+        assert_eq!(revoke_role(State(database.clone()), admin(), Json(RevokeRoleRequest { wallet: USER.to_string() })).await.unwrap(), StatusCode::NO_CONTENT);
+        assert_eq!(role_of(&database, USER).unwrap(), Role::User);
+
+        assert!(matches!(
+            revoke_role(State(database.clone()), admin(), Json(RevokeRoleRequest { wallet: ADMIN.to_string() })).await,
+            Err(RoleError::ValidationError(_))
+        ));
+        assert!(matches!(
+            grant_role(State(database), admin(), Json(GrantRoleRequest { wallet: "nope".to_string(), role: Role::Admin })).await,
+            Err(RoleError::ValidationError(_))
+        ));
+    }
+}
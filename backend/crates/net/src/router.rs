@@ -1,62 +1,110 @@
 use axum::{
-    routing::get, routing::post,
-    Router, handler::Handler
+    routing::get, routing::post, routing::put, routing::patch, routing::delete,
+    Router, handler::Handler, extract::DefaultBodyLimit
 };
+use tower_http::limit::RequestBodyLimitLayer;
 
-use std::fmt::Debug;
 
 
+// 컴포넌트 라우터들을 하나로 합치고 공유 상태를 주입한다.
+// 각 컴포넌트는 전체 경로로 라우트를 등록해 두므로 nest 가 아니라 merge 로 합친다.
+// (같은 경로에 GET/POST 를 따로 등록한 컴포넌트도 하나의 method router 로 합쳐진다)
+pub fn main_router<S>(components: Vec<(String, Router<S>)>, state: S) -> Router
+where
+    S: Clone + Send + Sync + 'static
+{
+    components
+        .into_iter()
+        .fold(Router::<S>::new(), |app, (_, router)| app.merge(router))
+        .with_state(state)
+}
+
 
-pub fn main_router<STATE>(components: Vec<(String, Router<STATE>)>, state: STATE) -> Router
+
+pub fn get_router_builder<T, S>(
+    path: String,
+    handler: impl Handler<T, S> + 'static
+) -> (String, Router<S>)
 where
-    STATE: Clone + Send + Sync + 'static
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
 {
-    let mut app = Router::<STATE>::new();
+    let router = Router::<S>::new().route(&path, get(handler));
+    (path, router)
+}
 
-    for (_, router) in components {
-        app = app.merge(router);
-    }
 
-    app.with_state(state)
+pub fn post_router_builder<T, S>(
+    path: String,
+    handler: impl Handler<T, S> + 'static
+) -> (String, Router<S>)
+where
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    let router = Router::<S>::new().route(&path, post(handler));
+    (path, router)
 }
 
 
+pub fn put_router_builder<T, S>(
+    path: String,
+    handler: impl Handler<T, S> + 'static
+) -> (String, Router<S>)
+where
+    T: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    let router = Router::<S>::new().route(&path, put(handler));
+    (path, router)
+}
 
-pub fn get_router_builder<T, S>(
+
+pub fn patch_router_builder<T, S>(
     path: String,
-    handler: impl Handler<T, S>  + Clone + Send + 'static
+    handler: impl Handler<T, S> + 'static
 ) -> (String, Router<S>)
 where
     T: 'static,
     S: Clone + Send + Sync + 'static,
 {
-    let mut app = Router::<S>::new();
-    let new_path = path.clone();
-    (path, app.route(&new_path, get(handler)))
+    let router = Router::<S>::new().route(&path, patch(handler));
+    (path, router)
 }
 
 
-pub fn post_router_builder<T, S>(
+pub fn delete_router_builder<T, S>(
     path: String,
-    handler: impl Handler<T, S>  + Clone + Send + 'static
+    handler: impl Handler<T, S> + 'static
 ) -> (String, Router<S>)
 where
     T: 'static,
     S: Clone + Send + Sync + 'static,
 {
-    let mut app = Router::<S>::new();
-    let new_path = path.clone();
-    (path, app.route(&new_path, post(handler)))
+    let router = Router::<S>::new().route(&path, delete(handler));
+    (path, router)
+}
+
+// 컴포넌트의 요청 본문 한도. Content-Length 가 넘으면 핸들러 전에 413 으로 끊고,
+// 길이를 모르는 본문도 추출기(Json, Multipart) 가 한도까지만 읽는다.
+pub fn body_limited<S>(component: (String, Router<S>), max_bytes: usize) -> (String, Router<S>)
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let (path, router) = component;
+    let router = router
+        .layer(DefaultBodyLimit::max(max_bytes))
+        .layer(RequestBodyLimitLayer::new(max_bytes));
+    (path, router)
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
-        response::IntoResponse,
-        routing::get,
         Router,
     };
     use tower::ServiceExt;
@@ -95,4 +143,47 @@ mod tests {
         println!("{:?}", response);
     }
 
+    #[derive(Clone)]
+    struct Greeting(Arc<String>);
+
+    async fn greet_handler(axum::extract::State(greeting): axum::extract::State<Greeting>) -> String {
+        greeting.0.to_string()
+    }
+
+    async fn send(app: &Router, method: &str, uri: &str, body: &str) -> (StatusCode, String) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_main_router_mounts_every_component() {
+        let components = vec![
+            get_router_builder("/api/greet".to_string(), greet_handler),
+            post_router_builder("/api/greet".to_string(), echo_handler),
+            get_router_builder("/api/hello".to_string(), hello_handler),
+        ];
+        let app = main_router(components, Greeting(Arc::new("hi from state".to_string())));
+
+        assert_eq!(send(&app, "GET", "/api/greet", "").await, (StatusCode::OK, "hi from state".to_string()));
+        assert_eq!(send(&app, "POST", "/api/greet", "ping").await, (StatusCode::OK, "ping".to_string()));
+        assert_eq!(send(&app, "GET", "/api/hello", "").await, (StatusCode::OK, "Hello, World!".to_string()));
+
+        assert_eq!(send(&app, "GET", "/api/missing", "").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(send(&app, "POST", "/api/hello", "").await.0, StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn test_main_router_without_components() {
+        let app = main_router(Vec::<(String, Router<()>)>::new(), ());
+        assert_eq!(send(&app, "GET", "/", "").await.0, StatusCode::NOT_FOUND);
+    }
+
 }
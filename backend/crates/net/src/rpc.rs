@@ -0,0 +1,293 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::error::Error as StdError;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use crate::config::app_config;
+use crate::telemetry::{current_request_id, REQUEST_ID_HEADER};
+
+// call 한 번이 엔드포인트를 바꿔 가며 시도하는 최대 횟수
+pub const MAX_ATTEMPTS: usize = 4;
+// 엔드포인트 하나가 이보다 오래 답하지 않으면 실패로 보고 다음으로 넘어간다
+pub const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// 시도 사이 대기: 100ms, 200ms, 400ms ... 최대 2초
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(100);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(2);
+// 연달아 실패한 엔드포인트는 1초, 2초, 4초 ... 최대 1분 동안 뒤로 미룬다
+const BASE_COOLDOWN: Duration = Duration::from_secs(1);
+const MAX_COOLDOWN: Duration = Duration::from_secs(60);
+// 건강 점수는 성공(1)/실패(0) 의 지수 이동 평균이다
+const SCORE_WEIGHT: f64 = 0.3;
+// 노드 자체가 뒤처졌거나 아프다는 JSON-RPC 에러. 요청이 아니라 엔드포인트 문제라서 다른 곳으로 넘긴다.
+const NODE_UNHEALTHY_CODE: i64 = -32005;
+
+#[derive(Debug)]
+pub struct RpcError(pub String);
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RPC error: {}", self.0)
+    }
+}
+
+impl StdError for RpcError {}
+
+// 엔드포인트별 지표 (/metrics). 키는 origin 만 남긴 라벨이라 URL 에 든 API 키가 나가지 않는다.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EndpointStats {
+    pub requests: u64,
+    pub failures: u64,
+    pub health: f64,
+    pub last_latency_secs: f64,
+}
+
+pub static RPC_METRICS: Mutex<BTreeMap<String, EndpointStats>> = Mutex::new(BTreeMap::new());
+
+pub fn endpoint_label(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed.origin().ascii_serialization(),
+        Err(_) => url.to_string(),
+    }
+}
+
+struct Health {
+    score: f64,
+    consecutive_failures: u32,
+    cooldown_until: Option<Instant>,
+}
+
+struct Endpoint {
+    url: String,
+    label: String,
+    health: Mutex<Health>,
+}
+
+impl Endpoint {
+    fn new(url: &str) -> Self {
+        let label = endpoint_label(url);
+        RPC_METRICS.lock().unwrap().entry(label.clone()).or_insert(EndpointStats { health: 1.0, ..Default::default() });
+        Self {
+            url: url.to_string(),
+            label,
+            health: Mutex::new(Health { score: 1.0, consecutive_failures: 0, cooldown_until: None }),
+        }
+    }
+
+    // 쉬는 중이 아닌 것, 점수가 높은 것 순서
+    fn rank(&self, now: Instant) -> (bool, f64) {
+        let health = self.health.lock().unwrap();
+        (health.cooldown_until.is_some_and(|until| until > now), health.score)
+    }
+
+    fn record(&self, ok: bool, latency: Duration) {
+        let score = {
+            let mut health = self.health.lock().unwrap();
+            health.score = health.score * (1.0 - SCORE_WEIGHT) + if ok { SCORE_WEIGHT } else { 0.0 };
+            match ok {
+                true => {
+                    health.consecutive_failures = 0;
+                    health.cooldown_until = None;
+                },
+                false => {
+                    health.consecutive_failures += 1;
+                    health.cooldown_until = Some(Instant::now() + cooldown(health.consecutive_failures));
+                },
+            }
+            health.score
+        };
+
+        let mut metrics = RPC_METRICS.lock().unwrap();
+        let stats = metrics.entry(self.label.clone()).or_default();
+        stats.requests += 1;
+        stats.failures += !ok as u64;
+        stats.health = score;
+        stats.last_latency_secs = latency.as_secs_f64();
+    }
+}
+
+fn cooldown(consecutive_failures: u32) -> Duration {
+    BASE_COOLDOWN.saturating_mul(1 << consecutive_failures.saturating_sub(1).min(16)).min(MAX_COOLDOWN)
+}
+
+fn retry_delay(attempt: usize) -> Duration {
+    BASE_RETRY_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16)).min(MAX_RETRY_DELAY)
+}
+
+// 엔드포인트 탓인 실패만 다른 엔드포인트로 다시 보낸다. 요청이 틀려서 난 JSON-RPC 에러는 어디로 보내도 같다.
+enum Failure {
+    Endpoint(String),
+    Rejected(String),
+}
+
+// 필요한 메서드만 쓰는 최소한의 Solana JSON-RPC 클라이언트.
+// 엔드포인트가 여러 개면 건강 점수가 높은 곳부터 쓰고, 실패하면 backoff 뒤에 다른 엔드포인트로 넘어간다.
+// 복제본끼리 건강 상태를 같이 쓴다.
+#[derive(Clone)]
+pub struct RpcClient {
+    http: reqwest::Client,
+    endpoints: Arc<Vec<Endpoint>>,
+}
+
+impl RpcClient {
+    // 앞에 있는 엔드포인트가 점수가 같을 때 먼저 쓰인다
+    pub fn with_endpoints(urls: &[String]) -> Self {
+        assert!(!urls.is_empty(), "RpcClient needs at least one endpoint");
+        Self {
+            http: reqwest::Client::new(),
+            endpoints: Arc::new(urls.iter().map(|url| Endpoint::new(url)).collect()),
+        }
+    }
+
+    // 이번 call 에서 아직 안 써 본 엔드포인트를 먼저 고른다. 다 써 봤으면 그중 가장 나은 곳을 다시 쓴다.
+    fn pick(&self, tried: &[usize]) -> usize {
+        let now = Instant::now();
+        let ranked = |index: &usize| {
+            let (cooling, score) = self.endpoints[*index].rank(now);
+            (tried.contains(index), cooling, -score, *index)
+        };
+        (0..self.endpoints.len())
+            .min_by(|a, b| ranked(a).partial_cmp(&ranked(b)).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or(0)
+    }
+
+    // result 필드를 돌려준다. 응답에 error 가 있으면 RpcError.
+    // 요청 처리 중이면 요청 ID 를 x-request-id 헤더로 같이 보내서 RPC 쪽 로그와 맞춰 볼 수 있게 한다.
+    #[tracing::instrument(name = "rpc", skip(self, params))]
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let mut tried = Vec::new();
+        let mut last_error = String::new();
+        for attempt in 0..MAX_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(retry_delay(attempt)).await;
+            }
+            let index = self.pick(&tried);
+            tried.push(index);
+            let endpoint = &self.endpoints[index];
+
+            let mut builder = self.http.post(&endpoint.url).timeout(RPC_REQUEST_TIMEOUT).json(&request);
+            if let Some(request_id) = current_request_id() {
+                builder = builder.header(REQUEST_ID_HEADER, request_id);
+            }
+
+            let started = Instant::now();
+            let result = send(builder).await.and_then(into_result);
+            let elapsed = started.elapsed();
+            let elapsed_ms = elapsed.as_millis() as u64;
+            match result {
+                Ok(value) => {
+                    endpoint.record(true, elapsed);
+                    tracing::debug!(endpoint = %endpoint.label, elapsed_ms, "rpc call");
+                    return Ok(value);
+                },
+                Err(Failure::Rejected(e)) => {
+                    endpoint.record(true, elapsed);
+                    tracing::warn!(endpoint = %endpoint.label, elapsed_ms, error = %e, "rpc call failed");
+                    return Err(RpcError(e));
+                },
+                Err(Failure::Endpoint(e)) => {
+                    endpoint.record(false, elapsed);
+                    tracing::warn!(endpoint = %endpoint.label, attempt, elapsed_ms, error = %e, "rpc endpoint failed");
+                    last_error = e;
+                },
+            }
+        }
+        Err(RpcError(last_error))
+    }
+}
+
+// 설정의 rpc_url 과 rpc_fallback_urls 로 한 번만 만든다. 인덱서, 체인 동기화, 트랜잭션 빌더가 같은 건강 상태를 본다.
+pub fn shared_rpc() -> RpcClient {
+    static SHARED_RPC: OnceLock<RpcClient> = OnceLock::new();
+    SHARED_RPC.get_or_init(|| RpcClient::with_endpoints(&app_config().rpc_endpoints())).clone()
+}
+
+// 연결 실패, HTTP 에러 상태, 깨진 본문은 모두 엔드포인트 탓으로 본다 (429 나 잘못된 API 키 포함)
+async fn send(builder: reqwest::RequestBuilder) -> Result<Value, Failure> {
+    builder
+        .send()
+        .await
+        .map_err(|e| Failure::Endpoint(e.to_string()))?
+        .error_for_status()
+        .map_err(|e| Failure::Endpoint(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| Failure::Endpoint(e.to_string()))
+}
+
+fn into_result(mut response: Value) -> Result<Value, Failure> {
+    if let Some(error) = response.get("error") {
+        return Err(match error["code"].as_i64() {
+            Some(NODE_UNHEALTHY_CODE) => Failure::Endpoint(error.to_string()),
+            _ => Failure::Rejected(error.to_string()),
+        });
+    }
+    Ok(response["result"].take())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    fn unwrap_err(result: Result<Value, Failure>) -> (bool, String) {
+        match result {
+            Err(Failure::Endpoint(e)) => (true, e),
+            Err(Failure::Rejected(e)) => (false, e),
+            Ok(value) => panic!("expected an error, got {}", value),
+        }
+    }
+
+    #[test]
+    fn test_into_result() {
+        let ok = json!({"jsonrpc": "2.0", "result": {"value": null}, "id": 1});
+        assert_eq!(into_result(ok).ok().unwrap(), json!({"value": null}));
+
+        let error = json!({"jsonrpc": "2.0", "error": {"code": -32602, "message": "Invalid param"}, "id": 1});
+        let (failover, message) = unwrap_err(into_result(error));
+        assert!(!failover && message.contains("Invalid param"));
+
+        let behind = json!({"jsonrpc": "2.0", "error": {"code": -32005, "message": "Node is behind by 42 slots"}, "id": 1});
+        assert!(unwrap_err(into_result(behind)).0);
+
+        assert_eq!(endpoint_label("https://rpc.example.com/v2/secret?api-key=abc"), "https://rpc.example.com");
+        assert_eq!((retry_delay(1), retry_delay(10)), (BASE_RETRY_DELAY, MAX_RETRY_DELAY));
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_healthy_endpoint() {
+        // 닫힌 포트 (연결이 거절된다)
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let down = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let up = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route("/", post(|| async { Json(json!({"jsonrpc": "2.0", "result": 42, "id": 1})) }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = RpcClient::with_endpoints(&[down.clone(), up.clone()]);
+        assert_eq!(client.call("getSlot", json!([])).await.unwrap(), 42);
+        let failures = |url: &str| RPC_METRICS.lock().unwrap()[&endpoint_label(url)].failures;
+        assert_eq!(failures(&down), 1);
+        assert!(client.endpoints[0].rank(Instant::now()).0);
+
+        // 실패한 엔드포인트는 쉬는 동안 건너뛴다
+        assert_eq!(client.call("getSlot", json!([])).await.unwrap(), 42);
+        assert_eq!(failures(&down), 1);
+        assert_eq!(client.pick(&[]), 1);
+
+        // 모두 죽으면 마지막 에러를 돌려준다
+        let client = RpcClient::with_endpoints(&[down]);
+        assert!(client.call("getSlot", json!([])).await.is_err());
+    }
+}
@@ -0,0 +1,411 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use crate::error::ApiError;
+
+// 작업 이름 -> 마지막 실행 기록. 재시작해도 주기를 이어 가고, 운영자가 /api/admin/jobs 로 본다.
+pub const JOB_RUNS: TypedTable<JobRun> = TypedTable::new("job_runs");
+
+// cron 식이 이 기간 안에 한 번도 맞지 않으면 (예: 2월 30일) 작업을 멈춘다
+const CRON_SEARCH_SECS: u64 = 5 * 366 * 86_400;
+
+#[derive(Debug, PartialEq)]
+pub enum SchedulerError {
+    InvalidSchedule(String),
+    DatabaseError(String),
+}
+
+impl fmt::Display for SchedulerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulerError::InvalidSchedule(msg) => write!(f, "Invalid schedule: {}", msg),
+            SchedulerError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for SchedulerError {}
+
+impl IntoResponse for SchedulerError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SchedulerError::InvalidSchedule(_) => StatusCode::BAD_REQUEST,
+            SchedulerError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobRun {
+    pub last_run_at: u64,
+    pub last_success_at: Option<u64>,
+    pub last_duration_ms: u64,
+    pub last_error: Option<String>,
+}
+
+// 프로세스가 뜬 뒤의 작업별 지표 (/metrics)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JobStats {
+    pub runs: u64,
+    pub failures: u64,
+    pub last_run_at: u64,
+    pub last_duration_secs: f64,
+}
+
+pub static JOB_METRICS: Mutex<BTreeMap<&'static str, JobStats>> = Mutex::new(BTreeMap::new());
+
+// 분 시 일 월 요일 (UTC). 각 칸은 *, */n, a, a-b, a-b/n 과 쉼표 목록을 받는다. 요일은 0(일)~6, 7 도 일요일.
+// 일과 요일이 둘 다 * 가 아니면 보통의 cron 처럼 둘 중 하나만 맞아도 된다.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u64>().map_err(|_| format!("bad step in {}", item))?),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    start.parse().map_err(|_| format!("bad value in {}", item))?,
+                    end.parse().map_err(|_| format!("bad value in {}", item))?,
+                ),
+                // "5/15" 는 5 부터 끝까지 15 간격
+                None => {
+                    let start = range.parse().map_err(|_| format!("bad value in {}", item))?;
+                    (start, if item.contains('/') { max } else { start })
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(format!("{} is outside {}-{}", item, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+// 1970-01-01 부터의 일 수 -> (월, 일)
+pub fn month_day(days: u64) -> (u64, u64) {
+    let z = days as i64 + 719_468;
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (month as u64, day as u64)
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, SchedulerError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(SchedulerError::InvalidSchedule(format!("expected 5 fields: {}", expr)));
+        };
+        let invalid = |e: String| SchedulerError::InvalidSchedule(format!("{}: {}", expr, e));
+        let mut weekdays = parse_field(weekday, 0, 7).map_err(invalid)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23).map_err(invalid)?,
+            days: parse_field(day, 1, 31).map_err(invalid)?,
+            months: parse_field(month, 1, 12).map_err(invalid)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    fn day_matches(&self, days: u64) -> bool {
+        let (month, day) = month_day(days);
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        // 1970-01-01 은 목요일
+        let day_ok = self.days & (1 << day) != 0;
+        let weekday_ok = self.weekdays & (1 << ((days + 4) % 7)) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day_ok || weekday_ok,
+            _ => day_ok && weekday_ok,
+        }
+    }
+
+    // after 보다 뒤의 첫 실행 시각 (분 단위로 맞춘 unix 초)
+    pub fn next_after(&self, after: u64) -> Option<u64> {
+        let mut t = (after / 60 + 1) * 60;
+        while t <= after + CRON_SEARCH_SECS {
+            let days = t / 86_400;
+            if !self.day_matches(days) {
+                t = (days + 1) * 86_400;
+            } else if self.hours & (1 << ((t % 86_400) / 3_600)) == 0 {
+                t = (t / 3_600 + 1) * 3_600;
+            } else if self.minutes & (1 << ((t % 3_600) / 60)) == 0 {
+                t += 60;
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Schedule {
+    Every(Duration),
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    // 마지막 실행 기록으로 다음 실행 시각을 정한다.
+    // Every 는 지난 실행에서 주기만큼 뒤 (이미 지났으면 바로), Cron 은 지금 다음으로 맞는 시각 (놓친 실행은 건너뛴다).
+    pub fn next_run(&self, last_run_at: Option<u64>, now: u64) -> Option<u64> {
+        match self {
+            Schedule::Every(interval) => Some(last_run_at.map_or(now, |last| last.saturating_add(interval.as_secs()).max(now))),
+            Schedule::Cron(cron) => cron.next_after(now),
+        }
+    }
+}
+
+type JobFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+struct Job<T> {
+    name: &'static str,
+    schedule: Schedule,
+    // 실행 시각마다 0~jitter 만큼 늦춰서 여러 인스턴스가 동시에 돌지 않게 한다
+    jitter: Duration,
+    run: Arc<dyn Fn(T) -> JobFuture + Send + Sync>,
+}
+
+// 주기 작업을 모아 두었다가 start 에서 작업마다 태스크 하나씩 띄운다.
+// 작업은 저장소 핸들을 받아서 Result<(), String> 을 돌려주는 async 함수이고, 실패는 로그와 지표에만 남는다.
+pub struct Scheduler<T> {
+    database: T,
+    jobs: Vec<Job<T>>,
+}
+
+impl<T: SafeDatabase> Scheduler<T> {
+    pub fn new(database: T) -> Self {
+        Self { database, jobs: Vec::new() }
+    }
+
+    pub fn register<F, Fut>(&mut self, name: &'static str, schedule: Schedule, jitter: Duration, run: F) -> &mut Self
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        self.jobs.push(Job {
+            name,
+            schedule,
+            jitter,
+            run: Arc::new(move |database| Box::pin(run(database))),
+        });
+        self
+    }
+
+    pub fn every<F, Fut>(&mut self, name: &'static str, interval: Duration, jitter: Duration, run: F) -> &mut Self
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        self.register(name, Schedule::Every(interval), jitter, run)
+    }
+
+    pub fn cron<F, Fut>(&mut self, name: &'static str, expr: &str, jitter: Duration, run: F) -> Result<&mut Self, SchedulerError>
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        Ok(self.register(name, Schedule::Cron(CronSchedule::parse(expr)?), jitter, run))
+    }
+
+    pub fn start(self) {
+        for job in self.jobs {
+            tokio::spawn(run_forever(self.database.clone(), job));
+        }
+    }
+}
+
+pub fn load_job_run<T: SafeDatabase>(database: &T, name: &str) -> Result<Option<JobRun>, SchedulerError> {
+    JOB_RUNS.get(database, name.as_bytes()).map_err(|e| SchedulerError::DatabaseError(e.to_string()))
+}
+
+async fn run_forever<T: SafeDatabase>(database: T, job: Job<T>) {
+    let mut last_run_at = match load_job_run(&database, job.name) {
+        Ok(run) => run.map(|run| run.last_run_at),
+        Err(e) => {
+            tracing::warn!(job = job.name, error = %e, "Could not read last job run");
+            None
+        }
+    };
+    loop {
+        let now = now_secs();
+        let Some(next) = job.schedule.next_run(last_run_at, now) else {
+            tracing::warn!(job = job.name, "Schedule never fires, job stopped");
+            return;
+        };
+        let jitter = match job.jitter.is_zero() {
+            true => Duration::ZERO,
+            false => Duration::from_millis(rand::thread_rng().gen_range(0..=job.jitter.as_millis() as u64)),
+        };
+        tokio::time::sleep(Duration::from_secs(next.saturating_sub(now)) + jitter).await;
+
+        let started_at = now_secs();
+        // 실패는 run_job 이 지표와 job_runs 에 남겼다. 다음 주기에 다시 돈다.
+        let _ = run_job(&database, &job, started_at).await;
+        last_run_at = Some(started_at);
+    }
+}
+
+// 한 번 돌리고 결과를 지표와 job_runs 에 남긴다
+async fn run_job<T: SafeDatabase>(database: &T, job: &Job<T>, started_at: u64) -> Result<(), String> {
+    let span = tracing::info_span!("job", job = job.name);
+    let started = Instant::now();
+    let result = span.in_scope(|| (job.run)(database.clone()));
+    let result = tracing::Instrument::instrument(result, span).await;
+    let elapsed = started.elapsed();
+
+    {
+        let mut metrics = JOB_METRICS.lock().unwrap();
+        let stats = metrics.entry(job.name).or_default();
+        stats.runs += 1;
+        stats.failures += result.is_err() as u64;
+        stats.last_run_at = started_at;
+        stats.last_duration_secs = elapsed.as_secs_f64();
+    }
+
+    let previous = load_job_run(database, job.name).ok().flatten().unwrap_or_default();
+    let run = JobRun {
+        last_run_at: started_at,
+        last_success_at: match result {
+            Ok(()) => Some(started_at),
+            Err(_) => previous.last_success_at,
+        },
+        last_duration_ms: elapsed.as_millis() as u64,
+        last_error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = JOB_RUNS.put(database, job.name.as_bytes(), &run) {
+        tracing::warn!(job = job.name, error = %e, "Could not record job run");
+    }
+    if let Err(e) = &result {
+        tracing::error!(job = job.name, error = %e, "Scheduled job failed");
+    }
+    result
+}
+
+#[derive(Serialize)]
+pub struct JobsResponse {
+    jobs: BTreeMap<String, JobRun>,
+}
+
+// 지금까지 기록된 작업별 마지막 실행
+pub async fn list_jobs<T: SafeDatabase>(
+    State(database): State<T>,
+) -> Result<Json<JobsResponse>, SchedulerError> {
+    let jobs = JOB_RUNS.scan(&database, &KeyRange::all())
+        .map_err(|e| SchedulerError::DatabaseError(e.to_string()))?
+        .into_iter()
+        .map(|(name, run)| (String::from_utf8_lossy(&name).to_string(), run))
+        .collect();
+    Ok(Json(JobsResponse { jobs }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    // 2024-03-01 00:00:00 UTC (금요일)
+    const MARCH_1_2024: u64 = 1_709_251_200;
+
+    #[test]
+    fn test_cron_next_after() {
+        let every_15 = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(every_15.next_after(MARCH_1_2024), Some(MARCH_1_2024 + 15 * 60));
+        assert_eq!(every_15.next_after(MARCH_1_2024 + 61), Some(MARCH_1_2024 + 15 * 60));
+
+        // 매일 03:30
+        let nightly = CronSchedule::parse("30 3 * * *").unwrap();
+        assert_eq!(nightly.next_after(MARCH_1_2024), Some(MARCH_1_2024 + 3 * 3_600 + 30 * 60));
+        assert_eq!(nightly.next_after(MARCH_1_2024 + 4 * 3_600), Some(MARCH_1_2024 + 86_400 + 3 * 3_600 + 30 * 60));
+
+        // 월요일 00:00 -> 3월 4일
+        let monday = CronSchedule::parse("0 0 * * 1").unwrap();
+        assert_eq!(monday.next_after(MARCH_1_2024), Some(MARCH_1_2024 + 3 * 86_400));
+        // 2월 29일은 윤년에만 있다
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(leap.next_after(MARCH_1_2024 - 86_400 - 1), Some(MARCH_1_2024 - 86_400));
+
+        assert_eq!(CronSchedule::parse("0 0 30 2 *").unwrap().next_after(MARCH_1_2024), None);
+        for bad in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "a * * * *"] {
+            assert!(CronSchedule::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_every_resumes_from_last_run() {
+        let schedule = Schedule::Every(Duration::from_secs(60));
+        assert_eq!(schedule.next_run(None, 1_000), Some(1_000));
+        assert_eq!(schedule.next_run(Some(980), 1_000), Some(1_040));
+        // 꺼져 있는 동안 주기가 지났으면 바로 돈다
+        assert_eq!(schedule.next_run(Some(100), 1_000), Some(1_000));
+    }
+
+    #[tokio::test]
+    async fn test_run_is_recorded() {
+        let database = MemoryDatabase::new();
+        let mut scheduler = Scheduler::new(database.clone());
+        scheduler.every("test_flaky", Duration::from_secs(60), Duration::ZERO, |database: MemoryDatabase| async move {
+            match database.read("fail", "flags").map_err(|e| e.to_string())? {
+                Some(_) => Err("told to fail".to_string()),
+                None => Ok(()),
+            }
+        });
+        let job = &scheduler.jobs[0];
+
+        assert!(run_job(&database, job, 100).await.is_ok());
+        database.write("fail", "1", "flags").unwrap();
+        assert!(run_job(&database, job, 200).await.is_err());
+
+        let run = load_job_run(&database, "test_flaky").unwrap().unwrap();
+        assert_eq!((run.last_run_at, run.last_success_at), (200, Some(100)));
+        assert_eq!(run.last_error.as_deref(), Some("told to fail"));
+
+        let stats = JOB_METRICS.lock().unwrap()["test_flaky"].clone();
+        assert_eq!((stats.runs, stats.failures, stats.last_run_at), (2, 1, 200));
+
+        let Json(response) = list_jobs(State(database)).await.unwrap();
+        assert_eq!(response.jobs["test_flaky"], run);
+    }
+}
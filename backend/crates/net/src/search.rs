@@ -0,0 +1,431 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error as StdError;
+use std::fmt;
+use tokio::sync::broadcast::error::RecvError;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::{KeyBuilder, KeyReader};
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::typed::TypedTable;
+use turtle_database::watch::ChangeFeed;
+use turtle_service::parser::chain::{ChainContent, ChainProposal};
+use turtle_service::parser::community::DaoInfo;
+use turtle_service::parser::profile::Profile;
+use turtle_service::parser::search::{SearchDocument, SearchKind};
+use crate::dao::DAO_REGISTRY;
+use crate::profile::PROFILES;
+use crate::reports::{is_hidden, HIDDEN_CONTENTS};
+use crate::sync::{CHAIN_CONTENTS, CHAIN_PROPOSALS};
+use crate::error::ApiError;
+
+// (원본 테이블, 원본 키) -> SearchDocument
+pub const SEARCH_DOCS: TypedTable<SearchDocument> = TypedTable::new("search_docs");
+// (토큰, 문서 키) -> 가중치. 토큰 하나로 prefix 스캔하면 그 토큰이 들어간 문서가 모두 나온다.
+pub const SEARCH_POSTINGS: TypedTable<u32> = TypedTable::new("search_postings");
+
+// 제목 토큰은 본문보다, #태그는 그냥 단어보다 무겁게 센다
+const TITLE_WEIGHT: u32 = 3;
+const TAG_WEIGHT: u32 = 2;
+const BODY_WEIGHT: u32 = 1;
+
+const MAX_TOKENS_PER_DOC: usize = 256;
+const MAX_QUERY_TOKENS: usize = 8;
+const SNIPPET_CHARS: usize = 160;
+
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: String,
+    kind: Option<SearchKind>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    kind: SearchKind,
+    id: String,
+    title: String,
+    snippet: String,
+    dao: Option<String>,
+    score: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    total: usize,
+    results: Vec<SearchHit>,
+}
+
+#[derive(Debug)]
+pub enum SearchError {
+    DatabaseError(String),
+    ValidationError(String),
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            SearchError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+        }
+    }
+}
+
+impl StdError for SearchError {}
+
+impl IntoResponse for SearchError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SearchError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SearchError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        };
+
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// 글자/숫자가 아닌 문자로 자르고 소문자로 바꾼다. 한 글자짜리는 버린다.
+// "#" 으로 시작하는 단어는 태그로 본다.
+fn words(text: &str) -> impl Iterator<Item = (String, bool)> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '#'))
+        .filter_map(|word| {
+            let is_tag = word.starts_with('#');
+            let token = word.trim_matches('#').to_lowercase();
+            (token.chars().count() >= 2 && !token.contains('#')).then_some((token, is_tag))
+        })
+}
+
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = words(text).map(|(token, _)| token).collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+// 토큰 -> 가중치 합
+fn weigh(title: &str, body: &str) -> BTreeMap<String, u32> {
+    let mut weights = BTreeMap::new();
+    for (token, _) in words(title) {
+        *weights.entry(token).or_insert(0) += TITLE_WEIGHT;
+    }
+    for (token, is_tag) in words(body) {
+        *weights.entry(token).or_insert(0) += if is_tag { TAG_WEIGHT } else { BODY_WEIGHT };
+    }
+    // 너무 긴 글은 가중치가 큰 토큰만 남긴다
+    if weights.len() > MAX_TOKENS_PER_DOC {
+        let mut ranked: Vec<_> = weights.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(MAX_TOKENS_PER_DOC);
+        weights = ranked.into_iter().collect();
+    }
+    weights
+}
+
+// 색인하는 원본 테이블. 이 테이블에 쓰기가 일어나면 그 키만 다시 색인한다.
+fn source_tables() -> [&'static str; 4] {
+    [CHAIN_CONTENTS.name(), CHAIN_PROPOSALS.name(), PROFILES.name(), DAO_REGISTRY.name()]
+}
+
+fn snippet(text: &str) -> String {
+    text.chars().take(SNIPPET_CHARS).collect()
+}
+
+fn doc_key(table: &str, key: &[u8]) -> Vec<u8> {
+    KeyBuilder::new().str(table).bytes(key).build()
+}
+
+fn posting_key(token: &str, doc_key: &[u8]) -> Vec<u8> {
+    KeyBuilder::new().str(token).bytes(doc_key).build()
+}
+
+fn decode<V: serde::de::DeserializeOwned>(bytes: Option<Vec<u8>>) -> Option<V> {
+    bytes.and_then(|bytes| serde_json::from_slice(&bytes).ok())
+}
+
+// 문서와 토큰 -> 가중치
+type WeightedDocument = (SearchDocument, BTreeMap<String, u32>);
+
+// 원본 레코드를 문서로 바꾼다. 원본이 없거나 모더레이터가 숨긴 콘텐츠면 None
+fn build_document<T: SafeDatabase>(database: &T, table: &str, key: &[u8]) -> Result<Option<WeightedDocument>, SearchError> {
+    if table == CHAIN_CONTENTS.name() && is_hidden(database, key).map_err(|e| SearchError::DatabaseError(e.to_string()))? {
+        return Ok(None);
+    }
+    let bytes = database.get(table, key)
+        .map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+
+    let document = match table {
+        table if table == CHAIN_CONTENTS.name() => decode::<ChainContent>(bytes).map(|content| {
+            let weights = weigh("", &content.text);
+            (SearchDocument {
+                kind: SearchKind::Content,
+                id: format!("{}/{}", content.dao, content.index),
+                title: snippet(content.text.lines().next().unwrap_or("")),
+                snippet: snippet(&content.text),
+                dao: Some(content.dao),
+                tokens: Vec::new(),
+            }, weights)
+        }),
+        table if table == CHAIN_PROPOSALS.name() => decode::<ChainProposal>(bytes).map(|proposal| {
+            let weights = weigh(&proposal.title, &proposal.description);
+            (SearchDocument {
+                kind: SearchKind::Proposal,
+                id: format!("{}/{}", proposal.dao, proposal.proposal_id),
+                title: proposal.title,
+                snippet: snippet(&proposal.description),
+                dao: Some(proposal.dao),
+                tokens: Vec::new(),
+            }, weights)
+        }),
+        table if table == PROFILES.name() => decode::<Profile>(bytes).map(|profile| {
+            let weights = weigh(&profile.display_name, &profile.bio);
+            (SearchDocument {
+                kind: SearchKind::Profile,
+                id: profile.wallet,
+                title: profile.display_name,
+                snippet: snippet(&profile.bio),
+                dao: None,
+                tokens: Vec::new(),
+            }, weights)
+        }),
+        table if table == DAO_REGISTRY.name() => decode::<DaoInfo>(bytes).map(|info| {
+            let weights = weigh(&info.name, &info.description);
+            (SearchDocument {
+                kind: SearchKind::Dao,
+                id: info.pubkey.clone(),
+                title: info.name,
+                snippet: snippet(&info.description),
+                dao: Some(info.pubkey),
+                tokens: Vec::new(),
+            }, weights)
+        }),
+        _ => None,
+    };
+
+    Ok(document.map(|(mut document, weights)| {
+        document.tokens = weights.keys().cloned().collect();
+        (document, weights)
+    }))
+}
+
+// 원본 키 하나를 다시 색인한다. 이전 토큰을 지우고 새 토큰을 쓰는 것까지 한 트랜잭션이다.
+pub fn reindex<T: SafeDatabase>(database: &T, table: &str, key: &[u8]) -> Result<(), SearchError> {
+    let doc_key = doc_key(table, key);
+    let mut ops: Vec<KvOp> = Vec::new();
+
+    let previous = SEARCH_DOCS.get(database, &doc_key)
+        .map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+    if let Some(previous) = previous {
+        ops.extend(previous.tokens.iter().map(|token| SEARCH_POSTINGS.delete_op(&posting_key(token, &doc_key))));
+        ops.push(SEARCH_DOCS.delete_op(&doc_key));
+    }
+
+    if let Some((document, weights)) = build_document(database, table, key)? {
+        for (token, weight) in &weights {
+            ops.push(SEARCH_POSTINGS.put_op(&posting_key(token, &doc_key), weight)
+                .map_err(|e| SearchError::DatabaseError(e.to_string()))?);
+        }
+        ops.push(SEARCH_DOCS.put_op(&doc_key, &document)
+            .map_err(|e| SearchError::DatabaseError(e.to_string()))?);
+    }
+
+    if ops.is_empty() {
+        return Ok(());
+    }
+    database.txn(&ops)
+        .map_err(|e| SearchError::DatabaseError(e.to_string()))
+}
+
+// 모든 원본 레코드와, 원본이 사라졌을 수 있는 기존 문서를 다시 색인한다
+pub fn rebuild_search_index<T: SafeDatabase>(database: &T) -> Result<usize, SearchError> {
+    let mut sources: Vec<(String, Vec<u8>)> = Vec::new();
+    for table in source_tables() {
+        let keys = database.scan(table, &KeyRange::all())
+            .map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+        sources.extend(keys.into_iter().map(|(key, _)| (table.to_string(), key)));
+    }
+    let indexed = SEARCH_DOCS.scan(database, &KeyRange::all())
+        .map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+    for (key, _) in indexed {
+        let mut reader = KeyReader::new(&key);
+        let (Ok(table), Ok(source_key)) = (reader.str(), reader.bytes()) else {
+            continue;
+        };
+        sources.push((table.to_string(), source_key.to_vec()));
+    }
+
+    sources.sort();
+    sources.dedup();
+    for (table, key) in &sources {
+        reindex(database, table, key)?;
+    }
+    Ok(sources.len())
+}
+
+// 시작할 때 한 번 전체를 색인하고, 이후에는 원본 테이블의 쓰기 알림마다 그 키만 다시 색인한다
+pub fn spawn_search_indexer<T: SafeDatabase + ChangeFeed>(database: T) {
+    tokio::spawn(async move {
+        let mut changes = database.subscribe();
+        if let Err(e) = rebuild_search_index(&database) {
+            tracing::error!(error = %e, "Search index rebuild failed");
+        }
+        loop {
+            let result = match changes.recv().await {
+                Ok(change) if source_tables().contains(&change.table.as_str()) => reindex(&database, &change.table, &change.key),
+                // 숨기거나 되돌린 콘텐츠는 키가 같은 chain_contents 문서를 다시 만든다
+                Ok(change) if change.table == HIDDEN_CONTENTS.name() => reindex(&database, CHAIN_CONTENTS.name(), &change.key),
+                Ok(_) => Ok(()),
+                // 알림을 놓쳤으면 전체를 다시 맞춘다
+                Err(RecvError::Lagged(_)) => rebuild_search_index(&database).map(|_| ()),
+                Err(RecvError::Closed) => break,
+            };
+            if let Err(e) = result {
+                tracing::error!(error = %e, "Search indexing failed");
+            }
+        }
+    });
+}
+
+// 문서 키 -> (맞은 토큰 수, 점수)
+fn score<T: SafeDatabase>(database: &T, tokens: &[String]) -> Result<HashMap<Vec<u8>, (usize, u32)>, SearchError> {
+    let mut scores: HashMap<Vec<u8>, (usize, u32)> = HashMap::new();
+    for token in tokens {
+        let postings = SEARCH_POSTINGS.scan(database, &KeyRange::prefix(KeyBuilder::new().str(token).build()))
+            .map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+        for (key, weight) in postings {
+            let mut reader = KeyReader::new(&key);
+            reader.str().map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+            let doc_key = reader.bytes().map_err(|e| SearchError::DatabaseError(e.to_string()))?;
+            let entry = scores.entry(doc_key.to_vec()).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 += weight;
+        }
+    }
+    Ok(scores)
+}
+
+// 검색어 토큰을 더 많이 포함한 문서가 먼저, 그다음 가중치 합이 큰 순서
+pub async fn search<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, SearchError> {
+    let mut tokens = tokenize(&query.q);
+    if tokens.is_empty() {
+        return Err(SearchError::ValidationError("q must contain at least one word of two or more characters".to_string()));
+    }
+    tokens.truncate(MAX_QUERY_TOKENS);
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+
+    let mut ranked: Vec<(Vec<u8>, (usize, u32))> = score(&database, &tokens)?.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let load = |doc_key: &[u8]| SEARCH_DOCS.get(&database, doc_key).map_err(|e| SearchError::DatabaseError(e.to_string()));
+    let to_hit = |document: SearchDocument, score: u32| SearchHit {
+        kind: document.kind,
+        id: document.id,
+        title: document.title,
+        snippet: document.snippet,
+        dao: document.dao,
+        score,
+    };
+
+    // 종류로 거를 때는 문서를 읽어 봐야 하므로 후보 전체를 읽는다
+    let (total, results) = match query.kind {
+        Some(kind) => {
+            let mut hits = Vec::new();
+            for (doc_key, (_, score)) in &ranked {
+                if let Some(document) = load(doc_key)?.filter(|document| document.kind == kind) {
+                    hits.push(to_hit(document, *score));
+                }
+            }
+            let total = hits.len();
+            (total, hits.into_iter().skip(offset).take(limit).collect())
+        }
+        None => {
+            let mut hits = Vec::new();
+            for (doc_key, (_, score)) in ranked.iter().skip(offset).take(limit) {
+                if let Some(document) = load(doc_key)? {
+                    hits.push(to_hit(document, *score));
+                }
+            }
+            (ranked.len(), hits)
+        }
+    };
+
+    Ok(Json(SearchResponse { total, results }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    fn content(dao: &str, index: u64, text: &str) -> ChainContent {
+        ChainContent {
+            dao: dao.to_string(),
+            index,
+            author: "alice".to_string(),
+            text: text.to_string(),
+            image_uri: String::new(),
+            timestamp: 0,
+            vote_count: 0,
+            round: 0,
+        }
+    }
+
+    fn put_content(database: &MemoryDatabase, record: &ChainContent) -> Vec<u8> {
+        let key = KeyBuilder::new().str(&record.dao).u64(record.index).build();
+        CHAIN_CONTENTS.put(database, &key, record).unwrap();
+        key
+    }
+
+    async fn run(database: &MemoryDatabase, query: &str) -> SearchResponse {
+        let Query(query) = Query::try_from_uri(&format!("/?{}", query).parse().unwrap()).unwrap();
+        search(State(database.clone()), Query(query)).await.unwrap().0
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Hello, World! a #Rust 거북이"), ["hello", "rust", "world", "거북이"]);
+        assert!(tokenize("a b !").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_and_reindexes() {
+        let database = MemoryDatabase::new();
+        put_content(&database, &content("dao", 0, "turtle race tonight"));
+        let key = put_content(&database, &content("dao", 1, "slow and steady #turtle wins the race"));
+        let profile = Profile {
+            display_name: "Turtle Fan".to_string(),
+            bio: "likes races".to_string(),
+            ..Profile::empty("wallet")
+        };
+        PROFILES.put(&database, b"wallet", &profile).unwrap();
+        assert_eq!(rebuild_search_index(&database).unwrap(), 3);
+
+        // 이름에 들어간 토큰(제목 가중치)이 먼저, 그다음 태그, 본문 순서
+        let response = run(&database, "q=turtle").await;
+        assert_eq!(response.total, 3);
+        let ids: Vec<_> = response.results.iter().map(|hit| hit.id.as_str()).collect();
+        assert_eq!(ids, ["wallet", "dao/1", "dao/0"]);
+
+        // 검색어 토큰을 모두 포함한 문서가 우선
+        let response = run(&database, "q=turtle+race&kind=content&limit=1").await;
+        assert_eq!(response.total, 2);
+        assert_eq!(response.results.len(), 1);
+
+        // 원본이 바뀌면 이전 토큰은 빠진다
+        put_content(&database, &content("dao", 1, "hare wins"));
+        reindex(&database, CHAIN_CONTENTS.name(), &key).unwrap();
+        assert_eq!(run(&database, "q=steady").await.total, 0);
+        assert_eq!(run(&database, "q=hare").await.results[0].id, "dao/1");
+    }
+}
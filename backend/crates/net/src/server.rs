@@ -1,74 +1,410 @@
-use axum::{http, Router};
+use axum::extract::DefaultBodyLimit;
+use axum::middleware;
+use axum::Router;
 use crate::router::*;
 use crate::profile::*;
 use crate::community::*;
-use turtle_database::basic_db::{SafeDatabase, InnerDatabase};
-use tower_http::cors::{Any, CorsLayer};
+use crate::admin::*;
+use crate::auth::*;
+use crate::session::{logout, refresh_session};
+use crate::roles::*;
+use crate::ratelimit::rate_limited;
+use crate::tx::*;
+use crate::relay::{get_relayer, relay_transaction};
+use crate::faucet::request_faucet;
+use crate::verification::verify_profile;
+use crate::sns::{register_sns_job, reverse_lookup};
+use crate::ws::{dao_stream, proposal_stream};
+use crate::chat::{chat_stream, list_chat_messages, send_chat_message};
+use crate::events::{list_events, stream_events};
+use crate::earnings::get_wallet_earnings;
+use crate::analytics::{get_dao_analytics, register_analytics_job, ANALYTICS_TABLES};
+use crate::leaderboard::{get_leaderboard, register_leaderboard_job, LEADERBOARD_TABLES};
+use crate::ranking::register_ranking_job;
+use crate::search::{search, spawn_search_indexer};
+use crate::notifications::{get_unread_count, list_notifications, mark_read, spawn_notifier};
+use crate::reports::{act_on_report, list_moderation_audit, list_reports, report_content};
+use crate::moderation::{get_moderation, spawn_moderation_worker, HttpModerationProvider, ModerationConfig};
+use crate::bridge::{spawn_bridge, BridgeConfig};
+use crate::webhooks::{create_webhook, delete_webhook, list_deliveries, list_webhooks, register_webhook_job, update_webhook};
+use crate::content::*;
+use crate::preview::get_content_preview;
+use crate::gateway::{block_media, get_media, unblock_media};
+use crate::upload::{get_upload, upload_content, MAX_IMAGE_BYTES, MAX_TEXT_BYTES};
+use crate::dao::*;
+use crate::indexer::{spawn_indexer, INDEXER_METRICS};
+use crate::rpc::shared_rpc;
+use crate::countdown::{register_countdown_job, KeeperConfig};
+use crate::scheduler::{list_jobs, Scheduler};
+use crate::sync::spawn_chain_sync;
+#[cfg(feature = "grpc")]
+use crate::grpc::spawn_grpc_server;
+use crate::config::{install, AppConfig, RateLimits};
+use crate::tenant::{install_tenants, mount_tenant, with_tenant, Tenant};
+use crate::telemetry::{init_logging, trace_requests};
+use crate::metrics::{get_metrics, track_requests};
+use crate::health::{healthz, readyz};
+use crate::cors::cors_layer;
+use crate::error::{normalize_errors, DEFAULT_BODY_LIMIT};
+use crate::tls::{load_rustls, spawn_cert_reload, spawn_https_redirect};
+use crate::etag::etag_cached;
+use crate::openapi::{ApiDoc, OPENAPI_JSON_PATH, SWAGGER_UI_PATH};
+use turtle_database::basic_db::{DatabaseBackend, SafeDatabase};
+use turtle_database::migrations::run_migrations;
+use turtle_database::ttl::purge_all_expired;
+use turtle_database::watch::{ChangeFeed, TableVersions, Watched};
+use turtle_database::history::History;
+use turtle_database::encryption::Encrypted;
+use turtle_database::cache::Cached;
+use turtle_database::trace::Traced;
+use turtle_database::tenant::Tenanted;
+use std::net::SocketAddr;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+use tower_http::compression::CompressionLayer;
+use std::time::Duration;
+
+// 만료된 TTL 항목(세션, nonce 등)을 정리하는 주기
+const TTL_PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+// 서버가 사용하는 저장소 백엔드. 핸들러들은 SafeDatabase 트레이트에만 의존한다.
+// Watched 로 감싸서 쓰기가 일어날 때마다 ChangeFeed 구독자에게 알림이 가고,
+// History 로 감싸서 프로필/콘텐츠의 이전 버전이 history 테이블에 남는다.
+// Cached 는 자주 읽히는 키를 메모리에 들고 있고,
+// Traced 는 캐시를 지나 저장소까지 내려간 호출마다 db span 을 남기고,
+// TURTLE_DB_KEY 가 설정되어 있으면 Encrypted 가 민감한 테이블의 값을 암호화해서 저장한다.
+type Layers<S> = Watched<History<Cached<Traced<Encrypted<S>>>>>;
+type Backend = Layers<Store>;
+
+// 맨 아래의 저장소. 백업도 이 형식의 데이터베이스로 남긴다.
+#[cfg(all(feature = "mdbx", not(feature = "sled")))]
+pub type Store = turtle_database::mdbx_db::InnerDatabase;
+#[cfg(feature = "sled")]
+pub type Store = turtle_database::sled_db::SledDatabase;
+#[cfg(not(any(feature = "mdbx", feature = "sled")))]
+compile_error!("turtle-net needs a storage backend: enable the mdbx or sled feature");
 
 pub async fn build_server() {
-    let shared_state = InnerDatabase::new(".").unwrap();
-    let shared_state2 = Clone::clone(&shared_state);
-    let components = collect_components();
+    // 설정 파일(TURTLE_CONFIG, 기본 turtle.toml) 과 TURTLE_* 환경 변수. 잘못된 값이 있으면 띄우지 않는다.
+    let config = match AppConfig::load() {
+        Ok(config) => install(config),
+        Err(e) => panic!("{}", e),
+    };
+    init_logging(config.log_format, &config.log_level);
+
+    let shared_state = Backend::open(&config.database_path).unwrap();
+    run_migrations(&shared_state).unwrap();
+
+    // TURTLE_ADMIN_WALLETS 의 지갑들을 admin 으로 넣어 둔다 (역할 부여 API 를 쓰려면 admin 이 한 명은 있어야 한다)
+    seed_admins(&shared_state, &admin_wallets_from_env()).unwrap();
+
+    // 등록된 DAO 계정을 주기적으로 RPC 에서 읽어 DB 에 스냅샷으로 남긴다 (요청 경로에서는 RPC 를 부르지 않는다)
+    let sync_config = config.sync();
+    spawn_chain_sync(shared_state.clone(), shared_rpc(), sync_config.interval);
+
+    // program_id 가 설정돼 있으면 프로그램 로그를 이벤트 테이블로 인덱싱한다
+    match config.indexer() {
+        Some(indexer_config) => spawn_indexer(shared_state.clone(), shared_rpc(), indexer_config, &INDEXER_METRICS),
+        None => tracing::warn!("program_id is not configured, program log indexer disabled"),
+    }
+
+    // 콘텐츠/제안/프로필/DAO 검색 색인. 쓰기 알림을 받아 바뀐 레코드만 다시 색인한다.
+    spawn_search_indexer(shared_state.clone());
+    // 인덱싱된 이벤트와 제안 실행을 지갑별 인박스 알림으로 바꾼다
+    spawn_notifier(shared_state.clone());
+    // Discord 웹훅이나 Telegram 봇이 설정돼 있으면 새 라운드/카운트다운 임박/우승을 채팅방에 올린다
+    match BridgeConfig::from_env() {
+        Some(bridge_config) => spawn_bridge(shared_state.clone(), bridge_config),
+        None => tracing::warn!("{} / {} are not set, chat bridge disabled", crate::bridge::DISCORD_WEBHOOKS_ENV, crate::bridge::TELEGRAM_BOT_TOKEN_ENV),
+    }
+    // ai_moderation 이 켜진 DAO 의 새 콘텐츠를 모더레이션 제공자에게 보내 판정을 남긴다
+    match ModerationConfig::from_env() {
+        Some(moderation_config) => spawn_moderation_worker(shared_state.clone(), HttpModerationProvider::new(&moderation_config), moderation_config.threshold),
+        None => tracing::warn!("{} is not set, AI moderation worker disabled", crate::moderation::MODERATION_URL_ENV),
+    }
+
+    // 주기 작업은 스케줄러가 돌린다. 마지막 실행은 job_runs 에 남아서 재시작해도 주기를 이어 간다.
+    let mut scheduler = Scheduler::new(shared_state.clone());
+    // 만료된 TTL 키를 지운다
+    scheduler.every("ttl_purge", TTL_PURGE_INTERVAL, Duration::from_secs(5), |database| async move {
+        purge_all_expired(&database).map(|_| ()).map_err(|e| e.to_string())
+    });
+    // 인덱싱된 이벤트로 DAO 별 리더보드를 주기적으로 다시 계산한다
+    register_leaderboard_job(&mut scheduler);
+    // 인덱싱된 이벤트를 DAO 별 하루 집계(활동 지갑, 예치, 상금 풀) 로 굴려 넣는다
+    register_analytics_job(&mut scheduler);
+    // 콘텐츠별 투표/보상을 모아 /api/contents 의 trending/top 랭킹 테이블을 다시 쓴다
+    register_ranking_job(&mut scheduler);
+    // 응답에 붙일 .sol 이름 중 캐시에 없던 지갑을 SNS 프록시에서 찾아 채운다 (이름 캐시는 배포끼리 함께 쓴다)
+    register_sns_job(&mut scheduler);
+    // Claim/새 제안/타이머 만료 임박을 등록된 웹훅으로 서명해서 보낸다 (실패하면 backoff 로 재시도)
+    register_webhook_job(&mut scheduler);
+    // 마감이 지난 라운드를 claimable 로 표시하고, keeper 키가 있으면 ProcessTimeout 도 대신 보낸다
+    let keeper = KeeperConfig::from_env();
+    if keeper.is_none() {
+        tracing::warn!("{} is not set, keeper crank disabled", crate::countdown::KEEPER_KEYPAIR_ENV);
+    }
+    register_countdown_job(&mut scheduler, keeper);
+    // backup_schedule 이 설정돼 있으면 그 cron 식마다 백업을 만든다 (식은 설정을 읽을 때 검증했다)
+    if let Some(schedule) = &config.backup_schedule {
+        register_backup_job(&mut scheduler, schedule).unwrap();
+    }
+    scheduler.start();
+
+    // 분석 파이프라인 같은 내부 소비자에게 이벤트 구독과 DAO/콘텐츠 조회를 gRPC 로 내보낸다
+    match &config.grpc_bind_addr {
+        #[cfg(feature = "grpc")]
+        Some(addr) => spawn_grpc_server(shared_state.clone(), addr.parse().unwrap()),
+        #[cfg(not(feature = "grpc"))]
+        Some(_) => tracing::warn!("grpc_bind_addr is set but the server was built without the grpc feature"),
+        None => {}
+    }
 
+    // [[tenants]] 의 배포마다 같은 저장소 안의 따로 된 테이블에서 sync/인덱서/주기 작업을 돌리고 /api/{cluster}/... 로 내보낸다
+    let tenant_apps: Vec<(&'static Tenant, Router)> = install_tenants(config)
+        .iter()
+        .map(|tenant| {
+            let database = tenant.database(shared_state.clone());
+            start_tenant(&database, tenant, config);
+            let components = collect_components::<Tenanted<Backend>>(&database, &config.rate_limits);
+            (tenant, main_router(components, database))
+        })
+        .collect();
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods([
-            http::Method::GET,
-            http::Method::POST,
-            http::Method::PUT,
-            http::Method::DELETE,
-            http::Method::OPTIONS
-        ])
-        .allow_headers(Any)
-        .allow_credentials(false);
+    let components = collect_components::<Backend>(&shared_state, &config.rate_limits);
+
+    // 허용할 origin/메서드/자격 증명은 [cors] 설정과 preset 을 따른다
+    let cors = cors_layer(&config.cors);
 
 
 
 
     // Use just one type parameter
-    let mut app = main_router(components, shared_state);
+    let app = main_router(components, shared_state);
+    let app = tenant_apps.into_iter().fold(app, |app, (tenant, tenant_app)| mount_tenant(app, tenant, tenant_app));
 
-    let app = app.layer(cors);
+    // 요청마다 request span (method/path/상태 코드/걸린 시간) 과 x-request-id 를 붙인다
+    // /metrics 용 요청 수/지연 시간은 라우트 패턴별로 센다
+    // 응답은 Accept-Encoding 에 따라 gzip/br 로 압축한다 (작은 응답, 이미지, SSE 는 그대로 둔다)
+    // 한도를 따로 주지 않은 라우트의 본문은 DEFAULT_BODY_LIMIT 까지, 에러 응답은 모두 {code, message, details} JSON 으로 내보낸다
+    let app = app
+        .layer(DefaultBodyLimit::max(DEFAULT_BODY_LIMIT))
+        .layer(middleware::from_fn(normalize_errors))
+        .layer(middleware::from_fn(track_requests))
+        .layer(middleware::from_fn(trace_requests))
+        .layer(CompressionLayer::new().gzip(true).br(true))
+        .layer(cors);
 
 
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // 요청 한도를 IP 별로 세려면 연결 정보가 필요하다
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
+    match &config.tls {
+        // [tls] 가 있으면 rustls 로 직접 받는다. 인증서는 SIGHUP 으로 다시 읽고, redirect_addr 의 평문 요청은 https 로 보낸다.
+        Some(tls) => {
+            let bind_addr: SocketAddr = config.bind_addr.parse().unwrap();
+            let rustls = load_rustls(tls).await;
+            spawn_cert_reload(rustls.clone(), tls.clone());
+            if let Some(redirect_addr) = &tls.redirect_addr {
+                spawn_https_redirect(redirect_addr.parse().unwrap(), bind_addr.port());
+            }
+            tracing::info!(bind_addr = %config.bind_addr, "server listening (TLS)");
+            axum_server::bind_rustls(bind_addr, rustls).serve(app).await.unwrap();
+        },
+        None => {
+            let listener = tokio::net::TcpListener::bind(&config.bind_addr).await.unwrap();
+            tracing::info!(bind_addr = %config.bind_addr, "server listening");
+            axum::serve(listener, app).await.unwrap();
+        },
+    }
 }
 
 
 
-fn collect_components() ->  Vec<(String,Router<InnerDatabase>)> {
-    let router_profile_post = post_router_builder("/api/profile".to_string(),profile_write::<InnerDatabase>);
-    let router_profile_get = get_router_builder("/api/profile".to_string(),get_profile_by_address::<InnerDatabase>);
+// 배포 하나의 백그라운드 작업. 채팅 브리지와 TTL 정리, 백업은 기본 배포에서만 돈다.
+fn start_tenant(database: &Tenanted<Backend>, tenant: &'static Tenant, config: &AppConfig) {
+    run_migrations(database).unwrap();
+    spawn_chain_sync(database.clone(), tenant.rpc(), tenant.config.sync(config).interval);
+    spawn_indexer(database.clone(), tenant.rpc(), tenant.config.indexer(config), &tenant.indexer_metrics);
+    spawn_search_indexer(database.clone());
+    spawn_notifier(database.clone());
+    if let Some(moderation_config) = ModerationConfig::from_env() {
+        spawn_moderation_worker(database.clone(), HttpModerationProvider::new(&moderation_config), moderation_config.threshold);
+    }
+
+    let mut scheduler = Scheduler::new(database.clone());
+    register_leaderboard_job(&mut scheduler);
+    register_analytics_job(&mut scheduler);
+    register_ranking_job(&mut scheduler);
+    register_webhook_job(&mut scheduler);
+    // keeper 는 이 배포의 program_id 와 RPC 로 crank 한다
+    with_tenant(tenant, || register_countdown_job(&mut scheduler, KeeperConfig::from_env()));
+    scheduler.start();
+    tracing::info!(cluster = tenant.cluster(), program_id = %tenant.config.program_id, "tenant started");
+}
+
+
+
+fn collect_components<T: SafeDatabase + ChangeFeed + TableVersions>(database: &T, limits: &RateLimits) ->  Vec<(String,Router<T>)> {
+    // 프로필 쓰기는 AuthedWallet 으로 세션의 지갑에만 쓴다
+    // POST 는 아바타 파일이 들어오는 multipart, PUT/PATCH 는 JSON
+    let router_profile_post = body_limited(rate_limited(post_router_builder("/api/profile".to_string(),profile_write::<T>), database, limits.profile_write.limit("profile_write")), MAX_AVATAR_BYTES + 64 * 1024);
+    let router_profile_put = rate_limited(put_router_builder("/api/profile".to_string(), put_profile::<T>), database, limits.profile_write.limit("profile_write"));
+    let router_profile_patch = rate_limited(patch_router_builder("/api/profile".to_string(), patch_profile::<T>), database, limits.profile_write.limit("profile_write"));
+    let router_profile_get = get_router_builder("/api/profile".to_string(),get_profile_by_address::<T>);
+    // 여러 지갑의 프로필/배지/.sol 이름을 한 번에 (작성자 목록 채우기용)
+    let router_profiles_batch = post_router_builder("/api/profiles/batch".to_string(), get_profiles_batch::<T>);
+    // 지갑 소유 증명 (세션 대신 nonce 에 묶인 서명/memo 트랜잭션으로 인증)
+    let router_profile_verify = rate_limited(post_router_builder("/api/profile/verify".to_string(), verify_profile::<T>), database, limits.profile_write.limit("profile_verify"));
+    // 지갑 -> 대표 .sol 이름 (캐시에 없으면 SNS 프록시에 묻는다)
+    let router_sns_reverse = get_router_builder("/api/sns/{wallet}".to_string(), reverse_lookup::<T>);
     // DAO PDA 관련 라우터
-    let router_pda_post = post_router_builder("/api/dao/pda".to_string(), save_pda::<InnerDatabase>);
-    let router_pda_get = get_router_builder("/api/dao/pdas".to_string(), get_all_pdas::<InnerDatabase>);
+    let router_pda_post = post_router_builder("/api/dao/pda".to_string(), save_pda::<T>);
+    let router_pda_get = get_router_builder("/api/dao/pdas".to_string(), get_all_pdas::<T>);
 
     // DAO Community 관련 라우터
-    let router_community_post = post_router_builder("/api/dao/community".to_string(), save_community::<InnerDatabase>);
-    let router_community_get_all = get_router_builder("/api/dao/communities".to_string(), get_all_communities::<InnerDatabase>);
-    let router_community_get = get_router_builder("/api/dao/community".to_string(), get_community_by_pda::<InnerDatabase>);
+    let router_community_post = post_router_builder("/api/dao/community".to_string(), save_community::<T>);
+    let router_community_get_all = get_router_builder("/api/dao/communities".to_string(), get_all_communities::<T>);
+    let router_community_get = get_router_builder("/api/dao/community".to_string(), get_community_by_pda::<T>);
 
     // DAO Content 관련 라우터
-    let router_content_post = post_router_builder("/api/dao/content".to_string(), save_content::<InnerDatabase>);
-    let router_content_get = get_router_builder("/api/dao/contents".to_string(), get_contents_by_pda::<InnerDatabase>);
+    let router_content_post = rate_limited(post_router_builder("/api/dao/content".to_string(), save_content::<T>), database, limits.content_post.limit("content_post"));
+    let router_content_get = get_router_builder("/api/dao/contents".to_string(), get_contents_by_pda::<T>);
 
     // DAO Depositor 관련 라우터
-    let router_depositor_post = post_router_builder("/api/dao/depositor".to_string(), save_depositor::<InnerDatabase>);
-    let router_depositor_get = get_router_builder("/api/dao/depositors".to_string(), get_depositors_by_pda::<InnerDatabase>);
+    let router_depositor_post = post_router_builder("/api/dao/depositor".to_string(), save_depositor::<T>);
+    let router_depositor_get = get_router_builder("/api/dao/depositors".to_string(), get_depositors_by_pda::<T>);
 
     // DAO Proposal 관련 라우터
-    let router_proposal_post = post_router_builder("/api/dao/proposal".to_string(), save_proposal::<InnerDatabase>);
-    let router_proposal_get = get_router_builder("/api/dao/proposals".to_string(), get_proposals_by_pda::<InnerDatabase>);
+    let router_proposal_post = post_router_builder("/api/dao/proposal".to_string(), save_proposal::<T>);
+    let router_proposal_get = get_router_builder("/api/dao/proposals".to_string(), get_proposals_by_pda::<T>);
+
+    // DAO 레지스트리 라우터
+    let router_dao_register = post_router_builder("/api/dao".to_string(), register_dao::<T>);
+    let router_dao_get = get_router_builder("/api/dao".to_string(), get_dao_summary::<T>);
+    let router_dao_list = get_router_builder("/api/daos".to_string(), list_daos::<T>);
+
+    // 실시간 카운트다운/새 콘텐츠 WebSocket
+    let router_dao_stream = get_router_builder("/ws/dao/{pubkey}".to_string(), dao_stream::<T>);
+    let router_proposal_stream = get_router_builder("/ws/proposal/{pubkey}/{proposal_id}".to_string(), proposal_stream::<T>);
+
+    // DAO 채팅 (보내기는 세션의 지갑으로만, 새 메시지는 WebSocket 방으로도 나간다)
+    let router_chat_post = rate_limited(post_router_builder("/api/dao/{pubkey}/chat".to_string(), send_chat_message::<T>), database, limits.chat_post.limit("chat_post"));
+    let router_chat_get = get_router_builder("/api/dao/{pubkey}/chat".to_string(), list_chat_messages::<T>);
+    let router_chat_stream = get_router_builder("/ws/dao/{pubkey}/chat".to_string(), chat_stream::<T>);
+
+    // 인덱싱된 이벤트 SSE 피드와 리더보드
+    // 목록 응답은 읽는 테이블의 버전으로 ETag 를 달아 If-None-Match 에 304 로 답한다
+    let router_dao_leaderboard = etag_cached(get_router_builder("/api/dao/{pubkey}/leaderboard".to_string(), get_leaderboard::<T>), database, LEADERBOARD_TABLES);
+    let router_dao_analytics = etag_cached(get_router_builder("/api/dao/{pubkey}/analytics".to_string(), get_dao_analytics::<T>), database, ANALYTICS_TABLES);
+    let router_event_stream = get_router_builder("/api/events/stream".to_string(), stream_events::<T>);
+    let router_event_list = get_router_builder("/api/events".to_string(), list_events::<T>);
+    // 지갑별 보상 내역 (format=csv 로 내려받을 수 있다)
+    let router_wallet_earnings = get_router_builder("/api/wallet/{pubkey}/earnings".to_string(), get_wallet_earnings::<T>);
+
+    // 콘텐츠 메타데이터 라우터
+    let router_content_record_post = rate_limited(post_router_builder("/api/content".to_string(), create_content::<T>), database, limits.content_post.limit("content_post"));
+    let router_content_record_get = get_router_builder("/api/content".to_string(), get_content_by_id::<T>);
+    let router_content_preview = get_router_builder("/api/content/{id}/preview".to_string(), get_content_preview::<T>);
+    let router_content_record_list = etag_cached(get_router_builder("/api/contents".to_string(), list_contents::<T>), database, CONTENT_LIST_TABLES);
+    let router_moderation_get = get_router_builder("/api/moderation".to_string(), get_moderation::<T>);
+    // 색인된 콘텐츠 신고 (지갑마다 콘텐츠 하나에 한 번)
+    let router_content_report = rate_limited(post_router_builder("/api/content/report".to_string(), report_content::<T>), database, limits.content_post.limit("content_report"));
+
+    // 검색 라우터
+    let router_search = get_router_builder("/api/search".to_string(), search::<T>);
+
+    // 알림 인박스 라우터 (세션의 지갑 것만 보인다)
+    let router_notifications = get_router_builder("/api/notifications".to_string(), list_notifications::<T>);
+    let router_notifications_unread = get_router_builder("/api/notifications/unread".to_string(), get_unread_count::<T>);
+    let router_notifications_read = post_router_builder("/api/notifications/read".to_string(), mark_read::<T>);
+
+    // 웹훅 라우터 (DAO 를 만든 지갑이나 admin 만)
+    let router_webhooks_post = post_router_builder("/api/webhooks".to_string(), create_webhook::<T>);
+    let router_webhooks_get = get_router_builder("/api/webhooks".to_string(), list_webhooks::<T>);
+    let router_webhooks_put = put_router_builder("/api/webhooks/{id}".to_string(), update_webhook::<T>);
+    let router_webhooks_delete = delete_router_builder("/api/webhooks/{id}".to_string(), delete_webhook::<T>);
+    let router_webhook_deliveries = get_router_builder("/api/webhooks/{id}/deliveries".to_string(), list_deliveries::<T>);
+
+    // IPFS 업로드 라우터 (이미지 크기만큼 본문 한도를 늘린다)
+    let router_upload_post = body_limited(rate_limited(post_router_builder("/api/upload".to_string(), upload_content::<T>), database, limits.upload.limit("upload")), MAX_IMAGE_BYTES + MAX_TEXT_BYTES + 64 * 1024);
+    let router_upload_get = get_router_builder("/api/upload".to_string(), get_upload::<T>);
+    let router_media_get = get_router_builder("/api/media/{id}".to_string(), get_media::<T>);
+
+    // 서명 전 트랜잭션 빌더 라우터 (지갑은 받은 트랜잭션에 서명만 한다)
+    let router_tx_initialize_dao = post_router_builder("/api/tx/initialize-dao".to_string(), build_initialize_dao_tx);
+    let router_tx_deposit = post_router_builder("/api/tx/deposit".to_string(), build_deposit_tx);
+    let router_tx_submit_content = post_router_builder("/api/tx/submit-content".to_string(), build_submit_content_tx);
+    let router_tx_create_vote = post_router_builder("/api/tx/create-vote".to_string(), build_create_vote_tx);
+    let router_tx_vote = post_router_builder("/api/tx/vote".to_string(), build_vote_tx);
+    let router_tx_process_timeout = post_router_builder("/api/tx/process-timeout".to_string(), build_process_timeout_tx);
+
+    // 수수료 대납 relay 라우터 (지갑별 하루 한도는 relay 안에서 센다)
+    // 서명된 트랜잭션 하나 (1232 바이트, base64) 만 받는다
+    let router_tx_relay = body_limited(rate_limited(post_router_builder("/api/tx/relay".to_string(), relay_transaction::<T>), database, limits.tx_relay.limit("tx_relay")), 8 * 1024);
+    let router_tx_relayer = get_router_builder("/api/tx/relayer".to_string(), get_relayer);
+    // devnet faucet 라우터 (지갑/IP 별 쿨다운은 faucet 안에서 센다)
+    let router_faucet = rate_limited(post_router_builder("/api/faucet".to_string(), request_faucet::<T>), database, limits.faucet.limit("faucet"));
+
+    // Sign-In-With-Solana 라우터
+    let router_auth_challenge = rate_limited(post_router_builder("/api/auth/challenge".to_string(), create_challenge::<T>), database, limits.auth_challenge.limit("auth_challenge"));
+    let router_auth_nonce = rate_limited(post_router_builder("/api/auth/nonce".to_string(), create_nonce::<T>), database, limits.auth_challenge.limit("auth_nonce"));
+    let router_auth_verify = post_router_builder("/api/auth/verify".to_string(), verify_challenge::<T>);
+    let router_auth_refresh = post_router_builder("/api/auth/refresh".to_string(), refresh_session::<T>);
+    let router_auth_logout = post_router_builder("/api/auth/logout".to_string(), logout::<T>);
+
+    // 관리자 라우터 (admin 역할만)
+    let router_admin_backup = restricted(post_router_builder("/api/admin/backup".to_string(), backup_database::<T>), database, Role::Admin);
+    let router_admin_jobs = restricted(get_router_builder("/api/admin/jobs".to_string(), list_jobs::<T>), database, Role::Admin);
+    let router_admin_db_stats = restricted(get_router_builder("/api/admin/db-stats".to_string(), get_db_stats::<T>), database, Role::Admin);
+    let router_admin_indexer = restricted(get_router_builder("/api/admin/indexer".to_string(), get_indexer_status::<T>), database, Role::Admin);
+    let router_admin_treasury = restricted(get_router_builder("/api/admin/treasury".to_string(), get_treasury::<T>), database, Role::Admin);
+    let router_admin_reward_drafts = restricted(post_router_builder("/api/admin/rewards/draft".to_string(), create_reward_drafts::<T>), database, Role::Admin);
+    let router_admin_quality_rewards = restricted(post_router_builder("/api/admin/rewards/quality".to_string(), create_quality_reward_draft::<T>), database, Role::Admin);
+    // 모더레이션 검토는 moderator 도 할 수 있다
+    let router_admin_flagged = restricted(get_router_builder("/api/admin/moderation/flagged".to_string(), list_flagged_content::<T>), database, Role::Moderator);
+    let router_admin_override = restricted(post_router_builder("/api/admin/moderation/override".to_string(), override_moderation::<T>), database, Role::Moderator);
+    let router_admin_reports = restricted(get_router_builder("/api/admin/moderation/reports".to_string(), list_reports::<T>), database, Role::Moderator);
+    let router_admin_report_action = restricted(post_router_builder("/api/admin/moderation/reports/action".to_string(), act_on_report::<T>), database, Role::Moderator);
+    let router_admin_moderation_audit = restricted(get_router_builder("/api/admin/moderation/audit".to_string(), list_moderation_audit::<T>), database, Role::Moderator);
+    let router_admin_media_block = restricted(post_router_builder("/api/admin/media/block".to_string(), block_media::<T>), database, Role::Moderator);
+    let router_admin_media_unblock = restricted(post_router_builder("/api/admin/media/unblock".to_string(), unblock_media::<T>), database, Role::Moderator);
+    let router_admin_roles_grant = post_router_builder("/api/admin/roles/grant".to_string(), grant_role::<T>);
+    let router_admin_roles_revoke = post_router_builder("/api/admin/roles/revoke".to_string(), revoke_role::<T>);
+    let router_admin_roles_list = get_router_builder("/api/admin/roles".to_string(), list_roles::<T>);
+
+    // Prometheus 스크레이프와 로드밸런서/오케스트레이터 프로브
+    let router_metrics = get_router_builder("/metrics".to_string(), get_metrics::<T>);
+    let router_healthz = get_router_builder("/healthz".to_string(), healthz);
+    let router_readyz = get_router_builder("/readyz".to_string(), readyz::<T>);
+
+    // OpenAPI 스펙과 Swagger UI
+    let router_docs = (SWAGGER_UI_PATH.to_string(), Router::from(SwaggerUi::new(SWAGGER_UI_PATH).url(OPENAPI_JSON_PATH, ApiDoc::openapi())));
 
     vec![
         // 프로필 라우터
         router_profile_get,
+        router_profiles_batch,
         router_profile_post,
+        router_profile_put,
+        router_profile_patch,
+        router_profile_verify,
+        router_sns_reverse,
 
         // DAO 라우터
+        router_dao_register,
+        router_dao_get,
+        router_dao_list,
+        router_dao_stream,
+        router_proposal_stream,
+        router_chat_post,
+        router_chat_get,
+        router_chat_stream,
+        router_event_stream,
+        router_event_list,
+        router_dao_leaderboard,
+        router_dao_analytics,
+        router_wallet_earnings,
         router_pda_post,
         router_pda_get,
         router_community_post,
@@ -79,7 +415,72 @@ fn collect_components() ->  Vec<(String,Router<InnerDatabase>)> {
         router_depositor_post,
         router_depositor_get,
         router_proposal_post,
-        router_proposal_get
+        router_proposal_get,
+
+        // 콘텐츠 라우터
+        router_content_record_post,
+        router_content_record_get,
+        router_content_preview,
+        router_content_record_list,
+        router_moderation_get,
+        router_content_report,
+        router_upload_post,
+        router_upload_get,
+        router_media_get,
+        router_search,
+        router_notifications,
+        router_notifications_unread,
+        router_notifications_read,
+        router_webhooks_post,
+        router_webhooks_get,
+        router_webhooks_put,
+        router_webhooks_delete,
+        router_webhook_deliveries,
+
+        // 트랜잭션 라우터
+        router_tx_initialize_dao,
+        router_tx_deposit,
+        router_tx_submit_content,
+        router_tx_create_vote,
+        router_tx_vote,
+        router_tx_process_timeout,
+        router_tx_relay,
+        router_tx_relayer,
+        router_faucet,
+
+        // 인증 라우터
+        router_auth_challenge,
+        router_auth_nonce,
+        router_auth_verify,
+        router_auth_refresh,
+        router_auth_logout,
+
+        // 관리자 라우터
+        router_admin_backup,
+        router_admin_jobs,
+        router_admin_db_stats,
+        router_admin_indexer,
+        router_admin_treasury,
+        router_admin_reward_drafts,
+        router_admin_quality_rewards,
+        router_admin_flagged,
+        router_admin_override,
+        router_admin_reports,
+        router_admin_report_action,
+        router_admin_moderation_audit,
+        router_admin_media_block,
+        router_admin_media_unblock,
+        router_admin_roles_grant,
+        router_admin_roles_revoke,
+        router_admin_roles_list,
+
+        // 모니터링 라우터
+        router_metrics,
+        router_healthz,
+        router_readyz,
+
+        // 문서 라우터
+        router_docs
     ]
 
 }
\ No newline at end of file
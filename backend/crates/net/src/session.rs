@@ -0,0 +1,269 @@
+use axum::extract::{FromRequestParts, State};
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::Json;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::{now_secs, TtlTable};
+use crate::auth::AuthError;
+
+// 토큰 서명 키. 설정하지 않으면 프로세스마다 새로 만들어서 재시작하면 모든 세션이 끊긴다.
+pub const SESSION_SECRET_ENV: &str = "TURTLE_SESSION_SECRET";
+// 로그아웃/회전된 토큰 ID 목록. 토큰이 어차피 만료되는 시각까지만 들고 있으면 된다 (ttl::TTL_TABLES)
+pub const REVOKED_TABLE: &str = "revoked_tokens";
+// API 호출에 쓰는 access token 의 수명
+pub const ACCESS_TTL: Duration = Duration::from_secs(15 * 60);
+// access token 을 다시 받는 데 쓰는 refresh token 의 수명
+pub const REFRESH_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const ID_BYTES: usize = 16;
+
+struct SessionKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+static SESSION_KEYS: OnceLock<SessionKeys> = OnceLock::new();
+
+fn session_keys() -> &'static SessionKeys {
+    SESSION_KEYS.get_or_init(|| {
+        let secret = match std::env::var(SESSION_SECRET_ENV) {
+            Ok(secret) if !secret.is_empty() => secret.into_bytes(),
+            _ => {
+                tracing::warn!("{} is not set, sessions will not survive a restart", SESSION_SECRET_ENV);
+                let mut secret = vec![0u8; 32];
+                OsRng.fill_bytes(&mut secret);
+                secret
+            }
+        };
+        SessionKeys {
+            encoding: EncodingKey::from_secret(&secret),
+            decoding: DecodingKey::from_secret(&secret),
+        }
+    })
+}
+
+fn random_id() -> String {
+    let mut bytes = [0u8; ID_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    bs58::encode(bytes).into_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    Access,
+    Refresh,
+}
+
+// HS256 JWT 의 payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    // 로그인한 지갑
+    pub sub: String,
+    // 로그인 한 번에 하나. refresh 로 회전해도 유지되고, 로그아웃하면 이 ID 전체가 무효가 된다.
+    pub sid: String,
+    // 토큰마다 하나
+    pub jti: String,
+    pub kind: TokenKind,
+    pub iat: u64,
+    pub exp: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenPair {
+    pub token: String,
+    pub refresh_token: String,
+    pub wallet: String,
+    pub expires_at: u64,
+    pub refresh_expires_at: u64,
+}
+
+#[derive(Deserialize)]
+pub struct RefreshRequest {
+    refresh_token: String,
+}
+
+fn sign(wallet: &str, sid: &str, kind: TokenKind, ttl: Duration, now: u64) -> Result<(String, u64), AuthError> {
+    let claims = Claims {
+        sub: wallet.to_string(),
+        sid: sid.to_string(),
+        jti: random_id(),
+        kind,
+        iat: now,
+        exp: now.saturating_add(ttl.as_secs()),
+    };
+    let token = encode(&Header::new(Algorithm::HS256), &claims, &session_keys().encoding)
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+    Ok((token, claims.exp))
+}
+
+fn issue_tokens(wallet: &str, sid: &str) -> Result<TokenPair, AuthError> {
+    let now = now_secs();
+    let (token, expires_at) = sign(wallet, sid, TokenKind::Access, ACCESS_TTL, now)?;
+    let (refresh_token, refresh_expires_at) = sign(wallet, sid, TokenKind::Refresh, REFRESH_TTL, now)?;
+
+    Ok(TokenPair {
+        token,
+        refresh_token,
+        wallet: wallet.to_string(),
+        expires_at,
+        refresh_expires_at,
+    })
+}
+
+// 서명 로그인이 끝난 지갑에 새 세션을 연다
+pub fn issue_session(wallet: &str) -> Result<TokenPair, AuthError> {
+    issue_tokens(wallet, &random_id())
+}
+
+fn is_revoked<T: SafeDatabase>(database: &T, claims: &Claims) -> Result<bool, AuthError> {
+    let revoked = TtlTable::new(database.clone(), REVOKED_TABLE);
+    for id in [&claims.sid, &claims.jti] {
+        let entry = revoked.get(id).map_err(|e| AuthError::DatabaseError(e.to_string()))?;
+        if entry.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn revoke<T: SafeDatabase>(database: &T, id: &str, until: u64) -> Result<(), AuthError> {
+    TtlTable::new(database.clone(), REVOKED_TABLE)
+        .put_until(id, b"", until)
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))
+}
+
+// 서명, 만료, 종류, 폐기 여부를 모두 확인한 claims
+pub fn verify_token<T: SafeDatabase>(database: &T, token: &str, kind: TokenKind) -> Result<Claims, AuthError> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.leeway = 0;
+
+    let claims = decode::<Claims>(token, &session_keys().decoding, &validation)
+        .map_err(|_| AuthError::Unauthorized("Invalid or expired session".to_string()))?
+        .claims;
+
+    if claims.kind != kind || is_revoked(database, &claims)? {
+        return Err(AuthError::Unauthorized("Invalid or expired session".to_string()));
+    }
+    Ok(claims)
+}
+
+pub fn bearer_token(headers: &HeaderMap) -> Result<&str, AuthError> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or_else(|| AuthError::Unauthorized("Missing bearer token".to_string()))
+}
+
+// 유효한 access token 을 들고 온 지갑.
+// 핸들러는 요청 본문의 주소 대신 이 값을 믿는다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthedWallet(pub String);
+
+impl<T: SafeDatabase> FromRequestParts<T> for AuthedWallet {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, database: &T) -> Result<Self, Self::Rejection> {
+        // require_session 미들웨어를 이미 통과했으면 다시 검증하지 않는다
+        if let Some(wallet) = parts.extensions.get::<AuthedWallet>() {
+            return Ok(wallet.clone());
+        }
+
+        let claims = verify_token(database, bearer_token(&parts.headers)?, TokenKind::Access)?;
+        Ok(AuthedWallet(claims.sub))
+    }
+}
+
+// refresh token 으로 새 토큰 쌍을 받는다. 쓴 refresh token 은 폐기해서 한 번만 쓸 수 있게 한다.
+pub async fn refresh_session<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<RefreshRequest>,
+) -> Result<Json<TokenPair>, AuthError> {
+    let claims = verify_token(&database, &request.refresh_token, TokenKind::Refresh)?;
+    revoke(&database, &claims.jti, claims.exp)?;
+
+    Ok(Json(issue_tokens(&claims.sub, &claims.sid)?))
+}
+
+// 현재 세션(같은 sid 의 access/refresh token 전부)을 폐기한다
+pub async fn logout<T: SafeDatabase>(
+    State(database): State<T>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AuthError> {
+    let claims = verify_token(&database, bearer_token(&headers)?, TokenKind::Access)?;
+    // 이 세션에서 나간 토큰은 모두 지금 + REFRESH_TTL 전에 만료된다
+    revoke(&database, &claims.sid, now_secs().saturating_add(REFRESH_TTL.as_secs()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+        headers
+    }
+
+    async fn extract(database: &MemoryDatabase, headers: HeaderMap) -> Result<AuthedWallet, AuthError> {
+        let mut request = axum::http::Request::builder().body(()).unwrap();
+        *request.headers_mut() = headers;
+        let (mut parts, _) = request.into_parts();
+        AuthedWallet::from_request_parts(&mut parts, database).await
+    }
+
+    #[tokio::test]
+    async fn test_extractor_accepts_only_access_tokens() {
+        let database = MemoryDatabase::new();
+        let pair = issue_session("alice").unwrap();
+
+        assert_eq!(extract(&database, bearer(&pair.token)).await.unwrap(), AuthedWallet("alice".to_string()));
+        assert!(matches!(extract(&database, bearer(&pair.refresh_token)).await, Err(AuthError::Unauthorized(_))));
+        assert!(matches!(extract(&database, bearer("garbage")).await, Err(AuthError::Unauthorized(_))));
+        assert!(matches!(extract(&database, HeaderMap::new()).await, Err(AuthError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_refresh_token() {
+        let database = MemoryDatabase::new();
+        let pair = issue_session("alice").unwrap();
+
+        let Json(refreshed) = refresh_session(State(database.clone()), Json(RefreshRequest { refresh_token: pair.refresh_token.clone() })).await.unwrap();
+        assert_eq!(refreshed.wallet, "alice");
+        assert!(verify_token(&database, &refreshed.token, TokenKind::Access).is_ok());
+
+        // 이미 쓴 refresh token 은 다시 쓸 수 없다
+        assert!(matches!(
+            refresh_session(State(database.clone()), Json(RefreshRequest { refresh_token: pair.refresh_token })).await,
+            Err(AuthError::Unauthorized(_))
+        ));
+        // access token 으로는 refresh 할 수 없다
+        assert!(refresh_session(State(database), Json(RefreshRequest { refresh_token: refreshed.token })).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_logout_revokes_whole_session() {
+        let database = MemoryDatabase::new();
+        let pair = issue_session("alice").unwrap();
+        let other = issue_session("alice").unwrap();
+
+        assert_eq!(logout(State(database.clone()), bearer(&pair.token)).await.unwrap(), StatusCode::NO_CONTENT);
+
+        assert!(verify_token(&database, &pair.token, TokenKind::Access).is_err());
+        assert!(verify_token(&database, &pair.refresh_token, TokenKind::Refresh).is_err());
+        // 다른 로그인 세션은 그대로
+        assert!(verify_token(&database, &other.token, TokenKind::Access).is_ok());
+    }
+}
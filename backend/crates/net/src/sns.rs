@@ -0,0 +1,280 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use sol::state::Pubkey;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+use utoipa::ToSchema;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::kv::KeyRange;
+use turtle_database::ttl::{now_secs, TtlTable};
+use turtle_database::typed::TypedTable;
+use crate::config::{app_config, AppConfig};
+use crate::scheduler::Scheduler;
+use crate::error::ApiError;
+
+// 지갑의 대표(favorite) .sol 이름을 알려 주는 SNS 프록시. Bonfida sns-sdk-proxy 와 같은 API 를 쓴다.
+pub const DEFAULT_SNS_API_URL: &str = "https://sns-sdk-proxy.bonfida.workers.dev";
+
+// wallet -> 대표 .sol 이름 (ttl::TTL_TABLES). 이름이 없는 지갑은 빈 값으로 남겨서 같은 조회를 되풀이하지 않는다.
+pub const SNS_NAMES_TABLE: &str = "sns_names";
+// 응답에 이름을 붙이려다 캐시에 없던 지갑 -> 처음 요청된 시각. sns_refresh 작업이 조회해서 채운다.
+pub const SNS_PENDING: TypedTable<u64> = TypedTable::new("sns_pending");
+
+const NAME_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+const MISSING_NAME_TTL: Duration = Duration::from_secs(60 * 60);
+const SNS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const SNS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+// 한 번에 프록시로 보내는 조회 수
+const SNS_REFRESH_BATCH: usize = 50;
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum SnsError {
+    DatabaseError(String),
+    ValidationError(String),
+    ResolverError(String),
+}
+
+impl fmt::Display for SnsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnsError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            SnsError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            SnsError::ResolverError(msg) => write!(f, "SNS lookup failed: {}", msg),
+        }
+    }
+}
+
+impl StdError for SnsError {}
+
+impl IntoResponse for SnsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SnsError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SnsError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            SnsError::ResolverError(_) => StatusCode::BAD_GATEWAY,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> SnsError {
+    SnsError::DatabaseError(e.to_string())
+}
+
+// 지갑의 대표 이름을 찾는 곳. 서버는 SnsClient 를 쓰고, 테스트는 이름을 정해 둔다.
+pub trait NameResolver: Send + Sync + 'static {
+    fn primary_name(&self, wallet: &str) -> impl Future<Output = Result<Option<String>, String>> + Send;
+}
+
+pub struct SnsClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct ProxyResponse {
+    s: String,
+    #[serde(default)]
+    result: serde_json::Value,
+}
+
+impl SnsClient {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: config.sns_api_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl NameResolver for SnsClient {
+    // GET {url}/favorite-domain/{wallet} -> {"s": "ok", "result": {"reverse": "turtle", ...}}.
+    // 대표 이름을 정하지 않은 지갑은 {"s": "error"} 로 온다.
+    async fn primary_name(&self, wallet: &str) -> Result<Option<String>, String> {
+        let response = self.client.get(format!("{}/favorite-domain/{}", self.base_url, wallet))
+            .timeout(SNS_REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_server_error() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+
+        let body: ProxyResponse = response.json().await.map_err(|e| e.to_string())?;
+        if body.s != "ok" {
+            return Ok(None);
+        }
+        Ok(body.result["reverse"].as_str().filter(|name| !name.is_empty()).map(|name| format!("{}.sol", name)))
+    }
+}
+
+static SNS_CLIENT: OnceLock<SnsClient> = OnceLock::new();
+
+pub fn sns_client() -> &'static SnsClient {
+    SNS_CLIENT.get_or_init(|| SnsClient::from_config(app_config()))
+}
+
+fn names<T: SafeDatabase>(database: &T) -> TtlTable<T> {
+    TtlTable::new(database.clone(), SNS_NAMES_TABLE)
+}
+
+// 캐시에 있으면 Some(이름 또는 None), 없거나 만료됐으면 None
+pub fn cached_name<T: SafeDatabase>(database: &T, wallet: &str, now: u64) -> Result<Option<Option<String>>, SnsError> {
+    let cached = names(database).get_at(wallet, now).map_err(db_err)?;
+    Ok(cached.map(|name| String::from_utf8(name).ok().filter(|name| !name.is_empty())))
+}
+
+pub fn store_name<T: SafeDatabase>(database: &T, wallet: &str, name: Option<&str>, now: u64) -> Result<(), SnsError> {
+    let ttl = if name.is_some() { NAME_TTL } else { MISSING_NAME_TTL };
+    names(database).put_until(wallet, name.unwrap_or("").as_bytes(), now + ttl.as_secs()).map_err(db_err)?;
+    SNS_PENDING.delete(database, wallet.as_bytes()).map_err(db_err)?;
+    Ok(())
+}
+
+// 응답에 붙일 이름들. 요청 경로에서는 프록시를 부르지 않고, 캐시에 없는 지갑은 sns_pending 에 넣어 둔다.
+pub fn sol_names<'a, T: SafeDatabase>(
+    database: &T,
+    wallets: impl IntoIterator<Item = &'a str>,
+    now: u64,
+) -> Result<HashMap<String, Option<String>>, SnsError> {
+    let mut found = HashMap::new();
+    for wallet in wallets {
+        if found.contains_key(wallet) {
+            continue;
+        }
+        let name = match cached_name(database, wallet, now)? {
+            Some(name) => name,
+            None => {
+                if SNS_PENDING.get(database, wallet.as_bytes()).map_err(db_err)?.is_none() {
+                    SNS_PENDING.put(database, wallet.as_bytes(), &now).map_err(db_err)?;
+                }
+                None
+            },
+        };
+        found.insert(wallet.to_string(), name);
+    }
+    Ok(found)
+}
+
+pub fn sol_name<T: SafeDatabase>(database: &T, wallet: &str, now: u64) -> Result<Option<String>, SnsError> {
+    Ok(sol_names(database, [wallet], now)?.remove(wallet).flatten())
+}
+
+// 캐시에 없으면 프록시에 물어보고 저장한다
+pub async fn lookup_name<T: SafeDatabase, R: NameResolver>(database: &T, resolver: &R, wallet: &str, now: u64) -> Result<Option<String>, SnsError> {
+    if let Some(name) = cached_name(database, wallet, now)? {
+        return Ok(name);
+    }
+    let name = resolver.primary_name(wallet).await.map_err(SnsError::ResolverError)?;
+    store_name(database, wallet, name.as_deref(), now)?;
+    Ok(name)
+}
+
+// sns_pending 의 지갑을 오래된 키 순서로 조회한다. 프록시가 실패하면 이번 회차는 멈추고 다음에 다시 한다.
+pub async fn refresh_pending<T: SafeDatabase, R: NameResolver>(database: &T, resolver: &R, now: u64) -> Result<usize, SnsError> {
+    let pending = SNS_PENDING.scan(database, &KeyRange::all()).map_err(db_err)?;
+
+    let mut resolved = 0;
+    for (key, _) in pending.into_iter().take(SNS_REFRESH_BATCH) {
+        let wallet = String::from_utf8_lossy(&key).into_owned();
+        lookup_name(database, resolver, &wallet, now).await?;
+        resolved += 1;
+    }
+    Ok(resolved)
+}
+
+pub fn register_sns_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>) {
+    scheduler.every("sns_refresh", SNS_REFRESH_INTERVAL, Duration::from_secs(5), |database| async move {
+        refresh_pending(&database, sns_client(), now_secs()).await.map(|_| ()).map_err(|e| e.to_string())
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct SolNameResponse {
+    wallet: String,
+    // 대표 .sol 이름. 정하지 않은 지갑이면 null.
+    name: Option<String>,
+}
+
+// 지갑 -> 대표 .sol 이름. 캐시에 없으면 이 요청에서 바로 조회한다.
+#[utoipa::path(
+    get, path = "/api/sns/{wallet}", tag = "profile",
+    params(("wallet" = String, Path, description = "Wallet address (base58)")),
+    responses(
+        (status = 200, body = SolNameResponse),
+        (status = 400, description = "Invalid wallet", body = String),
+        (status = 502, description = "SNS lookup failed", body = String),
+    )
+)]
+pub async fn reverse_lookup<T: SafeDatabase>(
+    State(database): State<T>,
+    Path(wallet): Path<String>,
+) -> Result<Json<SolNameResponse>, SnsError> {
+    wallet.parse::<Pubkey>()
+        .map_err(|_| SnsError::ValidationError(format!("Invalid wallet address: {}", wallet)))?;
+    let name = lookup_name(&database, sns_client(), &wallet, now_secs()).await?;
+    Ok(Json(SolNameResponse { wallet, name }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use turtle_database::memory_db::MemoryDatabase;
+
+    // 조회한 지갑을 기록하고, 정해 둔 이름을 돌려준다
+    struct FakeResolver {
+        names: HashMap<String, String>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl NameResolver for FakeResolver {
+        async fn primary_name(&self, wallet: &str) -> Result<Option<String>, String> {
+            self.calls.lock().unwrap().push(wallet.to_string());
+            Ok(self.names.get(wallet).cloned())
+        }
+    }
+
+    fn resolver() -> FakeResolver {
+        FakeResolver {
+            names: HashMap::from([("alice".to_string(), "alice.sol".to_string())]),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_misses_are_queued_and_refreshed() {
+        let database = MemoryDatabase::new();
+        let resolver = resolver();
+
+        // 요청 경로에서는 프록시를 부르지 않는다
+        let found = sol_names(&database, ["alice", "bob", "alice"], 100).unwrap();
+        assert_eq!(found, HashMap::from([("alice".to_string(), None), ("bob".to_string(), None)]));
+        assert!(resolver.calls.lock().unwrap().is_empty());
+        assert_eq!(SNS_PENDING.scan(&database, &KeyRange::all()).unwrap().len(), 2);
+
+        assert_eq!(refresh_pending(&database, &resolver, 100).await.unwrap(), 2);
+        assert!(SNS_PENDING.scan(&database, &KeyRange::all()).unwrap().is_empty());
+        assert_eq!(sol_name(&database, "alice", 101).unwrap().as_deref(), Some("alice.sol"));
+        assert_eq!(sol_name(&database, "bob", 101).unwrap(), None);
+
+        // 없는 이름도 캐시에 남아서 만료 전에는 다시 묻지 않는다
+        assert_eq!(lookup_name(&database, &resolver, "bob", 102).await.unwrap(), None);
+        assert_eq!(resolver.calls.lock().unwrap().len(), 2);
+        assert_eq!(lookup_name(&database, &resolver, "bob", 100 + MISSING_NAME_TTL.as_secs()).await.unwrap(), None);
+        assert_eq!(resolver.calls.lock().unwrap().len(), 3);
+
+        // 만료된 이름은 응답에서 빠지고 다시 대기열에 들어간다
+        assert_eq!(sol_name(&database, "alice", 100 + NAME_TTL.as_secs()).unwrap(), None);
+        assert!(SNS_PENDING.get(&database, b"alice").unwrap().is_some());
+    }
+}
@@ -0,0 +1,516 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sol::state::{DaoState, Pubkey, VoteProposal, VoteStatus};
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::KeyBuilder;
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::chain::{ChainContent, ChainProposal, DaoSnapshot};
+use turtle_service::parser::community::Community;
+use turtle_service::parser::moderation::Verdict;
+use crate::dao::{COMMUNITY_CACHE, DAO_REGISTRY};
+use crate::moderation::MODERATION_VERDICTS;
+use crate::reports::HIDDEN_CONTENTS;
+use crate::rpc::RpcClient;
+
+// 기본 RPC 엔드포인트와 동기화 주기. 설정의 rpc_url (과 rpc_fallback_urls), sync_interval_secs 로 바꿀 수 있다.
+pub const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+// pubkey -> DaoSnapshot
+pub const DAO_SNAPSHOTS: TypedTable<DaoSnapshot> = TypedTable::new("dao_state");
+// (dao, index) -> ChainContent
+pub const CHAIN_CONTENTS: TypedTable<ChainContent> = TypedTable::new("chain_contents");
+// (dao, proposal_id) -> ChainProposal
+pub const CHAIN_PROPOSALS: TypedTable<ChainProposal> = TypedTable::new("chain_proposals");
+// (dao, round, index) -> RoundContent. 끝난 라운드의 콘텐츠 (품질 보상 초안이 읽는다)
+pub const ROUND_CONTENTS: TypedTable<RoundContent> = TypedTable::new("round_contents");
+
+// 타임아웃이 비운 콘텐츠 행. 숨김과 판정도 (dao, index) 키라서 다음 라운드가 같은 index 를 쓰기 전 값을 같이 남긴다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundContent {
+    pub content: ChainContent,
+    pub hidden: bool,
+    pub verdict: Option<Verdict>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncConfig {
+    pub interval: Duration,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_SYNC_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum SyncError {
+    RpcError(String),
+    DecodeError(String),
+    DatabaseError(String),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            SyncError::DecodeError(msg) => write!(f, "Decode error: {}", msg),
+            SyncError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+        }
+    }
+}
+
+impl StdError for SyncError {}
+
+// 계정 데이터를 가져오는 곳. 서버는 RpcClient 를 쓰고, 테스트는 메모리에 든 계정을 쓴다.
+pub trait AccountSource: Send + Sync + 'static {
+    // 계정이 없으면 None
+    fn get_account_data(&self, pubkey: &Pubkey) -> impl Future<Output = Result<Option<Vec<u8>>, SyncError>> + Send;
+}
+
+impl AccountSource for RpcClient {
+    async fn get_account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, SyncError> {
+        let result = self
+            .call("getAccountInfo", json!([pubkey.to_string(), { "encoding": "base64", "commitment": "confirmed" }]))
+            .await
+            .map_err(|e| SyncError::RpcError(e.0))?;
+        parse_account_info(&result)
+    }
+}
+
+// {"context": ..., "value": {"data": ["<base64>", "base64"], ...}} 에서 데이터만 꺼낸다
+fn parse_account_info(result: &Value) -> Result<Option<Vec<u8>>, SyncError> {
+    let value = &result["value"];
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    let encoded = value["data"][0]
+        .as_str()
+        .ok_or_else(|| SyncError::DecodeError("account data missing from RPC response".to_string()))?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map(Some)
+        .map_err(|e| SyncError::DecodeError(e.to_string()))
+}
+
+fn owned_key(dao: &str, id: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(id).build()
+}
+
+fn owned_prefix(dao: &str) -> Vec<u8> {
+    KeyBuilder::new().str(dao).build()
+}
+
+pub fn round_content_prefix(dao: &str, round: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(round).build()
+}
+
+fn to_community(state: &DaoState) -> Community {
+    Community {
+        admin: state.initializer.to_string(),
+        time_limit: state.time_limit,
+        base_fee: state.base_fee,
+        ai_moderation: state.ai_moderation,
+        deposit_share: state.deposit_share,
+        last_activity_timestamp: state.last_activity_timestamp(),
+        total_deposit: state.total_deposit,
+        active_proposal_count: state.vote_proposals
+            .iter()
+            .filter(|proposal| proposal.status == VoteStatus::Active)
+            .count() as u64,
+        content_count: state.contents.len() as u64,
+        depositor_count: state.depositors.len() as u64,
+    }
+}
+
+// 선택지별 투표력 합. 범위를 벗어난 option_index 는 프로그램이 받지 않으므로 무시한다.
+// 계정 데이터는 믿을 수 없으니 합이 넘치면 u64::MAX 에서 멈춘다.
+fn option_power(proposal: &VoteProposal) -> Vec<u64> {
+    let mut power = vec![0u64; proposal.options.len()];
+    for vote in &proposal.votes {
+        if let Some(total) = power.get_mut(vote.option_index as usize) {
+            *total = total.saturating_add(vote.voting_power);
+        }
+    }
+    power
+}
+
+// 스냅샷, 콘텐츠, 제안을 쓰는 연산들. 체인에서 사라진 콘텐츠/제안 행은 지운다.
+// 지난 라운드의 콘텐츠 행은 덮어쓰거나 지우기 전에 ROUND_CONTENTS 로 옮겨 둔다.
+fn snapshot_ops<T: SafeDatabase>(
+    database: &T,
+    dao: &str,
+    state: &DaoState,
+    synced_at: u64,
+) -> Result<Vec<KvOp>, SyncError> {
+    let db_err = |e: turtle_database::error::DatabaseError| SyncError::DatabaseError(e.to_string());
+
+    let snapshot = DaoSnapshot {
+        pubkey: dao.to_string(),
+        dao_name: state.dao_name.clone(),
+        initializer: state.initializer.to_string(),
+        time_limit: state.time_limit,
+        base_fee: state.base_fee,
+        ai_moderation: state.ai_moderation,
+        deposit_share: state.deposit_share,
+        timeout_timestamp: state.timeout_timestamp,
+        total_deposit: state.total_deposit,
+        depositor_count: state.depositors.len() as u64,
+        content_count: state.contents.len() as u64,
+        proposal_count: state.vote_proposals.len() as u64,
+        next_proposal_id: state.next_proposal_id,
+        synced_at,
+        round: state.round,
+        last_submitter: if state.last_submitter == Pubkey::default() { String::new() } else { state.last_submitter.to_string() },
+    };
+
+    let mut ops = vec![
+        DAO_SNAPSHOTS.put_op(dao.as_bytes(), &snapshot).map_err(db_err)?,
+        COMMUNITY_CACHE.put_op(dao.as_bytes(), &to_community(state)).map_err(db_err)?,
+    ];
+
+    for (key, content) in CHAIN_CONTENTS.scan(database, &KeyRange::prefix(owned_prefix(dao))).map_err(db_err)? {
+        if content.round >= state.round {
+            continue;
+        }
+        let archived = RoundContent {
+            hidden: HIDDEN_CONTENTS.get(database, &key).map_err(db_err)?.is_some(),
+            verdict: MODERATION_VERDICTS.get(database, &key).map_err(db_err)?.map(|verdict| verdict.verdict),
+            content,
+        };
+        let archive_key = KeyBuilder::new().str(dao).u64(archived.content.round).u64(archived.content.index).build();
+        ops.push(ROUND_CONTENTS.put_op(&archive_key, &archived).map_err(db_err)?);
+    }
+
+    let mut live_keys = Vec::new();
+    for (index, content) in state.contents.iter().enumerate() {
+        let record = ChainContent {
+            dao: dao.to_string(),
+            index: index as u64,
+            author: content.author.to_string(),
+            text: content.text.clone(),
+            image_uri: content.image_uri.clone(),
+            timestamp: content.timestamp,
+            vote_count: content.vote_count,
+            round: state.round,
+        };
+        let key = owned_key(dao, record.index);
+        // 바뀐 행만 쓴다 (변경 알림을 받는 WebSocket 구독자에게 같은 콘텐츠가 반복해서 가지 않도록)
+        if CHAIN_CONTENTS.get(database, &key).map_err(db_err)?.as_ref() != Some(&record) {
+            ops.push(CHAIN_CONTENTS.put_op(&key, &record).map_err(db_err)?);
+        }
+        live_keys.push((CHAIN_CONTENTS.name(), key));
+    }
+
+    for proposal in &state.vote_proposals {
+        let record = ChainProposal {
+            dao: dao.to_string(),
+            proposal_id: proposal.proposal_id,
+            proposer: proposal.proposer.to_string(),
+            title: proposal.title.clone(),
+            description: proposal.description.clone(),
+            vote_type: format!("{:?}", proposal.vote_type),
+            options: proposal.options.clone(),
+            start_time: proposal.start_time,
+            end_time: proposal.end_time,
+            vote_count: proposal.votes.len() as u64,
+            status: format!("{:?}", proposal.status),
+            option_power: option_power(proposal),
+        };
+        let key = owned_key(dao, record.proposal_id);
+        if CHAIN_PROPOSALS.get(database, &key).map_err(db_err)?.as_ref() != Some(&record) {
+            ops.push(CHAIN_PROPOSALS.put_op(&key, &record).map_err(db_err)?);
+        }
+        live_keys.push((CHAIN_PROPOSALS.name(), key));
+    }
+
+    for table in [CHAIN_CONTENTS.name(), CHAIN_PROPOSALS.name()] {
+        for (key, _) in database.scan(table, &KeyRange::prefix(owned_prefix(dao))).map_err(db_err)? {
+            if !live_keys.iter().any(|(live_table, live_key)| *live_table == table && *live_key == key) {
+                ops.push(KvOp::delete(table, key));
+            }
+        }
+    }
+
+    Ok(ops)
+}
+
+// DAO 하나를 RPC 에서 읽어 스냅샷을 갱신한다. 계정이 아직 없으면 false.
+pub async fn sync_dao<T: SafeDatabase, A: AccountSource>(
+    database: &T,
+    source: &A,
+    dao: &str,
+) -> Result<bool, SyncError> {
+    let pubkey: Pubkey = dao.parse().map_err(|e: sol::state::ParsePubkeyError| SyncError::DecodeError(e.to_string()))?;
+    let Some(data) = source.get_account_data(&pubkey).await? else {
+        return Ok(false);
+    };
+
+    let state = DaoState::from_account_data(&data)
+        .map_err(|e| SyncError::DecodeError(format!("DaoState of {}: {}", dao, e)))?;
+    if !state.is_initialized {
+        return Ok(false);
+    }
+
+    let ops = snapshot_ops(database, dao, &state, now_secs())?;
+    database.txn(&ops)
+        .map_err(|e| SyncError::DatabaseError(e.to_string()))?;
+    Ok(true)
+}
+
+// 등록된 모든 DAO 를 한 번씩 동기화한다. 한 DAO 가 실패해도 나머지는 계속 진행하고 동기화된 수를 돌려준다.
+pub async fn sync_registered_daos<T: SafeDatabase, A: AccountSource>(
+    database: &T,
+    source: &A,
+) -> Result<usize, SyncError> {
+    let daos = DAO_REGISTRY.scan(database, &KeyRange::all())
+        .map_err(|e| SyncError::DatabaseError(e.to_string()))?;
+
+    let mut synced = 0;
+    for (_, info) in daos {
+        match sync_dao(database, source, &info.pubkey).await {
+            Ok(true) => synced += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!(dao = %info.pubkey, error = %e, "Chain sync failed"),
+        }
+    }
+    Ok(synced)
+}
+
+pub fn spawn_chain_sync<T: SafeDatabase, A: AccountSource>(database: T, source: A, interval: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sync_registered_daos(&database, &source).await {
+                tracing::error!(error = %e, "Chain sync failed");
+            }
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Mutex;
+    use proptest::prelude::*;
+    use sol::state::{Content, RoundState, VoteInfo, VoteType};
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::community::DaoInfo;
+
+    #[derive(Default)]
+    struct FakeAccounts(Mutex<HashMap<Pubkey, Vec<u8>>>);
+
+    impl FakeAccounts {
+        fn set(&self, pubkey: Pubkey, state: &DaoState) {
+            let mut data = borsh::to_vec(state).unwrap();
+            data.resize(data.len() + 256, 0);
+            self.0.lock().unwrap().insert(pubkey, data);
+        }
+    }
+
+    impl AccountSource for FakeAccounts {
+        async fn get_account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, SyncError> {
+            Ok(self.0.lock().unwrap().get(pubkey).cloned())
+        }
+    }
+
+    fn content(text: &str) -> Content {
+        Content {
+            author: Pubkey([2; 32]),
+            text: text.to_string(),
+            image_uri: String::new(),
+            timestamp: 6_400,
+            vote_count: 0,
+        }
+    }
+
+    fn state(contents: Vec<Content>) -> DaoState {
+        DaoState {
+            is_initialized: true,
+            dao_name: "turtles".to_string(),
+            initializer: Pubkey([1; 32]),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: false,
+            deposit_share: 10,
+            timeout_timestamp: 10_000,
+            total_deposit: 7_000,
+            depositors: vec![],
+            contents,
+            vote_proposals: vec![VoteProposal {
+                proposal_id: 0,
+                proposer: Pubkey([3; 32]),
+                title: "longer timer".to_string(),
+                description: String::new(),
+                vote_type: VoteType::ChangeTimeLimit,
+                options: vec!["yes".to_string(), "no".to_string()],
+                start_time: 6_000,
+                end_time: 9_000,
+                votes: vec![
+                    VoteInfo { voter: Pubkey([4; 32]), option_index: 0, voting_power: 3_000 },
+                    VoteInfo { voter: Pubkey([5; 32]), option_index: 1, voting_power: 1_000 },
+                ],
+                status: VoteStatus::Active,
+            }],
+            next_proposal_id: 1,
+            rent_reserve: 1_000,
+            bounty_pool: 7_000,
+            fee_pool: 0,
+            last_submitter: Pubkey::default(),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: false,
+            fee_tiers: vec![],
+            round: 0,
+            max_deposit_per_wallet: 0,
+            max_total_deposit: 0,
+            streak_days: 0,
+            streak_discount: 0,
+            retract_window: 0,
+            retract_refund: 0,
+            round_state: RoundState::Active,
+            min_round_pot: 0,
+        }
+    }
+
+    fn register(database: &MemoryDatabase, pubkey: Pubkey) -> String {
+        let info = DaoInfo {
+            pubkey: pubkey.to_string(),
+            name: "turtles".to_string(),
+            description: String::new(),
+            image_uri: None,
+            storage: Default::default(),
+            registered_at: 0,
+        };
+        DAO_REGISTRY.put(database, info.pubkey.as_bytes(), &info).unwrap();
+        info.pubkey
+    }
+
+    #[tokio::test]
+    async fn test_sync_writes_snapshots() {
+        let database = MemoryDatabase::new();
+        let accounts = FakeAccounts::default();
+        let dao = register(&database, Pubkey([9; 32]));
+        register(&database, Pubkey([8; 32])); // 아직 계정이 없는 DAO
+
+        accounts.set(Pubkey([9; 32]), &state(vec![content("a"), content("b")]));
+        assert_eq!(sync_registered_daos(&database, &accounts).await.unwrap(), 1);
+
+        let snapshot = DAO_SNAPSHOTS.get(&database, dao.as_bytes()).unwrap().unwrap();
+        assert_eq!((snapshot.total_deposit, snapshot.content_count), (7_000, 2));
+        let community = COMMUNITY_CACHE.get(&database, dao.as_bytes()).unwrap().unwrap();
+        assert_eq!((community.last_activity_timestamp, community.active_proposal_count), (6_400, 1));
+
+        let proposals = CHAIN_PROPOSALS.scan(&database, &KeyRange::prefix(owned_prefix(&dao))).unwrap();
+        assert_eq!(proposals[0].1.vote_type, "ChangeTimeLimit");
+        assert_eq!((proposals[0].1.vote_count, proposals[0].1.option_power.as_slice()), (2, &[3_000, 1_000][..]));
+
+        // 타임아웃 처리로 콘텐츠가 비워지면 이전 행도 지워진다
+        accounts.set(Pubkey([9; 32]), &state(vec![content("c")]));
+        assert!(sync_dao(&database, &accounts, &dao).await.unwrap());
+        let texts: Vec<String> = CHAIN_CONTENTS.scan(&database, &KeyRange::prefix(owned_prefix(&dao)))
+            .unwrap()
+            .into_iter()
+            .map(|(_, content)| content.text)
+            .collect();
+        assert_eq!(texts, vec!["c"]);
+
+        // 그대로인 콘텐츠는 다시 쓰지 않는다
+        let ops = snapshot_ops(&database, &dao, &state(vec![content("c")]), 0).unwrap();
+        assert!(ops.iter().all(|op| !matches!(op, KvOp::Put { table, .. } if table == CHAIN_CONTENTS.name())));
+        // 같은 라운드 안에서 사라진 콘텐츠 (철회) 는 남기지 않는다
+        assert!(ROUND_CONTENTS.scan(&database, &KeyRange::all()).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_archives_closed_round_contents() {
+        let database = MemoryDatabase::new();
+        let accounts = FakeAccounts::default();
+        let dao = register(&database, Pubkey([9; 32]));
+        let voted = Content { vote_count: 3, ..content("a") };
+        accounts.set(Pubkey([9; 32]), &state(vec![voted, content("b")]));
+        sync_dao(&database, &accounts, &dao).await.unwrap();
+        HIDDEN_CONTENTS.put(&database, &owned_key(&dao, 1), &1).unwrap();
+
+        // 타임아웃이 라운드를 닫고 다음 라운드의 첫 글이 index 0 을 다시 쓴다
+        accounts.set(Pubkey([9; 32]), &DaoState { round: 1, ..state(vec![content("c")]) });
+        sync_dao(&database, &accounts, &dao).await.unwrap();
+        let archived: Vec<RoundContent> = ROUND_CONTENTS.scan(&database, &KeyRange::prefix(round_content_prefix(&dao, 0)))
+            .unwrap()
+            .into_iter()
+            .map(|(_, archived)| archived)
+            .collect();
+        assert_eq!(archived.iter().map(|archived| (archived.content.text.as_str(), archived.content.vote_count)).collect::<Vec<_>>(), [("a", 3), ("b", 0)]);
+        assert_eq!(archived.iter().map(|archived| archived.hidden).collect::<Vec<_>>(), [false, true]);
+        assert_eq!(CHAIN_CONTENTS.get(&database, &owned_key(&dao, 0)).unwrap().unwrap().text, "c");
+
+        // 한 번 옮긴 뒤에는 지난 라운드 행이 없으니 다시 쓰지 않는다
+        let ops = snapshot_ops(&database, &dao, &DaoState { round: 1, ..state(vec![content("c")]) }, 0).unwrap();
+        assert!(ops.iter().all(|op| !matches!(op, KvOp::Put { table, .. } if table == ROUND_CONTENTS.name())));
+    }
+
+    #[test]
+    fn test_parse_account_info() {
+        let found = json!({"context": {"slot": 1}, "value": {"data": ["AQID", "base64"]}});
+        assert_eq!(parse_account_info(&found).unwrap(), Some(vec![1, 2, 3]));
+
+        let missing = json!({"context": {"slot": 1}, "value": null});
+        assert_eq!(parse_account_info(&missing).unwrap(), None);
+
+        let garbled = json!({"context": {"slot": 1}, "value": {"data": ["***", "base64"]}});
+        assert!(matches!(parse_account_info(&garbled), Err(SyncError::DecodeError(_))));
+    }
+
+    proptest! {
+        // 예치 순서대로 지갑별 투표력을 쌓고 (프로그램의 depositor.amount) 모두 투표했을 때,
+        // 선택지별 합은 넘치지 않고 u128 로 정확히 더한 값과 같다 (u64 를 넘으면 u64::MAX)
+        #[test]
+        fn prop_option_power_never_overflows(
+            deposits in prop::collection::vec((0u8..8, any::<u64>()), 1..50),
+            choices in prop::collection::vec(any::<u8>(), 8),
+            option_count in 1usize..5,
+        ) {
+            let mut powers: BTreeMap<u8, u64> = BTreeMap::new();
+            for (wallet, amount) in deposits {
+                let power = powers.entry(wallet).or_default();
+                *power = power.saturating_add(amount);
+            }
+            let votes: Vec<VoteInfo> = powers.iter()
+                .map(|(wallet, power)| VoteInfo { voter: Pubkey([*wallet; 32]), option_index: choices[*wallet as usize] % 6, voting_power: *power })
+                .collect();
+            let proposal = VoteProposal {
+                proposal_id: 0,
+                proposer: Pubkey([0; 32]),
+                title: String::new(),
+                description: String::new(),
+                vote_type: VoteType::ChangeBaseFee,
+                options: vec![String::new(); option_count],
+                start_time: 0,
+                end_time: 0,
+                votes: votes.clone(),
+                status: VoteStatus::Active,
+            };
+
+            let power = option_power(&proposal);
+            prop_assert_eq!(power.len(), option_count);
+            for (index, total) in power.iter().enumerate() {
+                let exact: u128 = votes.iter().filter(|vote| vote.option_index as usize == index).map(|vote| u128::from(vote.voting_power)).sum();
+                prop_assert_eq!(u128::from(*total), exact.min(u128::from(u64::MAX)));
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::Instrument;
+use tracing_subscriber::EnvFilter;
+
+// 요청마다 붙는 상관 ID. 클라이언트가 보낸 값이 있으면 그대로 쓰고, 응답과 RPC 요청에도 같은 헤더로 싣는다.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    // 사람이 읽는 한 줄 형식 (터미널이면 색을 입힌다)
+    #[default]
+    Pretty,
+    // 줄마다 JSON 객체 하나. 현재 span 과 상위 span 들의 필드(request_id 등)가 함께 찍힌다.
+    Json,
+}
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// 지금 처리 중인 요청의 ID. 백그라운드 작업처럼 요청 밖에서 부르면 None.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+// build_server 가 한 번 부른다. level 은 EnvFilter 문법 (예: "info", "turtle_net=debug,info").
+pub fn init_logging(format: LogFormat, level: &str) {
+    let builder = tracing_subscriber::fmt().with_env_filter(EnvFilter::new(level));
+    let result = match format {
+        LogFormat::Pretty => builder.try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).with_span_list(true).try_init(),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to install log subscriber: {}", e);
+    }
+}
+
+fn request_id(request: &Request) -> String {
+    request.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()))
+}
+
+// 요청 하나를 "request" span 으로 감싸고, 끝나면 상태 코드와 걸린 시간을 남긴다.
+// 핸들러 안의 DB/RPC 호출 로그는 이 span 아래에 달린다.
+pub async fn trace_requests(request: Request, next: Next) -> Response {
+    let request_id = request_id(&request);
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let started = Instant::now();
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .instrument(span.clone())
+        .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+
+    span.in_scope(|| match response.status().is_server_error() {
+        true => tracing::error!(status, latency_ms, "request failed"),
+        false => tracing::info!(status, latency_ms, "request completed"),
+    });
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn echo_request_id() -> String {
+        current_request_id().unwrap_or_default()
+    }
+
+    async fn send(app: &Router, request_id: Option<&str>) -> (String, String) {
+        let mut request = Request::builder().uri("/id");
+        if let Some(request_id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, request_id);
+        }
+        let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let header = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (header, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_request_id_reaches_handler_and_response() {
+        let app = Router::new()
+            .route("/id", get(echo_request_id))
+            .layer(middleware::from_fn(trace_requests));
+
+        let (header, body) = send(&app, Some("client-abc")).await;
+        assert_eq!((header.as_str(), body.as_str()), ("client-abc", "client-abc"));
+
+        // 없거나 이상한 값이면 새로 만든다
+        for request_id in [None, Some("has space"), Some(&*"x".repeat(200))] {
+            let (header, body) = send(&app, request_id).await;
+            assert_eq!(header.len(), 16);
+            assert_eq!(header, body);
+        }
+
+        assert_eq!(current_request_id(), None);
+    }
+}
@@ -0,0 +1,146 @@
+use axum::extract::{Request, State};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+use std::future::Future;
+use std::sync::OnceLock;
+use tower::Layer;
+use turtle_database::kv::KvStore;
+use turtle_database::tenant::Tenanted;
+use sol::state::Pubkey;
+use crate::config::{AppConfig, TenantConfig};
+use crate::indexer::IndexerMetrics;
+use crate::rpc::RpcClient;
+use crate::tx::TxConfig;
+
+// [[tenants]] 의 배포 하나. 서버가 뜰 때 만들어서 끝날 때까지 들고 있는다.
+pub struct Tenant {
+    pub config: TenantConfig,
+    // 이 배포의 program_id 와 RPC. 트랜잭션 빌더와 체인 조회가 이것을 쓴다.
+    pub tx: TxConfig,
+    pub indexer_metrics: IndexerMetrics,
+}
+
+impl Tenant {
+    pub fn new(config: TenantConfig) -> Self {
+        let tx = TxConfig {
+            // 설정을 읽을 때 검증했다
+            program_id: config.program_id.parse::<Pubkey>().unwrap(),
+            rpc: RpcClient::with_endpoints(&config.rpc_endpoints()),
+            lookup_table: config.lookup_table.as_ref().map(|key| key.parse::<Pubkey>().unwrap()),
+        };
+        Self { config, tx, indexer_metrics: IndexerMetrics::new() }
+    }
+
+    pub fn cluster(&self) -> &str {
+        &self.config.cluster
+    }
+
+    pub fn rpc(&self) -> RpcClient {
+        self.tx.rpc.clone()
+    }
+
+    // 같은 저장소 안에서 이 배포의 테이블 이름 앞에 붙는다
+    pub fn table_prefix(&self) -> String {
+        format!("{}.{}.", self.config.cluster, self.config.program_id)
+    }
+
+    pub fn database<S: KvStore>(&self, inner: S) -> Tenanted<S> {
+        Tenanted::new(inner, self.table_prefix())
+    }
+}
+
+static TENANTS: OnceLock<Vec<Tenant>> = OnceLock::new();
+
+// build_server 가 설정을 넣은 뒤 한 번 부른다
+pub fn install_tenants(config: &AppConfig) -> &'static [Tenant] {
+    TENANTS.get_or_init(|| config.tenants.iter().cloned().map(Tenant::new).collect())
+}
+
+pub fn tenants() -> &'static [Tenant] {
+    TENANTS.get().map(Vec::as_slice).unwrap_or(&[])
+}
+
+tokio::task_local! {
+    static CURRENT_TENANT: &'static Tenant;
+}
+
+// 테넌트 라우트 안이면 그 배포. 기본 배포(/api/...) 에서는 None.
+pub fn current_tenant() -> Option<&'static Tenant> {
+    CURRENT_TENANT.try_with(|tenant| *tenant).ok()
+}
+
+pub async fn in_tenant<F: Future>(tenant: &'static Tenant, future: F) -> F::Output {
+    CURRENT_TENANT.scope(tenant, future).await
+}
+
+// 등록할 때 tx_config() 를 읽는 작업(countdown keeper 등) 을 이 배포로 등록한다
+pub fn with_tenant<R>(tenant: &'static Tenant, f: impl FnOnce() -> R) -> R {
+    CURRENT_TENANT.sync_scope(tenant, f)
+}
+
+// nest_service 가 떼어 낸 "/api/{cluster}" 를 "/api" 로 되돌리고, 요청을 이 배포 안에서 처리한다
+async fn enter_tenant(
+    State((tenant, root)): State<(&'static Tenant, &'static str)>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let rest = request.uri().path_and_query().map_or("/", |path| path.as_str());
+    if let Ok(uri) = format!("{}{}", root, rest).parse() {
+        *request.uri_mut() = uri;
+    }
+    in_tenant(tenant, next.run(request)).await
+}
+
+// 이 배포의 라우터를 /api/{cluster}/... 와 /ws/{cluster}/... 에 붙인다.
+// 아래 라우터는 기본 배포와 같은 경로(/api/dao/... 등) 를 그대로 쓴다.
+pub fn mount_tenant(app: Router, tenant: &'static Tenant, tenant_app: Router) -> Router {
+    ["/api", "/ws"].into_iter().fold(app, |app, root| {
+        let service = middleware::from_fn_with_state((tenant, root), enter_tenant).layer(tenant_app.clone());
+        app.nest_service(&format!("{}/{}", root, tenant.cluster()), service)
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    fn devnet() -> &'static Tenant {
+        Box::leak(Box::new(Tenant::new(TenantConfig {
+            cluster: "devnet".to_string(),
+            program_id: "11111111111111111111111111111111".to_string(),
+            rpc_url: "http://127.0.0.1:8899".to_string(),
+            lookup_table: None,
+            rpc_fallback_urls: Vec::new(),
+            sync_interval_secs: None,
+            index_interval_secs: None,
+        })))
+    }
+
+    #[tokio::test]
+    async fn test_tenant_routes_keep_inner_paths() {
+        let tenant = devnet();
+        assert_eq!(tenant.table_prefix(), "devnet.11111111111111111111111111111111.");
+
+        let cluster = || async { current_tenant().map_or("default", Tenant::cluster).to_string() };
+        let tenant_app = Router::new().route("/api/dao", get(cluster)).route("/ws/dao/{pubkey}", get(cluster));
+        let app = mount_tenant(Router::new().route("/api/dao", get(cluster)), tenant, tenant_app);
+
+        for (uri, status, body) in [
+            ("/api/dao", 200, "default"),
+            ("/api/devnet/dao?pubkey=x", 200, "devnet"),
+            ("/ws/devnet/dao/abc", 200, "devnet"),
+            ("/api/mainnet/dao", 404, ""),
+        ] {
+            let response = app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+            assert_eq!(response.status().as_u16(), status, "{}", uri);
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(std::str::from_utf8(&bytes).unwrap(), body, "{}", uri);
+        }
+        assert!(current_tenant().is_none());
+    }
+}
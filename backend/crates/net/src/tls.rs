@@ -0,0 +1,99 @@
+use axum::extract::State;
+use axum::http::header::HOST;
+use axum::http::uri::Authority;
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use crate::config::TlsConfig;
+
+// 평문 요청을 돌려보낼 https 주소. Host 의 포트는 버리고 HTTPS 포트가 443 이 아니면 붙인다.
+pub fn https_location(host: &str, https_port: u16, uri: &Uri) -> Option<String> {
+    let authority = host.parse::<Authority>().ok()?;
+    let path = uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+    Some(match https_port {
+        443 => format!("https://{}{}", authority.host(), path),
+        port => format!("https://{}:{}{}", authority.host(), port, path),
+    })
+}
+
+async fn redirect_to_https(State(https_port): State<u16>, headers: HeaderMap, uri: Uri) -> Response {
+    let location = headers.get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .and_then(|host| https_location(host, https_port, &uri));
+    match location {
+        Some(location) => Redirect::permanent(&location).into_response(),
+        None => (StatusCode::BAD_REQUEST, "Missing or invalid Host header").into_response(),
+    }
+}
+
+// 인증서/키 PEM 을 읽는다. 잘못됐으면 띄우지 않는다.
+pub async fn load_rustls(tls: &TlsConfig) -> RustlsConfig {
+    // reqwest 도 rustls 를 쓰므로 provider 를 하나로 정해 둔다 (이미 정해져 있으면 그대로)
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    match RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await {
+        Ok(rustls) => rustls,
+        Err(e) => panic!("Failed to load TLS certificate {} / key {}: {}", tls.cert_path, tls.key_path, e),
+    }
+}
+
+// redirect_addr 로 들어온 평문 HTTP 요청을 모두 https 로 308 리다이렉트한다
+pub fn spawn_https_redirect(redirect_addr: SocketAddr, https_port: u16) {
+    let app = Router::new().fallback(redirect_to_https).with_state(https_port);
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(redirect_addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!(%redirect_addr, error = %e, "Failed to bind HTTP redirect listener");
+                return;
+            },
+        };
+        tracing::info!(%redirect_addr, "HTTP to HTTPS redirect listening");
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "HTTP redirect server stopped");
+        }
+    });
+}
+
+// SIGHUP 을 받으면 인증서/키를 다시 읽는다. 새 연결부터 새 인증서를 쓰고, 읽지 못하면 쓰던 것을 그대로 둔다.
+#[cfg(unix)]
+pub fn spawn_cert_reload(rustls: RustlsConfig, tls: TlsConfig) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to listen for SIGHUP, TLS certificate reload disabled");
+                return;
+            },
+        };
+        while hangup.recv().await.is_some() {
+            match rustls.reload_from_pem_file(&tls.cert_path, &tls.key_path).await {
+                Ok(()) => tracing::info!(cert_path = %tls.cert_path, "Reloaded TLS certificate"),
+                Err(e) => tracing::warn!(cert_path = %tls.cert_path, error = %e, "Failed to reload TLS certificate, keeping the old one"),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn spawn_cert_reload(_rustls: RustlsConfig, _tls: TlsConfig) {
+    tracing::warn!("SIGHUP is not available on this platform, TLS certificate reload disabled");
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_https_location_keeps_path_and_query() {
+        let uri: Uri = "/api/daos?limit=5".parse().unwrap();
+        assert_eq!(https_location("turtle.app", 443, &uri).as_deref(), Some("https://turtle.app/api/daos?limit=5"));
+        assert_eq!(https_location("turtle.app:80", 8443, &uri).as_deref(), Some("https://turtle.app:8443/api/daos?limit=5"));
+        assert_eq!(https_location("[::1]:80", 443, &"/".parse().unwrap()).as_deref(), Some("https://[::1]/"));
+        assert_eq!(https_location("bad host", 443, &uri), None);
+    }
+}
@@ -0,0 +1,665 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use serde_json::{json, Value};
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::sync::OnceLock;
+use axum::extract::Query;
+use axum::Json;
+use sol::instruction::{self, dao_address, Instruction};
+use sol::state::{Pubkey, VoteType};
+use sol::lookup_table::AddressLookupTable;
+use sol::transaction::{Message, V0Message};
+use crate::config::app_config;
+use crate::fees::{compute_budget_instructions, priority_fee, PriorityFeeSource, DEFAULT_COMPUTE_UNIT_LIMIT};
+use crate::relay::relay_config;
+use crate::rpc::{shared_rpc, RpcClient};
+use crate::sync::AccountSource;
+use crate::tenant::current_tenant;
+use crate::error::ApiError;
+
+// 시드 하나의 최대 길이 (DAO 이름은 PDA 시드로 들어간다)
+pub const MAX_DAO_NAME_LEN: usize = 32;
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum TxError {
+    ValidationError(String),
+    RpcError(String),
+    ConfigError(String),
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            TxError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            TxError::ConfigError(msg) => write!(f, "Config error: {}", msg),
+        }
+    }
+}
+
+impl StdError for TxError {}
+
+impl IntoResponse for TxError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            TxError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            TxError::RpcError(msg) => (StatusCode::BAD_GATEWAY, msg),
+            TxError::ConfigError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+// 트랜잭션을 만들 때 필요한 최근 blockhash
+pub trait BlockhashSource: Send + Sync + 'static {
+    fn latest_blockhash(&self) -> impl Future<Output = Result<[u8; 32], TxError>> + Send;
+}
+
+impl BlockhashSource for RpcClient {
+    async fn latest_blockhash(&self) -> Result<[u8; 32], TxError> {
+        let result = self
+            .call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))
+            .await
+            .map_err(|e| TxError::RpcError(e.to_string()))?;
+        parse_blockhash(&result)
+    }
+}
+
+// {"context": ..., "value": {"blockhash": "<base58>", "lastValidBlockHeight": n}}
+fn parse_blockhash(result: &Value) -> Result<[u8; 32], TxError> {
+    let blockhash = result["value"]["blockhash"]
+        .as_str()
+        .ok_or_else(|| TxError::RpcError(format!("Unexpected getLatestBlockhash response: {}", result)))?;
+    blockhash
+        .parse::<Pubkey>()
+        .map(Pubkey::to_bytes)
+        .map_err(|e| TxError::RpcError(e.to_string()))
+}
+
+// 주소 조회 테이블 계정을 읽어 오는 곳
+pub trait LookupTableSource: Send + Sync + 'static {
+    fn lookup_table(&self, key: &Pubkey) -> impl Future<Output = Result<AddressLookupTable, TxError>> + Send;
+}
+
+impl LookupTableSource for RpcClient {
+    async fn lookup_table(&self, key: &Pubkey) -> Result<AddressLookupTable, TxError> {
+        let data = self.get_account_data(key)
+            .await
+            .map_err(|e| TxError::RpcError(e.to_string()))?
+            .ok_or_else(|| TxError::ConfigError(format!("Lookup table {} does not exist", key)))?;
+        AddressLookupTable::from_account_data(*key, &data).map_err(|e| TxError::ConfigError(e.to_string()))
+    }
+}
+
+pub struct TxConfig {
+    pub program_id: Pubkey,
+    pub rpc: RpcClient,
+    // 설정의 lookup_table. v0 트랜잭션을 만들 때 쓴다.
+    pub lookup_table: Option<Pubkey>,
+}
+
+static TX_CONFIG: OnceLock<Option<TxConfig>> = OnceLock::new();
+
+// 설정의 program_id 와 공유 RPC 클라이언트로 한 번만 만든다.
+// /api/{cluster}/... 요청 안이면 그 배포의 program_id 와 RPC 를 쓴다.
+pub fn tx_config() -> Result<&'static TxConfig, TxError> {
+    if let Some(tenant) = current_tenant() {
+        return Ok(&tenant.tx);
+    }
+    TX_CONFIG
+        .get_or_init(|| {
+            let config = app_config();
+            Some(TxConfig {
+                program_id: config.program_id.as_ref()?.parse().ok()?,
+                rpc: shared_rpc(),
+                lookup_table: config.lookup_table.as_ref().and_then(|key| key.parse().ok()),
+            })
+        })
+        .as_ref()
+        .ok_or_else(|| TxError::ConfigError("program_id is not configured".to_string()))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnsignedTransactionResponse {
+    // 서명 자리가 비어 있는 직렬화된 트랜잭션 (base64). 지갑의 signTransaction 에 그대로 넘긴다.
+    pub transaction: String,
+    pub recent_blockhash: String,
+    pub dao: String,
+    // 붙인 SetComputeUnitPrice (micro-lamports / CU) 와 SetComputeUnitLimit
+    pub priority_fee: u64,
+    pub compute_unit_limit: u32,
+    // "legacy" 또는 "v0". v0 는 지갑이 주소 조회 테이블을 읽어 VersionedTransaction 으로 서명해야 한다.
+    pub version: String,
+}
+
+fn parse_pubkey(field: &str, value: &str) -> Result<Pubkey, TxError> {
+    value.parse::<Pubkey>()
+        .map_err(|_| TxError::ValidationError(format!("Invalid {}: {}", field, value)))
+}
+
+fn parse_vote_type(vote_type: &str) -> Result<VoteType, TxError> {
+    match vote_type {
+        "change_time_limit" => Ok(VoteType::ChangeTimeLimit),
+        "change_base_fee" => Ok(VoteType::ChangeBaseFee),
+        "change_ai_moderation" => Ok(VoteType::ChangeAiModeration),
+        "content_quality_rating" => Ok(VoteType::ContentQualityRating),
+        "change_deposit_resets_timer" => Ok(VoteType::ChangeDepositResetsTimer),
+        "change_fee_tiers" => Ok(VoteType::ChangeFeeTiers),
+        "replace_admin" => Ok(VoteType::ReplaceAdmin),
+        "change_deposit_caps" => Ok(VoteType::ChangeDepositCaps),
+        "change_streak_bonus" => Ok(VoteType::ChangeStreakBonus),
+        "change_retract_policy" => Ok(VoteType::ChangeRetractPolicy),
+        "change_round_minimum" => Ok(VoteType::ChangeRoundMinimum),
+        _ => Err(TxError::ValidationError(format!("Unknown vote_type: {}", vote_type))),
+    }
+}
+
+// payer 가 fee payer 인 트랜잭션. 보통은 wallet 자신이고, sponsored 면 relayer 다.
+// 혼잡할 때 밀리지 않도록 compute budget instruction 을 앞에 붙인다.
+// lookup_table 이 있으면 그 테이블에 든 계정을 인덱스로 줄인 v0 트랜잭션을 만든다.
+pub fn unsigned_transaction(
+    instruction: Instruction,
+    payer: &Pubkey,
+    dao: &Pubkey,
+    recent_blockhash: [u8; 32],
+    priority_fee: u64,
+    lookup_table: Option<&AddressLookupTable>,
+) -> UnsignedTransactionResponse {
+    let mut instructions = compute_budget_instructions(priority_fee);
+    instructions.push(instruction);
+    let (transaction, version) = match lookup_table {
+        Some(table) => (V0Message::new(&instructions, payer, recent_blockhash, std::slice::from_ref(table)).to_unsigned_transaction(), "v0"),
+        None => (Message::new(&instructions, payer, recent_blockhash).to_unsigned_transaction(), "legacy"),
+    };
+    UnsignedTransactionResponse {
+        transaction: base64::engine::general_purpose::STANDARD.encode(transaction),
+        recent_blockhash: Pubkey(recent_blockhash).to_string(),
+        dao: dao.to_string(),
+        priority_fee,
+        compute_unit_limit: DEFAULT_COMPUTE_UNIT_LIMIT,
+        version: version.to_string(),
+    }
+}
+
+// 우선 수수료는 요청의 priority_fee 를 쓰고, 없으면 DAO 계정에 걸린 최근 수수료로 추정한다
+async fn respond<B: BlockhashSource + PriorityFeeSource>(
+    source: &B,
+    instruction: Instruction,
+    payer: &Pubkey,
+    dao: &Pubkey,
+    requested_fee: Option<u64>,
+    lookup_table: Option<&AddressLookupTable>,
+) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let priority_fee = priority_fee(source, &[*dao], requested_fee).await?;
+    let recent_blockhash = source.latest_blockhash().await?;
+    Ok(Json(unsigned_transaction(instruction, payer, dao, recent_blockhash, priority_fee, lookup_table)))
+}
+
+// versioned 를 요청했으면 설정된 주소 조회 테이블을 읽어 온다. 테이블이 설정되지 않았으면 v0 를 만들 수 없다.
+pub async fn lookup_table_for<S: LookupTableSource>(source: &S, configured: Option<&Pubkey>, versioned: bool) -> Result<Option<AddressLookupTable>, TxError> {
+    if !versioned {
+        return Ok(None);
+    }
+    let key = configured.ok_or_else(|| TxError::ConfigError("lookup_table is not configured".to_string()))?;
+    source.lookup_table(key).await.map(Some)
+}
+
+#[derive(Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BuildOptions {
+    // true 면 relayer 를 fee payer 로 넣는다. 지갑이 서명한 뒤 /api/tx/relay 로 보낸다.
+    #[serde(default)]
+    sponsored: bool,
+    // 우선 수수료 (micro-lamports / CU). 없으면 최근 수수료로 추정한다.
+    priority_fee: Option<u64>,
+    // true 면 설정된 주소 조회 테이블로 v0 트랜잭션을 만든다. relay 는 레거시만 받으므로 sponsored 와 함께 쓸 수 없다.
+    #[serde(default)]
+    versioned: bool,
+}
+
+// sponsored 를 받지 않는 빌더 (DAO 생성, 예치) 의 쿼리
+#[derive(Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeeOptions {
+    // 우선 수수료 (micro-lamports / CU). 없으면 최근 수수료로 추정한다.
+    priority_fee: Option<u64>,
+    // true 면 설정된 주소 조회 테이블로 v0 트랜잭션을 만든다
+    #[serde(default)]
+    versioned: bool,
+}
+
+impl BuildOptions {
+    fn fee_payer(&self, wallet: &Pubkey) -> Result<Pubkey, TxError> {
+        if !self.sponsored {
+            return Ok(*wallet);
+        }
+        if self.versioned {
+            return Err(TxError::ValidationError("sponsored transactions must be legacy, drop versioned".to_string()));
+        }
+        relay_config()
+            .map(|config| config.fee_payer())
+            .map_err(|e| TxError::ConfigError(e.to_string()))
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct InitializeDaoTx {
+    wallet: String,
+    dao_name: String,
+    time_limit: u64,
+    base_fee: u64,
+    #[serde(default)]
+    ai_moderation: bool,
+    deposit_share: u8,
+    // 예치도 라운드 타이머를 다시 거는지 (기본은 글 제출만)
+    #[serde(default)]
+    deposit_resets_timer: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct DepositTx {
+    wallet: String,
+    dao: String,
+    amount: u64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SubmitContentTx {
+    wallet: String,
+    dao: String,
+    text: String,
+    #[serde(default)]
+    image_uri: String,
+    // true 면 연속 제출 기록 계정을 붙여 오늘 제출을 연속 일수에 센다 (처음 한 번은 작성자가 rent 를 낸다)
+    #[serde(default)]
+    streak: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateVoteTx {
+    wallet: String,
+    dao: String,
+    title: String,
+    #[serde(default)]
+    description: String,
+    vote_type: String,
+    options: Vec<String>,
+    voting_period: u64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CastVoteTx {
+    wallet: String,
+    dao: String,
+    proposal_id: u64,
+    option_index: u8,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ProcessTimeoutTx {
+    wallet: String,
+    dao: String,
+    // DAO 의 지금 round (승자가 나온 라운드 수). 승자가 있으면 이 번호의 RoundResult 계정이 만들어진다
+    #[serde(default)]
+    round: u64,
+    // DaoState.last_submitter. 상금을 받을 계정으로, 제출이 없었으면 비워 둔다
+    #[serde(default)]
+    last_submitter: Option<String>,
+}
+
+// 요청 본문을 (서명자, DAO 계정, instruction) 으로 바꾼다. 프로그램이 거부할 값은 여기서 먼저 걸러 낸다.
+impl InitializeDaoTx {
+    fn build(self, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Instruction), TxError> {
+        let wallet = parse_pubkey("wallet", &self.wallet)?;
+        if self.dao_name.is_empty() || self.dao_name.len() > MAX_DAO_NAME_LEN {
+            return Err(TxError::ValidationError(format!("dao_name must be 1..={} bytes", MAX_DAO_NAME_LEN)));
+        }
+        if self.deposit_share > 100 {
+            return Err(TxError::ValidationError("deposit_share must be 0..=100".to_string()));
+        }
+
+        // PDA 는 서버에서 계산한다 (seeds = "dao", initializer, dao_name)
+        let (dao, _) = dao_address(program_id, &wallet, &self.dao_name)
+            .ok_or_else(|| TxError::ValidationError("Could not derive DAO address".to_string()))?;
+        let instruction = instruction::initialize_dao(
+            program_id, &wallet, &dao, self.dao_name, self.time_limit, self.base_fee, self.ai_moderation, self.deposit_share,
+            self.deposit_resets_timer,
+        );
+        Ok((wallet, dao, instruction))
+    }
+}
+
+impl DepositTx {
+    fn build(self, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Instruction), TxError> {
+        let wallet = parse_pubkey("wallet", &self.wallet)?;
+        let dao = parse_pubkey("dao", &self.dao)?;
+        if self.amount == 0 {
+            return Err(TxError::ValidationError("amount must be greater than 0".to_string()));
+        }
+        Ok((wallet, dao, instruction::deposit(program_id, &wallet, &dao, self.amount)))
+    }
+}
+
+impl SubmitContentTx {
+    fn build(self, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Instruction), TxError> {
+        let wallet = parse_pubkey("wallet", &self.wallet)?;
+        let dao = parse_pubkey("dao", &self.dao)?;
+        if self.text.is_empty() {
+            return Err(TxError::ValidationError("text is required".to_string()));
+        }
+        let instruction = match self.streak {
+            true => instruction::submit_content_with_streak(program_id, &wallet, &dao, self.text, self.image_uri),
+            false => instruction::submit_content(program_id, &wallet, &dao, self.text, self.image_uri),
+        };
+        Ok((wallet, dao, instruction))
+    }
+}
+
+impl CreateVoteTx {
+    fn build(self, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Instruction), TxError> {
+        let wallet = parse_pubkey("wallet", &self.wallet)?;
+        let dao = parse_pubkey("dao", &self.dao)?;
+        let vote_type = parse_vote_type(&self.vote_type)?;
+        if self.options.len() < 2 {
+            return Err(TxError::ValidationError("At least two options are required".to_string()));
+        }
+        let instruction = instruction::create_vote(
+            program_id, &wallet, &dao, self.title, self.description, vote_type, self.options, self.voting_period,
+        );
+        Ok((wallet, dao, instruction))
+    }
+}
+
+impl CastVoteTx {
+    fn build(self, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Instruction), TxError> {
+        let wallet = parse_pubkey("wallet", &self.wallet)?;
+        let dao = parse_pubkey("dao", &self.dao)?;
+        Ok((wallet, dao, instruction::cast_vote(program_id, &wallet, &dao, self.proposal_id, self.option_index)))
+    }
+}
+
+impl ProcessTimeoutTx {
+    fn build(self, program_id: &Pubkey) -> Result<(Pubkey, Pubkey, Instruction), TxError> {
+        let wallet = parse_pubkey("wallet", &self.wallet)?;
+        let dao = parse_pubkey("dao", &self.dao)?;
+        let last_submitter = self.last_submitter.as_deref().map(|key| parse_pubkey("last_submitter", key)).transpose()?.unwrap_or_default();
+        Ok((wallet, dao, instruction::process_timeout(program_id, &wallet, &dao, self.round, &last_submitter)))
+    }
+}
+
+// 관리자가 보상 분배(ProcessTimeout) 를 대신 시작할 때 쓰는 초안. wallet 이 서명하고 수수료를 낸다.
+pub async fn draft_process_timeout<B: BlockhashSource + PriorityFeeSource>(source: &B, program_id: &Pubkey, wallet: &Pubkey, dao: &Pubkey, round: u64, last_submitter: &Pubkey) -> Result<UnsignedTransactionResponse, TxError> {
+    let Json(response) = respond(source, instruction::process_timeout(program_id, wallet, dao, round, last_submitter), wallet, dao, None, None).await?;
+    Ok(response)
+}
+
+// 품질 보상(DistributeQualityRewards) 초안. 받는 사람과 가중치는 관리자 API 가 투표 집계로 정한다.
+// round 의 RoundResult.quality_pool 을 나누므로 그 라운드를 ProcessTimeout 이 닫은 뒤에 보내야 한다.
+// 받는 사람마다 계정이 하나씩 늘어나므로 테이블이 있으면 v0 로 만든다.
+#[allow(clippy::too_many_arguments)]
+pub async fn draft_quality_rewards<B: BlockhashSource + PriorityFeeSource>(
+    source: &B,
+    program_id: &Pubkey,
+    wallet: &Pubkey,
+    dao: &Pubkey,
+    round: u64,
+    creators: Vec<Pubkey>,
+    weights: Vec<u8>,
+    lookup_table: Option<&AddressLookupTable>,
+) -> Result<UnsignedTransactionResponse, TxError> {
+    let instruction = instruction::distribute_quality_rewards(program_id, wallet, dao, round, creators, weights);
+    let Json(response) = respond(source, instruction, wallet, dao, None, lookup_table).await?;
+    Ok(response)
+}
+
+#[utoipa::path(
+    post, path = "/api/tx/initialize-dao", tag = "tx",
+    params(FeeOptions),
+    request_body = InitializeDaoTx,
+    responses(
+        (status = 200, description = "Unsigned transaction for the wallet to sign", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 502, description = "Could not fetch a recent blockhash", body = String),
+        (status = 503, description = "program_id or relayer is not configured", body = String),
+    )
+)]
+pub async fn build_initialize_dao_tx(Query(options): Query<FeeOptions>, Json(request): Json<InitializeDaoTx>) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let config = tx_config()?;
+    let (wallet, dao, instruction) = request.build(&config.program_id)?;
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), options.versioned).await?;
+    respond(&config.rpc, instruction, &wallet, &dao, options.priority_fee, lookup_table.as_ref()).await
+}
+
+#[utoipa::path(
+    post, path = "/api/tx/deposit", tag = "tx",
+    params(FeeOptions),
+    request_body = DepositTx,
+    responses(
+        (status = 200, description = "Unsigned transaction for the wallet to sign", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 502, description = "Could not fetch a recent blockhash", body = String),
+        (status = 503, description = "program_id or relayer is not configured", body = String),
+    )
+)]
+pub async fn build_deposit_tx(Query(options): Query<FeeOptions>, Json(request): Json<DepositTx>) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let config = tx_config()?;
+    let (wallet, dao, instruction) = request.build(&config.program_id)?;
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), options.versioned).await?;
+    respond(&config.rpc, instruction, &wallet, &dao, options.priority_fee, lookup_table.as_ref()).await
+}
+
+#[utoipa::path(
+    post, path = "/api/tx/submit-content", tag = "tx",
+    params(BuildOptions),
+    request_body = SubmitContentTx,
+    responses(
+        (status = 200, description = "Unsigned transaction for the wallet to sign", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 502, description = "Could not fetch a recent blockhash", body = String),
+        (status = 503, description = "program_id or relayer is not configured", body = String),
+    )
+)]
+pub async fn build_submit_content_tx(Query(options): Query<BuildOptions>, Json(request): Json<SubmitContentTx>) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let config = tx_config()?;
+    let (wallet, dao, instruction) = request.build(&config.program_id)?;
+    let payer = options.fee_payer(&wallet)?;
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), options.versioned).await?;
+    respond(&config.rpc, instruction, &payer, &dao, options.priority_fee, lookup_table.as_ref()).await
+}
+
+#[utoipa::path(
+    post, path = "/api/tx/create-vote", tag = "tx",
+    params(BuildOptions),
+    request_body = CreateVoteTx,
+    responses(
+        (status = 200, description = "Unsigned transaction for the wallet to sign", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 502, description = "Could not fetch a recent blockhash", body = String),
+        (status = 503, description = "program_id or relayer is not configured", body = String),
+    )
+)]
+pub async fn build_create_vote_tx(Query(options): Query<BuildOptions>, Json(request): Json<CreateVoteTx>) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let config = tx_config()?;
+    let (wallet, dao, instruction) = request.build(&config.program_id)?;
+    let payer = options.fee_payer(&wallet)?;
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), options.versioned).await?;
+    respond(&config.rpc, instruction, &payer, &dao, options.priority_fee, lookup_table.as_ref()).await
+}
+
+#[utoipa::path(
+    post, path = "/api/tx/vote", tag = "tx",
+    params(BuildOptions),
+    request_body = CastVoteTx,
+    responses(
+        (status = 200, description = "Unsigned transaction for the wallet to sign", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 502, description = "Could not fetch a recent blockhash", body = String),
+        (status = 503, description = "program_id or relayer is not configured", body = String),
+    )
+)]
+pub async fn build_vote_tx(Query(options): Query<BuildOptions>, Json(request): Json<CastVoteTx>) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let config = tx_config()?;
+    let (wallet, dao, instruction) = request.build(&config.program_id)?;
+    let payer = options.fee_payer(&wallet)?;
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), options.versioned).await?;
+    respond(&config.rpc, instruction, &payer, &dao, options.priority_fee, lookup_table.as_ref()).await
+}
+
+#[utoipa::path(
+    post, path = "/api/tx/process-timeout", tag = "tx",
+    params(BuildOptions),
+    request_body = ProcessTimeoutTx,
+    responses(
+        (status = 200, description = "Unsigned transaction for the wallet to sign", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid field", body = String),
+        (status = 502, description = "Could not fetch a recent blockhash", body = String),
+        (status = 503, description = "program_id or relayer is not configured", body = String),
+    )
+)]
+pub async fn build_process_timeout_tx(Query(options): Query<BuildOptions>, Json(request): Json<ProcessTimeoutTx>) -> Result<Json<UnsignedTransactionResponse>, TxError> {
+    let config = tx_config()?;
+    let (wallet, dao, instruction) = request.build(&config.program_id)?;
+    let payer = options.fee_payer(&wallet)?;
+    let lookup_table = lookup_table_for(&config.rpc, config.lookup_table.as_ref(), options.versioned).await?;
+    respond(&config.rpc, instruction, &payer, &dao, options.priority_fee, lookup_table.as_ref()).await
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol::transaction::SIGNATURE_BYTES;
+
+    struct FixedBlockhash;
+
+    impl BlockhashSource for FixedBlockhash {
+        async fn latest_blockhash(&self) -> Result<[u8; 32], TxError> {
+            Ok([9; 32])
+        }
+    }
+
+    impl PriorityFeeSource for FixedBlockhash {
+        async fn recent_prioritization_fees(&self, _accounts: &[Pubkey]) -> Result<Vec<u64>, TxError> {
+            Ok(vec![0, 1_000])
+        }
+    }
+
+    const PROGRAM_ID: Pubkey = Pubkey([3; 32]);
+
+    fn wallet() -> String {
+        Pubkey([1; 32]).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_dao_derives_pda() {
+        let request = InitializeDaoTx {
+            wallet: wallet(),
+            dao_name: "turtles".to_string(),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 20,
+            deposit_resets_timer: false,
+        };
+        let (wallet, dao, instruction) = request.build(&PROGRAM_ID).unwrap();
+        assert_eq!(dao.to_string(), "DY3qS2728PedjRB4m8jX4M6tJBse7zB82QhTfF1k4GPM");
+
+        let Json(response) = respond(&FixedBlockhash, instruction, &wallet, &dao, None, None).await.unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&response.transaction).unwrap();
+
+        // 서명 1개 자리 + 헤더 (서명자 1, 읽기 전용 서명자 0, 읽기 전용 비서명자 3: system program, compute budget, program)
+        assert_eq!(bytes[0], 1);
+        assert!(bytes[1..1 + SIGNATURE_BYTES].iter().all(|b| *b == 0));
+        assert_eq!(&bytes[1 + SIGNATURE_BYTES..1 + SIGNATURE_BYTES + 3], &[1, 0, 3]);
+        assert_eq!((response.priority_fee, response.compute_unit_limit), (1_000, DEFAULT_COMPUTE_UNIT_LIMIT));
+
+        // compute budget instruction 두 개가 프로그램 instruction 앞에 온다
+        let message = Message::deserialize(&bytes[1 + SIGNATURE_BYTES..]).unwrap();
+        let programs: Vec<Pubkey> = message.instructions.iter().map(|ix| message.account_keys[ix.program_id_index as usize]).collect();
+        assert_eq!(programs, [instruction::COMPUTE_BUDGET_PROGRAM_ID, instruction::COMPUTE_BUDGET_PROGRAM_ID, PROGRAM_ID]);
+
+        // 직접 넘긴 priority_fee 는 추정보다 앞선다
+        let (wallet, dao, instruction) = DepositTx { wallet: wallet.to_string(), dao: dao.to_string(), amount: 1 }.build(&PROGRAM_ID).unwrap();
+        let Json(response) = respond(&FixedBlockhash, instruction, &wallet, &dao, Some(25), None).await.unwrap();
+        assert_eq!(response.priority_fee, 25);
+        assert_eq!(response.recent_blockhash, Pubkey([9; 32]).to_string());
+        assert_eq!(response.dao, dao.to_string());
+    }
+
+    #[test]
+    fn test_rejects_invalid_requests() {
+        let dao = Pubkey([2; 32]).to_string();
+
+        let zero_deposit = DepositTx { wallet: wallet(), dao: dao.clone(), amount: 0 };
+        assert!(matches!(zero_deposit.build(&PROGRAM_ID), Err(TxError::ValidationError(_))));
+
+        let bad_wallet = CastVoteTx { wallet: "nope".to_string(), dao: dao.clone(), proposal_id: 0, option_index: 0 };
+        assert!(matches!(bad_wallet.build(&PROGRAM_ID), Err(TxError::ValidationError(_))));
+
+        let bad_vote = CreateVoteTx {
+            wallet: wallet(),
+            dao: dao.clone(),
+            title: "fee".to_string(),
+            description: String::new(),
+            vote_type: "change_everything".to_string(),
+            options: vec!["1%".to_string(), "2%".to_string()],
+            voting_period: 60,
+        };
+        assert!(matches!(bad_vote.build(&PROGRAM_ID), Err(TxError::ValidationError(_))));
+
+        let long_name = InitializeDaoTx {
+            wallet: wallet(),
+            dao_name: "x".repeat(MAX_DAO_NAME_LEN + 1),
+            time_limit: 1,
+            base_fee: 1,
+            ai_moderation: false,
+            deposit_share: 0,
+            deposit_resets_timer: false,
+        };
+        assert!(matches!(long_name.build(&PROGRAM_ID), Err(TxError::ValidationError(_))));
+
+        let deposit = DepositTx { wallet: wallet(), dao, amount: 5_000 };
+        let (_, _, instruction) = deposit.build(&PROGRAM_ID).unwrap();
+        assert_eq!(instruction.program_id, PROGRAM_ID);
+    }
+
+    impl LookupTableSource for FixedBlockhash {
+        async fn lookup_table(&self, key: &Pubkey) -> Result<AddressLookupTable, TxError> {
+            Ok(AddressLookupTable { key: *key, addresses: vec![Pubkey([2; 32]), sol::instruction::SYSTEM_PROGRAM_ID] })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_versioned_transaction_uses_lookup_table() {
+        let table_key = Pubkey([8; 32]);
+        assert!(lookup_table_for(&FixedBlockhash, Some(&table_key), false).await.unwrap().is_none());
+        assert!(matches!(lookup_table_for(&FixedBlockhash, None, true).await, Err(TxError::ConfigError(_))));
+        let table = lookup_table_for(&FixedBlockhash, Some(&table_key), true).await.unwrap();
+
+        let (wallet, dao, instruction) = DepositTx { wallet: wallet(), dao: Pubkey([2; 32]).to_string(), amount: 1 }.build(&PROGRAM_ID).unwrap();
+        let Json(response) = respond(&FixedBlockhash, instruction, &wallet, &dao, None, table.as_ref()).await.unwrap();
+        assert_eq!(response.version, "v0");
+
+        // 서명 1개 다음 버전 바이트, 헤더, 그리고 DAO 와 system program 이 빠진 정적 계정 3개 (wallet, compute budget, program)
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&response.transaction).unwrap();
+        let message = &bytes[1 + SIGNATURE_BYTES..];
+        assert_eq!(&message[..5], &[0x80, 1, 0, 2, 3]);
+
+        let sponsored = BuildOptions { sponsored: true, priority_fee: None, versioned: true };
+        assert!(matches!(sponsored.fee_payer(&wallet), Err(TxError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_parse_blockhash() {
+        let result = json!({"context": {"slot": 1}, "value": {"blockhash": Pubkey([9; 32]).to_string(), "lastValidBlockHeight": 10}});
+        assert_eq!(parse_blockhash(&result).unwrap(), [9; 32]);
+        assert!(parse_blockhash(&json!({"value": {}})).is_err());
+    }
+}
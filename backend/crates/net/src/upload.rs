@@ -0,0 +1,496 @@
+use axum::extract::{Multipart, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::sync::OnceLock;
+use sol::state::Pubkey;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::upload::{ImageVariant, StorageKind, UploadRecord};
+use crate::dao::DAO_REGISTRY;
+use crate::config::{app_config, AppConfig};
+use crate::media::{process_image, ProcessedImage};
+use crate::session::AuthedWallet;
+use crate::error::ApiError;
+
+// IPFS HTTP API (kubo 의 /api/v0). 로컬 노드가 기본이고, 같은 API 를 주는 pinning 서비스로 바꿀 수 있다.
+pub const DEFAULT_IPFS_API_URL: &str = "http://127.0.0.1:5001";
+
+// content_hash -> UploadRecord
+pub const UPLOADS: TypedTable<UploadRecord> = TypedTable::new("uploads");
+
+pub const MAX_TEXT_BYTES: usize = 4 * 1024;
+pub const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum UploadError {
+    MultipartError(String),
+    ValidationError(String),
+    StorageError(String),
+    DatabaseError(String),
+    ConfigError(String),
+    NotFound(String),
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadError::MultipartError(msg) => write!(f, "Multipart error: {}", msg),
+            UploadError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            UploadError::StorageError(msg) => write!(f, "Storage error: {}", msg),
+            UploadError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            UploadError::ConfigError(msg) => write!(f, "Config error: {}", msg),
+            UploadError::NotFound(hash) => write!(f, "Upload not found: {}", hash),
+        }
+    }
+}
+
+impl StdError for UploadError {}
+
+impl IntoResponse for UploadError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            UploadError::MultipartError(msg) => (StatusCode::BAD_REQUEST, msg),
+            UploadError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            UploadError::StorageError(msg) => (StatusCode::BAD_GATEWAY, msg),
+            UploadError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            UploadError::ConfigError(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            UploadError::NotFound(hash) => (StatusCode::NOT_FOUND, format!("Upload not found: {}", hash)),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+// 바이트를 저장하고 그 위치 URI (ipfs://..., ar://...) 를 돌려주는 곳.
+// 서버는 IpfsClient / ArweaveClient 를 쓰고, 테스트는 메모리에 담는다.
+pub trait StorageProvider: Send + Sync + 'static {
+    fn kind(&self) -> StorageKind;
+    fn store(&self, file_name: &str, content_type: &str, bytes: Vec<u8>) -> impl Future<Output = Result<String, UploadError>> + Send;
+}
+
+pub struct IpfsClient {
+    http: reqwest::Client,
+    api_url: String,
+    auth: Option<String>,
+}
+
+impl IpfsClient {
+    pub fn new(api_url: &str, auth: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            auth,
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(&config.ipfs_api_url, config.ipfs_api_auth.clone())
+    }
+}
+
+impl StorageProvider for IpfsClient {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Ipfs
+    }
+
+    async fn store(&self, file_name: &str, content_type: &str, bytes: Vec<u8>) -> Result<String, UploadError> {
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(file_name.to_string())
+            .mime_str(content_type)
+            .map_err(|e| UploadError::ValidationError(e.to_string()))?;
+        let form = reqwest::multipart::Form::new().part("file", part);
+
+        let mut request = self.http
+            .post(format!("{}/api/v0/add", self.api_url))
+            .query(&[("pin", "true"), ("cid-version", "1")])
+            .multipart(form);
+        if let Some(auth) = &self.auth {
+            request = request.header(reqwest::header::AUTHORIZATION, auth);
+        }
+
+        let response: Value = request
+            .send()
+            .await
+            .map_err(|e| UploadError::StorageError(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| UploadError::StorageError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| UploadError::StorageError(e.to_string()))?;
+        Ok(format!("ipfs://{}", parse_add_response(&response)?))
+    }
+}
+
+pub struct ArweaveClient {
+    http: reqwest::Client,
+    upload_url: String,
+    auth: Option<String>,
+}
+
+impl ArweaveClient {
+    pub fn new(upload_url: &str, auth: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            upload_url: upload_url.to_string(),
+            auth,
+        }
+    }
+
+    // arweave_upload_url 이 없으면 arweave 저장소는 꺼진다
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let upload_url = config.arweave_upload_url.as_ref()?;
+        Some(Self::new(upload_url, config.arweave_upload_auth.clone()))
+    }
+}
+
+impl StorageProvider for ArweaveClient {
+    fn kind(&self) -> StorageKind {
+        StorageKind::Arweave
+    }
+
+    async fn store(&self, _file_name: &str, content_type: &str, bytes: Vec<u8>) -> Result<String, UploadError> {
+        let mut request = self.http
+            .post(&self.upload_url)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(bytes);
+        if let Some(auth) = &self.auth {
+            request = request.header(reqwest::header::AUTHORIZATION, auth);
+        }
+
+        let response: Value = request
+            .send()
+            .await
+            .map_err(|e| UploadError::StorageError(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| UploadError::StorageError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| UploadError::StorageError(e.to_string()))?;
+        Ok(format!("ar://{}", parse_arweave_response(&response)?))
+    }
+}
+
+// {"Name": "...", "Hash": "<cid>", "Size": "..."}
+fn parse_add_response(response: &Value) -> Result<String, UploadError> {
+    response["Hash"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| UploadError::StorageError(format!("Unexpected IPFS add response: {}", response)))
+}
+
+// {"id": "<43자 base64url 트랜잭션 ID>", ...}
+fn parse_arweave_response(response: &Value) -> Result<String, UploadError> {
+    response["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| UploadError::StorageError(format!("Unexpected Arweave upload response: {}", response)))
+}
+
+static IPFS_CLIENT: OnceLock<IpfsClient> = OnceLock::new();
+static ARWEAVE_CLIENT: OnceLock<Option<ArweaveClient>> = OnceLock::new();
+
+pub fn ipfs_client() -> &'static IpfsClient {
+    IPFS_CLIENT.get_or_init(|| IpfsClient::from_config(app_config()))
+}
+
+pub fn arweave_client() -> Result<&'static ArweaveClient, UploadError> {
+    ARWEAVE_CLIENT
+        .get_or_init(|| ArweaveClient::from_config(app_config()))
+        .as_ref()
+        .ok_or_else(|| UploadError::ConfigError("arweave_upload_url is not configured, arweave storage disabled".to_string()))
+}
+
+pub fn parse_storage(value: &str) -> Result<StorageKind, UploadError> {
+    match value {
+        "ipfs" => Ok(StorageKind::Ipfs),
+        "arweave" => Ok(StorageKind::Arweave),
+        _ => Err(UploadError::ValidationError(format!("Unknown storage: {}", value))),
+    }
+}
+
+// 요청에 storage 가 있으면 그걸 쓰고, 없으면 등록된 DAO 의 설정, 그것도 없으면 IPFS
+pub fn choose_storage<T: SafeDatabase>(database: &T, requested: Option<StorageKind>, dao: &str) -> Result<StorageKind, UploadError> {
+    if let Some(kind) = requested {
+        return Ok(kind);
+    }
+    let configured = DAO_REGISTRY.get(database, dao.as_bytes())
+        .map_err(|e| UploadError::DatabaseError(e.to_string()))?
+        .map(|info| info.storage);
+    Ok(configured.unwrap_or_default())
+}
+
+// 텍스트와 이미지 참조로 만드는 콘텐츠 해시. 같은 글과 이미지는 항상 같은 해시가 된다.
+pub fn content_hash(text: &str, image_uri: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    hasher.update([0]);
+    hasher.update(image_uri.as_bytes());
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub struct Image {
+    pub file_name: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadResponse {
+    pub content_hash: String,
+    pub content_uri: String,
+    // SubmitContent 의 image_uri 로 그대로 넘긴다
+    pub image_uri: String,
+    pub image_variants: Vec<ImageVariant>,
+}
+
+impl From<UploadRecord> for UploadResponse {
+    fn from(record: UploadRecord) -> Self {
+        Self {
+            content_hash: record.content_hash,
+            content_uri: record.content_uri,
+            image_uri: record.image_uri,
+            image_variants: record.image_variants,
+        }
+    }
+}
+
+// 이미지(원본, 축소본)를 먼저 올리고, 텍스트와 이미지 참조를 담은 JSON 문서를 올린 뒤 매핑을 저장한다.
+// 해시와 저장 레코드는 저장소와 상관없이 같다. 해시에는 원본 이미지 참조만 들어간다.
+pub async fn store_upload<T: SafeDatabase, P: StorageProvider>(
+    database: &T,
+    storage: &P,
+    author: &str,
+    dao: &str,
+    text: String,
+    image: Option<ProcessedImage>,
+    now: u64,
+) -> Result<UploadRecord, UploadError> {
+    let mut image_uri = String::new();
+    let mut image_variants = Vec::new();
+    if let Some(image) = image {
+        let original = image.original;
+        image_uri = storage.store(&original.file_name, &original.content_type, original.bytes).await?;
+        for variant in image.variants {
+            let Image { file_name, content_type, bytes } = variant.image;
+            let uri = storage.store(&file_name, &content_type, bytes).await?;
+            image_variants.push(ImageVariant {
+                name: variant.name.to_string(),
+                uri,
+                content_type,
+                width: variant.width,
+                height: variant.height,
+            });
+        }
+    }
+    let content_hash = content_hash(&text, &image_uri);
+
+    let document = json!({
+        "text": text,
+        "image_uri": image_uri,
+        "image_variants": image_variants,
+        "author": author,
+        "dao": dao,
+        "content_hash": content_hash,
+    });
+    let document = serde_json::to_vec(&document)
+        .map_err(|e| UploadError::ValidationError(e.to_string()))?;
+    let content_uri = storage.store("content.json", "application/json", document).await?;
+
+    let record = UploadRecord {
+        content_hash,
+        content_uri,
+        image_uri,
+        image_variants,
+        author: author.to_string(),
+        dao: dao.to_string(),
+        storage: storage.kind(),
+        uploaded_at: now,
+    };
+    UPLOADS.put(database, record.content_hash.as_bytes(), &record)
+        .map_err(|e| UploadError::DatabaseError(e.to_string()))?;
+    Ok(record)
+}
+
+// multipart 필드: dao, text (필수), image, storage ("ipfs" | "arweave") (선택)
+pub async fn upload_content<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<UploadResponse>), UploadError> {
+    let mut dao = String::new();
+    let mut text = String::new();
+    let mut image = None;
+    let mut requested = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| UploadError::MultipartError(e.to_string()))? {
+        let name = field.name().unwrap_or("").to_string();
+
+        match name.as_str() {
+            "dao" => {
+                dao = field.text().await.map_err(|e| UploadError::MultipartError(e.to_string()))?;
+            },
+            "text" => {
+                text = field.text().await.map_err(|e| UploadError::MultipartError(e.to_string()))?;
+            },
+            "storage" => {
+                let value = field.text().await.map_err(|e| UploadError::MultipartError(e.to_string()))?;
+                requested = Some(parse_storage(&value)?);
+            },
+            "image" => {
+                let file_name = field.file_name().unwrap_or("image").to_string();
+                let content_type = field.content_type().unwrap_or("").to_string();
+                let bytes = field.bytes().await.map_err(|e| UploadError::MultipartError(e.to_string()))?;
+
+                if !bytes.is_empty() {
+                    image = Some(Image { file_name, content_type, bytes: bytes.to_vec() });
+                }
+            },
+            _ => continue,
+        }
+    }
+
+    dao.parse::<Pubkey>()
+        .map_err(|_| UploadError::ValidationError(format!("Invalid dao: {}", dao)))?;
+    if text.is_empty() || text.len() > MAX_TEXT_BYTES {
+        return Err(UploadError::ValidationError(format!("text must be 1..={} bytes", MAX_TEXT_BYTES)));
+    }
+    let image = match image {
+        Some(image) => {
+            if image.bytes.len() > MAX_IMAGE_BYTES {
+                return Err(UploadError::ValidationError(format!("image must be at most {} bytes", MAX_IMAGE_BYTES)));
+            }
+            // 보낸 그대로 올리지 않고 형식 확인, 메타데이터 제거, 축소본 생성을 거친다
+            let processed = tokio::task::spawn_blocking(move || process_image(&image.bytes))
+                .await
+                .map_err(|e| UploadError::ValidationError(format!("Image processing failed: {}", e)))??;
+            Some(processed)
+        },
+        None => None,
+    };
+
+    let record = match choose_storage(&database, requested, &dao)? {
+        StorageKind::Ipfs => store_upload(&database, ipfs_client(), &wallet, &dao, text, image, now_secs()).await?,
+        StorageKind::Arweave => store_upload(&database, arweave_client()?, &wallet, &dao, text, image, now_secs()).await?,
+    };
+    Ok((StatusCode::CREATED, Json(record.into())))
+}
+
+#[derive(Deserialize)]
+pub struct UploadQuery {
+    content_hash: String,
+}
+
+pub async fn get_upload<T: SafeDatabase>(
+    State(database): State<T>,
+    Query(query): Query<UploadQuery>,
+) -> Result<Json<UploadRecord>, UploadError> {
+    UPLOADS.get(&database, query.content_hash.as_bytes())
+        .map_err(|e| UploadError::DatabaseError(e.to_string()))?
+        .map(Json)
+        .ok_or(UploadError::NotFound(query.content_hash))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::community::DaoInfo;
+    use crate::media::Variant;
+
+    // 올린 순서대로 <scheme>://id0, id1 ... 을 돌려준다
+    struct MemoryStorage(StorageKind, Mutex<Vec<(String, Vec<u8>)>>);
+
+    impl StorageProvider for MemoryStorage {
+        fn kind(&self) -> StorageKind {
+            self.0
+        }
+
+        async fn store(&self, _file_name: &str, content_type: &str, bytes: Vec<u8>) -> Result<String, UploadError> {
+            let mut stored = self.1.lock().unwrap();
+            stored.push((content_type.to_string(), bytes));
+            let scheme = match self.0 {
+                StorageKind::Ipfs => "ipfs",
+                StorageKind::Arweave => "ar",
+            };
+            Ok(format!("{}://id{}", scheme, stored.len() - 1))
+        }
+    }
+
+    #[test]
+    fn test_content_hash_and_provider_responses() {
+        let hash = content_hash("hello", "");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, content_hash("hello", ""));
+        assert_ne!(hash, content_hash("hello", "ipfs://id0"));
+
+        assert_eq!(parse_add_response(&json!({"Name": "a", "Hash": "bafy", "Size": "3"})).unwrap(), "bafy");
+        assert!(parse_add_response(&json!({"Message": "error"})).is_err());
+        assert_eq!(parse_arweave_response(&json!({"id": "tx", "timestamp": 1})).unwrap(), "tx");
+        assert!(parse_arweave_response(&json!({})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_store_upload_pins_image_then_document() {
+        let database = MemoryDatabase::new();
+        let storage = MemoryStorage(StorageKind::Ipfs, Mutex::new(Vec::new()));
+        let image = Image { file_name: "a.png".to_string(), content_type: "image/png".to_string(), bytes: vec![1, 2, 3] };
+        let thumbnail = Image { file_name: "thumbnail.png".to_string(), content_type: "image/png".to_string(), bytes: vec![4] };
+        let image = ProcessedImage {
+            original: image,
+            variants: vec![Variant { name: "thumbnail", width: 320, height: 160, image: thumbnail }],
+        };
+
+        let record = store_upload(&database, &storage, "alice", "dao", "hello".to_string(), Some(image), 7).await.unwrap();
+        assert_eq!(record.image_uri, "ipfs://id0");
+        assert_eq!(record.image_variants[0].uri, "ipfs://id1");
+        assert_eq!((record.image_variants[0].width, record.image_variants[0].height), (320, 160));
+        assert_eq!(record.content_uri, "ipfs://id2");
+        assert_eq!(record.content_hash, content_hash("hello", "ipfs://id0"));
+
+        // 마지막으로 올린 건 이미지 참조를 담은 JSON 문서
+        let stored = storage.1.lock().unwrap();
+        assert_eq!(stored[0], ("image/png".to_string(), vec![1, 2, 3]));
+        let document: Value = serde_json::from_slice(&stored[2].1).unwrap();
+        assert_eq!(document["image_uri"], "ipfs://id0");
+        assert_eq!(document["image_variants"][0]["name"], "thumbnail");
+        assert_eq!(document["text"], "hello");
+
+        assert_eq!(UPLOADS.get(&database, record.content_hash.as_bytes()).unwrap(), Some(record));
+    }
+
+    #[tokio::test]
+    async fn test_storage_follows_dao_config_unless_requested() {
+        let database = MemoryDatabase::new();
+        let info = DaoInfo {
+            pubkey: "archive".to_string(),
+            name: "archive".to_string(),
+            description: String::new(),
+            image_uri: None,
+            storage: StorageKind::Arweave,
+            registered_at: 0,
+        };
+        DAO_REGISTRY.put(&database, info.pubkey.as_bytes(), &info).unwrap();
+
+        assert_eq!(choose_storage(&database, None, "archive").unwrap(), StorageKind::Arweave);
+        assert_eq!(choose_storage(&database, Some(StorageKind::Ipfs), "archive").unwrap(), StorageKind::Ipfs);
+        assert_eq!(choose_storage(&database, None, "unregistered").unwrap(), StorageKind::Ipfs);
+        assert!(parse_storage("s3").is_err());
+
+        let storage = MemoryStorage(StorageKind::Arweave, Mutex::new(Vec::new()));
+        let record = store_upload(&database, &storage, "alice", "archive", "hello".to_string(), None, 7).await.unwrap();
+        assert_eq!(record.content_uri, "ar://id0");
+        assert_eq!(record.storage, StorageKind::Arweave);
+        assert_eq!(record.content_hash, content_hash("hello", ""));
+    }
+}
@@ -0,0 +1,269 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use utoipa::ToSchema;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::ttl::now_secs;
+use turtle_service::parser::profile::{Profile, VerificationMethod, WalletVerification};
+use crate::admin::chain_reader;
+use crate::auth::{check_nonce, consume_nonce, signed_request_message, spend_nonce, AuthError, SignedRequest};
+use crate::profile::{mark_verified, ProfileError};
+use crate::rpc::RpcClient;
+use crate::error::ApiError;
+
+// /api/auth/nonce 에 이 purpose 로 nonce 를 받아서 증명에 쓴다
+pub const PROFILE_VERIFY_PURPOSE: &str = "profile_verify";
+// 서명 방식에서 signed_request_message 의 payload 로 들어가는 문장
+pub const PROFILE_BINDING_PAYLOAD: &str = "I control this wallet and bind it to my Turtle profile.";
+// SPL Memo v2 와 v1
+pub const MEMO_PROGRAM_IDS: &[&str] = &[
+    "MemoSq4gqABAXKAhh9wmbRHCCAxUPmeNb4hAGnvFGJ7",
+    "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo",
+];
+
+#[derive(Debug)]
+pub enum VerificationError {
+    DatabaseError(String),
+    ValidationError(String),
+    Unauthorized(String),
+    RpcError(String),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            VerificationError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            VerificationError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            VerificationError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+        }
+    }
+}
+
+impl StdError for VerificationError {}
+
+impl IntoResponse for VerificationError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            VerificationError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            VerificationError::ValidationError(_) => StatusCode::BAD_REQUEST,
+            VerificationError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            VerificationError::RpcError(_) => StatusCode::BAD_GATEWAY,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+impl From<AuthError> for VerificationError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::DatabaseError(msg) => VerificationError::DatabaseError(msg),
+            AuthError::ValidationError(msg) => VerificationError::ValidationError(msg),
+            AuthError::Unauthorized(msg) => VerificationError::Unauthorized(msg),
+        }
+    }
+}
+
+impl From<ProfileError> for VerificationError {
+    fn from(error: ProfileError) -> Self {
+        match error {
+            ProfileError::DatabaseError(msg) => VerificationError::DatabaseError(msg),
+            other => VerificationError::ValidationError(other.to_string()),
+        }
+    }
+}
+
+// memo 방식에서 트랜잭션에 넣어야 하는 memo
+pub fn verification_memo(nonce: &str) -> String {
+    format!("turtle:{}:{}", PROFILE_VERIFY_PURPOSE, nonce)
+}
+
+// 증명 트랜잭션에서 확인하는 부분
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoTransaction {
+    pub failed: bool,
+    pub signers: Vec<String>,
+    pub memos: Vec<String>,
+}
+
+// 증명 트랜잭션을 읽어 오는 곳. 서버는 RpcClient 를 쓴다.
+pub trait ProofSource: Send + Sync + 'static {
+    fn memo_transaction(&self, signature: &str) -> impl Future<Output = Result<Option<MemoTransaction>, String>> + Send;
+}
+
+impl ProofSource for RpcClient {
+    async fn memo_transaction(&self, signature: &str) -> Result<Option<MemoTransaction>, String> {
+        let options = json!({ "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 });
+        let result = self.call("getTransaction", json!([signature, options])).await.map_err(|e| e.to_string())?;
+        Ok(parse_memo_transaction(&result))
+    }
+}
+
+// 앞의 numRequiredSignatures 개의 계정이 서명자다. memo 인스트럭션의 data 는 base58 로 인코딩된 UTF-8 문장.
+fn parse_memo_transaction(result: &Value) -> Option<MemoTransaction> {
+    let message = &result["transaction"]["message"];
+    let account_keys: Vec<&str> = message["accountKeys"].as_array()?.iter().filter_map(Value::as_str).collect();
+    let required = message["header"]["numRequiredSignatures"].as_u64()? as usize;
+
+    let memos = message["instructions"].as_array()?.iter().filter_map(|instruction| {
+        let program_id = account_keys.get(instruction["programIdIndex"].as_u64()? as usize)?;
+        if !MEMO_PROGRAM_IDS.contains(program_id) {
+            return None;
+        }
+        let data = bs58::decode(instruction["data"].as_str()?).into_vec().ok()?;
+        String::from_utf8(data).ok()
+    });
+    Some(MemoTransaction {
+        failed: !result["meta"]["err"].is_null(),
+        signers: account_keys.iter().take(required).map(|key| key.to_string()).collect(),
+        memos: memos.collect(),
+    })
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum VerifyProfileRequest {
+    // signed_request_message(PROFILE_VERIFY_PURPOSE, wallet, nonce, PROFILE_BINDING_PAYLOAD) 에 대한 서명
+    SignedMessage { wallet: String, nonce: String, signature: String },
+    // verification_memo(nonce) 를 memo 로 넣고 지갑이 서명한 트랜잭션
+    MemoTransaction { wallet: String, nonce: String, tx_signature: String },
+}
+
+pub async fn verify_profile_wallet<T: SafeDatabase, S: ProofSource>(
+    database: &T,
+    source: &S,
+    request: VerifyProfileRequest,
+    now: u64,
+) -> Result<Profile, VerificationError> {
+    let (wallet, method, proof) = match request {
+        VerifyProfileRequest::SignedMessage { wallet, nonce, signature } => {
+            let message = signed_request_message(PROFILE_VERIFY_PURPOSE, &wallet, &nonce, PROFILE_BINDING_PAYLOAD);
+            let signed = SignedRequest { wallet, nonce, signature };
+            consume_nonce(database, &signed, PROFILE_VERIFY_PURPOSE, &message)?;
+            (signed.wallet, VerificationMethod::SignedMessage, signed.signature)
+        },
+        VerifyProfileRequest::MemoTransaction { wallet, nonce, tx_signature } => {
+            check_nonce(database, &wallet, &nonce, PROFILE_VERIFY_PURPOSE)?;
+            let transaction = source.memo_transaction(&tx_signature).await
+                .map_err(VerificationError::RpcError)?
+                .ok_or_else(|| VerificationError::ValidationError(format!("Transaction not found or not confirmed yet: {}", tx_signature)))?;
+            let memo = verification_memo(&nonce);
+            if transaction.failed || !transaction.signers.contains(&wallet) || !transaction.memos.contains(&memo) {
+                return Err(VerificationError::Unauthorized(format!("Transaction must succeed, be signed by the wallet and carry the memo {}", memo)));
+            }
+            spend_nonce(database, &nonce)?;
+            (wallet, VerificationMethod::MemoTransaction, tx_signature)
+        },
+    };
+
+    Ok(mark_verified(database, &wallet, WalletVerification { method, proof, verified_at: now })?)
+}
+
+// 지갑 소유를 증명하고 프로필에 배지를 붙인다. 세션 대신 nonce 에 묶인 서명이나 memo 트랜잭션으로 인증한다.
+#[utoipa::path(
+    post, path = "/api/profile/verify", tag = "profile",
+    request_body = VerifyProfileRequest,
+    responses(
+        (status = 200, description = "Profile with the verified badge", body = Profile),
+        (status = 400, description = "Invalid wallet or unconfirmed transaction", body = String),
+        (status = 401, description = "Bad signature, nonce or memo", body = String),
+        (status = 502, description = "Transaction lookup failed", body = String),
+    )
+)]
+pub async fn verify_profile<T: SafeDatabase>(
+    State(database): State<T>,
+    Json(request): Json<VerifyProfileRequest>,
+) -> Result<Json<Profile>, VerificationError> {
+    Ok(Json(verify_profile_wallet(&database, &chain_reader(), request, now_secs()).await?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::collections::HashMap;
+    use turtle_database::memory_db::MemoryDatabase;
+    use crate::auth::issue_nonce;
+    use crate::profile::{is_verified, update_profile, ProfilePatch};
+
+    struct FakeChain(HashMap<String, MemoTransaction>);
+
+    impl ProofSource for FakeChain {
+        async fn memo_transaction(&self, signature: &str) -> Result<Option<MemoTransaction>, String> {
+            Ok(self.0.get(signature).cloned())
+        }
+    }
+
+    fn wallet_of(key: &SigningKey) -> String {
+        bs58::encode(key.verifying_key().to_bytes()).into_string()
+    }
+
+    #[tokio::test]
+    async fn test_signed_message_marks_profile_verified() {
+        let database = MemoryDatabase::new();
+        let chain = FakeChain(HashMap::new());
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let wallet = wallet_of(&key);
+        let (nonce, _) = issue_nonce(&database, &wallet, PROFILE_VERIFY_PURPOSE).unwrap();
+        let sign = |payload: &str| {
+            let message = signed_request_message(PROFILE_VERIFY_PURPOSE, &wallet, &nonce, payload);
+            bs58::encode(key.sign(message.as_bytes()).to_bytes()).into_string()
+        };
+        let request = |signature: String| VerifyProfileRequest::SignedMessage { wallet: wallet.clone(), nonce: nonce.clone(), signature };
+
+        // 다른 문장에 대한 서명은 받지 않는다
+        let result = verify_profile_wallet(&database, &chain, request(sign("something else")), 10).await;
+        assert!(matches!(result, Err(VerificationError::Unauthorized(_))));
+        assert!(!is_verified(&database, &wallet).unwrap());
+
+        let profile = verify_profile_wallet(&database, &chain, request(sign(PROFILE_BINDING_PAYLOAD)), 10).await.unwrap();
+        assert!(profile.verified);
+        assert_eq!(profile.verification.unwrap().method, VerificationMethod::SignedMessage);
+
+        // PUT 으로 프로필을 바꿔도 배지는 남는다
+        let patch = ProfilePatch { display_name: Some("alice".to_string()), ..Default::default() };
+        assert!(update_profile(&database, &wallet, patch, true, 20).unwrap().verified);
+        assert!(is_verified(&database, &wallet).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memo_transaction_must_be_signed_by_wallet() {
+        let database = MemoryDatabase::new();
+        let wallet = wallet_of(&SigningKey::from_bytes(&[7; 32]));
+        let (nonce, _) = issue_nonce(&database, &wallet, PROFILE_VERIFY_PURPOSE).unwrap();
+        let transaction = |signer: &str, memo: String| MemoTransaction { failed: false, signers: vec![signer.to_string()], memos: vec![memo] };
+        let chain = FakeChain(HashMap::from([
+            ("other-signer".to_string(), transaction("mallory", verification_memo(&nonce))),
+            ("wrong-memo".to_string(), transaction(&wallet, verification_memo("stale"))),
+            ("good".to_string(), transaction(&wallet, verification_memo(&nonce))),
+        ]));
+        let request = |tx_signature: &str| VerifyProfileRequest::MemoTransaction { wallet: wallet.clone(), nonce: nonce.clone(), tx_signature: tx_signature.to_string() };
+
+        for tx_signature in ["other-signer", "wrong-memo"] {
+            assert!(matches!(verify_profile_wallet(&database, &chain, request(tx_signature), 10).await, Err(VerificationError::Unauthorized(_))));
+        }
+        assert!(matches!(verify_profile_wallet(&database, &chain, request("missing"), 10).await, Err(VerificationError::ValidationError(_))));
+
+        let profile = verify_profile_wallet(&database, &chain, request("good"), 10).await.unwrap();
+        assert_eq!(profile.verification.map(|proof| (proof.method, proof.proof)), Some((VerificationMethod::MemoTransaction, "good".to_string())));
+        // nonce 는 한 번만 쓴다
+        assert!(matches!(verify_profile_wallet(&database, &chain, request("good"), 11).await, Err(VerificationError::Unauthorized(_))));
+
+        let raw = json!({
+            "meta": { "err": null },
+            "transaction": { "message": {
+                "header": { "numRequiredSignatures": 1 },
+                "accountKeys": [wallet, MEMO_PROGRAM_IDS[0]],
+                "instructions": [{ "programIdIndex": 1, "accounts": [], "data": bs58::encode(verification_memo(&nonce)).into_string() }],
+            }},
+        });
+        assert_eq!(parse_memo_transaction(&raw), Some(transaction(&wallet, verification_memo(&nonce))));
+    }
+}
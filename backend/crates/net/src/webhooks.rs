@@ -0,0 +1,975 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::{KeyBuilder, KeyReader};
+use turtle_database::kv::{KeyRange, KvOp};
+use turtle_database::ttl::now_secs;
+use turtle_database::typed::TypedTable;
+use turtle_service::parser::event::TurtleEvent;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use turtle_service::parser::webhook::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+use crate::config::app_config;
+use crate::countdown::CLAIMABLE_ROUNDS;
+use crate::indexer::{events_after, load_checkpoint};
+use crate::roles::{role_of, Role};
+use crate::scheduler::Scheduler;
+use crate::session::AuthedWallet;
+use crate::sync::{CHAIN_PROPOSALS, DAO_SNAPSHOTS};
+use crate::error::ApiError;
+
+// id -> Webhook
+pub const WEBHOOKS: TypedTable<Webhook> = TypedTable::new("webhooks");
+// (webhook id, delivery id) -> WebhookDelivery
+pub const WEBHOOK_DELIVERIES: TypedTable<WebhookDelivery> = TypedTable::new("webhook_deliveries");
+// (보낼 시각, webhook id, delivery id) -> (). 시각 순서로 스캔해서 때가 된 전송만 꺼낸다.
+pub const WEBHOOK_QUEUE: TypedTable<()> = TypedTable::new("webhook_queue");
+pub const WEBHOOK_STATE: TypedTable<WebhookCheckpoint> = TypedTable::new("webhook_state");
+// 한 번만 보내는 이벤트의 표시 (새 제안, 타이머 만료 임박, 마감 지남) -> 처리한 시각
+pub const WEBHOOK_SEEN: TypedTable<u64> = TypedTable::new("webhook_seen");
+pub const CHECKPOINT_KEY: &[u8] = b"checkpoint";
+
+pub const WEBHOOK_INTERVAL: Duration = Duration::from_secs(5);
+// 타이머가 이만큼 남았을 때 timer_expiring 을 보낸다
+pub const EXPIRY_WARNING_SECS: u64 = 600;
+pub const MAX_ATTEMPTS: u32 = 6;
+pub const MAX_WEBHOOKS_PER_DAO: usize = 10;
+const BASE_BACKOFF_SECS: u64 = 30;
+const MAX_BACKOFF_SECS: u64 = 3_600;
+const MAX_URL_BYTES: usize = 512;
+const DELIVERY_BATCH: usize = 50;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const EVENT_BATCH: usize = 500;
+pub const DEFAULT_DELIVERY_LIMIT: usize = 20;
+pub const MAX_DELIVERY_LIMIT: usize = 100;
+
+// 받는 쪽은 "{timestamp}.{body}" 를 secret 으로 HMAC-SHA256 해서 signature 헤더와 비교한다
+pub const SIGNATURE_HEADER: &str = "x-turtle-signature";
+pub const TIMESTAMP_HEADER: &str = "x-turtle-timestamp";
+pub const EVENT_HEADER: &str = "x-turtle-event";
+pub const DELIVERY_HEADER: &str = "x-turtle-delivery";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookCheckpoint {
+    pub last_event_id: u64,             // 이 ID 까지의 인덱싱된 이벤트는 전송으로 바꿨다
+    pub next_delivery_id: u64,
+}
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum WebhookError {
+    DatabaseError(String),
+    ValidationError(String),
+    Forbidden(String),
+    NotFound(String),
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            WebhookError::ValidationError(msg) => write!(f, "Validation error: {}", msg),
+            WebhookError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            WebhookError::NotFound(msg) => write!(f, "Not found: {}", msg),
+        }
+    }
+}
+
+impl StdError for WebhookError {}
+
+impl IntoResponse for WebhookError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            WebhookError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            WebhookError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            WebhookError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            WebhookError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+        };
+
+        ApiError::new(status, error_message).into_response()
+    }
+}
+
+fn db_err(e: impl fmt::Display) -> WebhookError {
+    WebhookError::DatabaseError(e.to_string())
+}
+
+fn delivery_key(webhook_id: &str, id: u64) -> Vec<u8> {
+    KeyBuilder::new().str(webhook_id).u64(id).build()
+}
+
+fn queue_key(due: u64, webhook_id: &str, id: u64) -> Vec<u8> {
+    KeyBuilder::new().u64(due).str(webhook_id).u64(id).build()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+pub fn signature(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", to_hex(&mac.finalize().into_bytes()))
+}
+
+// 30초, 1분, 2분, ... 최대 1시간
+pub fn backoff(attempts: u32) -> u64 {
+    BASE_BACKOFF_SECS
+        .saturating_mul(1u64 << attempts.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF_SECS)
+}
+
+// 웹훅으로 보낼 DAO 이벤트 하나
+#[derive(Clone, Debug, PartialEq)]
+pub struct DaoEvent {
+    pub dao: String,
+    pub event: WebhookEvent,
+    pub data: Value,
+    pub occurred_at: u64,
+}
+
+fn seen_key(kind: &str, parts: &[u8]) -> Vec<u8> {
+    KeyBuilder::new().str(kind).bytes(parts).build()
+}
+
+fn timer_key(dao: &str, timeout_timestamp: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(timeout_timestamp).build()
+}
+
+// 처음 실행할 때는 지금까지의 이벤트와 제안을 본 것으로 치고 그 뒤의 것만 보낸다 (이 회차는 표시만 남긴다)
+fn initial_checkpoint<T: SafeDatabase>(database: &T, ops: &mut Vec<KvOp>, now: u64) -> Result<WebhookCheckpoint, WebhookError> {
+    let indexed = load_checkpoint(database).map_err(db_err)?;
+    for (key, _) in CHAIN_PROPOSALS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        ops.push(WEBHOOK_SEEN.put_op(&seen_key("proposal", &key), &now).map_err(db_err)?);
+    }
+    for (_, round) in CLAIMABLE_ROUNDS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        ops.push(WEBHOOK_SEEN.put_op(&seen_key("claimable", &timer_key(&round.dao, round.deadline)), &now).map_err(db_err)?);
+    }
+    Ok(WebhookCheckpoint {
+        last_event_id: indexed.next_event_id.saturating_sub(1),
+        next_delivery_id: 0,
+    })
+}
+
+// 체크포인트 이후 새로 생긴 Claim, 처음 보는 제안, 만료가 임박한 타이머, 마감이 지난 라운드를 모은다.
+// 다시 보내지 않도록 표시하는 연산은 ops 에 담아 전송 기록과 같이 쓴다.
+fn detect_events<T: SafeDatabase>(
+    database: &T,
+    checkpoint: &mut WebhookCheckpoint,
+    ops: &mut Vec<KvOp>,
+    now: u64,
+) -> Result<Vec<DaoEvent>, WebhookError> {
+    let mut events = Vec::new();
+
+    loop {
+        let records = events_after(database, checkpoint.last_event_id, EVENT_BATCH).map_err(db_err)?;
+        if records.is_empty() {
+            break;
+        }
+        for record in records {
+            checkpoint.last_event_id = record.id;
+            if let TurtleEvent::Claim { winner } = &record.event {
+                events.push(DaoEvent {
+                    dao: record.dao.clone(),
+                    event: WebhookEvent::Claim,
+                    data: json!({ "winner": winner, "claimed_by": record.actor, "signature": record.signature, "slot": record.slot }),
+                    occurred_at: record.block_time.unwrap_or(now),
+                });
+            }
+        }
+    }
+
+    for (key, proposal) in CHAIN_PROPOSALS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let seen = seen_key("proposal", &key);
+        if WEBHOOK_SEEN.get(database, &seen).map_err(db_err)?.is_some() {
+            continue;
+        }
+        ops.push(WEBHOOK_SEEN.put_op(&seen, &now).map_err(db_err)?);
+        events.push(DaoEvent {
+            dao: proposal.dao.clone(),
+            event: WebhookEvent::NewProposal,
+            data: json!({
+                "proposal_id": proposal.proposal_id,
+                "proposer": proposal.proposer,
+                "title": proposal.title,
+                "vote_type": proposal.vote_type,
+                "end_time": proposal.end_time,
+            }),
+            occurred_at: proposal.start_time,
+        });
+    }
+
+    for (_, snapshot) in DAO_SNAPSHOTS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let remaining = snapshot.timeout_timestamp.saturating_sub(now);
+        if remaining == 0 || remaining > EXPIRY_WARNING_SECS {
+            continue;
+        }
+        let seen = seen_key("timer", &timer_key(&snapshot.pubkey, snapshot.timeout_timestamp));
+        if WEBHOOK_SEEN.get(database, &seen).map_err(db_err)?.is_some() {
+            continue;
+        }
+        ops.push(WEBHOOK_SEEN.put_op(&seen, &now).map_err(db_err)?);
+        events.push(DaoEvent {
+            dao: snapshot.pubkey.clone(),
+            event: WebhookEvent::TimerExpiring,
+            data: json!({
+                "timeout_timestamp": snapshot.timeout_timestamp,
+                "seconds_left": remaining,
+                "pot_size": snapshot.total_deposit,
+            }),
+            occurred_at: now,
+        });
+    }
+
+    for (_, round) in CLAIMABLE_ROUNDS.scan(database, &KeyRange::all()).map_err(db_err)? {
+        let seen = seen_key("claimable", &timer_key(&round.dao, round.deadline));
+        if WEBHOOK_SEEN.get(database, &seen).map_err(db_err)?.is_some() {
+            continue;
+        }
+        ops.push(WEBHOOK_SEEN.put_op(&seen, &now).map_err(db_err)?);
+        events.push(DaoEvent {
+            dao: round.dao.clone(),
+            event: WebhookEvent::RoundClaimable,
+            data: json!({
+                "deadline": round.deadline,
+                "pot_size": round.pot_size,
+                "content_count": round.content_count,
+            }),
+            occurred_at: round.detected_at,
+        });
+    }
+
+    Ok(events)
+}
+
+// 새 DAO 이벤트를 구독 중인 웹훅마다 전송 기록으로 만들어 큐에 넣고, 만든 전송 수를 돌려준다
+pub fn collect_deliveries<T: SafeDatabase>(database: &T, now: u64) -> Result<usize, WebhookError> {
+    let mut ops = Vec::new();
+    let Some(mut checkpoint) = WEBHOOK_STATE.get(database, CHECKPOINT_KEY).map_err(db_err)? else {
+        let checkpoint = initial_checkpoint(database, &mut ops, now)?;
+        ops.push(WEBHOOK_STATE.put_op(CHECKPOINT_KEY, &checkpoint).map_err(db_err)?);
+        database.txn(&ops).map_err(db_err)?;
+        return Ok(0);
+    };
+
+    let events = detect_events(database, &mut checkpoint, &mut ops, now)?;
+    let webhooks: Vec<Webhook> = WEBHOOKS.scan(database, &KeyRange::all())
+        .map_err(db_err)?
+        .into_iter()
+        .map(|(_, webhook)| webhook)
+        .filter(|webhook| webhook.active)
+        .collect();
+
+    let mut created = 0;
+    for event in &events {
+        for webhook in webhooks.iter().filter(|webhook| webhook.dao == event.dao && webhook.events.contains(&event.event)) {
+            let id = checkpoint.next_delivery_id;
+            checkpoint.next_delivery_id += 1;
+
+            let payload = json!({
+                "id": id,
+                "event": event.event,
+                "dao": event.dao,
+                "occurred_at": event.occurred_at,
+                "data": event.data,
+            });
+            let delivery = WebhookDelivery {
+                id,
+                webhook_id: webhook.id.clone(),
+                event: event.event,
+                payload: payload.to_string(),
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                next_attempt_at: Some(now),
+                last_status_code: None,
+                last_error: None,
+                created_at: now,
+                completed_at: None,
+            };
+            ops.push(WEBHOOK_DELIVERIES.put_op(&delivery_key(&webhook.id, id), &delivery).map_err(db_err)?);
+            ops.push(WEBHOOK_QUEUE.put_op(&queue_key(now, &webhook.id, id), &()).map_err(db_err)?);
+            created += 1;
+        }
+    }
+
+    ops.push(WEBHOOK_STATE.put_op(CHECKPOINT_KEY, &checkpoint).map_err(db_err)?);
+    database.txn(&ops).map_err(db_err)?;
+    Ok(created)
+}
+
+// 웹훅 본문을 실제로 보내는 곳. 서버는 reqwest 를 쓰고, 테스트는 응답을 흉내낸다.
+// 받은 HTTP 상태 코드를, 연결 자체가 실패하면 에러 메시지를 돌려준다.
+pub trait WebhookSender: Send + Sync + 'static {
+    fn send(&self, url: &str, headers: Vec<(&'static str, String)>, body: String) -> impl Future<Output = Result<u16, String>> + Send;
+}
+
+// 서버가 쓰는 reqwest 클라이언트. 리다이렉트를 따라가지 않고, 내부망 주소로 풀리는 호스트에는 연결하지 않는다.
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: reqwest::Client,
+    allow_localhost: bool,
+}
+
+impl WebhookClient {
+    pub fn new(allow_localhost: bool) -> Self {
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver { allow_localhost }))
+            .build()
+            .expect("webhook client");
+        Self { client, allow_localhost }
+    }
+}
+
+impl WebhookSender for WebhookClient {
+    async fn send(&self, url: &str, headers: Vec<(&'static str, String)>, body: String) -> Result<u16, String> {
+        // IP 리터럴은 resolver 를 거치지 않으므로 여기서 막는다
+        let parsed = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+        if host_ip(&parsed).is_some_and(|ip| !allowed_ip(ip, self.allow_localhost)) {
+            return Err(format!("refusing to deliver to a non-public address: {}", url));
+        }
+
+        let mut request = self.client.post(parsed)
+            .timeout(DELIVERY_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        Ok(response.status().as_u16())
+    }
+}
+
+// 이름을 풀고 공개 주소만 남긴다. 등록 뒤에 DNS 가 내부 주소로 바뀌어도 연결 시점에 막힌다.
+struct PublicResolver {
+    allow_localhost: bool,
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allow_localhost = self.allow_localhost;
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allowed_ip(addr.ip(), allow_localhost))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// 루프백, 사설망, 링크 로컬(클라우드 메타데이터 포함), 지정되지 않은 주소 등은 공개 주소가 아니다
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                // 100.64.0.0/10 (carrier-grade NAT)
+                || (a == 100 && (64..128).contains(&b)))
+        },
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ip(IpAddr::V4(mapped)),
+            None => !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()),
+        },
+    }
+}
+
+// URL 의 호스트가 IP 리터럴이면 그 주소 (IPv6 는 대괄호를 벗긴다)
+fn host_ip(url: &reqwest::Url) -> Option<IpAddr> {
+    url.host_str()?.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+// 개발 설정이 켜져 있으면 루프백도 받는다
+fn allowed_ip(ip: IpAddr, allow_localhost: bool) -> bool {
+    is_public_ip(ip) || (allow_localhost && ip.is_loopback())
+}
+
+// 때가 된 전송을 보내고 결과를 기록한다. 실패하면 backoff 뒤로 다시 큐에 넣고, MAX_ATTEMPTS 번 실패하면 포기한다.
+pub async fn deliver_due<T: SafeDatabase, S: WebhookSender>(database: &T, sender: &S, now: u64) -> Result<usize, WebhookError> {
+    let due_range = KeyRange::between(KeyBuilder::new().u64(0).build(), KeyBuilder::new().u64(now.saturating_add(1)).build());
+    let due = WEBHOOK_QUEUE.scan(database, &due_range).map_err(db_err)?;
+
+    let mut attempted = 0;
+    for (queued, _) in due.into_iter().take(DELIVERY_BATCH) {
+        let mut reader = KeyReader::new(&queued);
+        let (Ok(_), Ok(webhook_id), Ok(id)) = (reader.u64(), reader.str(), reader.u64()) else {
+            WEBHOOK_QUEUE.delete(database, &queued).map_err(db_err)?;
+            continue;
+        };
+        let key = delivery_key(webhook_id, id);
+        let mut ops = vec![WEBHOOK_QUEUE.delete_op(&queued)];
+
+        let Some(mut delivery) = WEBHOOK_DELIVERIES.get(database, &key).map_err(db_err)? else {
+            database.txn(&ops).map_err(db_err)?;
+            continue;
+        };
+
+        match WEBHOOKS.get(database, webhook_id.as_bytes()).map_err(db_err)? {
+            Some(webhook) if webhook.active => {
+                let headers = vec![
+                    (EVENT_HEADER, serde_json::to_value(delivery.event).map_err(db_err)?.as_str().unwrap_or_default().to_string()),
+                    (DELIVERY_HEADER, delivery.id.to_string()),
+                    (TIMESTAMP_HEADER, now.to_string()),
+                    (SIGNATURE_HEADER, signature(&webhook.secret, now, &delivery.payload)),
+                ];
+                let result = sender.send(&webhook.url, headers, delivery.payload.clone()).await;
+                delivery.attempts += 1;
+                attempted += 1;
+
+                match result {
+                    Ok(code) if (200..300).contains(&code) => {
+                        delivery.status = DeliveryStatus::Delivered;
+                        delivery.last_status_code = Some(code);
+                        delivery.last_error = None;
+                    },
+                    Ok(code) => {
+                        delivery.last_status_code = Some(code);
+                        delivery.last_error = Some(format!("HTTP {}", code));
+                    },
+                    Err(e) => {
+                        delivery.last_status_code = None;
+                        delivery.last_error = Some(e);
+                    },
+                }
+
+                if delivery.status == DeliveryStatus::Pending && delivery.attempts < MAX_ATTEMPTS {
+                    let next = now + backoff(delivery.attempts);
+                    delivery.next_attempt_at = Some(next);
+                    ops.push(WEBHOOK_QUEUE.put_op(&queue_key(next, webhook_id, id), &()).map_err(db_err)?);
+                } else {
+                    if delivery.status == DeliveryStatus::Pending {
+                        delivery.status = DeliveryStatus::Failed;
+                    }
+                    delivery.next_attempt_at = None;
+                    delivery.completed_at = Some(now);
+                }
+            },
+            _ => {
+                delivery.status = DeliveryStatus::Failed;
+                delivery.last_error = Some("webhook was deleted or disabled".to_string());
+                delivery.next_attempt_at = None;
+                delivery.completed_at = Some(now);
+            },
+        }
+
+        ops.push(WEBHOOK_DELIVERIES.put_op(&key, &delivery).map_err(db_err)?);
+        database.txn(&ops).map_err(db_err)?;
+    }
+
+    Ok(attempted)
+}
+
+// 새 이벤트로 보낼 것을 만들고, 보낼 때가 된 것(재시도 포함) 을 보낸다
+pub fn register_webhook_job<T: SafeDatabase>(scheduler: &mut Scheduler<T>) {
+    let client = WebhookClient::new(app_config().webhook_allow_localhost);
+    scheduler.every("webhooks", WEBHOOK_INTERVAL, Duration::ZERO, move |database| {
+        let client = client.clone();
+        async move {
+            // 수집이 실패해도 밀려 있는 전송은 보낸다
+            let collected = collect_deliveries(&database, now_secs()).map(|_| ()).map_err(|e| e.to_string());
+            let delivered = deliver_due(&database, &client, now_secs()).await.map(|_| ()).map_err(|e| e.to_string());
+            collected.and(delivered)
+        }
+    });
+}
+
+// DAO 를 만든 지갑(체인 스냅샷의 initializer) 이나 admin 만 그 DAO 의 웹훅을 다룬다
+fn authorize_dao<T: SafeDatabase>(database: &T, wallet: &str, dao: &str) -> Result<(), WebhookError> {
+    if role_of(database, wallet).map_err(db_err)? >= Role::Admin {
+        return Ok(());
+    }
+    match DAO_SNAPSHOTS.get(database, dao.as_bytes()).map_err(db_err)? {
+        Some(snapshot) if snapshot.initializer == wallet => Ok(()),
+        Some(_) => Err(WebhookError::Forbidden("Only the DAO initializer or an admin can manage its webhooks".to_string())),
+        None => Err(WebhookError::NotFound(format!("Unknown DAO: {}", dao))),
+    }
+}
+
+// https 만 받고, 호스트가 공개 주소로 풀려야 한다.
+// http://localhost 같은 루프백은 개발 설정(webhook_allow_localhost) 이 켜져 있을 때만 받는다.
+async fn validate_url(url: &str, allow_localhost: bool) -> Result<(), WebhookError> {
+    let invalid = || WebhookError::ValidationError(format!("Invalid webhook url: {}", url));
+    if url.len() > MAX_URL_BYTES {
+        return Err(invalid());
+    }
+
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    let literal = match parsed.host_str() {
+        Some(host) if host.eq_ignore_ascii_case("localhost") => Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        Some(_) => host_ip(&parsed),
+        None => return Err(invalid()),
+    };
+    let local = literal.is_some_and(|ip| ip.is_loopback());
+    match parsed.scheme() {
+        "https" => {},
+        "http" if local && allow_localhost => {},
+        _ => return Err(invalid()),
+    }
+
+    let non_public = || WebhookError::ValidationError(format!("Webhook url must point to a public address: {}", url));
+    if let Some(ip) = literal {
+        return if allowed_ip(ip, allow_localhost) { Ok(()) } else { Err(non_public()) };
+    }
+
+    // 풀리지 않는 이름은 받아 두고, 보낼 때 PublicResolver 가 다시 확인한다
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let Ok(addrs) = tokio::net::lookup_host((parsed.host_str().unwrap_or_default(), port)).await else {
+        return Ok(());
+    };
+    let mut addrs = addrs.peekable();
+    if addrs.peek().is_some() && addrs.all(|addr| allowed_ip(addr.ip(), allow_localhost)) {
+        Ok(())
+    } else {
+        Err(non_public())
+    }
+}
+
+fn normalize_events(mut events: Vec<WebhookEvent>) -> Result<Vec<WebhookEvent>, WebhookError> {
+    events.sort();
+    events.dedup();
+    if events.is_empty() {
+        return Err(WebhookError::ValidationError("events must not be empty".to_string()));
+    }
+    Ok(events)
+}
+
+fn load_webhook<T: SafeDatabase>(database: &T, wallet: &str, id: &str) -> Result<Webhook, WebhookError> {
+    let webhook = WEBHOOKS.get(database, id.as_bytes())
+        .map_err(db_err)?
+        .ok_or_else(|| WebhookError::NotFound(format!("Unknown webhook: {}", id)))?;
+    authorize_dao(database, wallet, &webhook.dao)?;
+    Ok(webhook)
+}
+
+#[derive(Deserialize)]
+pub struct CreateWebhookRequest {
+    dao: String,
+    url: String,
+    events: Vec<WebhookEvent>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateWebhookRequest {
+    url: Option<String>,
+    events: Option<Vec<WebhookEvent>>,
+    active: Option<bool>,
+    // true 면 새 secret 을 만들어 응답에 한 번 보여 준다
+    rotate_secret: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookListQuery {
+    dao: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryQuery {
+    limit: Option<usize>,
+    status: Option<DeliveryStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    id: String,
+    dao: String,
+    url: String,
+    events: Vec<WebhookEvent>,
+    active: bool,
+    created_by: String,
+    created_at: u64,
+    updated_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+}
+
+impl WebhookResponse {
+    fn new(webhook: Webhook, reveal_secret: bool) -> Self {
+        Self {
+            secret: reveal_secret.then_some(webhook.secret),
+            id: webhook.id,
+            dao: webhook.dao,
+            url: webhook.url,
+            events: webhook.events,
+            active: webhook.active,
+            created_by: webhook.created_by,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookList {
+    webhooks: Vec<WebhookResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryList {
+    deliveries: Vec<WebhookDelivery>,
+}
+
+pub async fn create_webhook<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookResponse>), WebhookError> {
+    authorize_dao(&database, &wallet, &request.dao)?;
+    validate_url(&request.url, app_config().webhook_allow_localhost).await?;
+    let events = normalize_events(request.events)?;
+
+    let existing = WEBHOOKS.scan(&database, &KeyRange::all())
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|(_, webhook)| webhook.dao == request.dao)
+        .count();
+    if existing >= MAX_WEBHOOKS_PER_DAO {
+        return Err(WebhookError::ValidationError(format!("at most {} webhooks per DAO", MAX_WEBHOOKS_PER_DAO)));
+    }
+
+    let now = now_secs();
+    let webhook = Webhook {
+        id: random_hex(16),
+        dao: request.dao,
+        url: request.url,
+        events,
+        secret: random_hex(32),
+        active: true,
+        created_by: wallet,
+        created_at: now,
+        updated_at: now,
+    };
+    WEBHOOKS.put(&database, webhook.id.as_bytes(), &webhook).map_err(db_err)?;
+    Ok((StatusCode::CREATED, Json(WebhookResponse::new(webhook, true))))
+}
+
+pub async fn list_webhooks<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Query(query): Query<WebhookListQuery>,
+) -> Result<Json<WebhookList>, WebhookError> {
+    authorize_dao(&database, &wallet, &query.dao)?;
+
+    let webhooks = WEBHOOKS.scan(&database, &KeyRange::all())
+        .map_err(db_err)?
+        .into_iter()
+        .filter(|(_, webhook)| webhook.dao == query.dao)
+        .map(|(_, webhook)| WebhookResponse::new(webhook, false))
+        .collect();
+    Ok(Json(WebhookList { webhooks }))
+}
+
+pub async fn update_webhook<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookResponse>, WebhookError> {
+    let mut webhook = load_webhook(&database, &wallet, &id)?;
+
+    if let Some(url) = request.url {
+        validate_url(&url, app_config().webhook_allow_localhost).await?;
+        webhook.url = url;
+    }
+    if let Some(events) = request.events {
+        webhook.events = normalize_events(events)?;
+    }
+    if let Some(active) = request.active {
+        webhook.active = active;
+    }
+    let rotate = request.rotate_secret.unwrap_or(false);
+    if rotate {
+        webhook.secret = random_hex(32);
+    }
+    webhook.updated_at = now_secs();
+
+    WEBHOOKS.put(&database, webhook.id.as_bytes(), &webhook).map_err(db_err)?;
+    Ok(Json(WebhookResponse::new(webhook, rotate)))
+}
+
+// 웹훅과 전송 기록을 지운다. 큐에 남은 항목은 디스패처가 꺼낼 때 버린다.
+pub async fn delete_webhook<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Path(id): Path<String>,
+) -> Result<StatusCode, WebhookError> {
+    let webhook = load_webhook(&database, &wallet, &id)?;
+
+    let deliveries = WEBHOOK_DELIVERIES.scan(&database, &KeyRange::prefix(KeyBuilder::new().str(&webhook.id).build()))
+        .map_err(db_err)?;
+    let mut ops: Vec<KvOp> = deliveries.iter().map(|(key, _)| WEBHOOK_DELIVERIES.delete_op(key)).collect();
+    ops.push(WEBHOOKS.delete_op(webhook.id.as_bytes()));
+    database.txn(&ops).map_err(db_err)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 최근 전송부터
+pub async fn list_deliveries<T: SafeDatabase>(
+    State(database): State<T>,
+    AuthedWallet(wallet): AuthedWallet,
+    Path(id): Path<String>,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<Json<DeliveryList>, WebhookError> {
+    let webhook = load_webhook(&database, &wallet, &id)?;
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, MAX_DELIVERY_LIMIT);
+
+    let deliveries = WEBHOOK_DELIVERIES.scan(&database, &KeyRange::prefix(KeyBuilder::new().str(&webhook.id).build()))
+        .map_err(db_err)?
+        .into_iter()
+        .rev()
+        .map(|(_, delivery)| delivery)
+        .filter(|delivery| query.status.is_none_or(|status| delivery.status == status))
+        .take(limit)
+        .collect();
+    Ok(Json(DeliveryList { deliveries }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::indexer::{event_key, CLAIM_EVENTS};
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_service::parser::chain::{ChainProposal, DaoSnapshot};
+    use turtle_service::parser::event::EventRecord;
+
+    const NOW: u64 = 10_000;
+
+    // 미리 정한 응답을 차례로 돌려주고 받은 요청을 남긴다
+    type SentRequest = (String, Vec<(&'static str, String)>, String);
+
+    struct FakeSender {
+        responses: Mutex<Vec<Result<u16, String>>>,
+        requests: Mutex<Vec<SentRequest>>,
+    }
+
+    impl FakeSender {
+        fn new(mut responses: Vec<Result<u16, String>>) -> Self {
+            responses.reverse();
+            Self { responses: Mutex::new(responses), requests: Mutex::new(Vec::new()) }
+        }
+    }
+
+    impl WebhookSender for FakeSender {
+        async fn send(&self, url: &str, headers: Vec<(&'static str, String)>, body: String) -> Result<u16, String> {
+            self.requests.lock().unwrap().push((url.to_string(), headers, body));
+            self.responses.lock().unwrap().pop().unwrap_or(Ok(200))
+        }
+    }
+
+    fn snapshot(timeout_timestamp: u64) -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: "dao".to_string(),
+            dao_name: "turtles".to_string(),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: false,
+            deposit_share: 10,
+            timeout_timestamp,
+            total_deposit: 5_000,
+            depositor_count: 1,
+            content_count: 0,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    fn proposal(proposal_id: u64) -> ChainProposal {
+        ChainProposal {
+            dao: "dao".to_string(),
+            proposal_id,
+            proposer: "alice".to_string(),
+            title: format!("Proposal {}", proposal_id),
+            description: String::new(),
+            vote_type: "ChangeBaseFee".to_string(),
+            options: Vec::new(),
+            start_time: NOW,
+            end_time: NOW + 100,
+            vote_count: 0,
+            status: "Active".to_string(),
+            option_power: Vec::new(),
+        }
+    }
+
+    async fn create(database: &MemoryDatabase, wallet: &str, body: Value) -> Result<WebhookResponse, WebhookError> {
+        let request = serde_json::from_value(body).unwrap();
+        create_webhook(State(database.clone()), AuthedWallet(wallet.to_string()), Json(request)).await.map(|(_, Json(webhook))| webhook)
+    }
+
+    #[test]
+    fn test_signature_and_backoff() {
+        let signed = signature("secret", 1_700_000_000, r#"{"id":0}"#);
+        assert!(signed.starts_with("sha256="));
+        assert_eq!(signed.len(), "sha256=".len() + 64);
+        assert_eq!(signed, signature("secret", 1_700_000_000, r#"{"id":0}"#));
+        assert_ne!(signed, signature("secret", 1_700_000_001, r#"{"id":0}"#));
+        assert_ne!(signed, signature("other", 1_700_000_000, r#"{"id":0}"#));
+
+        assert_eq!([1, 2, 3, 10].map(backoff), [30, 60, 120, MAX_BACKOFF_SECS]);
+    }
+
+    #[test]
+    fn test_internal_addresses_are_not_public() {
+        for internal in ["127.0.0.1", "10.0.0.1", "172.16.5.4", "192.168.1.1", "169.254.169.254", "0.0.0.0", "100.64.0.1", "::1", "::", "fe80::1", "fd00::1", "::ffff:10.0.0.1"] {
+            assert!(!is_public_ip(internal.parse().unwrap()), "{}", internal);
+        }
+        for public in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(public.parse().unwrap()), "{}", public);
+        }
+        assert!(allowed_ip("127.0.0.1".parse().unwrap(), true));
+        assert!(!allowed_ip("10.0.0.1".parse().unwrap(), true));
+    }
+
+    #[tokio::test]
+    async fn test_validate_url_rejects_internal_hosts() {
+        for url in ["https://169.254.169.254/latest/meta-data", "https://10.0.0.1/hook", "https://[::1]/hook", "https://[::ffff:192.168.0.1]/hook", "ftp://1.1.1.1/hook"] {
+            assert!(validate_url(url, false).await.is_err(), "{}", url);
+        }
+        // 루프백은 개발 설정이 켜져 있을 때만 (http 포함) 받는다
+        for url in ["http://localhost:8080/hook", "http://127.0.0.1/hook", "https://localhost/hook"] {
+            assert!(validate_url(url, false).await.is_err(), "{}", url);
+            assert!(validate_url(url, true).await.is_ok(), "{}", url);
+        }
+        assert!(validate_url("https://10.0.0.1/hook", true).await.is_err());
+        assert!(validate_url("https://1.1.1.1/hook", false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_refuses_internal_literals() {
+        let client = WebhookClient::new(false);
+        let sent = client.send("https://169.254.169.254/latest", Vec::new(), "{}".to_string()).await;
+        assert!(sent.unwrap_err().contains("non-public"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_crud_requires_dao_owner() {
+        let database = MemoryDatabase::new();
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot(NOW + 3_600)).unwrap();
+
+        let body = json!({ "dao": "dao", "url": "https://hooks.example.com/turtle", "events": ["claim", "claim"] });
+        assert!(matches!(create(&database, "mallory", body.clone()).await, Err(WebhookError::Forbidden(_))));
+        let insecure = json!({ "dao": "dao", "url": "http://hooks.example.com", "events": ["claim"] });
+        assert!(matches!(create(&database, "alice", insecure).await, Err(WebhookError::ValidationError(_))));
+
+        let created = create(&database, "alice", body).await.unwrap();
+        assert_eq!(created.events, [WebhookEvent::Claim]);
+        assert_eq!(created.secret.as_ref().map(String::len), Some(64));
+
+        // 목록에는 secret 이 나오지 않는다
+        let list = list_webhooks(State(database.clone()), AuthedWallet("alice".to_string()), Query(WebhookListQuery { dao: "dao".to_string() })).await.unwrap().0;
+        assert_eq!(list.webhooks.len(), 1);
+        assert!(list.webhooks[0].secret.is_none());
+
+        let update = UpdateWebhookRequest { url: None, events: Some(vec![WebhookEvent::NewProposal]), active: Some(false), rotate_secret: None };
+        let updated = update_webhook(State(database.clone()), AuthedWallet("alice".to_string()), Path(created.id.clone()), Json(update)).await.unwrap().0;
+        assert_eq!((updated.events.as_slice(), updated.active), ([WebhookEvent::NewProposal].as_slice(), false));
+
+        let denied = delete_webhook(State(database.clone()), AuthedWallet("mallory".to_string()), Path(created.id.clone())).await;
+        assert!(matches!(denied, Err(WebhookError::Forbidden(_))));
+        let deleted = delete_webhook(State(database.clone()), AuthedWallet("alice".to_string()), Path(created.id.clone())).await.unwrap();
+        assert_eq!(deleted, StatusCode::NO_CONTENT);
+        assert!(WEBHOOKS.get(&database, created.id.as_bytes()).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_events_are_signed_delivered_and_retried() {
+        let database = MemoryDatabase::new();
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot(NOW + 3_600)).unwrap();
+        CHAIN_PROPOSALS.put(&database, b"p0", &proposal(0)).unwrap();
+        let body = json!({ "dao": "dao", "url": "https://hooks.example.com/turtle", "events": ["claim", "new_proposal", "timer_expiring"] });
+        let webhook = create(&database, "alice", body).await.unwrap();
+        let secret = webhook.secret.unwrap();
+
+        // 처음 실행 전부터 있던 제안은 보내지 않는다
+        assert_eq!(collect_deliveries(&database, NOW).unwrap(), 0);
+
+        let claim = EventRecord {
+            id: 1,
+            signature: "sig1".to_string(),
+            slot: 1,
+            block_time: Some(NOW),
+            dao: "dao".to_string(),
+            actor: "bob".to_string(),
+            event: TurtleEvent::Claim { winner: Some("carol".to_string()) },
+        };
+        CLAIM_EVENTS.put(&database, &event_key(1), &claim).unwrap();
+        CHAIN_PROPOSALS.put(&database, b"p1", &proposal(1)).unwrap();
+        DAO_SNAPSHOTS.put(&database, b"dao", &snapshot(NOW + 300)).unwrap();
+        assert_eq!(collect_deliveries(&database, NOW).unwrap(), 3);
+        assert_eq!(collect_deliveries(&database, NOW).unwrap(), 0);
+
+        // 첫 번째(claim) 는 한 번 실패하고 나중에 성공한다
+        let sender = FakeSender::new(vec![Err("connection refused".to_string()), Ok(204), Ok(200)]);
+        assert_eq!(deliver_due(&database, &sender, NOW).await.unwrap(), 3);
+        {
+            let requests = sender.requests.lock().unwrap();
+            let (url, headers, body) = &requests[0];
+            assert_eq!(url, "https://hooks.example.com/turtle");
+            assert!(headers.contains(&(EVENT_HEADER, "claim".to_string())));
+            assert!(headers.contains(&(SIGNATURE_HEADER, signature(&secret, NOW, body))));
+            let payload: Value = serde_json::from_str(body).unwrap();
+            assert_eq!(payload["data"]["winner"], "carol");
+        }
+
+        let history = |status: Option<DeliveryStatus>| {
+            let query = DeliveryQuery { limit: None, status };
+            list_deliveries(State(database.clone()), AuthedWallet("alice".to_string()), Path(webhook.id.clone()), Query(query))
+        };
+        let pending = history(Some(DeliveryStatus::Pending)).await.unwrap().0.deliveries;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event, WebhookEvent::Claim);
+        assert_eq!(pending[0].next_attempt_at, Some(NOW + backoff(1)));
+        assert_eq!(pending[0].last_error.as_deref(), Some("connection refused"));
+
+        // backoff 전에는 다시 보내지 않는다
+        assert_eq!(deliver_due(&database, &sender, NOW + 1).await.unwrap(), 0);
+        assert_eq!(deliver_due(&database, &sender, NOW + backoff(1)).await.unwrap(), 1);
+        let delivered = history(Some(DeliveryStatus::Delivered)).await.unwrap().0.deliveries;
+        assert_eq!(delivered.len(), 3);
+        assert!(delivered.iter().any(|delivery| delivery.event == WebhookEvent::Claim && delivery.attempts == 2));
+    }
+}
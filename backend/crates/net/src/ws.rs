@@ -0,0 +1,513 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use turtle_database::basic_db::SafeDatabase;
+use turtle_database::keys::{KeyBuilder, KeyReader};
+use turtle_database::ttl::now_secs;
+use turtle_database::watch::{ChangeEvent, ChangeFeed, ChangeKind};
+use turtle_service::parser::chain::{ChainContent, ChainProposal};
+use turtle_service::parser::event::TurtleEvent;
+use crate::dao::DAO_REGISTRY;
+use crate::indexer::VOTE_EVENTS;
+use crate::reports::is_hidden;
+use crate::sync::{CHAIN_CONTENTS, CHAIN_PROPOSALS, DAO_SNAPSHOTS};
+
+// 카운트다운 tick 을 보내는 주기
+pub const COUNTDOWN_TICK: Duration = Duration::from_secs(1);
+
+// 지금 열려 있는 /ws/dao, /ws/proposal (채팅방 포함) 연결 수 (/metrics 로 내보낸다)
+pub static WS_CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+
+// 연결이 어떻게 끝나든 (close, 에러, 태스크 취소) 한 번만 빼도록 drop 에서 센다
+pub struct ConnectionGuard;
+
+impl ConnectionGuard {
+    pub fn open() -> Self {
+        WS_CONNECTIONS.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        WS_CONNECTIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// /ws/dao/{pubkey} 로 나가는 메시지
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaoStreamMessage {
+    // 주기적으로 보내는 남은 시간. 아직 동기화된 스냅샷이 없으면 보내지 않는다.
+    Countdown {
+        dao: String,
+        countdown: u64,
+        timeout_timestamp: u64,
+        pot_size: u64,
+    },
+    // 예치/제출로 타이머가 다시 시작됨
+    TimerReset {
+        dao: String,
+        timeout_timestamp: u64,
+    },
+    // 체인에서 새 콘텐츠가 인덱싱됨
+    NewContent {
+        dao: String,
+        content: ChainContent,
+    },
+}
+
+fn countdown_message<T: SafeDatabase>(database: &T, dao: &str, now: u64) -> Option<DaoStreamMessage> {
+    let snapshot = DAO_SNAPSHOTS.get(database, dao.as_bytes()).ok()??;
+    Some(DaoStreamMessage::Countdown {
+        dao: dao.to_string(),
+        countdown: snapshot.timeout_timestamp.saturating_sub(now),
+        timeout_timestamp: snapshot.timeout_timestamp,
+        pot_size: snapshot.total_deposit,
+    })
+}
+
+fn current_timeout<T: SafeDatabase>(database: &T, dao: &str) -> Option<u64> {
+    DAO_SNAPSHOTS.get(database, dao.as_bytes())
+        .ok()
+        .flatten()
+        .map(|snapshot| snapshot.timeout_timestamp)
+}
+
+fn content_owner(key: &[u8]) -> Option<String> {
+    KeyReader::new(key).str().ok().map(str::to_string)
+}
+
+// 변경 알림 하나를 이 DAO 구독자에게 보낼 메시지로 바꾼다.
+// last_timeout 은 연결별로 기억해 두는 마지막 timeout_timestamp 이다.
+fn on_change<T: SafeDatabase>(
+    database: &T,
+    dao: &str,
+    change: &ChangeEvent,
+    last_timeout: &mut Option<u64>,
+) -> Option<DaoStreamMessage> {
+    if change.kind != ChangeKind::Put {
+        return None;
+    }
+
+    if change.table == DAO_SNAPSHOTS.name() && change.key == dao.as_bytes() {
+        let timeout_timestamp = current_timeout(database, dao)?;
+        let reset = last_timeout.is_some_and(|last| timeout_timestamp > last);
+        *last_timeout = Some(timeout_timestamp);
+        return reset.then(|| DaoStreamMessage::TimerReset {
+            dao: dao.to_string(),
+            timeout_timestamp,
+        });
+    }
+
+    if change.table == CHAIN_CONTENTS.name() && content_owner(&change.key).as_deref() == Some(dao) {
+        if is_hidden(database, &change.key).unwrap_or(false) {
+            return None;
+        }
+        let content = CHAIN_CONTENTS.get(database, &change.key).ok()??;
+        return Some(DaoStreamMessage::NewContent {
+            dao: dao.to_string(),
+            content,
+        });
+    }
+
+    None
+}
+
+// 선택지 하나의 집계
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OptionTally {
+    pub option: String,
+    pub power: u64,
+}
+
+// /ws/proposal/{pubkey}/{proposal_id} 로 나가는 메시지
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposalStreamMessage {
+    // 연결 직후와 동기화로 제안이 바뀔 때마다 보내는 전체 집계
+    Tally {
+        dao: String,
+        proposal_id: u64,
+        status: String,
+        options: Vec<OptionTally>,
+        voter_count: u64,
+        total_power: u64,
+        // 투표력 합 / DAO 예치 총액 (1.0 에서 자른다). 프로그램에 정족수가 없어서 예치 총액을 기준으로 본다.
+        quorum_progress: f64,
+        end_time: u64,
+        time_remaining: u64,
+    },
+    // 투표 중인 동안 주기적으로 보내는 남은 시간
+    Countdown {
+        dao: String,
+        proposal_id: u64,
+        end_time: u64,
+        time_remaining: u64,
+    },
+    // 인덱서가 이 제안의 투표 트랜잭션을 잡았다. 집계는 다음 동기화 뒤 Tally 로 온다.
+    VoteIndexed {
+        dao: String,
+        proposal_id: u64,
+        voter: String,
+        signature: String,
+    },
+}
+
+fn proposal_key(dao: &str, proposal_id: u64) -> Vec<u8> {
+    KeyBuilder::new().str(dao).u64(proposal_id).build()
+}
+
+fn tally_message(proposal: &ChainProposal, total_deposit: u64, now: u64) -> ProposalStreamMessage {
+    let options: Vec<OptionTally> = proposal.options.iter()
+        .enumerate()
+        .map(|(index, option)| OptionTally {
+            option: option.clone(),
+            power: proposal.option_power.get(index).copied().unwrap_or(0),
+        })
+        .collect();
+    let total_power = options.iter().fold(0u64, |total, option| total.saturating_add(option.power));
+    let quorum_progress = match total_deposit {
+        0 => 0.0,
+        deposit => (total_power as f64 / deposit as f64).min(1.0),
+    };
+    ProposalStreamMessage::Tally {
+        dao: proposal.dao.clone(),
+        proposal_id: proposal.proposal_id,
+        status: proposal.status.clone(),
+        options,
+        voter_count: proposal.vote_count,
+        total_power,
+        quorum_progress,
+        end_time: proposal.end_time,
+        time_remaining: proposal.end_time.saturating_sub(now),
+    }
+}
+
+fn current_tally<T: SafeDatabase>(database: &T, dao: &str, proposal_id: u64, now: u64) -> Option<ProposalStreamMessage> {
+    let proposal = CHAIN_PROPOSALS.get(database, &proposal_key(dao, proposal_id)).ok()??;
+    let total_deposit = DAO_SNAPSHOTS.get(database, dao.as_bytes())
+        .ok()
+        .flatten()
+        .map(|snapshot| snapshot.total_deposit)
+        .unwrap_or(0);
+    Some(tally_message(&proposal, total_deposit, now))
+}
+
+// 투표가 끝났거나 마감이 지났으면 보내지 않는다
+fn proposal_countdown<T: SafeDatabase>(database: &T, dao: &str, proposal_id: u64, now: u64) -> Option<ProposalStreamMessage> {
+    let proposal = CHAIN_PROPOSALS.get(database, &proposal_key(dao, proposal_id)).ok()??;
+    (proposal.status == "Active" && proposal.end_time > now).then(|| ProposalStreamMessage::Countdown {
+        dao: dao.to_string(),
+        proposal_id,
+        end_time: proposal.end_time,
+        time_remaining: proposal.end_time - now,
+    })
+}
+
+// 변경 알림 하나를 이 제안 구독자에게 보낼 메시지로 바꾼다
+fn on_proposal_change<T: SafeDatabase>(
+    database: &T,
+    dao: &str,
+    proposal_id: u64,
+    change: &ChangeEvent,
+    now: u64,
+) -> Option<ProposalStreamMessage> {
+    if change.kind != ChangeKind::Put {
+        return None;
+    }
+
+    // 예치 총액이 바뀌면 정족수 진행률도 바뀐다
+    let snapshot_changed = change.table == DAO_SNAPSHOTS.name() && change.key == dao.as_bytes();
+    if snapshot_changed || (change.table == CHAIN_PROPOSALS.name() && change.key == proposal_key(dao, proposal_id)) {
+        return current_tally(database, dao, proposal_id, now);
+    }
+
+    if change.table == VOTE_EVENTS.name() {
+        let record = VOTE_EVENTS.get(database, &change.key).ok()??;
+        return match record.event {
+            TurtleEvent::Vote { proposal_id: voted } if voted == proposal_id && record.dao == dao => {
+                Some(ProposalStreamMessage::VoteIndexed {
+                    dao: dao.to_string(),
+                    proposal_id,
+                    voter: record.actor,
+                    signature: record.signature,
+                })
+            },
+            _ => None,
+        };
+    }
+
+    None
+}
+
+pub async fn dao_stream<T: SafeDatabase + ChangeFeed>(
+    ws: WebSocketUpgrade,
+    State(database): State<T>,
+    Path(pubkey): Path<String>,
+) -> Response {
+    match DAO_REGISTRY.get(&database, pubkey.as_bytes()) {
+        Ok(Some(_)) => ws.on_upgrade(move |socket| stream_dao(socket, database, pubkey)),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("DAO not found: {}", pubkey)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// pubkey 는 제안이 들어 있는 DAO 계정이다 (제안에는 따로 계정이 없다)
+pub async fn proposal_stream<T: SafeDatabase + ChangeFeed>(
+    ws: WebSocketUpgrade,
+    State(database): State<T>,
+    Path((pubkey, proposal_id)): Path<(String, u64)>,
+) -> Response {
+    match CHAIN_PROPOSALS.get(&database, &proposal_key(&pubkey, proposal_id)) {
+        Ok(Some(_)) => ws.on_upgrade(move |socket| stream_proposal(socket, database, pubkey, proposal_id)),
+        Ok(None) => (StatusCode::NOT_FOUND, format!("Proposal not found: {}/{}", pubkey, proposal_id)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &impl Serialize) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => socket.send(Message::Text(text.into())).await.is_ok(),
+        Err(_) => true,
+    }
+}
+
+async fn stream_dao<T: SafeDatabase + ChangeFeed>(mut socket: WebSocket, database: T, dao: String) {
+    let _connection = ConnectionGuard::open();
+    let mut changes = database.subscribe();
+    let mut tick = tokio::time::interval(COUNTDOWN_TICK);
+    let mut last_timeout = current_timeout(&database, &dao);
+
+    loop {
+        let message = tokio::select! {
+            _ = tick.tick() => countdown_message(&database, &dao, now_secs()),
+            change = changes.recv() => match change {
+                Ok(change) => on_change(&database, &dao, &change, &mut last_timeout),
+                // 알림을 놓쳤으면 다음 tick 의 카운트다운으로 따라잡는다
+                Err(RecvError::Lagged(_)) => None,
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => None,
+            },
+        };
+
+        if let Some(message) = message {
+            if !send(&mut socket, &message).await {
+                break;
+            }
+        }
+    }
+}
+
+
+async fn stream_proposal<T: SafeDatabase + ChangeFeed>(mut socket: WebSocket, database: T, dao: String, proposal_id: u64) {
+    let _connection = ConnectionGuard::open();
+    let mut changes = database.subscribe();
+    let mut tick = tokio::time::interval(COUNTDOWN_TICK);
+    // interval 의 첫 tick 은 바로 오므로 집계를 먼저 보낸다
+    if let Some(tally) = current_tally(&database, &dao, proposal_id, now_secs()) {
+        if !send(&mut socket, &tally).await {
+            return;
+        }
+    }
+    tick.tick().await;
+
+    loop {
+        let message = tokio::select! {
+            _ = tick.tick() => proposal_countdown(&database, &dao, proposal_id, now_secs()),
+            change = changes.recv() => match change {
+                Ok(change) => on_proposal_change(&database, &dao, proposal_id, &change, now_secs()),
+                // 알림을 놓쳤으면 지금 집계를 다시 보낸다
+                Err(RecvError::Lagged(_)) => current_tally(&database, &dao, proposal_id, now_secs()),
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => None,
+            },
+        };
+
+        if let Some(message) = message {
+            if !send(&mut socket, &message).await {
+                break;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turtle_database::memory_db::MemoryDatabase;
+    use turtle_database::watch::Watched;
+    use turtle_service::parser::chain::DaoSnapshot;
+    use turtle_service::parser::event::EventRecord;
+    use crate::indexer::event_key;
+
+    fn snapshot(timeout_timestamp: u64) -> DaoSnapshot {
+        DaoSnapshot {
+            pubkey: "dao1".to_string(),
+            dao_name: "turtles".to_string(),
+            initializer: "alice".to_string(),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: false,
+            deposit_share: 10,
+            timeout_timestamp,
+            total_deposit: 5_000,
+            depositor_count: 1,
+            content_count: 0,
+            proposal_count: 0,
+            next_proposal_id: 0,
+            round: 0,
+            last_submitter: String::new(),
+            synced_at: 0,
+        }
+    }
+
+    fn content(dao: &str) -> ChainContent {
+        ChainContent {
+            dao: dao.to_string(),
+            index: 0,
+            author: "bob".to_string(),
+            text: "hello".to_string(),
+            image_uri: String::new(),
+            timestamp: 0,
+            vote_count: 0,
+            round: 0,
+        }
+    }
+
+    #[test]
+    fn test_countdown() {
+        let database = MemoryDatabase::new();
+        assert_eq!(countdown_message(&database, "dao1", 1_000), None);
+
+        DAO_SNAPSHOTS.put(&database, b"dao1", &snapshot(1_600)).unwrap();
+        assert_eq!(
+            countdown_message(&database, "dao1", 1_000),
+            Some(DaoStreamMessage::Countdown { dao: "dao1".to_string(), countdown: 600, timeout_timestamp: 1_600, pot_size: 5_000 })
+        );
+        assert!(matches!(countdown_message(&database, "dao1", 2_000), Some(DaoStreamMessage::Countdown { countdown: 0, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_changes_become_messages() {
+        let database = Watched::new(MemoryDatabase::new());
+        DAO_SNAPSHOTS.put(&database, b"dao1", &snapshot(1_600)).unwrap();
+        let mut changes = database.subscribe();
+        let mut last_timeout = current_timeout(&database, "dao1");
+
+        // 같은 타이머로 다시 동기화되면 알리지 않고, 타이머가 밀리면 알린다
+        DAO_SNAPSHOTS.put(&database, b"dao1", &snapshot(1_600)).unwrap();
+        DAO_SNAPSHOTS.put(&database, b"dao1", &snapshot(2_000)).unwrap();
+        CHAIN_CONTENTS.put(&database, &KeyBuilder::new().str("dao2").u64(0).build(), &content("dao2")).unwrap();
+        CHAIN_CONTENTS.put(&database, &KeyBuilder::new().str("dao1").u64(0).build(), &content("dao1")).unwrap();
+
+        let mut messages = Vec::new();
+        for _ in 0..4 {
+            let change = changes.recv().await.unwrap();
+            messages.extend(on_change(&database, "dao1", &change, &mut last_timeout));
+        }
+
+        assert_eq!(messages, vec![
+            DaoStreamMessage::TimerReset { dao: "dao1".to_string(), timeout_timestamp: 2_000 },
+            DaoStreamMessage::NewContent { dao: "dao1".to_string(), content: content("dao1") },
+        ]);
+    }
+
+    fn proposal(option_power: Vec<u64>) -> ChainProposal {
+        ChainProposal {
+            dao: "dao1".to_string(),
+            proposal_id: 2,
+            proposer: "alice".to_string(),
+            title: "longer rounds".to_string(),
+            description: String::new(),
+            vote_type: "ChangeTimeLimit".to_string(),
+            options: vec!["Yes".to_string(), "No".to_string()],
+            start_time: 1_000,
+            end_time: 1_600,
+            vote_count: option_power.iter().filter(|power| **power > 0).count() as u64,
+            status: "Active".to_string(),
+            option_power,
+        }
+    }
+
+    fn vote(id: u64, dao: &str, proposal_id: u64) -> EventRecord {
+        EventRecord {
+            id,
+            signature: format!("sig{}", id),
+            slot: id,
+            block_time: None,
+            dao: dao.to_string(),
+            actor: "carol".to_string(),
+            event: TurtleEvent::Vote { proposal_id },
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        let database = MemoryDatabase::new();
+        assert_eq!(current_tally(&database, "dao1", 2, 1_000), None);
+
+        CHAIN_PROPOSALS.put(&database, &proposal_key("dao1", 2), &proposal(vec![3_000, 1_000])).unwrap();
+        DAO_SNAPSHOTS.put(&database, b"dao1", &snapshot(1_600)).unwrap();
+        assert_eq!(current_tally(&database, "dao1", 2, 1_200), Some(ProposalStreamMessage::Tally {
+            dao: "dao1".to_string(),
+            proposal_id: 2,
+            status: "Active".to_string(),
+            options: vec![
+                OptionTally { option: "Yes".to_string(), power: 3_000 },
+                OptionTally { option: "No".to_string(), power: 1_000 },
+            ],
+            voter_count: 2,
+            total_power: 4_000,
+            quorum_progress: 0.8,
+            end_time: 1_600,
+            time_remaining: 400,
+        }));
+
+        // 예치 총액보다 많이 모여도 1.0, 예전 행처럼 option_power 가 없으면 0
+        assert!(matches!(tally_message(&proposal(vec![9_000, 0]), 5_000, 0), ProposalStreamMessage::Tally { quorum_progress, .. } if quorum_progress == 1.0));
+        assert!(matches!(tally_message(&proposal(vec![]), 0, 0), ProposalStreamMessage::Tally { total_power: 0, quorum_progress, .. } if quorum_progress == 0.0));
+
+        assert!(matches!(proposal_countdown(&database, "dao1", 2, 1_500), Some(ProposalStreamMessage::Countdown { time_remaining: 100, .. })));
+        assert_eq!(proposal_countdown(&database, "dao1", 2, 1_600), None);
+    }
+
+    #[tokio::test]
+    async fn test_proposal_changes_become_messages() {
+        let database = Watched::new(MemoryDatabase::new());
+        let mut changes = database.subscribe();
+
+        CHAIN_PROPOSALS.put(&database, &proposal_key("dao1", 1), &proposal(vec![1, 0])).unwrap();
+        VOTE_EVENTS.put(&database, &event_key(1), &vote(1, "dao1", 1)).unwrap();
+        VOTE_EVENTS.put(&database, &event_key(2), &vote(2, "dao2", 2)).unwrap();
+        VOTE_EVENTS.put(&database, &event_key(3), &vote(3, "dao1", 2)).unwrap();
+        CHAIN_PROPOSALS.put(&database, &proposal_key("dao1", 2), &proposal(vec![500, 0])).unwrap();
+
+        let mut messages = Vec::new();
+        for _ in 0..5 {
+            let change = changes.recv().await.unwrap();
+            messages.extend(on_proposal_change(&database, "dao1", 2, &change, 1_000));
+        }
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0], ProposalStreamMessage::VoteIndexed {
+            dao: "dao1".to_string(),
+            proposal_id: 2,
+            voter: "carol".to_string(),
+            signature: "sig3".to_string(),
+        });
+        assert!(matches!(&messages[1], ProposalStreamMessage::Tally { total_power: 500, time_remaining: 600, .. }));
+    }
+}
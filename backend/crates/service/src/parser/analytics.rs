@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+// 집계 작업이 (dao, 하루) 마다 analytics_daily 테이블에 쓰는 레코드.
+// 날짜는 이벤트의 block_time (UTC) 기준이고, 금액은 모두 lamports 이다.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyAnalytics {
+    pub dao: String,
+    pub period_start: u64,              // 그날 00:00 UTC 의 unix 초
+    pub active_wallets: u64,            // 그날 어떤 이벤트든 남긴 지갑 수
+    pub unique_posters: u64,            // 콘텐츠를 제출한 지갑 수
+    pub unique_depositors: u64,         // 상금 풀에 예치한 지갑 수
+    pub submissions: u64,
+    pub votes: u64,
+    pub deposits: u64,                  // 예치 횟수
+    pub deposit_volume: u64,
+    pub claims: u64,                    // 우승자가 있었던 타임아웃 처리 수
+    pub claim_amount: u64,              // 그 라운드들에서 분배된 상금 풀 합
+    pub tvl: u64,                       // 그날 마지막 이벤트 뒤의 상금 풀
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+// 체인 동기화 작업이 RPC 에서 읽은 DAO 계정을 그대로 옮겨 둔 스냅샷.
+// HTTP 핸들러는 RPC 대신 이 테이블들을 읽는다.
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaoSnapshot {
+    pub pubkey: String,                 // DAO 계정 공개키
+    pub dao_name: String,
+    pub initializer: String,            // DAO 를 만든 계정
+    pub time_limit: u64,                // 시간 제한(초)
+    pub base_fee: u64,                  // 기본 수수료(lamports)
+    pub ai_moderation: bool,
+    pub deposit_share: u8,
+    pub timeout_timestamp: u64,         // 이 시각이 지나면 보상이 분배된다
+    pub total_deposit: u64,             // 현재 상금 풀
+    pub depositor_count: u64,
+    pub content_count: u64,
+    pub proposal_count: u64,
+    pub next_proposal_id: u64,
+    pub synced_at: u64,                 // 마지막으로 RPC 에서 읽은 시각
+    // 승자가 나온 라운드 수 (ProcessTimeout 이 만들 RoundResult PDA 의 번호). 이 필드 전에 동기화된 행에는 없다.
+    #[serde(default)]
+    pub round: u64,
+    // 이번 라운드의 마지막 제출자 (ProcessTimeout 이 상금을 보낼 계정). 제출이 없었거나 이 필드 전에 동기화된 행은 빈 문자열.
+    #[serde(default)]
+    pub last_submitter: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainContent {
+    pub dao: String,
+    pub index: u64,                     // DaoState.contents 안의 위치
+    pub author: String,
+    pub text: String,
+    pub image_uri: String,
+    pub timestamp: u64,
+    pub vote_count: u64,
+    #[serde(default)]
+    pub round: u64,                     // 올라온 라운드 (DaoState.round). 품질 보상 자격을 가를 때 쓴다.
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainProposal {
+    pub dao: String,
+    pub proposal_id: u64,
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub vote_type: String,              // ChangeTimeLimit, ChangeBaseFee, ...
+    pub options: Vec<String>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub vote_count: u64,
+    pub status: String,                 // Active, Completed, Executed
+    // 선택지별 투표력 합 (options 와 같은 순서). 이 필드 전에 동기화된 행에는 없다.
+    #[serde(default)]
+    pub option_power: Vec<u64>,
+}
+
+// 스냅샷의 마감(last_activity_timestamp + time_limit) 이 지났는데 아직 타임아웃 처리가 안 된 라운드.
+// 카운트다운 감시 작업이 DAO 마다 하나씩 남기고, 처리돼서 마감이 밀려나면 지운다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimableRound {
+    pub dao: String,
+    pub deadline: u64,                  // 지난 마감 (DaoSnapshot.timeout_timestamp)
+    pub pot_size: u64,
+    pub content_count: u64,
+    pub detected_at: u64,
+    pub crank_attempted_at: Option<u64>, // keeper 가 마지막으로 ProcessTimeout 을 보낸 시각
+    pub crank_signature: Option<String>,
+    pub crank_error: Option<String>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// dao_chat 테이블에 (dao, sent_at, seq) 로 저장되는 메시지
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ChatMessage {
+    pub dao: String,
+    pub sender: String,                 // 보낸 세션의 지갑
+    pub text: String,
+    pub sent_at: u64,
+    pub seq: u32,                       // 같은 DAO 에 같은 초에 온 메시지 중 몇 번째인지
+}
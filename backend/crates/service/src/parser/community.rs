@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::parser::upload::StorageKind;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct Community {
     pub admin: String,                  // DAO 관리자 공개키
     pub time_limit: u64,                // 시간 제한(초)
@@ -14,7 +16,7 @@ pub struct Community {
     pub depositor_count: u64,           // 예치자 수
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct Content {
     pub author: String,                 // 작성자 공개키
     pub content_hash: String,           // 콘텐츠 해시(텍스트 + 이미지 참조)
@@ -25,7 +27,7 @@ pub struct Content {
 
 
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct Depositor {
     pub pubkey: String,                 // 예치자 공개키
     pub amount: u64,                    // 예치 금액
@@ -33,7 +35,7 @@ pub struct Depositor {
     pub voting_power: u64,              // 투표 파워(예치금액 기반)
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct Proposal {
     pub id: u64,                        // 제안 ID
     pub proposal_type: u8,    // 제안 유형(TimeLimit - 0, BaseFee - 1, AiModeration - 2)
@@ -44,7 +46,20 @@ pub struct Proposal {
     pub is_executed: bool,              // 실행 여부
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct Daopda{
     pub address: String            // 실행 여부
 }
+
+
+// /api/dao 로 등록된 DAO (프로그램 계정 + 표시용 메타데이터)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DaoInfo {
+    pub pubkey: String,                 // DAO 프로그램 계정 공개키 (community 테이블의 PDA)
+    pub name: String,                   // 표시 이름
+    pub description: String,            // 소개
+    pub image_uri: Option<String>,      // 대표 이미지 URI
+    #[serde(default)]
+    pub storage: StorageKind,           // /api/upload 의 기본 저장소
+    pub registered_at: u64,             // 등록 시각
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// /api/content 로 제출되어 content_records 테이블에 저장되는 콘텐츠 메타데이터
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ContentRecord {
+    pub id: u64,                        // 서버가 붙이는 증가 ID
+    pub dao: String,                    // 콘텐츠가 속한 DAO 공개키
+    pub author: String,                 // 작성자 공개키
+    pub content_hash: String,           // 콘텐츠 해시(텍스트 + 이미지 참조)
+    pub content_uri: String,            // 콘텐츠 상세 URI(예: IPFS 링크)
+    pub timestamp: u64,                 // 생성 타임스탬프
+}
+
+// 랭킹 작업이 콘텐츠마다 content_stats 테이블에 남기는 집계.
+// 체인의 콘텐츠는 라운드가 끝나면 계정에서 지워지므로 마지막으로 본 투표 수를 여기 붙잡아 둔다.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentStats {
+    pub votes: u64,                     // 마지막으로 본 온체인 투표 수
+    pub reward: u64,                    // 우승해서 작성자가 받은 lamports (quality share)
+    pub trending: u64,                  // 시간 감쇠를 적용한 투표 점수
+    pub ranked_at: u64,                 // 집계한 시각
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+// 프로그램 로그에서 읽어 낸 이벤트
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TurtleEvent {
+    Deposit { amount: u64 },
+    Submission,
+    // 타임아웃 처리. 제출된 콘텐츠가 없었으면 winner 가 없다.
+    Claim { winner: Option<String> },
+    Vote { proposal_id: u64 },
+}
+
+// 이벤트 테이블에 저장되는 레코드
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub id: u64,                        // 인덱서가 붙이는 전역 증가 ID (모든 이벤트 테이블에서 공유)
+    pub signature: String,              // 트랜잭션 서명
+    pub slot: u64,
+    pub block_time: Option<u64>,
+    pub dao: String,                    // 명령의 DAO 계정
+    pub actor: String,                  // 명령에 서명한 계정
+    #[serde(flatten)]
+    pub event: TurtleEvent,
+}
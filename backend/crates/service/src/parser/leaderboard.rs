@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+// 리더보드 집계 구간
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LeaderboardWindow {
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+    #[serde(rename = "all")]
+    AllTime,
+}
+
+// 인덱싱된 이벤트에서 모은 지갑별 집계
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub wallet: String,
+    pub submissions: u64,               // 제출한 콘텐츠 수
+    pub votes: u64,                     // 온체인 투표 수
+    pub tips: u64,                      // 상금 풀에 넣은 lamports (Deposit)
+    pub rewards: u64,                   // 타임아웃 때 우승자로 보상을 받은 횟수
+}
+
+// 집계 작업이 (dao, window) 마다 leaderboards 테이블에 쓰는 레코드
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub dao: String,
+    pub window: LeaderboardWindow,
+    pub computed_at: u64,               // 집계한 시각
+    pub entries: Vec<LeaderboardEntry>, // 지갑 순서. 정렬은 조회할 때 한다.
+}
@@ -1,2 +1,13 @@
 pub mod profile;
-pub mod community;
\ No newline at end of file
+pub mod community;
+pub mod content;
+pub mod chain;
+pub mod event;
+pub mod upload;
+pub mod leaderboard;
+pub mod search;
+pub mod notification;
+pub mod webhook;
+pub mod moderation;
+pub mod chat;
+pub mod analytics;
\ No newline at end of file
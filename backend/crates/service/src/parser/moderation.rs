@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Approved,
+    Rejected,
+}
+
+// moderation_verdicts 테이블의 값 (키는 chain_contents 와 같은 (dao, index))
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModerationVerdict {
+    pub dao: String,
+    pub index: u64,                     // DaoState.contents 안의 위치
+    pub verdict: Verdict,
+    pub score: f64,                     // 0.0 (안전) ~ 1.0 (위반). threshold 이상이면 Rejected
+    #[serde(default)]
+    pub categories: Vec<String>,        // 제공자가 붙인 위반 분류 (spam, hate, ...)
+    pub provider: String,               // 판정한 제공자 이름
+    pub moderated_at: u64,
+    #[serde(default)]
+    pub overridden: Option<ModerationOverride>,
+}
+
+// 운영자가 판정을 바꾼 기록. 워커는 판정이 있는 콘텐츠를 다시 보내지 않으므로 그대로 유지된다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationOverride {
+    pub by: String,                     // 바꾼 지갑
+    pub reason: Option<String>,
+    pub original: Option<Verdict>,      // 제공자가 낸 판정 (판정 전에 바꿨으면 None)
+    pub at: u64,
+}
+
+// 사용자가 콘텐츠를 신고한 이유
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Abuse,
+    Illegal,
+    Other,
+}
+
+// content_reports 테이블에 (dao, index, reporter) 로 저장되는 신고. 지갑 하나가 콘텐츠 하나에 한 번만 신고한다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentReport {
+    pub dao: String,
+    pub index: u64,
+    pub reporter: String,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+    pub created_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    Open,                               // 운영자가 아직 보지 않았다
+    Hidden,                             // API 응답에서 숨겼다
+    Escalated,                          // Rejected 모더레이션 판정으로 올렸다
+    Dismissed,                          // 문제없다고 닫았다
+}
+
+// report_cases 테이블에 (dao, index) 로 저장되는 콘텐츠별 신고 묶음. 모더레이터 큐의 한 줄이다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportCase {
+    pub dao: String,
+    pub index: u64,
+    pub status: CaseStatus,
+    pub report_count: u64,
+    pub first_reported_at: u64,
+    pub last_reported_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    Report,
+    Hide,
+    Unhide,
+    Escalate,
+    Dismiss,
+}
+
+// moderation_audit 테이블의 기록. 신고와 운영자 조치를 모두 남긴다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModerationAudit {
+    pub dao: String,
+    pub index: u64,
+    pub action: ModerationAction,
+    pub by: String,                     // 신고한 지갑이나 조치한 모더레이터
+    pub note: Option<String>,
+    pub at: u64,
+}
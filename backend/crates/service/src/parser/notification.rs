@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::collections::BTreeSet;
+
+// 인박스에 쌓이는 알림 종류
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationKind {
+    // 내 뒤에 다른 지갑이 콘텐츠를 제출해서 더 이상 마지막 제출자가 아니다
+    Outbid { by: String },
+    // 내가 투표한 제안이 실행됐다
+    ProposalExecuted { proposal_id: u64, title: String },
+    // 내가 참여한 라운드가 끝나고 보상이 분배됐다 (제출이 없었으면 winner 가 없다)
+    RewardDistributed { winner: Option<String> },
+    // 내가 제출한 라운드의 마감이 지나 타임아웃 처리(보상 분배) 를 할 수 있다
+    RoundClaimable { deadline: u64 },
+}
+
+// notifications 테이블에 (wallet, id) 로 저장되는 레코드
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Notification {
+    pub id: u64,                        // 알림 작업이 붙이는 전역 증가 ID
+    pub wallet: String,                 // 받는 지갑
+    pub dao: String,
+    pub event_id: Option<u64>,          // 알림을 만든 인덱싱된 이벤트 (제안 실행과 마감은 체인 동기화에서 온다)
+    pub created_at: u64,
+    pub read: bool,
+    #[serde(flatten)]
+    pub kind: NotificationKind,
+}
+
+// 알림 작업이 DAO 마다 들고 있는 현재 라운드 상태
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRound {
+    pub last_submitter: Option<String>,
+    pub participants: BTreeSet<String>, // 지난 Claim 이후 콘텐츠를 제출한 지갑들
+}
@@ -1,15 +1,66 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct UserProfile {
-    pub user_id: String,           // 사용자 고유 ID
-    pub user_name: String,
-    pub user_address: String,
-    pub github_account: String,
-    pub x_account: String,
-    pub tg_account: String,
-    pub user_bio: String,
-    pub user_avatar: Option<Vec<u8>>,  // 바이너리 이미지 데이터
-    pub avatar_content_type: Option<String>,  // 이미지 MIME 타입 (예: "image/jpeg")
+// 소셜 계정은 핸들만 저장한다 (@ 없이). website 는 http(s) URL.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SocialLinks {
+    #[serde(default)]
+    pub github: Option<String>,
+    #[serde(default)]
+    pub x: Option<String>,
+    #[serde(default)]
+    pub telegram: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMethod {
+    SignedMessage,                      // 프로필 연결 메시지에 지갑으로 서명
+    MemoTransaction,                    // 지갑이 서명한 0 lamport memo 트랜잭션
+}
+
+// 지갑을 실제로 가진 사람이 프로필을 연결했다는 증명. 프로필을 고쳐도 남는다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct WalletVerification {
+    pub method: VerificationMethod,
+    pub proof: String,                  // 메시지 서명 또는 memo 트랜잭션 서명 (base58)
+    pub verified_at: u64,
+}
+
+// user_profiles 테이블의 값 (키는 wallet). 스키마 v3 부터 이 형식이다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Profile {
+    pub wallet: String,                // 프로필이 연결된 지갑 주소
+    pub display_name: String,
+    pub bio: String,
+    pub avatar_uri: Option<String>,    // ipfs://, ar://, https:// (마이그레이션된 옛 아바타는 data: URI)
+    #[serde(default)]
+    pub links: SocialLinks,
+    pub created_at: u64,
+    pub updated_at: u64,
+    // 지갑 소유가 증명된 프로필에 붙는 배지. 목록에서 이름만 같은 다른 지갑과 구분하는 데 쓴다.
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub verification: Option<WalletVerification>,
+}
+
+impl Profile {
+    // 아직 저장된 적 없는 지갑의 빈 프로필 (타임스탬프 0)
+    pub fn empty(wallet: &str) -> Self {
+        Self {
+            wallet: wallet.to_string(),
+            display_name: String::new(),
+            bio: String::new(),
+            avatar_uri: None,
+            links: SocialLinks::default(),
+            created_at: 0,
+            updated_at: 0,
+            verified: false,
+            verification: None,
+        }
+    }
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    Content,
+    Proposal,
+    Profile,
+    Dao,
+}
+
+// 검색 색인에 들어간 문서 하나. 결과로 그대로 보여 줄 필드와, 다시 색인할 때 지울 토큰 목록을 들고 있다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchDocument {
+    pub kind: SearchKind,
+    pub id: String,                     // content/proposal: "{dao}/{번호}", profile: 지갑 주소, dao: 공개키
+    pub title: String,
+    pub snippet: String,                // 본문 앞부분
+    pub dao: Option<String>,
+    pub tokens: Vec<String>,            // 이 문서로 만든 색인 토큰
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// 업로드한 콘텐츠를 어디에 둘지. DAO 설정의 기본값이고, 요청마다 바꿀 수도 있다.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    #[default]
+    Ipfs,                               // ipfs://<cid>
+    Arweave,                            // ar://<transaction id>
+}
+
+// /api/upload 로 올린 콘텐츠. content_hash 로 찾아서 어디에 고정(pin)되어 있는지 알 수 있다.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadRecord {
+    pub content_hash: String,           // sha256(텍스트 + 이미지 참조), hex
+    pub content_uri: String,            // 텍스트와 이미지 참조를 담은 JSON 문서 (ipfs://<cid> 또는 ar://<id>)
+    pub image_uri: String,              // 이미지가 없으면 빈 문자열 (온체인 SubmitContent 와 같다)
+    #[serde(default)]
+    pub image_variants: Vec<ImageVariant>, // 원본(image_uri)과 함께 올린 축소본. 이전 레코드는 없다.
+    pub author: String,                 // 올린 지갑
+    pub dao: String,
+    #[serde(default)]
+    pub storage: StorageKind,           // 이전 레코드는 모두 IPFS 였다
+    pub uploaded_at: u64,
+}
+
+// 업로드 이미지의 축소본 하나
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageVariant {
+    pub name: String,                   // "thumbnail" | "medium"
+    pub uri: String,
+    pub content_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+// 미디어 게이트웨이(/api/media/{id})가 내주지 않는 객체
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedMedia {
+    pub id: String,                     // IPFS CID 또는 Arweave 트랜잭션 ID
+    pub reason: Option<String>,
+    // 숨긴 콘텐츠 때문에 막혔으면 그 콘텐츠의 (dao, index). 숨김을 풀면 같이 풀린다.
+    pub hidden_content: Option<(String, u64)>,
+    pub blocked_by: String,
+    pub blocked_at: u64,
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+// 웹훅으로 받을 수 있는 DAO 이벤트
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Claim,                              // 타임아웃 처리로 보상이 분배됨
+    NewProposal,                        // 체인 동기화에서 새 제안이 보임
+    TimerExpiring,                      // 타이머 만료가 얼마 남지 않음
+    RoundClaimable,                     // 마감이 지나 타임아웃 처리를 기다림
+}
+
+// /api/webhooks 로 등록된 웹훅 (키는 id)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub dao: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub secret: String,                 // HMAC-SHA256 서명 키 (hex). 만들거나 바꿀 때만 응답에 보여 준다.
+    pub active: bool,
+    pub created_by: String,             // 등록한 지갑
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,                             // 재시도를 모두 쓰거나 웹훅이 지워지거나 꺼짐
+}
+
+// 웹훅 한 곳으로 보내는 이벤트 하나의 전송 기록
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: u64,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    pub payload: String,                // 보내는 본문 그대로 (서명 대상)
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: Option<u64>,
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    pub completed_at: Option<u64>,
+}
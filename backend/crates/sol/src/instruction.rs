@@ -0,0 +1,654 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+use crate::state::{DaoPreset, ExternalPlatform, ExternalRef, Pubkey, VoteType};
+
+// 온체인 프로그램(solana_program/src/lib.rs)의 TurtleInstruction 과 계정 순서를 그대로 옮긴 빌더.
+// 프런트엔드가 instruction 인코딩을 따로 구현하지 않도록 서버가 이걸로 트랜잭션을 만든다.
+
+// 11111111111111111111111111111111
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey([0; 32]);
+// ComputeBudget111111111111111111111111111111
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = Pubkey([
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231,
+    188, 140, 229, 187, 197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0,
+]);
+// TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey([
+    6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172,
+    28, 180, 133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+]);
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+const MAX_SEED_LEN: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TurtleInstruction {
+    InitializeDao {
+        dao_name: String,
+        time_limit: u64,
+        base_fee: u64,
+        ai_moderation: bool,
+        deposit_share: u8,
+        deposit_resets_timer: bool,         // 예치도 라운드 타이머를 다시 거는지
+    },
+    Deposit {
+        amount: u64,
+    },
+    SubmitContent {
+        text: String,
+        image_uri: String,
+    },
+    CreateVote {
+        title: String,
+        description: String,
+        vote_type: VoteType,
+        options: Vec<String>,
+        voting_period: u64,
+    },
+    CastVote {
+        proposal_id: u64,
+        option_index: u8,
+    },
+    ProcessTimeout {},
+    // 끝난 라운드의 RoundResult.quality_pool 을 작성자들에게 나눈다. 가중치는 합이 100 인 퍼센트.
+    DistributeQualityRewards {
+        creator_pubkeys: Vec<Pubkey>,
+        distribution_weights: Vec<u8>,
+    },
+    // 아래는 DAO 금고의 SPL 토큰 자산. 예치해도 투표권이나 라운드 상금에는 들어가지 않는다.
+    InitializeTreasuryAsset {},
+    DepositAsset {
+        amount: u64,
+    },
+    WithdrawAsset {
+        amount: u64,
+    },
+    // round 의 RoundResult 승자에게 한 번만 지급된다 (mint 마다 따로)
+    DistributeAssetReward {
+        round: u64,
+        amount: u64,
+    },
+    // 작성자가 철회 기간 안에 자기 글을 내린다. content 는 Content::key
+    RetractContent {
+        content: Pubkey,
+    },
+    // InitializeDao 과 같은 계정. 파라미터는 프리셋이 정한다
+    InitializeDaoWithPreset {
+        dao_name: String,
+        preset: DaoPreset,
+    },
+    // SubmitContent 에 외부 게시물 링크를 더한 것. 그 게시물의 ExternalClaim 이 만들어진다
+    SubmitLinkedContent {
+        text: String,
+        image_uri: String,
+        external_ref: ExternalRef,
+    },
+    // 이번 라운드 예치자가 남의 글에 표를 준다. content 는 Content::key, 글마다 한 번
+    VoteContent {
+        content: Pubkey,
+    },
+}
+
+// solana_sdk::compute_budget::ComputeBudgetInstruction 과 같은 borsh 인코딩 (variant 순서가 곧 태그다)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ComputeBudgetInstruction {
+    RequestUnitsDeprecated { units: u32, additional_fee: u32 },
+    RequestHeapFrame(u32),
+    SetComputeUnitLimit(u32),
+    SetComputeUnitPrice(u64),           // micro-lamports / compute unit
+    SetLoadedAccountsDataSizeLimit(u32),
+}
+
+impl ComputeBudgetInstruction {
+    pub fn unpack(data: &[u8]) -> std::io::Result<Self> {
+        borsh::from_slice(data)
+    }
+}
+
+impl TurtleInstruction {
+    // 프로그램의 TurtleInstruction::try_from_slice 와 같이 남는 바이트가 있으면 실패한다
+    pub fn unpack(data: &[u8]) -> std::io::Result<Self> {
+        borsh::from_slice(data)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub fn new(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self { pubkey, is_signer, is_writable: true }
+    }
+
+    pub fn new_readonly(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self { pubkey, is_signer, is_writable: false }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+// Pubkey::create_program_address 와 같은 규칙. 결과가 ed25519 곡선 위의 점이면 PDA 가 될 수 없다.
+pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<Pubkey> {
+    if seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(program_id.0);
+    hasher.update(PDA_MARKER);
+    let hash: [u8; 32] = hasher.finalize().into();
+
+    match CompressedEdwardsY(hash).decompress() {
+        Some(_) => None,
+        None => Some(Pubkey(hash)),
+    }
+}
+
+// Pubkey::find_program_address 와 같이 bump 를 255 부터 내려가며 찾는다
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+    (0..=u8::MAX).rev().find_map(|bump| {
+        let bump_seed = [bump];
+        let mut with_bump = seeds.to_vec();
+        with_bump.push(&bump_seed);
+        create_program_address(&with_bump, program_id).map(|address| (address, bump))
+    })
+}
+
+// process_initialize_dao 가 검증하는 DAO 계정 주소 (seeds = "dao", initializer, dao_name)
+pub fn dao_address(program_id: &Pubkey, initializer: &Pubkey, dao_name: &str) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"dao", &initializer.0, dao_name.as_bytes()], program_id)
+}
+
+// 라운드 상금 보관 계정으로 예약된 주소 (seeds = "vault", dao). solana_program/src/pda.rs 의 find_vault_address 와 같다.
+pub fn vault_address(program_id: &Pubkey, dao: &Pubkey) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"vault", &dao.0], program_id)
+}
+
+// 승자가 나온 라운드의 기록 계정 (seeds = "round", dao, round 의 little-endian). solana_program/src/pda.rs 의 find_round_address 와 같다.
+pub fn round_result_address(program_id: &Pubkey, dao: &Pubkey, round: u64) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"round", &dao.0, &round.to_le_bytes()], program_id)
+}
+
+// mint 하나당 금고 자산 기록 (seeds = "asset", dao, mint). solana_program/src/pda.rs 의 find_treasury_asset_address 와 같다.
+pub fn treasury_asset_address(program_id: &Pubkey, dao: &Pubkey, mint: &Pubkey) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"asset", &dao.0, &mint.0], program_id)
+}
+
+// 그 자산의 토큰을 실제로 들고 있는 토큰 계정 (seeds = "asset_vault", dao, mint). 소유자는 금고 자산 기록이다.
+pub fn asset_vault_address(program_id: &Pubkey, dao: &Pubkey, mint: &Pubkey) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"asset_vault", &dao.0, &mint.0], program_id)
+}
+
+// 작성자의 연속 제출 기록 (seeds = "streak", dao, author). solana_program/src/pda.rs 의 find_streak_address 와 같다.
+pub fn streak_address(program_id: &Pubkey, dao: &Pubkey, author: &Pubkey) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"streak", &dao.0, &author.0], program_id)
+}
+
+// 외부 게시물 하나의 ExternalClaim (seeds = "claim", dao, platform, sha256(id)). solana_program/src/pda.rs 의 find_claim_address 와 같다.
+pub fn claim_address(program_id: &Pubkey, dao: &Pubkey, platform: ExternalPlatform, id: &str) -> Option<(Pubkey, u8)> {
+    let id_hash: [u8; 32] = Sha256::digest(id.as_bytes()).into();
+    find_program_address(&[b"claim", &dao.0, &[platform as u8], &id_hash], program_id)
+}
+
+// 투표자가 그 글에 준 표의 기록 (seeds = "content_vote", dao, content, voter). solana_program/src/pda.rs 의 find_content_vote_address 와 같다.
+pub fn content_vote_address(program_id: &Pubkey, dao: &Pubkey, content: &Pubkey, voter: &Pubkey) -> Option<(Pubkey, u8)> {
+    find_program_address(&[b"content_vote", &dao.0, &content.0, &voter.0], program_id)
+}
+
+fn asset_accounts(program_id: &Pubkey, dao_account: &Pubkey, mint: &Pubkey) -> (Pubkey, Pubkey) {
+    let (asset, _) = treasury_asset_address(program_id, dao_account, mint).expect("asset seeds always have a bump");
+    let (vault, _) = asset_vault_address(program_id, dao_account, mint).expect("asset vault seeds always have a bump");
+    (asset, vault)
+}
+
+fn compute_budget(data: &ComputeBudgetInstruction) -> Instruction {
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data: borsh::to_vec(data).expect("instruction serialization is infallible"),
+    }
+}
+
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    compute_budget(&ComputeBudgetInstruction::SetComputeUnitLimit(units))
+}
+
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    compute_budget(&ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports))
+}
+
+fn instruction(program_id: &Pubkey, accounts: Vec<AccountMeta>, data: &TurtleInstruction) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(data).expect("instruction serialization is infallible"),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_dao(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    dao_account: &Pubkey,
+    dao_name: String,
+    time_limit: u64,
+    base_fee: u64,
+    ai_moderation: bool,
+    deposit_share: u8,
+    deposit_resets_timer: bool,
+) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::InitializeDao { dao_name, time_limit, base_fee, ai_moderation, deposit_share, deposit_resets_timer },
+    )
+}
+
+pub fn initialize_dao_with_preset(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    dao_account: &Pubkey,
+    dao_name: String,
+    preset: DaoPreset,
+) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::InitializeDaoWithPreset { dao_name, preset },
+    )
+}
+
+pub fn deposit(program_id: &Pubkey, depositor: &Pubkey, dao_account: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*depositor, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::Deposit { amount },
+    )
+}
+
+// 작성자가 제출 수수료 (base_fee 에서 예치 할인을 뺀 lamports) 를 내므로 writable 이고 시스템 프로그램이 붙는다
+pub fn submit_content(program_id: &Pubkey, author: &Pubkey, dao_account: &Pubkey, text: String, image_uri: String) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*author, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::SubmitContent { text, image_uri },
+    )
+}
+
+// submit_content 에 작성자의 연속 제출 기록 계정을 붙인 것. 처음 한 번은 작성자가 그 계정의 rent 를 낸다.
+pub fn submit_content_with_streak(program_id: &Pubkey, author: &Pubkey, dao_account: &Pubkey, text: String, image_uri: String) -> Instruction {
+    let (streak, _) = streak_address(program_id, dao_account, author).expect("streak seeds always have a bump");
+    let mut ix = submit_content(program_id, author, dao_account, text, image_uri);
+    ix.accounts.push(AccountMeta::new(streak, false));
+    ix
+}
+
+// 작성자가 수수료와 claim 계정의 rent 를 낸다. 이미 누가 가져간 게시물이면 ExternalRefClaimed 로 실패한다
+pub fn submit_linked_content(
+    program_id: &Pubkey,
+    author: &Pubkey,
+    dao_account: &Pubkey,
+    text: String,
+    image_uri: String,
+    external_ref: ExternalRef,
+) -> Instruction {
+    let (claim, _) = claim_address(program_id, dao_account, external_ref.platform, &external_ref.id)
+        .expect("claim seeds always have a bump");
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*author, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new(claim, false),
+        ],
+        &TurtleInstruction::SubmitLinkedContent { text, image_uri, external_ref },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_vote(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    dao_account: &Pubkey,
+    title: String,
+    description: String,
+    vote_type: VoteType,
+    options: Vec<String>,
+    voting_period: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new_readonly(*proposer, true),
+            AccountMeta::new(*dao_account, false),
+        ],
+        &TurtleInstruction::CreateVote { title, description, vote_type, options, voting_period },
+    )
+}
+
+pub fn cast_vote(program_id: &Pubkey, voter: &Pubkey, dao_account: &Pubkey, proposal_id: u64, option_index: u8) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new_readonly(*voter, true),
+            AccountMeta::new(*dao_account, false),
+        ],
+        &TurtleInstruction::CastVote { proposal_id, option_index },
+    )
+}
+
+// round 는 지금 DaoState.round. 승자가 있으면 호출자가 rent 를 내고 그 번호의 RoundResult 계정이 만들어진다.
+// last_submitter 는 DaoState.last_submitter 로, 상금을 받는 계정이다. 아무도 제출하지 않았으면 (기본 주소) 빼고 보낸다.
+pub fn process_timeout(program_id: &Pubkey, caller: &Pubkey, dao_account: &Pubkey, round: u64, last_submitter: &Pubkey) -> Instruction {
+    let (round_result, _) = round_result_address(program_id, dao_account, round)
+        .expect("round seeds always have a bump");
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(*dao_account, false),
+        AccountMeta::new(round_result, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    if *last_submitter != Pubkey::default() {
+        accounts.push(AccountMeta::new(*last_submitter, false));
+    }
+    instruction(program_id, accounts, &TurtleInstruction::ProcessTimeout {})
+}
+
+// 관리자, DAO, round 의 RoundResult, 그다음 받는 작성자들 순서 (solana_program/src/instruction.rs 의 distribute_quality_rewards).
+// 라운드마다 한 번만 나눌 수 있고 두 번째는 RoundAlreadyPaid 로 실패한다.
+pub fn distribute_quality_rewards(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    dao_account: &Pubkey,
+    round: u64,
+    creator_pubkeys: Vec<Pubkey>,
+    distribution_weights: Vec<u8>,
+) -> Instruction {
+    let (round_result, _) = round_result_address(program_id, dao_account, round)
+        .expect("round seeds always have a bump");
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(*dao_account, false),
+        AccountMeta::new(round_result, false),
+    ];
+    accounts.extend(creator_pubkeys.iter().map(|creator| AccountMeta::new(*creator, false)));
+    instruction(
+        program_id,
+        accounts,
+        &TurtleInstruction::DistributeQualityRewards { creator_pubkeys, distribution_weights },
+    )
+}
+
+// 관리자만 부를 수 있고 관리자가 두 계정의 rent 를 낸다
+pub fn initialize_treasury_asset(program_id: &Pubkey, admin: &Pubkey, dao_account: &Pubkey, mint: &Pubkey) -> Instruction {
+    let (asset, vault) = asset_accounts(program_id, dao_account, mint);
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(*dao_account, false),
+            AccountMeta::new(asset, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::InitializeTreasuryAsset {},
+    )
+}
+
+// source 는 depositor 가 소유한 같은 mint 의 토큰 계정
+pub fn deposit_asset(program_id: &Pubkey, depositor: &Pubkey, dao_account: &Pubkey, mint: &Pubkey, source: &Pubkey, amount: u64) -> Instruction {
+    let (asset, vault) = asset_accounts(program_id, dao_account, mint);
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new_readonly(*depositor, true),
+            AccountMeta::new_readonly(*dao_account, false),
+            AccountMeta::new(asset, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::DepositAsset { amount },
+    )
+}
+
+pub fn withdraw_asset(program_id: &Pubkey, admin: &Pubkey, dao_account: &Pubkey, mint: &Pubkey, destination: &Pubkey, amount: u64) -> Instruction {
+    let (asset, vault) = asset_accounts(program_id, dao_account, mint);
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(*dao_account, false),
+            AccountMeta::new(asset, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::WithdrawAsset { amount },
+    )
+}
+
+// winner_token_account 는 RoundResult.winner 가 소유한 같은 mint 의 토큰 계정이어야 한다
+#[allow(clippy::too_many_arguments)]
+pub fn distribute_asset_reward(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    dao_account: &Pubkey,
+    mint: &Pubkey,
+    round: u64,
+    winner_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (asset, vault) = asset_accounts(program_id, dao_account, mint);
+    let (round_result, _) = round_result_address(program_id, dao_account, round)
+        .expect("round seeds always have a bump");
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(*dao_account, false),
+            AccountMeta::new_readonly(round_result, false),
+            AccountMeta::new(asset, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(*winner_token_account, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::DistributeAssetReward { round, amount },
+    )
+}
+
+// 환불은 작성자 계정으로 들어오므로 작성자도 writable
+pub fn retract_content(program_id: &Pubkey, author: &Pubkey, dao_account: &Pubkey, content: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*author, true),
+            AccountMeta::new(*dao_account, false),
+        ],
+        &TurtleInstruction::RetractContent { content: *content },
+    )
+}
+
+// 링크된 글을 철회하면서 그 ExternalClaim 도 닫는다. claim 의 rent 는 작성자에게 돌아간다
+pub fn retract_linked_content(
+    program_id: &Pubkey,
+    author: &Pubkey,
+    dao_account: &Pubkey,
+    content: &Pubkey,
+    external_ref: &ExternalRef,
+) -> Instruction {
+    let (claim, _) = claim_address(program_id, dao_account, external_ref.platform, &external_ref.id)
+        .expect("claim seeds always have a bump");
+    let mut ix = retract_content(program_id, author, dao_account, content);
+    ix.accounts.push(AccountMeta::new(claim, false));
+    ix
+}
+
+// 투표자가 표 기록 계정의 rent 를 낸다. 같은 글에 두 번 주면 ContentAlreadyVoted 로 실패한다
+pub fn vote_content(program_id: &Pubkey, voter: &Pubkey, dao_account: &Pubkey, content: &Pubkey) -> Instruction {
+    let (content_vote, _) = content_vote_address(program_id, dao_account, content, voter)
+        .expect("content vote seeds always have a bump");
+    instruction(
+        program_id,
+        vec![
+            AccountMeta::new(*voter, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new(content_vote, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        &TurtleInstruction::VoteContent { content: *content },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dao_address_matches_program() {
+        // solana-program 1.16 의 Pubkey::find_program_address 로 구한 값
+        let (address, bump) = dao_address(&Pubkey([3; 32]), &Pubkey([1; 32]), "turtles").unwrap();
+        assert_eq!(address.to_string(), "DY3qS2728PedjRB4m8jX4M6tJBse7zB82QhTfF1k4GPM");
+        assert_eq!(bump, 255);
+    }
+
+    #[test]
+    fn test_instruction_data_layout() {
+        let ix = deposit(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), 5_000);
+        assert_eq!(ix.data, vec![1, 0x88, 0x13, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ix.accounts[2], AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false));
+
+        let ix = process_timeout(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), 4, &Pubkey([7; 32]));
+        assert_eq!(ix.data, vec![5]);
+        assert_eq!(ix.accounts[2].pubkey, round_result_address(&Pubkey([3; 32]), &Pubkey([2; 32]), 4).unwrap().0);
+        assert_eq!(ix.accounts[4], AccountMeta::new(Pubkey([7; 32]), false));
+        assert_eq!(process_timeout(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), 4, &Pubkey::default()).accounts.len(), 4);
+        assert_eq!(TurtleInstruction::unpack(&ix.data).unwrap(), TurtleInstruction::ProcessTimeout {});
+        assert!(TurtleInstruction::unpack(&[5, 0]).is_err());
+
+        let ix = distribute_quality_rewards(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), 4, vec![Pubkey([4; 32])], vec![100]);
+        assert_eq!(&ix.data[..5], &[6, 1, 0, 0, 0]);
+        assert_eq!(&ix.data[37..], &[1, 0, 0, 0, 100]);
+        assert_eq!(ix.accounts[2], AccountMeta::new(round_result_address(&Pubkey([3; 32]), &Pubkey([2; 32]), 4).unwrap().0, false));
+        assert_eq!(ix.accounts[3], AccountMeta::new(Pubkey([4; 32]), false));
+
+        let ix = vote_content(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), &Pubkey([4; 32]));
+        assert_eq!(ix.accounts[2].pubkey, content_vote_address(&Pubkey([3; 32]), &Pubkey([2; 32]), &Pubkey([4; 32]), &Pubkey([1; 32])).unwrap().0);
+
+        let ix = distribute_asset_reward(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), &Pubkey([5; 32]), 4, &Pubkey([6; 32]), 500);
+        assert_eq!(ix.accounts[2], AccountMeta::new_readonly(round_result_address(&Pubkey([3; 32]), &Pubkey([2; 32]), 4).unwrap().0, false));
+        assert_eq!(ix.accounts[3].pubkey, treasury_asset_address(&Pubkey([3; 32]), &Pubkey([2; 32]), &Pubkey([5; 32])).unwrap().0);
+        assert_eq!(ix.accounts[4].pubkey, asset_vault_address(&Pubkey([3; 32]), &Pubkey([2; 32]), &Pubkey([5; 32])).unwrap().0);
+        assert_eq!(ix.accounts[6], AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false));
+        assert_eq!(TOKEN_PROGRAM_ID.to_string(), "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+        let ix = submit_content_with_streak(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), "hi".to_string(), String::new());
+        assert_eq!(ix.data, submit_content(&Pubkey([3; 32]), &Pubkey([1; 32]), &Pubkey([2; 32]), "hi".to_string(), String::new()).data);
+        assert_eq!(ix.accounts[3], AccountMeta::new(streak_address(&Pubkey([3; 32]), &Pubkey([2; 32]), &Pubkey([1; 32])).unwrap().0, false));
+
+        // solana-sdk 의 ComputeBudgetInstruction::set_compute_unit_price(5_000) 과 같은 바이트
+        let ix = set_compute_unit_price(5_000);
+        assert_eq!(ix.data, vec![3, 0x88, 0x13, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(COMPUTE_BUDGET_PROGRAM_ID.to_string(), "ComputeBudget111111111111111111111111111111");
+        assert_eq!(set_compute_unit_limit(200_000).data, vec![2, 0x40, 0x0d, 0x03, 0]);
+    }
+
+    // solana_program/src/lib.rs 의 test_instruction_layouts 와 같은 바이트. 한쪽만 바뀌면 여기서 깨진다.
+    #[test]
+    fn test_instruction_layouts_match_program() {
+        let distribute = [&[6u8, 1, 0, 0, 0][..], &[4; 32][..], &[1, 0, 0, 0, 100][..]].concat();
+        let retract = [&[11u8][..], &[1; 32][..]].concat();
+        let vote_content = [&[14u8][..], &[1; 32][..]].concat();
+        let cases: [(TurtleInstruction, &[u8]); 15] = [
+            (
+                TurtleInstruction::InitializeDao {
+                    dao_name: "dao".to_string(),
+                    time_limit: 3_600,
+                    base_fee: 10,
+                    ai_moderation: true,
+                    deposit_share: 20,
+                    deposit_resets_timer: true,
+                },
+                &[0, 3, 0, 0, 0, b'd', b'a', b'o', 0x10, 0x0e, 0, 0, 0, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 1, 20, 1],
+            ),
+            (TurtleInstruction::Deposit { amount: 1_000_000_000 }, &[1, 0x00, 0xca, 0x9a, 0x3b, 0, 0, 0, 0]),
+            (
+                TurtleInstruction::SubmitContent { text: "hi".to_string(), image_uri: "u".to_string() },
+                &[2, 2, 0, 0, 0, b'h', b'i', 1, 0, 0, 0, b'u'],
+            ),
+            (
+                TurtleInstruction::CreateVote {
+                    title: "t".to_string(),
+                    description: String::new(),
+                    vote_type: VoteType::ChangeBaseFee,
+                    options: vec!["a".to_string(), "b".to_string()],
+                    voting_period: 604_800,
+                },
+                &[3, 1, 0, 0, 0, b't', 0, 0, 0, 0, 1, 2, 0, 0, 0, 1, 0, 0, 0, b'a', 1, 0, 0, 0, b'b', 0x80, 0x3a, 0x09, 0, 0, 0, 0, 0],
+            ),
+            (TurtleInstruction::CastVote { proposal_id: 2, option_index: 1 }, &[4, 2, 0, 0, 0, 0, 0, 0, 0, 1]),
+            (TurtleInstruction::ProcessTimeout {}, &[5]),
+            (
+                TurtleInstruction::DistributeQualityRewards { creator_pubkeys: vec![Pubkey([4; 32])], distribution_weights: vec![100] },
+                &distribute,
+            ),
+            (TurtleInstruction::InitializeTreasuryAsset {}, &[7]),
+            (TurtleInstruction::DepositAsset { amount: 500 }, &[8, 0xf4, 0x01, 0, 0, 0, 0, 0, 0]),
+            (TurtleInstruction::WithdrawAsset { amount: 500 }, &[9, 0xf4, 0x01, 0, 0, 0, 0, 0, 0]),
+            (
+                TurtleInstruction::DistributeAssetReward { round: 2, amount: 500 },
+                &[10, 2, 0, 0, 0, 0, 0, 0, 0, 0xf4, 0x01, 0, 0, 0, 0, 0, 0],
+            ),
+            (TurtleInstruction::RetractContent { content: Pubkey([1; 32]) }, &retract),
+            (
+                TurtleInstruction::InitializeDaoWithPreset { dao_name: "dao".to_string(), preset: DaoPreset::ModeratedShowcase },
+                &[12, 3, 0, 0, 0, b'd', b'a', b'o', 2],
+            ),
+            (
+                TurtleInstruction::SubmitLinkedContent {
+                    text: "hi".to_string(),
+                    image_uri: String::new(),
+                    external_ref: ExternalRef { platform: ExternalPlatform::Farcaster, id: "0xab".to_string() },
+                },
+                &[13, 2, 0, 0, 0, b'h', b'i', 0, 0, 0, 0, 1, 4, 0, 0, 0, b'0', b'x', b'a', b'b'],
+            ),
+            (TurtleInstruction::VoteContent { content: Pubkey([1; 32]) }, &vote_content),
+        ];
+        for (instruction, bytes) in cases {
+            assert_eq!(borsh::to_vec(&instruction).unwrap(), bytes, "{:?}", instruction);
+            assert_eq!(TurtleInstruction::unpack(bytes).unwrap(), instruction);
+        }
+    }
+}
@@ -1,3 +1,8 @@
+pub mod state;
+pub mod instruction;
+pub mod transaction;
+pub mod lookup_table;
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -0,0 +1,149 @@
+use crate::instruction::{find_program_address, vault_address, AccountMeta, Instruction, SYSTEM_PROGRAM_ID};
+use crate::state::Pubkey;
+use crate::transaction::DecodeError;
+
+// 주소 조회 테이블(Address Lookup Table) 프로그램. v0 트랜잭션이 계정 32바이트 대신 1바이트 인덱스로 계정을 가리키게 한다.
+// 테이블을 만들거나 늘린 직후에는 쓸 수 없고 다음 slot 부터 조회된다.
+
+// AddressLookupTab1e1111111111111111111111111
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey = Pubkey([
+    2, 119, 166, 175, 151, 51, 155, 122, 200, 141, 24, 146, 201, 4, 70, 245,
+    0, 2, 48, 146, 102, 246, 46, 83, 193, 24, 36, 73, 130, 0, 0, 0,
+]);
+// TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey([
+    6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172,
+    28, 180, 133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+]);
+
+// 테이블 계정 앞부분 (상태 태그 u32, 비활성화 slot, 마지막 확장 slot, 그 시작 인덱스, authority, 패딩)
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+// 인덱스가 u8 이라 테이블 하나에 256 개까지 담긴다
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+const LOOKUP_TABLE_STATE_TAG: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressLookupTable {
+    pub key: Pubkey,
+    pub addresses: Vec<Pubkey>,
+}
+
+impl AddressLookupTable {
+    // getAccountInfo 로 받은 테이블 계정 데이터를 읽는다
+    pub fn from_account_data(key: Pubkey, data: &[u8]) -> Result<Self, DecodeError> {
+        if data.len() < LOOKUP_TABLE_META_SIZE || u32::from_le_bytes(data[..4].try_into().unwrap()) != LOOKUP_TABLE_STATE_TAG {
+            return Err(DecodeError("not an address lookup table".to_string()));
+        }
+        let addresses = &data[LOOKUP_TABLE_META_SIZE..];
+        if !addresses.len().is_multiple_of(32) {
+            return Err(DecodeError("lookup table addresses are not 32-byte aligned".to_string()));
+        }
+        let addresses = addresses.chunks_exact(32).map(|chunk| Pubkey(chunk.try_into().unwrap())).collect();
+        Ok(AddressLookupTable { key, addresses })
+    }
+
+    pub fn index_of(&self, key: &Pubkey) -> Option<u8> {
+        self.addresses.iter().position(|address| address == key).and_then(|index| u8::try_from(index).ok())
+    }
+}
+
+// DAO 트랜잭션마다 들어가는 계정들. 보상 분배처럼 계정이 많은 트랜잭션을 v0 로 줄일 때 테이블에 넣는다.
+// 프로그램 자신과 compute budget 은 program_id 로 불리므로 테이블로 옮길 수 없어 넣지 않는다.
+pub fn frequent_accounts(program_id: &Pubkey, dao: &Pubkey) -> Vec<Pubkey> {
+    let mut accounts = vec![*dao];
+    accounts.extend(vault_address(program_id, dao).map(|(vault, _)| vault));
+    accounts.extend([SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID]);
+    accounts
+}
+
+// seeds = authority, recent_slot (u64 little-endian)
+pub fn lookup_table_address(authority: &Pubkey, recent_slot: u64) -> Option<(Pubkey, u8)> {
+    find_program_address(&[&authority.0, &recent_slot.to_le_bytes()], &ADDRESS_LOOKUP_TABLE_PROGRAM_ID)
+}
+
+// 테이블 프로그램 명령은 bincode (u32 태그) 로 인코딩한다
+fn lookup_table_instruction(accounts: Vec<AccountMeta>, tag: u32, payload: &[u8]) -> Instruction {
+    let mut data = tag.to_le_bytes().to_vec();
+    data.extend_from_slice(payload);
+    Instruction { program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID, accounts, data }
+}
+
+// recent_slot 은 최근 slot 이어야 한다 (프로그램이 slot hashes 로 확인한다). authority 는 서명하지 않아도 된다.
+pub fn create_lookup_table(authority: &Pubkey, payer: &Pubkey, recent_slot: u64) -> Option<(Instruction, Pubkey)> {
+    let (table, bump) = lookup_table_address(authority, recent_slot)?;
+    let mut payload = recent_slot.to_le_bytes().to_vec();
+    payload.push(bump);
+    let instruction = lookup_table_instruction(
+        vec![
+            AccountMeta::new(table, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        0,
+        &payload,
+    );
+    Some((instruction, table))
+}
+
+pub fn extend_lookup_table(table: &Pubkey, authority: &Pubkey, payer: &Pubkey, new_addresses: &[Pubkey]) -> Instruction {
+    let mut payload = (new_addresses.len() as u64).to_le_bytes().to_vec();
+    for address in new_addresses {
+        payload.extend_from_slice(&address.0);
+    }
+    lookup_table_instruction(
+        vec![
+            AccountMeta::new(*table, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        2,
+        &payload,
+    )
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_ids() {
+        assert_eq!(ADDRESS_LOOKUP_TABLE_PROGRAM_ID.to_string(), "AddressLookupTab1e1111111111111111111111111");
+        assert_eq!(TOKEN_PROGRAM_ID.to_string(), "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+    }
+
+    #[test]
+    fn test_from_account_data() {
+        let mut data = vec![0u8; LOOKUP_TABLE_META_SIZE];
+        data[0] = 1;
+        data.extend_from_slice(&[5; 32]);
+        data.extend_from_slice(&[6; 32]);
+
+        let table = AddressLookupTable::from_account_data(Pubkey([9; 32]), &data).unwrap();
+        assert_eq!(table.addresses, vec![Pubkey([5; 32]), Pubkey([6; 32])]);
+        assert_eq!(table.index_of(&Pubkey([6; 32])), Some(1));
+        assert_eq!(table.index_of(&Pubkey([7; 32])), None);
+
+        data[0] = 0;
+        assert!(AddressLookupTable::from_account_data(Pubkey([9; 32]), &data).is_err());
+        data[0] = 1;
+        data.push(0);
+        assert!(AddressLookupTable::from_account_data(Pubkey([9; 32]), &data).is_err());
+    }
+
+    #[test]
+    fn test_create_and_extend_encoding() {
+        let authority = Pubkey([1; 32]);
+        let (create, table) = create_lookup_table(&authority, &authority, 300).unwrap();
+        let (_, bump) = lookup_table_address(&authority, 300).unwrap();
+        assert_eq!(create.accounts[0].pubkey, table);
+        assert_eq!(create.data, [&[0, 0, 0, 0][..], &300u64.to_le_bytes(), &[bump]].concat());
+
+        let extend = extend_lookup_table(&table, &authority, &authority, &[Pubkey([4; 32])]);
+        assert_eq!(&extend.data[..12], &[2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&extend.data[12..], &[4; 32]);
+        assert!(extend.accounts[1].is_signer);
+    }
+}
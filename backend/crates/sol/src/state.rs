@@ -0,0 +1,596 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+// 온체인 프로그램(solana_program/src/lib.rs) 계정 레이아웃의 백엔드 쪽 사본.
+// 프로그램 크레이트는 solana-program 1.16 에 묶여 있어서 서버 워크스페이스에 그대로 넣을 수 없으므로
+// 필드 순서와 타입을 똑같이 맞춘 Borsh 타입을 여기 둔다. 프로그램 쪽 구조체를 바꾸면 이 파일도 같이 바꿔야 한다.
+
+pub const PUBKEY_BYTES: usize = 32;
+
+// solana_program::pubkey::Pubkey 와 같은 32바이트 인코딩
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pubkey(pub [u8; PUBKEY_BYTES]);
+
+impl Pubkey {
+    pub fn to_bytes(self) -> [u8; PUBKEY_BYTES] {
+        self.0
+    }
+}
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", bs58::encode(self.0).into_string())
+    }
+}
+
+impl fmt::Debug for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePubkeyError(pub String);
+
+impl fmt::Display for ParsePubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pubkey: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParsePubkeyError {}
+
+impl FromStr for Pubkey {
+    type Err = ParsePubkeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = bs58::decode(s)
+            .into_vec()
+            .map_err(|_| ParsePubkeyError(s.to_string()))?;
+        let bytes: [u8; PUBKEY_BYTES] = bytes
+            .try_into()
+            .map_err(|_| ParsePubkeyError(s.to_string()))?;
+        Ok(Pubkey(bytes))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum VoteType {
+    ChangeTimeLimit,
+    ChangeBaseFee,
+    ChangeAiModeration,
+    ContentQualityRating,
+    ChangeDepositResetsTimer,
+    ChangeFeeTiers,
+    ReplaceAdmin,
+    ChangeDepositCaps,
+    ChangeStreakBonus,
+    ChangeRetractPolicy,
+    ChangeRoundMinimum,
+}
+
+// InitializeDaoWithPreset 의 프리셋. 실제 파라미터는 프로그램의 preset::DAO_PRESETS 에 있다
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaoPreset {
+    FastGame,
+    LongForm,
+    ModeratedShowcase,
+}
+
+// 글이 가리키는 외부 게시물의 플랫폼
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalPlatform {
+    Twitter,
+    Farcaster,
+}
+
+// 트윗 id 또는 Farcaster cast hash. id 는 최대 64 바이트
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExternalRef {
+    pub platform: ExternalPlatform,
+    pub id: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum VoteStatus {
+    Active,
+    Completed,
+    Executed,
+}
+
+// 라운드가 카운트다운 중인지. 새 DAO 는 Dormant 로 시작하고 상금이 min_round_pot 에 닿는 예치가 라운드를 연다.
+// 필드가 생기기 전 계정은 0 (Active) 으로 읽힌다
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundState {
+    Active,
+    Dormant,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DepositorInfo {
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub timestamp: u64,
+    pub locked_until: u64,
+}
+
+// 프로그램의 BoundedText / BoundedUri 한도 (바이트)
+pub const MAX_TEXT_LEN: usize = 1000;
+pub const MAX_IMAGE_URI_LEN: usize = 200;
+
+#[derive(BorshSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct Content {
+    pub author: Pubkey,
+    pub text: String,
+    pub image_uri: String,
+    pub timestamp: u64,
+    pub vote_count: u64,
+}
+
+// 한도가 생기기 전 글은 더 길 수 있다. 프로그램처럼 한도 안의 글자 경계에서 잘라 읽는다
+impl BorshDeserialize for Content {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            author: Pubkey::deserialize_reader(reader)?,
+            text: cut_to(String::deserialize_reader(reader)?, MAX_TEXT_LEN),
+            image_uri: cut_to(String::deserialize_reader(reader)?, MAX_IMAGE_URI_LEN),
+            timestamp: u64::deserialize_reader(reader)?,
+            vote_count: u64::deserialize_reader(reader)?,
+        })
+    }
+}
+
+fn cut_to(mut value: String, max: usize) -> String {
+    if value.len() > max {
+        let end = (0..=max).rev().find(|&end| value.is_char_boundary(end)).unwrap_or(0);
+        value.truncate(end);
+    }
+    value
+}
+
+impl Content {
+    // 프로그램의 content_key: sha256(author || text || image_uri || timestamp). RetractContent 가 글을 이 키로 찾는다
+    pub fn key(&self) -> Pubkey {
+        let mut hasher = Sha256::new();
+        hasher.update(self.author.0);
+        hasher.update(self.text.as_bytes());
+        hasher.update(self.image_uri.as_bytes());
+        hasher.update(self.timestamp.to_le_bytes());
+        Pubkey(hasher.finalize().into())
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VoteInfo {
+    pub voter: Pubkey,
+    pub option_index: u8,
+    pub voting_power: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VoteProposal {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub vote_type: VoteType,
+    pub options: Vec<String>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub votes: Vec<VoteInfo>,
+    pub status: VoteStatus,
+}
+
+// min_deposit 이상 예치한 작성자는 제출 수수료를 discount% 덜 낸다
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeeTier {
+    pub min_deposit: u64,
+    pub discount: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DaoState {
+    pub is_initialized: bool,
+    pub dao_name: String,
+    pub initializer: Pubkey,
+    pub time_limit: u64,
+    pub base_fee: u64,
+    pub ai_moderation: bool,
+    pub deposit_share: u8,
+    pub timeout_timestamp: u64,
+    pub total_deposit: u64,
+    pub depositors: Vec<DepositorInfo>,
+    pub contents: Vec<Content>,
+    pub vote_proposals: Vec<VoteProposal>,
+    pub next_proposal_id: u64,
+    // 계정 lamports 장부. lamports == rent_reserve + bounty_pool + fee_pool.
+    // 필드가 생기기 전 계정은 0 으로 읽히고, 프로그램이 다음 명령에서 채운다.
+    pub rent_reserve: u64,
+    pub bounty_pool: u64,
+    pub fee_pool: u64,                  // 제출 수수료 등. 우승자가 있는 라운드가 끝나면 팟에 합쳐진다
+    // 이번 라운드 마지막 제출. 타임아웃 때 이 작성자가 상금을 받는다. 제출이 없으면 0.
+    pub last_submitter: Pubkey,
+    pub last_content: Pubkey,           // 프로그램의 content_key (작성자, 본문, 이미지, 시각의 sha256)
+    pub deposit_resets_timer: bool,     // 예치도 타이머를 다시 거는 규칙. 필드가 생기기 전 계정은 false
+    pub fee_tiers: Vec<FeeTier>,        // 제출 수수료 할인 구간. 비어 있으면 모두 base_fee 를 낸다
+    pub round: u64,                     // 승자가 나온 라운드 수. 다음 RoundResult 가 이 번호로 만들어진다
+    pub max_deposit_per_wallet: u64,    // 지갑 하나가 한 라운드에 예치할 수 있는 한도. 0 이면 없다
+    pub max_total_deposit: u64,         // 한 라운드 total_deposit 의 한도. 0 이면 없다
+    pub streak_days: u64,               // 이 일수만큼 연속 제출하면 수수료 할인. 0 이면 보너스가 없다
+    pub streak_discount: u8,            // 연속 제출 할인 (퍼센트)
+    pub retract_window: u64,            // 제출 후 이 초 안에는 작성자가 글을 철회할 수 있다. 0 이면 철회 불가
+    pub retract_refund: u8,             // 철회할 때 돌려받는 수수료 (퍼센트)
+    pub round_state: RoundState,        // Dormant 면 마감이 없고 제출이 거절된다
+    pub min_round_pot: u64,             // 라운드를 여는 데 필요한 상금 (lamports). 0 이면 아무 예치나 연다
+}
+
+// 승자가 나온 라운드마다 타임아웃이 남기는 기록 (PDA seeds = "round", dao, round). 다음 라운드가 글을 지워도 남는다.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RoundResult {
+    pub dao: Pubkey,
+    pub round: u64,
+    pub winner: Pubkey,
+    pub reward: u64,                    // 승자 몫 (상금에서 품질 보상 몫을 뺀 것. 아무도 투표하지 않았으면 상금 전부)
+    pub pot: u64,                       // 그 라운드의 total_deposit
+    pub content: Pubkey,                // 이긴 글의 content_key
+    pub submitted_at: u64,
+    pub closed_at: u64,
+    pub quality_pool: u64,              // 상금 풀에 남은 품질 보상 몫. DistributeQualityRewards 가 나누면 0
+    pub candidates: Vec<QualityCandidate>, // 품질 보상을 받을 수 있는 글. 이 작성자들에게만 나눌 수 있다
+}
+
+// 라운드가 끝날 때 표를 받은 글. 표가 많은 순서로 (같으면 먼저 올라온 글) MAX_QUALITY_CANDIDATES 개까지 남는다
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QualityCandidate {
+    pub author: Pubkey,
+    pub content: Pubkey,                // 글의 content_key
+    pub votes: u64,                     // 라운드가 끝날 때의 vote_count
+}
+
+pub const MAX_QUALITY_CANDIDATES: usize = 10;
+
+impl RoundResult {
+    // 후보가 가장 많을 때의 크기로 할당된다. 후보가 적으면 뒤가 0 이다
+    pub const LEN: usize = 144 + 4 + MAX_QUALITY_CANDIDATES * 72;
+
+    // quality_pool 과 candidates 가 생기기 전 계정은 짧아서 0 (빈 목록) 으로 읽는다 (프로그램의 RoundResult::unpack)
+    pub fn from_account_data(data: &[u8]) -> io::Result<Self> {
+        let mut padded = data.to_vec();
+        padded.resize(padded.len().max(Self::LEN), 0);
+        let mut slice = padded.as_slice();
+        Self::deserialize(&mut slice)
+    }
+}
+
+// DAO 금고에 등록된 SPL mint 하나 (PDA seeds = "asset", dao, mint). 토큰은 vault 토큰 계정에 있다.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TreasuryAsset {
+    pub dao: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub balance: u64,                   // 프로그램이 센 잔액 (vault 로 직접 보낸 토큰은 빠진다)
+    pub next_round: u64,                // 아직 보상을 받을 수 있는 가장 이른 라운드
+}
+
+impl TreasuryAsset {
+    pub const LEN: usize = 112;
+}
+
+// 작성자가 DAO 에 며칠 연속 글을 올렸는지 (PDA seeds = "streak", dao, author). 날짜는 UTC 기준 timestamp / 86400.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionStreak {
+    pub dao: Pubkey,
+    pub author: Pubkey,
+    pub last_day: u64,                  // 마지막으로 제출한 날
+    pub days: u64,                      // last_day 까지 이어진 연속 일수
+}
+
+impl SubmissionStreak {
+    pub const LEN: usize = 80;
+}
+
+// 외부 게시물 하나를 어느 글이 가져갔는지 (PDA seeds = "claim", dao, platform, sha256(id)).
+// 라운드가 끝나도 남아서 같은 게시물은 DAO 에 한 번만 올라간다. 글을 철회하면 닫힌다.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExternalClaim {
+    pub dao: Pubkey,
+    pub author: Pubkey,
+    pub content: Pubkey,                // 글의 content_key
+    pub claimed_at: u64,
+    pub external_ref: ExternalRef,
+}
+
+impl ExternalClaim {
+    // 가장 긴 id 기준으로 할당된다. 짧은 id 면 뒤가 0 이다
+    pub const LEN: usize = 104 + 1 + 4 + 64;
+
+    pub fn from_account_data(data: &[u8]) -> io::Result<Self> {
+        let mut slice = data;
+        Self::deserialize(&mut slice)
+    }
+}
+
+impl DaoState {
+    // 계정 데이터는 최대 크기로 미리 할당되어 있어서 뒤쪽이 0 으로 채워져 있다.
+    // 프로그램의 try_from_slice_unchecked 처럼 남는 바이트는 무시한다.
+    pub fn from_account_data(data: &[u8]) -> io::Result<Self> {
+        let mut slice = data;
+        let mut state = Self::deserialize(&mut slice)?;
+        state.rekey_cut_submission();
+        Ok(state)
+    }
+
+    // 잘린 글은 content_key 가 바뀐다. 마지막 제출이 잘렸으면 프로그램의 rekey_cut_submission 처럼 새 키를 가리킨다
+    fn rekey_cut_submission(&mut self) {
+        if self.last_submitter == Pubkey::default() || self.contents.iter().any(|content| content.author == self.last_submitter && content.key() == self.last_content) {
+            return;
+        }
+        if let Some(last) = self.contents.last().filter(|content| content.author == self.last_submitter) {
+            self.last_content = last.key();
+        }
+    }
+
+    // 마지막 활동(예치/제출) 시각. 프로그램은 활동마다 timeout_timestamp 를 now + time_limit 으로 미룬다.
+    pub fn last_activity_timestamp(&self) -> u64 {
+        self.timeout_timestamp.saturating_sub(self.time_limit)
+    }
+
+    // 예치를 기다리는 중이라 timeout_timestamp 가 마감이 아니다 (타임아웃을 보낼 필요가 없다)
+    pub fn is_dormant(&self) -> bool {
+        self.round_state == RoundState::Dormant
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_state() -> DaoState {
+        let mut state = DaoState {
+            is_initialized: true,
+            dao_name: "turtles".to_string(),
+            initializer: Pubkey([7; 32]),
+            time_limit: 3_600,
+            base_fee: 1_000,
+            ai_moderation: true,
+            deposit_share: 20,
+            timeout_timestamp: 10_000,
+            total_deposit: 5_000,
+            depositors: vec![DepositorInfo {
+                depositor: Pubkey([1; 32]),
+                amount: 5_000,
+                timestamp: 6_000,
+                locked_until: 7_000,
+            }],
+            contents: vec![Content {
+                author: Pubkey([2; 32]),
+                text: "hello".to_string(),
+                image_uri: String::new(),
+                timestamp: 6_400,
+                vote_count: 3,
+            }],
+            vote_proposals: vec![],
+            next_proposal_id: 0,
+            rent_reserve: 1_000,
+            bounty_pool: 5_000,
+            fee_pool: 0,
+            last_submitter: Pubkey([2; 32]),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: false,
+            fee_tiers: vec![],
+            round: 0,
+            max_deposit_per_wallet: 0,
+            max_total_deposit: 0,
+            streak_days: 0,
+            streak_discount: 0,
+            retract_window: 0,
+            retract_refund: 0,
+            round_state: RoundState::Active,
+            min_round_pot: 0,
+        };
+        // 프로그램처럼 last_content 는 마지막 제출의 content_key 다
+        state.last_content = state.contents[0].key();
+        state
+    }
+
+    #[test]
+    fn test_decodes_padded_account_data() {
+        let state = sample_state();
+        let mut data = borsh::to_vec(&state).unwrap();
+        data.resize(data.len() + 512, 0);
+
+        assert_eq!(DaoState::from_account_data(&data).unwrap(), state);
+        assert_eq!(state.last_activity_timestamp(), 6_400);
+        assert!(DaoState::from_account_data(&data[..10]).is_err());
+    }
+
+    #[test]
+    fn test_pubkey_base58_roundtrip() {
+        let pubkey = Pubkey([9; 32]);
+        let text = pubkey.to_string();
+
+        assert_eq!(text.parse::<Pubkey>(), Ok(pubkey));
+        assert!("not-base58!".parse::<Pubkey>().is_err());
+        assert!("3vQB7B6MrGQZaxCuFg4oh".parse::<Pubkey>().is_err());
+    }
+
+    // 프로그램 테스트의 CONTENT_KEY 와 같은 값
+    const CONTENT_KEY: [u8; 32] = [
+        0xf7, 0xcd, 0x39, 0x27, 0x4b, 0xd9, 0xce, 0xd8, 0x27, 0x3e, 0x95, 0x09, 0xcd, 0x40, 0x69, 0xfa,
+        0xbc, 0xd3, 0xf3, 0xc4, 0x08, 0xc8, 0xd1, 0xb7, 0x74, 0xd4, 0x36, 0xb6, 0x4b, 0xdd, 0x0b, 0x60,
+    ];
+
+    // solana_program/src/lib.rs 의 test_dao_state_layout 과 같은 바이트. 필드 순서나 타입이 어긋나면 깨진다.
+    #[test]
+    fn test_dao_state_layout_matches_program() {
+        let key = Pubkey([1; 32]);
+        let state = DaoState {
+            is_initialized: true,
+            dao_name: "dao".to_string(),
+            initializer: Pubkey([7; 32]),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 20,
+            timeout_timestamp: 1_700_003_600,
+            total_deposit: 500,
+            depositors: vec![DepositorInfo { depositor: key, amount: 500, timestamp: 1_700_000_000, locked_until: 1_700_003_600 }],
+            contents: vec![Content { author: key, text: "hi".to_string(), image_uri: String::new(), timestamp: 1_700_000_000, vote_count: 0 }],
+            vote_proposals: vec![VoteProposal {
+                proposal_id: 0,
+                proposer: key,
+                title: "t".to_string(),
+                description: String::new(),
+                vote_type: VoteType::ChangeTimeLimit,
+                options: vec!["60".to_string(), "120".to_string()],
+                start_time: 1_700_000_000,
+                end_time: 1_700_604_800,
+                votes: vec![VoteInfo { voter: key, option_index: 1, voting_power: 500 }],
+                status: VoteStatus::Active,
+            }],
+            next_proposal_id: 1,
+            rent_reserve: 1_000,
+            bounty_pool: 500,
+            fee_pool: 0,
+            last_submitter: key,
+            last_content: Pubkey(CONTENT_KEY),
+            deposit_resets_timer: true,
+            fee_tiers: vec![FeeTier { min_deposit: 500, discount: 50 }],
+            round: 2,
+            max_deposit_per_wallet: 500,
+            max_total_deposit: 0,
+            streak_days: 7,
+            streak_discount: 20,
+            retract_window: 600,
+            retract_refund: 50,
+            round_state: RoundState::Dormant,
+            min_round_pot: 500,
+        };
+        assert_eq!(state.contents[0].key(), state.last_content);
+
+        let timestamp: &[u8] = &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0];
+        let deadline: &[u8] = &[0x10, 0xff, 0x53, 0x65, 0, 0, 0, 0];
+        let amount: &[u8] = &[0xf4, 0x01, 0, 0, 0, 0, 0, 0];
+        let parts: &[&[u8]] = &[
+            &[1], &[3, 0, 0, 0, b'd', b'a', b'o'], &[7; 32],
+            &[0x10, 0x0e, 0, 0, 0, 0, 0, 0], &[10, 0, 0, 0, 0, 0, 0, 0], &[0], &[20], deadline, amount,
+            &[1, 0, 0, 0], &[1; 32], amount, timestamp, deadline,
+            &[1, 0, 0, 0], &[1; 32], &[2, 0, 0, 0, b'h', b'i'], &[0, 0, 0, 0], timestamp, &[0; 8],
+            &[1, 0, 0, 0], &[0; 8], &[1; 32], &[1, 0, 0, 0, b't'], &[0, 0, 0, 0], &[0],
+            &[2, 0, 0, 0, 2, 0, 0, 0, b'6', b'0', 3, 0, 0, 0, b'1', b'2', b'0'],
+            timestamp, &[0x80, 0x2b, 0x5d, 0x65, 0, 0, 0, 0],
+            &[1, 0, 0, 0], &[1; 32], &[1], amount, &[0],
+            &[1, 0, 0, 0, 0, 0, 0, 0],
+            &[0xe8, 0x03, 0, 0, 0, 0, 0, 0], amount, &[0; 8],
+            &[1; 32], &CONTENT_KEY, &[1],
+            &[1, 0, 0, 0], amount, &[50],
+            &[2, 0, 0, 0, 0, 0, 0, 0],
+            amount, &[0; 8],
+            &[7, 0, 0, 0, 0, 0, 0, 0], &[20],
+            &[0x58, 0x02, 0, 0, 0, 0, 0, 0], &[50],
+            &[1], amount,
+        ];
+        let expected = parts.concat();
+
+        assert_eq!(borsh::to_vec(&state).unwrap(), expected);
+        let mut account = expected;
+        account.resize(8_000, 0);
+        assert_eq!(DaoState::from_account_data(&account).unwrap(), state);
+    }
+
+    // 한도가 생기기 전의 긴 글은 프로그램처럼 잘려 읽히고, 마지막 제출 키가 잘린 글을 가리킨다
+    #[test]
+    fn test_legacy_content_is_cut_on_decode() {
+        let author = Pubkey([1; 32]);
+        let legacy = Content { author, text: "가".repeat(MAX_TEXT_LEN), image_uri: "u".repeat(201), timestamp: 6_000, vote_count: 0 };
+        let mut state = sample_state();
+        state.last_submitter = author;
+        state.last_content = legacy.key();
+        state.contents = vec![legacy];
+
+        let decoded = DaoState::from_account_data(&borsh::to_vec(&state).unwrap()).unwrap();
+        let content = &decoded.contents[0];
+        assert_eq!(content.text, "가".repeat(333));
+        assert_eq!(content.image_uri, "u".repeat(MAX_IMAGE_URI_LEN));
+        assert_eq!(decoded.last_content, content.key());
+        assert_eq!(DaoState::from_account_data(&borsh::to_vec(&decoded).unwrap()).unwrap(), decoded);
+    }
+
+    // 프로그램의 test_round_result_layout 과 같은 바이트
+    #[test]
+    fn test_round_result_layout_matches_program() {
+        let round_result = RoundResult {
+            dao: Pubkey([1; 32]),
+            round: 1,
+            winner: Pubkey([7; 32]),
+            reward: 500,
+            pot: 500,
+            content: Pubkey(CONTENT_KEY),
+            submitted_at: 1_700_000_000,
+            closed_at: 1_700_003_600,
+            quality_pool: 100,
+            candidates: vec![QualityCandidate { author: Pubkey([7; 32]), content: Pubkey(CONTENT_KEY), votes: 3 }],
+        };
+        let amount: &[u8] = &[0xf4, 0x01, 0, 0, 0, 0, 0, 0];
+        let parts: &[&[u8]] = &[
+            &[1; 32], &[1, 0, 0, 0, 0, 0, 0, 0], &[7; 32], amount, amount, &CONTENT_KEY,
+            &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0], &[0x10, 0xff, 0x53, 0x65, 0, 0, 0, 0], &[100, 0, 0, 0, 0, 0, 0, 0],
+            &[1, 0, 0, 0], &[7; 32], &CONTENT_KEY, &[3, 0, 0, 0, 0, 0, 0, 0],
+        ];
+        let expected = parts.concat();
+
+        assert_eq!(borsh::to_vec(&round_result).unwrap(), expected);
+        assert_eq!(expected.len() + (MAX_QUALITY_CANDIDATES - 1) * 72, RoundResult::LEN);
+        let mut account = expected.clone();
+        account.resize(RoundResult::LEN, 0);
+        assert_eq!(RoundResult::from_account_data(&account).unwrap(), round_result);
+        // quality_pool 전의 계정은 closed_at 에서 끝난다
+        let legacy = RoundResult::from_account_data(&expected[..136]).unwrap();
+        assert_eq!(legacy, RoundResult { quality_pool: 0, candidates: Vec::new(), ..round_result });
+    }
+
+    // solana_program/src/lib.rs 의 test_treasury_asset_layout 과 같은 바이트
+    #[test]
+    fn test_treasury_asset_layout() {
+        let asset = TreasuryAsset {
+            dao: Pubkey([1; 32]),
+            mint: Pubkey([7; 32]),
+            vault: Pubkey(CONTENT_KEY),
+            balance: 500,
+            next_round: 2,
+        };
+        let parts: &[&[u8]] = &[
+            &[1; 32], &[7; 32], &CONTENT_KEY, &[0xf4, 0x01, 0, 0, 0, 0, 0, 0], &[2, 0, 0, 0, 0, 0, 0, 0],
+        ];
+        let expected = parts.concat();
+
+        assert_eq!(borsh::to_vec(&asset).unwrap(), expected);
+        assert_eq!(expected.len(), TreasuryAsset::LEN);
+        assert_eq!(TreasuryAsset::try_from_slice(&expected).unwrap(), asset);
+    }
+
+    // solana_program/src/lib.rs 의 test_external_claim_layout 과 같은 바이트
+    #[test]
+    fn test_external_claim_layout() {
+        let claim = ExternalClaim {
+            dao: Pubkey([1; 32]),
+            author: Pubkey([7; 32]),
+            content: Pubkey(CONTENT_KEY),
+            claimed_at: 1_700_000_000,
+            external_ref: ExternalRef { platform: ExternalPlatform::Twitter, id: "42".to_string() },
+        };
+        let parts: &[&[u8]] = &[
+            &[1; 32], &[7; 32], &CONTENT_KEY, &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0], &[0], &[2, 0, 0, 0, b'4', b'2'],
+        ];
+        let expected = parts.concat();
+        assert_eq!(borsh::to_vec(&claim).unwrap(), expected);
+
+        let mut account = expected;
+        account.resize(ExternalClaim::LEN, 0);
+        assert_eq!(ExternalClaim::from_account_data(&account).unwrap(), claim);
+    }
+}
@@ -0,0 +1,514 @@
+use crate::instruction::Instruction;
+use crate::lookup_table::AddressLookupTable;
+use crate::state::Pubkey;
+use std::fmt;
+
+// 레거시(버전 없는) 트랜잭션 메시지. solana-program 의 Message::new_with_blockhash 와 같은 바이트를 만든다.
+// 지갑이 서명만 하면 되도록 서명 자리는 0 으로 채운 채 직렬화한다.
+// 계정이 많아 레거시 크기 한도를 넘는 트랜잭션은 주소 조회 테이블을 쓰는 V0Message 로 만든다.
+
+pub const SIGNATURE_BYTES: usize = 64;
+// 직렬화된 트랜잭션 (서명 포함) 의 최대 크기 (solana 의 PACKET_DATA_SIZE)
+pub const MAX_TRANSACTION_BYTES: usize = 1232;
+// 버전 있는 메시지는 첫 바이트의 최상위 비트가 켜져 있다
+const VERSION_PREFIX_MASK: u8 = 0x80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError(pub String);
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid transaction: {}", self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub header: MessageHeader,
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: [u8; 32],
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct KeyMeta {
+    is_signer: bool,
+    is_writable: bool,
+    // 명령의 program_id 로 쓰인 계정. 주소 조회 테이블로 옮길 수 없다.
+    is_invoked: bool,
+}
+
+type KeyMetas = std::collections::BTreeMap<Pubkey, KeyMeta>;
+
+fn key_metas(instructions: &[Instruction], payer: &Pubkey) -> KeyMetas {
+    let mut metas = KeyMetas::new();
+    for instruction in instructions {
+        metas.entry(instruction.program_id).or_default().is_invoked = true;
+        for account in &instruction.accounts {
+            let meta = metas.entry(account.pubkey).or_default();
+            meta.is_signer |= account.is_signer;
+            meta.is_writable |= account.is_writable;
+        }
+    }
+    metas.insert(*payer, KeyMeta { is_signer: true, is_writable: true, is_invoked: false });
+    metas
+}
+
+// 계정 순서: fee payer, 쓰기 서명자, 읽기 서명자, 쓰기 비서명자, 읽기 비서명자.
+// 각 그룹 안에서는 solana 의 CompiledKeys(BTreeMap) 처럼 pubkey 순으로 정렬한다.
+fn static_keys(metas: &KeyMetas, payer: &Pubkey) -> (MessageHeader, Vec<Pubkey>) {
+    let group = |signer: bool, writable: bool| {
+        metas
+            .iter()
+            .filter(move |(key, meta)| *key != payer && meta.is_signer == signer && meta.is_writable == writable)
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>()
+    };
+    let writable_signers = group(true, true);
+    let readonly_signers = group(true, false);
+    let writable_unsigned = group(false, true);
+    let readonly_unsigned = group(false, false);
+
+    let header = MessageHeader {
+        num_required_signatures: (1 + writable_signers.len() + readonly_signers.len()) as u8,
+        num_readonly_signed_accounts: readonly_signers.len() as u8,
+        num_readonly_unsigned_accounts: readonly_unsigned.len() as u8,
+    };
+
+    let mut account_keys = vec![*payer];
+    account_keys.extend(writable_signers);
+    account_keys.extend(readonly_signers);
+    account_keys.extend(writable_unsigned);
+    account_keys.extend(readonly_unsigned);
+    (header, account_keys)
+}
+
+fn compile_instructions(instructions: &[Instruction], account_keys: &[Pubkey]) -> Vec<CompiledInstruction> {
+    let index_of = |key: &Pubkey| account_keys.iter().position(|k| k == key).unwrap() as u8;
+    instructions
+        .iter()
+        .map(|instruction| CompiledInstruction {
+            program_id_index: index_of(&instruction.program_id),
+            accounts: instruction.accounts.iter().map(|account| index_of(&account.pubkey)).collect(),
+            data: instruction.data.clone(),
+        })
+        .collect()
+}
+
+impl Message {
+    pub fn new(instructions: &[Instruction], payer: &Pubkey, recent_blockhash: [u8; 32]) -> Self {
+        let (header, account_keys) = static_keys(&key_metas(instructions, payer), payer);
+        let instructions = compile_instructions(instructions, &account_keys);
+        Message { header, account_keys, recent_blockhash, instructions }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = vec![
+            self.header.num_required_signatures,
+            self.header.num_readonly_signed_accounts,
+            self.header.num_readonly_unsigned_accounts,
+        ];
+
+        write_compact_u16(&mut bytes, self.account_keys.len());
+        for key in &self.account_keys {
+            bytes.extend_from_slice(&key.0);
+        }
+        bytes.extend_from_slice(&self.recent_blockhash);
+
+        write_compact_u16(&mut bytes, self.instructions.len());
+        for instruction in &self.instructions {
+            bytes.push(instruction.program_id_index);
+            write_compact_u16(&mut bytes, instruction.accounts.len());
+            bytes.extend_from_slice(&instruction.accounts);
+            write_compact_u16(&mut bytes, instruction.data.len());
+            bytes.extend_from_slice(&instruction.data);
+        }
+        bytes
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.peek()? & VERSION_PREFIX_MASK != 0 {
+            return Err(DecodeError("versioned messages are not supported".to_string()));
+        }
+
+        let header = MessageHeader {
+            num_required_signatures: reader.u8()?,
+            num_readonly_signed_accounts: reader.u8()?,
+            num_readonly_unsigned_accounts: reader.u8()?,
+        };
+
+        let key_count = reader.compact_u16()?;
+        let account_keys = (0..key_count)
+            .map(|_| reader.array::<32>().map(Pubkey))
+            .collect::<Result<Vec<_>, _>>()?;
+        let recent_blockhash = reader.array::<32>()?;
+
+        let instruction_count = reader.compact_u16()?;
+        let mut instructions = Vec::with_capacity(instruction_count);
+        for _ in 0..instruction_count {
+            let program_id_index = reader.u8()?;
+            let account_count = reader.compact_u16()?;
+            let accounts = reader.take(account_count)?.to_vec();
+            let data_len = reader.compact_u16()?;
+            let data = reader.take(data_len)?.to_vec();
+            instructions.push(CompiledInstruction { program_id_index, accounts, data });
+        }
+        reader.finish()?;
+
+        let message = Message { header, account_keys, recent_blockhash, instructions };
+        message.validate()?;
+        Ok(message)
+    }
+
+    // 인덱스가 계정 목록 안에 있는지 확인
+    fn validate(&self) -> Result<(), DecodeError> {
+        let key_count = self.account_keys.len();
+        let header = &self.header;
+        if header.num_required_signatures as usize + header.num_readonly_unsigned_accounts as usize > key_count
+            || header.num_readonly_signed_accounts > header.num_required_signatures
+            || header.num_required_signatures == 0
+        {
+            return Err(DecodeError("header does not match account keys".to_string()));
+        }
+
+        let in_range = |index: &u8| (*index as usize) < key_count;
+        let valid = self.instructions.iter().all(|instruction| {
+            in_range(&instruction.program_id_index) && instruction.accounts.iter().all(in_range)
+        });
+        if !valid {
+            return Err(DecodeError("account index out of range".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn fee_payer(&self) -> &Pubkey {
+        &self.account_keys[0]
+    }
+
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.account_keys[..self.header.num_required_signatures as usize]
+    }
+
+    // 서명이 비어 있는 트랜잭션 (signatures 배열 + message)
+    pub fn to_unsigned_transaction(&self) -> Vec<u8> {
+        Transaction::unsigned(self.clone()).serialize()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    // message.signers() 와 같은 순서. 아직 서명하지 않은 자리는 0 으로 채워져 있다.
+    pub signatures: Vec<[u8; SIGNATURE_BYTES]>,
+    pub message: Message,
+}
+
+impl Transaction {
+    pub fn unsigned(message: Message) -> Self {
+        let signatures = vec![[0; SIGNATURE_BYTES]; message.header.num_required_signatures as usize];
+        Transaction { signatures, message }
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_compact_u16(&mut bytes, self.signatures.len());
+        for signature in &self.signatures {
+            bytes.extend_from_slice(signature);
+        }
+        bytes.extend(self.message.serialize());
+        bytes
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let count = reader.compact_u16()?;
+        let signatures = (0..count)
+            .map(|_| reader.array::<SIGNATURE_BYTES>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let message = Message::deserialize(&bytes[reader.offset..])?;
+        if signatures.len() != message.header.num_required_signatures as usize {
+            return Err(DecodeError("signature count does not match header".to_string()));
+        }
+        Ok(Transaction { signatures, message })
+    }
+
+    pub fn is_signed_by(&self, index: usize) -> bool {
+        self.signatures.get(index).is_some_and(|signature| signature.iter().any(|b| *b != 0))
+    }
+}
+
+// 주소 조회 테이블 하나에서 불러오는 계정들의 인덱스
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageAddressTableLookup {
+    pub account_key: Pubkey,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+// v0 메시지. solana 의 v0::Message::try_compile 과 같은 규칙으로 만든다:
+// 서명자와 program_id 가 아닌 계정 중 테이블에 있는 것은 테이블 인덱스로 바꾸고 나머지만 계정 목록에 남긴다.
+// 명령의 계정 인덱스는 (남은 계정, 테이블의 쓰기 계정들, 테이블의 읽기 계정들) 을 이어 붙인 목록을 가리킨다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct V0Message {
+    // header/account_keys 는 테이블로 옮기고 남은 정적 계정만 센다
+    pub message: Message,
+    pub address_table_lookups: Vec<MessageAddressTableLookup>,
+}
+
+impl V0Message {
+    pub fn new(instructions: &[Instruction], payer: &Pubkey, recent_blockhash: [u8; 32], tables: &[AddressLookupTable]) -> Self {
+        let mut metas = key_metas(instructions, payer);
+        let mut address_table_lookups = Vec::new();
+        let mut loaded_writable = Vec::new();
+        let mut loaded_readonly = Vec::new();
+
+        for table in tables {
+            let mut lookup = MessageAddressTableLookup { account_key: table.key, writable_indexes: vec![], readonly_indexes: vec![] };
+            let mut loaded = Vec::new();
+            for (key, meta) in &metas {
+                if meta.is_signer || meta.is_invoked {
+                    continue;
+                }
+                let Some(index) = table.index_of(key) else {
+                    continue;
+                };
+                match meta.is_writable {
+                    true => {
+                        lookup.writable_indexes.push(index);
+                        loaded_writable.push(*key);
+                    }
+                    false => {
+                        lookup.readonly_indexes.push(index);
+                        loaded_readonly.push(*key);
+                    }
+                }
+                loaded.push(*key);
+            }
+            for key in &loaded {
+                metas.remove(key);
+            }
+            if !loaded.is_empty() {
+                address_table_lookups.push(lookup);
+            }
+        }
+
+        let (header, account_keys) = static_keys(&metas, payer);
+        let all_keys: Vec<Pubkey> = account_keys.iter().chain(&loaded_writable).chain(&loaded_readonly).copied().collect();
+        let instructions = compile_instructions(instructions, &all_keys);
+        V0Message {
+            message: Message { header, account_keys, recent_blockhash, instructions },
+            address_table_lookups,
+        }
+    }
+
+    // 버전 바이트 (0x80 | 0), 레거시와 같은 본문, 그다음 테이블 조회 목록
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = vec![VERSION_PREFIX_MASK];
+        bytes.extend(self.message.serialize());
+        write_compact_u16(&mut bytes, self.address_table_lookups.len());
+        for lookup in &self.address_table_lookups {
+            bytes.extend_from_slice(&lookup.account_key.0);
+            write_compact_u16(&mut bytes, lookup.writable_indexes.len());
+            bytes.extend_from_slice(&lookup.writable_indexes);
+            write_compact_u16(&mut bytes, lookup.readonly_indexes.len());
+            bytes.extend_from_slice(&lookup.readonly_indexes);
+        }
+        bytes
+    }
+
+    pub fn to_unsigned_transaction(&self) -> Vec<u8> {
+        let signature_count = self.message.header.num_required_signatures as usize;
+        let mut bytes = Vec::new();
+        write_compact_u16(&mut bytes, signature_count);
+        bytes.resize(bytes.len() + signature_count * SIGNATURE_BYTES, 0);
+        bytes.extend(self.serialize());
+        bytes
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self.offset.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| DecodeError("unexpected end of data".to_string()))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn peek(&self) -> Result<u8, DecodeError> {
+        self.bytes.get(self.offset).copied().ok_or_else(|| DecodeError("unexpected end of data".to_string()))
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn compact_u16(&mut self) -> Result<usize, DecodeError> {
+        let mut value = 0usize;
+        for shift in [0, 7, 14] {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError("compact-u16 too long".to_string()))
+    }
+
+    fn finish(&self) -> Result<(), DecodeError> {
+        if self.offset == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(DecodeError("trailing bytes".to_string()))
+        }
+    }
+}
+
+// solana 의 short_vec 길이 인코딩 (7비트씩, 최상위 비트는 계속 표시)
+pub fn write_compact_u16(bytes: &mut Vec<u8>, len: usize) {
+    let mut rest = len as u16;
+    loop {
+        let mut byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+        if rest == 0 {
+            bytes.push(byte);
+            return;
+        }
+        byte |= 0x80;
+        bytes.push(byte);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::{dao_address, deposit, distribute_quality_rewards, process_timeout};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_message_matches_solana_serialization() {
+        let program_id = Pubkey([3; 32]);
+        let payer = Pubkey([1; 32]);
+        let (dao, _) = dao_address(&program_id, &payer, "turtles").unwrap();
+
+        let message = Message::new(
+            &[deposit(&program_id, &payer, &dao, 5_000), process_timeout(&program_id, &Pubkey([0xaa; 32]), &dao, 0, &Pubkey::default())],
+            &payer,
+            [9; 32],
+        );
+
+        // solana-program 1.16 Message::new_with_blockhash(..).serialize() 결과
+        let expected = concat!(
+            "020002060101010101010101010101010101010101010101010101010101010101010101",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            "ba40742a31ceea4d0c4aa5e8a4fd28259fa81112cd8f7c72833eae6c32be6ed4",
+            "ee9fcc8aef488abfedaf897d74cf845cade19c464c0d96f1f50c8b2a1a7209d6",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0303030303030303030303030303030303030303030303030303030303030303",
+            "0909090909090909090909090909090909090909090909090909090909090909",
+            "020503000204090188130000000000000504010203040105",
+        );
+        assert_eq!(hex(&message.serialize()), expected);
+
+        let transaction = message.to_unsigned_transaction();
+        assert_eq!(transaction[0], 2);
+        assert!(transaction[1..1 + 2 * SIGNATURE_BYTES].iter().all(|b| *b == 0));
+        assert_eq!(&transaction[1 + 2 * SIGNATURE_BYTES..], message.serialize().as_slice());
+    }
+
+    #[test]
+    fn test_transaction_roundtrip() {
+        let program_id = Pubkey([3; 32]);
+        let payer = Pubkey([1; 32]);
+        let message = Message::new(&[process_timeout(&program_id, &Pubkey([0xaa; 32]), &Pubkey([2; 32]), 0, &Pubkey::default())], &payer, [9; 32]);
+
+        let mut transaction = Transaction::unsigned(message.clone());
+        transaction.signatures[1] = [7; SIGNATURE_BYTES];
+        let decoded = Transaction::deserialize(&transaction.serialize()).unwrap();
+
+        assert_eq!(decoded, transaction);
+        assert_eq!(decoded.message.signers(), &[payer, Pubkey([0xaa; 32])]);
+        assert!(!decoded.is_signed_by(0));
+        assert!(decoded.is_signed_by(1));
+
+        let bytes = transaction.serialize();
+        assert!(Transaction::deserialize(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Transaction::deserialize(&[bytes.as_slice(), &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_v0_message_moves_table_accounts() {
+        let program_id = Pubkey([3; 32]);
+        let admin = Pubkey([1; 32]);
+        let dao = Pubkey([2; 32]);
+        let creators = vec![Pubkey([0xc1; 32]), Pubkey([0xc2; 32]), Pubkey([0xc3; 32])];
+        let instruction = distribute_quality_rewards(&program_id, &admin, &dao, 0, creators.clone(), vec![50, 30, 20]);
+        let round_result = instruction.accounts[2].pubkey;
+        // 서명자(admin) 와 program_id 는 테이블에 있어도 정적 계정으로 남는다
+        let table = AddressLookupTable {
+            key: Pubkey([0x77; 32]),
+            addresses: vec![admin, program_id, Pubkey([0xc2; 32]), dao, Pubkey([0xc1; 32]), round_result],
+        };
+
+        let message = V0Message::new(&[instruction], &admin, [9; 32], &[table]);
+        assert_eq!(message.message.account_keys, vec![admin, Pubkey([0xc3; 32]), program_id]);
+        assert_eq!(message.address_table_lookups, vec![MessageAddressTableLookup {
+            account_key: Pubkey([0x77; 32]),
+            writable_indexes: vec![3, 4, 2, 5],
+            readonly_indexes: vec![],
+        }]);
+        // 정적 3개 다음에 테이블에서 불러온 dao, c1, c2, round_result (pubkey 순서)
+        let compiled = &message.message.instructions[0];
+        assert_eq!(compiled.program_id_index, 2);
+        assert_eq!(compiled.accounts, vec![0, 3, 6, 4, 5, 1]);
+
+        let bytes = message.to_unsigned_transaction();
+        assert_eq!(bytes[0], 1);
+        assert_eq!(bytes[1 + SIGNATURE_BYTES], 0x80);
+        assert_eq!(&bytes[1 + SIGNATURE_BYTES + 1..][..message.message.serialize().len()], message.message.serialize().as_slice());
+        assert!(Message::deserialize(&message.serialize()).is_err());
+
+        // 테이블이 없으면 레거시와 같은 계정 순서다
+        let legacy = Message::new(&[process_timeout(&program_id, &admin, &dao, 0, &Pubkey::default())], &admin, [9; 32]);
+        assert_eq!(V0Message::new(&[process_timeout(&program_id, &admin, &dao, 0, &Pubkey::default())], &admin, [9; 32], &[]).message, legacy);
+    }
+
+    #[test]
+    fn test_compact_u16() {
+        for (len, encoded) in [(0, vec![0]), (0x7f, vec![0x7f]), (0x80, vec![0x80, 0x01]), (0x3fff, vec![0xff, 0x7f])] {
+            let mut bytes = Vec::new();
+            write_compact_u16(&mut bytes, len);
+            assert_eq!(bytes, encoded);
+        }
+    }
+}
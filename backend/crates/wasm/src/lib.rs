@@ -0,0 +1,233 @@
+// 브라우저용 바인딩. 프런트엔드가 Borsh 레이아웃을 TypeScript 로 다시 짜지 않도록
+// sol 크레이트의 명령 빌더, PDA 계산, 계정 디코더를 그대로 JS 에 내보낸다.
+// wasm-pack build --target web crates/wasm 로 빌드한다. 네이티브에서도 컴파일되므로 보통 빌드/테스트에 함께 걸린다.
+//
+// 반환하는 instruction 은 web3.js 의 new TransactionInstruction({ programId, keys, data }) 에 넘길 수 있는 모양이다
+// (programId/pubkey 는 base58 문자열이라 new PublicKey(...) 로 감싸면 된다).
+pub mod view;
+
+use sol::instruction::{self as builders, Instruction};
+use sol::state::{DaoPreset, DaoState, ExternalPlatform, ExternalRef, Pubkey, VoteType};
+use wasm_bindgen::prelude::*;
+use crate::view::{DaoStateView, InstructionView, ProgramAddress};
+
+fn pubkey(value: &str) -> Result<Pubkey, JsError> {
+    value.parse().map_err(|e: sol::state::ParsePubkeyError| JsError::new(&e.to_string()))
+}
+
+fn to_js<T: serde::Serialize>(value: &T) -> Result<JsValue, JsError> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn instruction(instruction: Instruction) -> Result<JsValue, JsError> {
+    to_js(&InstructionView::from(instruction))
+}
+
+pub fn parse_vote_type(value: &str) -> Option<VoteType> {
+    match value {
+        "ChangeTimeLimit" => Some(VoteType::ChangeTimeLimit),
+        "ChangeBaseFee" => Some(VoteType::ChangeBaseFee),
+        "ChangeAiModeration" => Some(VoteType::ChangeAiModeration),
+        "ContentQualityRating" => Some(VoteType::ContentQualityRating),
+        "ChangeDepositResetsTimer" => Some(VoteType::ChangeDepositResetsTimer),
+        "ChangeFeeTiers" => Some(VoteType::ChangeFeeTiers),
+        "ReplaceAdmin" => Some(VoteType::ReplaceAdmin),
+        "ChangeDepositCaps" => Some(VoteType::ChangeDepositCaps),
+        "ChangeStreakBonus" => Some(VoteType::ChangeStreakBonus),
+        "ChangeRetractPolicy" => Some(VoteType::ChangeRetractPolicy),
+        "ChangeRoundMinimum" => Some(VoteType::ChangeRoundMinimum),
+        _ => None,
+    }
+}
+
+// 계정 데이터 (뒤쪽이 0 으로 채워져 있어도 된다) 를 DaoState 로 읽는다
+pub fn decode_dao_state_view(data: &[u8]) -> Result<DaoStateView, String> {
+    DaoState::from_account_data(data)
+        .map(DaoStateView::from)
+        .map_err(|e| e.to_string())
+}
+
+#[wasm_bindgen(js_name = findDaoAddress)]
+pub fn find_dao_address(program_id: &str, initializer: &str, dao_name: &str) -> Result<JsValue, JsError> {
+    let (address, bump) = builders::dao_address(&pubkey(program_id)?, &pubkey(initializer)?, dao_name)
+        .ok_or_else(|| JsError::new("no program address found for these seeds"))?;
+    to_js(&ProgramAddress { address: address.to_string(), bump })
+}
+
+// 승자가 나온 라운드의 기록 계정. round 는 0 부터, decodeDaoState 의 round 보다 작은 번호만 있다.
+#[wasm_bindgen(js_name = findRoundResultAddress)]
+pub fn find_round_result_address(program_id: &str, dao_account: &str, round: u64) -> Result<JsValue, JsError> {
+    let (address, bump) = builders::round_result_address(&pubkey(program_id)?, &pubkey(dao_account)?, round)
+        .ok_or_else(|| JsError::new("no program address found for these seeds"))?;
+    to_js(&ProgramAddress { address: address.to_string(), bump })
+}
+
+// 외부 게시물을 이미 다른 글이 가져갔는지는 이 주소의 계정이 있는지로 알 수 있다
+#[wasm_bindgen(js_name = findClaimAddress)]
+pub fn find_claim_address(program_id: &str, dao_account: &str, platform: &str, id: &str) -> Result<JsValue, JsError> {
+    let platform = platform_arg(platform)?;
+    let (address, bump) = builders::claim_address(&pubkey(program_id)?, &pubkey(dao_account)?, platform, id)
+        .ok_or_else(|| JsError::new("no program address found for these seeds"))?;
+    to_js(&ProgramAddress { address: address.to_string(), bump })
+}
+
+#[wasm_bindgen(js_name = decodeDaoState)]
+pub fn decode_dao_state(data: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&decode_dao_state_view(data).map_err(|e| JsError::new(&e))?)
+}
+
+// DAO 계정은 (initializer, dao_name) 의 PDA 라서 따로 받지 않는다
+#[wasm_bindgen(js_name = initializeDao)]
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_dao(
+    program_id: &str,
+    initializer: &str,
+    dao_name: String,
+    time_limit: u64,
+    base_fee: u64,
+    ai_moderation: bool,
+    deposit_share: u8,
+    deposit_resets_timer: bool,
+) -> Result<JsValue, JsError> {
+    let program_id = pubkey(program_id)?;
+    let initializer = pubkey(initializer)?;
+    let (dao_account, _) = builders::dao_address(&program_id, &initializer, &dao_name)
+        .ok_or_else(|| JsError::new("no program address found for these seeds"))?;
+    instruction(builders::initialize_dao(
+        &program_id, &initializer, &dao_account, dao_name, time_limit, base_fee, ai_moderation, deposit_share, deposit_resets_timer,
+    ))
+}
+
+pub fn parse_dao_preset(value: &str) -> Option<DaoPreset> {
+    match value {
+        "FastGame" => Some(DaoPreset::FastGame),
+        "LongForm" => Some(DaoPreset::LongForm),
+        "ModeratedShowcase" => Some(DaoPreset::ModeratedShowcase),
+        _ => None,
+    }
+}
+
+pub fn parse_external_platform(value: &str) -> Option<ExternalPlatform> {
+    match value {
+        "Twitter" => Some(ExternalPlatform::Twitter),
+        "Farcaster" => Some(ExternalPlatform::Farcaster),
+        _ => None,
+    }
+}
+
+fn platform_arg(value: &str) -> Result<ExternalPlatform, JsError> {
+    parse_external_platform(value).ok_or_else(|| JsError::new(&format!("unknown platform: {}", value)))
+}
+
+// preset 은 "FastGame" | "LongForm" | "ModeratedShowcase". 나머지 파라미터는 프로그램이 프리셋에서 채운다
+#[wasm_bindgen(js_name = initializeDaoWithPreset)]
+pub fn initialize_dao_with_preset(program_id: &str, initializer: &str, dao_name: String, preset: &str) -> Result<JsValue, JsError> {
+    let preset = parse_dao_preset(preset).ok_or_else(|| JsError::new(&format!("unknown preset: {}", preset)))?;
+    let program_id = pubkey(program_id)?;
+    let initializer = pubkey(initializer)?;
+    let (dao_account, _) = builders::dao_address(&program_id, &initializer, &dao_name)
+        .ok_or_else(|| JsError::new("no program address found for these seeds"))?;
+    instruction(builders::initialize_dao_with_preset(&program_id, &initializer, &dao_account, dao_name, preset))
+}
+
+#[wasm_bindgen]
+pub fn deposit(program_id: &str, depositor: &str, dao_account: &str, amount: u64) -> Result<JsValue, JsError> {
+    instruction(builders::deposit(&pubkey(program_id)?, &pubkey(depositor)?, &pubkey(dao_account)?, amount))
+}
+
+#[wasm_bindgen(js_name = submitContent)]
+pub fn submit_content(program_id: &str, author: &str, dao_account: &str, text: String, image_uri: String) -> Result<JsValue, JsError> {
+    instruction(builders::submit_content(&pubkey(program_id)?, &pubkey(author)?, &pubkey(dao_account)?, text, image_uri))
+}
+
+// submitContent 와 같고 작성자의 연속 제출 기록 계정이 붙는다
+#[wasm_bindgen(js_name = submitContentWithStreak)]
+pub fn submit_content_with_streak(program_id: &str, author: &str, dao_account: &str, text: String, image_uri: String) -> Result<JsValue, JsError> {
+    instruction(builders::submit_content_with_streak(&pubkey(program_id)?, &pubkey(author)?, &pubkey(dao_account)?, text, image_uri))
+}
+
+// platform 은 "Twitter" | "Farcaster", id 는 트윗 id 나 cast hash (최대 64 바이트)
+#[wasm_bindgen(js_name = submitLinkedContent)]
+pub fn submit_linked_content(
+    program_id: &str,
+    author: &str,
+    dao_account: &str,
+    text: String,
+    image_uri: String,
+    platform: &str,
+    id: String,
+) -> Result<JsValue, JsError> {
+    let external_ref = ExternalRef { platform: platform_arg(platform)?, id };
+    instruction(builders::submit_linked_content(&pubkey(program_id)?, &pubkey(author)?, &pubkey(dao_account)?, text, image_uri, external_ref))
+}
+
+// content 는 decodeDaoState 의 contents[].key
+#[wasm_bindgen(js_name = retractContent)]
+pub fn retract_content(program_id: &str, author: &str, dao_account: &str, content: &str) -> Result<JsValue, JsError> {
+    instruction(builders::retract_content(&pubkey(program_id)?, &pubkey(author)?, &pubkey(dao_account)?, &pubkey(content)?))
+}
+
+// submitLinkedContent 로 올린 글을 철회할 때. 같은 platform, id 를 넘기면 ExternalClaim 도 닫힌다
+#[wasm_bindgen(js_name = retractLinkedContent)]
+pub fn retract_linked_content(program_id: &str, author: &str, dao_account: &str, content: &str, platform: &str, id: String) -> Result<JsValue, JsError> {
+    let external_ref = ExternalRef { platform: platform_arg(platform)?, id };
+    instruction(builders::retract_linked_content(&pubkey(program_id)?, &pubkey(author)?, &pubkey(dao_account)?, &pubkey(content)?, &external_ref))
+}
+
+// vote_type 은 "ChangeTimeLimit" | "ChangeBaseFee" | "ChangeAiModeration" | "ContentQualityRating" | "ChangeDepositResetsTimer"
+// | "ChangeFeeTiers" (선택지는 "최소예치:할인%" 목록, 예: "1000000000:50,5000000000:75")
+// | "ReplaceAdmin" (선택지는 새 admin 의 base58 공개키. 투표와 예치 모두의 2/3 이상을 얻어야 바뀐다)
+// | "ChangeDepositCaps" (선택지는 "지갑한도:전체한도" lamports, 0 이나 "none" 은 한도 없음. 예: "1000000000:none")
+// | "ChangeStreakBonus" (선택지는 "연속일수:할인%", "none" 은 보너스 없음. 예: "7:20")
+// | "ChangeRetractPolicy" (선택지는 "철회가능초:환불%", "none" 은 철회 불가. 예: "600:50")
+// | "ChangeRoundMinimum" (선택지는 라운드를 여는 최소 상금 lamports, "none" 은 0. 예: "1000000000")
+// content 는 글의 content key. 작성자 자신이나 이번 라운드에 예치하지 않은 지갑은 투표할 수 없다
+#[wasm_bindgen(js_name = voteContent)]
+pub fn vote_content(program_id: &str, voter: &str, dao_account: &str, content: &str) -> Result<JsValue, JsError> {
+    instruction(builders::vote_content(&pubkey(program_id)?, &pubkey(voter)?, &pubkey(dao_account)?, &pubkey(content)?))
+}
+
+#[wasm_bindgen(js_name = createVote)]
+#[allow(clippy::too_many_arguments)]
+pub fn create_vote(
+    program_id: &str,
+    proposer: &str,
+    dao_account: &str,
+    title: String,
+    description: String,
+    vote_type: &str,
+    options: Vec<String>,
+    voting_period: u64,
+) -> Result<JsValue, JsError> {
+    let vote_type = parse_vote_type(vote_type).ok_or_else(|| JsError::new(&format!("unknown vote type: {}", vote_type)))?;
+    instruction(builders::create_vote(&pubkey(program_id)?, &pubkey(proposer)?, &pubkey(dao_account)?, title, description, vote_type, options, voting_period))
+}
+
+#[wasm_bindgen(js_name = castVote)]
+pub fn cast_vote(program_id: &str, voter: &str, dao_account: &str, proposal_id: u64, option_index: u8) -> Result<JsValue, JsError> {
+    instruction(builders::cast_vote(&pubkey(program_id)?, &pubkey(voter)?, &pubkey(dao_account)?, proposal_id, option_index))
+}
+
+// round 와 lastSubmitter 는 decodeDaoState 의 값을 그대로 넘긴다. 제출이 없었으면 lastSubmitter 는 비어 있다
+#[wasm_bindgen(js_name = processTimeout)]
+pub fn process_timeout(program_id: &str, caller: &str, dao_account: &str, round: u64, last_submitter: Option<String>) -> Result<JsValue, JsError> {
+    let last_submitter = last_submitter.as_deref().map(pubkey).transpose()?.unwrap_or_default();
+    instruction(builders::process_timeout(&pubkey(program_id)?, &pubkey(caller)?, &pubkey(dao_account)?, round, &last_submitter))
+}
+
+// round 는 보상을 나눌 끝난 라운드 (decodeDaoState 의 round 보다 작다)
+#[wasm_bindgen(js_name = distributeQualityRewards)]
+pub fn distribute_quality_rewards(
+    program_id: &str,
+    admin: &str,
+    dao_account: &str,
+    round: u64,
+    creators: Vec<String>,
+    weights: Vec<u8>,
+) -> Result<JsValue, JsError> {
+    if creators.len() != weights.len() {
+        return Err(JsError::new("creators and weights must have the same length"));
+    }
+    let creators = creators.iter().map(|creator| pubkey(creator)).collect::<Result<Vec<_>, _>>()?;
+    instruction(builders::distribute_quality_rewards(&pubkey(program_id)?, &pubkey(admin)?, &pubkey(dao_account)?, round, creators, weights))
+}
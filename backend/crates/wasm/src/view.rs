@@ -0,0 +1,291 @@
+use serde::Serialize;
+use sol::instruction::Instruction;
+use sol::state::{DaoState, Pubkey, VoteStatus, VoteType};
+
+// JS 로 넘기는 모양. 키는 camelCase, 공개키는 base58 문자열이다.
+// u64 금액/시각은 JS number 로 바뀌므로 2^53 을 넘는 값은 정밀도를 잃는다 (lamports 는 그 안에 든다).
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramAddress {
+    pub address: String,
+    pub bump: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMetaView {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionView {
+    pub program_id: String,
+    pub keys: Vec<AccountMetaView>,
+    // Uint8Array 로 넘긴다
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl From<Instruction> for InstructionView {
+    fn from(instruction: Instruction) -> Self {
+        InstructionView {
+            program_id: instruction.program_id.to_string(),
+            keys: instruction.accounts.iter().map(|meta| AccountMetaView {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            }).collect(),
+            data: instruction.data,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositorView {
+    pub depositor: String,
+    pub amount: u64,
+    pub timestamp: u64,
+    pub locked_until: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeTierView {
+    pub min_deposit: u64,
+    pub discount: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentView {
+    // retractContent 에 넘기는 content_key
+    pub key: String,
+    pub author: String,
+    pub text: String,
+    pub image_uri: String,
+    pub timestamp: u64,
+    pub vote_count: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteView {
+    pub voter: String,
+    pub option_index: u8,
+    pub voting_power: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalView {
+    pub proposal_id: u64,
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub vote_type: &'static str,
+    pub options: Vec<String>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub votes: Vec<VoteView>,
+    pub status: &'static str,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaoStateView {
+    pub is_initialized: bool,
+    pub dao_name: String,
+    pub initializer: String,
+    pub time_limit: u64,
+    pub base_fee: u64,
+    pub ai_moderation: bool,
+    pub deposit_share: u8,
+    pub timeout_timestamp: u64,
+    pub total_deposit: u64,
+    pub depositors: Vec<DepositorView>,
+    pub contents: Vec<ContentView>,
+    pub vote_proposals: Vec<ProposalView>,
+    pub next_proposal_id: u64,
+    pub rent_reserve: u64,
+    pub bounty_pool: u64,
+    pub fee_pool: u64,
+    // 제출이 없는 라운드에서는 None
+    pub last_submitter: Option<String>,
+    pub deposit_resets_timer: bool,
+    pub fee_tiers: Vec<FeeTierView>,
+    pub round: u64,
+    // 예치 한도 (0 이면 없다)
+    pub max_deposit_per_wallet: u64,
+    pub max_total_deposit: u64,
+    // 연속 제출 보너스 (0 일이면 없다)
+    pub streak_days: u64,
+    pub streak_discount: u8,
+    // 철회 정책 (0 초면 철회 불가)
+    pub retract_window: u64,
+    pub retract_refund: u8,
+    // 라운드가 열리기 전이면 true (timeout_timestamp 는 마감이 아니다)
+    pub dormant: bool,
+    pub min_round_pot: u64,
+}
+
+pub fn vote_type_name(vote_type: &VoteType) -> &'static str {
+    match vote_type {
+        VoteType::ChangeTimeLimit => "ChangeTimeLimit",
+        VoteType::ChangeBaseFee => "ChangeBaseFee",
+        VoteType::ChangeAiModeration => "ChangeAiModeration",
+        VoteType::ContentQualityRating => "ContentQualityRating",
+        VoteType::ChangeDepositResetsTimer => "ChangeDepositResetsTimer",
+        VoteType::ChangeFeeTiers => "ChangeFeeTiers",
+        VoteType::ReplaceAdmin => "ReplaceAdmin",
+        VoteType::ChangeDepositCaps => "ChangeDepositCaps",
+        VoteType::ChangeStreakBonus => "ChangeStreakBonus",
+        VoteType::ChangeRetractPolicy => "ChangeRetractPolicy",
+        VoteType::ChangeRoundMinimum => "ChangeRoundMinimum",
+    }
+}
+
+fn status_name(status: &VoteStatus) -> &'static str {
+    match status {
+        VoteStatus::Active => "Active",
+        VoteStatus::Completed => "Completed",
+        VoteStatus::Executed => "Executed",
+    }
+}
+
+impl From<DaoState> for DaoStateView {
+    fn from(state: DaoState) -> Self {
+        let dormant = state.is_dormant();
+        DaoStateView {
+            is_initialized: state.is_initialized,
+            dao_name: state.dao_name,
+            initializer: state.initializer.to_string(),
+            time_limit: state.time_limit,
+            base_fee: state.base_fee,
+            ai_moderation: state.ai_moderation,
+            deposit_share: state.deposit_share,
+            timeout_timestamp: state.timeout_timestamp,
+            total_deposit: state.total_deposit,
+            depositors: state.depositors.into_iter().map(|depositor| DepositorView {
+                depositor: depositor.depositor.to_string(),
+                amount: depositor.amount,
+                timestamp: depositor.timestamp,
+                locked_until: depositor.locked_until,
+            }).collect(),
+            contents: state.contents.into_iter().map(|content| ContentView {
+                key: content.key().to_string(),
+                author: content.author.to_string(),
+                text: content.text,
+                image_uri: content.image_uri,
+                timestamp: content.timestamp,
+                vote_count: content.vote_count,
+            }).collect(),
+            vote_proposals: state.vote_proposals.into_iter().map(|proposal| ProposalView {
+                proposal_id: proposal.proposal_id,
+                proposer: proposal.proposer.to_string(),
+                title: proposal.title,
+                description: proposal.description,
+                vote_type: vote_type_name(&proposal.vote_type),
+                options: proposal.options,
+                start_time: proposal.start_time,
+                end_time: proposal.end_time,
+                votes: proposal.votes.into_iter().map(|vote| VoteView {
+                    voter: vote.voter.to_string(),
+                    option_index: vote.option_index,
+                    voting_power: vote.voting_power,
+                }).collect(),
+                status: status_name(&proposal.status),
+            }).collect(),
+            next_proposal_id: state.next_proposal_id,
+            rent_reserve: state.rent_reserve,
+            bounty_pool: state.bounty_pool,
+            fee_pool: state.fee_pool,
+            last_submitter: (state.last_submitter != Pubkey::default()).then(|| state.last_submitter.to_string()),
+            deposit_resets_timer: state.deposit_resets_timer,
+            fee_tiers: state.fee_tiers.into_iter().map(|tier| FeeTierView {
+                min_deposit: tier.min_deposit,
+                discount: tier.discount,
+            }).collect(),
+            round: state.round,
+            max_deposit_per_wallet: state.max_deposit_per_wallet,
+            max_total_deposit: state.max_total_deposit,
+            streak_days: state.streak_days,
+            streak_discount: state.streak_discount,
+            retract_window: state.retract_window,
+            retract_refund: state.retract_refund,
+            dormant,
+            min_round_pot: state.min_round_pot,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol::instruction::deposit;
+    use sol::state::{Content, RoundState};
+    use crate::{decode_dao_state_view, parse_vote_type};
+
+    #[test]
+    fn test_instruction_view_uses_base58_keys() {
+        let view = InstructionView::from(deposit(&Pubkey([1; 32]), &Pubkey([2; 32]), &Pubkey([3; 32]), 500));
+        assert_eq!(view.program_id, Pubkey([1; 32]).to_string());
+        assert_eq!(view.keys[0], AccountMetaView { pubkey: Pubkey([2; 32]).to_string(), is_signer: true, is_writable: true });
+        assert_eq!(view.keys[2].pubkey, "11111111111111111111111111111111");
+        // Deposit 태그(1) 다음 u64 little-endian
+        assert_eq!(view.data, [1, 244, 1, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_decode_dao_state_view() {
+        let state = DaoState {
+            is_initialized: true,
+            dao_name: "turtles".to_string(),
+            initializer: Pubkey([1; 32]),
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 50,
+            timeout_timestamp: 7_200,
+            total_deposit: 1_000,
+            depositors: vec![],
+            contents: vec![Content { author: Pubkey([2; 32]), text: "hello".to_string(), image_uri: String::new(), timestamp: 10, vote_count: 3 }],
+            vote_proposals: vec![],
+            next_proposal_id: 0,
+            rent_reserve: 1_000,
+            bounty_pool: 1_000,
+            fee_pool: 0,
+            last_submitter: Pubkey::default(),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: false,
+            fee_tiers: vec![],
+            round: 0,
+            max_deposit_per_wallet: 0,
+            max_total_deposit: 0,
+            streak_days: 0,
+            streak_discount: 0,
+            retract_window: 0,
+            retract_refund: 0,
+            round_state: RoundState::Active,
+            min_round_pot: 0,
+        };
+        let mut data = borsh::to_vec(&state).unwrap();
+        data.resize(data.len() + 100, 0);
+
+        let view = decode_dao_state_view(&data).unwrap();
+        assert_eq!((view.dao_name.as_str(), view.contents[0].vote_count), ("turtles", 3));
+        assert_eq!(view.contents[0].author, Pubkey([2; 32]).to_string());
+        assert_eq!(view.contents[0].key, state.contents[0].key().to_string());
+        assert_eq!(view.last_submitter, None);
+        assert!(decode_dao_state_view(&[1, 2]).is_err());
+
+        for vote_type in [VoteType::ChangeTimeLimit, VoteType::ContentQualityRating, VoteType::ChangeDepositResetsTimer, VoteType::ChangeFeeTiers, VoteType::ReplaceAdmin, VoteType::ChangeDepositCaps, VoteType::ChangeStreakBonus, VoteType::ChangeRetractPolicy, VoteType::ChangeRoundMinimum] {
+            assert_eq!(parse_vote_type(vote_type_name(&vote_type)), Some(vote_type));
+        }
+    }
+}
@@ -10,24 +10,28 @@ type ProfileButtonProps = {
   isPixelMode?: boolean;
 };
 
+// Storage URIs are not loadable by the browser, so map them to public gateways
+const toAvatarUrl = (uri?: string | null): string | undefined => {
+  if (!uri) return undefined;
+  if (uri.startsWith('ipfs://')) return `https://ipfs.io/ipfs/${uri.slice('ipfs://'.length)}`;
+  if (uri.startsWith('ar://')) return `https://arweave.net/${uri.slice('ar://'.length)}`;
+  return uri;
+};
+
 // Helper to convert backend profile to frontend format
 const convertBackendProfile = (backendProfile: any): ProfileData => {
   console.log('Converting backend profile to frontend format:', backendProfile);
   
   return {
-    displayName: backendProfile.user_name || '',
-    bio: backendProfile.user_bio || '',
+    displayName: backendProfile.display_name || '',
+    bio: backendProfile.bio || '',
     socialLinks: {
-      github: backendProfile.github_account || undefined,
-      twitter: backendProfile.x_account || undefined,
-      telegram: backendProfile.tg_account || undefined,
+      github: backendProfile.links?.github || undefined,
+      twitter: backendProfile.links?.x || undefined,
+      telegram: backendProfile.links?.telegram || undefined,
     },
-    // Check if user_avatar is present, and if it's a string (base64) or a Uint8Array (direct binary)
-    avatarUrl: backendProfile.user_avatar ? 
-      (typeof backendProfile.user_avatar === 'string' 
-        ? `data:${backendProfile.avatar_content_type || 'image/jpeg'};base64,${backendProfile.user_avatar}` 
-        : `data:${backendProfile.avatar_content_type || 'image/jpeg'};base64,${Buffer.from(backendProfile.user_avatar).toString('base64')}`) 
-      : undefined
+    // avatar_uri is an ipfs:// or ar:// URI from the storage provider (or a data: URI for migrated avatars)
+    avatarUrl: toAvatarUrl(backendProfile.avatar_uri)
   };
 };
 
@@ -208,14 +208,9 @@ export const getProfileByWalletAddress = async (walletAddress: string): Promise<
         return null;
       }
       
-      // Check if the profile has meaningful data beyond just the user_address
-      // If user_name is empty and there's no other significant data, treat as non-existent profile
-      if (!data.user_name && 
-          !data.user_bio && 
-          !data.github_account && 
-          !data.x_account && 
-          !data.tg_account && 
-          !data.user_avatar) {
+      // Check if the profile has meaningful data beyond just the wallet
+      // A profile that was never saved comes back with created_at 0
+      if (!data.created_at) {
         console.log('Default profile returned - user has not set up profile yet');
         return null;
       }
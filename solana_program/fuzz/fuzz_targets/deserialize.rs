@@ -0,0 +1,24 @@
+#![no_main]
+
+// Arbitrary bytes as instruction data and as DAO account data. Decoding may fail but must not panic
+// or allocate from an untrusted length prefix, and anything that decodes must encode back to the same bytes.
+// Account data may hold content entries from before the bounds, which decode cut, so there the check
+// is that the cut state encodes stably.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use libfuzzer_sys::fuzz_target;
+use solana_program::borsh::try_from_slice_unchecked;
+use turtle_dao::{DaoState, TurtleInstruction};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(instruction) = TurtleInstruction::try_from_slice(data) {
+        assert_eq!(instruction.try_to_vec().unwrap(), data);
+    }
+
+    // Account data is read with trailing zero padding, like the processors do
+    if let Ok(state) = try_from_slice_unchecked::<DaoState>(data) {
+        let encoded = state.try_to_vec().unwrap();
+        let again = DaoState::try_from_slice(&encoded).unwrap();
+        assert_eq!(again.try_to_vec().unwrap(), encoded);
+    }
+});
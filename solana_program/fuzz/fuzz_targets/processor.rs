@@ -0,0 +1,396 @@
+#![no_main]
+
+// Runs a sequence of instructions through `process_instruction` against one DAO account whose starting
+// state is either built from arbitrary fields or raw arbitrary bytes. Each step picks the clock, the caller,
+// whether it signs and whether the DAO account is owned by the program. Errors are expected; panics
+// (including arithmetic overflow, which fuzz builds check) are findings.
+//
+// The syscall stubs stand in for the runtime: they serve the clock and rent sysvars and apply the lamport
+// movement of system CreateAccount and Transfer CPIs, so the DAO ledger can be reconciled against the
+// balance. Nothing else about the CPI is executed (src/test.rs covers the real runtime on BanksClient).
+
+use arbitrary::Arbitrary;
+use borsh::BorshSerialize;
+use libfuzzer_sys::fuzz_target;
+use solana_program::{
+    account_info::AccountInfo,
+    borsh::try_from_slice_unchecked,
+    clock::Clock,
+    entrypoint::{ProgramResult, SUCCESS},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use std::sync::Mutex;
+use turtle_dao::{
+    content_key, pda, preset::DaoPreset, process_instruction, Content, DaoState, DepositorInfo, ExternalPlatform, FeeTier,
+    RoundResult, RoundState, TurtleInstruction, VoteInfo, VoteProposal, VoteStatus, VoteType,
+};
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+const DAO_SPACE: usize = 8000;
+const MAX_STEPS: usize = 16;
+
+static CLOCK: Mutex<i64> = Mutex::new(0);
+
+struct FuzzStubs;
+
+impl SyscallStubs for FuzzStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        // CreateAccount (0) and Transfer (2) both start with a u32 tag followed by the u64 lamports
+        let data = &instruction.data;
+        if instruction.program_id != system_program::id() || data.len() < 12 || !matches!(data[0], 0 | 2) {
+            return Ok(());
+        }
+        let lamports = u64::from_le_bytes(data[4..12].try_into().unwrap());
+        let find = |key: &Pubkey| account_infos.iter().find(|info| info.key == key);
+        if let (Some(from), Some(to)) = (find(&instruction.accounts[0].pubkey), find(&instruction.accounts[1].pubkey)) {
+            if from.key == to.key || from.lamports() < lamports {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            **from.try_borrow_mut_lamports()? -= lamports;
+            **to.try_borrow_mut_lamports()? += lamports;
+        }
+        Ok(())
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock { unix_timestamp: *CLOCK.lock().unwrap(), ..Clock::default() };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+}
+
+// A handful of wallets so that callers, depositors, voters and authors overlap
+fn key(index: u8) -> Pubkey {
+    Pubkey::new_from_array([index % 4 + 1; 32])
+}
+
+fn vote_type(value: u8) -> VoteType {
+    match value % 11 {
+        0 => VoteType::ChangeTimeLimit,
+        1 => VoteType::ChangeBaseFee,
+        2 => VoteType::ChangeAiModeration,
+        3 => VoteType::ContentQualityRating,
+        4 => VoteType::ChangeDepositResetsTimer,
+        5 => VoteType::ChangeFeeTiers,
+        6 => VoteType::ReplaceAdmin,
+        7 => VoteType::ChangeDepositCaps,
+        8 => VoteType::ChangeStreakBonus,
+        9 => VoteType::ChangeRetractPolicy,
+        _ => VoteType::ChangeRoundMinimum,
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzProposal {
+    proposal_id: u64,
+    proposer: u8,
+    vote_type: u8,
+    options: Vec<String>,
+    start_time: u64,
+    end_time: u64,
+    votes: Vec<(u8, u8, u64)>,
+    status: u8,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzDaoState {
+    is_initialized: bool,
+    dao_name: String,
+    initializer: u8,
+    time_limit: u64,
+    base_fee: u64,
+    ai_moderation: bool,
+    deposit_share: u8,
+    timeout_timestamp: u64,
+    total_deposit: u64,
+    depositors: Vec<(u8, u64, u64, u64)>,
+    contents: Vec<(u8, String, String, u64, u64)>,
+    vote_proposals: Vec<FuzzProposal>,
+    next_proposal_id: u64,
+    rent_reserve: u64,
+    bounty_pool: u64,
+    fee_pool: u64,
+    last_submitter: u8,
+    // Usually the key of one of the contents, so the timeout's winner lookup can match
+    last_content: Option<u8>,
+    deposit_resets_timer: bool,
+    fee_tiers: Vec<(u64, u8)>,
+    round: u64,
+    max_deposit_per_wallet: u64,
+    max_total_deposit: u64,
+    streak_days: u64,
+    streak_discount: u8,
+    retract_window: u64,
+    retract_refund: u8,
+    dormant: bool,
+    min_round_pot: u64,
+}
+
+impl From<FuzzDaoState> for DaoState {
+    fn from(state: FuzzDaoState) -> Self {
+        let mut dao_state = DaoState {
+            is_initialized: state.is_initialized,
+            dao_name: state.dao_name,
+            initializer: key(state.initializer),
+            time_limit: state.time_limit,
+            base_fee: state.base_fee,
+            ai_moderation: state.ai_moderation,
+            deposit_share: state.deposit_share,
+            timeout_timestamp: state.timeout_timestamp,
+            total_deposit: state.total_deposit,
+            depositors: state.depositors.into_iter()
+                .map(|(depositor, amount, timestamp, locked_until)| DepositorInfo {
+                    depositor: key(depositor),
+                    amount,
+                    timestamp,
+                    locked_until,
+                })
+                .collect(),
+            contents: state.contents.into_iter()
+                .map(|(author, text, image_uri, timestamp, vote_count)| Content {
+                    author: key(author),
+                    text: text.try_into().unwrap_or_default(),
+                    image_uri: image_uri.try_into().unwrap_or_default(),
+                    timestamp,
+                    vote_count,
+                })
+                .collect(),
+            vote_proposals: state.vote_proposals.into_iter()
+                .map(|proposal| VoteProposal {
+                    proposal_id: proposal.proposal_id,
+                    proposer: key(proposal.proposer),
+                    title: String::new(),
+                    description: String::new(),
+                    vote_type: vote_type(proposal.vote_type),
+                    options: proposal.options,
+                    start_time: proposal.start_time,
+                    end_time: proposal.end_time,
+                    votes: proposal.votes.into_iter()
+                        .map(|(voter, option_index, voting_power)| VoteInfo { voter: key(voter), option_index, voting_power })
+                        .collect(),
+                    status: match proposal.status % 3 {
+                        0 => VoteStatus::Active,
+                        1 => VoteStatus::Completed,
+                        _ => VoteStatus::Executed,
+                    },
+                })
+                .collect(),
+            next_proposal_id: state.next_proposal_id,
+            rent_reserve: state.rent_reserve,
+            bounty_pool: state.bounty_pool,
+            fee_pool: state.fee_pool,
+            last_submitter: key(state.last_submitter),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: state.deposit_resets_timer,
+            fee_tiers: state.fee_tiers.into_iter()
+                .map(|(min_deposit, discount)| FeeTier { min_deposit, discount })
+                .collect(),
+            round: state.round,
+            max_deposit_per_wallet: state.max_deposit_per_wallet,
+            max_total_deposit: state.max_total_deposit,
+            streak_days: state.streak_days,
+            streak_discount: state.streak_discount,
+            retract_window: state.retract_window,
+            retract_refund: state.retract_refund,
+            round_state: if state.dormant { RoundState::Dormant } else { RoundState::Active },
+            min_round_pot: state.min_round_pot,
+        };
+        dao_state.last_content = match state.last_content {
+            Some(index) if !dao_state.contents.is_empty() => {
+                content_key(&dao_state.contents[index as usize % dao_state.contents.len()])
+            }
+            _ => Pubkey::new_from_array([state.last_submitter; 32]),
+        };
+        dao_state
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+enum FuzzInstruction {
+    InitializeDao {
+        dao_name: String,
+        time_limit: u64,
+        base_fee: u64,
+        ai_moderation: bool,
+        deposit_share: u8,
+        deposit_resets_timer: bool,
+    },
+    Deposit { amount: u64 },
+    SubmitContent { text: String, image_uri: String },
+    CreateVote { title: String, description: String, vote_type: u8, options: Vec<String>, voting_period: u64 },
+    CastVote { proposal_id: u64, option_index: u8 },
+    ProcessTimeout,
+    // Picks one of the DAO's current contents, so the retraction can find it
+    RetractContent { content: u8 },
+    InitializeDaoWithPreset { dao_name: String, preset: u8 },
+    SubmitLinkedContent { text: String, image_uri: String, platform: u8, id: String },
+    // Picks a content like RetractContent
+    VoteContent { content: u8 },
+    Raw(Vec<u8>),
+}
+
+impl FuzzInstruction {
+    fn data(self, contents: &[Pubkey]) -> Vec<u8> {
+        let instruction = match self {
+            FuzzInstruction::InitializeDao { dao_name, time_limit, base_fee, ai_moderation, deposit_share, deposit_resets_timer } => {
+                TurtleInstruction::InitializeDao { dao_name, time_limit, base_fee, ai_moderation, deposit_share, deposit_resets_timer }
+            }
+            FuzzInstruction::Deposit { amount } => TurtleInstruction::Deposit { amount },
+            // Encoded by hand so strings over the bounds reach the program's decoding
+            FuzzInstruction::SubmitContent { text, image_uri } => return (2u8, text, image_uri).try_to_vec().unwrap(),
+            FuzzInstruction::CreateVote { title, description, vote_type: value, options, voting_period } => {
+                TurtleInstruction::CreateVote { title, description, vote_type: vote_type(value), options, voting_period }
+            }
+            FuzzInstruction::CastVote { proposal_id, option_index } => TurtleInstruction::CastVote { proposal_id, option_index },
+            FuzzInstruction::ProcessTimeout => TurtleInstruction::ProcessTimeout {},
+            FuzzInstruction::RetractContent { content } => TurtleInstruction::RetractContent { content: pick(contents, content) },
+            FuzzInstruction::InitializeDaoWithPreset { dao_name, preset } => TurtleInstruction::InitializeDaoWithPreset {
+                dao_name,
+                preset: DaoPreset::ALL[preset as usize % DaoPreset::ALL.len()],
+            },
+            FuzzInstruction::SubmitLinkedContent { text, image_uri, platform, id } => {
+                return (13u8, text, image_uri, platform % 2, id).try_to_vec().unwrap()
+            }
+            FuzzInstruction::VoteContent { content } => TurtleInstruction::VoteContent { content: pick(contents, content) },
+            FuzzInstruction::Raw(data) => return data,
+        };
+        instruction.try_to_vec().unwrap()
+    }
+}
+
+fn pick(contents: &[Pubkey], content: u8) -> Pubkey {
+    match contents.len() {
+        0 => Pubkey::new_from_array([content; 32]),
+        len => contents[content as usize % len],
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct Step {
+    unix_timestamp: i64,
+    caller: u8,
+    signed: bool,
+    dao_owned_by_program: bool,
+    // InitializeDao only passes its PDA check when the DAO account is the caller's PDA for that name
+    dao_is_pda: bool,
+    instruction: FuzzInstruction,
+}
+
+#[derive(Arbitrary, Debug)]
+enum StartingState {
+    Fields(FuzzDaoState),
+    Raw(Vec<u8>),
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    state: StartingState,
+    steps: Vec<Step>,
+}
+
+fuzz_target!(|input: Input| {
+    set_syscall_stubs(Box::new(FuzzStubs));
+
+    let mut data = vec![0; DAO_SPACE];
+    match input.state {
+        StartingState::Fields(state) => {
+            // States that do not fit the account cannot exist on chain
+            if DaoState::from(state).serialize(&mut data.as_mut_slice()).is_err() {
+                return;
+            }
+        }
+        StartingState::Raw(bytes) => {
+            let len = bytes.len().min(DAO_SPACE);
+            data[..len].copy_from_slice(&bytes[..len]);
+        }
+    }
+
+    // The DAO balance persists across steps like its data; the stubbed CPIs move lamports in
+    let mut dao_lamports = 0;
+    let default_dao = Pubkey::new_from_array([9; 32]);
+    for step in input.steps.into_iter().take(MAX_STEPS) {
+        *CLOCK.lock().unwrap() = step.unix_timestamp;
+        let caller = key(step.caller);
+        let dao = match (&step.instruction, step.dao_is_pda) {
+            // find_program_address only accepts seeds of up to 32 bytes
+            (FuzzInstruction::InitializeDao { dao_name, .. } | FuzzInstruction::InitializeDaoWithPreset { dao_name, .. }, true)
+                if dao_name.len() <= 32 =>
+            {
+                pda::find_dao_address(&PROGRAM_ID, &caller, dao_name).0
+            }
+            _ => default_dao,
+        };
+        let owner = match step.dao_owned_by_program {
+            true => PROGRAM_ID,
+            false => system_program::id(),
+        };
+        let system = system_program::id();
+        let native_loader = Pubkey::default();
+
+        // ProcessTimeout takes the RoundResult PDA of the DAO's current round before the system program, and the
+        // last submitter after it
+        let state = try_from_slice_unchecked::<DaoState>(&data).ok();
+        let round = state.as_ref().map_or(0, |state| state.round);
+        let winner = state.as_ref().map_or_else(Pubkey::default, |state| state.last_submitter);
+        let contents: Vec<Pubkey> = state.map_or_else(Vec::new, |state| state.contents.iter().map(content_key).collect());
+        let round_address = pda::find_round_address(&PROGRAM_ID, &dao, round).0;
+        let is_timeout = matches!(step.instruction, FuzzInstruction::ProcessTimeout);
+        // SubmitLinkedContent takes the claim PDA of its external reference after the system program
+        let claim_address = match &step.instruction {
+            FuzzInstruction::SubmitLinkedContent { platform, id, .. } => {
+                let platform = [ExternalPlatform::Twitter, ExternalPlatform::Farcaster][*platform as usize % 2];
+                Some(pda::find_claim_address(&PROGRAM_ID, &dao, platform, id).0)
+            }
+            _ => None,
+        };
+        // VoteContent takes the caller's vote PDA for the content before the system program
+        let vote_address = match &step.instruction {
+            FuzzInstruction::VoteContent { content } => {
+                Some(pda::find_content_vote_address(&PROGRAM_ID, &dao, &pick(&contents, *content), &caller).0)
+            }
+            _ => None,
+        };
+
+        let (mut caller_lamports, mut system_lamports, mut round_lamports) = (u64::MAX / 2, 1, 0);
+        let mut caller_data = Vec::new();
+        let mut system_data = Vec::new();
+        let mut round_data = vec![0; RoundResult::LEN];
+        let (mut claim_lamports, mut claim_data) = (0, Vec::new());
+        let (mut vote_lamports, mut vote_data) = (0, Vec::new());
+        let (mut winner_lamports, mut winner_data) = (0, Vec::new());
+        let mut accounts = vec![
+            AccountInfo::new(&caller, step.signed, true, &mut caller_lamports, &mut caller_data, &system, false, 0),
+            AccountInfo::new(&dao, false, true, &mut dao_lamports, &mut data, &owner, false, 0),
+            AccountInfo::new(&system, false, false, &mut system_lamports, &mut system_data, &native_loader, true, 0),
+        ];
+        if is_timeout {
+            let round_account = AccountInfo::new(&round_address, false, true, &mut round_lamports, &mut round_data, &PROGRAM_ID, false, 0);
+            accounts.insert(2, round_account);
+            accounts.push(AccountInfo::new(&winner, false, true, &mut winner_lamports, &mut winner_data, &system, false, 0));
+        }
+        if let Some(vote_address) = vote_address.as_ref() {
+            accounts.insert(2, AccountInfo::new(vote_address, false, true, &mut vote_lamports, &mut vote_data, &system, false, 0));
+        }
+        if let Some(claim_address) = claim_address.as_ref() {
+            accounts.push(AccountInfo::new(claim_address, false, true, &mut claim_lamports, &mut claim_data, &system, false, 0));
+        }
+        let _ = process_instruction(&PROGRAM_ID, &accounts, &step.instruction.data(&contents));
+    }
+});
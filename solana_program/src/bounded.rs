@@ -0,0 +1,153 @@
+//! Strings with a byte limit enforced by their Borsh encoding.
+//!
+//! `BoundedString<N>` is laid out exactly like `String` (u32 length, then UTF-8 bytes), so existing
+//! accounts and clients read and write it unchanged. Decoding checks the length prefix against `N`
+//! before allocating, so oversized instruction data fails without being copied, and every value has
+//! a known maximum size for account sizing. Account state written before a bound existed may hold
+//! longer values; `deserialize_stored` reads those explicitly by cutting them down to the bound.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Write},
+    ops::Deref,
+};
+
+use crate::{
+    error::TurtleError,
+    instruction::{MAX_EXTERNAL_ID_LEN, MAX_IMAGE_URI_LEN, MAX_TEXT_LEN},
+};
+
+/// Text of a content submission
+pub type BoundedText = BoundedString<MAX_TEXT_LEN>;
+/// Image URI of a content submission
+pub type BoundedUri = BoundedString<MAX_IMAGE_URI_LEN>;
+/// Post id on an external platform (see `ExternalRef`)
+pub type BoundedExternalId = BoundedString<MAX_EXTERNAL_ID_LEN>;
+
+/// A `String` of at most `N` bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct BoundedString<const N: usize>(String);
+
+impl<const N: usize> BoundedString<N> {
+    /// Largest Borsh encoding of the type: the length prefix and `N` bytes
+    pub const MAX_LEN: usize = 4 + N;
+
+    /// `None` if `value` is longer than `N` bytes
+    pub fn new(value: String) -> Option<Self> {
+        (value.len() <= N).then_some(Self(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /// Decodes a value stored in an account. One written before the bound existed may be longer: it is cut at
+    /// the last character boundary within `N` bytes instead of failing. The bytes read are limited by what the
+    /// account holds, not by the length prefix.
+    pub fn deserialize_stored<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = u32::deserialize_reader(reader)? as usize;
+        let mut bytes = Vec::with_capacity(len.min(N));
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "string runs past the end of the data"));
+        }
+        let mut value = String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut end = value.len().min(N);
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        value.truncate(end);
+        Ok(Self(value))
+    }
+}
+
+impl<const N: usize> Deref for BoundedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const N: usize> TryFrom<String> for BoundedString<N> {
+    type Error = TurtleError;
+
+    fn try_from(value: String) -> Result<Self, TurtleError> {
+        Self::new(value).ok_or(TurtleError::InvalidContent)
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for BoundedString<N> {
+    type Error = TurtleError;
+
+    fn try_from(value: &str) -> Result<Self, TurtleError> {
+        Self::try_from(value.to_string())
+    }
+}
+
+impl<const N: usize> From<BoundedString<N>> for String {
+    fn from(value: BoundedString<N>) -> String {
+        value.0
+    }
+}
+
+impl<const N: usize> BorshSerialize for BoundedString<N> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl<const N: usize> BorshDeserialize for BoundedString<N> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = u32::deserialize_reader(reader)? as usize;
+        if len > N {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("string of {} bytes, at most {} allowed", len, N)));
+        }
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes)?;
+        String::from_utf8(bytes)
+            .map(Self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_layout_as_string() {
+        let bounded = BoundedString::<5>::try_from("hello").unwrap();
+        assert_eq!(bounded.try_to_vec().unwrap(), "hello".to_string().try_to_vec().unwrap());
+        assert_eq!(BoundedString::<5>::try_from_slice(&[5, 0, 0, 0, b'h', b'e', b'l', b'l', b'o']).unwrap(), bounded);
+        assert_eq!(BoundedString::<5>::MAX_LEN, 9);
+    }
+
+    #[test]
+    fn test_rejects_oversized() {
+        assert_eq!(BoundedString::<4>::try_from("hello"), Err(TurtleError::InvalidContent));
+        // A length prefix over the bound fails before the claimed 4 GiB would be allocated
+        let error = BoundedString::<4>::try_from_slice(&[0xff, 0xff, 0xff, 0xff]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(BoundedString::<4>::try_from_slice(&[2, 0, 0, 0, 0xff, 0xfe]).is_err());
+    }
+
+    #[test]
+    fn test_stored_values_over_the_bound_are_cut() {
+        let stored = |value: &str| BoundedString::<4>::deserialize_stored(&mut value.to_string().try_to_vec().unwrap().as_slice());
+        assert_eq!(stored("turt").unwrap().as_str(), "turt");
+        assert_eq!(stored("turtle").unwrap().as_str(), "turt");
+        // Never inside a character: "거북" is two 3-byte characters
+        assert_eq!(stored("거북").unwrap().as_str(), "거");
+        // A length prefix longer than the data is still an error
+        let error = BoundedString::<4>::deserialize_stored(&mut &[0xff, 0xff, 0xff, 0xff, b'a'][..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,164 @@
+// Compute-unit budgets per instruction. Only the SBF build is metered, so these checks run under
+// `cargo test-sbf` (it builds target/deploy/turtle_dao.so and sets SBF_OUT_DIR); a plain `cargo test` skips them.
+//
+// The test prints what every instruction consumed and fails if one goes over its budget. Budgets are ceilings
+// with headroom over the printed numbers: when an instruction legitimately gets heavier, raise its budget in the
+// same change, and keep everything far below the 200k default per-instruction limit.
+
+use crate::{
+    fixtures::{TestDao, TurtleFixture, FIXTURE_NOW, TIME_LIMIT},
+    instruction::turtle_instruction,
+    pda, ExternalPlatform, VoteType, MIN_VOTING_PERIOD,
+};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const BUDGETS: &[(&str, u64)] = &[
+    ("InitializeDao", 40_000),
+    ("Deposit", 30_000),
+    // Derives and creates (or updates) the author's SubmissionStreak PDA
+    ("SubmitContent", 45_000),
+    // SubmitContent plus deriving and creating the post's ExternalClaim PDA
+    ("SubmitLinkedContent", 60_000),
+    ("CreateVote", 30_000),
+    ("CastVote", 30_000),
+    // Derives and creates the RoundResult PDA (three system CPIs when it was prefunded) and writes its candidates
+    ("ProcessTimeout", 55_000),
+    // Derives and creates the voter's ContentVote PDA
+    ("VoteContent", 40_000),
+    // Checks the RoundResult PDA and moves lamports directly, without CPIs
+    ("DistributeQualityRewards", 30_000),
+    // The DAO account is deserialized and written back whole, so cost grows with what it holds
+    ("SubmitContent (full DAO)", 135_000),
+    ("SubmitLinkedContent (full DAO)", 150_000),
+    ("CastVote (full DAO)", 120_000),
+    ("ProcessTimeout (full DAO)", 140_000),
+    // Treasury instructions decode only the DAO's leading fields, so a full DAO must not cost them more than
+    // their own CPIs and PDA lookups
+    ("InitializeTreasuryAsset (full DAO)", 50_000),
+    ("DepositAsset (full DAO)", 30_000),
+    ("WithdrawAsset (full DAO)", 30_000),
+    ("DistributeAssetReward (full DAO)", 40_000),
+];
+
+fn metered() -> bool {
+    std::env::var_os("SBF_OUT_DIR").is_some() || std::env::var_os("BPF_OUT_DIR").is_some()
+}
+
+#[derive(Default)]
+struct Report(Vec<(&'static str, u64)>);
+
+impl Report {
+    fn record(&mut self, name: &'static str, units: Result<u64, TransactionError>) {
+        let units = units.unwrap_or_else(|error| panic!("{} failed: {:?}", name, error));
+        self.0.push((name, units));
+    }
+
+    fn check(self) {
+        let mut over = Vec::new();
+        for (name, units) in self.0 {
+            let budget = BUDGETS.iter().find(|(budget_name, _)| *budget_name == name).map(|(_, budget)| *budget).unwrap();
+            println!("{:<36} {:>7} CU  (budget {})", name, units, budget);
+            if units > budget {
+                over.push(name);
+            }
+        }
+        assert!(over.is_empty(), "over compute budget: {:?}", over);
+    }
+}
+
+#[tokio::test]
+async fn test_compute_unit_budgets() {
+    if !metered() {
+        println!("skipped: compute units are only metered under cargo test-sbf");
+        return;
+    }
+    let mut report = Report::default();
+
+    // One of each instruction on a fresh DAO
+    let mut test = TestDao::start_sbf().await;
+    let program_id = test.program_id;
+    let initializer = test.funded_keypair(1_000_000_000).await;
+    let instruction = turtle_instruction::initialize_dao(&program_id, &initializer.pubkey(), "turtles".to_string(), TIME_LIMIT, 10, false, 20, false).unwrap();
+    report.record("InitializeDao", test.send_metered(&[instruction], &[&initializer]).await);
+    let dao = pda::find_dao_address(&program_id, &initializer.pubkey(), "turtles").0;
+
+    let user = test.funded_keypair(1_000_000_000).await;
+    let instruction = turtle_instruction::deposit(&program_id, &user.pubkey(), &dao, 100_000_000).unwrap();
+    report.record("Deposit", test.send_metered(&[instruction], &[&user]).await);
+    let instruction = turtle_instruction::submit_content(&program_id, &user.pubkey(), &dao, "a".repeat(200), String::new()).unwrap();
+    report.record("SubmitContent", test.send_metered(&[instruction], &[&user]).await);
+    let instruction = turtle_instruction::submit_linked_content(
+        &program_id, &user.pubkey(), &dao, "a".repeat(200), String::new(), ExternalPlatform::Twitter, "1750000000000000000".to_string(),
+    ).unwrap();
+    report.record("SubmitLinkedContent", test.send_metered(&[instruction], &[&user]).await);
+    let voter = test.funded_keypair(1_000_000_000).await;
+    let instruction = turtle_instruction::deposit(&program_id, &voter.pubkey(), &dao, 100_000_000).unwrap();
+    test.send(&[instruction], &[&voter]).await.unwrap();
+    let content = test.dao_state(&dao).await.last_content;
+    let instruction = turtle_instruction::vote_content(&program_id, &voter.pubkey(), &dao, &content).unwrap();
+    report.record("VoteContent", test.send_metered(&[instruction], &[&voter]).await);
+    let options = vec!["5%".to_string(), "15%".to_string()];
+    let instruction = turtle_instruction::create_vote(
+        &program_id, &user.pubkey(), &dao, "fee".to_string(), String::new(), VoteType::ChangeBaseFee, options, MIN_VOTING_PERIOD,
+    ).unwrap();
+    report.record("CreateVote", test.send_metered(&[instruction], &[&user]).await);
+    let instruction = turtle_instruction::cast_vote(&program_id, &user.pubkey(), &dao, 0, 1).unwrap();
+    report.record("CastVote", test.send_metered(&[instruction], &[&user]).await);
+    let deadline = test.dao_state(&dao).await.timeout_timestamp;
+    test.warp_to_timestamp(deadline).await;
+    let instruction = turtle_instruction::process_timeout(&program_id, &user.pubkey(), &dao, 0, Some(&user.pubkey())).unwrap();
+    report.record("ProcessTimeout", test.send_metered(&[instruction], &[&user]).await);
+    let instruction = turtle_instruction::distribute_quality_rewards(&program_id, &initializer.pubkey(), &dao, 0, vec![user.pubkey()], vec![100]).unwrap();
+    report.record("DistributeQualityRewards", test.send_metered(&[instruction], &[&initializer]).await);
+
+    // The same against a DAO holding 20 depositors, 15 long contents and 5 voted proposals (~6.5 KB of 8 KB)
+    let wallets: Vec<Keypair> = (0..20).map(|_| Keypair::new()).collect();
+    let mut fixture = TurtleFixture::new();
+    for wallet in &wallets {
+        fixture = fixture.with_depositor(&wallet.pubkey(), 50_000_000);
+    }
+    for wallet in wallets.iter().take(15) {
+        fixture = fixture.with_content(&wallet.pubkey(), &"b".repeat(200));
+    }
+    for proposal_id in 0..5 {
+        fixture = fixture.with_proposal(&wallets[0].pubkey(), VoteType::ChangeBaseFee, &["5%", "15%"]);
+        for wallet in wallets.iter().skip(1).take(4) {
+            fixture = fixture.with_vote(&wallet.pubkey(), proposal_id, 0);
+        }
+    }
+    let admin = fixture.initializer().insecure_clone();
+    let (mut test, dao) = fixture.start_sbf().await;
+    let program_id = test.program_id;
+    let author = &wallets[19];
+    let instruction = turtle_instruction::submit_content(&program_id, &author.pubkey(), &dao, "c".repeat(200), String::new()).unwrap();
+    report.record("SubmitContent (full DAO)", test.send_metered(&[instruction], &[author]).await);
+    let instruction = turtle_instruction::submit_linked_content(
+        &program_id, &author.pubkey(), &dao, "c".repeat(200), String::new(), ExternalPlatform::Farcaster, "0xabc".to_string(),
+    ).unwrap();
+    report.record("SubmitLinkedContent (full DAO)", test.send_metered(&[instruction], &[author]).await);
+    let instruction = turtle_instruction::cast_vote(&program_id, &author.pubkey(), &dao, 4, 1).unwrap();
+    report.record("CastVote (full DAO)", test.send_metered(&[instruction], &[author]).await);
+    // Past the voting period, so every proposal is tallied and executed
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    let instruction = turtle_instruction::process_timeout(&program_id, &author.pubkey(), &dao, 0, Some(&author.pubkey())).unwrap();
+    report.record("ProcessTimeout (full DAO)", test.send_metered(&[instruction], &[author]).await);
+
+    let mint = test.create_mint().await;
+    let instruction = turtle_instruction::initialize_treasury_asset(&program_id, &admin.pubkey(), &dao, &mint).unwrap();
+    report.record("InitializeTreasuryAsset (full DAO)", test.send_metered(&[instruction], &[&admin]).await);
+    let admin_tokens = test.create_token_account(&mint, &admin.pubkey()).await;
+    test.mint_tokens(&mint, &admin_tokens, 1_000).await;
+    let instruction = turtle_instruction::deposit_asset(&program_id, &admin.pubkey(), &dao, &mint, &admin_tokens, 600).unwrap();
+    report.record("DepositAsset (full DAO)", test.send_metered(&[instruction], &[&admin]).await);
+    let instruction = turtle_instruction::withdraw_asset(&program_id, &admin.pubkey(), &dao, &mint, &admin_tokens, 100).unwrap();
+    report.record("WithdrawAsset (full DAO)", test.send_metered(&[instruction], &[&admin]).await);
+    // The author's submission won round 0
+    let winner_tokens = test.create_token_account(&mint, &author.pubkey()).await;
+    let instruction = turtle_instruction::distribute_asset_reward(&program_id, &admin.pubkey(), &dao, &mint, 0, &winner_tokens, 200).unwrap();
+    report.record("DistributeAssetReward (full DAO)", test.send_metered(&[instruction], &[&admin]).await);
+
+    report.check();
+}
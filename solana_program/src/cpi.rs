@@ -0,0 +1,82 @@
+//! Helpers for other on-chain programs that call into Turtle.
+//!
+//! Enable with the `cpi` feature (it implies `no-entrypoint`). Each wrapper builds the
+//! instruction with `instruction::turtle_instruction` and invokes it with the matching
+//! `AccountInfo`s in order. Invalid arguments fail before the CPI with a `TurtleError`.
+//! Pass `signer_seeds` when the signer is a PDA of the calling program.
+
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed};
+
+use crate::instruction::turtle_instruction;
+
+/// Deposit lamports from `depositor` into the DAO bounty pool
+pub fn deposit<'a>(
+    turtle_program: &AccountInfo<'a>,
+    depositor: &AccountInfo<'a>,
+    dao_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &turtle_instruction::deposit(turtle_program.key, depositor.key, dao_account.key, amount)?,
+        &[depositor.clone(), dao_account.clone(), system_program.clone(), turtle_program.clone()],
+        signer_seeds,
+    )
+}
+
+/// Submit content as `author` (must already be a depositor of the DAO)
+pub fn submit_content<'a>(
+    turtle_program: &AccountInfo<'a>,
+    author: &AccountInfo<'a>,
+    dao_account: &AccountInfo<'a>,
+    text: String,
+    image_uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &turtle_instruction::submit_content(turtle_program.key, author.key, dao_account.key, text, image_uri)?,
+        &[author.clone(), dao_account.clone(), turtle_program.clone()],
+        signer_seeds,
+    )
+}
+
+/// Cast a governance vote as `voter` (must already be a depositor of the DAO)
+pub fn cast_vote<'a>(
+    turtle_program: &AccountInfo<'a>,
+    voter: &AccountInfo<'a>,
+    dao_account: &AccountInfo<'a>,
+    proposal_id: u64,
+    option_index: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &turtle_instruction::cast_vote(turtle_program.key, voter.key, dao_account.key, proposal_id, option_index)?,
+        &[voter.clone(), dao_account.clone(), turtle_program.clone()],
+        signer_seeds,
+    )
+}
+
+/// Process an expired round. Anyone can crank this, e.g. a keeper program.
+/// `round_result` is the PDA for the DAO's current `round`, paid for by `caller` when the round has a winner,
+/// and `winner` the DAO's `last_submitter`, which receives the reward (`None` if the round has no submission).
+#[allow(clippy::too_many_arguments)]
+pub fn process_timeout<'a>(
+    turtle_program: &AccountInfo<'a>,
+    caller: &AccountInfo<'a>,
+    dao_account: &AccountInfo<'a>,
+    round_result: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    winner: Option<&AccountInfo<'a>>,
+    round: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let mut accounts = vec![caller.clone(), dao_account.clone(), round_result.clone(), system_program.clone()];
+    accounts.extend(winner.cloned());
+    accounts.push(turtle_program.clone());
+    invoke_signed(
+        &turtle_instruction::process_timeout(turtle_program.key, caller.key, dao_account.key, round, winner.map(|winner| winner.key))?,
+        &accounts,
+        signer_seeds,
+    )
+}
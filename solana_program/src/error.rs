@@ -1,7 +1,7 @@
 use solana_program::program_error::ProgramError;
 use thiserror::Error;
 
-#[derive(Error, Debug, Copy, Clone)]
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum TurtleError {
     #[error("Invalid instruction")]
     InvalidInstruction,
@@ -38,6 +38,36 @@ pub enum TurtleError {
     
     #[error("Invalid Distribution")]
     InvalidDistribution,
+
+    #[error("Ledger Mismatch")]
+    LedgerMismatch,
+
+    #[error("Wallet Deposit Cap Exceeded")]
+    WalletDepositCapExceeded,
+
+    #[error("Total Deposit Cap Exceeded")]
+    TotalDepositCapExceeded,
+
+    #[error("Insufficient Treasury Balance")]
+    InsufficientTreasuryBalance,
+
+    #[error("Round Already Paid")]
+    RoundAlreadyPaid,
+
+    #[error("Retract Window Closed")]
+    RetractWindowClosed,
+
+    #[error("External Reference Already Claimed")]
+    ExternalRefClaimed,
+
+    #[error("Round Dormant")]
+    RoundDormant,
+
+    #[error("Content Already Voted")]
+    ContentAlreadyVoted,
+
+    #[error("Ineligible Creator")]
+    IneligibleCreator,
 }
 
 impl From<TurtleError> for ProgramError {
@@ -0,0 +1,540 @@
+// Test fixtures: `TestDao` drives the program on solana-program-test (BanksClient), and `TurtleFixture`
+// writes a DAO account with depositors, contents, proposals and votes already in place, so a test can start
+// from the state it cares about instead of sending the transactions that lead there.
+//
+//     let voter = Keypair::new();
+//     let fixture = TurtleFixture::new().with_depositor(&voter.pubkey(), 250).with_proposal(&voter.pubkey(), VoteType::ChangeBaseFee, &["5%", "15%"]);
+//     let (mut test, dao) = fixture.start().await;
+//
+// `build()` and `account()` give the same state without a runtime, for tests that call the processors directly.
+
+use crate::{
+    content_key, instruction::turtle_instruction, ledger_total, try_from_slice_unchecked, open_round_if_funded, pda, process_instruction, submission_fee,
+    Content, ContentVote, DaoState, DepositorInfo, ExternalClaim, ExternalPlatform, FeeTier, RoundResult, RoundState, SubmissionStreak,
+    TreasuryAsset, TurtleInstruction, VoteInfo, VoteProposal, VoteStatus, VoteType, MIN_VOTING_PERIOD,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+pub const TIME_LIMIT: u64 = 3_600;
+pub const DAO_SPACE: usize = 8000;
+pub const DAO_NAME: &str = "turtles";
+// Clock of a fixture DAO unless `at` says otherwise
+pub const FIXTURE_NOW: u64 = 1_700_000_000;
+// Balance of every wallet a fixture funds
+pub const WALLET_LAMPORTS: u64 = 1_000_000_000;
+
+pub struct TestDao {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+    // Program-owned account that was never initialized
+    pub uninitialized: Pubkey,
+}
+
+impl TestDao {
+    pub async fn start() -> Self {
+        Self::launch(Pubkey::new_unique(), Vec::new(), false).await
+    }
+
+    pub async fn start_sbf() -> Self {
+        Self::launch(Pubkey::new_unique(), Vec::new(), true).await
+    }
+
+    // `sbf` loads target/deploy/turtle_dao.so (SBF_OUT_DIR) instead of the native processor; only SBF runs are metered
+    async fn launch(program_id: Pubkey, accounts: Vec<(Pubkey, Account)>, sbf: bool) -> Self {
+        let uninitialized = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new("turtle_dao", program_id, processor!(process_instruction));
+        program_test.prefer_bpf(sbf);
+        program_test.add_account(
+            uninitialized,
+            Account { lamports: WALLET_LAMPORTS, data: vec![0; DAO_SPACE], owner: program_id, ..Account::default() },
+        );
+        for (address, account) in accounts {
+            program_test.add_account(address, account);
+        }
+        let context = program_test.start_with_context().await;
+        Self { context, program_id, uninitialized }
+    }
+
+    // Each transaction gets a fresh blockhash so retrying an identical instruction is not deduplicated
+    async fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(instructions, Some(&self.context.payer.pubkey()), &all_signers, blockhash)
+    }
+
+    pub async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), TransactionError> {
+        let transaction = self.transaction(instructions, signers).await;
+        self.context.banks_client.process_transaction(transaction).await.map_err(|error| error.unwrap())
+    }
+
+    // Like `send`, returning the compute units the transaction consumed
+    pub async fn send_metered(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<u64, TransactionError> {
+        let transaction = self.transaction(instructions, signers).await;
+        let outcome = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        outcome.result?;
+        Ok(outcome.metadata.map_or(0, |metadata| metadata.compute_units_consumed))
+    }
+
+    pub async fn funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        self.send(&[system_instruction::transfer(&payer, &keypair.pubkey(), lamports)], &[]).await.unwrap();
+        keypair
+    }
+
+    pub async fn now(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp as u64
+    }
+
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: u64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    pub async fn dao_state(&mut self, dao: &Pubkey) -> DaoState {
+        let account = self.context.banks_client.get_account(*dao).await.unwrap().unwrap();
+        try_from_slice_unchecked::<DaoState>(&account.data).unwrap()
+    }
+
+    // None until ProcessTimeout closes that round with a winner
+    pub async fn round_result(&mut self, dao: &Pubkey, round: u64) -> Option<RoundResult> {
+        let (address, _) = pda::find_round_address(&self.program_id, dao, round);
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+        Some(RoundResult::unpack(&account.data).unwrap())
+    }
+
+    pub async fn treasury_asset(&mut self, dao: &Pubkey, mint: &Pubkey) -> TreasuryAsset {
+        let (address, _) = pda::find_treasury_asset_address(&self.program_id, dao, mint);
+        let account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        TreasuryAsset::try_from_slice(&account.data).unwrap()
+    }
+
+    // None until the author's first submission with a streak account
+    pub async fn streak(&mut self, dao: &Pubkey, author: &Pubkey) -> Option<SubmissionStreak> {
+        let (address, _) = pda::find_streak_address(&self.program_id, dao, author);
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+        Some(SubmissionStreak::try_from_slice(&account.data).unwrap())
+    }
+
+    // None until a linked submission claims the post (and again once its retraction closes the claim)
+    pub async fn external_claim(&mut self, dao: &Pubkey, platform: ExternalPlatform, id: &str) -> Option<ExternalClaim> {
+        let (address, _) = pda::find_claim_address(&self.program_id, dao, platform, id);
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+        Some(try_from_slice_unchecked::<ExternalClaim>(&account.data).unwrap())
+    }
+
+    // None until the voter votes for that content
+    pub async fn content_vote(&mut self, dao: &Pubkey, content: &Pubkey, voter: &Pubkey) -> Option<ContentVote> {
+        let (address, _) = pda::find_content_vote_address(&self.program_id, dao, content, voter);
+        let account = self.context.banks_client.get_account(address).await.unwrap()?;
+        Some(ContentVote::try_from_slice(&account.data).unwrap())
+    }
+
+    // A mint whose authority is the test payer (program-test loads the SPL Token program)
+    pub async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &payer, &mint.pubkey(), rent.minimum_balance(spl_token::state::Mint::LEN), spl_token::state::Mint::LEN as u64, &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint2(&spl_token::id(), &mint.pubkey(), &payer, None, 6).unwrap(),
+        ];
+        self.send(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &payer, &account.pubkey(), rent.minimum_balance(spl_token::state::Account::LEN), spl_token::state::Account::LEN as u64, &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(&spl_token::id(), &account.pubkey(), mint, owner).unwrap(),
+        ];
+        self.send(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    pub async fn mint_tokens(&mut self, mint: &Pubkey, destination: &Pubkey, amount: u64) {
+        let payer = self.context.payer.pubkey();
+        let instruction = spl_token::instruction::mint_to(&spl_token::id(), mint, destination, &payer, &[], amount).unwrap();
+        self.send(&[instruction], &[]).await.unwrap();
+    }
+
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self.context.banks_client.get_account(*token_account).await.unwrap().unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    /// Sends `address` the rent-exempt minimum of an empty account before the program creates anything there, as
+    /// anyone can; returns the lamports sent
+    pub async fn prefund(&mut self, address: &Pubkey) -> u64 {
+        let lamports = self.context.banks_client.get_rent().await.unwrap().minimum_balance(0);
+        let payer = self.context.payer.pubkey();
+        self.send(&[system_instruction::transfer(&payer, address, lamports)], &[]).await.unwrap();
+        lamports
+    }
+
+    pub async fn rent_exempt_minimum(&mut self) -> u64 {
+        self.context.banks_client.get_rent().await.unwrap().minimum_balance(DAO_SPACE)
+    }
+
+    pub async fn initialize_dao(&mut self, base_fee: u64, deposit_share: u8) -> (Keypair, Pubkey) {
+        let initializer = self.funded_keypair(WALLET_LAMPORTS).await;
+        let instruction = turtle_instruction::initialize_dao(
+            &self.program_id, &initializer.pubkey(), DAO_NAME.to_string(), TIME_LIMIT, base_fee, false, deposit_share, false,
+        ).unwrap();
+        self.send(&[instruction], &[&initializer]).await.unwrap();
+        let (dao, _) = pda::find_dao_address(&self.program_id, &initializer.pubkey(), DAO_NAME);
+        (initializer, dao)
+    }
+
+    pub async fn deposit(&mut self, depositor: &Keypair, dao: &Pubkey, amount: u64) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::deposit(&self.program_id, &depositor.pubkey(), dao, amount).unwrap();
+        self.send(&[instruction], &[depositor]).await
+    }
+
+    pub async fn submit_content(&mut self, author: &Keypair, dao: &Pubkey, text: &str) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::submit_content(
+            &self.program_id, &author.pubkey(), dao, text.to_string(), String::new(),
+        ).unwrap();
+        self.send(&[instruction], &[author]).await
+    }
+
+    pub async fn submit_content_with_streak(&mut self, author: &Keypair, dao: &Pubkey, text: &str) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::submit_content_with_streak(
+            &self.program_id, &author.pubkey(), dao, text.to_string(), String::new(),
+        ).unwrap();
+        self.send(&[instruction], &[author]).await
+    }
+
+    pub async fn submit_linked_content(
+        &mut self,
+        author: &Keypair,
+        dao: &Pubkey,
+        text: &str,
+        platform: ExternalPlatform,
+        id: &str,
+    ) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::submit_linked_content(
+            &self.program_id, &author.pubkey(), dao, text.to_string(), String::new(), platform, id.to_string(),
+        ).unwrap();
+        self.send(&[instruction], &[author]).await
+    }
+
+    pub async fn retract_content(&mut self, author: &Keypair, dao: &Pubkey, content: &Pubkey) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::retract_content(&self.program_id, &author.pubkey(), dao, content).unwrap();
+        self.send(&[instruction], &[author]).await
+    }
+
+    pub async fn vote_content(&mut self, voter: &Keypair, dao: &Pubkey, content: &Pubkey) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::vote_content(&self.program_id, &voter.pubkey(), dao, content).unwrap();
+        self.send(&[instruction], &[voter]).await
+    }
+
+    pub async fn create_vote(
+        &mut self,
+        proposer: &Keypair,
+        dao: &Pubkey,
+        vote_type: VoteType,
+        options: &[&str],
+    ) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::create_vote(
+            &self.program_id,
+            &proposer.pubkey(),
+            dao,
+            "proposal".to_string(),
+            String::new(),
+            vote_type,
+            options.iter().map(|option| option.to_string()).collect(),
+            MIN_VOTING_PERIOD,
+        ).unwrap();
+        self.send(&[instruction], &[proposer]).await
+    }
+
+    pub async fn cast_vote(&mut self, voter: &Keypair, dao: &Pubkey, proposal_id: u64, option_index: u8) -> Result<(), TransactionError> {
+        let instruction = turtle_instruction::cast_vote(&self.program_id, &voter.pubkey(), dao, proposal_id, option_index).unwrap();
+        self.send(&[instruction], &[voter]).await
+    }
+
+    pub async fn process_timeout(&mut self, dao: &Pubkey) -> Result<(), TransactionError> {
+        let caller = self.context.payer.pubkey();
+        let state = self.dao_state(dao).await;
+        let winner = (state.last_submitter != Pubkey::default()).then_some(state.last_submitter);
+        let instruction = turtle_instruction::process_timeout(&self.program_id, &caller, dao, state.round, winner.as_ref()).unwrap();
+        self.send(&[instruction], &[]).await
+    }
+
+    // Instruction the builders refuse to create, for checking the processor's own validation
+    pub fn raw_instruction(&self, data: TurtleInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction { program_id: self.program_id, accounts, data: data.try_to_vec().unwrap() }
+    }
+}
+
+/// Builds an initialized DAO account the way the instructions would have left it at `now`.
+/// Records are stamped with the clock current when they are added, so call `at` first.
+pub struct TurtleFixture {
+    program_id: Pubkey,
+    initializer: Keypair,
+    now: u64,
+    state: DaoState,
+    wallets: Vec<Pubkey>,
+}
+
+impl Default for TurtleFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TurtleFixture {
+    /// A DAO named `DAO_NAME` with a `TIME_LIMIT` round, a 10% base fee and a 20% deposit share. Like a DAO
+    /// `InitializeDao` just created it is dormant; the first `with_depositor` opens its round.
+    pub fn new() -> Self {
+        let initializer = Keypair::new();
+        let state = DaoState {
+            is_initialized: true,
+            dao_name: DAO_NAME.to_string(),
+            initializer: initializer.pubkey(),
+            time_limit: TIME_LIMIT,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 20,
+            timeout_timestamp: 0,
+            total_deposit: 0,
+            depositors: Vec::new(),
+            contents: Vec::new(),
+            vote_proposals: Vec::new(),
+            next_proposal_id: 0,
+            // program-test genesis uses the default rent
+            rent_reserve: Rent::default().minimum_balance(DAO_SPACE),
+            bounty_pool: 0,
+            fee_pool: 0,
+            last_submitter: Pubkey::default(),
+            last_content: Pubkey::default(),
+            deposit_resets_timer: false,
+            fee_tiers: Vec::new(),
+            round: 0,
+            max_deposit_per_wallet: 0,
+            max_total_deposit: 0,
+            streak_days: 0,
+            streak_discount: 0,
+            retract_window: 0,
+            retract_refund: 0,
+            round_state: RoundState::Dormant,
+            min_round_pot: 0,
+        };
+        Self { program_id: Pubkey::new_unique(), initializer, now: FIXTURE_NOW, state, wallets: Vec::new() }
+    }
+
+    pub fn at(mut self, now: u64) -> Self {
+        self.now = now;
+        if self.state.round_state == RoundState::Active {
+            self.state.timeout_timestamp = now + self.state.time_limit;
+        }
+        self
+    }
+
+    pub fn with_dao(mut self, time_limit: u64, base_fee: u64, ai_moderation: bool, deposit_share: u8) -> Self {
+        self.state.time_limit = time_limit;
+        self.state.base_fee = base_fee;
+        self.state.ai_moderation = ai_moderation;
+        self.state.deposit_share = deposit_share;
+        if self.state.round_state == RoundState::Active {
+            self.state.timeout_timestamp = self.now + time_limit;
+        }
+        self
+    }
+
+    /// Submission fee discount tiers, as a `ChangeFeeTiers` vote would have set them
+    pub fn with_fee_tiers(mut self, fee_tiers: &[FeeTier]) -> Self {
+        self.state.fee_tiers = fee_tiers.to_vec();
+        self
+    }
+
+    /// Deposit caps, as a `ChangeDepositCaps` vote would have set them (0 means no cap)
+    pub fn with_deposit_caps(mut self, max_deposit_per_wallet: u64, max_total_deposit: u64) -> Self {
+        self.state.max_deposit_per_wallet = max_deposit_per_wallet;
+        self.state.max_total_deposit = max_total_deposit;
+        self
+    }
+
+    /// Streak bonus, as a `ChangeStreakBonus` vote would have set it (0 days means none)
+    pub fn with_streak_bonus(mut self, streak_days: u64, streak_discount: u8) -> Self {
+        self.state.streak_days = streak_days;
+        self.state.streak_discount = streak_discount;
+        self
+    }
+
+    /// Retraction policy, as a `ChangeRetractPolicy` vote would have set it (0 seconds means off)
+    pub fn with_retract_policy(mut self, retract_window: u64, retract_refund: u8) -> Self {
+        self.state.retract_window = retract_window;
+        self.state.retract_refund = retract_refund;
+        self
+    }
+
+    /// Pot a dormant round needs before it opens, as a `ChangeRoundMinimum` vote would have set it
+    pub fn with_round_minimum(mut self, min_round_pot: u64) -> Self {
+        self.state.min_round_pot = min_round_pot;
+        self
+    }
+
+    /// The `deposit_resets_timer` game rule, as passed to `InitializeDao`
+    pub fn with_deposit_resets_timer(mut self, deposit_resets_timer: bool) -> Self {
+        self.state.deposit_resets_timer = deposit_resets_timer;
+        self
+    }
+
+    /// Same bookkeeping as `Deposit`; the wallet is also funded when the fixture starts
+    pub fn with_depositor(mut self, wallet: &Pubkey, amount: u64) -> Self {
+        let locked_until = self.now + self.state.time_limit;
+        match self.state.depositors.iter_mut().find(|info| info.depositor == *wallet) {
+            Some(info) => {
+                info.amount += amount;
+                info.timestamp = self.now;
+                info.locked_until = locked_until;
+            }
+            None => self.state.depositors.push(DepositorInfo { depositor: *wallet, amount, timestamp: self.now, locked_until }),
+        }
+        if self.state.deposit_resets_timer && self.state.round_state == RoundState::Active {
+            self.state.timeout_timestamp = self.now + self.state.time_limit;
+        }
+        self.state.total_deposit += amount;
+        self.state.bounty_pool += amount;
+        open_round_if_funded(&mut self.state, self.now);
+        self.with_wallet(wallet)
+    }
+
+    /// Same as `SubmitContent` without a streak account, including the submission fee, restarting the round and
+    /// recording the last submission
+    pub fn with_content(mut self, author: &Pubkey, text: &str) -> Self {
+        self.state.fee_pool += submission_fee(&self.state, author, 0);
+        let content = Content {
+            author: *author,
+            text: text.try_into().unwrap(),
+            image_uri: Default::default(),
+            timestamp: self.now,
+            vote_count: 0,
+        };
+        self.state.last_submitter = *author;
+        self.state.last_content = content_key(&content);
+        self.state.contents.push(content);
+        self.state.timeout_timestamp = self.now + self.state.time_limit;
+        self.with_wallet(author)
+    }
+
+    /// Same as `CreateVote` with the shortest voting period
+    pub fn with_proposal(mut self, proposer: &Pubkey, vote_type: VoteType, options: &[&str]) -> Self {
+        self.state.vote_proposals.push(VoteProposal {
+            proposal_id: self.state.next_proposal_id,
+            proposer: *proposer,
+            title: "proposal".to_string(),
+            description: String::new(),
+            vote_type,
+            options: options.iter().map(|option| option.to_string()).collect(),
+            start_time: self.now,
+            end_time: self.now + MIN_VOTING_PERIOD,
+            votes: Vec::new(),
+            status: VoteStatus::Active,
+        });
+        self.state.next_proposal_id += 1;
+        self.with_wallet(proposer)
+    }
+
+    /// Same as `CastVote`: the voter's deposit is its voting power
+    pub fn with_vote(mut self, voter: &Pubkey, proposal_id: u64, option_index: u8) -> Self {
+        let voting_power = self.state.depositors.iter()
+            .find(|info| info.depositor == *voter)
+            .map(|info| info.amount)
+            .expect("voter must be added with with_depositor first");
+        let proposal = self.state.vote_proposals.iter_mut()
+            .find(|proposal| proposal.proposal_id == proposal_id)
+            .expect("proposal must be added with with_proposal first");
+        proposal.votes.push(VoteInfo { voter: *voter, option_index, voting_power });
+        self
+    }
+
+    /// A wallet that is funded with `WALLET_LAMPORTS` but holds no DAO records
+    pub fn with_wallet(mut self, wallet: &Pubkey) -> Self {
+        if !self.wallets.contains(wallet) {
+            self.wallets.push(*wallet);
+        }
+        self
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn initializer(&self) -> &Keypair {
+        &self.initializer
+    }
+
+    pub fn dao(&self) -> Pubkey {
+        pda::find_dao_address(&self.program_id, &self.initializer.pubkey(), &self.state.dao_name).0
+    }
+
+    pub fn build(&self) -> &DaoState {
+        &self.state
+    }
+
+    /// The DAO account holding exactly what its ledger records: rent reserve, bounty pool and fee pool
+    pub fn account(&self) -> Account {
+        let mut data = vec![0; DAO_SPACE];
+        self.state.serialize(&mut data.as_mut_slice()).unwrap();
+        Account {
+            lamports: ledger_total(&self.state).unwrap(),
+            data,
+            owner: self.program_id,
+            ..Account::default()
+        }
+    }
+
+    /// Starts program-test with the DAO account and funded wallets, the clock set to the fixture's `now`
+    pub async fn start(self) -> (TestDao, Pubkey) {
+        self.launch(false).await
+    }
+
+    /// `start` with the SBF build of the program (`cargo test-sbf`)
+    pub async fn start_sbf(self) -> (TestDao, Pubkey) {
+        self.launch(true).await
+    }
+
+    async fn launch(self, sbf: bool) -> (TestDao, Pubkey) {
+        let dao = self.dao();
+        let mut accounts = vec![(dao, self.account())];
+        let initializer = self.initializer.pubkey();
+        for wallet in self.wallets.iter().chain([&initializer]) {
+            accounts.push((*wallet, Account { lamports: WALLET_LAMPORTS, ..Account::default() }));
+        }
+
+        let mut test = TestDao::launch(self.program_id, accounts, sbf).await;
+        test.warp_to_timestamp(self.now).await;
+        (test, dao)
+    }
+}
@@ -1,204 +1,577 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     system_program,
 };
 
-use crate::{ProposalType, TurtleInstruction};
+use crate::{
+    bounded::{BoundedExternalId, BoundedText, BoundedUri},
+    error::TurtleError,
+    check_quality_distribution, pda, preset::DaoPreset, ExternalPlatform, ExternalRef, TurtleInstruction, VoteType,
+    MIN_VOTING_PERIOD,
+};
+
+/// DAO names are PDA seeds, which are limited to 32 bytes
+pub const MAX_DAO_NAME_LEN: usize = 32;
+/// Limits that keep a round's contents and proposals inside the 8000-byte DAO account. Content text and
+/// image URIs are also enforced on-chain by their Borsh types (`bounded::BoundedText`, `bounded::BoundedUri`);
+/// the others are checked here only.
+pub const MAX_TEXT_LEN: usize = 1000;
+pub const MAX_IMAGE_URI_LEN: usize = 200;
+pub const MAX_TITLE_LEN: usize = 100;
+pub const MAX_DESCRIPTION_LEN: usize = 1000;
+pub const MAX_VOTE_OPTIONS: usize = 10;
+pub const MAX_OPTION_LEN: usize = 50;
+/// Post ids of an `ExternalRef`, enforced on-chain by `bounded::BoundedExternalId`. Long enough for tweet ids
+/// and hex Farcaster cast hashes.
+pub const MAX_EXTERNAL_ID_LEN: usize = 64;
+
+fn check_len(value: &str, max: usize, error: TurtleError) -> Result<(), TurtleError> {
+    match value.len() > max {
+        true => Err(error),
+        false => Ok(()),
+    }
+}
 
-/// Helper functions for creating instructions for the Turtle DAO program
+fn build(program_id: &Pubkey, data: &TurtleInstruction, accounts: Vec<AccountMeta>) -> Result<Instruction, TurtleError> {
+    let data = data.try_to_vec().map_err(|_| TurtleError::InvalidInstruction)?;
+    Ok(Instruction { program_id: *program_id, accounts, data })
+}
+
+/// Helper functions for creating instructions for the Turtle DAO program.
+/// Arguments the program would reject are caught here instead of failing the transaction.
 pub mod turtle_instruction {
     use super::*;
 
-    /// Creates an instruction to initialize a new DAO
+    /// Creates an instruction to initialize a new DAO at its PDA (see `pda::find_dao_address`)
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_dao(
         program_id: &Pubkey,
-        payer: &Pubkey,
-        dao_account: &Pubkey,
+        initializer: &Pubkey,
+        dao_name: String,
         time_limit: u64,
         base_fee: u64,
         ai_moderation: bool,
         deposit_share: u8,
-    ) -> Instruction {
-        let data = TurtleInstruction::InitializeDao {
-            time_limit,
-            base_fee,
-            ai_moderation,
-            deposit_share,
+        deposit_resets_timer: bool,
+    ) -> Result<Instruction, TurtleError> {
+        if dao_name.is_empty() {
+            return Err(TurtleError::InvalidParameter);
+        }
+        check_len(&dao_name, MAX_DAO_NAME_LEN, TurtleError::InvalidParameter)?;
+        if time_limit == 0 || deposit_share > 100 {
+            return Err(TurtleError::InvalidParameter);
         }
-        .try_to_vec()
-        .unwrap();
 
-        Instruction {
-            program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new_readonly(*payer, true),
-                AccountMeta::new(*dao_account, false),
+        let (dao_account, _) = pda::find_dao_address(program_id, initializer, &dao_name);
+        build(
+            program_id,
+            &TurtleInstruction::InitializeDao { dao_name, time_limit, base_fee, ai_moderation, deposit_share, deposit_resets_timer },
+            vec![
+                AccountMeta::new(*initializer, true),
+                AccountMeta::new(dao_account, false),
                 AccountMeta::new_readonly(system_program::id(), false),
             ],
-            data,
-        }
+        )
     }
 
-    /// Creates an instruction to submit content to the community
-    pub fn submit_content(
+    /// Creates an instruction to initialize a new DAO with the parameters of `preset`, at the same PDA as
+    /// `initialize_dao`
+    pub fn initialize_dao_with_preset(
         program_id: &Pubkey,
-        submitter: &Pubkey,
-        dao_account: &Pubkey,
-        content_account: &Pubkey,
-        content_hash: String,
-        content_uri: String,
-    ) -> Instruction {
-        let data = TurtleInstruction::SubmitContent {
-            content_hash,
-            content_uri,
+        initializer: &Pubkey,
+        dao_name: String,
+        preset: DaoPreset,
+    ) -> Result<Instruction, TurtleError> {
+        if dao_name.is_empty() {
+            return Err(TurtleError::InvalidParameter);
         }
-        .try_to_vec()
-        .unwrap();
+        check_len(&dao_name, MAX_DAO_NAME_LEN, TurtleError::InvalidParameter)?;
 
-        Instruction {
-            program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new(*submitter, true),
-                AccountMeta::new(*dao_account, false),
-                AccountMeta::new(*content_account, false),
+        let (dao_account, _) = pda::find_dao_address(program_id, initializer, &dao_name);
+        build(
+            program_id,
+            &TurtleInstruction::InitializeDaoWithPreset { dao_name, preset },
+            vec![
+                AccountMeta::new(*initializer, true),
+                AccountMeta::new(dao_account, false),
                 AccountMeta::new_readonly(system_program::id(), false),
             ],
-            data,
-        }
+        )
     }
 
-    /// Creates an instruction to deposit funds to the DAO
+    /// Creates an instruction to deposit lamports into the DAO bounty pool
     pub fn deposit(
         program_id: &Pubkey,
         depositor: &Pubkey,
         dao_account: &Pubkey,
-        depositor_state_account: &Pubkey,
         amount: u64,
-    ) -> Instruction {
-        let data = TurtleInstruction::Deposit { amount }.try_to_vec().unwrap();
-
-        Instruction {
-            program_id: *program_id,
-            accounts: vec![
+    ) -> Result<Instruction, TurtleError> {
+        if amount == 0 {
+            return Err(TurtleError::InvalidParameter);
+        }
+        build(
+            program_id,
+            &TurtleInstruction::Deposit { amount },
+            vec![
                 AccountMeta::new(*depositor, true),
                 AccountMeta::new(*dao_account, false),
-                AccountMeta::new(*depositor_state_account, false),
                 AccountMeta::new_readonly(system_program::id(), false),
             ],
-            data,
-        }
+        )
     }
 
-    /// Creates an instruction to claim reward as the last content submitter
-    pub fn claim_reward(
+    /// Creates an instruction to submit content (the author must be a depositor and pays the submission fee)
+    pub fn submit_content(
         program_id: &Pubkey,
-        claimer: &Pubkey,
+        author: &Pubkey,
         dao_account: &Pubkey,
-        content_account: &Pubkey,
-    ) -> Instruction {
-        let data = TurtleInstruction::ClaimReward {}.try_to_vec().unwrap();
-
-        Instruction {
-            program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new(*claimer, true),
+        text: String,
+        image_uri: String,
+    ) -> Result<Instruction, TurtleError> {
+        if text.is_empty() && image_uri.is_empty() {
+            return Err(TurtleError::InvalidContent);
+        }
+        let text = BoundedText::try_from(text)?;
+        let image_uri = BoundedUri::try_from(image_uri)?;
+        build(
+            program_id,
+            &TurtleInstruction::SubmitContent { text, image_uri },
+            vec![
+                AccountMeta::new(*author, true),
                 AccountMeta::new(*dao_account, false),
-                AccountMeta::new(*content_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
             ],
-            data,
+        )
+    }
+
+    /// Like `submit_content`, also counting the submission in the author's daily streak
+    /// (see `pda::find_streak_address`); the author pays rent for the streak record the first time
+    pub fn submit_content_with_streak(
+        program_id: &Pubkey,
+        author: &Pubkey,
+        dao_account: &Pubkey,
+        text: String,
+        image_uri: String,
+    ) -> Result<Instruction, TurtleError> {
+        let mut instruction = submit_content(program_id, author, dao_account, text, image_uri)?;
+        let (streak, _) = pda::find_streak_address(program_id, dao_account, author);
+        instruction.accounts.push(AccountMeta::new(streak, false));
+        Ok(instruction)
+    }
+
+    /// Creates an instruction submitting content linked to the post `id` on `platform`, claiming the post for
+    /// this DAO (see `pda::find_claim_address`); the author pays rent for the claim
+    pub fn submit_linked_content(
+        program_id: &Pubkey,
+        author: &Pubkey,
+        dao_account: &Pubkey,
+        text: String,
+        image_uri: String,
+        platform: ExternalPlatform,
+        id: String,
+    ) -> Result<Instruction, TurtleError> {
+        if (text.is_empty() && image_uri.is_empty()) || id.is_empty() {
+            return Err(TurtleError::InvalidContent);
         }
+        let (claim, _) = pda::find_claim_address(program_id, dao_account, platform, &id);
+        let external_ref = ExternalRef { platform, id: BoundedExternalId::try_from(id)? };
+        let text = BoundedText::try_from(text)?;
+        let image_uri = BoundedUri::try_from(image_uri)?;
+        build(
+            program_id,
+            &TurtleInstruction::SubmitLinkedContent { text, image_uri, external_ref },
+            vec![
+                AccountMeta::new(*author, true),
+                AccountMeta::new(*dao_account, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(claim, false),
+            ],
+        )
     }
 
-    /// Creates an instruction to create a governance vote
+    /// Creates an instruction to open a governance vote (the proposer must be a depositor)
+    #[allow(clippy::too_many_arguments)]
     pub fn create_vote(
         program_id: &Pubkey,
         proposer: &Pubkey,
         dao_account: &Pubkey,
-        depositor_state_account: &Pubkey,
-        proposal_account: &Pubkey,
-        proposal_type: ProposalType,
-        new_value: u64,
+        title: String,
+        description: String,
+        vote_type: VoteType,
+        options: Vec<String>,
         voting_period: u64,
-    ) -> Instruction {
-        let data = TurtleInstruction::CreateVote {
-            proposal_type,
-            new_value,
-            voting_period,
+    ) -> Result<Instruction, TurtleError> {
+        check_len(&title, MAX_TITLE_LEN, TurtleError::InvalidProposal)?;
+        check_len(&description, MAX_DESCRIPTION_LEN, TurtleError::InvalidProposal)?;
+        if title.is_empty() || options.len() < 2 || options.len() > MAX_VOTE_OPTIONS {
+            return Err(TurtleError::InvalidProposal);
         }
-        .try_to_vec()
-        .unwrap();
-
-        Instruction {
-            program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new(*proposer, true),
+        for option in &options {
+            check_len(option, MAX_OPTION_LEN, TurtleError::InvalidProposal)?;
+        }
+        if voting_period < MIN_VOTING_PERIOD {
+            return Err(TurtleError::InvalidProposal);
+        }
+        build(
+            program_id,
+            &TurtleInstruction::CreateVote { title, description, vote_type, options, voting_period },
+            vec![
+                AccountMeta::new_readonly(*proposer, true),
                 AccountMeta::new(*dao_account, false),
-                AccountMeta::new(*depositor_state_account, false),
-                AccountMeta::new(*proposal_account, false),
-                AccountMeta::new_readonly(system_program::id(), false),
             ],
-            data,
-        }
+        )
     }
 
-    /// Creates an instruction to vote on a governance proposal
-    pub fn vote(
+    /// Creates an instruction to vote on a governance proposal (the voter must be a depositor)
+    pub fn cast_vote(
         program_id: &Pubkey,
         voter: &Pubkey,
         dao_account: &Pubkey,
-        depositor_state_account: &Pubkey,
-        proposal_account: &Pubkey,
         proposal_id: u64,
-        approve: bool,
-    ) -> Instruction {
-        let data = TurtleInstruction::Vote { proposal_id, approve }
-            .try_to_vec()
-            .unwrap();
-
-        Instruction {
-            program_id: *program_id,
-            accounts: vec![
-                AccountMeta::new(*voter, true),
+        option_index: u8,
+    ) -> Result<Instruction, TurtleError> {
+        if option_index as usize >= MAX_VOTE_OPTIONS {
+            return Err(TurtleError::InvalidProposal);
+        }
+        build(
+            program_id,
+            &TurtleInstruction::CastVote { proposal_id, option_index },
+            vec![
+                AccountMeta::new_readonly(*voter, true),
                 AccountMeta::new(*dao_account, false),
-                AccountMeta::new(*depositor_state_account, false),
-                AccountMeta::new(*proposal_account, false),
             ],
-            data,
+        )
+    }
+
+    /// Creates an instruction to process an expired round (anyone can send it). `round` is the DAO's
+    /// current `DaoState::round` and `winner` its `last_submitter` (`None` if the round has no submission);
+    /// if the round has a winner the caller pays for its `RoundResult` PDA and the winner receives the reward.
+    pub fn process_timeout(
+        program_id: &Pubkey,
+        caller: &Pubkey,
+        dao_account: &Pubkey,
+        round: u64,
+        winner: Option<&Pubkey>,
+    ) -> Result<Instruction, TurtleError> {
+        let (round_result, _) = pda::find_round_address(program_id, dao_account, round);
+        let mut accounts = vec![
+            AccountMeta::new(*caller, true),
+            AccountMeta::new(*dao_account, false),
+            AccountMeta::new(round_result, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        if let Some(winner) = winner {
+            accounts.push(AccountMeta::new(*winner, false));
         }
+        build(program_id, &TurtleInstruction::ProcessTimeout {}, accounts)
     }
 
-    /// Creates an instruction to distribute rewards to high-quality content creators
+    /// Creates an instruction paying the quality share of `round` to `creator_pubkeys`, `distribution_weights[i]`
+    /// percent to the i-th (admin only). The weights must sum to 100 and each creator appear once.
     pub fn distribute_quality_rewards(
         program_id: &Pubkey,
         admin: &Pubkey,
         dao_account: &Pubkey,
+        round: u64,
         creator_pubkeys: Vec<Pubkey>,
         distribution_weights: Vec<u8>,
-    ) -> Instruction {
-        let data = TurtleInstruction::DistributeQualityRewards {
-            creator_pubkeys: creator_pubkeys.clone(),
-            distribution_weights,
-        }
-        .try_to_vec()
-        .unwrap();
-
-        // First include admin and dao accounts
+    ) -> Result<Instruction, TurtleError> {
+        check_quality_distribution(&creator_pubkeys, &distribution_weights)?;
+        let (round_result, _) = pda::find_round_address(program_id, dao_account, round);
         let mut accounts = vec![
-            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(*admin, true),
             AccountMeta::new(*dao_account, false),
+            AccountMeta::new(round_result, false),
         ];
+        accounts.extend(creator_pubkeys.iter().map(|creator| AccountMeta::new(*creator, false)));
+        build(program_id, &TurtleInstruction::DistributeQualityRewards { creator_pubkeys, distribution_weights }, accounts)
+    }
+
+    /// Creates an instruction to register `mint` in the DAO treasury (admin only). The admin pays rent for
+    /// the `TreasuryAsset` record and its vault token account.
+    pub fn initialize_treasury_asset(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        dao_account: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Instruction, TurtleError> {
+        let (treasury_asset, _) = pda::find_treasury_asset_address(program_id, dao_account, mint);
+        let (vault, _) = pda::find_asset_vault_address(program_id, dao_account, mint);
+        build(
+            program_id,
+            &TurtleInstruction::InitializeTreasuryAsset {},
+            vec![
+                AccountMeta::new(*admin, true),
+                AccountMeta::new_readonly(*dao_account, false),
+                AccountMeta::new(treasury_asset, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    }
+
+    /// Creates an instruction to deposit `amount` tokens of `mint` from the depositor's `source` token account
+    pub fn deposit_asset(
+        program_id: &Pubkey,
+        depositor: &Pubkey,
+        dao_account: &Pubkey,
+        mint: &Pubkey,
+        source: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, TurtleError> {
+        if amount == 0 {
+            return Err(TurtleError::InvalidParameter);
+        }
+        let (treasury_asset, _) = pda::find_treasury_asset_address(program_id, dao_account, mint);
+        let (vault, _) = pda::find_asset_vault_address(program_id, dao_account, mint);
+        build(
+            program_id,
+            &TurtleInstruction::DepositAsset { amount },
+            vec![
+                AccountMeta::new_readonly(*depositor, true),
+                AccountMeta::new_readonly(*dao_account, false),
+                AccountMeta::new(treasury_asset, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(*source, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+        )
+    }
 
-        // Then add all creator accounts
-        for pubkey in creator_pubkeys {
-            accounts.push(AccountMeta::new(pubkey, false));
+    /// Creates an instruction to withdraw `amount` tokens of `mint` to the `destination` token account (admin only)
+    pub fn withdraw_asset(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        dao_account: &Pubkey,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, TurtleError> {
+        if amount == 0 {
+            return Err(TurtleError::InvalidParameter);
         }
+        let (treasury_asset, _) = pda::find_treasury_asset_address(program_id, dao_account, mint);
+        let (vault, _) = pda::find_asset_vault_address(program_id, dao_account, mint);
+        build(
+            program_id,
+            &TurtleInstruction::WithdrawAsset { amount },
+            vec![
+                AccountMeta::new_readonly(*admin, true),
+                AccountMeta::new_readonly(*dao_account, false),
+                AccountMeta::new(treasury_asset, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+        )
+    }
 
-        Instruction {
-            program_id: *program_id,
-            accounts,
-            data,
+    /// Creates an instruction paying the winner of `round` `amount` tokens of `mint` (admin only).
+    /// `winner_token_account` must be a token account of that mint owned by the round's winner.
+    #[allow(clippy::too_many_arguments)]
+    pub fn distribute_asset_reward(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        dao_account: &Pubkey,
+        mint: &Pubkey,
+        round: u64,
+        winner_token_account: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, TurtleError> {
+        if amount == 0 {
+            return Err(TurtleError::InvalidParameter);
         }
+        let (round_result, _) = pda::find_round_address(program_id, dao_account, round);
+        let (treasury_asset, _) = pda::find_treasury_asset_address(program_id, dao_account, mint);
+        let (vault, _) = pda::find_asset_vault_address(program_id, dao_account, mint);
+        build(
+            program_id,
+            &TurtleInstruction::DistributeAssetReward { round, amount },
+            vec![
+                AccountMeta::new_readonly(*admin, true),
+                AccountMeta::new_readonly(*dao_account, false),
+                AccountMeta::new_readonly(round_result, false),
+                AccountMeta::new(treasury_asset, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(*winner_token_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+        )
+    }
+
+    /// Creates an instruction retracting `author`'s submission `content` (its `content_key`)
+    pub fn retract_content(
+        program_id: &Pubkey,
+        author: &Pubkey,
+        dao_account: &Pubkey,
+        content: &Pubkey,
+    ) -> Result<Instruction, TurtleError> {
+        build(
+            program_id,
+            &TurtleInstruction::RetractContent { content: *content },
+            vec![
+                AccountMeta::new(*author, true),
+                AccountMeta::new(*dao_account, false),
+            ],
+        )
+    }
+
+    /// Like `retract_content` for a linked submission, also closing the claim of its post so the post can be
+    /// submitted again
+    pub fn retract_linked_content(
+        program_id: &Pubkey,
+        author: &Pubkey,
+        dao_account: &Pubkey,
+        content: &Pubkey,
+        platform: ExternalPlatform,
+        id: &str,
+    ) -> Result<Instruction, TurtleError> {
+        let mut instruction = retract_content(program_id, author, dao_account, content)?;
+        let (claim, _) = pda::find_claim_address(program_id, dao_account, platform, id);
+        instruction.accounts.push(AccountMeta::new(claim, false));
+        Ok(instruction)
+    }
+
+    /// Creates an instruction voting for the content `content` (its `content_key`) of the current round. The voter
+    /// must have deposited this round and pays rent for the `ContentVote` record.
+    pub fn vote_content(
+        program_id: &Pubkey,
+        voter: &Pubkey,
+        dao_account: &Pubkey,
+        content: &Pubkey,
+    ) -> Result<Instruction, TurtleError> {
+        let (content_vote, _) = pda::find_content_vote_address(program_id, dao_account, content, voter);
+        build(
+            program_id,
+            &TurtleInstruction::VoteContent { content: *content },
+            vec![
+                AccountMeta::new(*voter, true),
+                AccountMeta::new(*dao_account, false),
+                AccountMeta::new(content_vote, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::turtle_instruction::*;
+    use super::*;
+    use borsh::BorshDeserialize;
+
+    #[test]
+    fn test_builders_match_processor_account_order() {
+        let program_id = Pubkey::new_unique();
+        let initializer = Pubkey::new_unique();
+
+        let instruction = initialize_dao(&program_id, &initializer, "turtles".to_string(), 3_600, 10, false, 20, false).unwrap();
+        let (dao, _) = pda::find_dao_address(&program_id, &initializer, "turtles");
+        let keys: Vec<(Pubkey, bool, bool)> = instruction.accounts.iter().map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable)).collect();
+        assert_eq!(keys, [(initializer, true, true), (dao, false, true), (system_program::id(), false, false)]);
+
+        let instruction = cast_vote(&program_id, &initializer, &dao, 3, 1).unwrap();
+        assert_eq!(
+            TurtleInstruction::try_from_slice(&instruction.data).unwrap(),
+            TurtleInstruction::CastVote { proposal_id: 3, option_index: 1 }
+        );
+        assert!(instruction.accounts[0].is_signer && !instruction.accounts[0].is_writable);
+        assert!(instruction.accounts[1].is_writable);
+    }
+
+    #[test]
+    fn test_builders_reject_invalid_arguments() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let dao = Pubkey::new_unique();
+        let options = || vec!["Yes".to_string(), "No".to_string()];
+
+        assert!(matches!(initialize_dao(&program_id, &user, "x".repeat(33), 3_600, 10, false, 20, false), Err(TurtleError::InvalidParameter)));
+        assert!(matches!(initialize_dao(&program_id, &user, "turtles".to_string(), 3_600, 10, false, 101, false), Err(TurtleError::InvalidParameter)));
+        assert!(matches!(initialize_dao_with_preset(&program_id, &user, "x".repeat(33), DaoPreset::LongForm), Err(TurtleError::InvalidParameter)));
+        assert!(matches!(deposit(&program_id, &user, &dao, 0), Err(TurtleError::InvalidParameter)));
+        assert!(matches!(deposit_asset(&program_id, &user, &dao, &dao, &user, 0), Err(TurtleError::InvalidParameter)));
+        assert!(matches!(submit_content(&program_id, &user, &dao, "a".repeat(MAX_TEXT_LEN + 1), String::new()), Err(TurtleError::InvalidContent)));
+        assert!(matches!(submit_content(&program_id, &user, &dao, String::new(), String::new()), Err(TurtleError::InvalidContent)));
+        let linked = |id: String| submit_linked_content(&program_id, &user, &dao, "hi".to_string(), String::new(), ExternalPlatform::Farcaster, id);
+        assert!(matches!(linked(String::new()), Err(TurtleError::InvalidContent)));
+        assert!(matches!(linked("f".repeat(MAX_EXTERNAL_ID_LEN + 1)), Err(TurtleError::InvalidContent)));
+
+        let vote = |options: Vec<String>, voting_period| {
+            create_vote(&program_id, &user, &dao, "longer rounds".to_string(), String::new(), VoteType::ChangeTimeLimit, options, voting_period)
+        };
+        assert!(vote(options(), MIN_VOTING_PERIOD).is_ok());
+        assert!(matches!(vote(options(), MIN_VOTING_PERIOD - 1), Err(TurtleError::InvalidProposal)));
+        assert!(matches!(vote(vec!["Yes".to_string()], MIN_VOTING_PERIOD), Err(TurtleError::InvalidProposal)));
+        assert!(matches!(cast_vote(&program_id, &user, &dao, 0, MAX_VOTE_OPTIONS as u8), Err(TurtleError::InvalidProposal)));
+
+        let quality = |creators: Vec<Pubkey>, weights: Vec<u8>| distribute_quality_rewards(&program_id, &user, &dao, 0, creators, weights);
+        assert!(quality(vec![user, dao], vec![60, 40]).is_ok());
+        assert!(matches!(quality(Vec::new(), Vec::new()), Err(TurtleError::InvalidDistribution)));
+        assert!(matches!(quality(vec![user, dao], vec![100]), Err(TurtleError::InvalidDistribution)));
+        assert!(matches!(quality(vec![user, dao], vec![60, 39]), Err(TurtleError::InvalidDistribution)));
+        assert!(matches!(quality(vec![user, user], vec![50, 50]), Err(TurtleError::InvalidDistribution)));
+    }
+
+    // idl/turtle_dao.json is regenerated with `shank idl`; this catches edits that forget to do so
+    #[test]
+    fn test_idl_matches_builders() {
+        let idl: serde_json::Value = serde_json::from_str(include_str!("../idl/turtle_dao.json")).unwrap();
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let dao = Pubkey::new_unique();
+        let options = vec!["Yes".to_string(), "No".to_string()];
+        let (mint, token_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // The creators follow the accounts the IDL lists
+        let mut quality_rewards = distribute_quality_rewards(&program_id, &user, &dao, 0, vec![token_account], vec![100]).unwrap();
+        quality_rewards.accounts.truncate(3);
+
+        let built = [
+            initialize_dao(&program_id, &user, "turtles".to_string(), 3_600, 10, false, 20, false).unwrap(),
+            deposit(&program_id, &user, &dao, 1).unwrap(),
+            submit_content(&program_id, &user, &dao, "hi".to_string(), String::new()).unwrap(),
+            create_vote(&program_id, &user, &dao, "t".to_string(), String::new(), VoteType::ChangeBaseFee, options, MIN_VOTING_PERIOD).unwrap(),
+            cast_vote(&program_id, &user, &dao, 0, 0).unwrap(),
+            process_timeout(&program_id, &user, &dao, 0, None).unwrap(),
+            quality_rewards.clone(),
+            initialize_treasury_asset(&program_id, &user, &dao, &mint).unwrap(),
+            deposit_asset(&program_id, &user, &dao, &mint, &token_account, 1).unwrap(),
+            withdraw_asset(&program_id, &user, &dao, &mint, &token_account, 1).unwrap(),
+            distribute_asset_reward(&program_id, &user, &dao, &mint, 0, &token_account, 1).unwrap(),
+            retract_content(&program_id, &user, &dao, &token_account).unwrap(),
+            initialize_dao_with_preset(&program_id, &user, "turtles".to_string(), DaoPreset::FastGame).unwrap(),
+            submit_linked_content(&program_id, &user, &dao, "hi".to_string(), String::new(), ExternalPlatform::Twitter, "42".to_string()).unwrap(),
+            vote_content(&program_id, &user, &dao, &token_account).unwrap(),
+        ];
+        let instructions = idl["instructions"].as_array().unwrap();
+        assert_eq!(instructions.len(), built.len());
+        for (entry, instruction) in instructions.iter().zip(&built) {
+            assert_eq!(entry["discriminant"]["value"], instruction.data[0], "{}", entry["name"]);
+            // Optional accounts are left out by the builders (the *_with_streak and retract_linked_content builders add them,
+            // process_timeout does for a winner)
+            let accounts: Vec<(bool, bool)> = entry["accounts"].as_array().unwrap().iter()
+                .filter(|account| !account["isOptional"].as_bool().unwrap_or(false))
+                .map(|account| (account["isSigner"].as_bool().unwrap(), account["isMut"].as_bool().unwrap()))
+                .collect();
+            let metas: Vec<(bool, bool)> = instruction.accounts.iter().map(|meta| (meta.is_signer, meta.is_writable)).collect();
+            assert_eq!(accounts, metas, "{}", entry["name"]);
+        }
+
+        let with_streak = submit_content_with_streak(&program_id, &user, &dao, "hi".to_string(), String::new()).unwrap();
+        let streak = &instructions[2]["accounts"][3];
+        assert_eq!((streak["name"].as_str(), streak["isOptional"].as_bool()), (Some("streak"), Some(true)));
+        assert_eq!(with_streak.accounts[3], AccountMeta::new(pda::find_streak_address(&program_id, &dao, &user).0, false));
+        let retract_linked = retract_linked_content(&program_id, &user, &dao, &token_account, ExternalPlatform::Twitter, "42").unwrap();
+        let claim = &instructions[11]["accounts"][2];
+        assert_eq!((claim["name"].as_str(), claim["isOptional"].as_bool()), (Some("claim"), Some(true)));
+        assert_eq!(retract_linked.accounts[2], AccountMeta::new(built[13].accounts[3].pubkey, false));
+
+        let errors = idl["errors"].as_array().unwrap();
+        assert_eq!(errors.last().unwrap()["code"], TurtleError::IneligibleCreator as u32);
+        assert_eq!(errors[4]["msg"], TurtleError::InvalidParameter.to_string());
     }
-}
\ No newline at end of file
+}
@@ -1,22 +1,62 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use shank::{ShankAccount, ShankInstruction};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    borsh::try_from_slice_unchecked,
     clock::Clock,
-    entrypoint,
     entrypoint::ProgramResult,
+    hash::hashv,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
-    program_pack::IsInitialized,
+    program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
     sysvar::{rent::Rent, Sysvar},
     system_instruction,
 };
-use std::convert::TryInto;
+use std::str::FromStr;
+
+use crate::bounded::{BoundedExternalId, BoundedText, BoundedUri};
+use crate::error::TurtleError;
+use crate::math::{percent_of, percent_off, PERCENT};
+use crate::preset::DaoPreset;
+
+#[cfg(not(feature = "no-entrypoint"))]
+use solana_program::entrypoint;
+
+pub mod bounded;
+pub mod error;
+pub mod instruction;
+pub mod math;
+pub mod pda;
+pub mod preset;
+#[cfg(feature = "serde")]
+pub mod serde_pubkey;
+#[cfg(feature = "cpi")]
+pub mod cpi;
+#[cfg(test)]
+pub mod fixtures;
+#[cfg(test)]
+mod test;
+#[cfg(test)]
+mod compute_units;
+
+/// Shortest governance voting period `CreateVote` accepts (one week)
+pub const MIN_VOTING_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// Most submission fee discount tiers a DAO can hold (see `FeeTier`)
+pub const MAX_FEE_TIERS: usize = 4;
+
+/// Length of the days submission streaks count in (UTC days since the Unix epoch)
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Most submissions a `RoundResult` keeps as quality reward candidates (see `QualityCandidate`)
+pub const MAX_QUALITY_CANDIDATES: usize = 10;
 
 // Define instruction types
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+// The `#[account]` attributes feed `shank idl` (see idl/turtle_dao.json); keep them in step with the
+// "Accounts expected" lists and the builders in instruction.rs.
+#[derive(BorshSerialize, BorshDeserialize, ShankInstruction, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TurtleInstruction {
     /// Initialize a new DAO
     /// 
@@ -24,12 +64,16 @@ pub enum TurtleInstruction {
     /// 0. `[signer]` Initializer account
     /// 1. `[writable]` DAO account to be created
     /// 2. `[]` System program
+    #[account(0, writable, signer, name = "initializer", desc = "Initializer account, pays for the DAO account")]
+    #[account(1, writable, name = "dao", desc = "DAO account to be created (PDA of [\"dao\", initializer, dao_name])")]
+    #[account(2, name = "system_program", desc = "System program")]
     InitializeDao {
         dao_name: String,
         time_limit: u64,
         base_fee: u64,
         ai_moderation: bool,
         deposit_share: u8,
+        deposit_resets_timer: bool,
     },
 
     /// Deposit funds to DAO
@@ -38,18 +82,30 @@ pub enum TurtleInstruction {
     /// 0. `[signer]` Depositor account
     /// 1. `[writable]` DAO account
     /// 2. `[]` System program
+    #[account(0, writable, signer, name = "depositor", desc = "Depositor account")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, name = "system_program", desc = "System program")]
     Deposit {
         amount: u64,
     },
 
-    /// Submit content to the DAO
-    /// 
+    /// Submit content to the DAO, paying the submission fee (`base_fee` lamports less the author's discount)
+    ///
+    /// Passing the author's `SubmissionStreak` counts the submission in their daily streak (the author pays
+    /// rent the first time) and lets a long enough streak earn the DAO's streak discount.
+    ///
     /// Accounts expected:
-    /// 0. `[signer]` Author account
+    /// 0. `[signer, writable]` Author account
     /// 1. `[writable]` DAO account
+    /// 2. `[]` System program, only read when a fee is due or a streak account is passed
+    /// 3. `[writable]` (optional) SubmissionStreak account of the author
+    #[account(0, writable, signer, name = "author", desc = "Author account, pays the submission fee")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, name = "system_program", desc = "System program")]
+    #[account(3, optional, writable, name = "streak", desc = "Author's submission streak (PDA of [\"streak\", dao, author])")]
     SubmitContent {
-        text: String,
-        image_uri: String,
+        text: BoundedText,
+        image_uri: BoundedUri,
     },
 
     /// Create a governance vote
@@ -57,6 +113,8 @@ pub enum TurtleInstruction {
     /// Accounts expected:
     /// 0. `[signer]` Proposer account (must be a depositor)
     /// 1. `[writable]` DAO account
+    #[account(0, signer, name = "proposer", desc = "Proposer account (must be a depositor)")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
     CreateVote {
         title: String,
         description: String,
@@ -70,58 +128,310 @@ pub enum TurtleInstruction {
     /// Accounts expected:
     /// 0. `[signer]` Voter account (must be a depositor)
     /// 1. `[writable]` DAO account
+    #[account(0, signer, name = "voter", desc = "Voter account (must be a depositor)")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
     CastVote {
         proposal_id: u64,
         option_index: u8,
     },
 
-    /// Process timeout and distribute rewards
+    /// Process timeout and distribute rewards. A round with a winner takes the fee pool into its pot, pays the
+    /// winner its reward out of the bounty pool and is recorded in a new `RoundResult` account, which the caller
+    /// pays rent for.
     /// 
     /// Accounts expected:
-    /// 0. `[signer]` Any account to trigger the timeout
+    /// 0. `[signer, writable]` Any account to trigger the timeout
     /// 1. `[writable]` DAO account
+    /// 2. `[writable]` RoundResult account of the closing round, only read when the round has a winner
+    /// 3. `[]` System program, only read when the round has a winner
+    /// 4. `[writable]` (optional) Winner account (`DaoState::last_submitter`), required when the round has a winner
+    #[account(0, writable, signer, name = "caller", desc = "Any account to trigger the timeout, pays for the round result")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, writable, name = "round_result", desc = "Round result to be created (PDA of [\"round\", dao, round])")]
+    #[account(3, name = "system_program", desc = "System program")]
+    #[account(4, optional, writable, name = "winner", desc = "Last submitter of the round, receives the reward")]
     ProcessTimeout {},
+
+    /// Pay out the quality share a closed round left in the bounty pool (`RoundResult::quality_pool`) to content
+    /// creators, `distribution_weights[i]` percent (summing to 100) to `creator_pubkeys[i]`; rounding leftovers go
+    /// to the first creator. Admin only; each round's quality share is paid once, and only to authors among the
+    /// round's `candidates` (fails with `IneligibleCreator` otherwise).
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Admin account (`DaoState::initializer`)
+    /// 1. `[writable]` DAO account
+    /// 2. `[writable]` RoundResult account of the round
+    /// 3. `[writable]` One account per creator, in `creator_pubkeys` order
+    #[account(0, signer, name = "admin", desc = "DAO admin")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, writable, name = "round_result", desc = "Round result holding the quality pool (PDA of [\"round\", dao, round])")]
+    DistributeQualityRewards {
+        creator_pubkeys: Vec<Pubkey>,
+        distribution_weights: Vec<u8>,
+    },
+
+    /// Register an SPL mint in the DAO treasury: creates its `TreasuryAsset` record and the vault token
+    /// account holding the tokens, owned by the record. Admin only; the admin pays rent for both.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Admin account (`DaoState::initializer`)
+    /// 1. `[]` DAO account
+    /// 2. `[writable]` TreasuryAsset account to be created
+    /// 3. `[writable]` Vault token account to be created
+    /// 4. `[]` Mint
+    /// 5. `[]` SPL Token program
+    /// 6. `[]` System program
+    #[account(0, writable, signer, name = "admin", desc = "DAO admin, pays for the treasury asset and its vault")]
+    #[account(1, name = "dao", desc = "DAO account")]
+    #[account(2, writable, name = "treasury_asset", desc = "Treasury asset to be created (PDA of [\"asset\", dao, mint])")]
+    #[account(3, writable, name = "vault", desc = "Vault token account to be created (PDA of [\"asset_vault\", dao, mint])")]
+    #[account(4, name = "mint", desc = "Mint of the asset")]
+    #[account(5, name = "token_program", desc = "SPL Token program")]
+    #[account(6, name = "system_program", desc = "System program")]
+    InitializeTreasuryAsset {},
+
+    /// Deposit SPL tokens into the DAO treasury. Treasury funds are kept apart from the SOL game:
+    /// they carry no voting power and are not part of any round's pot.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Depositor account (owner of the source token account)
+    /// 1. `[]` DAO account
+    /// 2. `[writable]` TreasuryAsset account
+    /// 3. `[writable]` Vault token account
+    /// 4. `[writable]` Source token account
+    /// 5. `[]` SPL Token program
+    #[account(0, signer, name = "depositor", desc = "Depositor account, owner of the source token account")]
+    #[account(1, name = "dao", desc = "DAO account")]
+    #[account(2, writable, name = "treasury_asset", desc = "Treasury asset of the mint")]
+    #[account(3, writable, name = "vault", desc = "Vault token account of the treasury asset")]
+    #[account(4, writable, name = "source", desc = "Token account the tokens come from")]
+    #[account(5, name = "token_program", desc = "SPL Token program")]
+    DepositAsset {
+        amount: u64,
+    },
+
+    /// Withdraw SPL tokens from the DAO treasury. Admin only.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Admin account (`DaoState::initializer`)
+    /// 1. `[]` DAO account
+    /// 2. `[writable]` TreasuryAsset account
+    /// 3. `[writable]` Vault token account
+    /// 4. `[writable]` Destination token account
+    /// 5. `[]` SPL Token program
+    #[account(0, signer, name = "admin", desc = "DAO admin")]
+    #[account(1, name = "dao", desc = "DAO account")]
+    #[account(2, writable, name = "treasury_asset", desc = "Treasury asset of the mint")]
+    #[account(3, writable, name = "vault", desc = "Vault token account of the treasury asset")]
+    #[account(4, writable, name = "destination", desc = "Token account receiving the tokens")]
+    #[account(5, name = "token_program", desc = "SPL Token program")]
+    WithdrawAsset {
+        amount: u64,
+    },
+
+    /// Pay the winner of a closed round in a treasury asset, on top of the SOL `RoundResult::reward` that
+    /// `ProcessTimeout` paid out of the bounty pool.
+    /// Admin only; each asset pays a round at most once, and rounds in order.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` Admin account (`DaoState::initializer`)
+    /// 1. `[]` DAO account
+    /// 2. `[]` RoundResult account of the round
+    /// 3. `[writable]` TreasuryAsset account
+    /// 4. `[writable]` Vault token account
+    /// 5. `[writable]` Winner's token account for the asset's mint
+    /// 6. `[]` SPL Token program
+    #[account(0, signer, name = "admin", desc = "DAO admin")]
+    #[account(1, name = "dao", desc = "DAO account")]
+    #[account(2, name = "round_result", desc = "Round result naming the winner (PDA of [\"round\", dao, round])")]
+    #[account(3, writable, name = "treasury_asset", desc = "Treasury asset paying the reward")]
+    #[account(4, writable, name = "vault", desc = "Vault token account of the treasury asset")]
+    #[account(5, writable, name = "winner_token_account", desc = "Winner's token account for the asset's mint")]
+    #[account(6, name = "token_program", desc = "SPL Token program")]
+    DistributeAssetReward {
+        round: u64,
+        amount: u64,
+    },
+
+    /// Take back a submission of the current round within `DaoState::retract_window` seconds of submitting it.
+    /// The content is removed, `retract_refund` percent of the fee is paid back from the fee pool, and if it
+    /// was the round's latest submission the one before it becomes the latest again. The timer is not rewound.
+    ///
+    /// Passing the content's `ExternalClaim` closes it too, so the external post can be submitted again.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Author of the content
+    /// 1. `[writable]` DAO account
+    /// 2. `[writable]` (optional) ExternalClaim of the content
+    #[account(0, writable, signer, name = "author", desc = "Author of the content, receives the refund")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, optional, writable, name = "claim", desc = "External claim of the content, closed to the author")]
+    RetractContent {
+        content: Pubkey,
+    },
+
+    /// Initialize a new DAO with the parameters of a preset (see `preset::DAO_PRESETS`)
+    ///
+    /// Accounts expected: the same as `InitializeDao`
+    #[account(0, writable, signer, name = "initializer", desc = "Initializer account, pays for the DAO account")]
+    #[account(1, writable, name = "dao", desc = "DAO account to be created (PDA of [\"dao\", initializer, dao_name])")]
+    #[account(2, name = "system_program", desc = "System program")]
+    InitializeDaoWithPreset {
+        dao_name: String,
+        preset: DaoPreset,
+    },
+
+    /// Submit content linked to a post on another platform. Same as `SubmitContent`, and also creates the
+    /// `ExternalClaim` of the post (author pays rent), so no one can submit that post to the DAO again.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Author account
+    /// 1. `[writable]` DAO account
+    /// 2. `[]` System program
+    /// 3. `[writable]` ExternalClaim account of the post
+    /// 4. `[writable]` (optional) SubmissionStreak account of the author
+    #[account(0, writable, signer, name = "author", desc = "Author account, pays the submission fee and the claim's rent")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, name = "system_program", desc = "System program")]
+    #[account(3, writable, name = "claim", desc = "External claim to be created (PDA of [\"claim\", dao, platform, sha256(id)])")]
+    #[account(4, optional, writable, name = "streak", desc = "Author's submission streak (PDA of [\"streak\", dao, author])")]
+    SubmitLinkedContent {
+        text: BoundedText,
+        image_uri: BoundedUri,
+        external_ref: ExternalRef,
+    },
+
+    /// Vote for a submission of the current round (its `content_key`), adding one to its `vote_count`. Depositors of
+    /// the round other than the author vote once per content: the `ContentVote` account, which the voter pays rent
+    /// for, records the vote.
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` Voter account (must have deposited this round)
+    /// 1. `[writable]` DAO account
+    /// 2. `[writable]` ContentVote account to be created
+    /// 3. `[]` System program
+    #[account(0, writable, signer, name = "voter", desc = "Voter account (must have deposited this round), pays for the content vote")]
+    #[account(1, writable, name = "dao", desc = "DAO account")]
+    #[account(2, writable, name = "content_vote", desc = "Content vote to be created (PDA of [\"content_vote\", dao, content, voter])")]
+    #[account(3, name = "system_program", desc = "System program")]
+    VoteContent {
+        content: Pubkey,
+    },
 }
 
 // Vote type enum
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VoteType {
     ChangeTimeLimit,
     ChangeBaseFee,
     ChangeAiModeration,
     ContentQualityRating,
+    ChangeDepositResetsTimer,
+    ChangeFeeTiers,
+    ReplaceAdmin,
+    ChangeDepositCaps,
+    ChangeStreakBonus,
+    ChangeRetractPolicy,
+    ChangeRoundMinimum,
 }
 
 // Vote status enum
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VoteStatus {
     Active,
     Completed,
     Executed,
 }
 
+// Whether the round is counting down. A new DAO starts `Dormant` and its first round opens once the pot
+// reaches `min_round_pot`; a round that closes leaves the DAO dormant until the pot qualifies again.
+// `Active` is tag 0 so accounts created before rounds could be dormant keep counting as they did.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundState {
+    Active,
+    Dormant,
+}
+
 // Depositor information
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DepositorInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
     pub depositor: Pubkey,
     pub amount: u64,
     pub timestamp: u64,
     pub locked_until: u64,
 }
 
-// Content structure
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+// Submission fee discount for depositors holding at least `min_deposit` in the current round
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeTier {
+    pub min_deposit: u64,
+    pub discount: u8, // percent off base_fee, 0..=100
+}
+
+// Content structure. Text and image URI keep the bounds they are submitted under (`BoundedText` / `BoundedUri`).
+#[derive(BorshSerialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Content {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
     pub author: Pubkey,
-    pub text: String,
-    pub image_uri: String,
+    pub text: BoundedText,
+    pub image_uri: BoundedUri,
     pub timestamp: u64,
     pub vote_count: u64,
 }
 
+impl Content {
+    /// Largest Borsh encoding of a content entry
+    pub const MAX_LEN: usize = 32 + BoundedText::MAX_LEN + BoundedUri::MAX_LEN + 8 + 8;
+}
+
+// Entries are only read from DAO accounts, where ones submitted before the bounds existed can be longer. Those
+// are cut down to the bounds (`BoundedString::deserialize_stored`) rather than failing the whole DAO; that changes
+// their key, so `DaoState::rekey_cut_submission` follows up once the state is decoded.
+impl BorshDeserialize for Content {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            author: Pubkey::deserialize_reader(reader)?,
+            text: BoundedText::deserialize_stored(reader)?,
+            image_uri: BoundedUri::deserialize_stored(reader)?,
+            timestamp: u64::deserialize_reader(reader)?,
+            vote_count: u64::deserialize_reader(reader)?,
+        })
+    }
+}
+
+// Platform of a post an `ExternalRef` points to
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExternalPlatform {
+    Twitter,
+    Farcaster,
+}
+
+// A post on another platform that a submission links to: a tweet id, or a Farcaster cast hash
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExternalRef {
+    pub platform: ExternalPlatform,
+    pub id: BoundedExternalId,
+}
+
+impl ExternalRef {
+    /// Largest Borsh encoding of an external reference
+    pub const MAX_LEN: usize = 1 + BoundedExternalId::MAX_LEN;
+}
+
 // Vote information
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VoteInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
     pub voter: Pubkey,
     pub option_index: u8,
     pub voting_power: u64,
@@ -129,8 +439,10 @@ pub struct VoteInfo {
 
 // Vote proposal
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VoteProposal {
     pub proposal_id: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
     pub proposer: Pubkey,
     pub title: String,
     pub description: String,
@@ -143,10 +455,13 @@ pub struct VoteProposal {
 }
 
 // DAO state structure
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DaoState {
     pub is_initialized: bool,
     pub dao_name: String,
+    // Acts as the DAO admin. A ReplaceAdmin referendum can rewrite it; the PDA stays derived from the original key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
     pub initializer: Pubkey,
     pub time_limit: u64,
     pub base_fee: u64,
@@ -158,6 +473,170 @@ pub struct DaoState {
     pub contents: Vec<Content>,
     pub vote_proposals: Vec<VoteProposal>,
     pub next_proposal_id: u64,
+    // Lamport ledger of the DAO account: lamports == rent_reserve + bounty_pool + fee_pool.
+    // Appended last so accounts created before these fields still decode (they read as zero, see `reconcile_ledger`).
+    pub rent_reserve: u64,              // paid by the initializer to keep the account rent-exempt
+    pub bounty_pool: u64,               // deposited lamports; unlike total_deposit it is not reset when a round ends
+    pub fee_pool: u64,                  // fees and lamports sent to the account outside the program; joins the pot when a round is won
+    // Winner of the current round: the author and key (`content_key`) of the latest submission.
+    // Default (all zeros) when nothing has been submitted since the round started.
+    pub last_submitter: Pubkey,
+    pub last_content: Pubkey,
+    // Game rule: deposits restart the countdown like submissions do. Accounts created before it read false.
+    pub deposit_resets_timer: bool,
+    // Submission fee discounts by deposit size, set by governance (ChangeFeeTiers). Empty: everyone pays base_fee.
+    pub fee_tiers: Vec<FeeTier>,
+    // Rounds closed with a winner so far; the next one is recorded as RoundResult number `round`
+    pub round: u64,
+    // Deposit limits set by governance (ChangeDepositCaps); 0 means no cap. The wallet cap applies to what
+    // one wallet has deposited this round, the total cap to the round's total_deposit.
+    pub max_deposit_per_wallet: u64,
+    pub max_total_deposit: u64,
+    // Streak bonus set by governance (ChangeStreakBonus): authors whose SubmissionStreak has reached
+    // `streak_days` consecutive days get `streak_discount` percent off the submission fee. 0 days: no bonus.
+    pub streak_days: u64,
+    pub streak_discount: u8,
+    // Retraction policy set by governance (ChangeRetractPolicy): authors may retract a submission for
+    // `retract_window` seconds after making it and get `retract_refund` percent of the fee back. 0 seconds: off.
+    pub retract_window: u64,
+    pub retract_refund: u8,
+    // Round bootstrap: while `Dormant` there is no deadline and submissions are refused. A deposit that brings
+    // total_deposit to at least `min_round_pot` (set by ChangeRoundMinimum, 0: any deposit) opens the round.
+    pub round_state: RoundState,
+    pub min_round_pot: u64,
+}
+
+// The leading fields of DaoState: enough to check the account and its admin without decoding the
+// depositors, contents and proposals behind them
+#[derive(BorshDeserialize)]
+struct DaoHeader {
+    is_initialized: bool,
+    _dao_name: String,
+    initializer: Pubkey,
+}
+
+// History of a round that closed with a winner, at the PDA of ["round", dao, round]. It keeps the winner
+// after the next round clears the contents, so past winners can be listed without replaying transactions.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundResult {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub dao: Pubkey,
+    pub round: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub winner: Pubkey,
+    pub reward: u64,                    // lamports awarded to the winner (the pot less the quality share)
+    pub pot: u64,                       // total_deposit of the round
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub content: Pubkey,                // content_key of the winning submission
+    pub submitted_at: u64,
+    pub closed_at: u64,
+    pub quality_pool: u64,              // quality share left in the bounty pool; 0 once DistributeQualityRewards paid it
+    pub candidates: Vec<QualityCandidate>, // the only creators DistributeQualityRewards pays
+}
+
+impl RoundResult {
+    // Allocated for the most candidates; fewer leave zeroes behind
+    pub const LEN: usize = 32 + 8 + 32 + 8 + 8 + 32 + 8 + 8 + 8 + 4 + MAX_QUALITY_CANDIDATES * QualityCandidate::LEN;
+
+    // Accounts created before quality_pool and candidates were appended are shorter and read them as zero (empty)
+    pub fn unpack(data: &[u8]) -> Result<Self, std::io::Error> {
+        let mut padded = data.to_vec();
+        padded.resize(padded.len().max(Self::LEN), 0);
+        try_from_slice_unchecked(&padded)
+    }
+}
+
+// A submission that had votes when its round closed. ProcessTimeout keeps the MAX_QUALITY_CANDIDATES with the
+// most votes (earlier submissions first on a tie), so the admin can only pay the quality share to their authors.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QualityCandidate {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub author: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub content: Pubkey,                // content_key of the submission
+    pub votes: u64,                     // vote_count when the round closed
+}
+
+impl QualityCandidate {
+    pub const LEN: usize = 32 + 32 + 8;
+}
+
+// SPL tokens a DAO holds in one mint, at the PDA of ["asset", dao, mint]. The tokens sit in the vault token
+// account at ["asset_vault", dao, mint], whose owner is this record, so only the program can move them.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreasuryAsset {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub dao: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub vault: Pubkey,
+    pub balance: u64,                   // deposited less withdrawn and paid out; tokens sent to the vault directly are not counted
+    pub next_round: u64,                // rounds before this one can no longer be paid in this asset
+}
+
+impl TreasuryAsset {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8;
+}
+
+// Consecutive days an author has submitted to a DAO, at the PDA of ["streak", dao, author]. Created by the
+// first SubmitContent that passes it; days are UTC days since the Unix epoch (timestamp / SECONDS_PER_DAY).
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubmissionStreak {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub dao: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub author: Pubkey,
+    pub last_day: u64,                  // day of the latest submission
+    pub days: u64,                      // consecutive days ending on last_day
+}
+
+impl SubmissionStreak {
+    pub const LEN: usize = 32 + 32 + 8 + 8;
+}
+
+// Claim of an external post by a submission, at the PDA of ["claim", dao, platform, sha256(id)]. Created by
+// `SubmitLinkedContent` and kept after the round ends, so a post can only be submitted to a DAO once; retracting
+// the content closes it. The fixed-size fields come first, so clients can look claims up by author or content
+// key with memcmp filters.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExternalClaim {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub dao: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub author: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub content: Pubkey,                // content_key of the submission
+    pub claimed_at: u64,
+    pub external_ref: ExternalRef,
+}
+
+impl ExternalClaim {
+    /// Space allocated for a claim: the largest encoding, whatever the id's length
+    pub const LEN: usize = 32 + 32 + 32 + 8 + ExternalRef::MAX_LEN;
+}
+
+// A depositor's vote for a content entry, at the PDA of ["content_vote", dao, content, voter]. Created by
+// `VoteContent`; its existence is what stops the voter from voting for that content twice.
+#[derive(BorshSerialize, BorshDeserialize, ShankAccount, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentVote {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub dao: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub content: Pubkey,                // content_key of the submission
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub voter: Pubkey,
+    pub voted_at: u64,
+}
+
+impl ContentVote {
+    pub const LEN: usize = 32 + 32 + 32 + 8;
 }
 
 impl IsInitialized for DaoState {
@@ -166,7 +645,8 @@ impl IsInitialized for DaoState {
     }
 }
 
-// Program entrypoint
+// Program entrypoint (left out with `no-entrypoint` so other crates can link this one)
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 // Program logic
@@ -185,6 +665,7 @@ pub fn process_instruction(
             base_fee,
             ai_moderation,
             deposit_share,
+            deposit_resets_timer,
         } => process_initialize_dao(
             program_id,
             accounts,
@@ -193,10 +674,11 @@ pub fn process_instruction(
             base_fee,
             ai_moderation,
             deposit_share,
+            deposit_resets_timer,
         ),
         TurtleInstruction::Deposit { amount } => process_deposit(program_id, accounts, amount),
         TurtleInstruction::SubmitContent { text, image_uri } => {
-            process_submit_content(program_id, accounts, text, image_uri)
+            process_submit_content(program_id, accounts, text, image_uri, None)
         }
         TurtleInstruction::CreateVote {
             title,
@@ -218,10 +700,38 @@ pub fn process_instruction(
             option_index,
         } => process_cast_vote(program_id, accounts, proposal_id, option_index),
         TurtleInstruction::ProcessTimeout {} => process_timeout(program_id, accounts),
+        TurtleInstruction::DistributeQualityRewards { creator_pubkeys, distribution_weights } => {
+            process_distribute_quality_rewards(program_id, accounts, creator_pubkeys, distribution_weights)
+        }
+        TurtleInstruction::InitializeTreasuryAsset {} => process_initialize_treasury_asset(program_id, accounts),
+        TurtleInstruction::DepositAsset { amount } => process_deposit_asset(program_id, accounts, amount),
+        TurtleInstruction::WithdrawAsset { amount } => process_withdraw_asset(program_id, accounts, amount),
+        TurtleInstruction::DistributeAssetReward { round, amount } => {
+            process_distribute_asset_reward(program_id, accounts, round, amount)
+        }
+        TurtleInstruction::RetractContent { content } => process_retract_content(program_id, accounts, content),
+        TurtleInstruction::InitializeDaoWithPreset { dao_name, preset } => {
+            let params = preset.params();
+            process_initialize_dao(
+                program_id,
+                accounts,
+                dao_name,
+                params.time_limit,
+                params.base_fee,
+                params.ai_moderation,
+                params.deposit_share,
+                params.deposit_resets_timer,
+            )
+        }
+        TurtleInstruction::SubmitLinkedContent { text, image_uri, external_ref } => {
+            process_submit_content(program_id, accounts, text, image_uri, Some(external_ref))
+        }
+        TurtleInstruction::VoteContent { content } => process_vote_content(program_id, accounts, content),
     }
 }
 
 // Initialize DAO function
+#[allow(clippy::too_many_arguments)]
 pub fn process_initialize_dao(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -230,6 +740,7 @@ pub fn process_initialize_dao(
     base_fee: u64,
     ai_moderation: bool,
     deposit_share: u8,
+    deposit_resets_timer: bool,
 ) -> ProgramResult {
     // Get accounts
     let account_iter = &mut accounts.iter();
@@ -248,14 +759,7 @@ pub fn process_initialize_dao(
     }
 
     // Create DAO account with PDA
-    let (dao_pda, bump_seed) = Pubkey::find_program_address(
-        &[
-            b"dao",
-            initializer.key.as_ref(),
-            dao_name.as_bytes(),
-        ],
-        program_id,
-    );
+    let (dao_pda, bump_seed) = pda::find_dao_address(program_id, initializer.key, &dao_name);
 
     // Verify the derived address
     if dao_pda != *dao_account.key {
@@ -277,13 +781,9 @@ pub fn process_initialize_dao(
             program_id,
         ),
         &[initializer.clone(), dao_account.clone(), system_program.clone()],
-        &[&[b"dao", initializer.key.as_ref(), dao_name.as_bytes(), &[bump_seed]]],
+        &[&[pda::DAO_SEED, initializer.key.as_ref(), dao_name.as_bytes(), &[bump_seed]]],
     )?;
 
-    // Get current timestamp
-    let clock = Clock::get()?;
-    let current_time = clock.unix_timestamp as u64;
-
     // Initialize DAO state
     let dao_state = DaoState {
         is_initialized: true,
@@ -293,16 +793,34 @@ pub fn process_initialize_dao(
         base_fee,
         ai_moderation,
         deposit_share,
-        timeout_timestamp: current_time + time_limit,
+        // No deadline until the first deposit opens the round
+        timeout_timestamp: 0,
         total_deposit: 0,
         depositors: Vec::new(),
         contents: Vec::new(),
         vote_proposals: Vec::new(),
         next_proposal_id: 0,
+        rent_reserve: rent_lamports,
+        bounty_pool: 0,
+        fee_pool: 0,
+        last_submitter: Pubkey::default(),
+        last_content: Pubkey::default(),
+        deposit_resets_timer,
+        fee_tiers: Vec::new(),
+        round: 0,
+        max_deposit_per_wallet: 0,
+        max_total_deposit: 0,
+        streak_days: 0,
+        streak_discount: 0,
+        retract_window: 0,
+        retract_refund: 0,
+        round_state: RoundState::Dormant,
+        min_round_pot: 0,
     };
+    check_ledger(&dao_state, dao_account.lamports())?;
 
     // Serialize and store the state
-    dao_state.serialize(&mut *dao_account.data.borrow_mut())?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
 
     msg!("DAO initialized: {}", dao_state.dao_name);
     Ok(())
@@ -330,20 +848,14 @@ pub fn process_deposit(
         return Err(ProgramError::InvalidArgument);
     }
 
-    // Verify the DAO account belongs to the program
-    if dao_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
     // Get current timestamp
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp as u64;
 
     // Get DAO state
-    let mut dao_state = try_from_slice_unchecked::<DaoState>(&dao_account.data.borrow())?;
-    if !dao_state.is_initialized {
-        return Err(ProgramError::UninitializedAccount);
-    }
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
+    check_deposit_caps(&dao_state, depositor.key, amount)?;
 
     // Transfer SOL from depositor to DAO account
     invoke(
@@ -383,47 +895,61 @@ pub fn process_deposit(
         });
     }
 
+    // Communities that opted in restart the round on deposits too; the last submitter stays the winner. A
+    // dormant DAO has no countdown to restart, so it keeps waiting for its minimum pot
+    if dao_state.deposit_resets_timer && dao_state.round_state == RoundState::Active {
+        dao_state.timeout_timestamp = current_time + dao_state.time_limit;
+    }
+
     // Update total deposit
-    dao_state.total_deposit += amount;
+    dao_state.total_deposit = dao_state.total_deposit.checked_add(amount).ok_or(TurtleError::AmountOverflow)?;
+    dao_state.bounty_pool = dao_state.bounty_pool.checked_add(amount).ok_or(TurtleError::AmountOverflow)?;
+    check_ledger(&dao_state, dao_account.lamports())?;
+
+    // The deposit that brings a dormant DAO's pot to its minimum starts the countdown
+    if open_round_if_funded(&mut dao_state, current_time) {
+        msg!("Round opened with a pot of {} lamports", dao_state.total_deposit);
+    }
 
     // Save updated state
-    dao_state.serialize(&mut *dao_account.data.borrow_mut())?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
 
     msg!("Deposit of {} lamports processed", amount);
     Ok(())
 }
 
-// Submit content function
+// Submit content function; `external_ref` is set for SubmitLinkedContent
 pub fn process_submit_content(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    text: String,
-    image_uri: String,
+    text: BoundedText,
+    image_uri: BoundedUri,
+    external_ref: Option<ExternalRef>,
 ) -> ProgramResult {
     // Get accounts
     let account_iter = &mut accounts.iter();
     let author = next_account_info(account_iter)?;
     let dao_account = next_account_info(account_iter)?;
+    let system_program = account_iter.next();
+    // A linked submission's claim account comes before the optional streak account
+    let claim = match external_ref {
+        Some(external_ref) => Some((external_ref, next_account_info(account_iter)?)),
+        None => None,
+    };
+    let streak_account = account_iter.next();
 
     // Check if author is the signer
     if !author.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Verify the DAO account belongs to the program
-    if dao_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
     // Get current timestamp
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp as u64;
 
     // Get DAO state
-    let mut dao_state = try_from_slice_unchecked::<DaoState>(&dao_account.data.borrow())?;
-    if !dao_state.is_initialized {
-        return Err(ProgramError::UninitializedAccount);
-    }
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
 
     // Verify author is a depositor
     let mut is_depositor = false;
@@ -438,6 +964,32 @@ pub fn process_submit_content(
         return Err(ProgramError::InvalidAccountData);
     }
 
+    // Nothing can be won before a deposit opens the round
+    if dao_state.round_state == RoundState::Dormant {
+        return Err(TurtleError::RoundDormant.into());
+    }
+
+    // Count today in the author's streak first, so the submission that reaches the bonus already gets it
+    let streak_days = match streak_account {
+        Some(streak_account) => {
+            let system_program = system_program.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            record_streak(program_id, author, dao_account.key, streak_account, system_program, current_time)?
+        }
+        None => 0,
+    };
+
+    // Charge the submission fee into fee_pool; free DAOs need no system program account
+    let fee = submission_fee(&dao_state, author.key, streak_days);
+    if fee > 0 {
+        let system_program = system_program.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        invoke(
+            &system_instruction::transfer(author.key, dao_account.key, fee),
+            &[author.clone(), dao_account.clone(), system_program.clone()],
+        )?;
+        dao_state.fee_pool = dao_state.fee_pool.checked_add(fee).ok_or(TurtleError::AmountOverflow)?;
+        check_ledger(&dao_state, dao_account.lamports())?;
+    }
+
     // Create new content
     let content = Content {
         author: *author.key,
@@ -447,19 +999,149 @@ pub fn process_submit_content(
         vote_count: 0,
     };
 
-    // Add content to DAO
+    // Record the submission as the round's latest together with the content itself
+    dao_state.last_submitter = content.author;
+    dao_state.last_content = content_key(&content);
     dao_state.contents.push(content);
 
+    // Claim the external post for this submission; an existing claim fails the whole submission
+    if let Some((external_ref, claim_account)) = claim {
+        let system_program = system_program.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let external_claim = ExternalClaim {
+            dao: *dao_account.key,
+            author: *author.key,
+            content: dao_state.last_content,
+            claimed_at: current_time,
+            external_ref,
+        };
+        create_external_claim(program_id, author, claim_account, system_program, &external_claim)?;
+    }
+
     // Reset timeout when content is submitted
     dao_state.timeout_timestamp = current_time + dao_state.time_limit;
 
     // Save updated state
-    dao_state.serialize(&mut *dao_account.data.borrow_mut())?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
 
     msg!("Content submitted, timeout reset");
     Ok(())
 }
 
+// Retract content function
+pub fn process_retract_content(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    content: Pubkey,
+) -> ProgramResult {
+    // Get accounts
+    let account_iter = &mut accounts.iter();
+    let author = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let claim_account = account_iter.next();
+
+    // Check if author is the signer
+    if !author.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Get current timestamp
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+
+    // Get DAO state
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
+
+    // Only the author's own content of the current round; the key covers the author, so others' never match
+    let index = dao_state.contents.iter()
+        .position(|entry| entry.author == *author.key && content_key(entry) == content)
+        .ok_or(TurtleError::InvalidContent)?;
+    if !can_retract(&dao_state, &dao_state.contents[index], current_time) {
+        return Err(TurtleError::RetractWindowClosed.into());
+    }
+
+    // Refund before removing the content, while the author still counts as this round's submitter
+    let refund = retract_refund(&dao_state, author.key).min(dao_state.fee_pool);
+    dao_state.contents.remove(index);
+
+    // The submission before it wins again if this one was the latest
+    if dao_state.last_content == content {
+        let previous = dao_state.contents.last().map(|entry| (entry.author, content_key(entry)));
+        let (last_submitter, last_content) = previous.unwrap_or_default();
+        dao_state.last_submitter = last_submitter;
+        dao_state.last_content = last_content;
+    }
+
+    // The DAO account belongs to the program, so the refund moves lamports directly
+    if refund > 0 {
+        **dao_account.try_borrow_mut_lamports()? -= refund;
+        let author_lamports = author.lamports().checked_add(refund).ok_or(TurtleError::AmountOverflow)?;
+        **author.try_borrow_mut_lamports()? = author_lamports;
+        dao_state.fee_pool -= refund;
+    }
+    check_ledger(&dao_state, dao_account.lamports())?;
+
+    // Release the external post the content claimed, returning the claim's rent
+    if let Some(claim_account) = claim_account {
+        close_external_claim(program_id, dao_account.key, &content, claim_account, author)?;
+    }
+
+    // Save updated state
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
+
+    msg!("Content retracted, {} lamports refunded", refund);
+    Ok(())
+}
+
+// Vote for content function
+pub fn process_vote_content(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    content: Pubkey,
+) -> ProgramResult {
+    // Get accounts
+    let account_iter = &mut accounts.iter();
+    let voter = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let vote_account = next_account_info(account_iter)?;
+    let system_program = next_account_info(account_iter)?;
+
+    // Check if voter is the signer
+    if !voter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Get current timestamp
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp as u64;
+
+    // Get DAO state
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
+
+    // Only depositors with a stake in this round vote, and not for their own content
+    if calculate_voting_power(voter.key, &dao_state.depositors) == 0 {
+        return Err(TurtleError::NotAuthorized.into());
+    }
+    let entry = dao_state.contents.iter_mut()
+        .find(|entry| content_key(entry) == content)
+        .ok_or(TurtleError::InvalidContent)?;
+    if entry.author == *voter.key {
+        return Err(TurtleError::NotAuthorized.into());
+    }
+    entry.vote_count = entry.vote_count.checked_add(1).ok_or(TurtleError::AmountOverflow)?;
+
+    // The vote record fails the whole vote if the voter already has one for this content
+    let content_vote = ContentVote { dao: *dao_account.key, content, voter: *voter.key, voted_at: current_time };
+    create_content_vote(program_id, voter, vote_account, system_program, &content_vote)?;
+
+    // Save updated state
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
+
+    msg!("Content {} voted by {}", content, voter.key);
+    Ok(())
+}
+
 // Create vote function
 pub fn process_create_vote(
     program_id: &Pubkey,
@@ -480,20 +1162,13 @@ pub fn process_create_vote(
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Verify the DAO account belongs to the program
-    if dao_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
     // Get current timestamp
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp as u64;
 
     // Get DAO state
-    let mut dao_state = try_from_slice_unchecked::<DaoState>(&dao_account.data.borrow())?;
-    if !dao_state.is_initialized {
-        return Err(ProgramError::UninitializedAccount);
-    }
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
 
     // Verify proposer is a depositor
     let mut is_depositor = false;
@@ -509,8 +1184,7 @@ pub fn process_create_vote(
     }
 
     // Validate voting period (at least one week)
-    const ONE_WEEK_SECONDS: u64 = 7 * 24 * 60 * 60;
-    if voting_period < ONE_WEEK_SECONDS {
+    if voting_period < MIN_VOTING_PERIOD {
         return Err(ProgramError::InvalidArgument);
     }
 
@@ -533,7 +1207,7 @@ pub fn process_create_vote(
     dao_state.next_proposal_id += 1;
 
     // Save updated state
-    dao_state.serialize(&mut *dao_account.data.borrow_mut())?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
 
     msg!("Vote proposal created: ID {}", dao_state.next_proposal_id - 1);
     Ok(())
@@ -556,20 +1230,13 @@ pub fn process_cast_vote(
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Verify the DAO account belongs to the program
-    if dao_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
     // Get current timestamp
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp as u64;
 
     // Get DAO state
-    let mut dao_state = try_from_slice_unchecked::<DaoState>(&dao_account.data.borrow())?;
-    if !dao_state.is_initialized {
-        return Err(ProgramError::UninitializedAccount);
-    }
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
 
     // Find voter's deposit amount for voting power
     let mut voting_power: u64 = 0;
@@ -627,7 +1294,7 @@ pub fn process_cast_vote(
     }
 
     // Save updated state
-    dao_state.serialize(&mut *dao_account.data.borrow_mut())?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
 
     msg!("Vote cast for proposal {}", proposal_id);
     Ok(())
@@ -649,19 +1316,21 @@ pub fn process_timeout(
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    // Verify the DAO account belongs to the program
-    if dao_account.owner != program_id {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-
     // Get current timestamp
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp as u64;
 
     // Get DAO state
-    let mut dao_state = try_from_slice_unchecked::<DaoState>(&dao_account.data.borrow())?;
-    if !dao_state.is_initialized {
-        return Err(ProgramError::UninitializedAccount);
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    reconcile_ledger(&mut dao_state, dao_account)?;
+
+    // A dormant DAO has no deadline and nothing to pay out; the timeout only settles finished votes, which may
+    // lower the minimum enough to open the round
+    if dao_state.round_state == RoundState::Dormant {
+        process_completed_votes(&mut dao_state, current_time);
+        open_round_if_funded(&mut dao_state, current_time);
+        dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
+        return Ok(());
     }
 
     // Check if timeout has occurred
@@ -670,10 +1339,22 @@ pub fn process_timeout(
     }
 
     // 스택 사용량을 줄이기 위해 별도의 함수로 분리
-    process_timeout_internal(&mut dao_state, current_time)?;
+    let round_result = process_timeout_internal(&mut dao_state, dao_account.key, current_time)?;
+
+    // A round with a winner pays out and leaves its RoundResult behind
+    if let Some(round_result) = round_result {
+        let round_account = next_account_info(account_iter)?;
+        let system_program = next_account_info(account_iter)?;
+        let winner_account = next_account_info(account_iter)?;
+        if *winner_account.key != round_result.winner {
+            return Err(ProgramError::InvalidArgument);
+        }
+        create_round_result(program_id, caller, round_account, system_program, &round_result)?;
+        distribute_rewards(&mut dao_state, round_result.reward, dao_account, winner_account)?;
+    }
 
     // Save updated state
-    dao_state.serialize(&mut *dao_account.data.borrow_mut())?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
@@ -681,40 +1362,48 @@ pub fn process_timeout(
 // 스택 사용량을 줄이기 위해 타임아웃 처리 로직을 분리
 fn process_timeout_internal(
     dao_state: &mut DaoState,
+    dao: &Pubkey,
     current_time: u64,
-) -> ProgramResult {
+) -> Result<Option<RoundResult>, ProgramError> {
     // Process any completed votes first
     process_completed_votes(dao_state, current_time);
 
-    // 최적화: 변수 스코프 제한하기
-    let best_content_info = {
-        // Find the best content by vote count
-        let mut best_index: Option<usize> = None;
-        let mut highest_votes: u64 = 0;
-
-        for (i, content) in dao_state.contents.iter().enumerate() {
-            if content.vote_count > highest_votes {
-                highest_votes = content.vote_count;
-                best_index = Some(i);
-            }
-        }
-        
-        best_index.map(|idx| (dao_state.contents[idx].author, highest_votes))
-    };
+    // The winner is the recorded last submitter, as long as the content it was recorded with is still there
+    let winner = find_last_submission(dao_state).map(|content| (content.author, content.timestamp));
+    let mut round_result = None;
 
     // If there's a winner, distribute rewards
-    if let Some((winner_pubkey, _)) = best_content_info {
-        // Calculate base fee amount from total deposit
-        let base_fee_amount = dao_state.total_deposit * (dao_state.base_fee as u64) / 100;
-        
-        // Calculate quality content producer share
-        let quality_share = base_fee_amount * (dao_state.deposit_share as u64) / 100;
-        
-        // Remaining amount to distribute proportionally
-        let remaining_amount = dao_state.total_deposit - base_fee_amount + (base_fee_amount - quality_share);
+    if let Some((winner_pubkey, submitted_at)) = winner {
+        // The round's submission fees (and lamports sent to the account outside the program) join its pot, so
+        // they are paid out with it rather than piling up in fee_pool
+        let fees = std::mem::take(&mut dao_state.fee_pool);
+        dao_state.bounty_pool = dao_state.bounty_pool.checked_add(fees).ok_or(TurtleError::AmountOverflow)?;
+        dao_state.total_deposit = dao_state.total_deposit.checked_add(fees).ok_or(TurtleError::AmountOverflow)?;
+
+        // Without votes no creator is eligible for the quality share, so the winner is paid the whole pot rather
+        // than leaving it in the bounty pool with nobody to claim it
+        let candidates = quality_candidates(&dao_state.contents);
+        let remaining_amount = match candidates.is_empty() {
+            true => dao_state.total_deposit,
+            false => winner_reward(dao_state),
+        };
+        let quality_pool = dao_state.total_deposit - remaining_amount;
+
+        round_result = Some(RoundResult {
+            dao: *dao,
+            round: dao_state.round,
+            winner: winner_pubkey,
+            reward: remaining_amount,
+            pot: dao_state.total_deposit,
+            content: dao_state.last_content,
+            submitted_at,
+            closed_at: current_time,
+            quality_pool,
+            candidates,
+        });
+        dao_state.round = dao_state.round.checked_add(1).ok_or(TurtleError::AmountOverflow)?;
 
         // Reset DAO state for next round
-        dao_state.timeout_timestamp = current_time + dao_state.time_limit;
         dao_state.total_deposit = 0;
         dao_state.contents.clear();
         
@@ -725,18 +1414,516 @@ fn process_timeout_internal(
         
         msg!("Timeout processed, rewards distributed to winner {}", winner_pubkey);
     } else {
-        // Reset timeout without distributing if no content was submitted
-        dao_state.timeout_timestamp = current_time + dao_state.time_limit;
         msg!("Timeout processed, no content submissions found");
     }
+    dao_state.last_submitter = Pubkey::default();
+    dao_state.last_content = Pubkey::default();
+
+    // The next round starts right away only if the pot left over still meets the minimum; until then there is
+    // no deadline, as after InitializeDao
+    dao_state.round_state = RoundState::Dormant;
+    dao_state.timeout_timestamp = 0;
+    open_round_if_funded(dao_state, current_time);
+
+    Ok(round_result)
+}
+
+// Creates the program-owned PDA `account` with `space` bytes, `payer` funding its rent. Anyone can send lamports
+// to the address beforehand, which makes `create_account` fail; such an account is topped up to rent exemption
+// and then allocated and assigned instead.
+fn create_pda_account<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent_lamports = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, account.key, rent_lamports, space as u64, program_id),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let shortfall = rent_lamports.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
+
+// Creates the RoundResult PDA of a closed round, funded by `payer`
+fn create_round_result<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    round_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    round_result: &RoundResult,
+) -> ProgramResult {
+    let (round_pda, bump_seed) = pda::find_round_address(program_id, &round_result.dao, round_result.round);
+    if round_pda != *round_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    create_pda_account(
+        program_id,
+        payer,
+        round_account,
+        system_program,
+        RoundResult::LEN,
+        &[pda::ROUND_SEED, round_result.dao.as_ref(), &round_result.round.to_le_bytes(), &[bump_seed]],
+    )?;
+    round_result.serialize(&mut &mut round_account.data.borrow_mut()[..])?;
 
+    msg!("Round {} recorded", round_result.round);
     Ok(())
 }
 
+// Counts a submission at `current_time` in the author's SubmissionStreak, creating the record (author pays
+// rent) on the first one. Returns the streak length including this submission.
+fn record_streak<'a>(
+    program_id: &Pubkey,
+    author: &AccountInfo<'a>,
+    dao: &Pubkey,
+    streak_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    current_time: u64,
+) -> Result<u64, ProgramError> {
+    let (streak_pda, bump_seed) = pda::find_streak_address(program_id, dao, author.key);
+    if streak_pda != *streak_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
 
-// Helper function to process completed votes
-// Helper function to process completed votes
-fn process_completed_votes(dao_state: &mut DaoState, current_time: u64) {
+    let mut streak = if streak_account.owner == program_id {
+        SubmissionStreak::try_from_slice(&streak_account.data.borrow())?
+    } else {
+        create_pda_account(
+            program_id,
+            author,
+            streak_account,
+            system_program,
+            SubmissionStreak::LEN,
+            &[pda::STREAK_SEED, dao.as_ref(), author.key.as_ref(), &[bump_seed]],
+        )?;
+        SubmissionStreak { dao: *dao, author: *author.key, last_day: 0, days: 0 }
+    };
+    advance_streak(&mut streak, current_time / SECONDS_PER_DAY);
+    streak.serialize(&mut &mut streak_account.data.borrow_mut()[..])?;
+
+    msg!("Submission streak: {} days", streak.days);
+    Ok(streak.days)
+}
+
+// Creates the ExternalClaim PDA of a linked submission, funded by its author. A claim that already exists makes
+// the submission fail with ExternalRefClaimed.
+fn create_external_claim<'a>(
+    program_id: &Pubkey,
+    author: &AccountInfo<'a>,
+    claim_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    external_claim: &ExternalClaim,
+) -> ProgramResult {
+    let external_ref = &external_claim.external_ref;
+    if external_ref.id.is_empty() {
+        return Err(TurtleError::InvalidContent.into());
+    }
+    let (claim_pda, bump_seed) = pda::find_claim_address(program_id, &external_claim.dao, external_ref.platform, &external_ref.id);
+    if claim_pda != *claim_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if claim_account.owner == program_id {
+        return Err(TurtleError::ExternalRefClaimed.into());
+    }
+
+    let id_hash = hashv(&[external_ref.id.as_bytes()]).to_bytes();
+    create_pda_account(
+        program_id,
+        author,
+        claim_account,
+        system_program,
+        ExternalClaim::LEN,
+        &[pda::CLAIM_SEED, external_claim.dao.as_ref(), &[external_ref.platform as u8], &id_hash, &[bump_seed]],
+    )?;
+    external_claim.serialize(&mut &mut claim_account.data.borrow_mut()[..])?;
+
+    msg!("Claimed {:?} post {}", external_ref.platform, external_ref.id.as_str());
+    Ok(())
+}
+
+// Creates the ContentVote PDA of a vote, funded by the voter. A vote that already exists makes the vote fail with
+// ContentAlreadyVoted.
+fn create_content_vote<'a>(
+    program_id: &Pubkey,
+    voter: &AccountInfo<'a>,
+    vote_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    content_vote: &ContentVote,
+) -> ProgramResult {
+    let (vote_pda, bump_seed) = pda::find_content_vote_address(program_id, &content_vote.dao, &content_vote.content, voter.key);
+    if vote_pda != *vote_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if vote_account.owner == program_id {
+        return Err(TurtleError::ContentAlreadyVoted.into());
+    }
+
+    create_pda_account(
+        program_id,
+        voter,
+        vote_account,
+        system_program,
+        ContentVote::LEN,
+        &[pda::CONTENT_VOTE_SEED, content_vote.dao.as_ref(), content_vote.content.as_ref(), voter.key.as_ref(), &[bump_seed]],
+    )?;
+    content_vote.serialize(&mut &mut vote_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+// Closes the ExternalClaim of the retracted `content`, sending its lamports to `author`
+fn close_external_claim(
+    program_id: &Pubkey,
+    dao: &Pubkey,
+    content: &Pubkey,
+    claim_account: &AccountInfo,
+    author: &AccountInfo,
+) -> ProgramResult {
+    if claim_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let external_claim = try_from_slice_unchecked::<ExternalClaim>(&claim_account.data.borrow())?;
+    if external_claim.dao != *dao || external_claim.content != *content {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let lamports = claim_account.lamports();
+    let author_lamports = author.lamports().checked_add(lamports).ok_or(TurtleError::AmountOverflow)?;
+    **author.try_borrow_mut_lamports()? = author_lamports;
+    **claim_account.try_borrow_mut_lamports()? = 0;
+    claim_account.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+// Reads an initialized DAO account owned by the program. Handlers call it once and write the state back
+// once at the end, only if they changed it.
+fn load_dao_state(program_id: &Pubkey, dao_account: &AccountInfo) -> Result<DaoState, ProgramError> {
+    if dao_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut dao_state = try_from_slice_unchecked::<DaoState>(&dao_account.data.borrow())?;
+    if !dao_state.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    dao_state.rekey_cut_submission();
+    Ok(dao_state)
+}
+
+// Like `load_dao_state`, decoding only the `DaoHeader`. Instructions that just check the DAO and its admin use
+// it so their cost does not grow with what the DAO holds, and they never write it back.
+fn load_dao_header(program_id: &Pubkey, dao_account: &AccountInfo) -> Result<DaoHeader, ProgramError> {
+    if dao_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let header = try_from_slice_unchecked::<DaoHeader>(&dao_account.data.borrow())?;
+    if !header.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    Ok(header)
+}
+
+// Like `load_dao_header`, for instructions only the DAO admin (`initializer`) may sign
+fn load_dao_as_admin(program_id: &Pubkey, dao_account: &AccountInfo, admin: &AccountInfo) -> Result<DaoHeader, ProgramError> {
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let header = load_dao_header(program_id, dao_account)?;
+    if header.initializer != *admin.key {
+        return Err(TurtleError::NotAdmin.into());
+    }
+    Ok(header)
+}
+
+// Reads the TreasuryAsset of `dao` that `vault` belongs to, with the bump its PDA signs with
+fn load_treasury_asset(
+    program_id: &Pubkey,
+    asset_account: &AccountInfo,
+    dao: &Pubkey,
+    vault: &AccountInfo,
+) -> Result<(TreasuryAsset, u8), ProgramError> {
+    if asset_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let asset = TreasuryAsset::try_from_slice(&asset_account.data.borrow())?;
+    let (asset_pda, bump_seed) = pda::find_treasury_asset_address(program_id, dao, &asset.mint);
+    if asset_pda != *asset_account.key || asset.dao != *dao || asset.vault != *vault.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok((asset, bump_seed))
+}
+
+fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Moves tokens out of an asset's vault, signed by the TreasuryAsset PDA that owns it
+fn transfer_from_vault<'a>(
+    token_program: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    asset_account: &AccountInfo<'a>,
+    asset: &TreasuryAsset,
+    bump_seed: u8,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token::instruction::transfer(token_program.key, vault.key, destination.key, asset_account.key, &[], amount)?,
+        &[vault.clone(), destination.clone(), asset_account.clone(), token_program.clone()],
+        &[&[pda::ASSET_SEED, asset.dao.as_ref(), asset.mint.as_ref(), &[bump_seed]]],
+    )
+}
+
+// Register an SPL mint in the DAO treasury
+pub fn process_initialize_treasury_asset(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+    let admin = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let asset_account = next_account_info(account_iter)?;
+    let vault = next_account_info(account_iter)?;
+    let mint = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+    let system_program = next_account_info(account_iter)?;
+
+    load_dao_as_admin(program_id, dao_account, admin)?;
+    check_token_program(token_program)?;
+    if mint.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (asset_pda, asset_bump) = pda::find_treasury_asset_address(program_id, dao_account.key, mint.key);
+    let (vault_pda, vault_bump) = pda::find_asset_vault_address(program_id, dao_account.key, mint.key);
+    if asset_pda != *asset_account.key || vault_pda != *vault.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let rent = Rent::get()?;
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            asset_account.key,
+            rent.minimum_balance(TreasuryAsset::LEN),
+            TreasuryAsset::LEN as u64,
+            program_id,
+        ),
+        &[admin.clone(), asset_account.clone(), system_program.clone()],
+        &[&[pda::ASSET_SEED, dao_account.key.as_ref(), mint.key.as_ref(), &[asset_bump]]],
+    )?;
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            vault.key,
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            token_program.key,
+        ),
+        &[admin.clone(), vault.clone(), system_program.clone()],
+        &[&[pda::ASSET_VAULT_SEED, dao_account.key.as_ref(), mint.key.as_ref(), &[vault_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(token_program.key, vault.key, mint.key, asset_account.key)?,
+        &[vault.clone(), mint.clone(), token_program.clone()],
+    )?;
+
+    let asset = TreasuryAsset { dao: *dao_account.key, mint: *mint.key, vault: *vault.key, balance: 0, next_round: 0 };
+    asset.serialize(&mut &mut asset_account.data.borrow_mut()[..])?;
+
+    msg!("Treasury asset {} registered", mint.key);
+    Ok(())
+}
+
+// Deposit SPL tokens into the DAO treasury
+pub fn process_deposit_asset(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+    let depositor = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let asset_account = next_account_info(account_iter)?;
+    let vault = next_account_info(account_iter)?;
+    let source = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+
+    if !depositor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if amount == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    load_dao_header(program_id, dao_account)?;
+    check_token_program(token_program)?;
+    let (mut asset, _) = load_treasury_asset(program_id, asset_account, dao_account.key, vault)?;
+
+    // The token program rejects a source of another mint
+    invoke(
+        &spl_token::instruction::transfer(token_program.key, source.key, vault.key, depositor.key, &[], amount)?,
+        &[source.clone(), vault.clone(), depositor.clone(), token_program.clone()],
+    )?;
+    asset.balance = asset.balance.checked_add(amount).ok_or(TurtleError::AmountOverflow)?;
+    asset.serialize(&mut &mut asset_account.data.borrow_mut()[..])?;
+
+    msg!("Deposit of {} tokens of {} processed", amount, asset.mint);
+    Ok(())
+}
+
+// Withdraw SPL tokens from the DAO treasury
+pub fn process_withdraw_asset(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+    let admin = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let asset_account = next_account_info(account_iter)?;
+    let vault = next_account_info(account_iter)?;
+    let destination = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+
+    load_dao_as_admin(program_id, dao_account, admin)?;
+    check_token_program(token_program)?;
+    let (mut asset, bump_seed) = load_treasury_asset(program_id, asset_account, dao_account.key, vault)?;
+    if amount == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    asset.balance = asset.balance.checked_sub(amount).ok_or(TurtleError::InsufficientTreasuryBalance)?;
+
+    transfer_from_vault(token_program, vault, destination, asset_account, &asset, bump_seed, amount)?;
+    asset.serialize(&mut &mut asset_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrawal of {} tokens of {} processed", amount, asset.mint);
+    Ok(())
+}
+
+// Pay the winner of a closed round in a treasury asset
+pub fn process_distribute_asset_reward(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    round: u64,
+    amount: u64,
+) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+    let admin = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let round_account = next_account_info(account_iter)?;
+    let asset_account = next_account_info(account_iter)?;
+    let vault = next_account_info(account_iter)?;
+    let winner_account = next_account_info(account_iter)?;
+    let token_program = next_account_info(account_iter)?;
+
+    load_dao_as_admin(program_id, dao_account, admin)?;
+    check_token_program(token_program)?;
+    let (mut asset, bump_seed) = load_treasury_asset(program_id, asset_account, dao_account.key, vault)?;
+    if amount == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Only the program writes RoundResult accounts, so the winner they name can be trusted
+    let (round_pda, _) = pda::find_round_address(program_id, dao_account.key, round);
+    if round_account.owner != program_id || round_pda != *round_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let round_result = RoundResult::unpack(&round_account.data.borrow())?;
+    if round < asset.next_round {
+        return Err(TurtleError::RoundAlreadyPaid.into());
+    }
+
+    if winner_account.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let winner_tokens = spl_token::state::Account::unpack(&winner_account.data.borrow())?;
+    if winner_tokens.owner != round_result.winner || winner_tokens.mint != asset.mint {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    asset.balance = asset.balance.checked_sub(amount).ok_or(TurtleError::InsufficientTreasuryBalance)?;
+    asset.next_round = round.checked_add(1).ok_or(TurtleError::AmountOverflow)?;
+    transfer_from_vault(token_program, vault, winner_account, asset_account, &asset, bump_seed, amount)?;
+    asset.serialize(&mut &mut asset_account.data.borrow_mut()[..])?;
+
+    msg!("Round {} winner {} paid {} tokens of {}", round, round_result.winner, amount, asset.mint);
+    Ok(())
+}
+
+// Pay a closed round's quality share to content creators
+pub fn process_distribute_quality_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    creator_pubkeys: Vec<Pubkey>,
+    distribution_weights: Vec<u8>,
+) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+    let admin = next_account_info(account_iter)?;
+    let dao_account = next_account_info(account_iter)?;
+    let round_account = next_account_info(account_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut dao_state = load_dao_state(program_id, dao_account)?;
+    if dao_state.initializer != *admin.key {
+        return Err(TurtleError::NotAdmin.into());
+    }
+    reconcile_ledger(&mut dao_state, dao_account)?;
+
+    // Only the program writes RoundResult accounts, so the quality pool they hold can be trusted
+    if round_account.owner != program_id {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut round_result = RoundResult::unpack(&round_account.data.borrow())?;
+    let (round_pda, _) = pda::find_round_address(program_id, dao_account.key, round_result.round);
+    if round_pda != *round_account.key || round_result.dao != *dao_account.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if round_result.quality_pool == 0 {
+        return Err(TurtleError::RoundAlreadyPaid.into());
+    }
+    check_quality_distribution(&creator_pubkeys, &distribution_weights)?;
+    if !creator_pubkeys.iter().all(|creator| round_result.candidates.iter().any(|candidate| candidate.author == *creator)) {
+        return Err(TurtleError::IneligibleCreator.into());
+    }
+
+    let pool = round_result.quality_pool;
+    let shares = quality_shares(pool, &distribution_weights);
+    for (creator, share) in creator_pubkeys.iter().zip(shares) {
+        let creator_account = next_account_info(account_iter)?;
+        if creator_account.key != creator {
+            return Err(ProgramError::InvalidArgument);
+        }
+        distribute_rewards(&mut dao_state, share, dao_account, creator_account)?;
+    }
+    round_result.quality_pool = 0;
+    check_ledger(&dao_state, dao_account.lamports())?;
+
+    round_result.serialize(&mut &mut round_account.data.borrow_mut()[..])?;
+    dao_state.serialize(&mut &mut dao_account.data.borrow_mut()[..])?;
+
+    msg!("Round {} quality pool of {} lamports paid to {} creators", round_result.round, pool, creator_pubkeys.len());
+    Ok(())
+}
+
+// Helper function to process completed votes
+// Helper function to process completed votes
+fn process_completed_votes(dao_state: &mut DaoState, current_time: u64) {
     for proposal in dao_state.vote_proposals.iter_mut() {
         // Skip already completed votes
         if proposal.status != VoteStatus::Active {
@@ -801,6 +1988,58 @@ fn process_completed_votes(dao_state: &mut DaoState, current_time: u64) {
                     // when determining rewards distribution
                     proposal.status = VoteStatus::Executed;
                 },
+                VoteType::ChangeDepositResetsTimer => {
+                    // Same "On"/"Off" options as AI moderation
+                    dao_state.deposit_resets_timer = proposal.options[winning_index].to_lowercase() == "on";
+                    proposal.status = VoteStatus::Executed;
+                },
+                VoteType::ChangeFeeTiers => {
+                    // Options list tiers as "min_deposit:discount" pairs (see parse_fee_tiers)
+                    if let Some(fee_tiers) = parse_fee_tiers(&proposal.options[winning_index]) {
+                        dao_state.fee_tiers = fee_tiers;
+                        proposal.status = VoteStatus::Executed;
+                    }
+                },
+                VoteType::ReplaceAdmin => {
+                    // Options are base58 admin keys; without a supermajority the proposal just stays Completed
+                    if let Some(new_admin) = parse_admin(&proposal.options[winning_index]) {
+                        if has_supermajority(highest_votes, total_votes, dao_state.total_deposit) {
+                            dao_state.initializer = new_admin;
+                            proposal.status = VoteStatus::Executed;
+                        }
+                    }
+                },
+                VoteType::ChangeDepositCaps => {
+                    // Options are "per_wallet:total" caps (see parse_deposit_caps); deposits already made stay
+                    if let Some((per_wallet, total)) = parse_deposit_caps(&proposal.options[winning_index]) {
+                        dao_state.max_deposit_per_wallet = per_wallet;
+                        dao_state.max_total_deposit = total;
+                        proposal.status = VoteStatus::Executed;
+                    }
+                },
+                VoteType::ChangeStreakBonus => {
+                    // Options are "days:discount" (see parse_streak_bonus); streaks already counted stay
+                    if let Some((days, discount)) = parse_streak_bonus(&proposal.options[winning_index]) {
+                        dao_state.streak_days = days;
+                        dao_state.streak_discount = discount;
+                        proposal.status = VoteStatus::Executed;
+                    }
+                },
+                VoteType::ChangeRetractPolicy => {
+                    // Options are "window:refund" (see parse_retract_policy)
+                    if let Some((window, refund)) = parse_retract_policy(&proposal.options[winning_index]) {
+                        dao_state.retract_window = window;
+                        dao_state.retract_refund = refund;
+                        proposal.status = VoteStatus::Executed;
+                    }
+                },
+                VoteType::ChangeRoundMinimum => {
+                    // Options are lamport amounts (see parse_round_minimum); an open round stays open
+                    if let Some(min_round_pot) = parse_round_minimum(&proposal.options[winning_index]) {
+                        dao_state.min_round_pot = min_round_pot;
+                        proposal.status = VoteStatus::Executed;
+                    }
+                },
             }
         }
     }
@@ -829,7 +2068,23 @@ pub fn get_space_needed(
                   4 + // Vec<DepositorInfo> length
                   4 + // Vec<Content> length
                   4 + // Vec<VoteProposal> length
-                  8;  // next_proposal_id: u64
+                  8 + // next_proposal_id: u64
+                  8 + // rent_reserve: u64
+                  8 + // bounty_pool: u64
+                  8 + // fee_pool: u64
+                  32 + // last_submitter: Pubkey
+                  32 + // last_content: Pubkey
+                  1 +  // deposit_resets_timer: bool
+                  4 + MAX_FEE_TIERS * (8 + 1) + // fee_tiers: Vec<FeeTier> (min_deposit: u64, discount: u8)
+                  8 + // round: u64
+                  8 + // max_deposit_per_wallet: u64
+                  8 + // max_total_deposit: u64
+                  8 + // streak_days: u64
+                  1 + // streak_discount: u8
+                  8 + // retract_window: u64
+                  1 + // retract_refund: u8
+                  1 + // round_state: RoundState
+                  8;  // min_round_pot: u64
 
     // Add space for depositors
     size += max_depositors * (
@@ -839,14 +2094,8 @@ pub fn get_space_needed(
         8    // locked_until: u64
     );
 
-    // Add space for contents (assuming average text and image URI sizes)
-    size += max_contents * (
-        32 +  // author: Pubkey
-        100 + // text: String (approximate)
-        100 + // image_uri: String (approximate)
-        8 +   // timestamp: u64
-        8     // vote_count: u64
-    );
+    // Add space for contents at their largest (text and image URI are bounded)
+    size += max_contents * Content::MAX_LEN;
 
     // Add space for votes (assuming average sizes)
     size += max_votes * (
@@ -864,6 +2113,18 @@ pub fn get_space_needed(
 
     size
 }
+
+// A submission from before the bounds that was cut down when decoded has a new key. If it was the round's latest,
+// `last_content` still holds the old one; the latest submission is always the last entry, so it is re-pointed there
+// and the author keeps the round.
+pub fn rekey_cut_submission(&mut self) {
+    if self.last_submitter == Pubkey::default() || find_last_submission(self).is_some() {
+        return;
+    }
+    if let Some(last) = self.contents.last().filter(|content| content.author == self.last_submitter) {
+        self.last_content = content_key(last);
+    }
+}
 }
 
 // Function to check if a depositor exists
@@ -895,6 +2156,188 @@ for content in contents {
 Some((best_author, highest_votes))
 }
 
+// Identifies a content entry: contents live inside the DAO account rather than in accounts of their own
+pub fn content_key(content: &Content) -> Pubkey {
+let hash = hashv(&[
+    content.author.as_ref(),
+    content.text.as_bytes(),
+    content.image_uri.as_bytes(),
+    &content.timestamp.to_le_bytes(),
+]);
+Pubkey::new_from_array(hash.to_bytes())
+}
+
+// Content recorded as the round's latest submission, if its author and key still match
+pub fn find_last_submission(dao_state: &DaoState) -> Option<&Content> {
+if dao_state.last_submitter == Pubkey::default() {
+    return None;
+}
+dao_state.contents.iter().rev().find(|content| {
+    content.author == dao_state.last_submitter && content_key(content) == dao_state.last_content
+})
+}
+
+// Submission fee for `author`: base_fee lamports less the largest discount its deposit or its submission
+// streak (`streak_days` consecutive days, 0 without a streak account) qualifies for
+pub fn submission_fee(dao_state: &DaoState, author: &Pubkey, streak_days: u64) -> u64 {
+let deposit = calculate_voting_power(author, &dao_state.depositors);
+let tier_discount = dao_state.fee_tiers.iter()
+    .filter(|tier| deposit >= tier.min_deposit)
+    .map(|tier| tier.discount.min(100))
+    .max()
+    .unwrap_or(0);
+let streak_discount = match dao_state.streak_days {
+    0 => 0,
+    required if streak_days >= required => dao_state.streak_discount.min(100),
+    _ => 0,
+};
+let discount = tier_discount.max(streak_discount);
+percent_off(dao_state.base_fee, discount as u64)
+}
+
+// Parses a ChangeFeeTiers option: "none" clears the tiers, otherwise comma separated "min_deposit:discount"
+// pairs such as "1000000000:50,5000000000:75" (discount in percent, at most MAX_FEE_TIERS pairs)
+pub fn parse_fee_tiers(option: &str) -> Option<Vec<FeeTier>> {
+let option = option.trim();
+if option.eq_ignore_ascii_case("none") {
+    return Some(Vec::new());
+}
+let mut fee_tiers = Vec::new();
+for pair in option.split(',') {
+    let (min_deposit, discount) = pair.split_once(':')?;
+    let min_deposit = min_deposit.trim().parse::<u64>().ok()?;
+    let discount = discount.trim().trim_end_matches('%').parse::<u8>().ok()?;
+    if discount > 100 {
+        return None;
+    }
+    fee_tiers.push(FeeTier { min_deposit, discount });
+}
+if fee_tiers.len() > MAX_FEE_TIERS {
+    return None;
+}
+Some(fee_tiers)
+}
+
+// Rejects a deposit that would take the wallet's deposits this round or the round's total over a cap
+pub fn check_deposit_caps(dao_state: &DaoState, depositor: &Pubkey, amount: u64) -> Result<(), TurtleError> {
+let deposited = dao_state.depositors.iter()
+    .find(|info| info.depositor == *depositor)
+    .map_or(0, |info| info.amount);
+if dao_state.max_deposit_per_wallet != 0 && deposited.saturating_add(amount) > dao_state.max_deposit_per_wallet {
+    return Err(TurtleError::WalletDepositCapExceeded);
+}
+if dao_state.max_total_deposit != 0 && dao_state.total_deposit.saturating_add(amount) > dao_state.max_total_deposit {
+    return Err(TurtleError::TotalDepositCapExceeded);
+}
+Ok(())
+}
+
+// Parses a ChangeDepositCaps option: "max_deposit_per_wallet:max_total_deposit" in lamports, where 0 or "none"
+// lifts that cap, e.g. "1000000000:none". A lone "none" lifts both.
+pub fn parse_deposit_caps(option: &str) -> Option<(u64, u64)> {
+let option = option.trim();
+if option.eq_ignore_ascii_case("none") {
+    return Some((0, 0));
+}
+let cap = |value: &str| match value.trim() {
+    value if value.eq_ignore_ascii_case("none") => Some(0),
+    value => value.parse::<u64>().ok(),
+};
+let (per_wallet, total) = option.split_once(':')?;
+Some((cap(per_wallet)?, cap(total)?))
+}
+
+// Counts a submission on `day` in `streak`: the same day again changes nothing, the next day extends the
+// streak and any later day starts it over
+pub fn advance_streak(streak: &mut SubmissionStreak, day: u64) {
+if streak.days > 0 && day == streak.last_day {
+    return;
+}
+streak.days = match streak.days {
+    days if days > 0 && day == streak.last_day.saturating_add(1) => days.saturating_add(1),
+    _ => 1,
+};
+streak.last_day = day;
+}
+
+// Parses a ChangeStreakBonus option: "streak_days:discount" (discount in percent), e.g. "7:20" for 20% off
+// after a week of daily submissions. "none" turns the bonus off.
+pub fn parse_streak_bonus(option: &str) -> Option<(u64, u8)> {
+let option = option.trim();
+if option.eq_ignore_ascii_case("none") {
+    return Some((0, 0));
+}
+let (days, discount) = option.split_once(':')?;
+let days = days.trim().parse::<u64>().ok().filter(|days| *days > 0)?;
+let discount = discount.trim().trim_end_matches('%').parse::<u8>().ok().filter(|discount| *discount <= 100)?;
+Some((days, discount))
+}
+
+// Whether `content` may still be retracted at `current_time`: within the DAO's retract window of its
+// submission and before the round's deadline, so a closed round's winner can no longer be changed
+pub fn can_retract(dao_state: &DaoState, content: &Content, current_time: u64) -> bool {
+dao_state.retract_window > 0
+    && current_time <= content.timestamp.saturating_add(dao_state.retract_window)
+    && current_time < dao_state.timeout_timestamp
+}
+
+// Lamports refunded to `author` for a retracted submission: `retract_refund` percent of the lowest fee the
+// author could have paid for it. Fee settings only change when a round closes and deposits only grow within
+// one, so this never exceeds what was charged; the streak discount is assumed since it isn't recorded.
+pub fn retract_refund(dao_state: &DaoState, author: &Pubkey) -> u64 {
+let fee = submission_fee(dao_state, author, u64::MAX);
+percent_of(fee, dao_state.retract_refund as u64)
+}
+
+// Parses a ChangeRetractPolicy option: "window_seconds:refund" (refund in percent of the fee), e.g. "600:50"
+// for half the fee back within ten minutes. "none" turns retraction off.
+pub fn parse_retract_policy(option: &str) -> Option<(u64, u8)> {
+let option = option.trim();
+if option.eq_ignore_ascii_case("none") {
+    return Some((0, 0));
+}
+let (window, refund) = option.split_once(':')?;
+let window = window.trim().parse::<u64>().ok().filter(|window| *window > 0)?;
+let refund = refund.trim().trim_end_matches('%').parse::<u8>().ok().filter(|refund| *refund <= 100)?;
+Some((window, refund))
+}
+
+// Parses a ChangeRoundMinimum option: the pot in lamports a dormant round needs before it opens, e.g.
+// "1000000000". "none" (or 0) lets any deposit open it.
+pub fn parse_round_minimum(option: &str) -> Option<u64> {
+let option = option.trim();
+if option.eq_ignore_ascii_case("none") {
+    return Some(0);
+}
+option.parse::<u64>().ok()
+}
+
+// Opens a dormant round once the pot holds something and at least `min_round_pot`, starting its countdown at
+// `current_time`. Returns whether it did.
+pub fn open_round_if_funded(dao_state: &mut DaoState, current_time: u64) -> bool {
+if dao_state.round_state == RoundState::Active
+    || dao_state.total_deposit == 0
+    || dao_state.total_deposit < dao_state.min_round_pot
+{
+    return false;
+}
+dao_state.round_state = RoundState::Active;
+dao_state.timeout_timestamp = current_time + dao_state.time_limit;
+true
+}
+
+// Parses a ReplaceAdmin option: the new admin's base58 key (the default key is rejected)
+pub fn parse_admin(option: &str) -> Option<Pubkey> {
+Pubkey::from_str(option.trim()).ok().filter(|admin| *admin != Pubkey::default())
+}
+
+// A ReplaceAdmin proposal passes only when the winning option holds at least two thirds of the votes cast
+// and of the current round's deposits, so a handful of small depositors can't take the DAO over
+pub fn has_supermajority(winning_votes: u64, total_votes: u64, total_deposit: u64) -> bool {
+let base = total_votes.max(total_deposit) as u128;
+winning_votes > 0 && winning_votes as u128 * 3 >= base * 2
+}
+
 // Helper function to tally votes for a proposal
 pub fn tally_proposal_votes(proposal: &VoteProposal) -> Vec<u64> {
 let mut option_votes = vec![0; proposal.options.len()];
@@ -921,35 +2364,74 @@ for depositor in depositors {
 0
 }
 
-// Function to check if time limit has expired
+// Function to check if time limit has expired (a dormant round has no deadline)
 pub fn is_timeout_expired(
 dao_state: &DaoState, 
 current_time: u64
 ) -> bool {
-current_time >= dao_state.timeout_timestamp
+dao_state.round_state == RoundState::Active && current_time >= dao_state.timeout_timestamp
+}
+
+// Lamports the winner of a closing round is owed: the pot less the quality content share, which is
+// `deposit_share` percent of `base_fee` percent of the pot. Nothing else is held back from the winner.
+pub fn winner_reward(dao_state: &DaoState) -> u64 {
+let base_fee_amount = percent_of(dao_state.total_deposit, dao_state.base_fee);
+let quality_share = percent_of(base_fee_amount, dao_state.deposit_share as u64);
+dao_state.total_deposit - quality_share
+}
+
+// A quality reward distribution pays each creator once, with weights summing to 100 percent
+pub fn check_quality_distribution(creator_pubkeys: &[Pubkey], distribution_weights: &[u8]) -> Result<(), TurtleError> {
+if creator_pubkeys.is_empty() || creator_pubkeys.len() != distribution_weights.len() {
+    return Err(TurtleError::InvalidDistribution);
+}
+if distribution_weights.iter().map(|weight| *weight as u64).sum::<u64>() != PERCENT {
+    return Err(TurtleError::InvalidDistribution);
+}
+if creator_pubkeys.iter().enumerate().any(|(i, creator)| creator_pubkeys[..i].contains(creator)) {
+    return Err(TurtleError::InvalidDistribution);
+}
+Ok(())
+}
+
+// The voted submissions of a closing round, most votes first (a stable sort keeps submission order on a tie),
+// at most MAX_QUALITY_CANDIDATES of them
+pub fn quality_candidates(contents: &[Content]) -> Vec<QualityCandidate> {
+let mut voted: Vec<&Content> = contents.iter().filter(|content| content.vote_count > 0).collect();
+voted.sort_by_key(|content| std::cmp::Reverse(content.vote_count));
+voted.into_iter()
+    .take(MAX_QUALITY_CANDIDATES)
+    .map(|content| QualityCandidate { author: content.author, content: content_key(content), votes: content.vote_count })
+    .collect()
 }
 
-// Helper function to distribute rewards to winner and depositors
-// Note: This would be implemented with actual token transfers in production
+// Lamports each weight earns out of `pool`: its percentage rounded down, with what rounding leaves over added
+// to the first share so the whole pool is paid
+pub fn quality_shares(pool: u64, distribution_weights: &[u8]) -> Vec<u64> {
+let mut shares: Vec<u64> = distribution_weights.iter().map(|weight| percent_of(pool, *weight as u64)).collect();
+let paid: u64 = shares.iter().sum();
+if let Some(first) = shares.first_mut() {
+    *first += pool - paid;
+}
+shares
+}
+
+// Pays a reward out of the bounty pool: the winner of a closing round, or a creator from a closed round's
+// quality share (its RoundResult::quality_pool, held in the bounty pool until DistributeQualityRewards). The DAO account is owned by the program, so its
+// lamports move without a system program CPI.
 pub fn distribute_rewards(
-dao_state: &DaoState,
-winner: &Pubkey,
-winner_amount: u64,
+dao_state: &mut DaoState,
+amount: u64,
 dao_account: &AccountInfo,
-program_id: &Pubkey
+recipient: &AccountInfo,
 ) -> ProgramResult {
-// In a real implementation, this would:
-// 1. Calculate each depositor's share
-// 2. Transfer SOL to the winner
-// 3. Return remaining funds to depositors proportionally
-
-// This would require CPIs to the System Program or Token Program
-
-// For now, just log the distribution
-msg!("Would distribute {} lamports to winner {}", winner_amount, winner);
-msg!("Remaining {} lamports would be distributed to depositors", 
-     dao_state.total_deposit - winner_amount);
-     
+dao_state.bounty_pool = dao_state.bounty_pool.checked_sub(amount).ok_or(TurtleError::LedgerMismatch)?;
+let dao_lamports = dao_account.lamports().checked_sub(amount).ok_or(TurtleError::LedgerMismatch)?;
+let recipient_lamports = recipient.lamports().checked_add(amount).ok_or(TurtleError::AmountOverflow)?;
+**dao_account.try_borrow_mut_lamports()? = dao_lamports;
+**recipient.try_borrow_mut_lamports()? = recipient_lamports;
+
+msg!("Paid {} lamports to {}", amount, recipient.key);
 Ok(())
 }
 
@@ -999,6 +2481,61 @@ match proposal.vote_type {
         // Nothing to update for content ratings
         msg!("Content quality rating processed");
     },
+    VoteType::ChangeDepositResetsTimer => {
+        let option_str = proposal.options[winning_option].to_lowercase();
+        if option_str == "true" || option_str == "on" {
+            dao_state.deposit_resets_timer = true;
+            msg!("Deposits now reset the timer");
+        } else if option_str == "false" || option_str == "off" {
+            dao_state.deposit_resets_timer = false;
+            msg!("Deposits no longer reset the timer");
+        } else {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+    },
+    VoteType::ChangeFeeTiers => {
+        dao_state.fee_tiers = parse_fee_tiers(&proposal.options[winning_option])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        msg!("Submission fee tiers updated ({} tiers)", dao_state.fee_tiers.len());
+    },
+    VoteType::ReplaceAdmin => {
+        let new_admin = parse_admin(&proposal.options[winning_option])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let votes = tally_proposal_votes(proposal);
+        let total_votes = votes.iter().sum();
+        if !has_supermajority(votes[winning_option], total_votes, dao_state.total_deposit) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        dao_state.initializer = new_admin;
+        msg!("Admin replaced by {}", new_admin);
+    },
+    VoteType::ChangeDepositCaps => {
+        let (per_wallet, total) = parse_deposit_caps(&proposal.options[winning_option])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        dao_state.max_deposit_per_wallet = per_wallet;
+        dao_state.max_total_deposit = total;
+        msg!("Deposit caps updated: {} per wallet, {} in total", per_wallet, total);
+    },
+    VoteType::ChangeStreakBonus => {
+        let (days, discount) = parse_streak_bonus(&proposal.options[winning_option])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        dao_state.streak_days = days;
+        dao_state.streak_discount = discount;
+        msg!("Streak bonus updated: {}% off after {} days", discount, days);
+    },
+    VoteType::ChangeRetractPolicy => {
+        let (window, refund) = parse_retract_policy(&proposal.options[winning_option])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        dao_state.retract_window = window;
+        dao_state.retract_refund = refund;
+        msg!("Retract policy updated: {}% back within {} seconds", refund, window);
+    },
+    VoteType::ChangeRoundMinimum => {
+        let min_round_pot = parse_round_minimum(&proposal.options[winning_option])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        dao_state.min_round_pot = min_round_pot;
+        msg!("Round minimum updated: {} lamports", min_round_pot);
+    },
 }
 
 Ok(())
@@ -1008,7 +2545,7 @@ Ok(())
 // Function to execute the results of completed votes
 pub fn execute_vote_results(
     dao_state: &mut DaoState, 
-    current_time: u64
+    _current_time: u64
 ) -> ProgramResult {
     // 첫 번째 단계: 처리해야 할 제안과 정보를 수집
     // (제안 인덱스, 승리한 옵션 인덱스, 투표 유형 복사본)
@@ -1045,6 +2582,13 @@ pub fn execute_vote_results(
             String::new()
         };
         
+        // 관리자 교체는 2/3 이상이 같은 후보를 골라야 한다. 못 미치면 실행하지 않고 끝낸다
+        if vote_type == VoteType::ReplaceAdmin
+            && !has_supermajority(highest_votes, votes.iter().sum(), dao_state.total_deposit) {
+            dao_state.vote_proposals[i].status = VoteStatus::Executed;
+            continue;
+        }
+
         // 업데이트 필요 목록에 추가
         if highest_votes > 0 {
             updates_needed.push((i, vote_type, winning_text));
@@ -1085,6 +2629,55 @@ pub fn execute_vote_results(
             VoteType::ContentQualityRating => {
                 msg!("Content quality rating processed");
             },
+            VoteType::ChangeDepositResetsTimer => {
+                let option_str = winning_text.to_lowercase();
+                if option_str == "true" || option_str == "on" {
+                    dao_state.deposit_resets_timer = true;
+                    msg!("Deposits now reset the timer");
+                } else if option_str == "false" || option_str == "off" {
+                    dao_state.deposit_resets_timer = false;
+                    msg!("Deposits no longer reset the timer");
+                }
+            },
+            VoteType::ChangeFeeTiers => {
+                if let Some(fee_tiers) = parse_fee_tiers(&winning_text) {
+                    dao_state.fee_tiers = fee_tiers;
+                    msg!("Submission fee tiers updated ({} tiers)", dao_state.fee_tiers.len());
+                }
+            },
+            VoteType::ReplaceAdmin => {
+                if let Some(new_admin) = parse_admin(&winning_text) {
+                    dao_state.initializer = new_admin;
+                    msg!("Admin replaced by {}", new_admin);
+                }
+            },
+            VoteType::ChangeDepositCaps => {
+                if let Some((per_wallet, total)) = parse_deposit_caps(&winning_text) {
+                    dao_state.max_deposit_per_wallet = per_wallet;
+                    dao_state.max_total_deposit = total;
+                    msg!("Deposit caps updated: {} per wallet, {} in total", per_wallet, total);
+                }
+            },
+            VoteType::ChangeStreakBonus => {
+                if let Some((days, discount)) = parse_streak_bonus(&winning_text) {
+                    dao_state.streak_days = days;
+                    dao_state.streak_discount = discount;
+                    msg!("Streak bonus updated: {}% off after {} days", discount, days);
+                }
+            },
+            VoteType::ChangeRetractPolicy => {
+                if let Some((window, refund)) = parse_retract_policy(&winning_text) {
+                    dao_state.retract_window = window;
+                    dao_state.retract_refund = refund;
+                    msg!("Retract policy updated: {}% back within {} seconds", refund, window);
+                }
+            },
+            VoteType::ChangeRoundMinimum => {
+                if let Some(min_round_pot) = parse_round_minimum(&winning_text) {
+                    dao_state.min_round_pot = min_round_pot;
+                    msg!("Round minimum updated: {} lamports", min_round_pot);
+                }
+            },
         }
         
         // 제안 상태 업데이트
@@ -1102,3 +2695,589 @@ current_time: u64
 current_time >= depositor_info.locked_until
 }
 
+// Decodes a `T` from the front of account data, ignoring the zero padding after it (accounts are allocated at
+// their largest size)
+pub fn try_from_slice_unchecked<T: BorshDeserialize>(data: &[u8]) -> Result<T, std::io::Error> {
+let mut data = data;
+T::deserialize(&mut data)
+}
+
+// Sum of the lamport ledger; None if the recorded pools overflow
+pub fn ledger_total(dao_state: &DaoState) -> Option<u64> {
+dao_state.rent_reserve
+    .checked_add(dao_state.bounty_pool)?
+    .checked_add(dao_state.fee_pool)
+}
+
+// The DAO account must hold exactly what its ledger records
+pub fn check_ledger(dao_state: &DaoState, lamports: u64) -> ProgramResult {
+match ledger_total(dao_state) {
+    Some(total) if total == lamports => Ok(()),
+    _ => {
+        msg!("Ledger mismatch: balance {} lamports", lamports);
+        Err(TurtleError::LedgerMismatch.into())
+    }
+}
+}
+
+// Bring the ledger in line with the DAO account balance before a handler touches it.
+// Accounts created before the ledger fields read them as zero: the rent-exempt minimum becomes
+// rent_reserve and the rest bounty_pool. Lamports sent to the account outside the program are
+// folded into fee_pool. A balance below the ledger means lamports left without being recorded.
+pub fn reconcile_ledger(dao_state: &mut DaoState, dao_account: &AccountInfo) -> ProgramResult {
+let lamports = dao_account.lamports();
+if dao_state.rent_reserve == 0 {
+    let rent_minimum = Rent::get()?.minimum_balance(dao_account.data_len());
+    dao_state.rent_reserve = rent_minimum.min(lamports);
+    dao_state.bounty_pool = lamports - dao_state.rent_reserve;
+    dao_state.fee_pool = 0;
+}
+
+let total = ledger_total(dao_state).ok_or(TurtleError::LedgerMismatch)?;
+if lamports < total {
+    msg!("Ledger mismatch: balance {} lamports, recorded {}", lamports, total);
+    return Err(TurtleError::LedgerMismatch.into());
+}
+dao_state.fee_pool += lamports - total;
+Ok(())
+}
+
+
+// Golden vectors for the Borsh layouts. Instruction data and DAO account data are the program's ABI:
+// reordering fields or variants, or changing a type, must fail here instead of misreading live accounts.
+// Update a vector only together with a migration for existing accounts and clients.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: Pubkey = Pubkey::new_from_array([1; 32]);
+    const INITIALIZER: Pubkey = Pubkey::new_from_array([7; 32]);
+    // content_key of the content in test_dao_state_layout: sha256(author || text || image_uri || timestamp)
+    const CONTENT_KEY: [u8; 32] = [
+        0xf7, 0xcd, 0x39, 0x27, 0x4b, 0xd9, 0xce, 0xd8, 0x27, 0x3e, 0x95, 0x09, 0xcd, 0x40, 0x69, 0xfa,
+        0xbc, 0xd3, 0xf3, 0xc4, 0x08, 0xc8, 0xd1, 0xb7, 0x74, 0xd4, 0x36, 0xb6, 0x4b, 0xdd, 0x0b, 0x60,
+    ];
+
+    #[test]
+    fn test_instruction_layouts() {
+        let distribute = [&[6u8, 1, 0, 0, 0][..], &[4; 32][..], &[1, 0, 0, 0, 100][..]].concat();
+        let retract = [&[11u8][..], &[1; 32][..]].concat();
+        let vote_content = [&[14u8][..], &[1; 32][..]].concat();
+        let cases: [(TurtleInstruction, &[u8]); 15] = [
+            (
+                TurtleInstruction::InitializeDao {
+                    dao_name: "dao".to_string(),
+                    time_limit: 3_600,
+                    base_fee: 10,
+                    ai_moderation: true,
+                    deposit_share: 20,
+                    deposit_resets_timer: true,
+                },
+                &[
+                    0, // variant
+                    3, 0, 0, 0, b'd', b'a', b'o', // dao_name
+                    0x10, 0x0e, 0, 0, 0, 0, 0, 0, // time_limit
+                    10, 0, 0, 0, 0, 0, 0, 0, // base_fee
+                    1,  // ai_moderation
+                    20, // deposit_share
+                    1,  // deposit_resets_timer
+                ],
+            ),
+            (
+                TurtleInstruction::Deposit { amount: 1_000_000_000 },
+                &[1, 0x00, 0xca, 0x9a, 0x3b, 0, 0, 0, 0],
+            ),
+            (
+                TurtleInstruction::SubmitContent { text: "hi".try_into().unwrap(), image_uri: "u".try_into().unwrap() },
+                &[2, 2, 0, 0, 0, b'h', b'i', 1, 0, 0, 0, b'u'],
+            ),
+            (
+                TurtleInstruction::CreateVote {
+                    title: "t".to_string(),
+                    description: String::new(),
+                    vote_type: VoteType::ChangeBaseFee,
+                    options: vec!["a".to_string(), "b".to_string()],
+                    voting_period: MIN_VOTING_PERIOD,
+                },
+                &[
+                    3, // variant
+                    1, 0, 0, 0, b't', // title
+                    0, 0, 0, 0, // description
+                    1, // vote_type
+                    2, 0, 0, 0, 1, 0, 0, 0, b'a', 1, 0, 0, 0, b'b', // options
+                    0x80, 0x3a, 0x09, 0, 0, 0, 0, 0, // voting_period
+                ],
+            ),
+            (
+                TurtleInstruction::CastVote { proposal_id: 2, option_index: 1 },
+                &[4, 2, 0, 0, 0, 0, 0, 0, 0, 1],
+            ),
+            (TurtleInstruction::ProcessTimeout {}, &[5]),
+            // Same bytes as the backend's DistributeQualityRewards drafts
+            (
+                TurtleInstruction::DistributeQualityRewards {
+                    creator_pubkeys: vec![Pubkey::new_from_array([4; 32])],
+                    distribution_weights: vec![100],
+                },
+                &distribute,
+            ),
+            (TurtleInstruction::InitializeTreasuryAsset {}, &[7]),
+            (TurtleInstruction::DepositAsset { amount: 500 }, &[8, 0xf4, 0x01, 0, 0, 0, 0, 0, 0]),
+            (TurtleInstruction::WithdrawAsset { amount: 500 }, &[9, 0xf4, 0x01, 0, 0, 0, 0, 0, 0]),
+            (
+                TurtleInstruction::DistributeAssetReward { round: 2, amount: 500 },
+                &[10, 2, 0, 0, 0, 0, 0, 0, 0, 0xf4, 0x01, 0, 0, 0, 0, 0, 0],
+            ),
+            (TurtleInstruction::RetractContent { content: KEY }, &retract),
+            (
+                TurtleInstruction::InitializeDaoWithPreset { dao_name: "dao".to_string(), preset: DaoPreset::ModeratedShowcase },
+                &[12, 3, 0, 0, 0, b'd', b'a', b'o', 2],
+            ),
+            (
+                TurtleInstruction::SubmitLinkedContent {
+                    text: "hi".try_into().unwrap(),
+                    image_uri: BoundedUri::default(),
+                    external_ref: ExternalRef { platform: ExternalPlatform::Farcaster, id: "0xab".try_into().unwrap() },
+                },
+                &[13, 2, 0, 0, 0, b'h', b'i', 0, 0, 0, 0, 1, 4, 0, 0, 0, b'0', b'x', b'a', b'b'],
+            ),
+            (TurtleInstruction::VoteContent { content: KEY }, &vote_content),
+        ];
+        for (instruction, bytes) in cases {
+            assert_eq!(instruction.try_to_vec().unwrap(), bytes, "{:?}", instruction);
+            assert_eq!(TurtleInstruction::try_from_slice(bytes).unwrap(), instruction);
+        }
+    }
+
+    #[test]
+    fn test_enum_tags() {
+        let vote_types = [
+            VoteType::ChangeTimeLimit,
+            VoteType::ChangeBaseFee,
+            VoteType::ChangeAiModeration,
+            VoteType::ContentQualityRating,
+            VoteType::ChangeDepositResetsTimer,
+            VoteType::ChangeFeeTiers,
+            VoteType::ReplaceAdmin,
+            VoteType::ChangeDepositCaps,
+            VoteType::ChangeStreakBonus,
+            VoteType::ChangeRetractPolicy,
+            VoteType::ChangeRoundMinimum,
+        ];
+        for (tag, vote_type) in vote_types.iter().enumerate() {
+            assert_eq!(vote_type.try_to_vec().unwrap(), [tag as u8]);
+        }
+        for (tag, status) in [VoteStatus::Active, VoteStatus::Completed, VoteStatus::Executed].iter().enumerate() {
+            assert_eq!(status.try_to_vec().unwrap(), [tag as u8]);
+        }
+        for (tag, round_state) in [RoundState::Active, RoundState::Dormant].iter().enumerate() {
+            assert_eq!(round_state.try_to_vec().unwrap(), [tag as u8]);
+        }
+    }
+
+    #[test]
+    fn test_dao_state_layout() {
+        let state = DaoState {
+            is_initialized: true,
+            dao_name: "dao".to_string(),
+            initializer: INITIALIZER,
+            time_limit: 3_600,
+            base_fee: 10,
+            ai_moderation: false,
+            deposit_share: 20,
+            timeout_timestamp: 1_700_003_600,
+            total_deposit: 500,
+            depositors: vec![DepositorInfo {
+                depositor: KEY,
+                amount: 500,
+                timestamp: 1_700_000_000,
+                locked_until: 1_700_003_600,
+            }],
+            contents: vec![Content {
+                author: KEY,
+                text: "hi".try_into().unwrap(),
+                image_uri: BoundedUri::default(),
+                timestamp: 1_700_000_000,
+                vote_count: 0,
+            }],
+            vote_proposals: vec![VoteProposal {
+                proposal_id: 0,
+                proposer: KEY,
+                title: "t".to_string(),
+                description: String::new(),
+                vote_type: VoteType::ChangeTimeLimit,
+                options: vec!["60".to_string(), "120".to_string()],
+                start_time: 1_700_000_000,
+                end_time: 1_700_604_800,
+                votes: vec![VoteInfo { voter: KEY, option_index: 1, voting_power: 500 }],
+                status: VoteStatus::Active,
+            }],
+            next_proposal_id: 1,
+            rent_reserve: 1_000,
+            bounty_pool: 500,
+            fee_pool: 0,
+            last_submitter: KEY,
+            last_content: Pubkey::new_from_array(CONTENT_KEY),
+            deposit_resets_timer: true,
+            fee_tiers: vec![FeeTier { min_deposit: 500, discount: 50 }],
+            round: 2,
+            max_deposit_per_wallet: 500,
+            max_total_deposit: 0,
+            streak_days: 7,
+            streak_discount: 20,
+            retract_window: 600,
+            retract_refund: 50,
+            round_state: RoundState::Dormant,
+            min_round_pot: 500,
+        };
+        assert_eq!(content_key(&state.contents[0]), state.last_content);
+        assert_eq!(find_last_submission(&state), Some(&state.contents[0]));
+
+        let timestamp: &[u8] = &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0]; // 1_700_000_000
+        let deadline: &[u8] = &[0x10, 0xff, 0x53, 0x65, 0, 0, 0, 0]; // 1_700_003_600
+        let amount: &[u8] = &[0xf4, 0x01, 0, 0, 0, 0, 0, 0]; // 500
+        let parts: &[&[u8]] = &[
+            &[1],                              // is_initialized
+            &[3, 0, 0, 0, b'd', b'a', b'o'],   // dao_name
+            &[7; 32],                          // initializer
+            &[0x10, 0x0e, 0, 0, 0, 0, 0, 0],   // time_limit
+            &[10, 0, 0, 0, 0, 0, 0, 0],        // base_fee
+            &[0],                              // ai_moderation
+            &[20],                             // deposit_share
+            deadline,                          // timeout_timestamp
+            amount,                            // total_deposit
+            // depositors
+            &[1, 0, 0, 0],
+            &[1; 32], amount, timestamp, deadline,
+            // contents
+            &[1, 0, 0, 0],
+            &[1; 32], &[2, 0, 0, 0, b'h', b'i'], &[0, 0, 0, 0], timestamp, &[0; 8],
+            // vote_proposals
+            &[1, 0, 0, 0],
+            &[0; 8],                                              // proposal_id
+            &[1; 32],                                             // proposer
+            &[1, 0, 0, 0, b't'], &[0, 0, 0, 0],                   // title, description
+            &[0],                                                 // vote_type
+            &[2, 0, 0, 0, 2, 0, 0, 0, b'6', b'0', 3, 0, 0, 0, b'1', b'2', b'0'], // options
+            timestamp, &[0x80, 0x2b, 0x5d, 0x65, 0, 0, 0, 0],     // start_time, end_time
+            &[1, 0, 0, 0], &[1; 32], &[1], amount,                // votes
+            &[0],                                                 // status
+            &[1, 0, 0, 0, 0, 0, 0, 0],                            // next_proposal_id
+            &[0xe8, 0x03, 0, 0, 0, 0, 0, 0],                      // rent_reserve
+            amount,                                               // bounty_pool
+            &[0; 8],                                              // fee_pool
+            &[1; 32],                                             // last_submitter
+            &CONTENT_KEY,                                         // last_content
+            &[1],                                                 // deposit_resets_timer
+            &[1, 0, 0, 0], amount, &[50],                         // fee_tiers
+            &[2, 0, 0, 0, 0, 0, 0, 0],                            // round
+            amount,                                               // max_deposit_per_wallet
+            &[0; 8],                                              // max_total_deposit
+            &[7, 0, 0, 0, 0, 0, 0, 0],                            // streak_days
+            &[20],                                                // streak_discount
+            &[0x58, 0x02, 0, 0, 0, 0, 0, 0],                      // retract_window
+            &[50],                                                // retract_refund
+            &[1],                                                 // round_state
+            amount,                                               // min_round_pot
+        ];
+        let expected = parts.concat();
+
+        assert_eq!(state.try_to_vec().unwrap(), expected);
+
+        // Accounts are allocated larger than the state; the processors read them with trailing zeros
+        let mut account = expected.clone();
+        account.resize(8000, 0);
+        let decoded = try_from_slice_unchecked::<DaoState>(&account).unwrap();
+        assert_eq!(decoded.try_to_vec().unwrap(), expected);
+        // Instructions that only check the admin decode the leading fields and nothing behind them
+        let header = try_from_slice_unchecked::<DaoHeader>(&account).unwrap();
+        assert!(header.is_initialized);
+        assert_eq!(header.initializer, INITIALIZER);
+
+        // Accounts written before the ledger, last-submission, timer-rule, fee-tier, round, cap, streak, retract and
+        // round-state fields existed read them as zero, which keeps their round running
+        let mut legacy = expected[..expected.len() - 153].to_vec();
+        legacy.resize(8000, 0);
+        let decoded = try_from_slice_unchecked::<DaoState>(&legacy).unwrap();
+        assert_eq!((decoded.rent_reserve, decoded.bounty_pool, decoded.fee_pool), (0, 0, 0));
+        assert_eq!(find_last_submission(&decoded), None);
+        assert!(!decoded.deposit_resets_timer);
+        assert!(decoded.fee_tiers.is_empty());
+        assert_eq!(decoded.round, 0);
+        assert_eq!((decoded.max_deposit_per_wallet, decoded.max_total_deposit), (0, 0));
+        assert_eq!((decoded.streak_days, decoded.streak_discount), (0, 0));
+        assert_eq!((decoded.retract_window, decoded.retract_refund), (0, 0));
+        assert_eq!((decoded.round_state, decoded.min_round_pot), (RoundState::Active, 0));
+        assert_eq!(decoded.next_proposal_id, 1);
+    }
+
+    #[test]
+    fn test_contents_from_before_the_bounds_decode() {
+        use crate::instruction::{MAX_IMAGE_URI_LEN, MAX_TEXT_LEN};
+        // Submissions made before text and image URIs were bounded can be longer. One, the round's latest, is
+        // spliced into an account in place of a bounded entry, with last_content holding its key
+        let mut data = crate::fixtures::TurtleFixture::new().with_content(&KEY, "short").account().data;
+        let state = try_from_slice_unchecked::<DaoState>(&data).unwrap();
+        let timestamp = state.contents[0].timestamp;
+        let (text, image_uri) = ("가".repeat(MAX_TEXT_LEN), "u".repeat(MAX_IMAGE_URI_LEN + 1));
+        let legacy_key = hashv(&[KEY.as_ref(), text.as_bytes(), image_uri.as_bytes(), &timestamp.to_le_bytes()]).to_bytes();
+        let splice = |data: &mut Vec<u8>, from: &[u8], to: Vec<u8>| {
+            let at = data.windows(from.len()).position(|window| window == from).unwrap();
+            data.splice(at..at + from.len(), to);
+        };
+        splice(&mut data, &state.contents[0].try_to_vec().unwrap(), (KEY, text, image_uri, timestamp, 0u64).try_to_vec().unwrap());
+        splice(&mut data, state.last_content.as_ref(), legacy_key.to_vec());
+
+        // The DAO still loads, the entry cut down to the bounds and never inside a character
+        let mut decoded = try_from_slice_unchecked::<DaoState>(&data).unwrap();
+        assert_eq!(decoded.contents[0].text.as_str(), "가".repeat(MAX_TEXT_LEN / 3));
+        assert_eq!(decoded.contents[0].image_uri.as_str(), "u".repeat(MAX_IMAGE_URI_LEN));
+        assert_eq!(decoded.last_content.to_bytes(), legacy_key);
+        // Cutting changed the entry's key; re-keyed, its author is still the round's latest submitter
+        assert_eq!(find_last_submission(&decoded), None);
+        decoded.rekey_cut_submission();
+        assert_eq!(find_last_submission(&decoded), Some(&decoded.contents[0]));
+        let last_content = decoded.last_content;
+        decoded.rekey_cut_submission();
+        assert_eq!(decoded.last_content, last_content);
+    }
+
+    #[test]
+    fn test_round_result_layout() {
+        let round_result = RoundResult {
+            dao: KEY,
+            round: 1,
+            winner: INITIALIZER,
+            reward: 500,
+            pot: 500,
+            content: Pubkey::new_from_array(CONTENT_KEY),
+            submitted_at: 1_700_000_000,
+            closed_at: 1_700_003_600,
+            quality_pool: 100,
+            candidates: vec![QualityCandidate { author: INITIALIZER, content: Pubkey::new_from_array(CONTENT_KEY), votes: 3 }],
+        };
+        let parts: &[&[u8]] = &[
+            &[1; 32],                              // dao
+            &[1, 0, 0, 0, 0, 0, 0, 0],             // round
+            &[7; 32],                              // winner
+            &[0xf4, 0x01, 0, 0, 0, 0, 0, 0],       // reward: 500
+            &[0xf4, 0x01, 0, 0, 0, 0, 0, 0],       // pot
+            &CONTENT_KEY,                          // content
+            &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0], // submitted_at
+            &[0x10, 0xff, 0x53, 0x65, 0, 0, 0, 0], // closed_at
+            &[100, 0, 0, 0, 0, 0, 0, 0],           // quality_pool
+            &[1, 0, 0, 0],                         // candidates: 1
+            &[7; 32],                              // author
+            &CONTENT_KEY,                          // content
+            &[3, 0, 0, 0, 0, 0, 0, 0],             // votes
+        ];
+        let expected = parts.concat();
+        assert_eq!(round_result.try_to_vec().unwrap(), expected);
+        assert_eq!(expected.len() + (MAX_QUALITY_CANDIDATES - 1) * QualityCandidate::LEN, RoundResult::LEN);
+
+        let mut account = expected.clone();
+        account.resize(RoundResult::LEN, 0);
+        assert_eq!(RoundResult::unpack(&account).unwrap(), round_result);
+        // Accounts from before the quality pool end after closed_at
+        let legacy = RoundResult { quality_pool: 0, candidates: Vec::new(), ..round_result };
+        assert_eq!(RoundResult::unpack(&expected[..136]).unwrap(), legacy);
+    }
+
+    #[test]
+    fn test_treasury_asset_layout() {
+        let asset = TreasuryAsset {
+            dao: KEY,
+            mint: INITIALIZER,
+            vault: Pubkey::new_from_array(CONTENT_KEY),
+            balance: 500,
+            next_round: 2,
+        };
+        let parts: &[&[u8]] = &[
+            &[1; 32],                        // dao
+            &[7; 32],                        // mint
+            &CONTENT_KEY,                    // vault
+            &[0xf4, 0x01, 0, 0, 0, 0, 0, 0], // balance
+            &[2, 0, 0, 0, 0, 0, 0, 0],       // next_round
+        ];
+        let expected = parts.concat();
+        assert_eq!(asset.try_to_vec().unwrap(), expected);
+        assert_eq!(expected.len(), TreasuryAsset::LEN);
+    }
+
+    #[test]
+    fn test_submission_streak_layout() {
+        let streak = SubmissionStreak { dao: KEY, author: INITIALIZER, last_day: 19_675, days: 3 };
+        let parts: &[&[u8]] = &[
+            &[1; 32],                        // dao
+            &[7; 32],                        // author
+            &[0xdb, 0x4c, 0, 0, 0, 0, 0, 0], // last_day
+            &[3, 0, 0, 0, 0, 0, 0, 0],       // days
+        ];
+        let expected = parts.concat();
+        assert_eq!(streak.try_to_vec().unwrap(), expected);
+        assert_eq!(expected.len(), SubmissionStreak::LEN);
+    }
+
+    #[test]
+    fn test_external_claim_layout() {
+        let claim = ExternalClaim {
+            dao: KEY,
+            author: INITIALIZER,
+            content: Pubkey::new_from_array(CONTENT_KEY),
+            claimed_at: 1_700_000_000,
+            external_ref: ExternalRef { platform: ExternalPlatform::Twitter, id: "42".try_into().unwrap() },
+        };
+        let parts: &[&[u8]] = &[
+            &[1; 32],                              // dao
+            &[7; 32],                              // author
+            &CONTENT_KEY,                          // content
+            &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0], // claimed_at
+            &[0],                                  // external_ref.platform
+            &[2, 0, 0, 0, b'4', b'2'],             // external_ref.id
+        ];
+        let expected = parts.concat();
+        assert_eq!(claim.try_to_vec().unwrap(), expected);
+
+        // Claims are allocated for the longest id and read back with trailing zeros
+        assert_eq!(ExternalClaim::LEN, 104 + 1 + 4 + crate::instruction::MAX_EXTERNAL_ID_LEN);
+        let mut account = expected;
+        account.resize(ExternalClaim::LEN, 0);
+        assert_eq!(try_from_slice_unchecked::<ExternalClaim>(&account).unwrap(), claim);
+    }
+
+    #[test]
+    fn test_content_vote_layout() {
+        let content_vote = ContentVote {
+            dao: KEY,
+            content: Pubkey::new_from_array(CONTENT_KEY),
+            voter: INITIALIZER,
+            voted_at: 1_700_000_000,
+        };
+        let parts: &[&[u8]] = &[
+            &[1; 32],                              // dao
+            &CONTENT_KEY,                          // content
+            &[7; 32],                              // voter
+            &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0], // voted_at
+        ];
+        let expected = parts.concat();
+        assert_eq!(content_vote.try_to_vec().unwrap(), expected);
+        assert_eq!(expected.len(), ContentVote::LEN);
+    }
+
+    #[test]
+    fn test_advance_streak() {
+        let mut streak = SubmissionStreak { dao: KEY, author: INITIALIZER, last_day: 0, days: 0 };
+        let mut days = Vec::new();
+        for day in [100, 100, 101, 102, 104, 105] {
+            advance_streak(&mut streak, day);
+            days.push(streak.days);
+        }
+        assert_eq!(days, [1, 1, 2, 3, 1, 2]);
+        assert_eq!(streak.last_day, 105);
+
+        assert_eq!(parse_streak_bonus("7:20"), Some((7, 20)));
+        assert_eq!(parse_streak_bonus(" 3 : 50% "), Some((3, 50)));
+        assert_eq!(parse_streak_bonus("None"), Some((0, 0)));
+        assert_eq!(parse_streak_bonus("0:20"), None);
+        assert_eq!(parse_streak_bonus("7:101"), None);
+        assert_eq!(parse_streak_bonus("7"), None);
+    }
+
+    #[test]
+    fn test_parse_retract_policy() {
+        assert_eq!(parse_retract_policy("600:50"), Some((600, 50)));
+        assert_eq!(parse_retract_policy(" 60 : 100% "), Some((60, 100)));
+        assert_eq!(parse_retract_policy("NONE"), Some((0, 0)));
+        assert_eq!(parse_retract_policy("0:50"), None);
+        assert_eq!(parse_retract_policy("600:101"), None);
+        assert_eq!(parse_retract_policy("600"), None);
+    }
+
+    #[test]
+    fn test_parse_round_minimum() {
+        assert_eq!(parse_round_minimum("1000000000"), Some(1_000_000_000));
+        assert_eq!(parse_round_minimum(" 0 "), Some(0));
+        assert_eq!(parse_round_minimum("None"), Some(0));
+        assert_eq!(parse_round_minimum("-1"), None);
+        assert_eq!(parse_round_minimum("1 SOL"), None);
+    }
+
+    #[test]
+    fn test_open_round_if_funded() {
+        use crate::fixtures::{TurtleFixture, FIXTURE_NOW, TIME_LIMIT};
+        // A fixture without depositors is a freshly initialized, dormant DAO
+        let mut state = try_from_slice_unchecked::<DaoState>(&TurtleFixture::new().account().data).unwrap();
+        assert_eq!(state.round_state, RoundState::Dormant);
+        state.min_round_pot = 500;
+
+        // An empty pot never opens a round, even without a minimum
+        assert!(!open_round_if_funded(&mut state, FIXTURE_NOW));
+        state.total_deposit = 499;
+        assert!(!open_round_if_funded(&mut state, FIXTURE_NOW));
+        state.total_deposit = 500;
+        assert!(open_round_if_funded(&mut state, FIXTURE_NOW + 10));
+        assert_eq!((state.round_state, state.timeout_timestamp), (RoundState::Active, FIXTURE_NOW + 10 + TIME_LIMIT));
+
+        // An open round keeps its deadline
+        assert!(!open_round_if_funded(&mut state, FIXTURE_NOW + 20));
+        assert_eq!(state.timeout_timestamp, FIXTURE_NOW + 10 + TIME_LIMIT);
+    }
+
+    #[test]
+    fn test_percentages_of_large_amounts() {
+        use crate::fixtures::TurtleFixture;
+        let mut state = try_from_slice_unchecked::<DaoState>(&TurtleFixture::new().account().data).unwrap();
+        let author = Pubkey::new_unique();
+
+        // Multiplying a pot this large by a percentage overflows u64
+        state.total_deposit = u64::MAX;
+        state.base_fee = 10;
+        state.deposit_share = 50;
+        let quality_share = u64::MAX / 10 / 2;
+        assert_eq!(winner_reward(&state), u64::MAX - quality_share);
+        // A base fee over 100% takes the whole pot as the cut rather than more than it
+        state.base_fee = 1_000;
+        state.deposit_share = 100;
+        assert_eq!(winner_reward(&state), 0);
+        state.total_deposit = 0;
+        assert_eq!(winner_reward(&state), 0);
+
+        state.base_fee = u64::MAX;
+        state.fee_tiers = vec![FeeTier { min_deposit: 0, discount: 25 }];
+        assert_eq!(submission_fee(&state, &author, 0), u64::MAX - u64::MAX / 4);
+        state.retract_refund = 100;
+        assert_eq!(retract_refund(&state, &author), u64::MAX - u64::MAX / 4);
+        state.fee_tiers[0].discount = 200;
+        assert_eq!((submission_fee(&state, &author, 0), retract_refund(&state, &author)), (0, 0));
+    }
+
+    #[test]
+    fn test_quality_candidates() {
+        let content = |author: u8, vote_count: u64| Content {
+            author: Pubkey::new_from_array([author; 32]),
+            text: Default::default(),
+            image_uri: Default::default(),
+            timestamp: 1_700_000_000,
+            vote_count,
+        };
+        // Most votes first, submission order on a tie, and no submission without votes
+        let contents = [content(1, 2), content(2, 0), content(3, 5), content(4, 2)];
+        let authors: Vec<u8> = quality_candidates(&contents).iter().map(|candidate| candidate.author.to_bytes()[0]).collect();
+        assert_eq!(authors, [3, 1, 4]);
+        assert_eq!(quality_candidates(&contents)[0].content, content_key(&contents[2]));
+
+        let many: Vec<Content> = (0..15).map(|author| content(author, author as u64 + 1)).collect();
+        let candidates = quality_candidates(&many);
+        assert_eq!(candidates.len(), MAX_QUALITY_CANDIDATES);
+        assert_eq!((candidates[0].votes, candidates[MAX_QUALITY_CANDIDATES - 1].votes), (15, 6));
+    }
+
+    #[test]
+    fn test_quality_shares() {
+        assert_eq!(quality_shares(1_000, &[67, 33]), [670, 330]);
+        // Rounding leftovers go to the first creator, so the whole pool is paid
+        assert_eq!(quality_shares(101, &[34, 33, 33]), [35, 33, 33]);
+        assert_eq!(quality_shares(u64::MAX, &[50, 50]), [u64::MAX - u64::MAX / 2, u64::MAX / 2]);
+        assert_eq!(quality_shares(7, &[100]), [7]);
+    }
+}
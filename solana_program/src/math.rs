@@ -0,0 +1,70 @@
+//! Proportional arithmetic on lamport amounts.
+//!
+//! Shares of a pot are computed as `value * numerator / denominator`. Multiplying in u64 first overflows once a
+//! pot passes `u64::MAX / 100` lamports, so these helpers widen to u128 and only narrow the final quotient.
+//! `mul_div` reports a quotient that doesn't fit, `saturating_mul_div` caps it, and the percentage helpers
+//! clamp the percentage to 100 so their result never exceeds `value` and can't fail.
+
+/// Denominator of a percentage
+pub const PERCENT: u64 = 100;
+
+/// `value * numerator / denominator` rounded down; `None` if `denominator` is zero or the quotient doesn't fit
+/// in a u64
+pub fn mul_div(value: u64, numerator: u64, denominator: u64) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    u64::try_from(value as u128 * numerator as u128 / denominator as u128).ok()
+}
+
+/// `mul_div` capped at `u64::MAX`, which is also the result for a zero `denominator`
+pub fn saturating_mul_div(value: u64, numerator: u64, denominator: u64) -> u64 {
+    mul_div(value, numerator, denominator).unwrap_or(u64::MAX)
+}
+
+/// `percent` percent of `value` rounded down, with `percent` clamped to 100
+pub fn percent_of(value: u64, percent: u64) -> u64 {
+    // At most 100/100 of value, so the quotient always fits
+    (value as u128 * percent.min(PERCENT) as u128 / PERCENT as u128) as u64
+}
+
+/// `value` less `percent` percent of it (the discount rounds down, so the rest rounds up)
+pub fn percent_off(value: u64, percent: u64) -> u64 {
+    value - percent_of(value, percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(mul_div(1_000, 7, 100), Some(70));
+        assert_eq!(mul_div(999, 1, 100), Some(9));
+        // The product overflows u64 but the quotient fits
+        assert_eq!(mul_div(u64::MAX, 99, 100), Some(18_262_276_632_972_456_098));
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(mul_div(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+
+        assert_eq!(saturating_mul_div(u64::MAX, 3, 2), u64::MAX);
+        assert_eq!(saturating_mul_div(1, 1, 0), u64::MAX);
+        assert_eq!(saturating_mul_div(u64::MAX, 1, 2), u64::MAX / 2);
+    }
+
+    #[test]
+    fn test_percentages() {
+        assert_eq!(percent_of(u64::MAX, 100), u64::MAX);
+        assert_eq!(percent_of(u64::MAX, 50), u64::MAX / 2);
+        assert_eq!(percent_of(u64::MAX, 1), u64::MAX / 100);
+        assert_eq!(percent_of(u64::MAX, 0), 0);
+        // Over 100% is read as all of it
+        assert_eq!(percent_of(1_000, 250), 1_000);
+        assert_eq!(percent_of(u64::MAX, u64::MAX), u64::MAX);
+
+        assert_eq!(percent_off(10, 25), 8);
+        assert_eq!(percent_off(u64::MAX, 1), u64::MAX - u64::MAX / 100);
+        assert_eq!(percent_off(u64::MAX, 100), 0);
+        assert_eq!(percent_off(u64::MAX, 101), 0);
+    }
+}
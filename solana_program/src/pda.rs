@@ -0,0 +1,131 @@
+//! Program-derived address helpers shared by the program and off-chain clients.
+//!
+//! Build with `--features no-entrypoint` to link these from another crate.
+//! The DAO account, the per-round `RoundResult` history, the per-mint treasury assets (record and
+//! vault token account), the per-author submission streaks, the external post claims and the content votes are PDAs today; depositor, proposal and vault records still live inside `DaoState`.
+//! Their seeds are fixed here so that clients and the program derive the same addresses once those accounts
+//! are split out.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+use crate::ExternalPlatform;
+
+pub const DAO_SEED: &[u8] = b"dao";
+pub const DEPOSITOR_SEED: &[u8] = b"depositor";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const ROUND_SEED: &[u8] = b"round";
+pub const ASSET_SEED: &[u8] = b"asset";
+pub const ASSET_VAULT_SEED: &[u8] = b"asset_vault";
+pub const STREAK_SEED: &[u8] = b"streak";
+pub const CLAIM_SEED: &[u8] = b"claim";
+pub const CONTENT_VOTE_SEED: &[u8] = b"content_vote";
+
+/// DAO account checked by `InitializeDao`: seeds = ["dao", initializer, dao_name]
+pub fn find_dao_address(program_id: &Pubkey, initializer: &Pubkey, dao_name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DAO_SEED, initializer.as_ref(), dao_name.as_bytes()], program_id)
+}
+
+/// Depositor record of a DAO: seeds = ["depositor", dao, depositor]
+pub fn find_depositor_address(program_id: &Pubkey, dao: &Pubkey, depositor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DEPOSITOR_SEED, dao.as_ref(), depositor.as_ref()], program_id)
+}
+
+/// Governance proposal of a DAO: seeds = ["proposal", dao, proposal_id (u64 little-endian)]
+pub fn find_proposal_address(program_id: &Pubkey, dao: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_SEED, dao.as_ref(), &proposal_id.to_le_bytes()], program_id)
+}
+
+/// Lamport vault holding a DAO's bounty pool: seeds = ["vault", dao]
+pub fn find_vault_address(program_id: &Pubkey, dao: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, dao.as_ref()], program_id)
+}
+
+/// History record written by `ProcessTimeout` for a round with a winner: seeds = ["round", dao, round (u64 little-endian)]
+pub fn find_round_address(program_id: &Pubkey, dao: &Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROUND_SEED, dao.as_ref(), &round.to_le_bytes()], program_id)
+}
+
+/// Treasury record of one SPL mint held by a DAO: seeds = ["asset", dao, mint]
+pub fn find_treasury_asset_address(program_id: &Pubkey, dao: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ASSET_SEED, dao.as_ref(), mint.as_ref()], program_id)
+}
+
+/// Token account holding a treasury asset, owned by its record: seeds = ["asset_vault", dao, mint]
+pub fn find_asset_vault_address(program_id: &Pubkey, dao: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ASSET_VAULT_SEED, dao.as_ref(), mint.as_ref()], program_id)
+}
+
+/// Daily submission streak of an author in a DAO: seeds = ["streak", dao, author]
+pub fn find_streak_address(program_id: &Pubkey, dao: &Pubkey, author: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STREAK_SEED, dao.as_ref(), author.as_ref()], program_id)
+}
+
+/// Claim of an external post in a DAO: seeds = ["claim", dao, platform (u8 tag), sha256(id)].
+/// The id is hashed because it may be longer than a seed can be.
+pub fn find_claim_address(program_id: &Pubkey, dao: &Pubkey, platform: ExternalPlatform, id: &str) -> (Pubkey, u8) {
+    let id_hash = hashv(&[id.as_bytes()]).to_bytes();
+    Pubkey::find_program_address(&[CLAIM_SEED, dao.as_ref(), &[platform as u8], &id_hash], program_id)
+}
+
+/// A depositor's vote for a content entry of a DAO: seeds = ["content_vote", dao, content_key, voter]
+pub fn find_content_vote_address(program_id: &Pubkey, dao: &Pubkey, content: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONTENT_VOTE_SEED, dao.as_ref(), content.as_ref(), voter.as_ref()], program_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_derivation(found: (Pubkey, u8), seeds: &[&[u8]], program_id: &Pubkey) {
+        let (address, bump) = found;
+        let mut signer_seeds = seeds.to_vec();
+        let bump_seed = [bump];
+        signer_seeds.push(&bump_seed);
+        // The program signs with the same seeds plus the bump, so the address must match create_program_address
+        assert_eq!(Pubkey::create_program_address(&signer_seeds, program_id), Ok(address));
+        assert_eq!(Pubkey::find_program_address(seeds, program_id), found);
+    }
+
+    #[test]
+    fn test_addresses_match_on_chain_derivation() {
+        let program_id = Pubkey::new_unique();
+        let initializer = Pubkey::new_unique();
+        let depositor = Pubkey::new_unique();
+        let (dao, _) = find_dao_address(&program_id, &initializer, "turtles");
+
+        assert_derivation(find_dao_address(&program_id, &initializer, "turtles"), &[b"dao", initializer.as_ref(), b"turtles"], &program_id);
+        assert_derivation(find_depositor_address(&program_id, &dao, &depositor), &[b"depositor", dao.as_ref(), depositor.as_ref()], &program_id);
+        assert_derivation(find_proposal_address(&program_id, &dao, 7), &[b"proposal", dao.as_ref(), &7u64.to_le_bytes()], &program_id);
+        assert_derivation(find_vault_address(&program_id, &dao), &[b"vault", dao.as_ref()], &program_id);
+        assert_derivation(find_round_address(&program_id, &dao, 3), &[b"round", dao.as_ref(), &3u64.to_le_bytes()], &program_id);
+        let mint = Pubkey::new_unique();
+        assert_derivation(find_treasury_asset_address(&program_id, &dao, &mint), &[b"asset", dao.as_ref(), mint.as_ref()], &program_id);
+        assert_derivation(find_asset_vault_address(&program_id, &dao, &mint), &[b"asset_vault", dao.as_ref(), mint.as_ref()], &program_id);
+        assert_derivation(find_streak_address(&program_id, &dao, &depositor), &[b"streak", dao.as_ref(), depositor.as_ref()], &program_id);
+        let id_hash = hashv(&[b"1750000000000000000"]).to_bytes();
+        assert_derivation(find_claim_address(&program_id, &dao, ExternalPlatform::Farcaster, "1750000000000000000"), &[b"claim", dao.as_ref(), &[1], &id_hash], &program_id);
+        let content = Pubkey::new_unique();
+        assert_derivation(find_content_vote_address(&program_id, &dao, &content, &depositor), &[b"content_vote", dao.as_ref(), content.as_ref(), depositor.as_ref()], &program_id);
+    }
+
+    #[test]
+    fn test_addresses_are_distinct_per_input() {
+        let program_id = Pubkey::new_unique();
+        let initializer = Pubkey::new_unique();
+        let (dao, _) = find_dao_address(&program_id, &initializer, "turtles");
+
+        assert_ne!(dao, find_dao_address(&program_id, &initializer, "rabbits").0);
+        assert_ne!(find_proposal_address(&program_id, &dao, 0).0, find_proposal_address(&program_id, &dao, 1).0);
+        assert_ne!(find_round_address(&program_id, &dao, 0).0, find_proposal_address(&program_id, &dao, 0).0);
+        assert_ne!(find_vault_address(&program_id, &dao).0, dao);
+        assert!(!find_vault_address(&program_id, &dao).0.is_on_curve());
+        let mint = Pubkey::new_unique();
+        assert_ne!(find_treasury_asset_address(&program_id, &dao, &mint).0, find_asset_vault_address(&program_id, &dao, &mint).0);
+        // The same id on two platforms is two posts
+        assert_ne!(
+            find_claim_address(&program_id, &dao, ExternalPlatform::Twitter, "42").0,
+            find_claim_address(&program_id, &dao, ExternalPlatform::Farcaster, "42").0
+        );
+    }
+}
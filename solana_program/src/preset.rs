@@ -0,0 +1,83 @@
+//! Parameter bundles for `InitializeDaoWithPreset`.
+//!
+//! A preset expands on-chain into the same parameters `InitializeDao` takes, so a community can start from a
+//! vetted configuration instead of picking time limits and fee percentages by hand. Governance can change every
+//! parameter afterwards as usual.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Named DAO configuration, see `DAO_PRESETS`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DaoPreset {
+    /// Ten minute rounds that deposits also restart, small fees
+    FastGame,
+    /// Day-long rounds for longer posts, a larger share for quality content
+    LongForm,
+    /// Three day rounds with AI moderation and half the fee for quality content
+    ModeratedShowcase,
+}
+
+/// The `InitializeDao` parameters a preset stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaoParams {
+    pub time_limit: u64,
+    pub base_fee: u64,
+    pub ai_moderation: bool,
+    pub deposit_share: u8,
+    pub deposit_resets_timer: bool,
+}
+
+/// Parameters of each preset, indexed by its Borsh tag
+pub const DAO_PRESETS: [DaoParams; 3] = [
+    // FastGame
+    DaoParams { time_limit: 10 * 60, base_fee: 5, ai_moderation: false, deposit_share: 10, deposit_resets_timer: true },
+    // LongForm
+    DaoParams { time_limit: 24 * 60 * 60, base_fee: 10, ai_moderation: false, deposit_share: 30, deposit_resets_timer: false },
+    // ModeratedShowcase
+    DaoParams { time_limit: 3 * 24 * 60 * 60, base_fee: 20, ai_moderation: true, deposit_share: 50, deposit_resets_timer: false },
+];
+
+impl DaoPreset {
+    pub const ALL: [DaoPreset; 3] = [DaoPreset::FastGame, DaoPreset::LongForm, DaoPreset::ModeratedShowcase];
+
+    pub fn params(self) -> DaoParams {
+        DAO_PRESETS[self as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instruction::turtle_instruction, MIN_VOTING_PERIOD};
+    use solana_program::pubkey::Pubkey;
+
+    #[test]
+    fn test_presets_match_their_tags() {
+        assert_eq!(DaoPreset::ALL.len(), DAO_PRESETS.len());
+        for (tag, preset) in DaoPreset::ALL.iter().enumerate() {
+            assert_eq!(preset.try_to_vec().unwrap(), [tag as u8]);
+            assert_eq!(preset.params(), DAO_PRESETS[tag]);
+        }
+    }
+
+    #[test]
+    fn test_preset_invariants() {
+        for preset in DaoPreset::ALL {
+            let params = preset.params();
+            // A round ends at least once per voting period, so finished votes are executed on every preset
+            assert!(params.time_limit > 0 && params.time_limit <= MIN_VOTING_PERIOD, "{:?}", preset);
+            // Both are percentages of the round's pot; ChangeBaseFee only accepts up to 100 as well
+            assert!(params.base_fee <= 100 && params.deposit_share <= 100, "{:?}", preset);
+            // Whatever InitializeDao accepts from the builder
+            turtle_instruction::initialize_dao(
+                &Pubkey::new_unique(), &Pubkey::new_unique(), "turtles".to_string(),
+                params.time_limit, params.base_fee, params.ai_moderation, params.deposit_share, params.deposit_resets_timer,
+            ).unwrap();
+        }
+        for (i, a) in DAO_PRESETS.iter().enumerate() {
+            assert!(DAO_PRESETS[i + 1..].iter().all(|b| a != b), "duplicate preset {}", i);
+        }
+        assert!(DaoPreset::ModeratedShowcase.params().ai_moderation);
+    }
+}
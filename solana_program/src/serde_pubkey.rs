@@ -0,0 +1,60 @@
+//! Base58 string (de)serialization for `Pubkey` fields under the `serde` feature.
+//!
+//! `solana_program::pubkey::Pubkey` serializes as a 32-byte array by default;
+//! JSON consumers expect the same base58 form wallets and explorers show.
+//! Use with `#[serde(with = "crate::serde_pubkey")]`.
+
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(pubkey)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Pubkey::from_str(&value).map_err(|e| D::Error::custom(format!("invalid pubkey {}: {}", value, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Content, TurtleInstruction, VoteType};
+    use solana_program::pubkey::Pubkey;
+
+    #[test]
+    fn test_pubkey_fields_use_base58() {
+        let content = Content {
+            author: Pubkey::new_from_array([1; 32]),
+            text: "hello".try_into().unwrap(),
+            image_uri: Default::default(),
+            timestamp: 10,
+            vote_count: 2,
+        };
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["author"], Pubkey::new_from_array([1; 32]).to_string());
+
+        let decoded: Content = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.author, content.author);
+
+        let bad = serde_json::json!({"author": "not-a-key", "text": "", "image_uri": "", "timestamp": 0, "vote_count": 0});
+        assert!(serde_json::from_value::<Content>(bad).is_err());
+        // Bounded strings keep their limit through serde too
+        let long = serde_json::json!({"author": content.author.to_string(), "text": "a".repeat(1001), "image_uri": "", "timestamp": 0, "vote_count": 0});
+        assert!(serde_json::from_value::<Content>(long).is_err());
+    }
+
+    #[test]
+    fn test_instruction_round_trip() {
+        let instruction = TurtleInstruction::CreateVote {
+            title: "fee".to_string(),
+            description: String::new(),
+            vote_type: VoteType::ChangeBaseFee,
+            options: vec!["5".to_string(), "10".to_string()],
+            voting_period: 604_800,
+        };
+        let json = serde_json::to_string(&instruction).unwrap();
+        assert!(json.contains("\"ChangeBaseFee\""));
+        assert_eq!(serde_json::from_str::<TurtleInstruction>(&json).unwrap(), instruction);
+    }
+}
@@ -1,543 +1,1013 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        instruction::turtle_instruction,
-        process_instruction, Content, DaoState, Depositor, ProposalType, Proposal,
-    };
-    use borsh::{BorshDeserialize, BorshSerialize};
-    use solana_program::{
-        account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, program_pack::Pack,
-        pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
-    };
-    use std::{collections::BTreeMap, mem};
-
-    // Mock the Solana runtime environment
-    pub struct SolanaRuntime {
-        pub program_id: Pubkey,
-        pub accounts: BTreeMap<Pubkey, Account>,
-        pub clock: Clock,
-        pub rent: Rent,
-    }
+// Program tests on solana-program-test: every instruction runs through the real runtime (BanksClient),
+// so system program CPIs actually move lamports and the clock only changes through `warp_to_timestamp`.
 
-    // Mock account structure
-    pub struct Account {
-        pub lamports: u64,
-        pub data: Vec<u8>,
-        pub owner: Pubkey,
-        pub executable: bool,
-        pub rent_epoch: u64,
-    }
+use crate::{
+    error::TurtleError,
+    fixtures::{TestDao, TurtleFixture, DAO_SPACE, FIXTURE_NOW, TIME_LIMIT, WALLET_LAMPORTS},
+    instruction::{turtle_instruction, MAX_IMAGE_URI_LEN},
+    preset::DaoPreset,
+    content_key, ledger_total, parse_admin, parse_deposit_caps, parse_fee_tiers, pda, ContentVote, ExternalClaim, ExternalPlatform, FeeTier,
+    QualityCandidate, RoundResult, RoundState, TreasuryAsset, TurtleInstruction, VoteStatus, VoteType, MIN_VOTING_PERIOD, SECONDS_PER_DAY,
+};
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    system_instruction, system_program,
+};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
 
-    impl SolanaRuntime {
-        pub fn new(program_id: Pubkey) -> Self {
-            let mut accounts = BTreeMap::new();
-            let clock = Clock {
-                slot: 0,
-                epoch_start_timestamp: 0,
-                epoch: 0,
-                leader_schedule_epoch: 0,
-                unix_timestamp: 0,
-            };
-            let rent = Rent {
-                lamports_per_byte_year: 1,
-                exemption_threshold: 2.0,
-                burn_percent: 5,
-            };
+fn instruction_error(error: InstructionError) -> TransactionError {
+    TransactionError::InstructionError(0, error)
+}
 
-            Self {
-                program_id,
-                accounts,
-                clock,
-                rent,
-            }
-        }
+#[tokio::test]
+async fn test_initialize_dao() {
+    let mut test = TestDao::start().await;
+    let (initializer, dao) = test.initialize_dao(10, 20).await;
 
-        // Helper to create a new account
-        pub fn create_account(&mut self, pubkey: &Pubkey, lamports: u64, space: usize, owner: &Pubkey) {
-            let account = Account {
-                lamports,
-                data: vec![0; space],
-                owner: *owner,
-                executable: false,
-                rent_epoch: 0,
-            };
-            self.accounts.insert(*pubkey, account);
-        }
+    let account = test.context.banks_client.get_account(dao).await.unwrap().unwrap();
+    assert_eq!(account.owner, test.program_id);
+    assert_eq!(account.data.len(), DAO_SPACE);
+    assert_eq!(account.lamports, test.rent_exempt_minimum().await);
 
-        // Helper to process an instruction
-        pub fn process_instruction(&mut self, instruction: &Instruction) -> ProgramResult {
-            let mut account_infos = Vec::new();
-            
-            for account_meta in &instruction.accounts {
-                let account = self.accounts.get_mut(&account_meta.pubkey).unwrap();
-                let is_signer = account_meta.is_signer;
-                let is_writable = account_meta.is_writable;
-                
-                let account_info = AccountInfo::new(
-                    &account_meta.pubkey,
-                    is_signer,
-                    is_writable,
-                    &mut account.lamports,
-                    &mut account.data,
-                    &account.owner,
-                    account.executable,
-                    account.rent_epoch,
-                );
-                
-                account_infos.push(account_info);
-            }
-            
-            process_instruction(&self.program_id, &account_infos, &instruction.data)
-        }
-    }
+    let state = test.dao_state(&dao).await;
+    assert!(state.is_initialized);
+    assert_eq!(state.dao_name, "turtles");
+    assert_eq!(state.initializer, initializer.pubkey());
+    assert_eq!((state.time_limit, state.base_fee, state.ai_moderation, state.deposit_share), (TIME_LIMIT, 10, false, 20));
+    assert!(!state.deposit_resets_timer);
+    // Nothing counts down until a deposit opens the first round
+    assert_eq!((state.round_state, state.timeout_timestamp, state.min_round_pot), (RoundState::Dormant, 0, 0));
+    assert_eq!(state.total_deposit, 0);
+    assert!(state.depositors.is_empty() && state.contents.is_empty() && state.vote_proposals.is_empty());
+    assert_eq!(state.next_proposal_id, 0);
+
+    // The PDA already exists, so the system program refuses to create it again (SystemError::AccountAlreadyInUse)
+    let instruction = turtle_instruction::initialize_dao(
+        &test.program_id, &initializer.pubkey(), "turtles".to_string(), TIME_LIMIT, 10, false, 20, false,
+    ).unwrap();
+    assert_eq!(test.send(&[instruction], &[&initializer]).await, Err(instruction_error(InstructionError::Custom(0))));
+}
 
-    #[test]
-    fn test_initialize_dao() {
-        // Setup
-        let program_id = Pubkey::new_unique();
-        let mut runtime = SolanaRuntime::new(program_id);
-        
-        let payer = Pubkey::new_unique();
-        let dao_account = Pubkey::new_unique();
-        let system_program = Pubkey::new_unique();
-        
-        // Fund payer account
-        runtime.create_account(&payer, 100000, 0, &system_program);
-        
-        // Create DAO account
-        let dao_space = mem::size_of::<DaoState>();
-        runtime.create_account(&dao_account, 0, dao_space, &program_id);
-        
-        // Create instruction
-        let time_limit = 1800; // 30 minutes
-        let base_fee = 10000000; // 0.01 SOL in lamports
-        let ai_moderation = true;
-        let deposit_share = 20; // 20% for quality content
-        
-        let instruction = turtle_instruction::initialize_dao(
-            &program_id,
-            &payer,
-            &dao_account,
-            time_limit,
-            base_fee,
-            ai_moderation,
-            deposit_share,
-        );
-        
-        // Process instruction
-        runtime.process_instruction(&instruction).unwrap();
-        
-        // Verify DAO state
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        
-        assert_eq!(dao_state.admin, payer);
-        assert_eq!(dao_state.time_limit, time_limit);
-        assert_eq!(dao_state.base_fee, base_fee);
-        assert_eq!(dao_state.ai_moderation, ai_moderation);
-        assert_eq!(dao_state.deposit_share, deposit_share);
-        assert_eq!(dao_state.total_deposit, 0);
-        assert_eq!(dao_state.content_count, 0);
-        assert_eq!(dao_state.depositor_count, 0);
+#[tokio::test]
+async fn test_initialize_dao_with_preset() {
+    let mut test = TestDao::start().await;
+    for (i, preset) in DaoPreset::ALL.into_iter().enumerate() {
+        let initializer = test.funded_keypair(1_000_000_000).await;
+        let dao_name = format!("preset-{}", i);
+        let instruction = turtle_instruction::initialize_dao_with_preset(&test.program_id, &initializer.pubkey(), dao_name.clone(), preset).unwrap();
+        test.send(&[instruction], &[&initializer]).await.unwrap();
+
+        // The preset expands into exactly the parameters InitializeDao would have been given
+        let (dao, _) = pda::find_dao_address(&test.program_id, &initializer.pubkey(), &dao_name);
+        let state = test.dao_state(&dao).await;
+        let params = preset.params();
+        assert_eq!((state.dao_name.as_str(), state.initializer), (dao_name.as_str(), initializer.pubkey()));
+        assert_eq!((state.time_limit, state.base_fee, state.ai_moderation, state.deposit_share), (params.time_limit, params.base_fee, params.ai_moderation, params.deposit_share));
+        assert_eq!(state.deposit_resets_timer, params.deposit_resets_timer);
+        assert_eq!((state.round_state, state.timeout_timestamp), (RoundState::Dormant, 0));
+        assert_eq!(ledger_total(&state), Some(test.lamports(&dao).await));
     }
+}
 
-    #[test]
-    fn test_submit_content() {
-        // Setup
-        let program_id = Pubkey::new_unique();
-        let mut runtime = SolanaRuntime::new(program_id);
-        
-        let submitter = Pubkey::new_unique();
-        let dao_account = Pubkey::new_unique();
-        let content_account = Pubkey::new_unique();
-        let system_program = Pubkey::new_unique();
-        
-        // Fund submitter account
-        runtime.create_account(&submitter, 1000000000, 0, &system_program);
-        
-        // Create DAO account with initial state
-        let dao_space = mem::size_of::<DaoState>();
-        runtime.create_account(&dao_account, 0, dao_space, &program_id);
-        
-        // Initialize DAO state
-        let dao_state = DaoState {
-            admin: submitter, // submitter is admin for simplicity
-            time_limit: 1800,
-            base_fee: 10000000,
-            ai_moderation: true,
-            deposit_share: 20,
-            last_activity_timestamp: 0,
-            total_deposit: 0,
-            active_proposal_count: 0,
-            content_count: 0,
-            depositor_count: 0,
-        };
-        
-        let mut dao_data = runtime.accounts.get_mut(&dao_account).unwrap().data.as_mut();
-        dao_state.serialize(&mut dao_data).unwrap();
-        
-        // Create content account
-        let content_hash = "content_hash".to_string();
-        let content_uri = "ipfs://QmXoypizjW3WknFiJnKLwHCnL72vedxjQkDDP1mXWo6uco".to_string();
-        let content_space = mem::size_of::<Content>() + content_hash.len() + content_uri.len();
-        runtime.create_account(&content_account, 0, content_space, &program_id);
-        
-        // Create instruction
-        let instruction = turtle_instruction::submit_content(
-            &program_id,
-            &submitter,
-            &dao_account,
-            &content_account,
-            content_hash.clone(),
-            content_uri.clone(),
-        );
-        
-        // Set timestamp for testing
-        runtime.clock.unix_timestamp = 1000;
-        
-        // Process instruction
-        runtime.process_instruction(&instruction).unwrap();
-        
-        // Verify content state
-        let content_data = &runtime.accounts.get(&content_account).unwrap().data;
-        let content = Content::try_from_slice(content_data).unwrap();
-        
-        assert_eq!(content.author, submitter);
-        assert_eq!(content.content_hash, content_hash);
-        assert_eq!(content.content_uri, content_uri);
-        assert_eq!(content.timestamp, 1000);
-        
-        // Verify DAO state updated (timer reset)
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        
-        assert_eq!(dao_state.last_activity_timestamp, 1000);
-        assert_eq!(dao_state.content_count, 1);
+#[tokio::test]
+async fn test_initialize_dao_errors() {
+    let mut test = TestDao::start().await;
+    let initializer = test.funded_keypair(1_000_000_000).await;
+    let (dao, _) = pda::find_dao_address(&test.program_id, &initializer.pubkey(), "turtles");
+    let initialize = |deposit_share| TurtleInstruction::InitializeDao {
+        dao_name: "turtles".to_string(),
+        time_limit: TIME_LIMIT,
+        base_fee: 10,
+        ai_moderation: false,
+        deposit_share,
+        deposit_resets_timer: false,
+    };
+    let accounts = |initializer_signs, dao| vec![
+        AccountMeta::new(initializer.pubkey(), initializer_signs),
+        AccountMeta::new(dao, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = test.raw_instruction(initialize(20), accounts(false, dao));
+    assert_eq!(test.send(&[instruction], &[]).await, Err(instruction_error(InstructionError::MissingRequiredSignature)));
+
+    let instruction = test.raw_instruction(initialize(101), accounts(true, dao));
+    assert_eq!(test.send(&[instruction], &[&initializer]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+
+    // Not the PDA of ["dao", initializer, dao_name]
+    let instruction = test.raw_instruction(initialize(20), accounts(true, Pubkey::new_unique()));
+    assert_eq!(test.send(&[instruction], &[&initializer]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+
+    let instruction = Instruction { program_id: test.program_id, accounts: accounts(true, dao), data: vec![42] };
+    assert_eq!(test.send(&[instruction], &[&initializer]).await, Err(instruction_error(InstructionError::InvalidInstructionData)));
+
+    assert!(test.context.banks_client.get_account(dao).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_deposit() {
+    let mut test = TestDao::start().await;
+    let (_, dao) = test.initialize_dao(10, 20).await;
+    let rent = test.rent_exempt_minimum().await;
+    let depositor = test.funded_keypair(1_000_000_000).await;
+
+    let before = test.lamports(&depositor.pubkey()).await;
+    test.deposit(&depositor, &dao, 300_000_000).await.unwrap();
+    let start = test.now().await;
+    test.warp_to_timestamp(start + 100).await;
+    test.deposit(&depositor, &dao, 200_000_000).await.unwrap();
+
+    // The system program transfer really moved the lamports (transaction fees are paid by the test payer)
+    assert_eq!(test.lamports(&depositor.pubkey()).await, before - 500_000_000);
+    assert_eq!(test.lamports(&dao).await, rent + 500_000_000);
+
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.total_deposit, 500_000_000);
+    assert_eq!(state.depositors.len(), 1);
+    let info = &state.depositors[0];
+    assert_eq!((info.depositor, info.amount), (depositor.pubkey(), 500_000_000));
+    assert_eq!(info.timestamp, start + 100);
+    assert_eq!(info.locked_until, start + 100 + TIME_LIMIT);
+
+    let instruction = test.raw_instruction(
+        TurtleInstruction::Deposit { amount: 0 },
+        vec![
+            AccountMeta::new(depositor.pubkey(), true),
+            AccountMeta::new(dao, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    assert_eq!(test.send(&[instruction], &[&depositor]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+
+    // More than the depositor holds: the system program transfer fails (SystemError::ResultWithNegativeLamports)
+    assert_eq!(test.deposit(&depositor, &dao, 1_000_000_000).await, Err(instruction_error(InstructionError::Custom(1))));
+}
+
+#[tokio::test]
+async fn test_deposit_resets_timer() {
+    let mut test = TestDao::start().await;
+    let start = test.now().await;
+    let depositor = test.funded_keypair(1_000_000_000).await;
+
+    // Default rule: only submissions restart the round
+    let (_, classic) = test.initialize_dao(10, 20).await;
+    // Opted in at initialization: deposits restart it too
+    let initializer = test.funded_keypair(1_000_000_000).await;
+    let instruction = turtle_instruction::initialize_dao(
+        &test.program_id, &initializer.pubkey(), "relay".to_string(), TIME_LIMIT, 10, false, 20, true,
+    ).unwrap();
+    test.send(&[instruction], &[&initializer]).await.unwrap();
+    let (relay, _) = pda::find_dao_address(&test.program_id, &initializer.pubkey(), "relay");
+    assert!(test.dao_state(&relay).await.deposit_resets_timer);
+
+    // The first deposit opens either round; only the relay DAO restarts on the ones after it
+    test.warp_to_timestamp(start + 600).await;
+    test.deposit(&depositor, &classic, 100_000_000).await.unwrap();
+    test.deposit(&depositor, &relay, 100_000_000).await.unwrap();
+    test.warp_to_timestamp(start + 900).await;
+    test.deposit(&depositor, &classic, 100_000_000).await.unwrap();
+    test.deposit(&depositor, &relay, 100_000_000).await.unwrap();
+    assert_eq!(test.dao_state(&classic).await.timeout_timestamp, start + 600 + TIME_LIMIT);
+    assert_eq!(test.dao_state(&relay).await.timeout_timestamp, start + 900 + TIME_LIMIT);
+
+    // The rule is governable: depositors vote it off
+    let voter = Keypair::new();
+    let (mut test, dao) = TurtleFixture::new()
+        .with_deposit_resets_timer(true)
+        .with_depositor(&voter.pubkey(), 100_000_000)
+        .with_proposal(&voter.pubkey(), VoteType::ChangeDepositResetsTimer, &["On", "Off"])
+        .with_vote(&voter.pubkey(), 0, 1)
+        .start()
+        .await;
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.vote_proposals[0].status, VoteStatus::Executed);
+    assert!(!state.deposit_resets_timer);
+
+    let deadline = state.timeout_timestamp;
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 60).await;
+    test.deposit(&voter, &dao, 1_000).await.unwrap();
+    assert_eq!(test.dao_state(&dao).await.timeout_timestamp, deadline);
+}
+
+#[tokio::test]
+async fn test_deposit_caps() {
+    let voter = Keypair::new();
+    let (mut test, dao) = TurtleFixture::new()
+        .with_depositor(&voter.pubkey(), 100_000_000)
+        .with_proposal(&voter.pubkey(), VoteType::ChangeDepositCaps, &["200000000:250000000", "none"])
+        .with_vote(&voter.pubkey(), 0, 0)
+        .start()
+        .await;
+
+    // Governance sets the caps; with no submissions the round restarts and the voter's deposit still counts
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.max_deposit_per_wallet, state.max_total_deposit), (200_000_000, 250_000_000));
+
+    let wallet_cap = Err(instruction_error(InstructionError::Custom(TurtleError::WalletDepositCapExceeded as u32)));
+    assert_eq!(test.deposit(&voter, &dao, 100_000_001).await, wallet_cap);
+    test.deposit(&voter, &dao, 100_000_000).await.unwrap();
+
+    let newcomer = test.funded_keypair(1_000_000_000).await;
+    let total_cap = Err(instruction_error(InstructionError::Custom(TurtleError::TotalDepositCapExceeded as u32)));
+    assert_eq!(test.deposit(&newcomer, &dao, 60_000_000).await, total_cap);
+    test.deposit(&newcomer, &dao, 50_000_000).await.unwrap();
+    assert_eq!(test.dao_state(&dao).await.total_deposit, 250_000_000);
+
+    assert_eq!(parse_deposit_caps("None"), Some((0, 0)));
+    assert_eq!(parse_deposit_caps("1000:none"), Some((1_000, 0)));
+    assert_eq!(parse_deposit_caps("1000"), None);
+    assert_eq!(parse_deposit_caps("lots:1"), None);
+}
+
+#[tokio::test]
+async fn test_submit_content() {
+    let mut test = TestDao::start().await;
+    let (_, dao) = test.initialize_dao(10, 20).await;
+    let author = test.funded_keypair(1_000_000_000).await;
+    let outsider = test.funded_keypair(1_000_000_000).await;
+    let late_depositor = test.funded_keypair(1_000_000_000).await;
+
+    // Only depositors may submit
+    assert_eq!(test.submit_content(&author, &dao, "hello").await, Err(instruction_error(InstructionError::InvalidAccountData)));
+    test.deposit(&author, &dao, 100_000_000).await.unwrap();
+
+    let later = test.now().await + 600;
+    test.warp_to_timestamp(later).await;
+    test.submit_content(&author, &dao, "hello").await.unwrap();
+
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.contents.len(), 1);
+    let content = &state.contents[0];
+    assert_eq!((content.author, content.text.as_str(), content.timestamp), (author.pubkey(), "hello", later));
+    assert_eq!(content.vote_count, 0);
+    // Submitting restarts the round and records the submission as the round's latest
+    assert_eq!(state.timeout_timestamp, later + TIME_LIMIT);
+    assert_eq!((state.last_submitter, state.last_content), (author.pubkey(), content_key(content)));
+
+    // An image URI over the limit is rejected while decoding the instruction, whoever built it
+    let data = (2u8, String::new(), "u".repeat(MAX_IMAGE_URI_LEN + 1)).try_to_vec().unwrap();
+    let accounts = vec![AccountMeta::new(author.pubkey(), true), AccountMeta::new(dao, false), AccountMeta::new_readonly(system_program::id(), false)];
+    let instruction = Instruction { program_id: test.program_id, accounts, data };
+    assert_eq!(test.send(&[instruction], &[&author]).await, Err(instruction_error(InstructionError::InvalidInstructionData)));
+
+    // A deposit landing after the submission neither restarts the round nor takes the last submission
+    test.deposit(&late_depositor, &dao, 100_000_000).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.timeout_timestamp, later + TIME_LIMIT);
+    assert_eq!(state.last_submitter, author.pubkey());
+
+    assert_eq!(test.submit_content(&outsider, &dao, "spam").await, Err(instruction_error(InstructionError::InvalidAccountData)));
+}
+
+#[tokio::test]
+async fn test_submission_fee_tiers() {
+    let (small, mid, whale) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_dao(TIME_LIMIT, 1_000, false, 20)
+        .with_depositor(&small.pubkey(), 100_000)
+        .with_depositor(&mid.pubkey(), 1_000_000)
+        .with_depositor(&whale.pubkey(), 5_000_000)
+        .with_proposal(&whale.pubkey(), VoteType::ChangeFeeTiers, &["1000000:50,5000000:100%", "none"])
+        .with_vote(&whale.pubkey(), 0, 0)
+        .start()
+        .await;
+
+    // Governance sets the tiers; with no submissions the round just restarts and deposits stay
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.fee_tiers, [FeeTier { min_deposit: 1_000_000, discount: 50 }, FeeTier { min_deposit: 5_000_000, discount: 100 }]);
+
+    // Below every tier pays base_fee, the first tier half, the top tier nothing (transaction fees are paid by the test payer)
+    for (author, fee) in [(&small, 1_000), (&mid, 500), (&whale, 0)] {
+        let before = test.lamports(&author.pubkey()).await;
+        test.submit_content(author, &dao, "hello").await.unwrap();
+        assert_eq!(before - test.lamports(&author.pubkey()).await, fee);
     }
+    assert_eq!(test.dao_state(&dao).await.fee_pool, 1_500);
 
-    #[test]
-    fn test_deposit_and_claim_reward() {
-        // Setup
-        let program_id = Pubkey::new_unique();
-        let mut runtime = SolanaRuntime::new(program_id);
-        
-        let depositor = Pubkey::new_unique();
-        let content_creator = Pubkey::new_unique();
-        let dao_account = Pubkey::new_unique();
-        let depositor_state_account = Pubkey::new_unique();
-        let content_account = Pubkey::new_unique();
-        let system_program = Pubkey::new_unique();
-        
-        // Fund accounts
-        runtime.create_account(&depositor, 2000000000, 0, &system_program);
-        runtime.create_account(&content_creator, 1000000000, 0, &system_program);
-        
-        // Create DAO account with initial state
-        let dao_space = mem::size_of::<DaoState>();
-        runtime.create_account(&dao_account, 0, dao_space, &program_id);
-        
-        // Initialize DAO state
-        let dao_state = DaoState {
-            admin: depositor, // depositor is admin for simplicity
-            time_limit: 1800,
-            base_fee: 10000000,
-            ai_moderation: true,
-            deposit_share: 20,
-            last_activity_timestamp: 0,
-            total_deposit: 0,
-            active_proposal_count: 0,
-            content_count: 0,
-            depositor_count: 0,
-        };
-        
-        let mut dao_data = runtime.accounts.get_mut(&dao_account).unwrap().data.as_mut();
-        dao_state.serialize(&mut dao_data).unwrap();
-        
-        // Create depositor state account
-        let depositor_space = mem::size_of::<Depositor>();
-        runtime.create_account(&depositor_state_account, 0, depositor_space, &program_id);
-        
-        // Create deposit instruction
-        let deposit_amount = 1000000000; // 1 SOL
-        let deposit_instruction = turtle_instruction::deposit(
-            &program_id,
-            &depositor,
-            &dao_account,
-            &depositor_state_account,
-            deposit_amount,
-        );
-        
-        // Set initial timestamp
-        runtime.clock.unix_timestamp = 1000;
-        
-        // Process deposit instruction
-        runtime.process_instruction(&deposit_instruction).unwrap();
-        
-        // Verify depositor state
-        let depositor_data = &runtime.accounts.get(&depositor_state_account).unwrap().data;
-        let depositor_state = Depositor::try_from_slice(depositor_data).unwrap();
-        
-        assert_eq!(depositor_state.pubkey, depositor);
-        assert_eq!(depositor_state.amount, deposit_amount);
-        assert_eq!(depositor_state.voting_power, deposit_amount);
-        
-        // Verify DAO state updated
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        
-        assert_eq!(dao_state.total_deposit, deposit_amount);
-        assert_eq!(dao_state.depositor_count, 1);
-        
-        // Now submit content from creator
-        let content_hash = "content_hash".to_string();
-        let content_uri = "ipfs://QmXoypizjW3WknFiJnKLwHCnL72vedxjQkDDP1mXWo6uco".to_string();
-        let content_space = mem::size_of::<Content>() + content_hash.len() + content_uri.len();
-        runtime.create_account(&content_account, 0, content_space, &program_id);
-        
-        // Create content submission instruction
-        let submit_instruction = turtle_instruction::submit_content(
-            &program_id,
-            &content_creator,
-            &dao_account,
-            &content_account,
-            content_hash.clone(),
-            content_uri.clone(),
-        );
-        
-        // Set timestamp for content submission
-        runtime.clock.unix_timestamp = 1100;
-        
-        // Process content submission
-        runtime.process_instruction(&submit_instruction).unwrap();
-        
-        // Verify content state
-        let content_data = &runtime.accounts.get(&content_account).unwrap().data;
-        let content = Content::try_from_slice(content_data).unwrap();
-        
-        assert_eq!(content.author, content_creator);
-        assert_eq!(content.timestamp, 1100);
-        
-        // Verify DAO state updated (timer reset)
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        
-        assert_eq!(dao_state.last_activity_timestamp, 1100);
-        
-        // Now claim reward after time limit
-        let claim_instruction = turtle_instruction::claim_reward(
-            &program_id,
-            &content_creator,
-            &dao_account,
-            &content_account,
-        );
-        
-        // Set timestamp after time limit
-        runtime.clock.unix_timestamp = 3000; // 1100 + 1800 (time limit) + buffer
-        
-        // Process claim instruction
-        runtime.process_instruction(&claim_instruction).unwrap();
-        
-        // Verify creator received reward
-        let creator_account = runtime.accounts.get(&content_creator).unwrap();
-        // Should receive 80% of deposit (as deposit_share is 20%)
-        let expected_reward = (deposit_amount * 80) / 100;
-        
-        // Initial balance + reward - base fee
-        let expected_balance = 1000000000 + expected_reward - dao_state.base_fee;
-        assert_eq!(creator_account.lamports, expected_balance);
-        
-        // Verify DAO state updated
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        
-        // DAO should retain 20% for quality rewards
-        let expected_dao_balance = (deposit_amount * 20) / 100 + dao_state.base_fee;
-        assert_eq!(dao_state.total_deposit, (deposit_amount * 20) / 100);
+    // Without a fee to pay the system program account may be left out, as before submission fees
+    let instruction = test.raw_instruction(
+        TurtleInstruction::SubmitContent { text: "again".try_into().unwrap(), image_uri: Default::default() },
+        vec![AccountMeta::new_readonly(whale.pubkey(), true), AccountMeta::new(dao, false)],
+    );
+    test.send(&[instruction], &[&whale]).await.unwrap();
+    let instruction = test.raw_instruction(
+        TurtleInstruction::SubmitContent { text: "again".try_into().unwrap(), image_uri: Default::default() },
+        vec![AccountMeta::new(small.pubkey(), true), AccountMeta::new(dao, false)],
+    );
+    assert_eq!(test.send(&[instruction], &[&small]).await, Err(instruction_error(InstructionError::NotEnoughAccountKeys)));
+
+    assert_eq!(parse_fee_tiers("None"), Some(Vec::new()));
+    assert_eq!(parse_fee_tiers("10:101"), None);
+    assert_eq!(parse_fee_tiers("1:1,2:2,3:3,4:4,5:5"), None);
+    assert_eq!(parse_fee_tiers("lots:50"), None);
+}
+
+#[tokio::test]
+async fn test_submission_streaks() {
+    let (author, plain) = (Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_dao(TIME_LIMIT, 1_000, false, 20)
+        .with_depositor(&author.pubkey(), 100_000)
+        .with_depositor(&plain.pubkey(), 100_000)
+        .with_proposal(&author.pubkey(), VoteType::ChangeStreakBonus, &["3:50", "none"])
+        .with_vote(&author.pubkey(), 0, 0)
+        .start()
+        .await;
+    let program_id = test.program_id;
+
+    // Governance grants half off from the third consecutive day
+    let voting_end = FIXTURE_NOW + MIN_VOTING_PERIOD + 1;
+    test.warp_to_timestamp(voting_end).await;
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.streak_days, state.streak_discount), (3, 50));
+
+    // A second submission the same day does not count; skipping a day starts over. The record is created on the
+    // first one even though lamports were sent to its address beforehand
+    test.prefund(&pda::find_streak_address(&program_id, &dao, &author.pubkey()).0).await;
+    let first_day = voting_end / SECONDS_PER_DAY + 1;
+    let mut fee_pool = state.fee_pool;
+    for (day, days, fee) in [(0, 1, 1_000), (0, 1, 1_000), (1, 2, 1_000), (2, 3, 500), (4, 1, 1_000)] {
+        test.warp_to_timestamp((first_day + day) * SECONDS_PER_DAY + 60).await;
+        test.submit_content_with_streak(&author, &dao, "gm").await.unwrap();
+        let streak = test.streak(&dao, &author.pubkey()).await.unwrap();
+        assert_eq!((streak.last_day, streak.days), (first_day + day, days));
+        let state = test.dao_state(&dao).await;
+        assert_eq!(state.fee_pool - fee_pool, fee, "day {}", day);
+        fee_pool = state.fee_pool;
     }
 
-    #[test]
-    fn test_governance() {
-        // Setup
-        let program_id = Pubkey::new_unique();
-        let mut runtime = SolanaRuntime::new(program_id);
-        
-        let admin = Pubkey::new_unique();
-        let depositor1 = Pubkey::new_unique();
-        let depositor2 = Pubkey::new_unique();
-        let dao_account = Pubkey::new_unique();
-        let admin_state_account = Pubkey::new_unique();
-        let depositor1_state_account = Pubkey::new_unique();
-        let depositor2_state_account = Pubkey::new_unique();
-        let proposal_account = Pubkey::new_unique();
-        let system_program = Pubkey::new_unique();
-        
-        // Fund accounts
-        runtime.create_account(&admin, 2000000000, 0, &system_program);
-        runtime.create_account(&depositor1, 2000000000, 0, &system_program);
-        runtime.create_account(&depositor2, 2000000000, 0, &system_program);
-        
-        // Create DAO account with initial state
-        let dao_space = mem::size_of::<DaoState>();
-        runtime.create_account(&dao_account, 0, dao_space, &program_id);
-        
-        // Initialize DAO state
-        let initial_time_limit = 1800;
-        let dao_state = DaoState {
-            admin: admin,
-            time_limit: initial_time_limit,
-            base_fee: 10000000,
-            ai_moderation: true,
-            deposit_share: 20,
-            last_activity_timestamp: 0,
-            total_deposit: 0,
-            active_proposal_count: 0,
-            content_count: 0,
-            depositor_count: 0,
+    // Submitting without the streak account neither counts nor creates one
+    test.submit_content(&plain, &dao, "gm").await.unwrap();
+    assert_eq!(test.streak(&dao, &plain.pubkey()).await, None);
+
+    // Another author's streak is not accepted
+    let mut instruction = turtle_instruction::submit_content_with_streak(&program_id, &plain.pubkey(), &dao, "gm".to_string(), String::new()).unwrap();
+    instruction.accounts[3].pubkey = pda::find_streak_address(&program_id, &dao, &author.pubkey()).0;
+    assert_eq!(test.send(&[instruction], &[&plain]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+}
+
+#[tokio::test]
+async fn test_content_retraction() {
+    let (first, second) = (Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_dao(TIME_LIMIT, 1_000, false, 20)
+        .with_retract_policy(600, 50)
+        .with_depositor(&first.pubkey(), 100_000)
+        .with_depositor(&second.pubkey(), 100_000)
+        .with_content(&first.pubkey(), "first")
+        .with_content(&second.pubkey(), "second")
+        .start()
+        .await;
+    let state = test.dao_state(&dao).await;
+    let (first_key, second_key) = (content_key(&state.contents[0]), content_key(&state.contents[1]));
+
+    // Nobody else can retract a submission
+    let not_found = Err(instruction_error(InstructionError::Custom(TurtleError::InvalidContent as u32)));
+    assert_eq!(test.retract_content(&first, &dao, &second_key).await, not_found);
+
+    // The latest submission is taken back for half its fee, and the one before it wins again
+    let before = test.lamports(&second.pubkey()).await;
+    test.retract_content(&second, &dao, &second_key).await.unwrap();
+    assert_eq!(test.lamports(&second.pubkey()).await - before, 500);
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.fee_pool, 1_500);
+    assert_eq!(state.contents.len(), 1);
+    assert_eq!((state.last_submitter, state.last_content), (first.pubkey(), first_key));
+    assert_eq!(state.timeout_timestamp, FIXTURE_NOW + TIME_LIMIT);
+    assert_eq!(ledger_total(&state), Some(test.lamports(&dao).await));
+    assert_eq!(test.retract_content(&second, &dao, &second_key).await, not_found);
+
+    // Past the window the submission stays
+    test.warp_to_timestamp(FIXTURE_NOW + 601).await;
+    let closed = Err(instruction_error(InstructionError::Custom(TurtleError::RetractWindowClosed as u32)));
+    assert_eq!(test.retract_content(&first, &dao, &first_key).await, closed);
+    assert_eq!(test.dao_state(&dao).await.contents.len(), 1);
+}
+
+#[tokio::test]
+async fn test_linked_content_claims() {
+    const TWEET: &str = "1750000000000000000";
+    let (first, second) = (Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_dao(TIME_LIMIT, 1_000, false, 20)
+        .with_retract_policy(600, 50)
+        .with_depositor(&first.pubkey(), 100_000)
+        .with_depositor(&second.pubkey(), 100_000)
+        .start()
+        .await;
+    let program_id = test.program_id;
+
+    // A linked submission is an ordinary submission plus the claim of its post
+    test.submit_linked_content(&first, &dao, "gm", ExternalPlatform::Twitter, TWEET).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.contents.len(), state.last_submitter, state.fee_pool), (1, first.pubkey(), 1_000));
+    let claim = test.external_claim(&dao, ExternalPlatform::Twitter, TWEET).await.unwrap();
+    assert_eq!((claim.dao, claim.author, claim.content, claim.claimed_at), (dao, first.pubkey(), state.last_content, FIXTURE_NOW));
+    assert_eq!(claim.external_ref.id.as_str(), TWEET);
+
+    // Nobody can submit the post again; the same id on another platform is another post, and lamports sent to
+    // its claim address beforehand don't stop it from being claimed
+    let claimed = Err(instruction_error(InstructionError::Custom(TurtleError::ExternalRefClaimed as u32)));
+    assert_eq!(test.submit_linked_content(&second, &dao, "gm too", ExternalPlatform::Twitter, TWEET).await, claimed);
+    assert_eq!(test.submit_linked_content(&first, &dao, "gm again", ExternalPlatform::Twitter, TWEET).await, claimed);
+    test.prefund(&pda::find_claim_address(&program_id, &dao, ExternalPlatform::Farcaster, TWEET).0).await;
+    test.submit_linked_content(&second, &dao, "gm too", ExternalPlatform::Farcaster, TWEET).await.unwrap();
+    assert_eq!(test.external_claim(&dao, ExternalPlatform::Farcaster, TWEET).await.unwrap().author, second.pubkey());
+    assert_eq!(test.dao_state(&dao).await.contents.len(), 2);
+
+    // The claim account must be the post's PDA
+    let mut instruction = turtle_instruction::submit_linked_content(
+        &program_id, &second.pubkey(), &dao, "gm".to_string(), String::new(), ExternalPlatform::Twitter, "1".to_string(),
+    ).unwrap();
+    instruction.accounts[3].pubkey = pda::find_claim_address(&program_id, &dao, ExternalPlatform::Twitter, "2").0;
+    assert_eq!(test.send(&[instruction], &[&second]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+
+    // Retracting with the claim closes it, returning its rent, and frees the post
+    let claim_rent = test.context.banks_client.get_rent().await.unwrap().minimum_balance(ExternalClaim::LEN);
+    let before = test.lamports(&first.pubkey()).await;
+    let instruction = turtle_instruction::retract_linked_content(
+        &program_id, &first.pubkey(), &dao, &claim.content, ExternalPlatform::Twitter, TWEET,
+    ).unwrap();
+    test.send(&[instruction], &[&first]).await.unwrap();
+    assert_eq!(test.lamports(&first.pubkey()).await - before, 500 + claim_rent);
+    assert_eq!(test.external_claim(&dao, ExternalPlatform::Twitter, TWEET).await, None);
+    test.submit_linked_content(&second, &dao, "mine now", ExternalPlatform::Twitter, TWEET).await.unwrap();
+    assert_eq!(test.external_claim(&dao, ExternalPlatform::Twitter, TWEET).await.unwrap().author, second.pubkey());
+    let state = test.dao_state(&dao).await;
+    assert_eq!(ledger_total(&state), Some(test.lamports(&dao).await));
+}
+
+#[tokio::test]
+async fn test_content_votes() {
+    let (author, voter, outsider) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_depositor(&author.pubkey(), 100_000)
+        .with_depositor(&voter.pubkey(), 100_000)
+        .with_wallet(&outsider.pubkey())
+        .with_content(&author.pubkey(), "vote for me")
+        .start()
+        .await;
+    let program_id = test.program_id;
+    let content = test.dao_state(&dao).await.last_content;
+
+    // Only depositors of the round vote, and not for their own content or content that isn't there
+    let not_authorized = Err(instruction_error(InstructionError::Custom(TurtleError::NotAuthorized as u32)));
+    assert_eq!(test.vote_content(&outsider, &dao, &content).await, not_authorized);
+    assert_eq!(test.vote_content(&author, &dao, &content).await, not_authorized);
+    let not_found = Err(instruction_error(InstructionError::Custom(TurtleError::InvalidContent as u32)));
+    assert_eq!(test.vote_content(&voter, &dao, &Pubkey::new_unique()).await, not_found);
+
+    // A vote counts once, even with lamports sent to its record's address beforehand
+    test.prefund(&pda::find_content_vote_address(&program_id, &dao, &content, &voter.pubkey()).0).await;
+    test.vote_content(&voter, &dao, &content).await.unwrap();
+    assert_eq!(test.dao_state(&dao).await.contents[0].vote_count, 1);
+    let content_vote = test.content_vote(&dao, &content, &voter.pubkey()).await.unwrap();
+    assert_eq!(content_vote, ContentVote { dao, content, voter: voter.pubkey(), voted_at: FIXTURE_NOW });
+    let voted = Err(instruction_error(InstructionError::Custom(TurtleError::ContentAlreadyVoted as u32)));
+    assert_eq!(test.vote_content(&voter, &dao, &content).await, voted);
+    assert_eq!(test.dao_state(&dao).await.contents[0].vote_count, 1);
+
+    // The record must be the voter's PDA for that content
+    let mut instruction = turtle_instruction::vote_content(&program_id, &outsider.pubkey(), &dao, &content).unwrap();
+    instruction.accounts[0].pubkey = voter.pubkey();
+    instruction.accounts[2].pubkey = Pubkey::new_unique();
+    assert_eq!(test.send(&[instruction], &[&voter]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+    let state = test.dao_state(&dao).await;
+    assert_eq!(ledger_total(&state), Some(test.lamports(&dao).await));
+}
+
+#[tokio::test]
+async fn test_create_vote() {
+    let mut test = TestDao::start().await;
+    let (_, dao) = test.initialize_dao(10, 20).await;
+    let proposer = test.funded_keypair(1_000_000_000).await;
+
+    assert_eq!(
+        test.create_vote(&proposer, &dao, VoteType::ChangeBaseFee, &["5%", "15%"]).await,
+        Err(instruction_error(InstructionError::InvalidAccountData)),
+    );
+    test.deposit(&proposer, &dao, 100_000_000).await.unwrap();
+
+    let now = test.now().await;
+    test.create_vote(&proposer, &dao, VoteType::ChangeBaseFee, &["5%", "15%"]).await.unwrap();
+    test.create_vote(&proposer, &dao, VoteType::ChangeAiModeration, &["On", "Off"]).await.unwrap();
+
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.next_proposal_id, 2);
+    let proposal = &state.vote_proposals[0];
+    assert_eq!((proposal.proposal_id, proposal.proposer), (0, proposer.pubkey()));
+    assert_eq!(proposal.vote_type, VoteType::ChangeBaseFee);
+    assert_eq!(proposal.options, ["5%", "15%"]);
+    assert_eq!((proposal.start_time, proposal.end_time), (now, now + MIN_VOTING_PERIOD));
+    assert_eq!(proposal.status, VoteStatus::Active);
+    assert!(proposal.votes.is_empty());
+    assert_eq!(state.vote_proposals[1].proposal_id, 1);
+
+    let instruction = test.raw_instruction(
+        TurtleInstruction::CreateVote {
+            title: "too short".to_string(),
+            description: String::new(),
+            vote_type: VoteType::ChangeTimeLimit,
+            options: vec!["60".to_string(), "120".to_string()],
+            voting_period: MIN_VOTING_PERIOD - 1,
+        },
+        vec![AccountMeta::new_readonly(proposer.pubkey(), true), AccountMeta::new(dao, false)],
+    );
+    assert_eq!(test.send(&[instruction], &[&proposer]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+}
+
+#[tokio::test]
+async fn test_cast_vote() {
+    let (voter, outsider, late_voter) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_depositor(&voter.pubkey(), 250_000_000)
+        .with_depositor(&late_voter.pubkey(), 100_000_000)
+        .with_wallet(&outsider.pubkey())
+        .with_proposal(&voter.pubkey(), VoteType::ChangeBaseFee, &["5%", "15%"])
+        .start()
+        .await;
+
+    // No deposit, no voting power
+    assert_eq!(test.cast_vote(&outsider, &dao, 0, 0).await, Err(instruction_error(InstructionError::InvalidAccountData)));
+    // Unknown proposal and an option the proposal does not have
+    assert_eq!(test.cast_vote(&voter, &dao, 7, 0).await, Err(instruction_error(InstructionError::InvalidArgument)));
+    assert_eq!(test.cast_vote(&voter, &dao, 0, 2).await, Err(instruction_error(InstructionError::InvalidArgument)));
+
+    test.cast_vote(&voter, &dao, 0, 1).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    let vote = &state.vote_proposals[0].votes[0];
+    assert_eq!((vote.voter, vote.option_index, vote.voting_power), (voter.pubkey(), 1, 250_000_000));
+
+    // One vote per depositor
+    assert_eq!(test.cast_vote(&voter, &dao, 0, 0).await, Err(instruction_error(InstructionError::InvalidAccountData)));
+
+    // Voting is closed once the period is over
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    assert_eq!(test.cast_vote(&late_voter, &dao, 0, 0).await, Err(instruction_error(InstructionError::InvalidAccountData)));
+}
+
+#[tokio::test]
+async fn test_process_timeout() {
+    let (author, late_depositor) = (Keypair::new(), Keypair::new());
+    let (mut test, dao) = TurtleFixture::new()
+        .with_depositor(&author.pubkey(), 400_000_000)
+        .at(FIXTURE_NOW + 60)
+        .with_content(&author.pubkey(), "first")
+        .with_depositor(&late_depositor.pubkey(), 100_000_000)
+        .start()
+        .await;
+    let rent = test.rent_exempt_minimum().await;
+
+    let deadline = FIXTURE_NOW + 60 + TIME_LIMIT;
+    test.warp_to_timestamp(deadline - 1).await;
+    assert_eq!(test.process_timeout(&dao).await, Err(instruction_error(InstructionError::InvalidAccountData)));
+
+    test.warp_to_timestamp(deadline).await;
+    // The round result has to go to the PDA of the DAO's current round, and the reward to the last submitter
+    let payer = test.context.payer.pubkey();
+    let wrong_round = turtle_instruction::process_timeout(&test.program_id, &payer, &dao, 5, Some(&author.pubkey())).unwrap();
+    assert_eq!(test.send(&[wrong_round], &[]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+    let wrong_winner = turtle_instruction::process_timeout(&test.program_id, &payer, &dao, 0, Some(&late_depositor.pubkey())).unwrap();
+    assert_eq!(test.send(&[wrong_winner], &[]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+    let no_winner = turtle_instruction::process_timeout(&test.program_id, &payer, &dao, 0, None).unwrap();
+    assert_eq!(test.send(&[no_winner], &[]).await, Err(instruction_error(InstructionError::NotEnoughAccountKeys)));
+    let last_content = test.dao_state(&dao).await.last_content;
+    // Lamports sent to the round's PDA ahead of time don't block recording it
+    let (round_pda, _) = pda::find_round_address(&test.program_id, &dao, 0);
+    test.prefund(&round_pda).await;
+    assert_eq!(test.dao_state(&dao).await.fee_pool, 10);
+    test.process_timeout(&dao).await.unwrap();
+
+    // The round is kept in its RoundResult account. The submission's base_fee (10 lamports) joined the 500M pot;
+    // nobody voted, so no creator can claim the quality share and the winner is paid the whole pot
+    let round_result = test.round_result(&dao, 0).await.unwrap();
+    assert_eq!(round_result, RoundResult {
+        dao,
+        round: 0,
+        winner: author.pubkey(),
+        reward: 500_000_010,
+        pot: 500_000_010,
+        content: last_content,
+        submitted_at: FIXTURE_NOW + 60,
+        closed_at: deadline,
+        quality_pool: 0,
+        candidates: Vec::new(),
+    });
+    let round_rent = test.context.banks_client.get_rent().await.unwrap().minimum_balance(RoundResult::LEN);
+    assert_eq!(test.lamports(&round_pda).await, round_rent);
+
+    // The last submitter wins even though a deposit came in after the submission and is paid the reward
+    // from the bounty pool; no lamports are left behind in either pool. The round closes with its contents and
+    // deposits cleared.
+    assert_eq!(test.lamports(&author.pubkey()).await, WALLET_LAMPORTS + 500_000_010);
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.bounty_pool, state.fee_pool), (0, 0));
+    assert_eq!((state.round_state, state.timeout_timestamp), (RoundState::Dormant, 0));
+    assert!(state.contents.is_empty());
+    assert_eq!(state.total_deposit, 0);
+    assert!(state.depositors.iter().all(|info| info.amount == 0));
+    assert_eq!((state.last_submitter, state.last_content), (Pubkey::default(), Pubkey::default()));
+    assert_eq!(test.lamports(&dao).await, rent);
+
+    // With the pot paid out the next round waits for a deposit to open it
+    let dormant = Err(instruction_error(InstructionError::Custom(TurtleError::RoundDormant as u32)));
+    assert_eq!(test.submit_content(&author, &dao, "too early").await, dormant);
+    test.deposit(&late_depositor, &dao, 100_000_000).await.unwrap();
+    assert_eq!(test.dao_state(&dao).await.timeout_timestamp, deadline + TIME_LIMIT);
+
+    // That round has its own deadline and ends without a winner when nothing is submitted; the pot it keeps
+    // opens the one after right away
+    assert_eq!(test.process_timeout(&dao).await, Err(instruction_error(InstructionError::InvalidAccountData)));
+    test.warp_to_timestamp(deadline + TIME_LIMIT).await;
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.round_state, state.timeout_timestamp), (RoundState::Active, deadline + 2 * TIME_LIMIT));
+    assert_eq!(state.last_submitter, Pubkey::default());
+    // Only rounds with a winner are numbered and recorded
+    assert_eq!(state.round, 1);
+    assert_eq!(test.round_result(&dao, 1).await, None);
+}
+
+#[tokio::test]
+async fn test_quality_rewards() {
+    let (alice, bob, carol) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let fixture = TurtleFixture::new()
+        .with_depositor(&alice.pubkey(), 200_000_000)
+        .with_depositor(&bob.pubkey(), 100_000_000)
+        .with_depositor(&carol.pubkey(), 200_000_000)
+        .with_content(&alice.pubkey(), "alice")
+        .with_content(&bob.pubkey(), "bob");
+    let admin = fixture.initializer().insecure_clone();
+    let (mut test, dao) = fixture.start().await;
+    let program_id = test.program_id;
+    let state = test.dao_state(&dao).await;
+    let (alice_content, bob_content) = (content_key(&state.contents[0]), content_key(&state.contents[1]));
+    test.vote_content(&carol, &dao, &alice_content).await.unwrap();
+    test.vote_content(&bob, &dao, &alice_content).await.unwrap();
+    test.vote_content(&carol, &dao, &bob_content).await.unwrap();
+
+    // Bob's later submission wins; 10% of the 500M pot is fee and a fifth of that the quality share
+    test.warp_to_timestamp(FIXTURE_NOW + TIME_LIMIT).await;
+    test.process_timeout(&dao).await.unwrap();
+    let round_result = test.round_result(&dao, 0).await.unwrap();
+    assert_eq!(round_result.quality_pool, 10_000_000);
+    assert_eq!(test.dao_state(&dao).await.bounty_pool, 10_000_000);
+    // Only the voted submissions are candidates, Alice's first with more votes
+    assert_eq!(round_result.candidates, [
+        QualityCandidate { author: alice.pubkey(), content: alice_content, votes: 2 },
+        QualityCandidate { author: bob.pubkey(), content: bob_content, votes: 1 },
+    ]);
+
+    let distribute = |admin: &Pubkey, weights: Vec<u8>| {
+        turtle_instruction::distribute_quality_rewards(&program_id, admin, &dao, 0, vec![alice.pubkey(), bob.pubkey()], weights).unwrap()
+    };
+    let not_admin = Err(instruction_error(InstructionError::Custom(TurtleError::NotAdmin as u32)));
+    assert_eq!(test.send(&[distribute(&carol.pubkey(), vec![67, 33])], &[&carol]).await, not_admin);
+    // The program checks the weights as well as the builder
+    let invalid = test.raw_instruction(
+        TurtleInstruction::DistributeQualityRewards { creator_pubkeys: vec![alice.pubkey()], distribution_weights: vec![99] },
+        distribute(&admin.pubkey(), vec![67, 33]).accounts[..4].to_vec(),
+    );
+    let invalid_distribution = Err(instruction_error(InstructionError::Custom(TurtleError::InvalidDistribution as u32)));
+    assert_eq!(test.send(&[invalid], &[&admin]).await, invalid_distribution);
+    // Carol deposited but has no voted submission
+    let ineligible = turtle_instruction::distribute_quality_rewards(&program_id, &admin.pubkey(), &dao, 0, vec![alice.pubkey(), carol.pubkey()], vec![50, 50]).unwrap();
+    let ineligible_creator = Err(instruction_error(InstructionError::Custom(TurtleError::IneligibleCreator as u32)));
+    assert_eq!(test.send(&[ineligible], &[&admin]).await, ineligible_creator);
+    // Creator accounts come in creator_pubkeys order
+    let mut swapped = distribute(&admin.pubkey(), vec![67, 33]);
+    swapped.accounts.swap(3, 4);
+    assert_eq!(test.send(&[swapped], &[&admin]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+
+    // The quality share is paid out of the bounty pool, once
+    let (alice_before, bob_before) = (test.lamports(&alice.pubkey()).await, test.lamports(&bob.pubkey()).await);
+    test.send(&[distribute(&admin.pubkey(), vec![67, 33])], &[&admin]).await.unwrap();
+    assert_eq!(test.lamports(&alice.pubkey()).await - alice_before, 6_700_000);
+    assert_eq!(test.lamports(&bob.pubkey()).await - bob_before, 3_300_000);
+    assert_eq!(test.round_result(&dao, 0).await.unwrap().quality_pool, 0);
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.bounty_pool, 0);
+    assert_eq!(ledger_total(&state), Some(test.lamports(&dao).await));
+    let already_paid = Err(instruction_error(InstructionError::Custom(TurtleError::RoundAlreadyPaid as u32)));
+    assert_eq!(test.send(&[distribute(&admin.pubkey(), vec![50, 50])], &[&admin]).await, already_paid);
+}
+
+#[tokio::test]
+async fn test_round_bootstrap() {
+    let (small, big) = (Keypair::new(), Keypair::new());
+    // 100M is short of the 300M minimum, so the round has not opened yet. Deposits restart the timer here,
+    // which must not give a dormant DAO a deadline
+    let (mut test, dao) = TurtleFixture::new()
+        .with_round_minimum(300_000_000)
+        .with_deposit_resets_timer(true)
+        .with_depositor(&small.pubkey(), 100_000_000)
+        .with_proposal(&small.pubkey(), VoteType::ChangeRoundMinimum, &["none", "1000000000"])
+        .with_vote(&small.pubkey(), 0, 1)
+        .with_wallet(&big.pubkey())
+        .start()
+        .await;
+    let dormant = Err(instruction_error(InstructionError::Custom(TurtleError::RoundDormant as u32)));
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.round_state, state.timeout_timestamp), (RoundState::Dormant, 0));
+    assert_eq!(test.submit_content(&small, &dao, "hello").await, dormant);
+
+    // Without a deadline a timeout may come at any time; it only settles votes that have finished
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.round_state, state.vote_proposals[0].status.clone()), (RoundState::Dormant, VoteStatus::Active));
+
+    // The deposit that reaches the minimum, whoever makes it, starts the countdown
+    let opened_at = FIXTURE_NOW + 600;
+    test.warp_to_timestamp(opened_at).await;
+    test.deposit(&big, &dao, 200_000_000).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.round_state, state.timeout_timestamp), (RoundState::Active, opened_at + TIME_LIMIT));
+    test.submit_content(&small, &dao, "hello").await.unwrap();
+
+    // The round closes with a winner and the vote raises the minimum to 1 SOL; the emptied pot leaves the DAO
+    // dormant, and a deposit below the new minimum does not open it
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    test.process_timeout(&dao).await.unwrap();
+    assert_eq!(test.round_result(&dao, 0).await.unwrap().winner, small.pubkey());
+    test.deposit(&big, &dao, 200_000_000).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.round_state, state.min_round_pot, state.round), (RoundState::Dormant, 1_000_000_000, 1));
+    assert_eq!(state.timeout_timestamp, 0);
+    assert_eq!(test.submit_content(&small, &dao, "again").await, dormant);
+}
+
+#[tokio::test]
+async fn test_process_timeout_executes_finished_votes() {
+    let (whale, minnow) = (Keypair::new(), Keypair::new());
+    // Votes are weighted by deposit: the whale outvotes the minnow
+    let (mut test, dao) = TurtleFixture::new()
+        .with_depositor(&whale.pubkey(), 500_000_000)
+        .with_depositor(&minnow.pubkey(), 100_000_000)
+        .with_proposal(&whale.pubkey(), VoteType::ChangeTimeLimit, &["60 seconds", "7200 seconds"])
+        .with_proposal(&whale.pubkey(), VoteType::ChangeBaseFee, &["5%", "15%"])
+        .with_proposal(&whale.pubkey(), VoteType::ChangeAiModeration, &["On", "Off"])
+        .with_proposal(&whale.pubkey(), VoteType::ContentQualityRating, &["Good", "Bad"])
+        .with_proposal(&whale.pubkey(), VoteType::ChangeBaseFee, &["1%", "2%"])
+        .with_vote(&whale.pubkey(), 0, 1)
+        .with_vote(&minnow.pubkey(), 0, 0)
+        .with_vote(&minnow.pubkey(), 1, 1)
+        .with_vote(&whale.pubkey(), 2, 0)
+        .with_vote(&minnow.pubkey(), 3, 0)
+        .start()
+        .await;
+
+    // Before the voting period ends a timeout leaves the proposals open
+    test.warp_to_timestamp(FIXTURE_NOW + TIME_LIMIT).await;
+    test.process_timeout(&dao).await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert!(state.vote_proposals.iter().all(|proposal| proposal.status == VoteStatus::Active));
+
+    let after_voting = FIXTURE_NOW + MIN_VOTING_PERIOD + 1;
+    test.warp_to_timestamp(after_voting).await;
+    test.process_timeout(&dao).await.unwrap();
+
+    let state = test.dao_state(&dao).await;
+    assert_eq!(state.time_limit, 7200);
+    assert_eq!(state.base_fee, 15);
+    assert!(state.ai_moderation);
+    let statuses: Vec<VoteStatus> = state.vote_proposals.iter().map(|proposal| proposal.status.clone()).collect();
+    // The last proposal got no votes, so it is closed without being executed
+    assert_eq!(statuses, [VoteStatus::Executed, VoteStatus::Executed, VoteStatus::Executed, VoteStatus::Executed, VoteStatus::Completed]);
+    // The new round uses the time limit the vote just set
+    assert_eq!(state.timeout_timestamp, after_voting + 7200);
+
+    assert_eq!(test.cast_vote(&whale, &dao, 4, 0).await, Err(instruction_error(InstructionError::InvalidAccountData)));
+}
+
+#[tokio::test]
+async fn test_replace_admin() {
+    let (whale, minnow) = (Keypair::new(), Keypair::new());
+    let (new_admin, usurper) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (new_admin_option, usurper_option) = (new_admin.to_string(), usurper.to_string());
+    // 500 of 600 deposited clears the two-thirds bar, 100 of 600 doesn't
+    let (mut test, dao) = TurtleFixture::new()
+        .with_depositor(&whale.pubkey(), 500_000_000)
+        .with_depositor(&minnow.pubkey(), 100_000_000)
+        .with_proposal(&whale.pubkey(), VoteType::ReplaceAdmin, &[&new_admin_option, &usurper_option])
+        .with_proposal(&minnow.pubkey(), VoteType::ReplaceAdmin, &[&usurper_option])
+        .with_vote(&whale.pubkey(), 0, 0)
+        .with_vote(&minnow.pubkey(), 0, 1)
+        .with_vote(&minnow.pubkey(), 1, 0)
+        .start()
+        .await;
+    let old_admin = test.dao_state(&dao).await.initializer;
+
+    test.warp_to_timestamp(FIXTURE_NOW + MIN_VOTING_PERIOD + 1).await;
+    test.process_timeout(&dao).await.unwrap();
+
+    // Nobody needed the old admin's signature, and the DAO keeps its address
+    let state = test.dao_state(&dao).await;
+    assert_ne!(old_admin, new_admin);
+    assert_eq!(state.initializer, new_admin);
+    let statuses: Vec<VoteStatus> = state.vote_proposals.iter().map(|proposal| proposal.status.clone()).collect();
+    assert_eq!(statuses, [VoteStatus::Executed, VoteStatus::Completed]);
+    let depositor = test.funded_keypair(1_000_000_000).await;
+    test.deposit(&depositor, &dao, 1_000).await.unwrap();
+
+    assert_eq!(parse_admin(&new_admin_option), Some(new_admin));
+    assert_eq!(parse_admin("not a key"), None);
+    assert_eq!(parse_admin(&Pubkey::default().to_string()), None);
+}
+
+#[tokio::test]
+async fn test_treasury_assets() {
+    let mut test = TestDao::start().await;
+    let program_id = test.program_id;
+    let (admin, dao) = test.initialize_dao(10, 20).await;
+    let author = test.funded_keypair(1_000_000_000).await;
+    let usdc = test.create_mint().await;
+
+    // Only the admin registers mints
+    let instruction = turtle_instruction::initialize_treasury_asset(&program_id, &author.pubkey(), &dao, &usdc).unwrap();
+    assert_eq!(test.send(&[instruction], &[&author]).await, Err(instruction_error(InstructionError::Custom(TurtleError::NotAdmin as u32))));
+    let instruction = turtle_instruction::initialize_treasury_asset(&program_id, &admin.pubkey(), &dao, &usdc).unwrap();
+    test.send(&[instruction], &[&admin]).await.unwrap();
+    let (vault, _) = pda::find_asset_vault_address(&program_id, &dao, &usdc);
+    assert_eq!(test.treasury_asset(&dao, &usdc).await, TreasuryAsset { dao, mint: usdc, vault, balance: 0, next_round: 0 });
+
+    // Anyone can fund the treasury; the tokens land in the vault
+    let funder = test.funded_keypair(100_000_000).await;
+    let funder_tokens = test.create_token_account(&usdc, &funder.pubkey()).await;
+    test.mint_tokens(&usdc, &funder_tokens, 1_000).await;
+    let instruction = turtle_instruction::deposit_asset(&program_id, &funder.pubkey(), &dao, &usdc, &funder_tokens, 600).unwrap();
+    test.send(&[instruction], &[&funder]).await.unwrap();
+    assert_eq!(test.token_balance(&vault).await, 600);
+
+    // The admin withdraws, never more than the recorded balance
+    let admin_tokens = test.create_token_account(&usdc, &admin.pubkey()).await;
+    let withdraw = |amount| turtle_instruction::withdraw_asset(&program_id, &admin.pubkey(), &dao, &usdc, &admin_tokens, amount).unwrap();
+    test.send(&[withdraw(100)], &[&admin]).await.unwrap();
+    let insufficient = Err(instruction_error(InstructionError::Custom(TurtleError::InsufficientTreasuryBalance as u32)));
+    assert_eq!(test.send(&[withdraw(501)], &[&admin]).await, insufficient);
+    assert_eq!(test.token_balance(&admin_tokens).await, 100);
+
+    // A round with a winner can be topped up in the asset, once and only to the winner
+    test.deposit(&author, &dao, 100_000_000).await.unwrap();
+    test.submit_content(&author, &dao, "hello").await.unwrap();
+    let deadline = test.dao_state(&dao).await.timeout_timestamp;
+    test.warp_to_timestamp(deadline).await;
+    test.process_timeout(&dao).await.unwrap();
+
+    let winner_tokens = test.create_token_account(&usdc, &author.pubkey()).await;
+    let reward = |token_account: &Pubkey| {
+        turtle_instruction::distribute_asset_reward(&program_id, &admin.pubkey(), &dao, &usdc, 0, token_account, 200).unwrap()
+    };
+    assert_eq!(test.send(&[reward(&admin_tokens)], &[&admin]).await, Err(instruction_error(InstructionError::InvalidArgument)));
+    test.send(&[reward(&winner_tokens)], &[&admin]).await.unwrap();
+    assert_eq!(test.token_balance(&winner_tokens).await, 200);
+    let already_paid = Err(instruction_error(InstructionError::Custom(TurtleError::RoundAlreadyPaid as u32)));
+    assert_eq!(test.send(&[reward(&winner_tokens)], &[&admin]).await, already_paid);
+    let asset = test.treasury_asset(&dao, &usdc).await;
+    assert_eq!((asset.balance, asset.next_round), (300, 1));
+    assert_eq!(test.token_balance(&vault).await, 300);
+}
+
+// After every instruction the DAO balance is exactly rent_reserve + bounty_pool + fee_pool
+#[tokio::test]
+async fn test_ledger_matches_balance() {
+    let mut test = TestDao::start().await;
+    let (_, dao) = test.initialize_dao(10, 20).await;
+    let rent = test.rent_exempt_minimum().await;
+    let depositor = test.funded_keypair(1_000_000_000).await;
+
+    let mut steps = Vec::new();
+    steps.push(test.deposit(&depositor, &dao, 300_000_000).await);
+    steps.push(test.submit_content(&depositor, &dao, "hello").await);
+    steps.push(test.create_vote(&depositor, &dao, VoteType::ChangeBaseFee, &["5%", "15%"]).await);
+    steps.push(test.cast_vote(&depositor, &dao, 0, 1).await);
+    let now = test.now().await;
+    test.warp_to_timestamp(now + TIME_LIMIT).await;
+    steps.push(test.process_timeout(&dao).await);
+    assert!(steps.iter().all(Result::is_ok), "{:?}", steps);
+
+    let state = test.dao_state(&dao).await;
+    // The submission paid base_fee (10 lamports) into fee_pool, which left it with the pot; nobody voted on the
+    // submission, so the winner took the whole pot
+    assert_eq!((state.rent_reserve, state.bounty_pool, state.fee_pool), (rent, 0, 0));
+    assert_eq!(ledger_total(&state), Some(test.lamports(&dao).await));
+}
+
+#[tokio::test]
+async fn test_ledger_reconciliation() {
+    let author = Keypair::new();
+    let (mut test, dao) = TurtleFixture::new().with_depositor(&author.pubkey(), 400_000_000).start().await;
+    let rent = test.rent_exempt_minimum().await;
+
+    // Lamports sent to the DAO outside the program are folded into fee_pool by the next instruction,
+    // next to the 10 lamport submission fee
+    let donor = test.funded_keypair(1_000_000_000).await;
+    test.send(&[system_instruction::transfer(&donor.pubkey(), &dao, 5_000)], &[&donor]).await.unwrap();
+    test.submit_content(&author, &dao, "first").await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.rent_reserve, state.bounty_pool, state.fee_pool), (rent, 400_000_000, 5_010));
+
+    // An account written before the ledger fields reads them as zero: rent is reserved, the rest is bounty
+    let mut legacy = test.context.banks_client.get_account(dao).await.unwrap().unwrap();
+    let mut state = test.dao_state(&dao).await;
+    (state.rent_reserve, state.bounty_pool, state.fee_pool) = (0, 0, 0);
+    legacy.data = vec![0; DAO_SPACE];
+    state.serialize(&mut legacy.data.as_mut_slice()).unwrap();
+    test.context.set_account(&dao, &legacy.into());
+    test.submit_content(&author, &dao, "second").await.unwrap();
+    let state = test.dao_state(&dao).await;
+    assert_eq!((state.rent_reserve, state.bounty_pool, state.fee_pool), (rent, 400_005_010, 10));
+
+    // A recorded pool the balance cannot cover is refused
+    let mut short = test.context.banks_client.get_account(dao).await.unwrap().unwrap();
+    let mut state = test.dao_state(&dao).await;
+    state.fee_pool += 1;
+    state.serialize(&mut short.data.as_mut_slice()).unwrap();
+    test.context.set_account(&dao, &short.into());
+    assert_eq!(
+        test.submit_content(&author, &dao, "third").await,
+        Err(instruction_error(InstructionError::Custom(TurtleError::LedgerMismatch as u32))),
+    );
+}
+
+// The fixture writes the same account InitializeDao and Deposit would have
+#[tokio::test]
+async fn test_fixture_matches_instructions() {
+    let mut test = TestDao::start().await;
+    let now = test.now().await;
+    let (initializer, dao) = test.initialize_dao(10, 20).await;
+    let depositor = test.funded_keypair(1_000_000_000).await;
+    test.deposit(&depositor, &dao, 300_000_000).await.unwrap();
+    let sent = test.context.banks_client.get_account(dao).await.unwrap().unwrap();
+
+    let fixture = TurtleFixture::new().at(now).with_dao(TIME_LIMIT, 10, false, 20).with_depositor(&depositor.pubkey(), 300_000_000);
+    let built = fixture.account();
+    assert_eq!(built.lamports, sent.lamports);
+    assert_eq!(built.owner, fixture.program_id());
+    // Only the program id and initializer (and so the DAO address) differ
+    let mut state = test.dao_state(&dao).await;
+    state.initializer = fixture.initializer().pubkey();
+    assert_eq!(state.try_to_vec().unwrap(), fixture.build().try_to_vec().unwrap());
+    assert_ne!(initializer.pubkey(), fixture.initializer().pubkey());
+}
+
+// Checks every instruction after InitializeDao shares: signer, DAO account owner and initialization
+#[tokio::test]
+async fn test_dao_account_checks() {
+    let mut test = TestDao::start().await;
+    let (_, dao) = test.initialize_dao(10, 20).await;
+    let user = test.funded_keypair(1_000_000_000).await;
+    let not_owned = user.pubkey();
+    let uninitialized = test.uninitialized;
+    let program_id = test.program_id;
+
+    let instructions = [
+        TurtleInstruction::Deposit { amount: 1 },
+        TurtleInstruction::SubmitContent { text: "hi".try_into().unwrap(), image_uri: Default::default() },
+        TurtleInstruction::CreateVote {
+            title: "t".to_string(),
+            description: String::new(),
+            vote_type: VoteType::ChangeBaseFee,
+            options: vec!["5%".to_string(), "15%".to_string()],
+            voting_period: MIN_VOTING_PERIOD,
+        },
+        TurtleInstruction::CastVote { proposal_id: 0, option_index: 0 },
+        TurtleInstruction::ProcessTimeout {},
+    ];
+    for data in instructions {
+        let name = format!("{:?}", data);
+        let bytes = data.try_to_vec().unwrap();
+        let accounts = |signs: bool, dao_account: Pubkey| {
+            let mut accounts = vec![AccountMeta::new(user.pubkey(), signs), AccountMeta::new(dao_account, false)];
+            if matches!(data, TurtleInstruction::Deposit { .. }) {
+                accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+            }
+            accounts
         };
-        
-        let mut dao_data = runtime.accounts.get_mut(&dao_account).unwrap().data.as_mut();
-        dao_state.serialize(&mut dao_data).unwrap();
-        
-        // Create depositor state accounts
-        let depositor_space = mem::size_of::<Depositor>();
-        runtime.create_account(&admin_state_account, 0, depositor_space, &program_id);
-        runtime.create_account(&depositor1_state_account, 0, depositor_space, &program_id);
-        runtime.create_account(&depositor2_state_account, 0, depositor_space, &program_id);
-        
-        // Set initial timestamp
-        runtime.clock.unix_timestamp = 1000;
-        
-        // Admin makes deposit
-        let admin_deposit = 500000000; // 0.5 SOL
-        let admin_deposit_instruction = turtle_instruction::deposit(
-            &program_id,
-            &admin,
-            &dao_account,
-            &admin_state_account,
-            admin_deposit,
-        );
-        runtime.process_instruction(&admin_deposit_instruction).unwrap();
-        
-        // Depositor1 makes deposit
-        let depositor1_deposit = 300000000; // 0.3 SOL
-        let depositor1_deposit_instruction = turtle_instruction::deposit(
-            &program_id,
-            &depositor1,
-            &dao_account,
-            &depositor1_state_account,
-            depositor1_deposit,
-        );
-        runtime.process_instruction(&depositor1_deposit_instruction).unwrap();
-        
-        // Depositor2 makes deposit
-        let depositor2_deposit = 200000000; // 0.2 SOL
-        let depositor2_deposit_instruction = turtle_instruction::deposit(
-            &program_id,
-            &depositor2,
-            &dao_account,
-            &depositor2_state_account,
-            depositor2_deposit,
-        );
-        runtime.process_instruction(&depositor2_deposit_instruction).unwrap();
-        
-        // Verify DAO state
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        assert_eq!(dao_state.total_deposit, admin_deposit + depositor1_deposit + depositor2_deposit);
-        assert_eq!(dao_state.depositor_count, 3);
-        
-        // Create proposal account
-        let proposal_space = mem::size_of::<Proposal>();
-        runtime.create_account(&proposal_account, 0, proposal_space, &program_id);
-        
-        // Admin creates proposal to change time limit
-        let new_time_limit = 3600; // 1 hour
-        let min_voting_period = 7 * 24 * 60 * 60; // 1 week in seconds
-        let create_vote_instruction = turtle_instruction::create_vote(
-            &program_id,
-            &admin,
-            &dao_account,
-            &admin_state_account,
-            &proposal_account,
-            ProposalType::TimeLimit,
-            new_time_limit,
-            min_voting_period,
-        );
-        
-        runtime.process_instruction(&create_vote_instruction).unwrap();
-        
-        // Verify proposal created
-        let proposal_data = &runtime.accounts.get(&proposal_account).unwrap().data;
-        let proposal = Proposal::try_from_slice(proposal_data).unwrap();
-        assert_eq!(proposal.proposal_type, ProposalType::TimeLimit);
-        assert_eq!(proposal.new_value, new_time_limit);
-        assert_eq!(proposal.yes_votes, 0);
-        assert_eq!(proposal.no_votes, 0);
-        
-        // Depositors vote on proposal
-        let admin_vote_instruction = turtle_instruction::vote(
-            &program_id,
-            &admin,
-            &dao_account,
-            &admin_state_account,
-            &proposal_account,
-            0, // proposal id
-            true, // approve
-        );
-        runtime.process_instruction(&admin_vote_instruction).unwrap();
-        
-        let depositor1_vote_instruction = turtle_instruction::vote(
-            &program_id,
-            &depositor1,
-            &dao_account,
-            &depositor1_state_account,
-            &proposal_account,
-            0, // proposal id
-            true, // approve
-        );
-        runtime.process_instruction(&depositor1_vote_instruction).unwrap();
-        
-        let depositor2_vote_instruction = turtle_instruction::vote(
-            &program_id,
-            &depositor2,
-            &dao_account,
-            &depositor2_state_account,
-            &proposal_account,
-            0, // proposal id
-            false, // reject
-        );
-        runtime.process_instruction(&depositor2_vote_instruction).unwrap();
-        
-        // Verify votes recorded
-        let proposal_data = &runtime.accounts.get(&proposal_account).unwrap().data;
-        let proposal = Proposal::try_from_slice(proposal_data).unwrap();
-        assert_eq!(proposal.yes_votes, admin_deposit + depositor1_deposit);
-        assert_eq!(proposal.no_votes, depositor2_deposit);
-        
-        // Move time forward to end of voting period
-        runtime.clock.unix_timestamp = 1000 + min_voting_period + 1;
-        
-        // One more vote to trigger execution
-        let final_vote_instruction = turtle_instruction::vote(
-            &program_id,
-            &admin,
-            &dao_account,
-            &admin_state_account,
-            &proposal_account,
-            0, // proposal id
-            true, // approve
-        );
-        runtime.process_instruction(&final_vote_instruction).unwrap();
-        
-        // Verify proposal executed
-        let proposal_data = &runtime.accounts.get(&proposal_account).unwrap().data;
-        let proposal = Proposal::try_from_slice(proposal_data).unwrap();
-        assert_eq!(proposal.is_executed, true);
-        
-        // Verify DAO state updated
-        let dao_data = &runtime.accounts.get(&dao_account).unwrap().data;
-        let dao_state = DaoState::try_from_slice(dao_data).unwrap();
-        assert_eq!(dao_state.time_limit, new_time_limit);
+        let instruction = |accounts| Instruction { program_id, accounts, data: bytes.clone() };
+
+        let cases = [
+            (instruction(accounts(false, dao)), InstructionError::MissingRequiredSignature),
+            (instruction(accounts(true, not_owned)), InstructionError::IncorrectProgramId),
+            (instruction(accounts(true, uninitialized)), InstructionError::UninitializedAccount),
+        ];
+        for (instruction, expected) in cases {
+            let signers: Vec<&Keypair> = match instruction.accounts[0].is_signer {
+                true => vec![&user],
+                false => vec![],
+            };
+            assert_eq!(test.send(&[instruction], &signers).await, Err(instruction_error(expected)), "{}", name);
+        }
     }
-}
\ No newline at end of file
+}